@@ -143,6 +143,17 @@ impl InlineAssistant {
         workspace: View<Workspace>,
         event: &workspace::Event,
         cx: &mut WindowContext,
+    ) {
+        for event in event.unbatched() {
+            self.handle_unbatched_workspace_event(&workspace, event, cx);
+        }
+    }
+
+    fn handle_unbatched_workspace_event(
+        &mut self,
+        workspace: &View<Workspace>,
+        event: &workspace::Event,
+        cx: &mut WindowContext,
     ) {
         match event {
             workspace::Event::UserSavedItem { item, .. } => {
@@ -159,7 +170,7 @@ impl InlineAssistant {
                 }
             }
             workspace::Event::ItemAdded { item } => {
-                self.register_workspace_item(&workspace, item.as_ref(), cx);
+                self.register_workspace_item(workspace, item.as_ref(), cx);
             }
             _ => (),
         }
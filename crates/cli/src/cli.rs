@@ -17,6 +17,9 @@ pub enum CliRequest {
         open_new_workspace: Option<bool>,
         env: Option<HashMap<String, String>>,
     },
+    /// Keeps Zed from saving or reloading the file at `path` while another tool rewrites it,
+    /// for `duration_ms` at most.
+    LockForExternalUpdate { path: String, duration_ms: u64 },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
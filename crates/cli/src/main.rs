@@ -59,6 +59,18 @@ struct Args {
     /// Run zed in dev-server mode
     #[arg(long)]
     dev_server_token: Option<String>,
+    /// Keep Zed from saving or reloading the given file while another tool rewrites it, until
+    /// the lock duration elapses.
+    #[arg(long, value_name = "PATH")]
+    lock_for_update: Option<PathBuf>,
+    /// How many seconds `--lock-for-update` keeps the file locked.
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 10,
+        requires = "lock_for_update"
+    )]
+    lock_duration: u64,
     /// Uninstall Zed from user system
     #[cfg(all(
         any(target_os = "linux", target_os = "macos"),
@@ -196,13 +208,23 @@ fn main() -> Result<()> {
             let (_, handshake) = server.accept().context("Handshake after Zed spawn")?;
             let (tx, rx) = (handshake.requests, handshake.responses);
 
-            tx.send(CliRequest::Open {
-                paths,
-                urls,
-                wait: args.wait,
-                open_new_workspace,
-                env,
-            })?;
+            let request = match args.lock_for_update {
+                Some(path) => CliRequest::LockForExternalUpdate {
+                    path: fs::canonicalize(&path)
+                        .unwrap_or(path)
+                        .to_string_lossy()
+                        .to_string(),
+                    duration_ms: args.lock_duration * 1000,
+                },
+                None => CliRequest::Open {
+                    paths,
+                    urls,
+                    wait: args.wait,
+                    open_new_workspace,
+                    env,
+                },
+            };
+            tx.send(request)?;
 
             while let Ok(response) = rx.recv() {
                 match response {
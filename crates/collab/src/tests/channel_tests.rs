@@ -13,6 +13,7 @@ use rpc::{
     RECEIVE_TIMEOUT,
 };
 use std::sync::Arc;
+use workspace::Workspace;
 
 #[gpui::test]
 async fn test_core_channels(
@@ -1458,3 +1459,88 @@ fn assert_channels_list_shape(
     });
     pretty_assertions::assert_eq!(actual, expected_channels);
 }
+
+#[gpui::test]
+async fn test_join_channel_progress(executor: BackgroundExecutor, cx_a: &mut TestAppContext) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let channel_id = server
+        .make_channel("the-channel", None, (&client_a, cx_a), &mut [])
+        .await;
+    let project = client_a.build_empty_local_project(cx_a);
+    let (workspace, cx) = client_a.build_workspace(&project, cx_a);
+    let window = cx.update(|cx| cx.window_handle().downcast::<Workspace>().unwrap());
+
+    let join = cx.update(|cx| {
+        workspace::join_channel(channel_id, client_a.app_state.clone(), Some(window), cx)
+    });
+    // The client is connected, so the progress goes straight to joining the channel.
+    let progress = loop {
+        if let Some(progress) =
+            workspace.update(cx, |workspace, _| workspace.join_channel_progress())
+        {
+            break progress;
+        }
+        assert!(executor.tick());
+    };
+    progress.update(cx, |progress, _| {
+        assert_eq!(progress.message(), "Joining channel…");
+    });
+
+    join.await.unwrap();
+    executor.run_until_parked();
+    workspace.update(cx, |workspace, _| {
+        assert!(workspace.join_channel_progress().is_none());
+    });
+    cx.read(ActiveCall::global).read_with(cx, |call, cx| {
+        assert_eq!(call.channel_id(cx), Some(channel_id));
+    });
+}
+
+#[gpui::test]
+async fn test_cancel_joining_channel(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+    let project = client_a.build_empty_local_project(cx_a);
+    let (workspace, cx) = client_a.build_workspace(&project, cx_a);
+    let window = cx.update(|cx| cx.window_handle().downcast::<Workspace>().unwrap());
+
+    let join = cx.update(|cx| {
+        workspace::join_channel(channel_id, client_a.app_state.clone(), Some(window), cx)
+    });
+    let progress = loop {
+        if let Some(progress) =
+            workspace.update(cx, |workspace, _| workspace.join_channel_progress())
+        {
+            break progress;
+        }
+        assert!(executor.tick());
+    };
+
+    // Cancelling while the channel is being joined stops the join altogether.
+    progress.update(cx, |progress, cx| progress.simulate_click(cx));
+    join.await.unwrap();
+    executor.run_until_parked();
+    workspace.update(cx, |workspace, _| {
+        assert!(workspace.join_channel_progress().is_none());
+    });
+    cx.read(ActiveCall::global).read_with(cx, |call, _| {
+        assert!(call.room().is_none());
+    });
+    client_b.channel_store().read_with(cx_b, |channels, _| {
+        assert!(channels.channel_participants(channel_id).is_empty());
+    });
+}
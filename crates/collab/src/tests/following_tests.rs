@@ -3,7 +3,7 @@ use crate::tests::TestServer;
 use call::{ActiveCall, ParticipantLocation};
 use client::ChannelId;
 use collab_ui::{
-    channel_view::ChannelView,
+    channel_view::ChannelView, follow_participant::FollowParticipantModal,
     notifications::project_shared_notification::ProjectSharedNotification,
 };
 use editor::{Editor, ExcerptRange, MultiBuffer};
@@ -13,9 +13,11 @@ use gpui::{
 };
 use language::Capability;
 use project::WorktreeSettings;
+use rand::Rng;
 use rpc::proto::PeerId;
 use serde_json::json;
 use settings::SettingsStore;
+use std::{cell::Cell, rc::Rc, time::Duration};
 use workspace::{item::ItemHandle as _, SplitDirection, Workspace};
 
 use super::TestClient;
@@ -1316,6 +1318,109 @@ async fn test_auto_unfollowing(cx_a: &mut TestAppContext, cx_b: &mut TestAppCont
     );
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_removing_following_pane_while_leader_opens_view(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let executor = cx_a.executor();
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    cx_a.update(editor::init);
+    cx_b.update(editor::init);
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "1.txt": "one", "2.txt": "two" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    active_call_a
+        .update(cx_a, |call, cx| call.set_location(Some(&project_a), cx))
+        .await
+        .unwrap();
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    active_call_b
+        .update(cx_b, |call, cx| call.set_location(Some(&project_b), cx))
+        .await
+        .unwrap();
+
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    let (workspace_b, cx_b) = client_b.build_workspace(&project_b, cx_b);
+
+    workspace_a
+        .update(cx_a, |workspace, cx| {
+            workspace.open_path((worktree_id, "1.txt"), None, true, cx)
+        })
+        .await
+        .unwrap();
+
+    // Client B follows client A in one of two panes.
+    let pane_b = workspace_b.update(cx_b, |workspace, _| workspace.active_pane().clone());
+    workspace_b
+        .update(cx_b, |workspace, cx| {
+            workspace.open_path((worktree_id, "1.txt"), None, true, cx)
+        })
+        .await
+        .unwrap();
+    workspace_b.update(cx_b, |workspace, cx| {
+        workspace.split_and_clone(pane_b.clone(), SplitDirection::Right, cx);
+        workspace.activate_pane_in_direction(SplitDirection::Left, cx);
+    });
+    let leader_id = project_b.update(cx_b, |project, _| {
+        project.collaborators().values().next().unwrap().peer_id
+    });
+    workspace_b.update(cx_b, |workspace, cx| workspace.follow(leader_id, cx));
+    executor.run_until_parked();
+    assert_eq!(
+        workspace_b.update(cx_b, |workspace, _| workspace.leader_for_pane(&pane_b)),
+        Some(leader_id)
+    );
+
+    // Client A opens a file, and client B removes the following pane at some point
+    // while constructing the corresponding view.
+    workspace_a
+        .update(cx_a, |workspace, cx| {
+            workspace.open_path((worktree_id, "2.txt"), None, true, cx)
+        })
+        .await
+        .unwrap();
+    let steps = executor.rng().gen_range(0..20);
+    for _ in 0..steps {
+        if !executor.tick() {
+            break;
+        }
+    }
+    pane_b.update(cx_b, |_, cx| {
+        cx.emit(workspace::pane::Event::Remove {
+            focus_on_pane: None,
+        })
+    });
+    executor.run_until_parked();
+
+    assert_eq!(
+        pane_summaries(&workspace_b, cx_b),
+        &[PaneSummary {
+            active: true,
+            leader: None,
+            items: vec![(true, "1.txt".into())]
+        }]
+    );
+    assert!(pane_b.update(cx_b, |pane, cx| pane
+        .items()
+        .all(|item| item.tab_description(0, cx).as_deref() != Some("2.txt"))));
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_peers_simultaneously_following_each_other(
     cx_a: &mut TestAppContext,
@@ -2166,3 +2271,643 @@ async fn test_following_while_deactivated(cx_a: &mut TestAppContext, cx_b: &mut
         assert_eq!(editor.tab_description(0, cx).unwrap(), "2.js");
     });
 }
+
+#[gpui::test]
+async fn test_follow_next_collaborator_cycling(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+    cx_d: &mut TestAppContext,
+) {
+    let executor = cx_a.executor();
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let client_d = server.create_client(cx_d, "user_d").await;
+    server
+        .create_room(&mut [
+            (&client_a, cx_a),
+            (&client_b, cx_b),
+            (&client_c, cx_c),
+            (&client_d, cx_d),
+        ])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    let active_call_c = cx_c.read(ActiveCall::global);
+    let active_call_d = cx_d.read(ActiveCall::global);
+
+    cx_a.update(editor::init);
+    cx_b.update(editor::init);
+    cx_c.update(editor::init);
+    cx_d.update(editor::init);
+
+    client_a.fs().insert_tree("/a", json!({})).await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+
+    // B and C are in the shared project, while D joined it but is looking at another shared
+    // project of their own.
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    let _workspace_b = client_b.build_workspace(&project_b, cx_b);
+    active_call_b
+        .update(cx_b, |call, cx| call.set_location(Some(&project_b), cx))
+        .await
+        .unwrap();
+    let project_c = client_c.join_remote_project(project_id, cx_c).await;
+    let _workspace_c = client_c.build_workspace(&project_c, cx_c);
+    active_call_c
+        .update(cx_c, |call, cx| call.set_location(Some(&project_c), cx))
+        .await
+        .unwrap();
+    let project_d = client_d.join_remote_project(project_id, cx_d).await;
+    let _workspace_d = client_d.build_workspace(&project_d, cx_d);
+    client_d.fs().insert_tree("/d", json!({})).await;
+    let (own_project_d, _) = client_d.build_local_project("/d", cx_d).await;
+    active_call_d
+        .update(cx_d, |call, cx| {
+            call.share_project(own_project_d.clone(), cx)
+        })
+        .await
+        .unwrap();
+    active_call_d
+        .update(cx_d, |call, cx| call.set_location(Some(&own_project_d), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let peer_id_b = client_b.peer_id().unwrap();
+    let peer_id_c = client_c.peer_id().unwrap();
+    let follow_next = |cx: &mut VisualTestContext| {
+        workspace_a.update(cx, |workspace, cx| {
+            workspace.follow_next_collaborator(&workspace::FollowNextCollaborator, cx);
+            workspace.leader_for_pane(workspace.active_pane())
+        })
+    };
+
+    // Collaborators are visited in the order they joined the call, skipping D.
+    assert_eq!(follow_next(cx_a), Some(peer_id_b));
+    executor.run_until_parked();
+    assert_eq!(follow_next(cx_a), Some(peer_id_c));
+    executor.run_until_parked();
+
+    // Cycling past the last collaborator wraps around to the first one.
+    assert_eq!(follow_next(cx_a), Some(peer_id_b));
+    executor.run_until_parked();
+
+    // When B is the only followable collaborator, cycling onto them again unfollows.
+    active_call_c
+        .update(cx_c, |call, cx| call.set_location(None, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(follow_next(cx_a), None);
+    executor.run_until_parked();
+
+    // Cycling again resumes with the collaborator that was last followed in this pane.
+    assert_eq!(follow_next(cx_a), Some(peer_id_b));
+}
+
+#[gpui::test]
+async fn test_follow_participant_picker(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let executor = cx_a.executor();
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    let active_call_c = cx_c.read(ActiveCall::global);
+
+    cx_a.update(editor::init);
+    cx_b.update(editor::init);
+
+    client_a.fs().insert_tree("/a", json!({})).await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+
+    // B is in the shared project, while C is outside of Zed.
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    let _workspace_b = client_b.build_workspace(&project_b, cx_b);
+    active_call_b
+        .update(cx_b, |call, cx| call.set_location(Some(&project_b), cx))
+        .await
+        .unwrap();
+    active_call_c
+        .update(cx_c, |call, cx| call.set_location(None, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let peer_id_b = client_b.peer_id().unwrap();
+    let peer_id_c = client_c.peer_id().unwrap();
+    cx_a.dispatch_action(workspace::FollowParticipant);
+    let picker = workspace_a.update(cx_a, |workspace, cx| {
+        workspace
+            .active_modal::<FollowParticipantModal>(cx)
+            .unwrap()
+            .read(cx)
+            .picker()
+            .clone()
+    });
+    executor.run_until_parked();
+    let matching_participants = |cx: &mut VisualTestContext| {
+        picker.update(cx, |picker, _| {
+            picker
+                .delegate
+                .matching_participants()
+                .map(|participant| {
+                    (
+                        participant.peer_id,
+                        participant.location.clone(),
+                        participant.can_follow,
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    // Everybody but the local user is listed, and C can't be followed.
+    assert_eq!(
+        matching_participants(cx_a),
+        vec![
+            (peer_id_b, "In this project".into(), true),
+            (
+                peer_id_c,
+                "Viewing a window outside of Zed, can't be followed".into(),
+                false
+            ),
+        ]
+    );
+
+    picker.update(cx_a, |picker, cx| picker.set_query("user_c", cx));
+    executor.run_until_parked();
+    assert_eq!(
+        matching_participants(cx_a)
+            .into_iter()
+            .map(|(peer_id, _, _)| peer_id)
+            .collect::<Vec<_>>(),
+        vec![peer_id_c]
+    );
+
+    // Confirming a participant that can't be followed does nothing.
+    picker.update(cx_a, |picker, cx| picker.delegate.confirm(false, cx));
+    executor.run_until_parked();
+    workspace_a.update(cx_a, |workspace, cx| {
+        assert!(!workspace.is_being_followed(peer_id_c));
+        assert!(workspace
+            .active_modal::<FollowParticipantModal>(cx)
+            .is_some());
+    });
+
+    picker.update(cx_a, |picker, cx| picker.set_query("user_b", cx));
+    executor.run_until_parked();
+    picker.update(cx_a, |picker, cx| picker.delegate.confirm(false, cx));
+    executor.run_until_parked();
+    workspace_a.update(cx_a, |workspace, cx| {
+        assert_eq!(
+            workspace.leader_for_pane(workspace.active_pane()),
+            Some(peer_id_b)
+        );
+        assert!(workspace
+            .active_modal::<FollowParticipantModal>(cx)
+            .is_none());
+    });
+}
+
+#[gpui::test]
+async fn test_follow_in_new_window(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let executor = cx_a.executor();
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    cx_a.update(editor::init);
+    cx_b.update(editor::init);
+
+    client_a.fs().insert_tree("/a", json!({})).await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    let _workspace_b = client_b.build_workspace(&project_b, cx_b);
+    active_call_b
+        .update(cx_b, |call, cx| call.set_location(Some(&project_b), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // B is followed in a new window on the same project, while the original window keeps
+    // its own context.
+    let peer_id_b = client_b.peer_id().unwrap();
+    let window_count = cx_a.update(|cx| cx.windows().len());
+    let follow_window = workspace_a
+        .update(cx_a, |workspace, cx| {
+            workspace.follow_in_new_window(peer_id_b, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(cx_a.update(|cx| cx.windows().len()), window_count + 1);
+    follow_window
+        .update(cx_a, |workspace, _| {
+            assert_eq!(workspace.project(), &project_a);
+            assert_eq!(workspace.follow_window_leader(), Some(peer_id_b));
+            assert_eq!(
+                workspace.leader_for_pane(workspace.active_pane()),
+                Some(peer_id_b)
+            );
+        })
+        .unwrap();
+    workspace_a.update(cx_a, |workspace, _| {
+        assert!(!workspace.is_being_followed(peer_id_b));
+        assert_eq!(workspace.leader_for_pane(workspace.active_pane()), None);
+    });
+
+    // A follow window in which B is unfollowed is a regular window from then on.
+    let unfollowed_window = workspace_a
+        .update(cx_a, |workspace, cx| {
+            workspace.follow_in_new_window(peer_id_b, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    unfollowed_window
+        .update(cx_a, |workspace, cx| {
+            assert_eq!(workspace.follow_window_leader(), Some(peer_id_b));
+            let pane = workspace.active_pane().clone();
+            assert_eq!(workspace.unfollow_in_pane(&pane, cx), Some(peer_id_b));
+            assert_eq!(workspace.follow_window_leader(), None);
+        })
+        .unwrap();
+
+    // Once B leaves the call, closing the window is offered.
+    active_call_b
+        .update(cx_b, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    follow_window
+        .update(cx_a, |workspace, _| {
+            assert_eq!(workspace.follow_window_leader(), None);
+            assert_eq!(workspace.notification_ids().len(), 1);
+        })
+        .unwrap();
+    unfollowed_window
+        .update(cx_a, |workspace, _| {
+            assert!(workspace.notification_ids().is_empty());
+        })
+        .unwrap();
+    workspace_a.update(cx_a, |workspace, _| {
+        assert!(workspace.notification_ids().is_empty());
+    });
+}
+
+#[gpui::test]
+async fn test_unfollow_all(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let executor = cx_a.executor();
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    let active_call_c = cx_c.read(ActiveCall::global);
+
+    cx_a.update(editor::init);
+    cx_b.update(editor::init);
+    cx_c.update(editor::init);
+
+    client_a.fs().insert_tree("/a", json!({})).await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    let _workspace_b = client_b.build_workspace(&project_b, cx_b);
+    active_call_b
+        .update(cx_b, |call, cx| call.set_location(Some(&project_b), cx))
+        .await
+        .unwrap();
+    let project_c = client_c.join_remote_project(project_id, cx_c).await;
+    let _workspace_c = client_c.build_workspace(&project_c, cx_c);
+    active_call_c
+        .update(cx_c, |call, cx| call.set_location(Some(&project_c), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // A follows B and C in two different panes.
+    let peer_id_b = client_b.peer_id().unwrap();
+    let peer_id_c = client_c.peer_id().unwrap();
+    let pane_b = workspace_a.update(cx_a, |workspace, cx| {
+        workspace.follow(peer_id_b, cx);
+        workspace.active_pane().clone()
+    });
+    executor.run_until_parked();
+    let pane_c = workspace_a.update(cx_a, |workspace, cx| {
+        workspace.split_pane(pane_b.clone(), SplitDirection::Right, cx)
+    });
+    executor.run_until_parked();
+    workspace_a.update(cx_a, |workspace, cx| workspace.follow(peer_id_c, cx));
+    executor.run_until_parked();
+    workspace_a.update(cx_a, |workspace, _| {
+        assert_eq!(workspace.leader_for_pane(&pane_b), Some(peer_id_b));
+        assert_eq!(workspace.leader_for_pane(&pane_c), Some(peer_id_c));
+    });
+
+    cx_a.dispatch_action(workspace::UnfollowAll);
+    executor.run_until_parked();
+    workspace_a.update(cx_a, |workspace, _| {
+        assert_eq!(workspace.leader_for_pane(&pane_b), None);
+        assert_eq!(workspace.leader_for_pane(&pane_c), None);
+        assert!(!workspace.is_being_followed(peer_id_b));
+        assert!(!workspace.is_being_followed(peer_id_c));
+    });
+}
+
+#[gpui::test]
+async fn test_follow_all_collaborators(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+    cx_d: &mut TestAppContext,
+) {
+    let executor = cx_a.executor();
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let client_d = server.create_client(cx_d, "user_d").await;
+    server
+        .create_room(&mut [
+            (&client_a, cx_a),
+            (&client_b, cx_b),
+            (&client_c, cx_c),
+            (&client_d, cx_d),
+        ])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    let active_call_c = cx_c.read(ActiveCall::global);
+    let active_call_d = cx_d.read(ActiveCall::global);
+
+    cx_a.update(editor::init);
+    cx_b.update(editor::init);
+    cx_c.update(editor::init);
+    cx_d.update(editor::init);
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "c.txt": "c" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    let _workspace_b = client_b.build_workspace(&project_b, cx_b);
+    active_call_b
+        .update(cx_b, |call, cx| call.set_location(Some(&project_b), cx))
+        .await
+        .unwrap();
+    let project_c = client_c.join_remote_project(project_id, cx_c).await;
+    let (workspace_c, cx_c) = client_c.build_workspace(&project_c, cx_c);
+    active_call_c
+        .update(cx_c, |call, cx| call.set_location(Some(&project_c), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // A follows B in the active pane and C in a pane split off from it.
+    let peer_id_b = client_b.peer_id().unwrap();
+    let peer_id_c = client_c.peer_id().unwrap();
+    let peer_id_d = client_d.peer_id().unwrap();
+    let original_pane = workspace_a.update(cx_a, |workspace, _| workspace.active_pane().clone());
+    cx_a.dispatch_action(workspace::FollowAllCollaborators);
+    executor.run_until_parked();
+    let leaders = |workspace: &Workspace| {
+        workspace
+            .panes()
+            .iter()
+            .map(|pane| workspace.leader_for_pane(pane))
+            .collect::<Vec<_>>()
+    };
+    workspace_a.update(cx_a, |workspace, _| {
+        assert_eq!(leaders(workspace), [Some(peer_id_b), Some(peer_id_c)]);
+        assert_eq!(workspace.panes()[0], original_pane);
+        assert!(workspace.notification_ids().is_empty());
+    });
+
+    // D joining the project is announced rather than followed right away.
+    let project_d = client_d.join_remote_project(project_id, cx_d).await;
+    let _workspace_d = client_d.build_workspace(&project_d, cx_d);
+    active_call_d
+        .update(cx_d, |call, cx| call.set_location(Some(&project_d), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    workspace_a.update(cx_a, |workspace, cx| {
+        assert_eq!(workspace.notification_ids().len(), 1);
+        assert_eq!(workspace.panes().len(), 2);
+        workspace.clear_all_notifications(cx);
+        workspace.follow_all_collaborators(cx);
+    });
+    executor.run_until_parked();
+    workspace_a.update(cx_a, |workspace, _| {
+        assert_eq!(
+            leaders(workspace),
+            [Some(peer_id_b), Some(peer_id_c), Some(peer_id_d)]
+        );
+    });
+
+    // The pane of a collaborator who leaves is removed, along with their views.
+    workspace_c
+        .update(cx_c, |workspace, cx| {
+            workspace.open_path((worktree_id, "c.txt"), None, true, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    workspace_a.update(cx_a, |workspace, cx| {
+        assert_eq!(workspace.panes()[1].read(cx).items_len(), 1);
+        assert_eq!(workspace.items(cx).count(), 1);
+    });
+    active_call_c
+        .update(cx_c, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    workspace_a.update(cx_a, |workspace, cx| {
+        assert_eq!(leaders(workspace), [Some(peer_id_b), Some(peer_id_d)]);
+        assert_eq!(workspace.items(cx).count(), 0);
+    });
+
+    // Unfollowing everyone removes the panes split off for following.
+    cx_a.dispatch_action(workspace::UnfollowAll);
+    executor.run_until_parked();
+    workspace_a.update(cx_a, |workspace, _| {
+        assert_eq!(workspace.panes(), [original_pane.clone()]);
+        assert_eq!(leaders(workspace), [None]);
+        assert!(!workspace.is_being_followed(peer_id_b));
+        assert!(!workspace.is_being_followed(peer_id_d));
+    });
+}
+
+#[gpui::test]
+async fn test_followers_of_local_user(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let executor = cx_a.executor();
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    let active_call_c = cx_c.read(ActiveCall::global);
+
+    cx_a.update(editor::init);
+    cx_b.update(editor::init);
+    cx_c.update(editor::init);
+
+    client_a.fs().insert_tree("/a", json!({})).await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    let (workspace_b, cx_b) = client_b.build_workspace(&project_b, cx_b);
+    active_call_b
+        .update(cx_b, |call, cx| call.set_location(Some(&project_b), cx))
+        .await
+        .unwrap();
+    let project_c = client_c.join_remote_project(project_id, cx_c).await;
+    let (workspace_c, cx_c) = client_c.build_workspace(&project_c, cx_c);
+    active_call_c
+        .update(cx_c, |call, cx| call.set_location(Some(&project_c), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let followers_changed = Rc::new(Cell::new(0));
+    let _subscription = cx_a.update({
+        let followers_changed = followers_changed.clone();
+        |cx| {
+            cx.subscribe(&workspace_a, move |_, event, _| {
+                if matches!(event, workspace::Event::FollowersChanged) {
+                    followers_changed.set(followers_changed.get() + 1);
+                }
+            })
+        }
+    });
+    let peer_id_a = client_a.peer_id().unwrap();
+    let peer_id_b = client_b.peer_id().unwrap();
+    let peer_id_c = client_c.peer_id().unwrap();
+
+    // B starting to follow A is announced to A.
+    workspace_b.update(cx_b, |workspace, cx| workspace.follow(peer_id_a, cx));
+    executor.run_until_parked();
+    assert_eq!(followers_changed.take(), 1);
+    workspace_a.update(cx_a, |workspace, cx| {
+        assert_eq!(workspace.followers(cx), [peer_id_b]);
+        assert_eq!(workspace.notification_ids().len(), 1);
+        workspace.clear_all_notifications(cx);
+    });
+
+    // C following A right after isn't announced.
+    workspace_c.update(cx_c, |workspace, cx| workspace.follow(peer_id_a, cx));
+    executor.run_until_parked();
+    assert_eq!(followers_changed.take(), 1);
+    workspace_a.update(cx_a, |workspace, cx| {
+        let mut followers = workspace.followers(cx);
+        followers.sort();
+        let mut expected = vec![peer_id_b, peer_id_c];
+        expected.sort();
+        assert_eq!(followers, expected);
+        assert!(workspace.notification_ids().is_empty());
+    });
+
+    // B unfollowing A updates the list.
+    workspace_b.update(cx_b, |workspace, cx| {
+        workspace.unfollow(peer_id_a, cx).unwrap();
+    });
+    executor.run_until_parked();
+    assert_eq!(followers_changed.take(), 1);
+    workspace_a.update(cx_a, |workspace, cx| {
+        assert_eq!(workspace.followers(cx), [peer_id_c]);
+    });
+
+    // A while later, B following A again is announced.
+    executor.advance_clock(Duration::from_secs(61));
+    workspace_b.update(cx_b, |workspace, cx| workspace.follow(peer_id_a, cx));
+    executor.run_until_parked();
+    assert_eq!(followers_changed.take(), 1);
+    workspace_a.update(cx_a, |workspace, _| {
+        assert_eq!(workspace.notification_ids().len(), 1);
+    });
+
+    // The followers leaving the call are forgotten.
+    active_call_c
+        .update(cx_c, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert!(followers_changed.take() > 0);
+    workspace_a.update(cx_a, |workspace, cx| {
+        assert_eq!(workspace.followers(cx), [peer_id_b]);
+    });
+
+    // So are all of them once the call ends.
+    active_call_a
+        .update(cx_a, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert!(followers_changed.take() > 0);
+    workspace_a.update(cx_a, |workspace, cx| {
+        assert!(workspace.followers(cx).is_empty());
+    });
+}
@@ -6699,3 +6699,51 @@ async fn test_remote_git_branches(
 
     assert_eq!(host_branch.as_ref(), "totally-new-branch");
 }
+
+#[gpui::test]
+async fn test_path_prompts_without_handlers_in_remote_project(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "a.txt": "a-contents" }))
+        .await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    executor.run_until_parked();
+
+    // Without in-app prompts, remote paths can't be picked, so the prompts resolve to nothing.
+    let (workspace_b, cx_b) = client_b.build_workspace(&project_b, cx_b);
+    let (new_path, open_paths) = workspace_b.update(cx_b, |workspace, cx| {
+        assert!(!workspace.has_path_prompt_handlers());
+        workspace.set_use_system_path_prompts(Some(true));
+        (
+            workspace.prompt_for_new_path(cx),
+            workspace.prompt_for_open_path(
+                gpui::PathPromptOptions {
+                    files: true,
+                    ..Default::default()
+                },
+                project::DirectoryLister::Project(project_b.clone()),
+                cx,
+            ),
+        )
+    });
+    assert_eq!(new_path.await.unwrap(), None);
+    assert_eq!(open_paths.await.unwrap(), None);
+    assert!(!cx_b.did_prompt_for_new_path());
+}
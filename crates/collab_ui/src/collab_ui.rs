@@ -1,6 +1,7 @@
 pub mod channel_view;
 pub mod chat_panel;
 pub mod collab_panel;
+pub mod follow_participant;
 pub mod notification_panel;
 pub mod notifications;
 mod panel_settings;
@@ -30,6 +31,7 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
     channel_view::init(cx);
     chat_panel::init(cx);
     collab_panel::init(cx);
+    follow_participant::init(cx);
     notification_panel::init(cx);
     notifications::init(app_state, cx);
     title_bar::init(cx);
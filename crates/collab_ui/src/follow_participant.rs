@@ -0,0 +1,311 @@
+use std::sync::Arc;
+
+use call::{ActiveCall, ParticipantLocation};
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, SharedUri, Task, View,
+    ViewContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use rpc::proto::PeerId;
+use ui::{prelude::*, Avatar, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{FollowInNewWindow, FollowParticipant, ModalView, Workspace};
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(FollowParticipantModal::register)
+        .detach();
+}
+
+/// Lists the participants of the current call and follows the confirmed one, either in the
+/// workspace or in a new window.
+pub struct FollowParticipantModal {
+    picker: View<Picker<FollowParticipantDelegate>>,
+}
+
+impl ModalView for FollowParticipantModal {}
+
+impl FollowParticipantModal {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &FollowParticipant, cx| {
+            Self::toggle(workspace, false, cx)
+        });
+        workspace.register_action(|workspace, _: &FollowInNewWindow, cx| {
+            Self::toggle(workspace, true, cx)
+        });
+    }
+
+    fn toggle(workspace: &mut Workspace, in_new_window: bool, cx: &mut ViewContext<Workspace>) {
+        let participants = followable_participants(workspace, cx);
+        let weak_workspace = cx.view().downgrade();
+        workspace.toggle_modal(cx, |cx| {
+            Self::new(weak_workspace, participants, in_new_window, cx)
+        });
+    }
+
+    fn new(
+        workspace: WeakView<Workspace>,
+        participants: Vec<FollowableParticipant>,
+        in_new_window: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate = FollowParticipantDelegate {
+            follow_participant: cx.view().downgrade(),
+            workspace,
+            participants,
+            in_new_window,
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+
+    pub fn picker(&self) -> &View<Picker<FollowParticipantDelegate>> {
+        &self.picker
+    }
+}
+
+impl EventEmitter<DismissEvent> for FollowParticipantModal {}
+
+impl FocusableView for FollowParticipantModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for FollowParticipantModal {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+/// A participant of the current call, as listed by [`FollowParticipantModal`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FollowableParticipant {
+    pub peer_id: PeerId,
+    pub github_login: SharedString,
+    pub avatar_uri: SharedUri,
+    /// Where the participant is, relative to the workspace's project.
+    pub location: SharedString,
+    /// Participants outside of Zed can't be followed.
+    pub can_follow: bool,
+    pub is_followed: bool,
+}
+
+fn followable_participants(
+    workspace: &Workspace,
+    cx: &mut ViewContext<Workspace>,
+) -> Vec<FollowableParticipant> {
+    let Some(room) = ActiveCall::global(cx).read(cx).room() else {
+        return Vec::new();
+    };
+    let local_peer_id = workspace.client().peer_id();
+    let project_id = workspace.project().read(cx).remote_id();
+    room.read(cx)
+        .remote_participants()
+        .values()
+        .filter(|participant| Some(participant.peer_id) != local_peer_id)
+        .map(|participant| {
+            let location = match participant.location {
+                ParticipantLocation::SharedProject {
+                    project_id: participant_project_id,
+                } => {
+                    if Some(participant_project_id) == project_id {
+                        "In this project".to_string()
+                    } else {
+                        let root_names = participant
+                            .projects
+                            .iter()
+                            .find(|project| project.id == participant_project_id)
+                            .map(|project| project.worktree_root_names.join(", "))
+                            .filter(|root_names| !root_names.is_empty());
+                        match root_names {
+                            Some(root_names) => format!("In {root_names}"),
+                            None => "In another project".to_string(),
+                        }
+                    }
+                }
+                ParticipantLocation::UnsharedProject => {
+                    "Viewing an unshared Zed project".to_string()
+                }
+                ParticipantLocation::External => {
+                    "Viewing a window outside of Zed, can't be followed".to_string()
+                }
+            };
+            FollowableParticipant {
+                peer_id: participant.peer_id,
+                github_login: participant.user.github_login.clone().into(),
+                avatar_uri: participant.user.avatar_uri.clone(),
+                location: location.into(),
+                can_follow: participant.location != ParticipantLocation::External,
+                is_followed: workspace.is_being_followed(participant.peer_id),
+            }
+        })
+        .collect()
+}
+
+pub struct FollowParticipantDelegate {
+    follow_participant: WeakView<FollowParticipantModal>,
+    workspace: WeakView<Workspace>,
+    participants: Vec<FollowableParticipant>,
+    /// Whether the confirmed participant is followed in a new window.
+    in_new_window: bool,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl FollowParticipantDelegate {
+    /// The participants matching the current query, in the order they are listed.
+    pub fn matching_participants(&self) -> impl Iterator<Item = &FollowableParticipant> {
+        self.matches
+            .iter()
+            .map(|string_match| &self.participants[string_match.candidate_id])
+    }
+}
+
+impl PickerDelegate for FollowParticipantDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        if self.in_new_window {
+            "Follow a collaborator in a new window…".into()
+        } else {
+            "Follow a collaborator…".into()
+        }
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        if self.participants.is_empty() {
+            "There is nobody else in the call".into()
+        } else {
+            "No matches".into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .participants
+            .iter()
+            .enumerate()
+            .map(|(id, participant)| {
+                StringMatchCandidate::new(id, participant.github_login.to_string())
+            })
+            .collect::<Vec<_>>();
+        let executor = cx.background_executor().clone();
+        cx.spawn(|picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.,
+                        positions: Vec::new(),
+                        string: candidate.string,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    usize::MAX,
+                    &Default::default(),
+                    executor,
+                )
+                .await
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(participant) = self
+            .matches
+            .get(self.selected_index)
+            .map(|string_match| &self.participants[string_match.candidate_id])
+        else {
+            return;
+        };
+        if !participant.can_follow {
+            return;
+        }
+        let peer_id = participant.peer_id;
+        let in_new_window = self.in_new_window;
+        self.workspace
+            .update(cx, |workspace, cx| {
+                if in_new_window {
+                    workspace
+                        .follow_in_new_window(peer_id, cx)
+                        .detach_and_log_err(cx);
+                } else {
+                    workspace.follow(peer_id, cx);
+                }
+            })
+            .log_err();
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.follow_participant
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let participant = &self.participants[self.matches.get(ix)?.candidate_id];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .disabled(!participant.can_follow)
+                .start_slot(Avatar::new(participant.avatar_uri.clone()))
+                .child(
+                    v_flex()
+                        .child(Label::new(participant.github_login.clone()).color(
+                            if participant.can_follow {
+                                Color::Default
+                            } else {
+                                Color::Disabled
+                            },
+                        ))
+                        .child(
+                            Label::new(participant.location.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                )
+                .end_slot::<Label>(participant.is_followed.then(|| {
+                    Label::new("Following")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                })),
+        )
+    }
+}
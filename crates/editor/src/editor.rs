@@ -297,8 +297,34 @@ pub fn init(cx: &mut AppContext) {
     init_settings(cx);
 
     workspace::register_project_item::<Editor>(cx);
+    workspace::register_plain_text_project_item::<Editor>(cx);
     workspace::FollowableViewRegistry::register::<Editor>(cx);
     workspace::register_serializable_item::<Editor>(cx);
+    workspace::register_navigation_data::<NavigationData>(cx);
+    workspace::register_scratch_item_builder(
+        |buffer, title, breadcrumb_header, project, cx| {
+            let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx).with_title(title));
+            Box::new(cx.new_view(|cx| {
+                let mut editor = Editor::for_multibuffer(buffer, Some(project.clone()), true, cx);
+                editor.set_ephemeral(true);
+                if let Some(breadcrumb_header) = breadcrumb_header {
+                    editor.set_breadcrumb_header(breadcrumb_header);
+                }
+                editor
+            }))
+        },
+        cx,
+    );
+    workspace::register_untitled_item_builder(
+        |text, project, cx| {
+            let buffer =
+                project.update(cx, |project, cx| project.create_local_buffer("", None, cx));
+            // Set the text as an edit, so that the buffer is dirty like the one it replaces.
+            buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+            Box::new(cx.new_view(|cx| Editor::for_buffer(buffer, Some(project.clone()), cx)))
+        },
+        cx,
+    );
 
     cx.observe_new_views(
         |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
@@ -612,6 +638,7 @@ pub struct Editor {
     input_enabled: bool,
     use_modal_editing: bool,
     read_only: bool,
+    ephemeral: bool,
     leader_peer_id: Option<PeerId>,
     remote_id: Option<ViewId>,
     hover_state: HoverState,
@@ -1763,7 +1790,7 @@ pub struct ClipboardSelection {
     pub first_line_indent: u32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub(crate) struct NavigationData {
     cursor_anchor: Anchor,
     cursor_position: Point,
@@ -2096,6 +2123,7 @@ impl Editor {
             input_enabled: true,
             use_modal_editing: mode == EditorMode::Full,
             read_only: false,
+            ephemeral: false,
             use_autoclose: true,
             use_auto_surround: true,
             auto_replace_emoji_shortcode: false,
@@ -2521,6 +2549,12 @@ impl Editor {
         self.read_only = read_only;
     }
 
+    /// Marks the editor as a scratch item that is never serialized, and whose changes are
+    /// discarded without prompting.
+    pub fn set_ephemeral(&mut self, ephemeral: bool) {
+        self.ephemeral = ephemeral;
+    }
+
     pub fn set_use_autoclose(&mut self, autoclose: bool) {
         self.use_autoclose = autoclose;
     }
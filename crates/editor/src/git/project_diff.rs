@@ -922,6 +922,10 @@ impl Item for ProjectDiffEditor {
         Some("Project Diff".into())
     }
 
+    fn preview_replaceable(&self, _: &AppContext) -> bool {
+        false
+    }
+
     fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
         if self.buffer_changes.is_empty() {
             Label::new("No changes")
@@ -51,6 +51,8 @@ use workspace::{
 };
 
 pub const MAX_TAB_TITLE_LEN: usize = 24;
+/// Name of the file an editor's crash recovery snapshot is written to.
+const RECOVERY_CONTENTS_FILE_NAME: &str = "contents";
 
 impl FollowableItem for Editor {
     fn remote_id(&self) -> Option<ViewId> {
@@ -706,10 +708,29 @@ impl Item for Editor {
         self.hide_hovered_link(cx);
     }
 
+    fn set_external_update_in_progress(&mut self, in_progress: bool, cx: &mut ViewContext<Self>) {
+        for buffer in self.buffer().read(cx).all_buffers() {
+            buffer.update(cx, |buffer, cx| {
+                buffer.set_external_update_in_progress(in_progress, cx)
+            });
+        }
+        // The tab shows whether the file is in conflict.
+        cx.emit(EditorEvent::TitleChanged);
+    }
+
     fn is_dirty(&self, cx: &AppContext) -> bool {
         self.buffer().read(cx).read(cx).is_dirty()
     }
 
+    fn is_ephemeral(&self, _: &AppContext) -> bool {
+        self.ephemeral
+    }
+
+    fn unsaved_text(&self, cx: &AppContext) -> Option<String> {
+        let buffer = self.buffer().read(cx).as_singleton()?.read(cx);
+        buffer.file().is_none().then(|| buffer.text())
+    }
+
     fn has_deleted_file(&self, cx: &AppContext) -> bool {
         self.buffer().read(cx).read(cx).has_deleted_file()
     }
@@ -718,6 +739,16 @@ impl Item for Editor {
         self.buffer().read(cx).read(cx).has_conflict()
     }
 
+    fn has_external_changes(&self, cx: &AppContext) -> bool {
+        self.buffer().read(cx).all_buffers().iter().any(|buffer| {
+            let buffer = buffer.read(cx);
+            buffer.file().map_or(false, |file| {
+                let mtime = file.disk_state().mtime();
+                mtime.is_some() && mtime != buffer.saved_mtime()
+            })
+        })
+    }
+
     fn can_save(&self, cx: &AppContext) -> bool {
         let buffer = &self.buffer().read(cx);
         if let Some(buffer) = buffer.as_singleton() {
@@ -833,6 +864,34 @@ impl Item for Editor {
         })
     }
 
+    fn write_recovery(&self, dir: &Path, cx: &mut ViewContext<Self>) -> Option<Task<Result<()>>> {
+        let buffer = self.buffer().read(cx).as_singleton()?;
+        let fs = self.project.as_ref()?.read(cx).fs().clone();
+        let text = buffer.read(cx).text();
+        let path = dir.join(RECOVERY_CONTENTS_FILE_NAME);
+        Some(
+            cx.background_executor()
+                .spawn(async move { fs.atomic_write(path, text).await }),
+        )
+    }
+
+    fn restore_recovery(
+        &mut self,
+        dir: &Path,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let buffer = self.buffer().read(cx).as_singleton()?;
+        let fs = self.project.as_ref()?.read(cx).fs().clone();
+        let path = dir.join(RECOVERY_CONTENTS_FILE_NAME);
+        Some(cx.spawn(|_, mut cx| async move {
+            let text = fs.load(&path).await?;
+            buffer.update(&mut cx, |buffer, cx| {
+                buffer.set_text(text, cx);
+            })?;
+            Ok(())
+        }))
+    }
+
     fn as_searchable(&self, handle: &View<Self>) -> Option<Box<dyn SearchableItemHandle>> {
         Some(Box::new(handle.clone()))
     }
@@ -1625,7 +1684,13 @@ mod tests {
     use gpui::{AppContext, VisualTestContext};
     use language::{LanguageMatcher, TestFile};
     use project::FakeFs;
-    use std::path::{Path, PathBuf};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::{
+        path::{Path, PathBuf},
+        time::Duration,
+    };
+    use workspace::{AutosaveSetting, WorkspaceSettings};
 
     #[gpui::test]
     fn test_path_for_file(cx: &mut AppContext) {
@@ -1816,4 +1881,99 @@ mod tests {
             });
         }
     }
+
+    #[gpui::test]
+    async fn test_external_update_lock(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.autosave = Some(AutosaveSetting::AfterDelay { milliseconds: 100 });
+                });
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({
+                "generated.rs": "one",
+                "edited.rs": "two",
+                "opened.rs": "three",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let project_path = |name: &str| ProjectPath {
+            worktree_id,
+            path: Path::new(name).into(),
+        };
+        let open = |name: &'static str, cx: &mut VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                workspace.open_path(project_path(name), None, true, cx)
+            })
+        };
+        let generated = open("generated.rs", cx).await.unwrap();
+        let edited = open("edited.rs", cx).await.unwrap();
+        let generated = generated.downcast::<Editor>().unwrap();
+        let edited = edited.downcast::<Editor>().unwrap();
+
+        // While the files are locked, the rewritten file isn't reloaded, the edited one isn't
+        // autosaved, and the items opened meanwhile are locked as well.
+        let guards = workspace.update(cx, |workspace, cx| {
+            ["generated.rs", "edited.rs", "opened.rs"].map(|name| {
+                workspace.lock_item_for_external_update(
+                    project_path(name),
+                    Duration::from_secs(60),
+                    cx,
+                )
+            })
+        });
+        fs.save(
+            Path::new("/root/generated.rs"),
+            &"regenerated".into(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        edited.update(cx, |editor, cx| editor.insert("edited ", cx));
+        let opened = open("opened.rs", cx).await.unwrap();
+        let opened = opened.downcast::<Editor>().unwrap();
+        cx.executor().advance_clock(Duration::from_millis(500));
+        cx.run_until_parked();
+        let external_update_in_progress = |editor: &View<Editor>, cx: &mut VisualTestContext| {
+            editor.update(cx, |editor, cx| {
+                let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+                buffer.read(cx).external_update_in_progress()
+            })
+        };
+        for editor in [&generated, &edited, &opened] {
+            assert!(external_update_in_progress(editor, cx));
+        }
+        generated.update(cx, |editor, cx| {
+            assert_eq!(editor.text(cx), "one");
+            assert!(!editor.has_conflict(cx));
+        });
+        assert_eq!(fs.load(Path::new("/root/edited.rs")).await.unwrap(), "two");
+
+        // Once released, the rewritten file is reloaded and the skipped autosave is run.
+        drop(guards);
+        cx.run_until_parked();
+        for editor in [&generated, &edited, &opened] {
+            assert!(!external_update_in_progress(editor, cx));
+        }
+        generated.update(cx, |editor, cx| {
+            assert_eq!(editor.text(cx), "regenerated");
+            assert!(!editor.is_dirty(cx));
+        });
+        assert_eq!(
+            fs.load(Path::new("/root/edited.rs")).await.unwrap(),
+            "edited two"
+        );
+        edited.update(cx, |editor, cx| assert!(!editor.is_dirty(cx)));
+    }
 }
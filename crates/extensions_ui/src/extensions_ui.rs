@@ -61,6 +61,7 @@ pub fn init(cx: &mut AppContext) {
                         files: false,
                         directories: true,
                         multiple: false,
+                        ..Default::default()
                     },
                     DirectoryLister::Local(workspace.app_state().fs.clone()),
                     cx,
@@ -1,5 +1,6 @@
 use futures::channel::oneshot;
 use fuzzy::StringMatchCandidate;
+use gpui::PathPromptOptions;
 use picker::{Picker, PickerDelegate};
 use project::DirectoryLister;
 use std::{
@@ -48,9 +49,9 @@ struct DirectoryState {
 
 impl OpenPathPrompt {
     pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
-        workspace.set_prompt_for_open_path(Box::new(|workspace, lister, cx| {
+        workspace.set_prompt_for_open_path(Box::new(|workspace, lister, options, cx| {
             let (tx, rx) = futures::channel::oneshot::channel();
-            Self::prompt_for_open_path(workspace, lister, tx, cx);
+            Self::prompt_for_open_path(workspace, lister, options, tx, cx);
             rx
         }));
     }
@@ -58,6 +59,7 @@ impl OpenPathPrompt {
     fn prompt_for_open_path(
         workspace: &mut Workspace,
         lister: DirectoryLister,
+        options: PathPromptOptions,
         tx: oneshot::Sender<Option<Vec<PathBuf>>>,
         cx: &mut ViewContext<Workspace>,
     ) {
@@ -65,7 +67,10 @@ impl OpenPathPrompt {
             let delegate = OpenPathDelegate::new(tx, lister.clone());
 
             let picker = Picker::uniform_list(delegate, cx).width(rems(34.));
-            let query = lister.default_query(cx);
+            let query = match options.starting_directory {
+                Some(directory) => directory.to_string_lossy().to_string(),
+                None => lister.default_query(cx),
+            };
             picker.set_query(query, cx);
             picker
         });
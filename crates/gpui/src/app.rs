@@ -400,6 +400,12 @@ impl AppContext {
         self.platform.quit();
     }
 
+    /// Sets whether closing the last window quits the application, which it does by default on
+    /// Linux and Windows. Has no effect on macOS, where the application keeps running.
+    pub fn set_quit_when_last_window_closes(&self, quit: bool) {
+        self.platform.set_quit_when_last_window_closes(quit);
+    }
+
     /// Schedules all windows in the application to be redrawn. This can be called
     /// multiple times in an update cycle and still result in a single redraw.
     pub fn refresh(&mut self) {
@@ -9,7 +9,9 @@ use crate::{
 };
 use anyhow::{anyhow, bail};
 use futures::{channel::oneshot, Stream, StreamExt};
-use std::{cell::RefCell, future::Future, ops::Deref, rc::Rc, sync::Arc, time::Duration};
+use std::{
+    cell::RefCell, future::Future, ops::Deref, path::PathBuf, rc::Rc, sync::Arc, time::Duration,
+};
 
 /// A TestAppContext is provided to tests created with `#[gpui::test]`, it provides
 /// an implementation of `Context` with additional methods that are useful in tests.
@@ -287,6 +289,17 @@ impl TestAppContext {
         self.test_window(window_handle).simulate_resize(size);
     }
 
+    /// Sets whether the window supports capturing its contents. When it does, captures
+    /// return a blank image of the requested size.
+    pub fn simulate_window_capture_support(&self, window_handle: AnyWindowHandle, supported: bool) {
+        self.test_window(window_handle).0.lock().capture_supported = supported;
+    }
+
+    /// The last path revealed with cx.reveal_path() during this test.
+    pub fn revealed_path(&self) -> Option<PathBuf> {
+        self.test_platform.revealed_path.borrow().clone()
+    }
+
     /// Causes the given sources to be returned if the application queries for screen
     /// capture sources.
     pub fn set_screen_capture_sources(&self, sources: Vec<TestScreenCaptureSource>) {
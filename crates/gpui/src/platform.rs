@@ -37,7 +37,7 @@ use anyhow::{anyhow, Result};
 use async_task::Runnable;
 use futures::channel::oneshot;
 use image::codecs::gif::GifDecoder;
-use image::{AnimationDecoder as _, Frame};
+use image::{AnimationDecoder as _, Frame, RgbaImage};
 use parking::Unparker;
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 use seahash::SeaHasher;
@@ -140,6 +140,8 @@ pub(crate) trait Platform: 'static {
 
     fn run(&self, on_finish_launching: Box<dyn 'static + FnOnce()>);
     fn quit(&self);
+    /// Whether closing the last window quits the app, on the platforms where it does so.
+    fn set_quit_when_last_window_closes(&self, _quit: bool) {}
     fn restart(&self, binary_path: Option<PathBuf>);
     fn activate(&self, ignoring_other_apps: bool);
     fn hide(&self);
@@ -399,6 +401,9 @@ pub(crate) trait PlatformWindow: HasWindowHandle + HasDisplayHandle {
     fn zoom(&self);
     fn toggle_fullscreen(&self);
     fn is_fullscreen(&self) -> bool;
+    /// Moves and resizes the window. Platforms that don't let applications position
+    /// their windows (e.g. Wayland) ignore this.
+    fn set_bounds(&self, _bounds: Bounds<Pixels>) {}
     fn on_request_frame(&self, callback: Box<dyn FnMut(RequestFrameOptions)>);
     fn on_input(&self, callback: Box<dyn FnMut(PlatformInput) -> DispatchEventResult>);
     fn on_active_status_change(&self, callback: Box<dyn FnMut(bool)>);
@@ -411,6 +416,13 @@ pub(crate) trait PlatformWindow: HasWindowHandle + HasDisplayHandle {
     fn draw(&self, scene: &Scene);
     fn completed_frame(&self) {}
     fn sprite_atlas(&self) -> Arc<dyn PlatformAtlas>;
+    /// Captures the window's contents within `bounds`, given in the window's coordinate
+    /// space. Platforms that can't do this return an error.
+    fn capture_region(&self, _bounds: Bounds<Pixels>) -> Result<RgbaImage> {
+        Err(anyhow!(
+            "capturing window contents is not supported on this platform"
+        ))
+    }
 
     // macOS specific methods
     fn set_edited(&mut self, _edited: bool) {}
@@ -1079,7 +1091,7 @@ pub enum WindowBackgroundAppearance {
 }
 
 /// The options that can be configured for a file dialog prompt
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct PathPromptOptions {
     /// Should the prompt allow files to be selected?
     pub files: bool,
@@ -1087,6 +1099,11 @@ pub struct PathPromptOptions {
     pub directories: bool,
     /// Should the prompt allow multiple files to be selected?
     pub multiple: bool,
+    /// The directory the prompt should start in, if the platform supports it.
+    pub starting_directory: Option<PathBuf>,
+    /// Named groups of file extensions, without the leading dot, that the selectable files
+    /// are restricted to, if the platform supports it. Empty to allow any file.
+    pub extension_filters: Vec<(String, Vec<String>)>,
 }
 
 /// What kind of prompt styling to show
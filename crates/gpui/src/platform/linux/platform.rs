@@ -93,6 +93,7 @@ pub(crate) struct LinuxCommon {
     pub(crate) callbacks: PlatformHandlers,
     pub(crate) signal: LoopSignal,
     pub(crate) menus: Vec<OwnedMenu>,
+    pub(crate) quit_when_last_window_closes: bool,
 }
 
 impl LinuxCommon {
@@ -119,6 +120,7 @@ impl LinuxCommon {
             callbacks,
             signal,
             menus: Vec::new(),
+            quit_when_last_window_closes: true,
         };
 
         (common, main_receiver)
@@ -161,6 +163,10 @@ impl<P: LinuxClient + 'static> Platform for P {
         self.with_common(|common| common.signal.stop());
     }
 
+    fn set_quit_when_last_window_closes(&self, quit: bool) {
+        self.with_common(|common| common.quit_when_last_window_closes = quit);
+    }
+
     fn compositor_name(&self) -> &'static str {
         self.compositor_name()
     }
@@ -297,6 +303,14 @@ impl<P: LinuxClient + 'static> Platform for P {
                     .title(title)
                     .multiple(options.multiple)
                     .directory(options.directories)
+                    .current_folder(options.starting_directory.as_deref())
+                    .expect("pathbuf should not be nul terminated")
+                    .filters(options.extension_filters.iter().map(|(name, extensions)| {
+                        extensions.iter().fold(
+                            ashpd::desktop::file_chooser::FileFilter::new(name),
+                            |filter, extension| filter.glob(&format!("*.{extension}")),
+                        )
+                    }))
                     .send()
                     .await
                 {
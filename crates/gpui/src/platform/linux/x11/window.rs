@@ -1297,6 +1297,23 @@ impl PlatformWindow for X11Window {
         .unwrap();
     }
 
+    fn set_bounds(&self, bounds: Bounds<Pixels>) {
+        let bounds = bounds.to_device_pixels(self.0.state.borrow().scale_factor);
+        check_reply(
+            || "X11 ConfigureWindow failed.",
+            self.0.xcb.configure_window(
+                self.0.x_window,
+                &xproto::ConfigureWindowAux::new()
+                    .x(bounds.origin.x.0)
+                    .y(bounds.origin.y.0)
+                    .width(bounds.size.width.0 as u32)
+                    .height(bounds.size.height.0 as u32),
+            ),
+        )
+        .log_err();
+        self.0.xcb.flush().log_err();
+    }
+
     fn toggle_fullscreen(&self) {
         let state = self.0.state.borrow();
         self.set_wm_hints(
@@ -665,6 +665,21 @@ impl Platform for MacPlatform {
                     panel.setAllowsMultipleSelection_(options.multiple.to_objc());
                     panel.setCanCreateDirectories(true.to_objc());
                     panel.setResolvesAliases_(false.to_objc());
+                    if let Some(directory) = options.starting_directory.as_ref() {
+                        let path = ns_string(directory.to_string_lossy().as_ref());
+                        let url = NSURL::fileURLWithPath_isDirectory_(nil, path, true.to_objc());
+                        panel.setDirectoryURL(url);
+                    }
+                    if !options.extension_filters.is_empty() {
+                        let file_types = options
+                            .extension_filters
+                            .iter()
+                            .flat_map(|(_, extensions)| extensions)
+                            .map(|extension| ns_string(extension))
+                            .collect::<Vec<_>>();
+                        let file_types = NSArray::arrayWithObjects(nil, &file_types);
+                        let _: () = msg_send![panel, setAllowedFileTypes: file_types];
+                    }
                     let done_tx = Cell::new(Some(done_tx));
                     let block = ConcreteBlock::new(move |response: NSModalResponse| {
                         let result = if response == NSModalResponse::NSModalResponseOk {
@@ -1040,6 +1040,34 @@ impl PlatformWindow for MacWindow {
             .detach();
     }
 
+    fn set_bounds(&self, bounds: Bounds<Pixels>) {
+        let this = self.0.lock();
+        let window = this.native_window;
+        this.executor
+            .spawn(async move {
+                unsafe {
+                    let mut screen = NSWindow::screen(window);
+                    // A window that is entirely off-screen has no screen, so fall back to
+                    // the primary one, which bounds are relative to in that case.
+                    if screen == nil {
+                        screen = NSArray::objectAtIndex(NSScreen::screens(nil), 0);
+                    }
+                    let screen_frame = NSScreen::frame(screen);
+                    // This is the inverse of the coordinate flip in `bounds`.
+                    let frame = NSRect::new(
+                        NSPoint::new(
+                            screen_frame.origin.x + bounds.origin.x.0 as f64,
+                            screen_frame.size.height + screen_frame.origin.y
+                                - (bounds.origin.y + bounds.size.height).0 as f64,
+                        ),
+                        NSSize::new(bounds.size.width.0 as f64, bounds.size.height.0 as f64),
+                    );
+                    window.setFrame_display_(frame, YES);
+                }
+            })
+            .detach();
+    }
+
     fn toggle_fullscreen(&self) {
         let this = self.0.lock();
         let window = this.native_window;
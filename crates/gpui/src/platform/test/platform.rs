@@ -33,6 +33,7 @@ pub(crate) struct TestPlatform {
     pub(crate) prompts: RefCell<TestPrompts>,
     screen_capture_sources: RefCell<Vec<TestScreenCaptureSource>>,
     pub opened_url: RefCell<Option<String>>,
+    pub revealed_path: RefCell<Option<PathBuf>>,
     pub text_system: Arc<dyn PlatformTextSystem>,
     #[cfg(target_os = "windows")]
     bitmap_factory: std::mem::ManuallyDrop<IWICImagingFactory>,
@@ -107,6 +108,7 @@ impl TestPlatform {
             current_primary_item: Mutex::new(None),
             weak: weak.clone(),
             opened_url: Default::default(),
+            revealed_path: Default::default(),
             #[cfg(target_os = "windows")]
             bitmap_factory,
             text_system,
@@ -299,8 +301,8 @@ impl Platform for TestPlatform {
         rx
     }
 
-    fn reveal_path(&self, _path: &std::path::Path) {
-        unimplemented!()
+    fn reveal_path(&self, path: &std::path::Path) {
+        *self.revealed_path.borrow_mut() = Some(path.to_path_buf())
     }
 
     fn on_quit(&self, _callback: Box<dyn FnMut()>) {}
@@ -28,6 +28,7 @@ pub(crate) struct TestWindowState {
     moved_callback: Option<Box<dyn FnMut()>>,
     input_handler: Option<PlatformInputHandler>,
     is_fullscreen: bool,
+    pub(crate) capture_supported: bool,
 }
 
 #[derive(Clone)]
@@ -72,6 +73,7 @@ impl TestWindow {
             moved_callback: None,
             input_handler: None,
             is_fullscreen: false,
+            capture_supported: false,
         })))
     }
 
@@ -212,6 +214,17 @@ impl PlatformWindow for TestWindow {
         unimplemented!()
     }
 
+    fn set_bounds(&self, bounds: Bounds<Pixels>) {
+        let mut lock = self.0.lock();
+        lock.bounds = bounds;
+        let Some(mut callback) = lock.moved_callback.take() else {
+            return;
+        };
+        drop(lock);
+        callback();
+        self.0.lock().moved_callback = Some(callback);
+    }
+
     fn toggle_fullscreen(&self) {
         let mut lock = self.0.lock();
         lock.is_fullscreen = !lock.is_fullscreen;
@@ -261,6 +274,17 @@ impl PlatformWindow for TestWindow {
         Some(self)
     }
 
+    fn capture_region(&self, bounds: Bounds<Pixels>) -> anyhow::Result<image::RgbaImage> {
+        if !self.0.lock().capture_supported {
+            anyhow::bail!("capturing window contents is not supported on this platform");
+        }
+        let size = bounds.size.scale(self.scale_factor());
+        Ok(image::RgbaImage::new(
+            size.width.0.ceil() as u32,
+            size.height.0.ceil() as u32,
+        ))
+    }
+
     #[cfg(target_os = "windows")]
     fn get_raw_handle(&self) -> windows::Win32::Foundation::HWND {
         unimplemented!()
@@ -49,6 +49,7 @@ pub(crate) struct WindowsPlatformState {
     callbacks: PlatformCallbacks,
     // NOTE: standard cursor handles don't need to close.
     pub(crate) current_cursor: HCURSOR,
+    quit_when_last_window_closes: bool,
 }
 
 #[derive(Default)]
@@ -69,6 +70,7 @@ impl WindowsPlatformState {
         Self {
             callbacks,
             current_cursor,
+            quit_when_last_window_closes: true,
         }
     }
 }
@@ -162,7 +164,7 @@ impl WindowsPlatform {
             .unwrap();
         lock.remove(index);
 
-        lock.is_empty()
+        lock.is_empty() && self.state.borrow().quit_when_last_window_closes
     }
 
     #[inline]
@@ -270,6 +272,10 @@ impl Platform for WindowsPlatform {
             .detach();
     }
 
+    fn set_quit_when_last_window_closes(&self, quit: bool) {
+        self.state.borrow_mut().quit_when_last_window_closes = quit;
+    }
+
     fn restart(&self, _: Option<PathBuf>) {
         let pid = std::process::id();
         let Some(app_path) = self.app_path().log_err() else {
@@ -625,8 +631,40 @@ fn file_open_dialog(options: PathPromptOptions) -> Result<Option<Vec<PathBuf>>>
         dialog_options |= FOS_PICKFOLDERS;
     }
 
+    if let Some(directory) = options.starting_directory.as_ref() {
+        if let Some(full_path) = directory.canonicalize().log_err() {
+            let full_path = SanitizedPath::from(full_path);
+            let path_item: IShellItem = unsafe {
+                SHCreateItemFromParsingName(&HSTRING::from(full_path.to_string()), None)?
+            };
+            unsafe { folder_dialog.SetFolder(&path_item).log_err() };
+        }
+    }
+    // The filter specs point into these strings, which have to outlive the dialog.
+    let filters = options
+        .extension_filters
+        .iter()
+        .map(|(name, extensions)| {
+            let spec = extensions
+                .iter()
+                .map(|extension| format!("*.{extension}"))
+                .join(";");
+            (HSTRING::from(name.as_str()), HSTRING::from(spec))
+        })
+        .collect::<Vec<_>>();
+    let filter_specs = filters
+        .iter()
+        .map(|(name, spec)| Common::COMDLG_FILTERSPEC {
+            pszName: PCWSTR::from_raw(name.as_ptr()),
+            pszSpec: PCWSTR::from_raw(spec.as_ptr()),
+        })
+        .collect::<Vec<_>>();
+
     unsafe {
         folder_dialog.SetOptions(dialog_options)?;
+        if !filter_specs.is_empty() {
+            folder_dialog.SetFileTypes(&filter_specs)?;
+        }
         if folder_dialog.Show(None).is_err() {
             // User cancelled
             return Ok(None);
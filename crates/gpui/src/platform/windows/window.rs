@@ -707,6 +707,27 @@ impl PlatformWindow for WindowsWindow {
         }
     }
 
+    fn set_bounds(&self, bounds: Bounds<Pixels>) {
+        let state = self.0.state.borrow();
+        let rect = calculate_window_rect(
+            bounds.to_device_pixels(state.scale_factor),
+            state.border_offset,
+        );
+        drop(state);
+        unsafe {
+            SetWindowPos(
+                self.0.hwnd,
+                HWND::default(),
+                rect.left,
+                rect.top,
+                rect.right - rect.left,
+                rect.bottom - rect.top,
+                SWP_NOACTIVATE | SWP_NOZORDER,
+            )
+        }
+        .log_err();
+    }
+
     fn toggle_fullscreen(&self) {
         if unsafe { IsWindowVisible(self.0.hwnd).as_bool() } {
             self.0.toggle_fullscreen();
@@ -3623,6 +3623,23 @@ impl<'a> WindowContext<'a> {
         self.window.platform_window.toggle_fullscreen();
     }
 
+    /// Moves and resizes the window at the platform level, see [`Self::bounds`] for the
+    /// coordinate space. This has no effect on platforms that don't allow it.
+    pub fn set_window_bounds(&self, bounds: Bounds<Pixels>) {
+        self.window.platform_window.set_bounds(bounds);
+    }
+
+    /// Captures the window's contents within `bounds`, given in the same coordinate space as
+    /// element bounds, and encodes them as a PNG. Fails on platforms that don't support it.
+    pub fn capture_region_png(&self, bounds: Bounds<Pixels>) -> Result<Vec<u8>> {
+        let image = self.window.platform_window.capture_region(bounds)?;
+        let mut png = Vec::new();
+        image
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .context("failed to encode the captured region")?;
+        Ok(png)
+    }
+
     /// Updates the IME panel position suggestions for languages like japanese, chinese.
     pub fn invalidate_character_coordinates(&self) {
         self.on_next_frame(|cx| {
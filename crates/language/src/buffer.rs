@@ -124,6 +124,9 @@ pub struct Buffer {
     deferred_ops: OperationQueue<Operation>,
     capability: Capability,
     has_conflict: bool,
+    /// Whether a tool outside of Zed is rewriting the file, in which case the buffer
+    /// neither reloads it nor reports conflicts with it.
+    external_update_in_progress: bool,
     /// Memoize calls to has_changes_since(saved_version).
     /// The contents of a cell are (self.version, has_changes) at the time of a last call.
     has_unsaved_edits: Cell<(clock::Global, bool)>,
@@ -782,6 +785,7 @@ impl Buffer {
             completion_triggers_timestamp: Default::default(),
             deferred_ops: OperationQueue::new(),
             has_conflict: false,
+            external_update_in_progress: false,
             _subscriptions: Vec::new(),
         }
     }
@@ -971,6 +975,25 @@ impl Buffer {
         cx.emit(BufferEvent::CapabilityChanged)
     }
 
+    /// Marks the file as being rewritten by a tool outside of Zed. Meanwhile, the buffer
+    /// isn't reloaded when the file changes, and doesn't report conflicts with it; whoever
+    /// set the flag is expected to reload the buffer once the tool is done.
+    pub fn set_external_update_in_progress(
+        &mut self,
+        in_progress: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if self.external_update_in_progress != in_progress {
+            self.external_update_in_progress = in_progress;
+            cx.notify();
+        }
+    }
+
+    /// Whether the file is being rewritten by a tool outside of Zed.
+    pub fn external_update_in_progress(&self) -> bool {
+        self.external_update_in_progress
+    }
+
     /// This method is called to signal that the buffer has been saved.
     pub fn did_save(
         &mut self,
@@ -1068,7 +1091,10 @@ impl Buffer {
             let new_state = new_file.disk_state();
             if old_state != new_state {
                 file_changed = true;
-                if !was_dirty && matches!(new_state, DiskState::Present { .. }) {
+                if !was_dirty
+                    && !self.external_update_in_progress
+                    && matches!(new_state, DiskState::Present { .. })
+                {
                     cx.emit(BufferEvent::ReloadNeeded)
                 }
             }
@@ -1682,6 +1708,9 @@ impl Buffer {
     /// Checks if the buffer and its file have both changed since the buffer
     /// was last saved or reloaded.
     pub fn has_conflict(&self) -> bool {
+        if self.external_update_in_progress {
+            return false;
+        }
         if self.has_conflict {
             return true;
         }
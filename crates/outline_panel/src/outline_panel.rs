@@ -54,7 +54,7 @@ use workspace::{
         LabelCommon, ListItem, Scrollbar, ScrollbarState, Selectable, StyledExt, StyledTypography,
         Tooltip,
     },
-    OpenInTerminal, WeakItemHandle, Workspace,
+    OpenInTerminal, WeakItemHandle, Workspace, WorkspaceEventSubscriber,
 };
 use worktree::{Entry, ProjectEntryId, WorktreeId};
 
@@ -636,30 +636,24 @@ impl OutlinePanel {
             let focus_out_subscription = cx.on_focus_out(&focus_handle, |outline_panel, _, cx| {
                 outline_panel.hide_scrollbar(cx);
             });
-            let workspace_subscription = cx.subscribe(
+            let workspace_subscription = WorkspaceEventSubscriber::new(
                 &workspace
                     .weak_handle()
                     .upgrade()
                     .expect("have a &mut Workspace"),
-                move |outline_panel, workspace, event, cx| {
-                    if let workspace::Event::ActiveItemChanged = event {
-                        if let Some((new_active_item, new_active_editor)) =
-                            workspace_active_editor(workspace.read(cx), cx)
-                        {
-                            if outline_panel.should_replace_active_item(new_active_item.as_ref()) {
-                                outline_panel.replace_active_editor(
-                                    new_active_item,
-                                    new_active_editor,
-                                    cx,
-                                );
-                            }
-                        } else {
-                            outline_panel.clear_previous(cx);
-                            cx.notify();
-                        }
+            )
+            .on_active_item_changed(cx, |outline_panel, workspace, cx| {
+                if let Some((new_active_item, new_active_editor)) =
+                    workspace_active_editor(workspace.read(cx), cx)
+                {
+                    if outline_panel.should_replace_active_item(new_active_item.as_ref()) {
+                        outline_panel.replace_active_editor(new_active_item, new_active_editor, cx);
                     }
-                },
-            );
+                } else {
+                    outline_panel.clear_previous(cx);
+                    cx.notify();
+                }
+            });
 
             let icons_subscription = cx.observe_global::<FileIcons>(|_, cx| {
                 cx.notify();
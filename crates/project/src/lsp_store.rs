@@ -7630,6 +7630,18 @@ pub struct LanguageServerPromptRequest {
 }
 
 impl LanguageServerPromptRequest {
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn test(lsp_name: &str, message: &str, actions: Vec<MessageActionItem>) -> Self {
+        let (tx, _) = smol::channel::bounded(1);
+        Self {
+            level: PromptLevel::Info,
+            message: message.to_string(),
+            actions,
+            lsp_name: lsp_name.to_string(),
+            response_channel: tx,
+        }
+    }
+
     pub async fn respond(self, index: usize) -> Option<()> {
         if let Some(response) = self.actions.into_iter().nth(index) {
             self.response_channel.send(response).await.ok()
@@ -1,4 +1,5 @@
 mod project_panel_settings;
+mod rename_item_modal;
 mod utils;
 
 use client::{ErrorCode, ErrorExt};
@@ -38,6 +39,7 @@ use project::{
 use project_panel_settings::{
     ProjectPanelDockPosition, ProjectPanelSettings, ShowDiagnostics, ShowIndentGuides,
 };
+use rename_item_modal::RenameItemModal;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use std::any::TypeId;
@@ -221,6 +223,7 @@ pub fn init_settings(cx: &mut AppContext) {
 pub fn init(assets: impl AssetSource, cx: &mut AppContext) {
     init_settings(cx);
     file_icons::init(assets, cx);
+    workspace::register_rename_item_modal(RenameItemModal::open, cx);
 
     cx.observe_new_views(|workspace: &mut Workspace, _| {
         workspace.register_action(|workspace, _: &ToggleFocus, cx| {
@@ -487,6 +490,13 @@ impl ProjectPanel {
         })
         .detach();
 
+        let panel = project_panel.downgrade();
+        workspace.set_reveal_handler::<Self>(Box::new(move |path, cx| {
+            panel
+                .update(cx, |panel, cx| panel.reveal_path(&path, cx))
+                .unwrap_or(false)
+        }));
+
         project_panel
     }
 
@@ -3719,6 +3729,14 @@ impl ProjectPanel {
         }
     }
 
+    fn reveal_path(&mut self, path: &ProjectPath, cx: &mut ViewContext<Self>) -> bool {
+        let Some(entry) = self.project.read(cx).entry_for_path(path, cx) else {
+            return false;
+        };
+        self.reveal_entry(self.project.clone(), entry.id, false, cx);
+        true
+    }
+
     fn find_active_indent_guide(
         &self,
         indent_guides: &[IndentGuideLayout],
@@ -7909,7 +7927,7 @@ mod tests {
         });
     }
 
-    fn init_test_with_editor(cx: &mut TestAppContext) {
+    pub(crate) fn init_test_with_editor(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let app_state = AppState::test(cx);
             theme::init(theme::LoadThemes::JustBase, cx);
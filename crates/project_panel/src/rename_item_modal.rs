@@ -0,0 +1,245 @@
+use editor::{Editor, EditorEvent};
+use gpui::{
+    DismissEvent, EventEmitter, FocusHandle, FocusableView, Subscription, View, ViewContext,
+    WeakView,
+};
+use project::ProjectPath;
+use ui::{prelude::*, Checkbox, Selection};
+use workspace::{ModalView, Workspace};
+
+/// Asks for the new name of the file behind the active item, see
+/// [`Workspace::interactive_rename_active_item`].
+pub(crate) struct RenameItemModal {
+    workspace: WeakView<Workspace>,
+    project_path: ProjectPath,
+    editor: View<Editor>,
+    allow_move: bool,
+    error: Option<SharedString>,
+    _subscription: Subscription,
+}
+
+impl ModalView for RenameItemModal {}
+
+impl RenameItemModal {
+    pub(crate) fn open(
+        workspace: &mut Workspace,
+        project_path: ProjectPath,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let weak_workspace = cx.view().downgrade();
+        workspace.toggle_modal(cx, |cx| Self::new(weak_workspace, project_path, cx));
+    }
+
+    fn new(
+        workspace: WeakView<Workspace>,
+        project_path: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let file_name = file_name(&project_path);
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_text(file_name, cx);
+            editor.select_all(&Default::default(), cx);
+            editor
+        });
+        let subscription = cx.subscribe(&editor, |this, _, event: &EditorEvent, cx| {
+            if let EditorEvent::BufferEdited = event {
+                if this.error.take().is_some() {
+                    cx.notify();
+                }
+            }
+        });
+        Self {
+            workspace,
+            project_path,
+            editor,
+            allow_move: false,
+            error: None,
+            _subscription: subscription,
+        }
+    }
+
+    /// Switches between editing the file name and the path relative to the worktree root.
+    fn toggle_move(&mut self, cx: &mut ViewContext<Self>) {
+        self.allow_move = !self.allow_move;
+        let text = if self.allow_move {
+            self.project_path.path.to_string_lossy().to_string()
+        } else {
+            file_name(&self.project_path)
+        };
+        self.editor
+            .update(cx, |editor, cx| editor.set_text(text, cx));
+        self.error = None;
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let new_name = self.editor.read(cx).text(cx);
+        let project_path = self.project_path.clone();
+        let allow_move = self.allow_move;
+        let rename = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                let new_path =
+                    workspace.validate_item_rename(&project_path, &new_name, allow_move, cx)?;
+                anyhow::Ok(workspace.rename_item(project_path, new_path, cx))
+            })
+            .and_then(|rename| rename);
+        let rename = match rename {
+            Ok(rename) => rename,
+            Err(error) => {
+                self.error = Some(error.to_string().into());
+                cx.notify();
+                return;
+            }
+        };
+
+        cx.spawn(|this, mut cx| async move {
+            let result = rename.await;
+            this.update(&mut cx, |this, cx| match result {
+                Ok(()) => cx.emit(DismissEvent),
+                Err(error) => {
+                    this.error = Some(error.to_string().into());
+                    cx.notify();
+                }
+            })
+        })
+        .detach();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+fn file_name(project_path: &ProjectPath) -> String {
+    project_path
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+impl EventEmitter<DismissEvent> for RenameItemModal {}
+
+impl FocusableView for RenameItemModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for RenameItemModal {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let allow_move = if self.allow_move {
+            Selection::Selected
+        } else {
+            Selection::Unselected
+        };
+        v_flex()
+            .key_context("RenameItemModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_3(cx)
+            .w(rems(34.))
+            .p_2()
+            .gap_2()
+            .child(
+                Label::new("Rename File")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(self.editor.clone())
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Checkbox::new("rename-item-allow-move", allow_move)
+                            .on_click(cx.listener(|this, _, cx| this.toggle_move(cx))),
+                    )
+                    .child(Label::new("Move to another directory").size(LabelSize::Small)),
+            )
+            .when_some(self.error.clone(), |this, error| {
+                this.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test_with_editor;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use std::path::Path;
+    use workspace::RenameActiveItem;
+
+    #[gpui::test]
+    async fn test_rename_active_item(cx: &mut TestAppContext) {
+        init_test_with_editor(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "one.txt": "one", "two.txt": "two" }))
+            .await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let item = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_abs_path("/root/one.txt".into(), true, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        cx.dispatch_action(RenameActiveItem);
+        let modal = workspace.update(cx, |workspace, cx| {
+            workspace.active_modal::<RenameItemModal>(cx).unwrap()
+        });
+        modal.update(cx, |modal, cx| {
+            assert_eq!(modal.editor.read(cx).text(cx), "one.txt");
+            modal
+                .editor
+                .update(cx, |editor, cx| editor.set_text("two.txt", cx));
+        });
+
+        // Collisions are shown in the modal, until the name is edited.
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        modal.update(cx, |modal, cx| {
+            assert_eq!(modal.error.as_deref(), Some("\"two.txt\" already exists"));
+            modal
+                .editor
+                .update(cx, |editor, cx| editor.set_text("renamed.txt", cx));
+        });
+        cx.run_until_parked();
+        modal.update(cx, |modal, _| assert_eq!(modal.error, None));
+
+        // The file is renamed, and its tab and history follow it.
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        assert!(fs.is_file(Path::new("/root/renamed.txt")).await);
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.active_modal::<RenameItemModal>(cx).is_none());
+            let pane = workspace.active_pane().read(cx);
+            assert_eq!(pane.items_len(), 1);
+            let project_path = item.project_path(cx).unwrap();
+            assert_eq!(project_path.path.as_ref(), Path::new("renamed.txt"));
+            assert_eq!(
+                pane.nav_history().path_for_item(item.item_id()),
+                Some((project_path, Some("/root/renamed.txt".into())))
+            );
+        });
+
+        // Untitled items are saved under a new name instead.
+        workspace
+            .update(cx, |workspace, cx| Editor::new_in_workspace(workspace, cx))
+            .await
+            .unwrap();
+        cx.dispatch_action(RenameActiveItem);
+        cx.run_until_parked();
+        assert!(cx.did_prompt_for_new_path());
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.active_modal::<RenameItemModal>(cx).is_none());
+        });
+    }
+}
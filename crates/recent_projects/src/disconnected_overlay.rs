@@ -1,5 +1,3 @@
-use std::path::PathBuf;
-
 use gpui::{ClickEvent, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, WeakView};
 use project::project_settings::ProjectSettings;
 use remote::SshConnectionOptions;
@@ -9,9 +7,7 @@ use ui::{
     Headline, HeadlineSize, IconName, IconPosition, InteractiveElement, IntoElement, Label, Modal,
     ModalFooter, ModalHeader, ParentElement, Section, Styled, StyledExt, ViewContext,
 };
-use workspace::{notifications::DetachAndPromptErr, ModalView, OpenOptions, Workspace};
-
-use crate::open_ssh_project;
+use workspace::{notifications::DetachAndPromptErr, ModalView, Workspace};
 
 enum Host {
     RemoteProject,
@@ -73,49 +69,21 @@ impl DisconnectedOverlay {
         cx.emit(DismissEvent);
 
         match &self.host {
-            Host::SshRemoteProject(ssh_connection_options) => {
-                self.reconnect_to_ssh_remote(ssh_connection_options.clone(), cx);
+            Host::SshRemoteProject(_) => {
+                self.reconnect_to_ssh_remote(cx);
             }
             _ => {}
         }
     }
 
-    fn reconnect_to_ssh_remote(
-        &self,
-        connection_options: SshConnectionOptions,
-        cx: &mut ViewContext<Self>,
-    ) {
+    fn reconnect_to_ssh_remote(&self, cx: &mut ViewContext<Self>) {
         let Some(workspace) = self.workspace.upgrade() else {
             return;
         };
 
-        let Some(ssh_project) = workspace.read(cx).serialized_ssh_project() else {
-            return;
-        };
-
-        let Some(window) = cx.window_handle().downcast::<Workspace>() else {
-            return;
-        };
-
-        let app_state = workspace.read(cx).app_state().clone();
-
-        let paths = ssh_project.paths.iter().map(PathBuf::from).collect();
-
-        cx.spawn(move |_, mut cx| async move {
-            open_ssh_project(
-                connection_options,
-                paths,
-                app_state,
-                OpenOptions {
-                    replace_window: Some(window),
-                    ..Default::default()
-                },
-                &mut cx,
-            )
-            .await?;
-            Ok(())
-        })
-        .detach_and_prompt_err("Failed to reconnect", cx, |_, _| None);
+        workspace
+            .update(cx, |workspace, cx| workspace.reconnect_ssh_project(cx))
+            .detach_and_prompt_err("Failed to reconnect", cx, |_, _| None);
     }
 
     fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Task, View, ViewContext,
+    WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{
+    notifications::DetachAndPromptErr, LayoutCheckpoint, ModalView, RestoreLayoutCheckpoint,
+    Workspace,
+};
+
+/// Lists the layout checkpoints of a workspace and restores the confirmed one.
+pub struct LayoutCheckpoints {
+    picker: View<Picker<LayoutCheckpointsDelegate>>,
+}
+
+impl ModalView for LayoutCheckpoints {}
+
+impl LayoutCheckpoints {
+    pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &RestoreLayoutCheckpoint, cx| {
+            let checkpoints = workspace.layout_checkpoints(cx);
+            let weak_workspace = cx.view().downgrade();
+            workspace.toggle_modal(cx, |cx| Self::new(weak_workspace, checkpoints, cx));
+        });
+    }
+
+    fn new(
+        workspace: WeakView<Workspace>,
+        checkpoints: Task<Result<Vec<LayoutCheckpoint>>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate = LayoutCheckpointsDelegate {
+            layout_checkpoints: cx.view().downgrade(),
+            workspace,
+            checkpoints: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        cx.spawn(|this, mut cx| async move {
+            let checkpoints = checkpoints.await.log_err().unwrap_or_default();
+            this.update(&mut cx, move |this, cx| {
+                this.picker.update(cx, move |picker, cx| {
+                    picker.delegate.checkpoints = checkpoints;
+                    picker.update_matches(picker.query(cx), cx)
+                })
+            })
+            .ok()
+        })
+        .detach();
+        Self { picker }
+    }
+}
+
+impl EventEmitter<DismissEvent> for LayoutCheckpoints {}
+
+impl FocusableView for LayoutCheckpoints {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for LayoutCheckpoints {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+pub struct LayoutCheckpointsDelegate {
+    layout_checkpoints: WeakView<LayoutCheckpoints>,
+    workspace: WeakView<Workspace>,
+    checkpoints: Vec<LayoutCheckpoint>,
+    matches: Vec<usize>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for LayoutCheckpointsDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Restore a layout checkpoint…".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        if self.checkpoints.is_empty() {
+            "Layout checkpoints will show up here once `layout_checkpoint_interval_minutes` is set"
+                .into()
+        } else {
+            "No matches".into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, _: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        self.matches = self
+            .checkpoints
+            .iter()
+            .enumerate()
+            .filter(|(_, checkpoint)| checkpoint.created_at.contains(query.trim()))
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = self
+            .selected_index
+            .min(self.matches.len().saturating_sub(1));
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(checkpoint) = self
+            .matches
+            .get(self.selected_index)
+            .map(|&ix| self.checkpoints[ix].clone())
+        else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .restore_layout_checkpoint(&checkpoint, cx)
+                    .detach_and_prompt_err("Failed to restore layout", cx, |_, _| None);
+            })
+            .log_err();
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.layout_checkpoints
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let checkpoint = &self.checkpoints[*self.matches.get(ix)?];
+        let panes = match checkpoint.pane_count {
+            1 => "1 pane".to_string(),
+            count => format!("{count} panes"),
+        };
+        let items = match checkpoint.item_count {
+            1 => "1 item".to_string(),
+            count => format!("{count} items"),
+        };
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Label::new(format!("{} UTC", checkpoint.created_at)))
+                        .child(
+                            Label::new(format!("{panes}, {items}"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
@@ -1,8 +1,11 @@
 pub mod disconnected_overlay;
+mod layout_checkpoints;
 mod remote_servers;
+mod session_notes;
 mod ssh_connections;
 pub use ssh_connections::{is_connecting_over_ssh, open_ssh_project};
 
+use collections::HashMap;
 use disconnected_overlay::DisconnectedOverlay;
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
@@ -10,12 +13,14 @@ use gpui::{
     Subscription, Task, View, ViewContext, WeakView,
 };
 use itertools::Itertools;
+use layout_checkpoints::LayoutCheckpoints;
 use ordered_float::OrderedFloat;
 use picker::{
     highlighted_match_with_paths::{HighlightedMatchWithPaths, HighlightedText},
     Picker, PickerDelegate,
 };
 pub use remote_servers::RemoteServerProjects;
+use session_notes::SessionNotes;
 use settings::Settings;
 pub use ssh_connections::SshSettings;
 use std::{
@@ -36,6 +41,9 @@ pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(RemoteServerProjects::register)
         .detach();
     cx.observe_new_views(DisconnectedOverlay::register).detach();
+    cx.observe_new_views(LayoutCheckpoints::register).detach();
+    cx.observe_new_views(SessionNotes::register).detach();
+    workspace::register_ssh_connector(ssh_connections::reconnect_over_ssh, cx);
 }
 
 pub struct RecentProjects {
@@ -65,9 +73,21 @@ impl RecentProjects {
                 .await
                 .log_err()
                 .unwrap_or_default();
+            let mut session_notes = HashMap::default();
+            for (workspace_id, _) in &workspaces {
+                if let Some(notes) = WORKSPACE_DB
+                    .session_notes(*workspace_id)
+                    .await
+                    .log_err()
+                    .flatten()
+                {
+                    session_notes.insert(*workspace_id, notes.into());
+                }
+            }
             this.update(&mut cx, move |this, cx| {
                 this.picker.update(cx, move |picker, cx| {
                     picker.delegate.set_workspaces(workspaces);
+                    picker.delegate.session_notes = session_notes;
                     picker.update_matches(picker.query(cx), cx)
                 })
             })
@@ -134,6 +154,7 @@ impl Render for RecentProjects {
 pub struct RecentProjectsDelegate {
     workspace: WeakView<Workspace>,
     workspaces: Vec<(WorkspaceId, SerializedWorkspaceLocation)>,
+    session_notes: HashMap<WorkspaceId, SharedString>,
     selected_match_index: usize,
     matches: Vec<StringMatch>,
     render_paths: bool,
@@ -148,6 +169,7 @@ impl RecentProjectsDelegate {
         Self {
             workspace,
             workspaces: Vec::new(),
+            session_notes: HashMap::default(),
             selected_match_index: 0,
             matches: Default::default(),
             create_new_window,
@@ -361,7 +383,8 @@ impl PickerDelegate for RecentProjectsDelegate {
     ) -> Option<Self::ListItem> {
         let hit = self.matches.get(ix)?;
 
-        let (_, location) = self.workspaces.get(hit.candidate_id)?;
+        let (workspace_id, location) = self.workspaces.get(hit.candidate_id)?;
+        let session_notes = self.session_notes.get(workspace_id).cloned();
 
         let mut path_start_offset = 0;
         let paths = match location {
@@ -445,8 +468,10 @@ impl PickerDelegate for RecentProjectsDelegate {
                 })
                 .tooltip(move |cx| {
                     let tooltip_highlighted_location = highlighted_match.clone();
+                    let session_notes = session_notes.clone();
                     cx.new_view(move |_| MatchTooltip {
                         highlighted_location: tooltip_highlighted_location,
+                        session_notes,
                     })
                     .into()
                 }),
@@ -564,12 +589,21 @@ impl RecentProjectsDelegate {
 }
 struct MatchTooltip {
     highlighted_location: HighlightedMatchWithPaths,
+    session_notes: Option<SharedString>,
 }
 
 impl Render for MatchTooltip {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         tooltip_container(cx, |div, _| {
-            self.highlighted_location.render_paths_children(div)
+            self.highlighted_location
+                .render_paths_children(div)
+                .when_some(self.session_notes.as_ref(), |div, notes| {
+                    div.child(
+                        Label::new(util::truncate_lines_and_trailoff(notes, 3))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                })
         })
     }
 }
@@ -719,7 +753,7 @@ mod tests {
             .unwrap()
     }
 
-    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+    pub(crate) fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
         cx.update(|cx| {
             let state = AppState::test(cx);
             language::init(cx);
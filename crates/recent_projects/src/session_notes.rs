@@ -0,0 +1,138 @@
+use editor::{Editor, EditorEvent};
+use gpui::{
+    DismissEvent, EventEmitter, FocusHandle, FocusableView, Subscription, View, ViewContext,
+    WeakView,
+};
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::{ModalView, ToggleSessionNotes, Workspace, MAX_SESSION_NOTES_CHARS};
+
+/// Edits the notes the user keeps about where they left off in a workspace.
+pub struct SessionNotes {
+    workspace: WeakView<Workspace>,
+    editor: View<Editor>,
+    exceeds_limit: bool,
+    _subscription: Subscription,
+}
+
+impl ModalView for SessionNotes {}
+
+impl SessionNotes {
+    pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &ToggleSessionNotes, cx| {
+            let notes = workspace.session_notes().unwrap_or_default().to_string();
+            let weak_workspace = cx.view().downgrade();
+            workspace.toggle_modal(cx, |cx| Self::new(weak_workspace, notes, cx));
+        });
+    }
+
+    fn new(workspace: WeakView<Workspace>, notes: String, cx: &mut ViewContext<Self>) -> Self {
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::auto_height(12, cx);
+            editor.set_placeholder_text("Where did you leave off?", cx);
+            editor.set_text(notes, cx);
+            editor
+        });
+        let subscription = cx.subscribe(&editor, |this, editor, event: &EditorEvent, cx| {
+            if let EditorEvent::BufferEdited = event {
+                let notes = editor.read(cx).text(cx);
+                this.exceeds_limit = notes.chars().count() > MAX_SESSION_NOTES_CHARS;
+                this.workspace
+                    .update(cx, |workspace, cx| workspace.set_session_notes(&notes, cx))
+                    .log_err();
+                cx.notify();
+            }
+        });
+        Self {
+            workspace,
+            editor,
+            exceeds_limit: false,
+            _subscription: subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<DismissEvent> for SessionNotes {}
+
+impl FocusableView for SessionNotes {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for SessionNotes {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("SessionNotes")
+            .on_action(cx.listener(Self::cancel))
+            .elevation_3(cx)
+            .w(rems(34.))
+            .p_2()
+            .gap_2()
+            .child(
+                Label::new("Session Notes")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(self.editor.clone())
+            .when(self.exceeds_limit, |this| {
+                this.child(
+                    Label::new(format!(
+                        "Notes are limited to {MAX_SESSION_NOTES_CHARS} characters, the rest won't be saved."
+                    ))
+                    .size(LabelSize::Small)
+                    .color(Color::Warning),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test;
+    use gpui::TestAppContext;
+    use project::Project;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_edit_session_notes(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        app_state.fs.as_fake().insert_tree("/root", json!({})).await;
+        let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        cx.dispatch_action(ToggleSessionNotes);
+        cx.simulate_input("Where I left off");
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.active_modal::<SessionNotes>(cx).is_some());
+            assert_eq!(workspace.session_notes(), Some("Where I left off"));
+        });
+
+        // Reopening the notes shows what was written before.
+        cx.dispatch_action(menu::Cancel);
+        cx.dispatch_action(ToggleSessionNotes);
+        let modal = workspace.update(cx, |workspace, cx| {
+            workspace.active_modal::<SessionNotes>(cx).unwrap()
+        });
+        modal.update(cx, |modal, cx| {
+            assert_eq!(modal.editor.read(cx).text(cx), "Where I left off");
+            assert!(!modal.exceeds_limit);
+            modal.editor.update(cx, |editor, cx| {
+                editor.set_text("a".repeat(MAX_SESSION_NOTES_CHARS + 1), cx)
+            });
+        });
+        cx.run_until_parked();
+        modal.update(cx, |modal, _| assert!(modal.exceeds_limit));
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(
+                workspace.session_notes().unwrap().len(),
+                MAX_SESSION_NOTES_CHARS
+            );
+        });
+    }
+}
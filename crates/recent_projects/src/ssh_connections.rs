@@ -535,6 +535,33 @@ pub fn connect_over_ssh(
     )
 }
 
+/// Connects a workspace to its SSH host again, showing the connection's progress in a modal.
+pub(crate) fn reconnect_over_ssh(
+    workspace: &mut Workspace,
+    unique_identifier: ConnectionIdentifier,
+    connection_options: SshConnectionOptions,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<Option<Model<SshRemoteClient>>>> {
+    let paths = workspace
+        .serialized_ssh_project()
+        .map(|ssh_project| ssh_project.paths.iter().map(PathBuf::from).collect())
+        .unwrap_or_default();
+    workspace.toggle_modal(cx, |cx| {
+        SshConnectionModal::new(&connection_options, paths, cx)
+    });
+    let Some(modal) = workspace.active_modal::<SshConnectionModal>(cx) else {
+        return Task::ready(Ok(None));
+    };
+
+    let ui = modal.read(cx).prompt.clone();
+    let connect = connect_over_ssh(unique_identifier, connection_options, ui, cx);
+    cx.spawn(|_, mut cx| async move {
+        let session = connect.await;
+        modal.update(&mut cx, |modal, cx| modal.finished(cx)).ok();
+        session
+    })
+}
+
 pub async fn open_ssh_project(
     connection_options: SshConnectionOptions,
     paths: Vec<PathBuf>,
@@ -57,6 +57,11 @@ impl Global for ActiveSettings {}
 
 pub fn init(cx: &mut AppContext) {
     cx.set_global(ActiveSettings::default());
+    workspace::register_split_view(
+        workspace::PROJECT_SEARCH_VIEW_KIND,
+        ProjectSearchView::new_search_in_split,
+        cx,
+    );
     cx.observe_new_views(|workspace: &mut Workspace, _cx| {
         register_workspace_action(workspace, move |search_bar, _: &Deploy, cx| {
             search_bar.focus_search(cx);
@@ -863,13 +868,25 @@ impl ProjectSearchView {
         Self::existing_or_new_search(workspace, None, &DeploySearch::find(), cx)
     }
 
-    fn existing_or_new_search(
+    /// Builds the search opened by [`workspace::NewSearchInSplit`], which the workspace adds
+    /// to the split.
+    fn new_search_in_split(
         workspace: &mut Workspace,
-        existing: Option<View<ProjectSearchView>>,
-        action: &workspace::DeploySearch,
         cx: &mut ViewContext<Workspace>,
-    ) {
-        let query = workspace.active_item(cx).and_then(|item| {
+    ) -> Option<Box<dyn ItemHandle>> {
+        let query = Self::query_suggestion(workspace, cx);
+        let search = Self::build_search_view(workspace, cx);
+        if let Some(query) = query {
+            search.update(cx, |search, cx| search.set_query(&query, cx));
+        }
+        Some(Box::new(search))
+    }
+
+    fn query_suggestion(
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Option<String> {
+        workspace.active_item(cx).and_then(|item| {
             if let Some(buffer_search_query) = buffer_search_query(workspace, item.as_ref(), cx) {
                 return Some(buffer_search_query);
             }
@@ -881,25 +898,39 @@ impl ProjectSearchView {
             } else {
                 Some(query)
             }
-        });
+        })
+    }
 
-        let search = if let Some(existing) = existing {
-            workspace.activate_item(&existing, true, true, cx);
-            existing
-        } else {
-            let settings = cx
-                .global::<ActiveSettings>()
-                .0
-                .get(&workspace.project().downgrade());
+    fn build_search_view(
+        workspace: &Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) -> View<ProjectSearchView> {
+        let settings = cx
+            .global::<ActiveSettings>()
+            .0
+            .get(&workspace.project().downgrade());
 
-            let settings = settings.cloned();
+        let settings = settings.cloned();
+
+        let weak_workspace = cx.view().downgrade();
 
-            let weak_workspace = cx.view().downgrade();
+        let model = cx.new_model(|cx| ProjectSearch::new(workspace.project().clone(), cx));
+        cx.new_view(|cx| ProjectSearchView::new(weak_workspace, model, cx, settings))
+    }
 
-            let model = cx.new_model(|cx| ProjectSearch::new(workspace.project().clone(), cx));
-            let view =
-                cx.new_view(|cx| ProjectSearchView::new(weak_workspace, model, cx, settings));
+    fn existing_or_new_search(
+        workspace: &mut Workspace,
+        existing: Option<View<ProjectSearchView>>,
+        action: &workspace::DeploySearch,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let query = Self::query_suggestion(workspace, cx);
 
+        let search = if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, cx);
+            existing
+        } else {
+            let view = Self::build_search_view(workspace, cx);
             workspace.add_item_to_active_pane(Box::new(view.clone()), None, true, cx);
             view
         };
@@ -1,5 +1,8 @@
 #[cfg(test)]
 mod tab_switcher_tests;
+mod window_switcher;
+#[cfg(test)]
+mod window_switcher_tests;
 
 use collections::HashMap;
 use editor::items::entry_git_aware_label_color;
@@ -21,6 +24,8 @@ use workspace::{
     ModalView, Pane, SaveIntent, Workspace,
 };
 
+pub use window_switcher::{WindowEntry, WindowSwitcher, WindowSwitcherDelegate};
+
 const PANEL_WIDTH_REMS: f32 = 28.;
 
 #[derive(PartialEq, Clone, Deserialize, Default)]
@@ -41,6 +46,7 @@ impl ModalView for TabSwitcher {}
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(TabSwitcher::register).detach();
+    cx.observe_new_views(WindowSwitcher::register).detach();
 }
 
 impl TabSwitcher {
@@ -0,0 +1,281 @@
+use std::sync::Arc;
+
+use call::ActiveCall;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    AnyWindowHandle, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Task,
+    View, ViewContext, WeakView, WindowHandle,
+};
+use picker::{Picker, PickerDelegate};
+use ui::{prelude::*, Indicator, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, SwitchWindow, Workspace};
+
+/// Lists the open workspace windows and activates the confirmed one.
+pub struct WindowSwitcher {
+    picker: View<Picker<WindowSwitcherDelegate>>,
+}
+
+impl ModalView for WindowSwitcher {}
+
+impl WindowSwitcher {
+    pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &SwitchWindow, cx| {
+            let windows = open_windows(workspace, cx);
+            workspace.toggle_modal(cx, |cx| Self::new(windows, cx));
+        });
+    }
+
+    fn new(windows: Vec<WindowEntry>, cx: &mut ViewContext<Self>) -> Self {
+        let delegate = WindowSwitcherDelegate {
+            window_switcher: cx.view().downgrade(),
+            windows,
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+
+    pub fn picker(&self) -> &View<Picker<WindowSwitcherDelegate>> {
+        &self.picker
+    }
+}
+
+impl EventEmitter<DismissEvent> for WindowSwitcher {}
+
+impl FocusableView for WindowSwitcher {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for WindowSwitcher {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("WindowSwitcher")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+/// An open workspace window, as listed by [`WindowSwitcher`].
+#[derive(Clone, PartialEq)]
+pub struct WindowEntry {
+    pub window: WindowHandle<Workspace>,
+    pub title: SharedString,
+    pub root_names: SharedString,
+    pub active_item: Option<SharedString>,
+    pub dirty_items: usize,
+    /// Whether the window's project is the one of the active call.
+    pub in_call: bool,
+    pub is_remote: bool,
+}
+
+impl WindowEntry {
+    fn new(window: WindowHandle<Workspace>, workspace: &Workspace, cx: &AppContext) -> Self {
+        let project = workspace.project();
+        let in_call = ActiveCall::try_global(cx)
+            .and_then(|call| call.read(cx).location()?.upgrade())
+            .map_or(false, |call_project| &call_project == project);
+        let project = project.read(cx);
+        Self {
+            window,
+            title: workspace.window_title(cx).into(),
+            root_names: project
+                .worktree_root_names(cx)
+                .collect::<Vec<_>>()
+                .join(", ")
+                .into(),
+            active_item: workspace
+                .active_item(cx)
+                .and_then(|item| item.tab_description(0, cx)),
+            dirty_items: workspace.items(cx).filter(|item| item.is_dirty(cx)).count(),
+            in_call,
+            is_remote: project.is_via_ssh() || project.is_via_collab(),
+        }
+    }
+}
+
+/// The windows of the workspace store, most recently used first when the platform knows it.
+/// Windows that were closed are skipped.
+fn open_windows(workspace: &Workspace, cx: &ViewContext<Workspace>) -> Vec<WindowEntry> {
+    let this_window = cx.window_handle();
+    let window_stack = cx.window_stack().unwrap_or_default();
+    let mut windows = workspace
+        .app_state()
+        .workspace_store
+        .read(cx)
+        .workspaces()
+        .iter()
+        .filter_map(|&window| {
+            // This window's workspace is being updated, so it can't be read through its handle.
+            if AnyWindowHandle::from(window) == this_window {
+                Some(WindowEntry::new(window, workspace, cx))
+            } else {
+                Some(WindowEntry::new(window, window.read(cx).ok()?, cx))
+            }
+        })
+        .collect::<Vec<_>>();
+    windows.sort_by_key(|entry| {
+        let window = AnyWindowHandle::from(entry.window);
+        (
+            window_stack
+                .iter()
+                .position(|stacked| *stacked == window)
+                .unwrap_or(usize::MAX),
+            window.window_id().as_u64(),
+        )
+    });
+    windows
+}
+
+pub struct WindowSwitcherDelegate {
+    window_switcher: WeakView<WindowSwitcher>,
+    windows: Vec<WindowEntry>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl WindowSwitcherDelegate {
+    /// The windows matching the current query, in the order they are listed.
+    pub fn matching_windows(&self) -> impl Iterator<Item = &WindowEntry> {
+        self.matches
+            .iter()
+            .map(|string_match| &self.windows[string_match.candidate_id])
+    }
+}
+
+impl PickerDelegate for WindowSwitcherDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Switch to window…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .windows
+            .iter()
+            .enumerate()
+            .map(|(id, window)| {
+                StringMatchCandidate::new(id, format!("{} {}", window.title, window.root_names))
+            })
+            .collect::<Vec<_>>();
+        let executor = cx.background_executor().clone();
+        cx.spawn(|picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.,
+                        positions: Vec::new(),
+                        string: candidate.string,
+                    })
+                    .collect()
+            } else {
+                let mut matches = match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    usize::MAX,
+                    &Default::default(),
+                    executor,
+                )
+                .await;
+                // Keep the most recently used windows first among equally good matches.
+                matches.sort_by(|a, b| {
+                    b.score
+                        .total_cmp(&a.score)
+                        .then(a.candidate_id.cmp(&b.candidate_id))
+                });
+                matches
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(window) = self.matching_windows().nth(self.selected_index) else {
+            return;
+        };
+        let window = window.window;
+        // Dismiss first, the activated window may be this one.
+        self.dismissed(cx);
+        if AnyWindowHandle::from(window) == cx.window_handle() {
+            cx.activate_window();
+        } else {
+            window.update(cx, |_, cx| cx.activate_window()).log_err();
+        }
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.window_switcher
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let window = &self.windows[self.matches.get(ix)?.candidate_id];
+        let mut details = vec![window.root_names.to_string()];
+        if let Some(active_item) = &window.active_item {
+            details.push(active_item.to_string());
+        }
+        if window.dirty_items > 0 {
+            details.push(format!("{} unsaved", window.dirty_items));
+        }
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    v_flex().child(Label::new(window.title.clone())).child(
+                        Label::new(details.join(" · "))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+                )
+                .end_slot(
+                    h_flex()
+                        .gap_1()
+                        .when(window.is_remote, |this| {
+                            this.child(
+                                Icon::new(IconName::Server)
+                                    .size(IconSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .when(window.in_call, |this| {
+                            this.child(Indicator::dot().color(Color::Success))
+                        }),
+                ),
+        )
+    }
+}
@@ -0,0 +1,151 @@
+use super::*;
+use editor::Editor;
+use gpui::{TestAppContext, WindowHandle};
+use project::{Project, ProjectPath};
+use serde_json::json;
+use std::path::Path;
+use workspace::{AppState, SwitchWindow, Workspace};
+
+#[gpui::test]
+async fn test_switch_window(cx: &mut TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/",
+            json!({
+                "alpha": { "a.txt": "" },
+                "beta": { "b.txt": "" },
+                "gamma": { "c.txt": "" },
+            }),
+        )
+        .await;
+
+    let window_1 = open_window("/alpha", &app_state, cx).await;
+    let window_2 = open_window("/beta", &app_state, cx).await;
+    let window_3 = open_window("/gamma", &app_state, cx).await;
+
+    // An unsaved file is open in the second window.
+    let editor = window_2
+        .update(cx, |workspace, cx| {
+            let worktree_id = workspace.project().read(cx).worktrees(cx).next().unwrap();
+            let project_path = ProjectPath {
+                worktree_id: worktree_id.read(cx).id(),
+                path: Arc::from(Path::new("b.txt")),
+            };
+            workspace.open_path(project_path, None, true, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    window_2
+        .update(cx, |_, cx| {
+            editor.update(cx, |editor, cx| editor.insert("b", cx))
+        })
+        .unwrap();
+
+    let picker = open_window_switcher(window_1, cx);
+    picker.update(cx, |picker, _| {
+        let windows = picker.delegate.matching_windows().collect::<Vec<_>>();
+        assert_eq!(
+            windows
+                .iter()
+                .map(|entry| (entry.window.window_id(), entry.root_names.as_ref()))
+                .collect::<Vec<_>>(),
+            [
+                (window_1.window_id(), "alpha"),
+                (window_2.window_id(), "beta"),
+                (window_3.window_id(), "gamma")
+            ]
+        );
+        assert_eq!(windows[0].title.as_ref(), "alpha");
+        assert_eq!(windows[0].dirty_items, 0);
+        assert_eq!(windows[1].title.as_ref(), "beta — b.txt");
+        assert_eq!(windows[1].active_item.as_deref(), Some("b.txt"));
+        assert_eq!(windows[1].dirty_items, 1);
+        assert!(!windows[1].in_call && !windows[1].is_remote);
+    });
+
+    // Typing filters the windows, and confirming activates the selected one.
+    cx.simulate_input(window_1.into(), "gam");
+    cx.run_until_parked();
+    picker.update(cx, |picker, _| {
+        let windows = picker
+            .delegate
+            .matching_windows()
+            .map(|entry| entry.window.window_id())
+            .collect::<Vec<_>>();
+        assert_eq!(windows, [window_3.window_id()]);
+    });
+    cx.dispatch_action(window_1.into(), menu::Confirm);
+    cx.run_until_parked();
+    assert_eq!(
+        cx.update(|cx| cx.active_window().map(|window| window.window_id())),
+        Some(window_3.window_id())
+    );
+    window_1
+        .update(cx, |workspace, cx| {
+            assert!(workspace.active_modal::<WindowSwitcher>(cx).is_none())
+        })
+        .unwrap();
+
+    // Closed windows aren't listed, even while their workspace is still around.
+    let _workspace_2 = window_2.root_view(cx).unwrap();
+    window_2.update(cx, |_, cx| cx.remove_window()).unwrap();
+    cx.run_until_parked();
+    let picker = open_window_switcher(window_3, cx);
+    picker.update(cx, |picker, _| {
+        let windows = picker
+            .delegate
+            .matching_windows()
+            .map(|entry| entry.window.window_id())
+            .collect::<Vec<_>>();
+        assert_eq!(windows, [window_1.window_id(), window_3.window_id()]);
+    });
+}
+
+fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+    cx.update(|cx| {
+        let state = AppState::test(cx);
+        theme::init(theme::LoadThemes::JustBase, cx);
+        language::init(cx);
+        super::init(cx);
+        editor::init(cx);
+        workspace::init_settings(cx);
+        Project::init_settings(cx);
+        state
+    })
+}
+
+async fn open_window(
+    root: &str,
+    app_state: &Arc<AppState>,
+    cx: &mut TestAppContext,
+) -> WindowHandle<Workspace> {
+    let project = Project::test(app_state.fs.clone(), [root.as_ref()], cx).await;
+    let window = cx.add_window(|cx| Workspace::new(None, project, app_state.clone(), cx));
+    cx.run_until_parked();
+    window
+}
+
+#[track_caller]
+fn open_window_switcher(
+    window: WindowHandle<Workspace>,
+    cx: &mut TestAppContext,
+) -> View<Picker<WindowSwitcherDelegate>> {
+    cx.dispatch_action(window.into(), SwitchWindow);
+    cx.run_until_parked();
+    window
+        .update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<WindowSwitcher>(cx)
+                .expect("window switcher is not open")
+                .read(cx)
+                .picker()
+                .clone()
+        })
+        .unwrap()
+}
@@ -23,7 +23,7 @@ use settings::Settings;
 use task::{RevealStrategy, Shell, SpawnInTerminal, TaskId};
 use terminal::{
     terminal_settings::{TerminalDockPosition, TerminalSettings},
-    Terminal,
+    TaskStatus, Terminal,
 };
 use ui::{
     prelude::*, ButtonCommon, Clickable, ContextMenu, FluentBuilder, PopoverMenu, Selectable,
@@ -663,6 +663,7 @@ impl TerminalPanel {
                 })?
                 .await?;
             let result = workspace.update(&mut cx, |workspace, cx| {
+                track_running_task(workspace, &terminal, cx);
                 let terminal_view = Box::new(cx.new_view(|cx| {
                     TerminalView::new(
                         terminal.clone(),
@@ -762,6 +763,11 @@ impl TerminalPanel {
                 .ok()?
                 .await
                 .log_err()?;
+            task_workspace
+                .update(&mut cx, |workspace, cx| {
+                    track_running_task(workspace, &new_terminal, cx)
+                })
+                .ok()?;
             terminal_to_replace
                 .update(&mut cx, |terminal_to_replace, cx| {
                     terminal_to_replace.set_terminal(new_terminal, cx);
@@ -965,6 +971,35 @@ async fn wait_for_terminals_tasks(
     let _: Vec<()> = join_all(pending_tasks).await;
 }
 
+/// Reports the task run by `terminal` to the workspace's running tasks, until it completes.
+fn track_running_task(
+    workspace: &mut Workspace,
+    terminal: &Model<Terminal>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(task) = terminal.read(cx).task() else {
+        return;
+    };
+    let owner = terminal.downgrade();
+    workspace.add_running_task(task.id.clone(), task.label.clone(), owner.clone().into());
+    let completed = terminal.read(cx).wait_for_completed_task(cx);
+    cx.spawn(|workspace, mut cx| async move {
+        completed.await;
+        workspace.update(&mut cx, |workspace, cx| {
+            let status = owner
+                .upgrade()
+                .and_then(|terminal| Some(terminal.read(cx).task()?.status));
+            match status {
+                Some(TaskStatus::Completed { success }) => {
+                    workspace.task_completed(&owner.into(), success, cx)
+                }
+                _ => workspace.task_cancelled(&owner.into()),
+            }
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
 fn add_paths_to_terminal(pane: &mut Pane, paths: &[PathBuf], cx: &mut ViewContext<'_, Pane>) {
     if let Some(terminal_view) = pane
         .active_item()
@@ -33,8 +33,8 @@ use workspace::{
     notifications::NotifyResultExt,
     register_serializable_item,
     searchable::{SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
-    CloseActiveItem, NewCenterTerminal, NewTerminal, OpenVisible, ToolbarItemLocation, Workspace,
-    WorkspaceId,
+    CloseActiveItem, NewCenterTerminal, NewTerminal, OpenVisible, ReservationKind,
+    ToolbarItemLocation, Workspace, WorkspaceId,
 };
 
 use anyhow::Context;
@@ -1172,6 +1172,10 @@ impl Item for TerminalView {
     fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
         f(*event)
     }
+
+    fn reservation_kind(&self, _: &AppContext) -> ReservationKind {
+        ReservationKind::Terminal
+    }
 }
 
 impl SerializableItem for TerminalView {
@@ -112,12 +112,10 @@ pub fn init(cx: &mut AppContext) {
             })
         });
 
-        workspace.register_action(|_, _: &OpenDefaultKeymap, cx| {
-            cx.emit(workspace::Event::OpenBundledFile {
-                text: settings::vim_keymap(),
-                title: "Default Vim Bindings",
-                language: "JSON",
-            });
+        workspace.register_action(|workspace, _: &OpenDefaultKeymap, cx| {
+            workspace
+                .open_bundled_file(settings::vim_keymap(), "Default Vim Bindings", "JSON", cx)
+                .detach_and_log_err(cx);
         });
 
         workspace.register_action(|workspace, _: &ResetPaneSizes, cx| {
@@ -0,0 +1,224 @@
+use std::sync::atomic::Ordering;
+
+use gpui::{View, ViewContext, WeakView};
+
+use crate::{
+    item::{ItemHandle, WeakItemHandle},
+    pane::Pane,
+    ActivateNextItemGlobal, ActivatePreviousItemGlobal, Workspace,
+};
+
+/// An item that was activated in one of the workspace's panes.
+pub(crate) struct ItemActivation {
+    pane: WeakView<Pane>,
+    item: Box<dyn WeakItemHandle>,
+    timestamp: usize,
+}
+
+impl Workspace {
+    /// Returns the items activated in any of the panes, most recently activated first, along
+    /// with the panes they were activated in.
+    pub fn activation_history(
+        &mut self,
+        cx: &mut ViewContext<Self>,
+    ) -> Vec<(View<Pane>, Box<dyn ItemHandle>)> {
+        let mut history = Vec::with_capacity(self.item_activations.len());
+        self.item_activations.retain(|activation| {
+            let Some((pane, item)) = activation.pane.upgrade().zip(activation.item.upgrade())
+            else {
+                return false;
+            };
+            // Items may have been closed or moved to another pane since their activation.
+            if pane.read(cx).index_for_item(item.as_ref()).is_none() {
+                return false;
+            }
+            history.push((activation.timestamp, pane, item));
+            true
+        });
+        history.sort_by_key(|(timestamp, _, _)| std::cmp::Reverse(*timestamp));
+        history
+            .into_iter()
+            .map(|(_, pane, item)| (pane, item))
+            .collect()
+    }
+
+    pub(crate) fn record_item_activation(&mut self, pane: &View<Pane>, cx: &mut ViewContext<Self>) {
+        let Some(item) = pane.read(cx).active_item() else {
+            return;
+        };
+        let item_id = item.item_id();
+        if let Some(cursor) = self.item_activation_cursor.take() {
+            // Activating the item that is cycled to doesn't end the cycling.
+            if cursor == item_id {
+                self.item_activation_cursor = Some(cursor);
+                return;
+            }
+            // Otherwise the cycling ended on that item, so it counts as used before this one.
+            let timestamp = self.next_item_activation_timestamp();
+            if let Some(activation) = self
+                .item_activations
+                .iter_mut()
+                .find(|activation| activation.item.id() == cursor)
+            {
+                activation.timestamp = timestamp;
+            }
+        }
+
+        self.item_activations
+            .retain(|activation| activation.item.id() != item_id);
+        let timestamp = self.next_item_activation_timestamp();
+        self.item_activations.push(ItemActivation {
+            pane: pane.downgrade(),
+            item: item.downgrade_item(),
+            timestamp,
+        });
+    }
+
+    fn next_item_activation_timestamp(&self) -> usize {
+        self.pane_history_timestamp.fetch_add(1, Ordering::SeqCst)
+    }
+
+    pub(crate) fn activate_previous_item_global(
+        &mut self,
+        _: &ActivatePreviousItemGlobal,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.cycle_activation_history(1, cx);
+    }
+
+    pub(crate) fn activate_next_item_global(
+        &mut self,
+        _: &ActivateNextItemGlobal,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.cycle_activation_history(-1, cx);
+    }
+
+    /// Activates the item `offset` entries older than the one cycled to last, or than the
+    /// active item if not cycling, and focuses its pane.
+    fn cycle_activation_history(&mut self, offset: isize, cx: &mut ViewContext<Self>) {
+        let history = self.activation_history(cx);
+        if history.len() < 2 {
+            return;
+        }
+        let current_item_id = self.item_activation_cursor.or_else(|| {
+            self.active_pane
+                .read(cx)
+                .active_item()
+                .map(|item| item.item_id())
+        });
+        let current_ix = current_item_id
+            .and_then(|item_id| {
+                history
+                    .iter()
+                    .position(|(_, item)| item.item_id() == item_id)
+            })
+            .unwrap_or(0);
+        let ix = (current_ix as isize + offset).rem_euclid(history.len() as isize) as usize;
+        let (pane, item) = &history[ix];
+        self.item_activation_cursor = Some(item.item_id());
+        self.activate_item_in_pane(pane, item.as_ref(), cx);
+    }
+
+    fn activate_item_in_pane(
+        &mut self,
+        pane: &View<Pane>,
+        item: &dyn ItemHandle,
+        cx: &mut ViewContext<Self>,
+    ) {
+        pane.update(cx, |pane, cx| {
+            if let Some(index) = pane.index_for_item(item) {
+                pane.activate_item(index, true, true, cx);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{item::test::TestItem, tests::init_test, SplitDirection};
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_cycle_activation_history_across_panes(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let item_1 = cx.new_view(|cx| TestItem::new(cx).with_label("1"));
+        let item_2 = cx.new_view(|cx| TestItem::new(cx).with_label("2"));
+        let item_3 = cx.new_view(|cx| TestItem::new(cx).with_label("3"));
+        let left_pane = workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item_1.clone()), None, true, cx);
+            workspace.add_item_to_active_pane(Box::new(item_2.clone()), None, true, cx);
+            workspace.active_pane().clone()
+        });
+        cx.run_until_parked();
+        let right_pane = workspace.update(cx, |workspace, cx| {
+            let right_pane = workspace.split_pane(left_pane.clone(), SplitDirection::Right, cx);
+            right_pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(item_3.clone()), true, true, None, cx)
+            });
+            right_pane
+        });
+        cx.run_until_parked();
+
+        let active_item_id = |cx: &mut gpui::VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                let pane = workspace.active_pane().clone();
+                assert!(pane.focus_handle(cx).contains_focused(cx));
+                (pane, workspace.active_item(cx).unwrap().item_id())
+            })
+        };
+        let history = workspace.update(cx, |workspace, cx| {
+            workspace
+                .activation_history(cx)
+                .into_iter()
+                .map(|(_, item)| item.item_id())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(
+            history,
+            [item_3.item_id(), item_2.item_id(), item_1.item_id()]
+        );
+
+        // A single action switches to the last used item, in the other pane, and back.
+        cx.dispatch_action(ActivatePreviousItemGlobal);
+        cx.run_until_parked();
+        assert_eq!(active_item_id(cx), (left_pane.clone(), item_2.item_id()));
+        cx.dispatch_action(ActivateNextItemGlobal);
+        cx.run_until_parked();
+        assert_eq!(active_item_id(cx), (right_pane.clone(), item_3.item_id()));
+
+        // Repeating the action cycles further back in the history.
+        cx.dispatch_action(ActivatePreviousItemGlobal);
+        cx.dispatch_action(ActivatePreviousItemGlobal);
+        cx.run_until_parked();
+        assert_eq!(active_item_id(cx), (left_pane.clone(), item_1.item_id()));
+
+        // The item cycling stopped at becomes the last used one once another is activated,
+        // and closed items are dropped from the history.
+        left_pane.update(cx, |pane, cx| {
+            pane.close_item_by_id(item_2.item_id(), crate::SaveIntent::Skip, cx)
+                .detach()
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            workspace.activate_item(&item_3, true, true, cx);
+        });
+        cx.run_until_parked();
+        let history = workspace.update(cx, |workspace, cx| {
+            workspace
+                .activation_history(cx)
+                .into_iter()
+                .map(|(_, item)| item.item_id())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(history, [item_3.item_id(), item_1.item_id()]);
+    }
+}
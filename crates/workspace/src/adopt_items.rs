@@ -0,0 +1,144 @@
+use gpui::{View, ViewContext};
+
+use crate::{pane::Pane, Workspace};
+
+impl Workspace {
+    /// Moves the items of `pane` into `target`, or into the active pane, keeping them open with
+    /// their state, e.g. unsaved changes, intact. Used before removing a pane that still has
+    /// items, which would otherwise be dropped without going through the close flow.
+    pub fn adopt_items_from(
+        &mut self,
+        pane: &View<Pane>,
+        target: Option<View<Pane>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(target) = target.or_else(|| self.adoption_target(pane)) else {
+            return;
+        };
+        if &target == pane {
+            return;
+        }
+
+        let items = pane
+            .read(cx)
+            .items()
+            .map(|item| item.boxed_clone())
+            .collect::<Vec<_>>();
+        let active_item_id = pane.read(cx).active_item().map(|item| item.item_id());
+        if items.is_empty() {
+            return;
+        }
+        for item in &items {
+            pane.update(cx, |pane, cx| {
+                pane.remove_item(item.item_id(), false, false, cx)
+            });
+        }
+        target.update(cx, |target, cx| {
+            for item in items {
+                target.add_item(item, false, false, None, cx);
+            }
+            let active_ix =
+                active_item_id.and_then(|id| target.items().position(|item| item.item_id() == id));
+            if let Some(ix) = active_ix {
+                target.activate_item(ix, false, false, cx);
+            }
+        });
+    }
+
+    /// Removes a pane, moving the items still in it into `focus_on`, or into the active pane,
+    /// rather than dropping them with the pane.
+    pub fn remove_pane_adopting_items(
+        &mut self,
+        pane: View<Pane>,
+        focus_on: Option<View<Pane>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.center.panes().len() > 1 && self.center.panes().contains(&&pane) {
+            self.adopt_items_from(&pane, focus_on.clone(), cx);
+        }
+        self.remove_pane(pane, focus_on, cx);
+    }
+
+    /// The pane that adopts the items of `pane` when none is given: the active pane, or else
+    /// the last pane opened.
+    fn adoption_target(&self, pane: &View<Pane>) -> Option<View<Pane>> {
+        if &self.active_pane != pane {
+            return Some(self.active_pane.clone());
+        }
+        self.panes
+            .iter()
+            .rev()
+            .find(|other| *other != pane)
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{item::test::TestItem, tests::init_test, SplitDirection};
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_adopt_items_from_removed_pane(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let (left, right, dirty, clean) = workspace.update(cx, |workspace, cx| {
+            let left = workspace.active_pane().clone();
+            let right = workspace.split_pane(left.clone(), SplitDirection::Right, cx);
+            let dirty = cx.new_view(|cx| TestItem::new(cx).with_dirty(true));
+            let clean = cx.new_view(TestItem::new);
+            workspace.add_item(right.clone(), Box::new(dirty.clone()), None, true, true, cx);
+            workspace.add_item(right.clone(), Box::new(clean.clone()), None, true, true, cx);
+            right.update(cx, |pane, cx| pane.activate_item(0, false, false, cx));
+            (left, right, dirty, clean)
+        });
+        cx.run_until_parked();
+
+        // Removing a pane with items moves them into the remaining pane, without prompting.
+        workspace.update(cx, |workspace, cx| {
+            workspace.remove_pane_adopting_items(right.clone(), None, cx);
+            assert_eq!(workspace.panes(), [left.clone()]);
+        });
+        cx.run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        workspace.update(cx, |workspace, cx| {
+            let item_ids = left
+                .read(cx)
+                .items()
+                .map(|item| item.item_id())
+                .collect::<Vec<_>>();
+            assert_eq!(item_ids, [dirty.entity_id(), clean.entity_id()]);
+            assert_eq!(
+                left.read(cx).active_item().map(|item| item.item_id()),
+                Some(dirty.entity_id())
+            );
+            assert!(dirty.read(cx).is_dirty);
+            assert_eq!(
+                workspace
+                    .panes_by_item
+                    .get(&dirty.entity_id())
+                    .and_then(|pane| pane.upgrade()),
+                Some(left.clone())
+            );
+        });
+
+        // Items can be adopted by a given pane.
+        let bottom = workspace.update(cx, |workspace, cx| {
+            let bottom = workspace.split_pane(left.clone(), SplitDirection::Down, cx);
+            workspace.adopt_items_from(&left, Some(bottom.clone()), cx);
+            bottom
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |_, cx| {
+            assert_eq!(left.read(cx).items_len(), 0);
+            assert_eq!(bottom.read(cx).items_len(), 2);
+            assert!(dirty.read(cx).is_dirty);
+        });
+    }
+}
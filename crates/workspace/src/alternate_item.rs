@@ -0,0 +1,215 @@
+use gpui::{View, ViewContext, WeakView};
+use project::ProjectPath;
+use settings::Settings;
+
+use crate::{
+    item::{ItemHandle, WeakItemHandle},
+    pane::Pane,
+    workspace_settings::{AlternateItemScope, WorkspaceSettings},
+    ActivateAlternateItem, Workspace,
+};
+
+/// An item that was the active item of the workspace.
+pub(crate) struct TrackedItem {
+    pane: WeakView<Pane>,
+    item: Box<dyn WeakItemHandle>,
+    /// Where to reopen the item from once it's closed.
+    project_path: Option<ProjectPath>,
+}
+
+/// The active item of the workspace and the one active before it, whichever panes they are
+/// in, for [`ActivateAlternateItem`] with [`AlternateItemScope::Workspace`].
+#[derive(Default)]
+pub(crate) struct AlternateItems {
+    active: Option<TrackedItem>,
+    alternate: Option<TrackedItem>,
+}
+
+impl Workspace {
+    /// Records the active item of `pane` as the active item of the workspace, the previous one
+    /// becoming the alternate item.
+    pub(crate) fn track_alternate_item(&mut self, pane: &View<Pane>, cx: &mut ViewContext<Self>) {
+        // Panes outside of the center, e.g. in the terminal panel, aren't tracked.
+        if pane != self.active_pane() || !self.panes.contains(pane) || self.is_mirror_pane(pane) {
+            return;
+        }
+        let Some(item) = pane.read(cx).active_item() else {
+            return;
+        };
+        let active = &mut self.alternate_items.active;
+        if let Some(active) = active.as_mut() {
+            if active.item.id() == item.item_id() {
+                active.pane = pane.downgrade();
+                return;
+            }
+        }
+        let previous = active.replace(TrackedItem {
+            pane: pane.downgrade(),
+            item: item.downgrade_item(),
+            project_path: item.project_path(cx),
+        });
+        if previous.is_some() {
+            self.alternate_items.alternate = previous;
+        }
+    }
+
+    /// The item that was active before the active one, if it's still open. With
+    /// [`AlternateItemScope::Pane`], only the items of the active pane are considered.
+    pub fn alternate_item(&self, cx: &mut ViewContext<Self>) -> Option<Box<dyn ItemHandle>> {
+        let active_item_id = self.active_item(cx).map(|item| item.item_id());
+        let item = match WorkspaceSettings::get_global(cx).alternate_item_scope {
+            AlternateItemScope::Pane => self.active_pane.read(cx).alternate_file_item()?,
+            AlternateItemScope::Workspace => {
+                self.alternate_items.alternate.as_ref()?.item.upgrade()?
+            }
+        };
+        (Some(item.item_id()) != active_item_id).then_some(item)
+    }
+
+    pub(crate) fn activate_alternate_item(
+        &mut self,
+        _: &ActivateAlternateItem,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match WorkspaceSettings::get_global(cx).alternate_item_scope {
+            AlternateItemScope::Pane => {
+                self.active_pane
+                    .update(cx, |pane, cx| pane.activate_alternate_file(cx));
+            }
+            AlternateItemScope::Workspace => {
+                let Some(alternate) = self.alternate_items.alternate.as_ref() else {
+                    return;
+                };
+                let pane = alternate
+                    .pane
+                    .upgrade()
+                    .filter(|pane| self.panes.contains(pane))
+                    .unwrap_or_else(|| self.active_pane.clone());
+                if let Some(item) = alternate.item.upgrade() {
+                    if !self.activate_item(item.as_ref(), true, true, cx) {
+                        pane.update(cx, |pane, cx| pane.add_item(item, true, true, None, cx));
+                    }
+                } else if let Some(project_path) = alternate.project_path.clone() {
+                    // The item was closed, so it's opened again where it was.
+                    self.open_path(project_path, Some(pane.downgrade()), true, cx)
+                        .detach_and_log_err(cx);
+                } else {
+                    self.alternate_items.alternate = None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{item::test::TestItem, register_project_item, tests::init_test, SplitDirection};
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{Project, WorktreeId};
+    use settings::SettingsStore;
+
+    fn project_path(path: &str) -> ProjectPath {
+        (WorktreeId::from_usize(0), path).into()
+    }
+
+    fn set_alternate_item_scope(scope: AlternateItemScope, cx: &mut VisualTestContext) {
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.alternate_item_scope = Some(scope);
+                });
+            });
+        });
+    }
+
+    #[gpui::test]
+    async fn test_alternate_item_across_panes(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| register_project_item::<TestItem>(cx));
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let open = |path: &'static str, pane: &View<Pane>, cx: &mut VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                workspace.open_path(project_path(path), Some(pane.downgrade()), true, cx)
+            })
+        };
+        let alternate_path = |cx: &mut VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                workspace
+                    .alternate_item(cx)
+                    .and_then(|item| item.project_path(cx))
+            })
+        };
+        let active_path = |cx: &mut VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                let item = workspace.active_item(cx).unwrap();
+                (
+                    workspace.active_pane().clone(),
+                    item.project_path(cx).unwrap(),
+                )
+            })
+        };
+
+        // Files 1 and 2 in the left pane, then file 3 in the right one.
+        let left_pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+        let item_1 = open("1.txt", &left_pane, cx).await.unwrap();
+        let item_2 = open("2.txt", &left_pane, cx).await.unwrap();
+        let right_pane = workspace.update(cx, |workspace, cx| {
+            workspace.split_pane(left_pane.clone(), SplitDirection::Right, cx)
+        });
+        open("3.txt", &right_pane, cx).await.unwrap();
+        cx.run_until_parked();
+
+        // By default, the alternate item is the one of the active pane, and the right pane has
+        // none.
+        assert_eq!(alternate_path(cx), None);
+        cx.dispatch_action(ActivateAlternateItem);
+        cx.run_until_parked();
+        assert_eq!(active_path(cx), (right_pane.clone(), project_path("3.txt")));
+
+        // Across panes, the alternate item is the one active before, in the other pane, and
+        // activating it switches back and forth.
+        set_alternate_item_scope(AlternateItemScope::Workspace, cx);
+        assert_eq!(alternate_path(cx), Some(project_path("2.txt")));
+        cx.dispatch_action(ActivateAlternateItem);
+        cx.run_until_parked();
+        assert_eq!(active_path(cx), (left_pane.clone(), project_path("2.txt")));
+        assert_eq!(alternate_path(cx), Some(project_path("3.txt")));
+        cx.dispatch_action(ActivateAlternateItem);
+        cx.run_until_parked();
+        assert_eq!(active_path(cx), (right_pane.clone(), project_path("3.txt")));
+
+        // Back to the pane-local behavior, the left pane alternates between its own items.
+        set_alternate_item_scope(AlternateItemScope::Pane, cx);
+        workspace.update(cx, |workspace, cx| {
+            workspace.activate_item(item_2.as_ref(), true, true, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(alternate_path(cx), Some(project_path("1.txt")));
+        cx.dispatch_action(ActivateAlternateItem);
+        cx.run_until_parked();
+        assert_eq!(active_path(cx), (left_pane.clone(), project_path("1.txt")));
+
+        // A closed alternate item is reopened by path, in the pane it was in.
+        set_alternate_item_scope(AlternateItemScope::Workspace, cx);
+        assert_eq!(alternate_path(cx), Some(project_path("2.txt")));
+        let item_2_id = item_2.item_id();
+        drop((item_1, item_2));
+        left_pane
+            .update(cx, |pane, cx| {
+                pane.close_item_by_id(item_2_id, crate::SaveIntent::Skip, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(alternate_path(cx), None);
+        cx.dispatch_action(ActivateAlternateItem);
+        cx.run_until_parked();
+        assert_eq!(active_path(cx), (left_pane.clone(), project_path("2.txt")));
+        left_pane.read_with(cx, |pane, _| assert_eq!(pane.items_len(), 2));
+    }
+}
@@ -0,0 +1,326 @@
+use std::path::{Path, PathBuf};
+
+use gpui::{AppContext, EntityId, View, ViewContext};
+
+use crate::{
+    item::ItemHandle, pane::Pane, persistence::DB, CycleBookmarkedItems, ToggleItemBookmark,
+    Workspace,
+};
+
+/// How many items can be bookmarked in a workspace. Bookmarking another one drops the oldest
+/// bookmark.
+pub const MAX_ITEM_BOOKMARKS: usize = 20;
+
+/// An item marked with [`ToggleItemBookmark`], to get back to it with [`CycleBookmarkedItems`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ItemBookmark {
+    item_id: Option<EntityId>,
+    abs_path: Option<PathBuf>,
+}
+
+impl ItemBookmark {
+    /// The id of the bookmarked item, unless it hasn't been opened since the bookmark was
+    /// restored.
+    pub fn item_id(&self) -> Option<EntityId> {
+        self.item_id
+    }
+
+    /// The path of the file shown by the bookmarked item, from which it is reopened once it
+    /// has been closed.
+    pub fn abs_path(&self) -> Option<&Path> {
+        self.abs_path.as_deref()
+    }
+
+    fn matches(&self, item_id: EntityId, abs_path: Option<&Path>) -> bool {
+        self.item_id == Some(item_id) || (abs_path.is_some() && self.abs_path() == abs_path)
+    }
+}
+
+impl Workspace {
+    /// The bookmarked items, in the order they were bookmarked.
+    pub fn bookmarks(&self) -> &[ItemBookmark] {
+        &self.item_bookmarks
+    }
+
+    pub fn is_item_bookmarked(&self, item: &dyn ItemHandle, cx: &AppContext) -> bool {
+        let abs_path = self.item_abs_path(item, cx);
+        self.item_bookmarks
+            .iter()
+            .any(|bookmark| bookmark.matches(item.item_id(), abs_path.as_deref()))
+    }
+
+    pub(crate) fn toggle_item_bookmark(
+        &mut self,
+        _: &ToggleItemBookmark,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(item) = self.active_item(cx) else {
+            return;
+        };
+        let abs_path = self.item_abs_path(item.as_ref(), cx);
+        if let Some(ix) = self
+            .item_bookmarks
+            .iter()
+            .position(|bookmark| bookmark.matches(item.item_id(), abs_path.as_deref()))
+        {
+            self.item_bookmarks.remove(ix);
+        } else {
+            if self.item_bookmarks.len() >= MAX_ITEM_BOOKMARKS {
+                self.item_bookmarks.remove(0);
+            }
+            self.item_bookmarks.push(ItemBookmark {
+                item_id: Some(item.item_id()),
+                abs_path,
+            });
+        }
+        self.serialize_item_bookmarks(cx);
+        cx.notify();
+    }
+
+    /// Activates the bookmarked item after the active one, reopening it if it has been closed.
+    pub(crate) fn cycle_bookmarked_items(
+        &mut self,
+        _: &CycleBookmarkedItems,
+        cx: &mut ViewContext<Self>,
+    ) {
+        // Bookmarks of closed items that can't be reopened are of no use anymore.
+        let bookmark_count = self.item_bookmarks.len();
+        let open_item_ids = self
+            .items(cx)
+            .map(|item| item.item_id())
+            .collect::<Vec<_>>();
+        self.item_bookmarks.retain(|bookmark| {
+            bookmark.abs_path.is_some()
+                || bookmark
+                    .item_id
+                    .map_or(false, |item_id| open_item_ids.contains(&item_id))
+        });
+        if self.item_bookmarks.len() != bookmark_count {
+            self.serialize_item_bookmarks(cx);
+        }
+        if self.item_bookmarks.is_empty() {
+            return;
+        }
+
+        let next_ix = self
+            .active_item(cx)
+            .and_then(|item| {
+                let abs_path = self.item_abs_path(item.as_ref(), cx);
+                self.item_bookmarks
+                    .iter()
+                    .position(|bookmark| bookmark.matches(item.item_id(), abs_path.as_deref()))
+            })
+            .map_or(0, |ix| (ix + 1) % self.item_bookmarks.len());
+        let bookmark = self.item_bookmarks[next_ix].clone();
+        if let Some((pane, item)) = bookmark
+            .item_id
+            .and_then(|item_id| self.pane_and_item_for_id(item_id, cx))
+        {
+            pane.update(cx, |pane, cx| {
+                if let Some(index) = pane.index_for_item(item.as_ref()) {
+                    pane.activate_item(index, true, true, cx);
+                }
+            });
+            return;
+        }
+
+        let Some(abs_path) = bookmark.abs_path else {
+            return;
+        };
+        let open = self.open_abs_path(abs_path.clone(), true, cx);
+        cx.spawn(|this, mut cx| async move {
+            let item = open.await?;
+            this.update(&mut cx, |this, _| {
+                if let Some(bookmark) = this
+                    .item_bookmarks
+                    .iter_mut()
+                    .find(|bookmark| bookmark.abs_path.as_ref() == Some(&abs_path))
+                {
+                    bookmark.item_id = Some(item.item_id());
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Restores the bookmarks stored for the workspace, linking them to the items that are
+    /// already open.
+    pub(crate) fn restore_item_bookmarks(
+        &mut self,
+        abs_paths: Vec<PathBuf>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let open_items = self
+            .items(cx)
+            .filter_map(|item| Some((self.item_abs_path(item.as_ref(), cx)?, item.item_id())))
+            .collect::<Vec<_>>();
+        self.item_bookmarks = abs_paths
+            .into_iter()
+            .take(MAX_ITEM_BOOKMARKS)
+            .map(|abs_path| ItemBookmark {
+                item_id: open_items
+                    .iter()
+                    .find(|(item_path, _)| item_path == &abs_path)
+                    .map(|(_, item_id)| *item_id),
+                abs_path: Some(abs_path),
+            })
+            .collect();
+        cx.notify();
+    }
+
+    fn serialize_item_bookmarks(&self, cx: &mut ViewContext<Self>) {
+        let Some(workspace_id) = self.database_id() else {
+            return;
+        };
+        let abs_paths = self
+            .item_bookmarks
+            .iter()
+            .filter_map(|bookmark| bookmark.abs_path.clone())
+            .collect();
+        cx.background_executor()
+            .spawn(DB.set_item_bookmarks(workspace_id, abs_paths))
+            .detach_and_log_err(cx);
+    }
+
+    fn item_abs_path(&self, item: &dyn ItemHandle, cx: &AppContext) -> Option<PathBuf> {
+        let project_path = item.project_path(cx)?;
+        self.project.read(cx).absolute_path(&project_path, cx)
+    }
+
+    fn pane_and_item_for_id(
+        &self,
+        item_id: EntityId,
+        cx: &AppContext,
+    ) -> Option<(View<Pane>, Box<dyn ItemHandle>)> {
+        self.panes.iter().find_map(|pane| {
+            let item = pane
+                .read(cx)
+                .items()
+                .find(|item| item.item_id() == item_id)?;
+            Some((pane.clone(), item.boxed_clone()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{item::test::TestItem, tests::init_test, SplitDirection};
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_toggle_and_cycle_bookmarks(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let items = (0..4)
+            .map(|_| cx.new_view(TestItem::new))
+            .collect::<Vec<_>>();
+        let left_pane = workspace.update(cx, |workspace, cx| {
+            for item in &items[..3] {
+                workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, cx);
+            }
+            workspace.active_pane().clone()
+        });
+        let right_pane = workspace.update(cx, |workspace, cx| {
+            let right_pane = workspace.split_pane(left_pane.clone(), SplitDirection::Right, cx);
+            right_pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(items[3].clone()), true, true, None, cx)
+            });
+            right_pane
+        });
+        cx.run_until_parked();
+
+        // Bookmark the last item first, then the first one.
+        cx.dispatch_action(ToggleItemBookmark);
+        workspace.update(cx, |workspace, cx| {
+            workspace.activate_item(&items[0], true, true, cx);
+        });
+        cx.dispatch_action(ToggleItemBookmark);
+        let active_item_id = |cx: &mut gpui::VisualTestContext| {
+            cx.run_until_parked();
+            workspace.update(cx, |workspace, cx| {
+                workspace.active_item(cx).unwrap().item_id()
+            })
+        };
+        workspace.update(cx, |workspace, cx| {
+            let bookmarked_ids = workspace
+                .bookmarks()
+                .iter()
+                .map(|bookmark| bookmark.item_id().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(bookmarked_ids, [items[3].item_id(), items[0].item_id()]);
+            assert!(workspace.is_item_bookmarked(&items[0], cx));
+            assert!(!workspace.is_item_bookmarked(&items[1], cx));
+        });
+
+        // Cycling follows the bookmark order, across panes, and wraps around.
+        cx.dispatch_action(CycleBookmarkedItems);
+        assert_eq!(active_item_id(cx), items[3].item_id());
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.active_pane(), &right_pane)
+        });
+        cx.dispatch_action(CycleBookmarkedItems);
+        assert_eq!(active_item_id(cx), items[0].item_id());
+        cx.dispatch_action(CycleBookmarkedItems);
+        assert_eq!(active_item_id(cx), items[3].item_id());
+
+        // From an item that isn't bookmarked, cycling starts over.
+        workspace.update(cx, |workspace, cx| {
+            workspace.activate_item(&items[1], true, true, cx);
+        });
+        cx.dispatch_action(CycleBookmarkedItems);
+        assert_eq!(active_item_id(cx), items[3].item_id());
+
+        // Toggling again removes the bookmark.
+        cx.dispatch_action(ToggleItemBookmark);
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.bookmarks().len(), 1);
+            assert!(!workspace.is_item_bookmarked(&items[3], cx));
+        });
+
+        // Closed items that can't be reopened are dropped from the bookmarks.
+        left_pane
+            .update(cx, |pane, cx| {
+                pane.close_item_by_id(items[0].item_id(), crate::SaveIntent::Skip, cx)
+            })
+            .await
+            .unwrap();
+        cx.dispatch_action(CycleBookmarkedItems);
+        workspace.update(cx, |workspace, _| assert!(workspace.bookmarks().is_empty()));
+    }
+
+    #[gpui::test]
+    async fn test_bookmarks_are_capped(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let mut item_ids = Vec::new();
+        for _ in 0..MAX_ITEM_BOOKMARKS + 1 {
+            let item = cx.new_view(TestItem::new);
+            item_ids.push(item.item_id());
+            workspace.update(cx, |workspace, cx| {
+                workspace.add_item_to_active_pane(Box::new(item), None, true, cx)
+            });
+            cx.dispatch_action(ToggleItemBookmark);
+        }
+
+        // The oldest bookmark makes room for the newest one.
+        workspace.update(cx, |workspace, _| {
+            let bookmarked_ids = workspace
+                .bookmarks()
+                .iter()
+                .map(|bookmark| bookmark.item_id().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(bookmarked_ids, &item_ids[1..]);
+        });
+    }
+}
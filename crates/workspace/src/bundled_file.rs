@@ -0,0 +1,172 @@
+use std::borrow::Cow;
+
+use anyhow::Result;
+use gpui::{
+    AppContext, EventEmitter, FocusHandle, FocusableView, SharedString, Task, ViewContext,
+    VisualContext,
+};
+use language::Capability;
+use ui::prelude::*;
+
+use crate::{
+    item::{Item, ItemHandle},
+    scratch::GlobalScratchItemBuilder,
+    Event, Workspace,
+};
+
+impl Workspace {
+    /// Opens a file bundled with the app, such as the default settings, as a read-only buffer
+    /// titled in the breadcrumbs, in the active pane. Like scratch items, it is never serialized nor prompted for. Without
+    /// a registered [`crate::ScratchItemBuilder`], or when the project is disconnected, its
+    /// text is shown in a plain item instead.
+    ///
+    /// [`Event::OpenBundledFile`] is still emitted, for the subscribers that relied on it.
+    pub fn open_bundled_file(
+        &mut self,
+        text: Cow<'static, str>,
+        title: &'static str,
+        language: &'static str,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<Box<dyn ItemHandle>>> {
+        cx.emit(Event::OpenBundledFile {
+            text: text.clone(),
+            title,
+            language,
+        });
+        if cx.has_global::<GlobalScratchItemBuilder>() && !self.project.read(cx).is_disconnected(cx)
+        {
+            return self.open_scratch_buffer(
+                text,
+                title,
+                Some(title.to_string()),
+                language,
+                Capability::ReadOnly,
+                cx,
+            );
+        }
+
+        let item: Box<dyn ItemHandle> =
+            Box::new(cx.new_view(|cx| BundledFile::new(text, title, cx)));
+        self.add_item_to_active_pane(item.boxed_clone(), None, true, cx);
+        Task::ready(Ok(item))
+    }
+}
+
+/// Shows the text of a bundled file when it can't be opened in a buffer.
+struct BundledFile {
+    text: Cow<'static, str>,
+    title: &'static str,
+    focus_handle: FocusHandle,
+}
+
+impl BundledFile {
+    fn new(text: Cow<'static, str>, title: &'static str, cx: &mut ViewContext<Self>) -> Self {
+        Self {
+            text,
+            title,
+            focus_handle: cx.focus_handle(),
+        }
+    }
+}
+
+impl EventEmitter<()> for BundledFile {}
+
+impl FocusableView for BundledFile {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for BundledFile {
+    type Event = ();
+
+    fn tab_content_text(&self, _: &WindowContext) -> Option<SharedString> {
+        Some(self.title.into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn is_ephemeral(&self, _: &AppContext) -> bool {
+        true
+    }
+}
+
+impl Render for BundledFile {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div()
+            .id("bundled-file")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_2()
+            .overflow_y_scroll()
+            .bg(cx.theme().colors().editor_background)
+            .font_buffer(cx)
+            .text_buffer(cx)
+            .children(
+                self.text
+                    .lines()
+                    .map(|line| div().child(SharedString::from(line.to_string()))),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{item::test::TestItem, register_scratch_item_builder, tests::init_test};
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_open_bundled_file(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        // Without a way to build buffer items, the text is shown as is.
+        let item = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_bundled_file("{}".into(), "Default Settings", "JSON", cx)
+            })
+            .await
+            .unwrap();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.active_item(cx).unwrap().item_id(), item.item_id());
+            assert_eq!(
+                item.downcast::<BundledFile>().unwrap().read(cx).title,
+                "Default Settings"
+            );
+            assert!(item.to_serializable_item_handle(cx).is_none());
+        });
+
+        cx.update(|cx| {
+            register_scratch_item_builder(
+                |_, _, breadcrumb_header, _, cx| {
+                    let label = breadcrumb_header.unwrap_or_default();
+                    Box::new(
+                        cx.new_view(|cx| TestItem::new(cx).with_label(&label).with_ephemeral(true)),
+                    )
+                },
+                cx,
+            )
+        });
+        let item = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_bundled_file("{}".into(), "Default Key Bindings", "JSON", cx)
+            })
+            .await
+            .unwrap();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.active_pane().read(cx).items_len(), 2);
+            assert_eq!(
+                item.downcast::<TestItem>().unwrap().read(cx).label,
+                "Default Key Bindings"
+            );
+        });
+    }
+}
@@ -0,0 +1,240 @@
+use gpui::{AppContext, Global, Model, ViewContext};
+use project::Project;
+use settings::Settings;
+
+use crate::{item::ItemHandle, Workspace, WorkspaceSettings};
+
+/// Builds a dirty untitled item with the given contents, see
+/// [`crate::item::Item::unsaved_text`].
+pub type UntitledItemBuilder =
+    fn(String, &Model<Project>, &mut ViewContext<Workspace>) -> Box<dyn ItemHandle>;
+
+struct GlobalUntitledItemBuilder(UntitledItemBuilder);
+
+impl Global for GlobalUntitledItemBuilder {}
+
+/// Registers how to recreate untitled items when they are carried over to the workspace
+/// replacing theirs.
+pub fn register_untitled_item_builder(builder: UntitledItemBuilder, cx: &mut AppContext) {
+    cx.set_global(GlobalUntitledItemBuilder(builder));
+}
+
+/// The contents of the dirty untitled items of a workspace that is about to be replaced.
+pub(crate) struct CarriedUntitledItems {
+    build_item: UntitledItemBuilder,
+    contents: Vec<String>,
+}
+
+/// Whether the dirty untitled items of a workspace are carried over to the workspace replacing
+/// it, rather than being prompted for before it is closed.
+pub(crate) fn carries_untitled_items(cx: &AppContext) -> bool {
+    WorkspaceSettings::get_global(cx).carry_untitled && cx.has_global::<GlobalUntitledItemBuilder>()
+}
+
+impl Workspace {
+    /// Collects the contents of the dirty untitled items, if they are carried over to the
+    /// workspace replacing this one.
+    pub(crate) fn carried_untitled_items(&self, cx: &AppContext) -> Option<CarriedUntitledItems> {
+        if !carries_untitled_items(cx) {
+            return None;
+        }
+        let build_item = cx.global::<GlobalUntitledItemBuilder>().0;
+        let contents = self
+            .items(cx)
+            .filter(|item| item.is_dirty(cx) && !item.is_ephemeral(cx))
+            .filter_map(|item| item.unsaved_text(cx))
+            .collect::<Vec<_>>();
+        (!contents.is_empty()).then_some(CarriedUntitledItems {
+            build_item,
+            contents,
+        })
+    }
+
+    pub(crate) fn add_carried_untitled_items(
+        &mut self,
+        items: CarriedUntitledItems,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let project = self.project.clone();
+        for text in items.contents {
+            let item = (items.build_item)(text, &project, cx);
+            self.add_item_to_active_pane(item, None, true, cx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::{TestItem, TestProjectItem},
+        tests::init_test,
+        CloseIntent,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::PathBuf;
+
+    #[gpui::test]
+    async fn test_carry_untitled_items_when_replacing_window(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            register_untitled_item_builder(
+                |text, _, cx| {
+                    Box::new(cx.new_view(|cx| {
+                        let mut item = TestItem::new(cx).with_dirty(true);
+                        item.state = text;
+                        item
+                    }))
+                },
+                cx,
+            );
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.carry_untitled = Some(true);
+                });
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "1.txt": "" })).await;
+        fs.insert_tree("/other", json!({})).await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project, cx));
+
+        let untitled = window
+            .update(cx, |_, cx| {
+                cx.new_view(|cx| {
+                    let mut item = TestItem::new(cx).with_dirty(true);
+                    item.state = "draft".into();
+                    item
+                })
+            })
+            .unwrap();
+        let titled = window
+            .update(cx, |_, cx| {
+                cx.new_view(|cx| {
+                    TestItem::new(cx)
+                        .with_dirty(true)
+                        .with_project_items(&[TestProjectItem::new(1, "1.txt", cx)])
+                })
+            })
+            .unwrap();
+        window
+            .update(cx, |workspace, cx| {
+                workspace.add_item_to_active_pane(Box::new(untitled.clone()), None, true, cx);
+                workspace.add_item_to_active_pane(Box::new(titled.clone()), None, true, cx);
+            })
+            .unwrap();
+
+        // Only the titled item is prompted for.
+        let task = window
+            .update(cx, |workspace, cx| {
+                workspace.prepare_to_close(CloseIntent::ReplaceWindow, cx)
+            })
+            .unwrap();
+        cx.executor().run_until_parked();
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(1); // don't save
+        cx.executor().run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        assert!(task.await.unwrap());
+        assert_eq!(titled.read_with(cx, |item, _| item.save_count), 0);
+
+        window
+            .update(cx, |workspace, cx| {
+                workspace.open_workspace_for_paths(true, vec!["/other".into()], cx)
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        cx.executor().run_until_parked();
+
+        // The untitled item is recreated, still dirty, in the new workspace.
+        let workspace = window.root(cx).unwrap();
+        workspace.read_with(cx, |workspace, cx| {
+            let worktree_paths = workspace
+                .worktrees(cx)
+                .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+                .collect::<Vec<_>>();
+            assert_eq!(worktree_paths, [PathBuf::from("/other")]);
+
+            let items = workspace.items(cx).collect::<Vec<_>>();
+            assert_eq!(items.len(), 1);
+            assert_ne!(items[0].item_id(), untitled.entity_id());
+            assert!(items[0].is_dirty(cx));
+            assert_eq!(items[0].unsaved_text(cx).as_deref(), Some("draft"));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_untitled_items_stay_when_opening_in_another_window(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            register_untitled_item_builder(
+                |text, _, cx| {
+                    Box::new(cx.new_view(|cx| {
+                        let mut item = TestItem::new(cx).with_dirty(true);
+                        item.state = text;
+                        item
+                    }))
+                },
+                cx,
+            );
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.carry_untitled = Some(true);
+                });
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({})).await;
+        fs.insert_tree("/other", json!({})).await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project, cx));
+        let other_project = Project::test(fs, ["/other".as_ref()], cx).await;
+        let other_window = cx.add_window(|cx| Workspace::test_new(other_project, cx));
+
+        let untitled = window
+            .update(cx, |_, cx| {
+                cx.new_view(|cx| {
+                    let mut item = TestItem::new(cx).with_dirty(true);
+                    item.state = "draft".into();
+                    item
+                })
+            })
+            .unwrap();
+        window
+            .update(cx, |workspace, cx| {
+                workspace.add_item_to_active_pane(Box::new(untitled.clone()), None, true, cx);
+            })
+            .unwrap();
+
+        // The folder is already open in the other window, so this window isn't replaced and
+        // keeps its untitled item, which isn't duplicated into the other window.
+        window
+            .update(cx, |workspace, cx| {
+                workspace.open_workspace_for_paths(true, vec!["/other".into()], cx)
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        cx.executor().run_until_parked();
+
+        window
+            .read_with(cx, |workspace, cx| {
+                let items = workspace.items(cx).collect::<Vec<_>>();
+                assert_eq!(items.len(), 1);
+                assert_eq!(items[0].item_id(), untitled.entity_id());
+            })
+            .unwrap();
+        other_window
+            .read_with(cx, |workspace, cx| {
+                assert_eq!(workspace.items(cx).count(), 0);
+            })
+            .unwrap();
+    }
+}
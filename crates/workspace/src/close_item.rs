@@ -0,0 +1,336 @@
+use anyhow::Result;
+use collections::HashSet;
+use gpui::{EntityId, Task, ViewContext, WeakView};
+use util::{paths::PathMatcher, ResultExt};
+
+use crate::{
+    item::Item,
+    notifications::{NotificationId, Toast},
+    CloseItemsMatching, SaveIntent, Workspace,
+};
+
+struct ClosedItemsMatching;
+
+/// Lets an item close itself, e.g. once the resource it shows is gone, going through the same
+/// save prompts as when the user closes it. Resolves to whether the item was closed.
+pub fn request_item_close<T: Item>(
+    workspace: &WeakView<Workspace>,
+    save_intent: SaveIntent,
+    cx: &mut ViewContext<T>,
+) -> Task<Result<bool>> {
+    let item_id = cx.entity_id();
+    let workspace = workspace.clone();
+    // The pane reads the item when closing it, which can't happen while it's being updated.
+    cx.spawn(|_, mut cx| async move {
+        workspace
+            .update(&mut cx, |workspace, cx| {
+                workspace.close_item(item_id, save_intent, cx)
+            })?
+            .await
+    })
+}
+
+impl Workspace {
+    /// Closes an item of the center panes like the user would, prompting to save it depending
+    /// on `save_intent`. Resolves to whether the item was closed.
+    pub fn close_item(
+        &mut self,
+        item_id: EntityId,
+        save_intent: SaveIntent,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<bool>> {
+        let Some(pane) = self
+            .panes_by_item
+            .get(&item_id)
+            .and_then(|pane| pane.upgrade())
+        else {
+            return Task::ready(Ok(false));
+        };
+        let close = pane.update(cx, |pane, cx| {
+            pane.close_item_by_id(item_id, save_intent, cx)
+        });
+        cx.spawn(|_, mut cx| async move {
+            close.await?;
+            // Closing doesn't fail when the user cancels it.
+            pane.update(&mut cx, |pane, _| {
+                pane.items().all(|item| item.item_id() != item_id)
+            })
+        })
+    }
+
+    /// Closes the items of the center panes whose path matches `glob`, either relative to its
+    /// worktree or absolute, prompting to save them depending on `save_intent`. Items without
+    /// a path never match, and pinned items only match `**`. Resolves to the number of items
+    /// closed.
+    pub fn close_items_matching(
+        &mut self,
+        glob: &str,
+        save_intent: SaveIntent,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<usize>> {
+        let matcher = match PathMatcher::new(&[glob.to_string()]) {
+            Ok(matcher) => matcher,
+            Err(error) => return Task::ready(Err(error.into())),
+        };
+        let close_pinned = glob == "**";
+        let project = self.project.read(cx);
+        let mut closes = Vec::new();
+        for pane in &self.panes {
+            let pane_ref = pane.read(cx);
+            let item_ids = pane_ref
+                .items()
+                .enumerate()
+                .filter(|(index, item)| {
+                    let Some(path) = item.project_path(cx) else {
+                        return false;
+                    };
+                    (close_pinned || *index >= pane_ref.pinned_count())
+                        && (matcher.is_match(&path.path)
+                            || project
+                                .absolute_path(&path, cx)
+                                .map_or(false, |abs_path| matcher.is_match(abs_path)))
+                })
+                .map(|(_, item)| item.item_id())
+                .collect::<HashSet<_>>();
+            if !item_ids.is_empty() {
+                closes.push((pane.clone(), item_ids));
+            }
+        }
+
+        // The panes are closed one after another, so that their save prompts don't pile up.
+        cx.spawn(|_, mut cx| async move {
+            let mut closed = 0;
+            for (pane, item_ids) in closes {
+                let close = pane.update(&mut cx, |pane, cx| {
+                    let item_ids = item_ids.clone();
+                    pane.close_items(cx, save_intent, move |item_id| item_ids.contains(&item_id))
+                })?;
+                close.await.log_err();
+                // Closing doesn't fail when the user cancels it.
+                closed += pane.update(&mut cx, |pane, _| {
+                    let open = pane
+                        .items()
+                        .filter(|item| item_ids.contains(&item.item_id()))
+                        .count();
+                    item_ids.len() - open
+                })?;
+            }
+            Ok(closed)
+        })
+    }
+
+    pub(crate) fn close_items_matching_action(
+        &mut self,
+        action: &CloseItemsMatching,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let close = self.close_items_matching(
+            &action.glob,
+            action.save_intent.unwrap_or(SaveIntent::Close),
+            cx,
+        );
+        cx.spawn(|this, mut cx| async move {
+            let closed = close.await?;
+            this.update(&mut cx, |this, cx| {
+                let message = match closed {
+                    1 => "Closed 1 item".to_string(),
+                    closed => format!("Closed {closed} items"),
+                };
+                this.show_toast(
+                    Toast::new(NotificationId::unique::<ClosedItemsMatching>(), message).autohide(),
+                    cx,
+                );
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::{TestItem, TestProjectItem},
+        tests::init_test,
+        Pane, SplitDirection,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, View, VisualContext, VisualTestContext};
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_close_dirty_item(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let project_item = cx.update(|cx| TestProjectItem::new(1, "1.txt", cx));
+        project_item.update(cx, |item, _| item.is_dirty = true);
+        let item = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_project_items(&[project_item])
+        });
+        let (pane, other_item) = workspace.update(cx, |workspace, cx| {
+            let other_item = cx.new_view(TestItem::new);
+            workspace.add_item_to_active_pane(Box::new(other_item.clone()), None, true, cx);
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, cx);
+            (workspace.active_pane().clone(), other_item)
+        });
+
+        // Closing prompts to save the item, and cancelling the prompt keeps it open.
+        let close = workspace.update(cx, |workspace, cx| {
+            workspace.close_item(item.item_id(), SaveIntent::Close, cx)
+        });
+        cx.run_until_parked();
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(2);
+        assert!(!close.await.unwrap());
+        pane.update(cx, |pane, _| assert_eq!(pane.items_len(), 2));
+
+        // Closing without saving doesn't prompt.
+        let close = item.update(cx, |_, cx| {
+            request_item_close(&workspace.downgrade(), SaveIntent::Skip, cx)
+        });
+        cx.run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        assert!(close.await.unwrap());
+        pane.update(cx, |pane, _| {
+            assert_eq!(pane.items_len(), 1);
+            assert_eq!(pane.active_item().unwrap().item_id(), other_item.item_id());
+        });
+
+        // Items that aren't open can't be closed.
+        let close = workspace.update(cx, |workspace, cx| {
+            workspace.close_item(item.item_id(), SaveIntent::Skip, cx)
+        });
+        assert!(!close.await.unwrap());
+    }
+
+    #[gpui::test]
+    async fn test_close_items_matching(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let add_item =
+            |path: Option<(u64, &str)>, pane: &View<Pane>, cx: &mut VisualTestContext| {
+                let item = cx.new_view(|cx| {
+                    let item = TestItem::new(cx);
+                    match path {
+                        Some((id, path)) => {
+                            item.with_project_items(&[TestProjectItem::new(id, path, cx)])
+                        }
+                        None => item,
+                    }
+                });
+                pane.update(cx, |pane, cx| {
+                    pane.add_item(Box::new(item), true, true, None, cx)
+                });
+            };
+        let (left_pane, right_pane) = workspace.update(cx, |workspace, cx| {
+            let left_pane = workspace.active_pane().clone();
+            let right_pane = workspace.split_pane(left_pane.clone(), SplitDirection::Right, cx);
+            (left_pane, right_pane)
+        });
+        add_item(Some((1, "src/legacy/pinned.rs")), &left_pane, cx);
+        left_pane.update(cx, |pane, cx| pane.pin_tab_at(0, cx));
+        add_item(Some((2, "src/legacy/a.rs")), &left_pane, cx);
+        add_item(Some((3, "src/main.rs")), &left_pane, cx);
+        add_item(None, &left_pane, cx);
+        add_item(Some((4, "src/legacy/b.rs")), &right_pane, cx);
+        add_item(Some((5, "src/lib.rs")), &right_pane, cx);
+
+        let close_matching = |glob: &str, cx: &mut VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                workspace.close_items_matching(glob, SaveIntent::Skip, cx)
+            })
+        };
+        let open_paths = |pane: &View<Pane>, cx: &mut VisualTestContext| {
+            pane.update(cx, |pane, cx| {
+                pane.items()
+                    .map(|item| {
+                        item.project_path(cx)
+                            .map(|path| path.path.to_string_lossy().into_owned())
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        // Pinned and untitled items are kept.
+        assert_eq!(close_matching("src/legacy/**", cx).await.unwrap(), 2);
+        assert_eq!(
+            open_paths(&left_pane, cx),
+            [
+                Some("src/legacy/pinned.rs".to_string()),
+                Some("src/main.rs".to_string()),
+                None
+            ]
+        );
+        assert_eq!(
+            open_paths(&right_pane, cx),
+            [Some("src/lib.rs".to_string())]
+        );
+
+        // Pinned items are closed by `**`, but untitled items still don't match.
+        assert_eq!(close_matching("**", cx).await.unwrap(), 3);
+        assert_eq!(open_paths(&left_pane, cx), [None]);
+
+        assert!(close_matching("src/[", cx).await.is_err());
+    }
+
+    #[gpui::test]
+    async fn test_close_items_matching_prompts_one_pane_at_a_time(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let add_dirty_item = |id, path, pane: &View<Pane>, cx: &mut VisualTestContext| {
+            let project_item = cx.update(|cx| TestProjectItem::new(id, path, cx));
+            project_item.update(cx, |item, _| item.is_dirty = true);
+            let item = cx.new_view(|cx| {
+                TestItem::new(cx)
+                    .with_dirty(true)
+                    .with_project_items(&[project_item])
+            });
+            pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(item.clone()), true, true, None, cx)
+            });
+            item
+        };
+        let (left_pane, right_pane) = workspace.update(cx, |workspace, cx| {
+            let left_pane = workspace.active_pane().clone();
+            let right_pane = workspace.split_pane(left_pane.clone(), SplitDirection::Right, cx);
+            (left_pane, right_pane)
+        });
+        let left_item = add_dirty_item(1, "left.rs", &left_pane, cx);
+        let right_item = add_dirty_item(2, "right.rs", &right_pane, cx);
+        let is_open = |item: &View<TestItem>, pane: &View<Pane>, cx: &mut VisualTestContext| {
+            pane.update(cx, |pane, _| pane.index_for_item(item).is_some())
+        };
+
+        let close = workspace.update(cx, |workspace, cx| {
+            workspace.close_items_matching("**", SaveIntent::Close, cx)
+        });
+        cx.run_until_parked();
+        assert!(cx.has_pending_prompt());
+
+        // The second pane only prompts once the first one is done.
+        cx.simulate_prompt_answer(1);
+        assert!(!cx.has_pending_prompt());
+        cx.run_until_parked();
+        assert!(!is_open(&left_item, &left_pane, cx));
+        assert!(is_open(&right_item, &right_pane, cx));
+        assert!(cx.has_pending_prompt());
+
+        cx.simulate_prompt_answer(1);
+        assert_eq!(close.await.unwrap(), 2);
+        assert!(!is_open(&right_item, &right_pane, cx));
+    }
+}
@@ -0,0 +1,342 @@
+use gpui::{Axis, Bounds, Pixels, View, ViewContext, WindowContext};
+use serde::Serialize;
+use util::ResultExt;
+
+use crate::{
+    dock::DockPosition, persistence::model::DockData, DumpWorkspaceState, Member, Pane, PaneAxis,
+    ViewId, Workspace,
+};
+
+/// A snapshot of a workspace's layout, see [`Workspace::debug_state`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WorkspaceDebugState {
+    pub center: PaneGroupDebugState,
+    pub left_dock: DockDebugState,
+    pub right_dock: DockDebugState,
+    pub bottom_dock: DockDebugState,
+    pub zoom: Option<ZoomDebugState>,
+    /// The leaders followed by this workspace, ordered by peer id.
+    pub followers: Vec<FollowerDebugState>,
+    pub centered_layout: bool,
+    pub bounds: BoundsDebugState,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PaneGroupDebugState {
+    Group {
+        axis: AxisDebugState,
+        flexes: Vec<f32>,
+        members: Vec<PaneGroupDebugState>,
+    },
+    Pane(PaneDebugState),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AxisDebugState {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PaneDebugState {
+    pub items: Vec<ItemDebugState>,
+    pub active_item_index: Option<usize>,
+    pub pinned_count: usize,
+    /// Whether this is the active pane of the workspace.
+    pub active: bool,
+    pub focused: bool,
+    pub zoomed: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ItemDebugState {
+    /// The kind the item is serialized with, if it is serializable.
+    pub kind: Option<&'static str>,
+    pub title: Option<String>,
+    pub dirty: bool,
+    pub preview: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DockDebugState {
+    pub open: bool,
+    /// Whether the dock is restored open, which differs from `open` for the docks hidden by
+    /// the `auto_hide_docks_below_width` setting.
+    pub visible: bool,
+    pub active_panel: Option<String>,
+    pub zoomed: bool,
+    pub focused: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ZoomDebugState {
+    /// The dock of the zoomed panel, or `None` for a zoomed center pane.
+    pub dock: Option<DockPosition>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FollowerDebugState {
+    pub leader: String,
+    pub active_view: Option<ViewIdDebugState>,
+    /// The views created for the leader, ordered by view id.
+    pub views: Vec<ViewIdDebugState>,
+    /// Whether the leader is followed in a dock rather than in a center pane.
+    pub in_dock: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ViewIdDebugState {
+    pub creator: String,
+    pub id: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct BoundsDebugState {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl From<ViewId> for ViewIdDebugState {
+    fn from(view_id: ViewId) -> Self {
+        Self {
+            creator: view_id.creator.to_string(),
+            id: view_id.id,
+        }
+    }
+}
+
+impl From<Bounds<Pixels>> for BoundsDebugState {
+    fn from(bounds: Bounds<Pixels>) -> Self {
+        Self {
+            x: bounds.origin.x.0,
+            y: bounds.origin.y.0,
+            width: bounds.size.width.0,
+            height: bounds.size.height.0,
+        }
+    }
+}
+
+impl Workspace {
+    /// Captures the layout of the workspace, including the runtime state that isn't
+    /// serialized, such as the focus and the followed leaders. Tests can compare it to assert
+    /// on the whole layout at once, and [`DumpWorkspaceState`] shows it as JSON.
+    pub fn debug_state(&self, cx: &mut WindowContext) -> WorkspaceDebugState {
+        let docks = self.build_serialized_docks(cx);
+        let dock_state =
+            |position: DockPosition, data: DockData, cx: &WindowContext| DockDebugState {
+                open: self.dock_at_position(position).read(cx).is_open(),
+                visible: data.visible,
+                active_panel: data.active_panel,
+                zoomed: data.zoom,
+                focused: data.focused,
+            };
+
+        let mut followers = self
+            .follower_states
+            .iter()
+            .map(|(leader_id, state)| {
+                let mut views = state
+                    .items_by_leader_view_id
+                    .keys()
+                    .map(|view_id| ViewIdDebugState::from(*view_id))
+                    .collect::<Vec<_>>();
+                views.sort();
+                FollowerDebugState {
+                    leader: leader_id.to_string(),
+                    active_view: state.active_view_id.map(ViewIdDebugState::from),
+                    views,
+                    in_dock: state.dock_pane.is_some(),
+                }
+            })
+            .collect::<Vec<_>>();
+        followers.sort_by(|a, b| a.leader.cmp(&b.leader));
+
+        WorkspaceDebugState {
+            center: self.pane_group_debug_state(&self.center.root, cx),
+            left_dock: dock_state(DockPosition::Left, docks.left, cx),
+            right_dock: dock_state(DockPosition::Right, docks.right, cx),
+            bottom_dock: dock_state(DockPosition::Bottom, docks.bottom, cx),
+            zoom: self.zoomed.as_ref().map(|_| ZoomDebugState {
+                dock: self.zoomed_position,
+            }),
+            followers,
+            centered_layout: self.centered_layout,
+            bounds: self.bounds.into(),
+        }
+    }
+
+    fn pane_group_debug_state(&self, member: &Member, cx: &WindowContext) -> PaneGroupDebugState {
+        match member {
+            Member::Axis(PaneAxis {
+                axis,
+                members,
+                flexes,
+                bounding_boxes: _,
+            }) => PaneGroupDebugState::Group {
+                axis: match axis {
+                    Axis::Horizontal => AxisDebugState::Horizontal,
+                    Axis::Vertical => AxisDebugState::Vertical,
+                },
+                flexes: flexes.lock().clone(),
+                members: members
+                    .iter()
+                    .map(|member| self.pane_group_debug_state(member, cx))
+                    .collect(),
+            },
+            Member::Pane(pane) => PaneGroupDebugState::Pane(self.pane_debug_state(pane, cx)),
+        }
+    }
+
+    fn pane_debug_state(&self, pane_handle: &View<Pane>, cx: &WindowContext) -> PaneDebugState {
+        let pane = pane_handle.read(cx);
+        let items = pane
+            .items()
+            .map(|item| ItemDebugState {
+                kind: item
+                    .to_serializable_item_handle(cx)
+                    .map(|item| item.serialized_item_kind()),
+                title: item.tab_content_text(cx).map(|title| title.to_string()),
+                dirty: item.is_dirty(cx),
+                preview: pane.preview_item_id() == Some(item.item_id()),
+            })
+            .collect::<Vec<_>>();
+        PaneDebugState {
+            active_item_index: (!items.is_empty()).then(|| pane.active_item_index()),
+            items,
+            pinned_count: pane.pinned_count(),
+            active: pane_handle == &self.active_pane,
+            focused: pane.has_focus(cx),
+            zoomed: pane.is_zoomed(),
+        }
+    }
+
+    pub(crate) fn dump_workspace_state(
+        &mut self,
+        _: &DumpWorkspaceState,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(json) = serde_json::to_string_pretty(&self.debug_state(cx)).log_err() else {
+            return;
+        };
+        let open_scratch = self.open_scratch(json.clone(), "Workspace State", "JSON", cx);
+        cx.spawn(|_, _| async move {
+            if open_scratch.await.log_err().is_none() {
+                log::info!("workspace state:\n{json}");
+            }
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dock::test::TestPanel,
+        item::test::{TestItem, TestProjectItem},
+        tests::init_test,
+        SplitDirection,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_debug_state(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let item = |id, label: &'static str, cx: &mut ViewContext<Workspace>| {
+            Box::new(cx.new_view(|cx| {
+                TestItem::new(cx)
+                    .with_label(label)
+                    .with_project_items(&[TestProjectItem::new(id, label, cx)])
+            }))
+        };
+        let right_pane = workspace.update(cx, |workspace, cx| {
+            let panel = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            workspace.add_panel(panel, cx);
+            workspace.toggle_dock(DockPosition::Left, cx);
+
+            workspace.add_item_to_active_pane(item(1, "a.txt", cx), None, true, cx);
+            let dirty_item = item(2, "b.txt", cx);
+            dirty_item.update(cx, |item, _| item.is_dirty = true);
+            workspace.add_item_to_active_pane(dirty_item, None, true, cx);
+            let left_pane = workspace.active_pane().clone();
+            let right_pane = workspace.split_pane(left_pane, SplitDirection::Right, cx);
+            workspace.add_item(
+                right_pane.clone(),
+                item(3, "c.txt", cx),
+                None,
+                true,
+                true,
+                cx,
+            );
+            right_pane
+        });
+        cx.run_until_parked();
+
+        let state = workspace.update(cx, |workspace, cx| workspace.debug_state(cx));
+        let item_state = |title: &str, dirty| ItemDebugState {
+            kind: None,
+            title: Some(title.to_string()),
+            dirty,
+            preview: false,
+        };
+        assert_eq!(
+            state.center,
+            PaneGroupDebugState::Group {
+                axis: AxisDebugState::Horizontal,
+                flexes: vec![1., 1.],
+                members: vec![
+                    PaneGroupDebugState::Pane(PaneDebugState {
+                        items: vec![item_state("a.txt", false), item_state("b.txt", true)],
+                        active_item_index: Some(1),
+                        pinned_count: 0,
+                        active: false,
+                        focused: false,
+                        zoomed: false,
+                    }),
+                    PaneGroupDebugState::Pane(PaneDebugState {
+                        items: vec![item_state("c.txt", false)],
+                        active_item_index: Some(0),
+                        pinned_count: 0,
+                        active: true,
+                        focused: true,
+                        zoomed: false,
+                    }),
+                ],
+            }
+        );
+        assert_eq!(
+            state.left_dock,
+            DockDebugState {
+                open: true,
+                visible: true,
+                active_panel: Some("TestPanel".to_string()),
+                zoomed: false,
+                focused: false,
+            }
+        );
+        assert!(!state.right_dock.open && !state.bottom_dock.open);
+        assert_eq!(state.zoom, None);
+        assert!(state.followers.is_empty());
+
+        // Zooming the pane shows in the snapshot, and the snapshot serializes to JSON.
+        workspace.update(cx, |workspace, cx| {
+            right_pane.update(cx, |pane, cx| pane.toggle_zoom(&Default::default(), cx));
+            let state = workspace.debug_state(cx);
+            assert_eq!(state.zoom, Some(ZoomDebugState { dock: None }));
+            let json = serde_json::to_value(&state).unwrap();
+            assert_eq!(json["center"]["type"], "group");
+            assert_eq!(json["center"]["members"][1]["zoomed"], true);
+        });
+    }
+}
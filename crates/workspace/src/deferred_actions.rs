@@ -0,0 +1,151 @@
+use std::{mem, rc::Rc};
+
+use gpui::{Action, ViewContext};
+
+use crate::Workspace;
+
+type DeferredAction = Box<dyn FnOnce(&mut Workspace, &mut ViewContext<Workspace>)>;
+
+/// Whether the workspace is still being loaded, e.g. restored from the database, and the
+/// actions dispatched meanwhile that would otherwise be undone by the restore.
+#[derive(Default)]
+pub(crate) struct WorkspaceLoading {
+    depth: usize,
+    deferred_actions: Vec<DeferredAction>,
+}
+
+/// Wraps an action handler so that the actions dispatched while the workspace is loading are
+/// handled once it's done, in the order they were dispatched, e.g. for handlers changing the
+/// layout that the restored one would overwrite. The wrapped handler can be registered with
+/// [`Workspace::register_action`] like any other.
+pub fn defer_until_loaded<A: Action>(
+    handler: impl Fn(&mut Workspace, &A, &mut ViewContext<Workspace>) + 'static,
+) -> impl Fn(&mut Workspace, &A, &mut ViewContext<Workspace>) + 'static {
+    let handler = Rc::new(handler);
+    move |workspace, action, cx| {
+        if !workspace.is_loading() {
+            handler(workspace, action, cx);
+            return;
+        }
+        let handler = handler.clone();
+        let action = action.boxed_clone();
+        workspace.defer_action(move |workspace, cx| {
+            if let Some(action) = action.as_any().downcast_ref::<A>() {
+                handler(workspace, action, cx);
+            }
+        });
+    }
+}
+
+impl Workspace {
+    /// Whether the workspace is still restoring its panes, items and docks, or opening the
+    /// paths it was opened with.
+    pub fn is_loading(&self) -> bool {
+        self.loading.depth > 0
+    }
+
+    pub(crate) fn begin_loading(&mut self) {
+        self.loading.depth += 1;
+    }
+
+    /// Ends the loading begun last, replaying the deferred actions once no loading is left.
+    pub(crate) fn finish_loading(&mut self, cx: &mut ViewContext<Self>) {
+        self.loading.depth = self.loading.depth.saturating_sub(1);
+        if self.loading.depth == 0 {
+            for action in mem::take(&mut self.loading.deferred_actions) {
+                action(self, cx);
+            }
+        }
+    }
+
+    /// Queues `action` to run once the workspace is loaded. See [`defer_until_loaded`].
+    pub(crate) fn defer_action(
+        &mut self,
+        action: impl FnOnce(&mut Self, &mut ViewContext<Self>) + 'static,
+    ) {
+        self.loading.deferred_actions.push(Box::new(action));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        dock::{test::TestPanel, DockPosition},
+        item::test::{TestItem, TestItemDeserializationDelay},
+        persistence::model::{
+            SerializedItem, SerializedPane, SerializedPaneGroup, SerializedWorkspace,
+            SerializedWorkspaceLocation,
+        },
+        register_serializable_item,
+        tests::init_test,
+        ToggleBottomDock, WorkspaceId,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_actions_deferred_until_loaded(cx: &mut TestAppContext) {
+        const LOAD_TIME: Duration = Duration::from_millis(100);
+        init_test(cx);
+        cx.update(|cx| {
+            register_serializable_item::<TestItem>(cx);
+            cx.set_global(TestItemDeserializationDelay(LOAD_TIME));
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let docks = workspace.update(cx, |workspace, cx| {
+            let panel = cx.new_view(|cx| TestPanel::new(DockPosition::Bottom, cx));
+            workspace.add_panel(panel, cx);
+            workspace.build_serialized_docks(cx)
+        });
+        assert!(!docks.bottom.visible);
+        let serialized_workspace = SerializedWorkspace {
+            id: WorkspaceId(1),
+            location: SerializedWorkspaceLocation::from_local_paths(["/root"]),
+            center_group: SerializedPaneGroup::Pane(SerializedPane::new(
+                vec![SerializedItem::new("TestItem", 1, true, false)],
+                true,
+                0,
+            )),
+            window_bounds: None,
+            centered_layout: false,
+            session_notes: None,
+            display: None,
+            docks,
+            session_id: None,
+            window_id: None,
+        };
+
+        // The dock toggled while the items are being restored isn't closed again by the
+        // restored docks.
+        let load = workspace.update(cx, |_, cx| {
+            Workspace::load_workspace(serialized_workspace, Vec::new(), cx)
+        });
+        cx.run_until_parked();
+        assert!(workspace.update(cx, |workspace, _| workspace.is_loading()));
+        cx.dispatch_action(ToggleBottomDock);
+        workspace.update(cx, |workspace, cx| {
+            assert!(!workspace.is_dock_open(DockPosition::Bottom, cx));
+        });
+        cx.executor().advance_clock(LOAD_TIME);
+        load.await.unwrap();
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            assert!(!workspace.is_loading());
+            assert_eq!(workspace.items_of_type::<TestItem>(cx).count(), 1);
+            assert!(workspace.is_dock_open(DockPosition::Bottom, cx));
+        });
+
+        // Once loaded, the actions are handled right away.
+        cx.dispatch_action(ToggleBottomDock);
+        workspace.update(cx, |workspace, cx| {
+            assert!(!workspace.is_dock_open(DockPosition::Bottom, cx));
+        });
+    }
+}
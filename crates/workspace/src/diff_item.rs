@@ -0,0 +1,255 @@
+use anyhow::{anyhow, Result};
+use gpui::{AppContext, Global, Model, Task, View, ViewContext, VisualContext};
+use project::{Project, ProjectPath};
+
+use crate::{
+    item::{Item, ItemHandle},
+    pane::Pane,
+    Workspace,
+};
+
+/// An item showing the differences between two project items, see [`Workspace::open_diff`].
+pub trait DiffItem: Item {
+    type Item: project::ProjectItem;
+
+    fn for_project_items(
+        project: Model<Project>,
+        left: Model<Self::Item>,
+        right: Model<Self::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self
+    where
+        Self: Sized;
+}
+
+type BuildDiffItem = Box<dyn FnOnce(&mut ViewContext<Workspace>) -> Box<dyn ItemHandle>>;
+
+/// Opens the project items at both paths, returning `None` if the registered [`DiffItem`]
+/// can't show them.
+type DiffItemBuilder = fn(
+    &Model<Project>,
+    &ProjectPath,
+    &ProjectPath,
+    &mut AppContext,
+) -> Option<Task<Result<BuildDiffItem>>>;
+
+struct GlobalDiffItemBuilder(DiffItemBuilder);
+
+impl Global for GlobalDiffItemBuilder {}
+
+/// Registers the [`DiffItem`] used to compare files, replacing the one registered before.
+pub fn register_diff_item<I: DiffItem>(cx: &mut AppContext) {
+    cx.set_global(GlobalDiffItemBuilder(|project, left, right, cx| {
+        let left = <I::Item as project::ProjectItem>::try_open(project, left, cx)?;
+        let right = <I::Item as project::ProjectItem>::try_open(project, right, cx)?;
+        let project = project.clone();
+        Some(cx.spawn(|_| async move {
+            let (left, right) = futures::future::try_join(left, right).await?;
+            Ok(Box::new(move |cx: &mut ViewContext<Workspace>| {
+                Box::new(cx.new_view(|cx| I::for_project_items(project, left, right, cx)))
+                    as Box<dyn ItemHandle>
+            }) as BuildDiffItem)
+        }))
+    }));
+}
+
+impl Workspace {
+    /// Opens an item comparing the files at both paths in the active pane.
+    pub fn open_diff(
+        &mut self,
+        left: ProjectPath,
+        right: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<Box<dyn ItemHandle>>> {
+        let Some(build_diff_item) = cx
+            .try_global::<GlobalDiffItemBuilder>()
+            .map(|global| global.0)
+        else {
+            return Task::ready(Err(anyhow!("no diff item builder is registered")));
+        };
+        let Some(open) = build_diff_item(&self.project, &left, &right, cx) else {
+            return Task::ready(Err(anyhow!(
+                "{:?} and {:?} can't be compared",
+                left.path,
+                right.path
+            )));
+        };
+        cx.spawn(|workspace, mut cx| async move {
+            let build_item = open.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let item = build_item(cx);
+                workspace.add_item_to_active_pane(item.boxed_clone(), None, true, cx);
+                item
+            })
+        })
+    }
+
+    /// Compares an item of `pane` with the active item of the pane that was used last before
+    /// it, see [`crate::pane::CompareWithActiveItem`].
+    pub fn compare_with_active_item(
+        &mut self,
+        pane: &View<Pane>,
+        item: &dyn ItemHandle,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<Box<dyn ItemHandle>>> {
+        let other_pane = self
+            .activation_history(cx)
+            .into_iter()
+            .map(|(pane, _)| pane)
+            .chain(self.panes.iter().cloned())
+            .find(|other_pane| other_pane != pane);
+        let Some(other_item) = other_pane.and_then(|pane| pane.read(cx).active_item()) else {
+            return Task::ready(Err(anyhow!(
+                "there is no item in another pane to compare with"
+            )));
+        };
+        let (Some(left), Some(right)) = (item.project_path(cx), other_item.project_path(cx)) else {
+            return Task::ready(Err(anyhow!("only files can be compared")));
+        };
+        self.open_diff(left, right, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::{TestItem, TestProjectItem},
+        pane::CompareWithActiveItem,
+        tests::init_test,
+        SplitDirection,
+    };
+    use fs::FakeFs;
+    use gpui::{
+        Empty, EventEmitter, FocusHandle, FocusableView, IntoElement, Render, TestAppContext,
+    };
+    use project::WorktreeId;
+
+    struct TestDiffView {
+        left: ProjectPath,
+        right: ProjectPath,
+        focus_handle: FocusHandle,
+    }
+
+    impl Item for TestDiffView {
+        type Event = ();
+    }
+
+    impl EventEmitter<()> for TestDiffView {}
+
+    impl FocusableView for TestDiffView {
+        fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+            self.focus_handle.clone()
+        }
+    }
+
+    impl Render for TestDiffView {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            Empty
+        }
+    }
+
+    impl DiffItem for TestDiffView {
+        type Item = TestProjectItem;
+
+        fn for_project_items(
+            _: Model<Project>,
+            left: Model<TestProjectItem>,
+            right: Model<TestProjectItem>,
+            cx: &mut ViewContext<Self>,
+        ) -> Self {
+            Self {
+                left: left.read(cx).project_path.clone().unwrap(),
+                right: right.read(cx).project_path.clone().unwrap(),
+                focus_handle: cx.focus_handle(),
+            }
+        }
+    }
+
+    fn project_path(path: &str) -> ProjectPath {
+        (WorktreeId::from_usize(0), path).into()
+    }
+
+    #[gpui::test]
+    async fn test_open_diff(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let error = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_diff(project_path("a.txt"), project_path("b.txt"), cx)
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "no diff item builder is registered");
+
+        cx.update(|cx| register_diff_item::<TestDiffView>(cx));
+        let error = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_diff(project_path("a.txt"), project_path("b.png"), cx)
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#""a.txt" and "b.png" can't be compared"#
+        );
+
+        let item = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_diff(project_path("a.txt"), project_path("b.txt"), cx)
+            })
+            .await
+            .unwrap();
+        workspace.update(cx, |workspace, cx| {
+            let diff = item.downcast::<TestDiffView>().unwrap().read(cx);
+            assert_eq!(diff.left, project_path("a.txt"));
+            assert_eq!(diff.right, project_path("b.txt"));
+            assert_eq!(workspace.active_item(cx).unwrap().item_id(), item.item_id());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_compare_with_active_item_in_other_pane(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| register_diff_item::<TestDiffView>(cx));
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let left_item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(1, "a.txt", cx)])
+        });
+        let right_item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(2, "b.txt", cx)])
+        });
+        let right_pane = workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(left_item), None, true, cx);
+            let left_pane = workspace.active_pane().clone();
+            let right_pane = workspace.split_pane(left_pane, SplitDirection::Right, cx);
+            right_pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(right_item), true, true, None, cx)
+            });
+            right_pane
+        });
+        cx.run_until_parked();
+
+        // The focused pane's active item is compared with the one of the other pane.
+        cx.dispatch_action(CompareWithActiveItem);
+        cx.run_until_parked();
+        right_pane.update(cx, |pane, cx| {
+            let diff = pane
+                .active_item()
+                .unwrap()
+                .downcast::<TestDiffView>()
+                .unwrap()
+                .read(cx);
+            assert_eq!(diff.left, project_path("b.txt"));
+            assert_eq!(diff.right, project_path("a.txt"));
+        });
+    }
+}
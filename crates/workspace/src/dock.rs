@@ -3,10 +3,10 @@ use crate::{status_bar::StatusItemView, Workspace};
 use crate::{DraggedDock, Event, Pane};
 use client::proto;
 use gpui::{
-    deferred, div, px, Action, AnchorCorner, AnyView, AppContext, Axis, Entity, EntityId,
-    EventEmitter, FocusHandle, FocusableView, IntoElement, KeyContext, MouseButton, MouseDownEvent,
-    MouseUpEvent, ParentElement, Render, SharedString, StyleRefinement, Styled, Subscription, View,
-    ViewContext, VisualContext, WeakView, WindowContext,
+    canvas, deferred, div, px, Action, AnchorCorner, AnyView, AppContext, Axis, Bounds, Entity,
+    EntityId, EventEmitter, FocusHandle, FocusableView, IntoElement, KeyContext, MouseButton,
+    MouseDownEvent, MouseUpEvent, ParentElement, Render, SharedString, StyleRefinement, Styled,
+    Subscription, View, ViewContext, VisualContext, WeakView, WindowContext,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -169,6 +169,8 @@ pub struct Dock {
     focus_handle: FocusHandle,
     pub(crate) serialized_dock: Option<DockData>,
     resizeable: bool,
+    /// Where the dock was last drawn in its window, while it was open.
+    pub(crate) bounds: Option<Bounds<Pixels>>,
     _subscriptions: [Subscription; 2],
 }
 
@@ -223,9 +225,8 @@ impl Dock {
                 }
             });
             let zoom_subscription = cx.subscribe(&workspace, |dock, workspace, e: &Event, cx| {
-                if matches!(e, Event::ZoomChanged) {
-                    let is_zoomed = workspace.read(cx).zoomed.is_some();
-                    dock.resizeable = !is_zoomed;
+                if let Event::ZoomChanged(zoom_state) = e {
+                    dock.resizeable = zoom_state.is_none();
                 }
             });
             Self {
@@ -237,6 +238,7 @@ impl Dock {
                 _subscriptions: [focus_subscription, zoom_subscription],
                 serialized_dock: None,
                 resizeable: true,
+                bounds: None,
             }
         });
 
@@ -256,7 +258,7 @@ impl Dock {
                     workspace.zoomed = None;
                     workspace.zoomed_position = None;
                 }
-                cx.emit(Event::ZoomChanged);
+                workspace.emit_zoom_changed(cx);
                 workspace.dismiss_zoomed_items_to_reveal(Some(position), cx);
                 workspace.update_active_view_for_followers(cx)
             }
@@ -269,7 +271,7 @@ impl Dock {
                     if panel.is_zoomed(cx) {
                         workspace.zoomed = Some(panel.to_any().downgrade());
                         workspace.zoomed_position = Some(position);
-                        cx.emit(Event::ZoomChanged);
+                        workspace.emit_zoom_changed(cx);
                         return;
                     }
                 }
@@ -277,7 +279,7 @@ impl Dock {
             if workspace.zoomed_position == Some(position) {
                 workspace.zoomed = None;
                 workspace.zoomed_position = None;
-                cx.emit(Event::ZoomChanged);
+                workspace.emit_zoom_changed(cx);
             }
         })
         .detach();
@@ -417,7 +419,7 @@ impl Dock {
                         .update(cx, |workspace, cx| {
                             workspace.zoomed = Some(panel.downgrade().into());
                             workspace.zoomed_position = Some(panel.read(cx).position(cx));
-                            cx.emit(Event::ZoomChanged);
+                            workspace.emit_zoom_changed(cx);
                         })
                         .ok();
                 }
@@ -428,7 +430,7 @@ impl Dock {
                             if workspace.zoomed_position == Some(this.position) {
                                 workspace.zoomed = None;
                                 workspace.zoomed_position = None;
-                                cx.emit(Event::ZoomChanged);
+                                workspace.emit_zoom_changed(cx);
                             }
                             cx.notify();
                         })
@@ -483,6 +485,20 @@ impl Dock {
                 }
             }
             self.set_open(serialized.visible, cx);
+
+            // Only take the focus once, not every time another panel gets added.
+            if serialized.focused && serialized.visible {
+                let restored_panel = self.active_panel().filter(|panel| {
+                    serialized.active_panel.as_deref() == Some(panel.persistent_name())
+                });
+                if let Some(panel) = restored_panel {
+                    let focus_handle = panel.focus_handle(cx);
+                    cx.focus(&focus_handle);
+                    if let Some(serialized_dock) = self.serialized_dock.as_mut() {
+                        serialized_dock.focused = false;
+                    }
+                }
+            }
             return true;
         }
         false
@@ -696,6 +712,15 @@ impl Render for Dock {
                         ),
                 )
                 .when(self.resizeable, |this| this.child(create_resize_handle()))
+                .child({
+                    let this = cx.view().clone();
+                    canvas(
+                        move |bounds, cx| this.update(cx, |this, _| this.bounds = Some(bounds)),
+                        |_, _, _| {},
+                    )
+                    .absolute()
+                    .size_full()
+                })
         } else {
             div()
                 .key_context(dispatch_context)
@@ -0,0 +1,147 @@
+use gpui::{FocusableView, ViewContext};
+use settings::Settings;
+
+use crate::{dock::DockPosition, Workspace, WorkspaceSettings};
+
+/// The side docks closed by the `auto_hide_docks_below_width` setting.
+#[derive(Default)]
+pub(crate) struct DockAutoHide {
+    /// Whether the window was narrower than the setting when its bounds last changed.
+    narrow: bool,
+    /// The docks closed when the window got narrow, which are opened again when it grows.
+    hidden: Vec<DockPosition>,
+}
+
+impl DockAutoHide {
+    /// Whether the dock at `position` is meant to be open, despite being hidden.
+    pub(crate) fn is_hidden(&self, position: DockPosition) -> bool {
+        self.hidden.contains(&position)
+    }
+}
+
+impl Workspace {
+    /// Closes the side docks when the window gets narrower than the
+    /// `auto_hide_docks_below_width` setting, and opens them again when it grows back.
+    pub(crate) fn update_dock_auto_hide(&mut self, cx: &mut ViewContext<Self>) {
+        let narrow = WorkspaceSettings::get_global(cx)
+            .auto_hide_docks_below_width
+            .map_or(false, |min_width| self.bounds.size.width < min_width);
+        if narrow == self.dock_auto_hide.narrow {
+            return;
+        }
+        self.dock_auto_hide.narrow = narrow;
+
+        if narrow {
+            let mut focus_center = false;
+            for position in [DockPosition::Left, DockPosition::Right] {
+                let dock = self.dock_at_position(position).clone();
+                if !dock.read(cx).is_open() {
+                    continue;
+                }
+                dock.update(cx, |dock, cx| {
+                    focus_center |= dock
+                        .active_panel()
+                        .map_or(false, |panel| panel.focus_handle(cx).contains_focused(cx));
+                    dock.set_open(false, cx);
+                });
+                self.dock_auto_hide.hidden.push(position);
+            }
+            if focus_center {
+                self.active_pane.update(cx, |pane, cx| pane.focus(cx));
+            }
+        } else {
+            for position in std::mem::take(&mut self.dock_auto_hide.hidden) {
+                self.dock_at_position(position)
+                    .update(cx, |dock, cx| dock.set_open(true, cx));
+            }
+        }
+        cx.notify();
+    }
+
+    /// Stops managing the side docks once the user opens or closes one of them while the
+    /// window is narrow, until the window crosses the `auto_hide_docks_below_width` setting
+    /// again.
+    pub(crate) fn side_dock_toggled_by_user(&mut self, position: DockPosition) {
+        if position != DockPosition::Bottom && self.dock_auto_hide.narrow {
+            self.dock_auto_hide.hidden.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dock::test::TestPanel, tests::init_test};
+    use fs::FakeFs;
+    use gpui::{px, size, TestAppContext, View, VisualContext, VisualTestContext};
+    use project::Project;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_auto_hide_docks_below_width(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.auto_hide_docks_below_width = Some(800.);
+                });
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        workspace.update(cx, |workspace, cx| {
+            for position in [
+                DockPosition::Left,
+                DockPosition::Right,
+                DockPosition::Bottom,
+            ] {
+                let panel = cx.new_view(|cx| TestPanel::new(position, cx));
+                workspace.add_panel(panel, cx);
+                workspace.set_dock_open(position, Some(true), cx);
+            }
+        });
+        let resize = |width: f32, cx: &mut VisualTestContext| {
+            cx.simulate_resize(size(px(width), px(600.)));
+            cx.run_until_parked();
+        };
+        let open_docks = |workspace: &View<Workspace>, cx: &mut VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                [
+                    DockPosition::Left,
+                    DockPosition::Right,
+                    DockPosition::Bottom,
+                ]
+                .map(|position| workspace.dock_at_position(position).read(cx).is_open())
+            })
+        };
+
+        // The side docks are hidden while the window is narrow, but stored as open.
+        resize(600., cx);
+        assert_eq!(open_docks(&workspace, cx), [false, false, true]);
+        workspace.update(cx, |workspace, cx| {
+            let docks = workspace.build_serialized_docks(cx);
+            assert!(docks.left.visible && docks.right.visible);
+        });
+        resize(1000., cx);
+        assert_eq!(open_docks(&workspace, cx), [true, true, true]);
+
+        // Opening a dock while the window is narrow keeps it open, and the hidden docks are
+        // left closed.
+        resize(600., cx);
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_dock(DockPosition::Left, cx)
+        });
+        resize(700., cx);
+        assert_eq!(open_docks(&workspace, cx), [true, false, true]);
+        resize(1000., cx);
+        assert_eq!(open_docks(&workspace, cx), [true, false, true]);
+
+        // The docks are managed again once the window crossed the width.
+        resize(600., cx);
+        assert_eq!(open_docks(&workspace, cx), [false, false, true]);
+        resize(1000., cx);
+        assert_eq!(open_docks(&workspace, cx), [true, false, true]);
+    }
+}
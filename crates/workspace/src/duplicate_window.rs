@@ -0,0 +1,161 @@
+use anyhow::{anyhow, Result};
+use gpui::{Task, ViewContext, WindowHandle};
+use util::ResultExt;
+
+use crate::{persistence::model::SerializedWorkspace, DuplicateWindow, Workspace};
+
+impl Workspace {
+    /// Opens another window on the same folders, with a copy of this workspace's panes, docks
+    /// and items. The copy is stored as a workspace of its own, so that the two windows can be
+    /// rearranged independently. Collaboration state, such as the followed collaborators and
+    /// the shared screens, isn't copied.
+    pub fn open_duplicate_window(
+        &mut self,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<WindowHandle<Workspace>>> {
+        let Some(local_paths) = self.local_paths(cx) else {
+            return Task::ready(Err(anyhow!("only local workspaces can be duplicated")));
+        };
+        let abs_paths = local_paths
+            .iter()
+            .map(|path| path.to_path_buf())
+            .collect::<Vec<_>>();
+        let env = self.project.read(cx).cli_environment(cx);
+        let centered_layout = self.centered_layout;
+        // The copied items are deserialized from what this workspace stores for them, so
+        // workspaces that aren't stored only get their folders duplicated.
+        let layout = self
+            .database_id()
+            .zip(self.serialized_location(cx))
+            .map(|(id, location)| SerializedWorkspace {
+                id,
+                location,
+                center_group: self.build_serialized_center_group(cx),
+                window_bounds: None,
+                display: None,
+                docks: self.build_serialized_docks(cx),
+                centered_layout,
+                session_notes: None,
+                session_id: None,
+                window_id: None,
+            });
+        let serializable_items = self
+            .items(cx)
+            .filter_map(|item| item.to_serializable_item_handle(cx))
+            .collect::<Vec<_>>();
+        let item_serializations = serializable_items
+            .into_iter()
+            .filter_map(|item| item.serialize(self, false, cx))
+            .collect::<Vec<_>>();
+        let app_state = self.app_state.clone();
+
+        cx.spawn(|_, mut cx| async move {
+            for result in futures::future::join_all(item_serializations).await {
+                result.log_err();
+            }
+            let (window, _) = cx
+                .update(|cx| {
+                    Workspace::open_local(abs_paths, app_state, None, env, None, false, cx)
+                })?
+                .await?;
+            let restore_layout = window.update(&mut cx, |workspace, cx| {
+                workspace.centered_layout = centered_layout;
+                layout.map(|layout| {
+                    Workspace::restore_serialized_workspace(
+                        layout,
+                        Vec::new(),
+                        Vec::new(),
+                        false,
+                        cx,
+                    )
+                })
+            })?;
+            if let Some(restore_layout) = restore_layout {
+                restore_layout.await?;
+            }
+            Ok(window)
+        })
+    }
+
+    pub(crate) fn duplicate_window(&mut self, _: &DuplicateWindow, cx: &mut ViewContext<Self>) {
+        self.open_duplicate_window(cx).detach_and_log_err(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::TestItem, register_serializable_item, tests::init_test, AppState,
+        SplitDirection,
+    };
+    use gpui::{TestAppContext, VisualContext};
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_duplicate_window(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(register_serializable_item::<TestItem>);
+
+        let app_state = cx.update(AppState::test);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree("/root", json!({ "a.txt": "" }))
+            .await;
+        let (window, _) = cx
+            .update(|cx| {
+                Workspace::new_local(vec!["/root".into()], app_state, None, None, None, cx)
+            })
+            .await
+            .unwrap();
+        let original_items = window
+            .update(cx, |workspace, cx| {
+                let items = (0..2)
+                    .map(|_| cx.new_view(TestItem::new))
+                    .collect::<Vec<_>>();
+                workspace.add_item_to_active_pane(Box::new(items[0].clone()), None, true, cx);
+                let pane = workspace.active_pane().clone();
+                let new_pane = workspace.split_pane(pane, SplitDirection::Right, cx);
+                new_pane.update(cx, |pane, cx| {
+                    pane.add_item(Box::new(items[1].clone()), true, true, None, cx)
+                });
+                items
+            })
+            .unwrap();
+
+        let duplicate = window
+            .update(cx, |workspace, cx| workspace.open_duplicate_window(cx))
+            .unwrap()
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(cx.windows().len(), 2);
+
+        let original_id = window
+            .update(cx, |workspace, _| workspace.database_id())
+            .unwrap();
+        duplicate
+            .update(cx, |workspace, cx| {
+                // The layout is copied with new items, in a workspace stored on its own.
+                assert!(workspace.database_id().is_some());
+                assert_ne!(workspace.database_id(), original_id);
+                assert_eq!(workspace.visible_worktrees(cx).count(), 1);
+                assert_eq!(workspace.panes().len(), 2);
+                for (pane, original_item) in workspace.panes().iter().zip(&original_items) {
+                    let pane = pane.read(cx);
+                    assert_eq!(pane.items_len(), 1);
+                    let item = pane.active_item().unwrap();
+                    assert!(item.downcast::<TestItem>().is_some());
+                    assert_ne!(item.item_id(), original_item.entity_id());
+                }
+            })
+            .unwrap();
+        window
+            .update(cx, |workspace, cx| {
+                assert_eq!(workspace.panes().len(), 2);
+                assert_eq!(workspace.items(cx).count(), 2);
+            })
+            .unwrap();
+    }
+}
@@ -0,0 +1,299 @@
+use anyhow::{anyhow, Result};
+use collections::{HashMap, HashSet};
+use futures::{channel::mpsc, StreamExt};
+use gpui::{AppContext, Task, ViewContext, WindowContext};
+use project::ProjectPath;
+use std::{path::Path, time::Duration};
+use util::ResultExt;
+
+use crate::{item::ItemHandle, local_workspace_windows, Pane, Workspace};
+
+/// The paths locked with [`Workspace::lock_item_for_external_update`].
+pub(crate) struct ExternalUpdateLocks {
+    next_lock_id: usize,
+    locks: HashMap<usize, ExternalUpdateLock>,
+    /// The locked paths whose items were due to be autosaved.
+    skipped_autosaves: HashSet<ProjectPath>,
+    release_tx: mpsc::UnboundedSender<usize>,
+    _release_locks: Task<()>,
+}
+
+struct ExternalUpdateLock {
+    path: ProjectPath,
+    _timeout: Task<()>,
+}
+
+/// Keeps an item locked for an external update, see
+/// [`Workspace::lock_item_for_external_update`]. Dropping the guard releases the lock.
+#[must_use]
+pub struct ExternalUpdateGuard {
+    lock_id: Option<usize>,
+    release_tx: mpsc::UnboundedSender<usize>,
+}
+
+impl ExternalUpdateGuard {
+    /// Keeps the lock until it times out, instead of releasing it when the guard is dropped.
+    pub fn detach(mut self) {
+        self.lock_id = None;
+    }
+}
+
+impl Drop for ExternalUpdateGuard {
+    fn drop(&mut self) {
+        if let Some(lock_id) = self.lock_id.take() {
+            self.release_tx.unbounded_send(lock_id).ok();
+        }
+    }
+}
+
+impl ExternalUpdateLocks {
+    pub(crate) fn new(cx: &mut ViewContext<Workspace>) -> Self {
+        let (release_tx, mut release_rx) = mpsc::unbounded();
+        let release_locks = cx.spawn(|this, mut cx| async move {
+            while let Some(lock_id) = release_rx.next().await {
+                this.update(&mut cx, |this, cx| {
+                    this.release_external_update_lock(lock_id, cx)
+                })
+                .log_err();
+            }
+        });
+        Self {
+            next_lock_id: 0,
+            locks: HashMap::default(),
+            skipped_autosaves: HashSet::default(),
+            release_tx,
+            _release_locks: release_locks,
+        }
+    }
+
+    fn is_locked(&self, path: &ProjectPath) -> bool {
+        self.locks.values().any(|lock| &lock.path == path)
+    }
+
+    /// Whether the item's autosave must be skipped because it's locked. The skipped
+    /// autosave is run once the lock is released.
+    pub(crate) fn skip_autosave(&mut self, item: &dyn ItemHandle, cx: &AppContext) -> bool {
+        let Some(path) = item.project_path(cx).filter(|path| self.is_locked(path)) else {
+            return false;
+        };
+        self.skipped_autosaves.insert(path);
+        true
+    }
+}
+
+impl Workspace {
+    /// Keeps the items for `path` hands off while a tool outside of Zed, such as a formatter
+    /// or a code generator, rewrites the file. Until the returned guard is dropped or
+    /// `duration` elapses, the items, including those opened meanwhile, aren't autosaved and
+    /// are told to defer their conflict detection. Once released, the items without unsaved
+    /// changes are reloaded, once, and the autosaves skipped meanwhile are run.
+    pub fn lock_item_for_external_update(
+        &mut self,
+        path: ProjectPath,
+        duration: Duration,
+        cx: &mut ViewContext<Self>,
+    ) -> ExternalUpdateGuard {
+        let lock_id = self.external_update_locks.next_lock_id;
+        self.external_update_locks.next_lock_id += 1;
+
+        let timeout = cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(duration).await;
+            this.update(&mut cx, |this, cx| {
+                this.release_external_update_lock(lock_id, cx)
+            })
+            .ok();
+        });
+        if !self.external_update_locks.is_locked(&path) {
+            for item in self.items_for_project_path(&path, cx) {
+                item.set_external_update_in_progress(true, cx);
+            }
+        }
+        self.external_update_locks.locks.insert(
+            lock_id,
+            ExternalUpdateLock {
+                path,
+                _timeout: timeout,
+            },
+        );
+
+        ExternalUpdateGuard {
+            lock_id: Some(lock_id),
+            release_tx: self.external_update_locks.release_tx.clone(),
+        }
+    }
+
+    /// Whether the item is locked for an external update, and thus shouldn't be autosaved.
+    pub fn is_locked_for_external_update(&self, item: &dyn ItemHandle, cx: &WindowContext) -> bool {
+        item.project_path(cx)
+            .map_or(false, |path| self.external_update_locks.is_locked(&path))
+    }
+
+    /// Whether the item's autosave must be skipped because it's locked for an external
+    /// update, in which case it's autosaved once the lock is released.
+    pub(crate) fn skip_autosave_for_external_update(
+        &mut self,
+        item: &dyn ItemHandle,
+        cx: &WindowContext,
+    ) -> bool {
+        self.external_update_locks.skip_autosave(item, cx)
+    }
+
+    fn release_external_update_lock(&mut self, lock_id: usize, cx: &mut ViewContext<Self>) {
+        let Some(lock) = self.external_update_locks.locks.remove(&lock_id) else {
+            return;
+        };
+        if self.external_update_locks.is_locked(&lock.path) {
+            return;
+        }
+
+        let autosave_skipped = self
+            .external_update_locks
+            .skipped_autosaves
+            .remove(&lock.path);
+        for item in self.items_for_project_path(&lock.path, cx) {
+            item.set_external_update_in_progress(false, cx);
+            if item.is_dirty(cx) {
+                if autosave_skipped {
+                    Pane::autosave_item(item.as_ref(), self.project.clone(), cx)
+                        .detach_and_log_err(cx);
+                }
+            } else if item.can_save(cx) {
+                item.reload(self.project.clone(), cx).detach_and_log_err(cx);
+            }
+        }
+    }
+
+    fn items_for_project_path(
+        &self,
+        path: &ProjectPath,
+        cx: &WindowContext,
+    ) -> Vec<Box<dyn ItemHandle>> {
+        self.items(cx)
+            .filter(|item| item.project_path(cx).as_ref() == Some(path))
+            .map(|item| item.boxed_clone())
+            .collect()
+    }
+}
+
+/// Locks `abs_path` for an external update in every local workspace containing it, until
+/// `duration` elapses. This is how the CLI requests the lock for the tools that run it.
+pub fn lock_path_for_external_update(
+    abs_path: &Path,
+    duration: Duration,
+    cx: &mut AppContext,
+) -> Result<()> {
+    let mut locked = false;
+    for window in local_workspace_windows(cx) {
+        window
+            .update(cx, |workspace, cx| {
+                let worktree = workspace.project.read(cx).find_worktree(abs_path, cx);
+                if let Some((worktree, path)) = worktree {
+                    let path = ProjectPath {
+                        worktree_id: worktree.read(cx).id(),
+                        path: path.into(),
+                    };
+                    workspace
+                        .lock_item_for_external_update(path, duration, cx)
+                        .detach();
+                    locked = true;
+                }
+            })
+            .log_err();
+    }
+    if locked {
+        Ok(())
+    } else {
+        Err(anyhow!("{abs_path:?} is not open in any local workspace"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::{
+            test::{TestItem, TestProjectItem},
+            ItemEvent,
+        },
+        tests::init_test,
+        AutosaveSetting, WorkspaceSettings,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, View, VisualContext, VisualTestContext};
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_lock_item_for_external_update(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.autosave = Some(AutosaveSetting::AfterDelay { milliseconds: 100 });
+                });
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        let project = project::Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let new_item = |id, name, cx: &mut VisualTestContext| {
+            cx.new_view(|cx| {
+                TestItem::new(cx).with_project_items(&[TestProjectItem::new(id, name, cx)])
+            })
+        };
+        let generated = new_item(1, "generated.rs", cx);
+        let edited = new_item(2, "edited.rs", cx);
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(generated.clone()), None, true, cx);
+            workspace.add_item_to_active_pane(Box::new(edited.clone()), None, true, cx);
+        });
+        let path = |item: &View<TestItem>, cx: &mut VisualTestContext| {
+            cx.update(|cx| item.project_path(cx).unwrap())
+        };
+        let generated_path = path(&generated, cx);
+        let edited_path = path(&edited, cx);
+
+        // While the files are rewritten, the items aren't saved nor reloaded.
+        let (generated_guard, edited_guard) = workspace.update(cx, |workspace, cx| {
+            (
+                workspace.lock_item_for_external_update(
+                    generated_path,
+                    Duration::from_secs(60),
+                    cx,
+                ),
+                workspace.lock_item_for_external_update(edited_path, Duration::from_secs(1), cx),
+            )
+        });
+        edited.update(cx, |item, cx| {
+            item.is_dirty = true;
+            cx.emit(ItemEvent::Edit);
+        });
+        cx.executor().advance_clock(Duration::from_millis(500));
+        cx.run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        for item in [&generated, &edited] {
+            item.update(cx, |item, _| {
+                assert!(item.external_update_in_progress);
+                assert_eq!((item.save_count, item.reload_count), (0, 0));
+            });
+        }
+
+        // Once released, the items are reloaded once, except those with unsaved changes, which
+        // get the autosave skipped meanwhile instead.
+        drop(generated_guard);
+        cx.executor().advance_clock(Duration::from_millis(500));
+        cx.run_until_parked();
+        edited_guard.detach();
+        cx.run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        for item in [&generated, &edited] {
+            item.update(cx, |item, _| assert!(!item.external_update_in_progress));
+        }
+        generated.update(cx, |item, _| {
+            assert_eq!((item.save_count, item.reload_count), (0, 1))
+        });
+        edited.update(cx, |item, _| {
+            assert_eq!((item.save_count, item.reload_count), (1, 0))
+        });
+    }
+}
@@ -0,0 +1,182 @@
+use std::time::Duration;
+
+use gpui::{
+    Div, FocusableView, InteractiveElement, MouseMoveEvent, ViewContext, WeakView, WindowContext,
+};
+use settings::Settings;
+
+use crate::{dock::Dock, Pane, Workspace, WorkspaceSettings};
+
+/// How long the mouse has to stay over a pane or a dock before it's focused, so that moving
+/// the mouse across the window doesn't focus everything on the way.
+pub(crate) const FOCUS_FOLLOWS_MOUSE_DELAY: Duration = Duration::from_millis(100);
+
+/// What gets focused when the mouse stays over it, with the `focus_follows_mouse` setting.
+#[derive(Clone, PartialEq)]
+pub(crate) enum FocusFollowsMouseTarget {
+    Pane(WeakView<Pane>),
+    Dock(WeakView<Dock>),
+}
+
+impl Workspace {
+    /// Makes `element`, which renders `target`, focus it when the mouse stays over it, if the
+    /// `focus_follows_mouse` setting is enabled.
+    pub(crate) fn focus_follows_mouse(
+        element: Div,
+        target: FocusFollowsMouseTarget,
+        cx: &mut ViewContext<Self>,
+    ) -> Div {
+        if !WorkspaceSettings::get_global(cx).focus_follows_mouse {
+            return element;
+        }
+        element.on_mouse_move(cx.listener(move |workspace, _: &MouseMoveEvent, cx| {
+            workspace.hover_focus_target(target.clone(), cx)
+        }))
+    }
+
+    fn hover_focus_target(&mut self, target: FocusFollowsMouseTarget, cx: &mut ViewContext<Self>) {
+        if self
+            .pending_mouse_focus
+            .as_ref()
+            .is_some_and(|(pending, _)| *pending == target)
+        {
+            return;
+        }
+        // Moving over another target restarts the delay.
+        self.pending_mouse_focus = None;
+        if self.is_focus_target_focused(&target, cx) {
+            return;
+        }
+        let task = cx.spawn(|this, mut cx| async move {
+            cx.background_executor()
+                .timer(FOCUS_FOLLOWS_MOUSE_DELAY)
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_mouse_focus = None;
+                this.focus_target(&target, cx);
+            })
+            .ok();
+        });
+        self.pending_mouse_focus = Some((target, task));
+    }
+
+    fn is_focus_target_focused(
+        &self,
+        target: &FocusFollowsMouseTarget,
+        cx: &WindowContext,
+    ) -> bool {
+        match target {
+            FocusFollowsMouseTarget::Pane(pane) => pane
+                .upgrade()
+                .map_or(true, |pane| pane.focus_handle(cx).contains_focused(cx)),
+            FocusFollowsMouseTarget::Dock(dock) => dock.upgrade().map_or(true, |dock| {
+                dock.read(cx)
+                    .active_panel()
+                    .map_or(true, |panel| panel.focus_handle(cx).contains_focused(cx))
+            }),
+        }
+    }
+
+    fn focus_target(&mut self, target: &FocusFollowsMouseTarget, cx: &mut ViewContext<Self>) {
+        // The panes behind a modal or a zoomed item stay unfocused, as they can't be used.
+        if self.has_active_modal(cx)
+            || self.zoomed.is_some()
+            || self.is_focus_target_focused(target, cx)
+        {
+            return;
+        }
+        match target {
+            FocusFollowsMouseTarget::Pane(pane) => {
+                if let Some(pane) = pane.upgrade() {
+                    cx.focus_view(&pane);
+                }
+            }
+            FocusFollowsMouseTarget::Dock(dock) => {
+                let Some(dock) = dock.upgrade() else {
+                    return;
+                };
+                let dock = dock.read(cx);
+                if let Some(panel) = dock.active_panel().filter(|_| dock.is_open()) {
+                    panel.focus_handle(cx).focus(cx);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::TestItem,
+        tests::{init_test, TestModal},
+        SplitDirection,
+    };
+    use fs::FakeFs;
+    use gpui::{Modifiers, TestAppContext, View, VisualContext, VisualTestContext};
+    use project::Project;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_focus_follows_mouse(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.focus_follows_mouse = Some(true);
+                });
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let (left_pane, right_pane) = workspace.update(cx, |workspace, cx| {
+            let left_pane = workspace.active_pane().clone();
+            workspace.add_item_to_active_pane(Box::new(cx.new_view(TestItem::new)), None, true, cx);
+            let right_pane = workspace.split_pane(left_pane.clone(), SplitDirection::Right, cx);
+            right_pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(cx.new_view(TestItem::new)), true, true, None, cx)
+            });
+            (left_pane, right_pane)
+        });
+        workspace.update(cx, |workspace, cx| {
+            workspace.activate_pane_in_direction(SplitDirection::Left, cx)
+        });
+        cx.run_until_parked();
+        assert!(workspace.update(cx, |workspace, _| *workspace.active_pane() == left_pane));
+
+        let hover = |pane: &View<Pane>, cx: &mut VisualTestContext| {
+            let bounds = workspace
+                .update(cx, |workspace, _| {
+                    workspace.center.bounding_box_for_pane(pane)
+                })
+                .unwrap();
+            cx.simulate_mouse_move(bounds.center(), None, Modifiers::none());
+        };
+
+        // Panes are focused once the mouse stays over them.
+        hover(&right_pane, cx);
+        assert!(workspace.update(cx, |workspace, _| *workspace.active_pane() == left_pane));
+        cx.executor().advance_clock(FOCUS_FOLLOWS_MOUSE_DELAY);
+        cx.run_until_parked();
+        assert!(workspace.update(cx, |workspace, _| *workspace.active_pane() == right_pane));
+
+        // Passing over a pane doesn't focus it.
+        hover(&left_pane, cx);
+        hover(&right_pane, cx);
+        cx.executor().advance_clock(FOCUS_FOLLOWS_MOUSE_DELAY);
+        cx.run_until_parked();
+        assert!(workspace.update(cx, |workspace, _| *workspace.active_pane() == right_pane));
+
+        // Modals keep the focus.
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(cx, TestModal::new)
+        });
+        hover(&left_pane, cx);
+        cx.executor().advance_clock(FOCUS_FOLLOWS_MOUSE_DELAY);
+        cx.run_until_parked();
+        assert!(workspace.update(cx, |workspace, cx| workspace.has_active_modal(cx)));
+        assert!(workspace.update(cx, |workspace, _| *workspace.active_pane() == right_pane));
+    }
+}
@@ -0,0 +1,146 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::Result;
+use collections::HashSet;
+use fs::Fs;
+use gpui::{AsyncAppContext, PromptLevel, WindowHandle};
+use settings::Settings;
+
+use crate::{FolderOpenBehavior, Workspace, WorkspaceSettings};
+
+/// Returns the indices of the directories among `abs_paths` to open in new windows rather than
+/// adding them as worktrees to the project of `window`, according to the
+/// `folder_open_behavior` setting. Only the directories that aren't in that project yet are
+/// considered, and only when the project has worktrees already.
+pub(crate) async fn folders_for_new_windows(
+    window: WindowHandle<Workspace>,
+    abs_paths: &[PathBuf],
+    fs: &Arc<dyn Fs>,
+    cx: &mut AsyncAppContext,
+) -> Result<HashSet<usize>> {
+    let mut new_window_ixs = HashSet::default();
+    for (ix, abs_path) in abs_paths.iter().enumerate() {
+        if !fs.is_dir(abs_path).await {
+            continue;
+        }
+        let behavior = window.update(cx, |workspace, cx| {
+            let project = workspace.project().read(cx);
+            if project.visible_worktrees(cx).next().is_none()
+                || project.find_worktree(abs_path, cx).is_some()
+            {
+                None
+            } else {
+                Some(WorkspaceSettings::get_global(cx).folder_open_behavior)
+            }
+        })?;
+        let opens_in_new_window = match behavior {
+            None | Some(FolderOpenBehavior::AddWorktree) => false,
+            Some(FolderOpenBehavior::NewWindow) => true,
+            Some(FolderOpenBehavior::Prompt) => {
+                let folder_name = abs_path
+                    .file_name()
+                    .map_or_else(|| abs_path.to_string_lossy(), |name| name.to_string_lossy());
+                let answer = window.update(cx, |_, cx| {
+                    cx.activate_window();
+                    cx.prompt(
+                        PromptLevel::Info,
+                        &format!("Open \"{folder_name}\" in a new window?"),
+                        Some("It can also be added to the project of this window."),
+                        &["Open in New Window", "Add to This Window"],
+                    )
+                })?;
+                answer.await? == 0
+            }
+        };
+        if opens_in_new_window {
+            new_window_ixs.insert(ix);
+        }
+    }
+    Ok(new_window_ixs)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{open_paths, tests::init_test, AppState, OpenOptions};
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn set_folder_open_behavior(behavior: FolderOpenBehavior, cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.folder_open_behavior = Some(behavior);
+                });
+            });
+        });
+    }
+
+    fn worktree_count(window: WindowHandle<Workspace>, cx: &mut TestAppContext) -> usize {
+        window
+            .update(cx, |workspace, cx| workspace.visible_worktrees(cx).count())
+            .unwrap()
+    }
+
+    #[gpui::test]
+    async fn test_folder_open_behavior(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let app_state = cx.update(AppState::test);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                "/root",
+                json!({
+                    "a": { "file.txt": "" },
+                    "b": {},
+                    "c": {},
+                    "d": {},
+                }),
+            )
+            .await;
+        let open = |paths: &[&str], cx: &mut TestAppContext| {
+            let paths = paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+            let app_state = app_state.clone();
+            cx.update(|cx| open_paths(&paths, app_state, OpenOptions::default(), cx))
+        };
+
+        let (window, _) = open(&["/root/a"], cx).await.unwrap();
+        assert_eq!(cx.windows().len(), 1);
+
+        // Folders are added as worktrees by default, along with the files opened in the window.
+        let (opened_window, results) = open(&["/root/b", "/root/a/file.txt"], cx).await.unwrap();
+        assert!(opened_window == window);
+        assert!(results[0].is_none());
+        assert!(matches!(results[1], Some(Ok(_))));
+        assert_eq!(cx.windows().len(), 1);
+        assert_eq!(worktree_count(window, cx), 2);
+
+        // Each folder gets its own window otherwise, leaving the files in the existing one.
+        set_folder_open_behavior(FolderOpenBehavior::NewWindow, cx);
+        let (opened_window, results) = open(&["/root/c", "/root/a/file.txt"], cx).await.unwrap();
+        assert!(opened_window == window);
+        assert!(results[0].is_none());
+        assert!(matches!(results[1], Some(Ok(_))));
+        assert_eq!(cx.windows().len(), 2);
+        assert_eq!(worktree_count(window, cx), 2);
+
+        // The prompt lets the folder be added to the window anyway.
+        set_folder_open_behavior(FolderOpenBehavior::Prompt, cx);
+        let task = open(&["/root/a/file.txt", "/root/d"], cx);
+        cx.executor().run_until_parked();
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(1);
+        let (opened_window, results) = task.await.unwrap();
+        assert!(opened_window == window);
+        assert!(matches!(results[0], Some(Ok(_))));
+        assert!(results[1].is_none());
+        assert!(!cx.has_pending_prompt());
+        assert_eq!(cx.windows().len(), 2);
+        assert_eq!(worktree_count(window, cx), 3);
+    }
+}
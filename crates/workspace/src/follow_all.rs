@@ -0,0 +1,161 @@
+use client::proto::PeerId;
+use gpui::{View, ViewContext, WeakView};
+use ui::SharedString;
+
+use crate::{
+    notifications::{NotificationId, Toast},
+    FollowAllCollaborators, Pane, SplitDirection, Workspace,
+};
+
+/// The panes split off by [`FollowAllCollaborators`], see [`Workspace::follow_all_collaborators`].
+#[derive(Default)]
+pub(crate) struct FollowAll {
+    /// Whether every collaborator is being followed, until [`Workspace::unfollow_all`].
+    active: bool,
+    created_panes: Vec<WeakView<Pane>>,
+}
+
+struct CollaboratorJoinedToast;
+
+impl Workspace {
+    /// Follows every collaborator in the project, each in their own pane. Collaborators that
+    /// are already followed keep their pane, the active pane is used for the first of the
+    /// others, and the rest get panes split off into a grid.
+    pub fn follow_all_collaborators(&mut self, cx: &mut ViewContext<Self>) {
+        let leaders = self
+            .followable_collaborators(cx)
+            .into_iter()
+            .map(|(peer_id, _)| peer_id)
+            .filter(|peer_id| !self.follower_states.contains_key(peer_id))
+            .collect::<Vec<_>>();
+        self.follow_all.active = true;
+        if leaders.is_empty() {
+            return;
+        }
+
+        let active_pane = self.active_pane.clone();
+        let base_pane = if self.leader_for_pane(&active_pane).is_none() {
+            active_pane
+        } else {
+            self.split_follow_all_pane(active_pane, SplitDirection::Right, cx)
+        };
+        let panes = self.split_into_grid(base_pane, leaders.len(), cx);
+        for (leader_id, pane) in leaders.into_iter().zip(panes) {
+            if let Some(task) = self.start_following_in_pane(leader_id, pane, cx) {
+                task.detach_and_log_err(cx);
+            }
+        }
+    }
+
+    /// Splits `pane` into a grid of `count` panes, as close to a square as possible, and
+    /// returns them column by column, starting with `pane`.
+    fn split_into_grid(
+        &mut self,
+        pane: View<Pane>,
+        count: usize,
+        cx: &mut ViewContext<Self>,
+    ) -> Vec<View<Pane>> {
+        let columns = (count as f64).sqrt().ceil() as usize;
+        let mut column_panes = vec![pane];
+        for _ in 1..columns {
+            let last = column_panes.last().unwrap().clone();
+            column_panes.push(self.split_follow_all_pane(last, SplitDirection::Right, cx));
+        }
+
+        let mut panes = Vec::with_capacity(count);
+        for (column, pane) in column_panes.into_iter().enumerate() {
+            let rows = count / columns + usize::from(column < count % columns);
+            let mut last = pane.clone();
+            panes.push(pane);
+            for _ in 1..rows {
+                last = self.split_follow_all_pane(last, SplitDirection::Down, cx);
+                panes.push(last.clone());
+            }
+        }
+        panes
+    }
+
+    fn split_follow_all_pane(
+        &mut self,
+        pane: View<Pane>,
+        direction: SplitDirection,
+        cx: &mut ViewContext<Self>,
+    ) -> View<Pane> {
+        let new_pane = self.split_pane(pane, direction, cx);
+        self.follow_all
+            .created_panes
+            .retain(|pane| pane.upgrade().is_some());
+        self.follow_all.created_panes.push(new_pane.downgrade());
+        new_pane
+    }
+
+    fn is_follow_all_pane(&self, pane: &View<Pane>) -> bool {
+        self.follow_all
+            .created_panes
+            .iter()
+            .any(|created_pane| created_pane == &pane.downgrade())
+    }
+
+    /// Removes the panes split off by [`FollowAllCollaborators`], once the collaborators are
+    /// unfollowed. Panes with unsaved changes are kept.
+    pub(crate) fn collapse_follow_all_panes(&mut self, cx: &mut ViewContext<Self>) {
+        self.follow_all.active = false;
+        let created_panes = std::mem::take(&mut self.follow_all.created_panes);
+        for pane in created_panes.iter().filter_map(|pane| pane.upgrade()) {
+            let dirty = pane.read(cx).items().any(|item| item.is_dirty(cx));
+            if !dirty && self.panes.len() > 1 && self.panes.contains(&pane) {
+                self.remove_pane(pane, None, cx);
+            }
+        }
+    }
+
+    /// Removes the pane split off by [`FollowAllCollaborators`] for a collaborator who left.
+    pub(crate) fn remove_follow_all_pane(&mut self, pane: View<Pane>, cx: &mut ViewContext<Self>) {
+        if self.is_follow_all_pane(&pane) && self.panes.len() > 1 && self.panes.contains(&pane) {
+            self.follow_all
+                .created_panes
+                .retain(|created_pane| created_pane != &pane.downgrade());
+            self.remove_pane(pane, None, cx);
+        }
+    }
+
+    /// Offers to follow a collaborator who joined the project while everyone is being
+    /// followed, instead of changing the grid under the user.
+    pub(crate) fn offer_to_follow_joined_collaborator(
+        &mut self,
+        peer_id: PeerId,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if !self.follow_all.active {
+            return;
+        }
+        let github_login = self
+            .active_call()
+            .and_then(|call| call.read(cx).room())
+            .and_then(|room| {
+                let room = room.read(cx);
+                let participant = room.remote_participant_for_peer_id(peer_id)?;
+                Some(SharedString::from(participant.user.github_login.clone()))
+            })
+            .unwrap_or_else(|| "A collaborator".into());
+        let workspace = cx.view().downgrade();
+        self.show_toast(
+            Toast::new(
+                NotificationId::unique::<CollaboratorJoinedToast>(),
+                format!("{github_login} joined the project"),
+            )
+            .on_click("Follow too?", move |cx| {
+                workspace
+                    .update(cx, |workspace, cx| {
+                        let pane = workspace.active_pane.clone();
+                        let pane = workspace.split_follow_all_pane(pane, SplitDirection::Right, cx);
+                        if let Some(task) = workspace.start_following_in_pane(peer_id, pane, cx) {
+                            task.detach_and_log_err(cx);
+                        }
+                    })
+                    .ok();
+            }),
+            cx,
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use std::time::Duration;
+
+use client::proto::PeerId;
+use gpui::ViewContext;
+
+use crate::{RestartFollowing, Workspace};
+
+/// How long a leader can have updates queued without any of them being applied before
+/// following it is considered stuck by [`RestartFollowing`].
+pub const STALLED_FOLLOWING_THRESHOLD: Duration = Duration::from_secs(10);
+
+/// The state of the updates received from a followed collaborator, see
+/// [`Workspace::follow_diagnostics`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FollowDiag {
+    pub leader: PeerId,
+    /// Updates received from the leader that haven't been applied yet.
+    pub queued_updates: usize,
+    /// Time since an update of the leader was last applied, or since following started.
+    pub last_update_age: Duration,
+    /// How many of the leader's views are followed.
+    pub items: usize,
+}
+
+impl Workspace {
+    /// Describes the updates of every leader this workspace is following, to tell whether
+    /// following got stuck.
+    pub fn follow_diagnostics(&self, cx: &ViewContext<Self>) -> Vec<FollowDiag> {
+        let now = cx.background_executor().now();
+        let mut diagnostics = self
+            .follower_states
+            .iter()
+            .map(|(leader_id, state)| FollowDiag {
+                leader: *leader_id,
+                queued_updates: state.queued_updates,
+                last_update_age: now.saturating_duration_since(state.last_update_at),
+                items: state.items_by_leader_view_id.len(),
+            })
+            .collect::<Vec<_>>();
+        diagnostics.sort_by_key(|diagnostic| (diagnostic.leader.owner_id, diagnostic.leader.id));
+        diagnostics
+    }
+
+    pub(crate) fn leader_update_processed(&mut self, leader_id: PeerId, cx: &ViewContext<Self>) {
+        if let Some(state) = self.follower_states.get_mut(&leader_id) {
+            state.queued_updates = state.queued_updates.saturating_sub(1);
+            state.last_update_at = cx.background_executor().now();
+        }
+    }
+
+    /// Replaces the task applying leader updates, dropping the updates queued for the
+    /// previous one.
+    pub(crate) fn restart_leader_updates(&mut self, cx: &mut ViewContext<Self>) {
+        let (leader_updates_tx, apply_leader_updates) = Self::apply_leader_updates(cx);
+        self.leader_updates_tx = leader_updates_tx;
+        self._apply_leader_updates = apply_leader_updates;
+        for state in self.follower_states.values_mut() {
+            state.queued_updates = 0;
+        }
+    }
+
+    /// Follows again the leaders whose updates stalled, in the panes they were followed in.
+    /// Leaders that merely haven't sent any updates lately are left alone.
+    pub(crate) fn restart_following(&mut self, _: &RestartFollowing, cx: &mut ViewContext<Self>) {
+        let stalled_leaders = self
+            .follow_diagnostics(cx)
+            .into_iter()
+            .filter(|diagnostic| {
+                diagnostic.queued_updates > 0
+                    && diagnostic.last_update_age > STALLED_FOLLOWING_THRESHOLD
+            })
+            .collect::<Vec<_>>();
+        if stalled_leaders.is_empty() {
+            return;
+        }
+
+        // Updates that are queued for that long won't get applied by the current task.
+        log::error!("leader updates stopped being applied, restarting them");
+        self.restart_leader_updates(cx);
+        for diagnostic in stalled_leaders {
+            let Some(pane) = self
+                .follower_states
+                .get(&diagnostic.leader)
+                .map(|state| state.center_pane.clone())
+            else {
+                continue;
+            };
+            if let Some(task) = self.start_following_in_pane(diagnostic.leader, pane, cx) {
+                task.detach_and_log_err(cx);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test;
+    use client::proto;
+    use fs::FakeFs;
+    use futures::channel::mpsc;
+    use gpui::{Task, TestAppContext};
+    use project::Project;
+
+    fn update_active_view() -> proto::UpdateFollowers {
+        proto::UpdateFollowers {
+            room_id: 1,
+            project_id: None,
+            variant: Some(proto::update_followers::Variant::UpdateActiveView(
+                proto::UpdateActiveView {
+                    id: None,
+                    leader_id: None,
+                    view: None,
+                },
+            )),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_restart_stalled_following(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let leader_id = PeerId { owner_id: 1, id: 1 };
+        let diagnostic = |cx: &mut gpui::VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                let diagnostics = workspace.follow_diagnostics(cx);
+                assert_eq!(diagnostics.len(), 1);
+                (
+                    diagnostics[0].queued_updates,
+                    diagnostics[0].last_update_age,
+                )
+            })
+        };
+
+        let pane = workspace.update(cx, |workspace, cx| {
+            workspace.start_following(leader_id, cx);
+            workspace.active_pane().clone()
+        });
+        workspace.update(cx, |workspace, cx| {
+            workspace.handle_update_followers(leader_id, update_active_view(), cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(diagnostic(cx), (0, Duration::ZERO));
+
+        // Nothing consumes the updates anymore.
+        let (stalled_tx, _stalled_rx) = mpsc::unbounded();
+        workspace.update(cx, |workspace, cx| {
+            workspace.leader_updates_tx = stalled_tx;
+            workspace.handle_update_followers(leader_id, update_active_view(), cx);
+            workspace.handle_update_followers(leader_id, update_active_view(), cx);
+        });
+        cx.executor().advance_clock(Duration::from_secs(5));
+        cx.run_until_parked();
+        assert_eq!(diagnostic(cx), (2, Duration::from_secs(5)));
+
+        // Following isn't restarted before the threshold is reached.
+        cx.dispatch_action(RestartFollowing);
+        assert_eq!(diagnostic(cx), (2, Duration::from_secs(5)));
+
+        cx.executor().advance_clock(STALLED_FOLLOWING_THRESHOLD);
+        cx.dispatch_action(RestartFollowing);
+        assert_eq!(diagnostic(cx), (0, Duration::ZERO));
+
+        // Updates are applied again.
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.leader_for_pane(&pane), Some(leader_id));
+            workspace.handle_update_followers(leader_id, update_active_view(), cx);
+        });
+        assert_eq!(diagnostic(cx), (1, Duration::ZERO));
+        cx.run_until_parked();
+        assert_eq!(diagnostic(cx), (0, Duration::ZERO));
+    }
+
+    #[gpui::test]
+    async fn test_idle_leader_is_not_restarted(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let leader_id = PeerId { owner_id: 1, id: 1 };
+
+        workspace.update(cx, |workspace, cx| {
+            workspace.start_following(leader_id, cx);
+        });
+        cx.run_until_parked();
+
+        // The leader doesn't send anything for a while, without any updates being queued.
+        cx.executor()
+            .advance_clock(STALLED_FOLLOWING_THRESHOLD + Duration::from_secs(1));
+        let leader_updates_tx =
+            workspace.update(cx, |workspace, _| workspace.leader_updates_tx.clone());
+        cx.dispatch_action(RestartFollowing);
+        workspace.update(cx, |workspace, cx| {
+            let diagnostics = workspace.follow_diagnostics(cx);
+            assert_eq!(diagnostics[0].queued_updates, 0);
+            assert_eq!(
+                diagnostics[0].last_update_age,
+                STALLED_FOLLOWING_THRESHOLD + Duration::from_secs(1)
+            );
+            assert!(workspace
+                .leader_updates_tx
+                .same_receiver(&leader_updates_tx));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_restart_terminated_leader_updates(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let leader_id = PeerId { owner_id: 1, id: 1 };
+
+        workspace.update(cx, |workspace, cx| {
+            workspace.start_following(leader_id, cx);
+            workspace._apply_leader_updates = Task::ready(Ok(()));
+        });
+        cx.run_until_parked();
+
+        // The next update restarts the task, which applies it.
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.leader_updates_tx.is_closed());
+            workspace.handle_update_followers(leader_id, update_active_view(), cx);
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            assert!(!workspace.leader_updates_tx.is_closed());
+            assert_eq!(workspace.follow_diagnostics(cx)[0].queued_updates, 0);
+        });
+    }
+}
@@ -0,0 +1,80 @@
+use anyhow::{Context as _, Result};
+use client::proto::PeerId;
+use gpui::{Task, ViewContext, VisualContext, WindowBounds, WindowHandle};
+
+use crate::{
+    notifications::{simple_message_notification::MessageNotification, NotificationId},
+    persistence::DB,
+    resolve_window_options, CloseWindow, WindowOptionsOverride, Workspace,
+};
+
+impl Workspace {
+    /// Opens another window on this workspace's project and follows `leader_id` there, leaving
+    /// this window as it is. The window is made full screen on another display, when there is
+    /// one, so that the leader can be watched next to the local editing.
+    pub fn follow_in_new_window(
+        &mut self,
+        leader_id: PeerId,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<WindowHandle<Workspace>>> {
+        let project = self.project.clone();
+        let app_state = self.app_state.clone();
+        let current_display_id = cx.display().map(|display| display.id());
+        let secondary_display = cx
+            .displays()
+            .into_iter()
+            .find(|display| Some(display.id()) != current_display_id);
+        let display_uuid = secondary_display
+            .as_ref()
+            .and_then(|display| display.uuid().ok());
+        let window_options_override = secondary_display.map(|display| -> WindowOptionsOverride {
+            let display_id = display.id();
+            let bounds = display.bounds();
+            Box::new(move |mut options| {
+                options.display_id = Some(display_id);
+                options.window_bounds = Some(WindowBounds::Fullscreen(bounds));
+                options
+            })
+        });
+
+        cx.spawn(|_, mut cx| async move {
+            let workspace_id = DB.next_id().await.unwrap_or_else(|_| Default::default());
+            let options = cx.update(|cx| (app_state.build_window_options)(display_uuid, cx))?;
+            let options = resolve_window_options(options, None, window_options_override, None);
+            let window = cx.open_window(options, |cx| {
+                cx.new_view(|cx| Workspace::new(Some(workspace_id), project, app_state, cx))
+            })?;
+            let following = window.update(&mut cx, |workspace, cx| {
+                cx.activate_window();
+                workspace.follow_window_leader = Some(leader_id);
+                workspace.start_following(leader_id, cx)
+            })?;
+            following
+                .context("cannot follow outside of a call")?
+                .await?;
+            Ok(window)
+        })
+    }
+
+    /// Offers to close a window opened by [`Workspace::follow_in_new_window`], once there is
+    /// nobody to follow in it anymore.
+    pub(crate) fn notify_follow_window_ended(&mut self, cx: &mut ViewContext<Self>) {
+        if self.follow_window_leader.take().is_none() {
+            return;
+        }
+        struct FollowWindowEnded;
+        self.show_notification(NotificationId::unique::<FollowWindowEnded>(), cx, |cx| {
+            cx.new_view(|_| {
+                MessageNotification::new("The collaborator followed in this window is gone.")
+                    .with_click_message("Close Window")
+                    .on_click(|cx| cx.dispatch_action(Box::new(CloseWindow)))
+            })
+        });
+    }
+
+    /// The collaborator followed in this window, when it was opened by
+    /// [`Workspace::follow_in_new_window`].
+    pub fn follow_window_leader(&self) -> Option<PeerId> {
+        self.follow_window_leader
+    }
+}
@@ -0,0 +1,305 @@
+use client::{proto, Status};
+use gpui::{ModelContext, Task};
+use postage::stream::Stream as _;
+use std::{collections::VecDeque, time::Duration};
+
+use crate::WorkspaceStore;
+
+/// The updates sent to followers through [`WorkspaceStore::update_followers`], throttled so
+/// that bursts of scrolling and cursor moves only send their latest state, and held while the
+/// client reconnects.
+pub(crate) struct FollowerUpdateQueue {
+    pending: VecDeque<proto::UpdateFollowers>,
+    throttle: Duration,
+    flush_task: Option<Task<()>>,
+}
+
+impl FollowerUpdateQueue {
+    const THROTTLE: Duration = Duration::from_millis(50);
+
+    pub(crate) fn new() -> Self {
+        Self {
+            pending: VecDeque::new(),
+            // Tests rely on the updates being sent without advancing the clock.
+            throttle: if cfg!(any(test, feature = "test-support")) {
+                Duration::ZERO
+            } else {
+                Self::THROTTLE
+            },
+            flush_task: None,
+        }
+    }
+
+    /// Queues `update`, replacing the queued updates it supersedes: the previous active view
+    /// of the same project, and the previous selections and scroll position of the same view.
+    /// Updates that add or remove excerpts are never replaced, as followers apply them as
+    /// deltas, and updates are never moved before the ones queued ahead of them.
+    fn push(&mut self, mut update: proto::UpdateFollowers) {
+        let project_id = update.project_id;
+        match &mut update.variant {
+            Some(proto::update_followers::Variant::UpdateActiveView(_)) => {
+                self.pending.retain(|pending| {
+                    pending.project_id != project_id
+                        || !matches!(
+                            pending.variant,
+                            Some(proto::update_followers::Variant::UpdateActiveView(_))
+                        )
+                });
+            }
+            Some(proto::update_followers::Variant::UpdateView(view)) => {
+                if let Some(proto::update_view::Variant::Editor(editor)) = &mut view.variant {
+                    let previous_ix = self.pending.iter().rposition(|pending| {
+                        pending.project_id == project_id
+                            && matches!(
+                                &pending.variant,
+                                Some(proto::update_followers::Variant::UpdateView(pending_view))
+                                    if pending_view.id == view.id
+                                        && pending_view.leader_id == view.leader_id
+                            )
+                    });
+                    let previous_editor =
+                        previous_ix.and_then(|ix| match &self.pending[ix].variant {
+                            Some(proto::update_followers::Variant::UpdateView(
+                                proto::UpdateView {
+                                    variant: Some(proto::update_view::Variant::Editor(editor)),
+                                    ..
+                                },
+                            )) => Some(editor),
+                            _ => None,
+                        });
+                    if let Some((ix, previous_editor)) = previous_ix.zip(previous_editor) {
+                        if !changes_excerpts(previous_editor) && !changes_excerpts(editor) {
+                            merge_editor_update(previous_editor, editor);
+                            self.pending.remove(ix);
+                        }
+                    }
+                }
+            }
+            Some(proto::update_followers::Variant::CreateView(_)) | None => {}
+        }
+        self.pending.push_back(update);
+    }
+}
+
+fn changes_excerpts(editor: &proto::update_view::Editor) -> bool {
+    !editor.inserted_excerpts.is_empty() || !editor.deleted_excerpts.is_empty()
+}
+
+/// Fills in the selections and scroll position that `later` leaves unchanged from `earlier`.
+fn merge_editor_update(
+    earlier: &proto::update_view::Editor,
+    later: &mut proto::update_view::Editor,
+) {
+    if later.selections.is_empty() && later.pending_selection.is_none() {
+        later.selections = earlier.selections.clone();
+        later.pending_selection = earlier.pending_selection.clone();
+    }
+    if later.scroll_top_anchor.is_none() {
+        later.scroll_top_anchor = earlier.scroll_top_anchor.clone();
+        later.scroll_x = earlier.scroll_x;
+        later.scroll_y = earlier.scroll_y;
+    }
+}
+
+impl WorkspaceStore {
+    pub(crate) fn send_follower_update(
+        &mut self,
+        update: proto::UpdateFollowers,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.follower_updates.push(update);
+        if self.follower_updates.flush_task.is_some() {
+            return;
+        }
+
+        let throttle = self.follower_updates.throttle;
+        if throttle.is_zero() {
+            self.flush_follower_updates(cx);
+        } else {
+            self.follower_updates.flush_task = Some(cx.spawn(|this, mut cx| async move {
+                cx.background_executor().timer(throttle).await;
+                this.update(&mut cx, |this, cx| {
+                    this.follower_updates.flush_task = None;
+                    this.flush_follower_updates(cx);
+                })
+                .ok();
+            }));
+        }
+    }
+
+    /// Sends the queued updates in order. If sending fails while the client is reconnecting,
+    /// the remaining updates are kept, and sent once the client is connected again.
+    fn flush_follower_updates(&mut self, cx: &mut ModelContext<Self>) {
+        while let Some(update) = self.follower_updates.pending.pop_front() {
+            if let Err(error) = self.client.send(update.clone()) {
+                self.follower_updates.pending.push_front(update);
+                if self.client.status().borrow().is_signed_out() {
+                    log::error!("failed to update followers: {error:?}");
+                    self.follower_updates.pending.clear();
+                } else {
+                    log::info!("updating followers once reconnected: {error:?}");
+                    self.flush_follower_updates_once_connected(cx);
+                }
+                return;
+            }
+        }
+    }
+
+    fn flush_follower_updates_once_connected(&mut self, cx: &mut ModelContext<Self>) {
+        let mut status = self.client.status();
+        self.follower_updates.flush_task = Some(cx.spawn(|this, mut cx| async move {
+            let connected = loop {
+                match status.next().await {
+                    Some(Status::Connected { .. }) => break true,
+                    Some(status) if !status.is_signed_out() => {}
+                    _ => break false,
+                }
+            };
+            this.update(&mut cx, |this, cx| {
+                this.follower_updates.flush_task = None;
+                if connected {
+                    this.flush_follower_updates(cx);
+                } else {
+                    this.follower_updates.pending.clear();
+                }
+            })
+            .ok();
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::{test::FakeServer, Client};
+    use clock::FakeSystemClock;
+    use gpui::{Context as _, TestAppContext};
+    use http_client::FakeHttpClient;
+    use std::sync::Arc;
+
+    #[gpui::test]
+    async fn test_follower_update_throttling(cx: &mut TestAppContext) {
+        crate::tests::init_test(cx);
+        let client = cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::new()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        let server = FakeServer::for_client(1, &client, cx).await;
+        let store = cx.new_model(|cx| {
+            let mut store = WorkspaceStore::new(client.clone(), cx);
+            store.follower_updates.throttle = FollowerUpdateQueue::THROTTLE;
+            store
+        });
+
+        let view_id = |id| proto::ViewId {
+            creator: Some(proto::PeerId { owner_id: 1, id: 1 }),
+            id,
+        };
+        let active_view = |id| {
+            proto::update_followers::Variant::UpdateActiveView(proto::UpdateActiveView {
+                id: Some(view_id(id)),
+                leader_id: None,
+                view: None,
+            })
+        };
+        let anchor = |excerpt_id| proto::EditorAnchor {
+            excerpt_id,
+            anchor: None,
+        };
+        let update_view = |editor| {
+            proto::update_followers::Variant::UpdateView(proto::UpdateView {
+                id: Some(view_id(1)),
+                leader_id: None,
+                variant: Some(proto::update_view::Variant::Editor(editor)),
+            })
+        };
+        let selections = proto::update_view::Editor {
+            selections: vec![proto::Selection {
+                id: 1,
+                start: Some(anchor(2)),
+                end: Some(anchor(3)),
+                reversed: false,
+            }],
+            ..Default::default()
+        };
+        let scroll = proto::update_view::Editor {
+            scroll_top_anchor: Some(anchor(5)),
+            scroll_y: 1.,
+            ..Default::default()
+        };
+        let send = |variant, cx: &mut TestAppContext| {
+            store.update(cx, |store, cx| {
+                store.send_follower_update(
+                    proto::UpdateFollowers {
+                        room_id: 1,
+                        project_id: Some(1),
+                        variant: Some(variant),
+                    },
+                    cx,
+                )
+            });
+        };
+
+        // A burst of updates only sends the latest active view, after the selections and
+        // scroll position of the view, merged into one update.
+        send(active_view(1), cx);
+        send(update_view(selections.clone()), cx);
+        send(active_view(2), cx);
+        send(update_view(scroll.clone()), cx);
+        send(active_view(3), cx);
+        cx.executor().advance_clock(FollowerUpdateQueue::THROTTLE);
+        cx.run_until_parked();
+        let mut merged = selections.clone();
+        merged.scroll_top_anchor = scroll.scroll_top_anchor.clone();
+        merged.scroll_y = scroll.scroll_y;
+        assert_eq!(
+            server
+                .receive::<proto::UpdateFollowers>()
+                .await
+                .unwrap()
+                .payload
+                .variant,
+            Some(update_view(merged))
+        );
+        assert_eq!(
+            server
+                .receive::<proto::UpdateFollowers>()
+                .await
+                .unwrap()
+                .payload
+                .variant,
+            Some(active_view(3))
+        );
+
+        // The updates that fail while reconnecting are sent once connected again.
+        server.forbid_connections();
+        server.disconnect();
+        cx.run_until_parked();
+        send(active_view(4), cx);
+        send(active_view(5), cx);
+        cx.executor().advance_clock(FollowerUpdateQueue::THROTTLE);
+        cx.run_until_parked();
+        store.read_with(cx, |store, _| {
+            assert_eq!(store.follower_updates.pending.len(), 1);
+        });
+        server.allow_connections();
+        cx.executor().advance_clock(Duration::from_secs(10));
+        cx.run_until_parked();
+        assert!(client.status().borrow().is_connected());
+        assert_eq!(
+            server
+                .receive::<proto::UpdateFollowers>()
+                .await
+                .unwrap()
+                .payload
+                .variant,
+            Some(active_view(5))
+        );
+        store.read_with(cx, |store, _| {
+            assert!(store.follower_updates.pending.is_empty());
+        });
+    }
+}
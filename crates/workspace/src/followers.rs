@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use client::{proto, proto::PeerId, TypedEnvelope};
+use collections::HashSet;
+use gpui::{AppContext, AsyncAppContext, Model, ModelContext, ViewContext};
+
+use crate::{
+    notifications::simple_message_notification::MessageNotification, Event, Follower, Workspace,
+    WorkspaceStore,
+};
+
+/// The namespace of the notification shown when someone starts following the local user.
+const NEW_FOLLOWER_NOTIFICATION: &str = "new_follower";
+
+/// How long after telling that someone started following, new followers are not announced.
+const NEW_FOLLOWER_NOTIFICATION_INTERVAL: Duration = Duration::from_secs(60);
+
+impl WorkspaceStore {
+    pub(crate) fn add_follower(&mut self, follower: Follower, cx: &mut ModelContext<Self>) {
+        if let Err(ix) = self.followers.binary_search(&follower) {
+            let peer_id = follower.peer_id;
+            let is_new_peer = self.followers.iter().all(|f| f.peer_id != peer_id);
+            self.followers.insert(ix, follower);
+            self.followers_changed(is_new_peer.then_some(peer_id), cx);
+        }
+    }
+
+    pub(crate) async fn handle_unfollow(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::Unfollow>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let follower = Follower {
+            project_id: envelope.payload.project_id,
+            peer_id: envelope.original_sender_id()?,
+        };
+        this.update(&mut cx, |this, cx| {
+            this.remove_followers(|f| *f == follower, cx)
+        })
+    }
+
+    fn remove_followers(
+        &mut self,
+        mut predicate: impl FnMut(&Follower) -> bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let count = self.followers.len();
+        self.followers.retain(|follower| !predicate(follower));
+        if self.followers.len() != count {
+            self.followers_changed(None, cx);
+        }
+    }
+
+    /// Tells the workspaces about the change once the current update is done, as it may come
+    /// from one of them.
+    fn followers_changed(&mut self, new_follower: Option<PeerId>, cx: &mut ModelContext<Self>) {
+        let this = cx.handle();
+        cx.defer(move |cx| {
+            this.update(cx, |this, cx| {
+                this.workspaces.retain(|workspace| {
+                    workspace
+                        .update(cx, |workspace, cx| {
+                            workspace.followers_changed(new_follower, cx)
+                        })
+                        .is_ok()
+                });
+            })
+        });
+    }
+}
+
+impl Workspace {
+    /// The collaborators in the call following the local user in this workspace's project, or
+    /// outside of any project.
+    pub fn followers(&self, cx: &AppContext) -> Vec<PeerId> {
+        let Some(room) = self.active_call().and_then(|call| call.read(cx).room()) else {
+            return Vec::new();
+        };
+        let room = room.read(cx);
+        let project_id = self.project.read(cx).remote_id();
+        let mut seen = HashSet::default();
+        self.app_state
+            .workspace_store
+            .read(cx)
+            .followers
+            .iter()
+            .filter(|follower| follower.project_id.is_none() || follower.project_id == project_id)
+            .map(|follower| follower.peer_id)
+            .filter(|peer_id| {
+                room.remote_participant_for_peer_id(*peer_id).is_some() && seen.insert(*peer_id)
+            })
+            .collect()
+    }
+
+    fn followers_changed(&mut self, new_follower: Option<PeerId>, cx: &mut ViewContext<Self>) {
+        cx.emit(Event::FollowersChanged);
+        if let Some(peer_id) = new_follower {
+            if self.followers(cx).contains(&peer_id) {
+                self.announce_new_follower(peer_id, cx);
+            }
+        }
+    }
+
+    fn announce_new_follower(&mut self, peer_id: PeerId, cx: &mut ViewContext<Self>) {
+        let now = cx.background_executor().now();
+        if self
+            .last_new_follower_notification
+            .map_or(false, |shown_at| {
+                now.duration_since(shown_at) < NEW_FOLLOWER_NOTIFICATION_INTERVAL
+            })
+        {
+            return;
+        }
+        let Some(github_login) = self
+            .active_call()
+            .and_then(|call| call.read(cx).room())
+            .and_then(|room| {
+                let participant = room.read(cx).remote_participant_for_peer_id(peer_id)?;
+                Some(participant.user.github_login.clone())
+            })
+        else {
+            return;
+        };
+        self.last_new_follower_notification = Some(now);
+        self.show_suppressible_notification(NEW_FOLLOWER_NOTIFICATION, cx, |_| {
+            MessageNotification::new(format!("{github_login} started following you"))
+        });
+    }
+
+    /// Forgets the followers of a collaborator who left this workspace's project.
+    pub(crate) fn remove_followers_of_project(
+        &mut self,
+        peer_id: PeerId,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let project_id = self.project.read(cx).remote_id();
+        self.app_state.workspace_store.update(cx, |store, cx| {
+            store.remove_followers(
+                |follower| follower.peer_id == peer_id && follower.project_id == project_id,
+                cx,
+            )
+        });
+    }
+
+    /// Forgets all followers once the call ended.
+    pub(crate) fn clear_followers(&mut self, cx: &mut ViewContext<Self>) {
+        self.app_state
+            .workspace_store
+            .update(cx, |store, cx| store.remove_followers(|_| true, cx));
+        self.last_new_follower_notification = None;
+    }
+}
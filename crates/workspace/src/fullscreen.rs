@@ -0,0 +1,168 @@
+use gpui::{ViewContext, WindowContext};
+use settings::Settings;
+use util::ResultExt;
+
+use crate::{persistence::DB, Event, Workspace, WorkspaceSettings};
+
+/// What the workspace knows about the fullscreen state of its window.
+#[derive(Default)]
+pub(crate) struct FullscreenState {
+    /// Whether the window was fullscreen when last checked.
+    fullscreen: bool,
+    /// The centered layout to go back to when leaving fullscreen, if the
+    /// `centered_in_fullscreen` setting turned it on.
+    centered_layout_before: Option<bool>,
+}
+
+impl Workspace {
+    /// Whether the window of the workspace is in native fullscreen.
+    pub fn is_fullscreen(&self, cx: &WindowContext) -> bool {
+        cx.is_fullscreen()
+    }
+
+    /// Enters or exits the native fullscreen of the window.
+    pub fn set_fullscreen(&mut self, fullscreen: bool, cx: &mut ViewContext<Self>) {
+        if cx.is_fullscreen() != fullscreen {
+            cx.toggle_fullscreen();
+        }
+        self.check_fullscreen_changed(cx);
+    }
+
+    /// Enters fullscreen again if the window was fullscreen when the workspace was last open.
+    pub(crate) fn restore_fullscreen(&mut self, cx: &mut ViewContext<Self>) {
+        let fullscreen = self
+            .database_id()
+            .and_then(|database_id| DB.stored_fullscreen(database_id).log_err())
+            .flatten()
+            .flatten()
+            .unwrap_or(false);
+        if fullscreen {
+            self.set_fullscreen(true, cx);
+        } else {
+            self.check_fullscreen_changed(cx);
+        }
+    }
+
+    /// The centered layout chosen by the user, rather than the one turned on for fullscreen.
+    pub(crate) fn persisted_centered_layout(&self) -> bool {
+        self.fullscreen_state
+            .centered_layout_before
+            .unwrap_or(self.centered_layout)
+    }
+
+    /// Keeps the centered layout chosen while in fullscreen when leaving it.
+    pub(crate) fn forget_centered_layout_before_fullscreen(&mut self) {
+        self.fullscreen_state.centered_layout_before = None;
+    }
+
+    /// Reacts to the window entering or leaving fullscreen, which may happen without going
+    /// through [`Workspace::set_fullscreen`], e.g. with the window's own controls.
+    pub(crate) fn check_fullscreen_changed(&mut self, cx: &mut ViewContext<Self>) {
+        let fullscreen = cx.is_fullscreen();
+        if fullscreen == self.fullscreen_state.fullscreen {
+            return;
+        }
+        self.fullscreen_state.fullscreen = fullscreen;
+
+        if fullscreen {
+            if WorkspaceSettings::get_global(cx).centered_in_fullscreen && !self.centered_layout {
+                self.fullscreen_state.centered_layout_before = Some(self.centered_layout);
+                self.centered_layout = true;
+            }
+        } else if let Some(centered_layout) = self.fullscreen_state.centered_layout_before.take() {
+            self.centered_layout = centered_layout;
+        }
+
+        if let Some(database_id) = self.database_id() {
+            cx.background_executor()
+                .spawn(DB.set_fullscreen(database_id, fullscreen))
+                .detach_and_log_err(cx);
+        }
+        cx.emit(Event::FullscreenChanged(fullscreen));
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::init_test, ToggleCenteredLayout};
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use project::Project;
+    use settings::SettingsStore;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[gpui::test]
+    async fn test_fullscreen(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.centered_in_fullscreen = Some(true);
+                });
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let workspace_id = DB.next_id().await.unwrap();
+        workspace.update(cx, |workspace, _| {
+            workspace.database_id = Some(workspace_id)
+        });
+        let events = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let events = events.clone();
+            cx.subscribe(&workspace, move |_, event, _| {
+                if let Event::FullscreenChanged(fullscreen) = event {
+                    events.borrow_mut().push(*fullscreen);
+                }
+            })
+            .detach();
+        });
+
+        // Entering fullscreen turns the centered layout on, and is saved.
+        workspace.update(cx, |workspace, cx| {
+            workspace.set_fullscreen(true, cx);
+            assert!(workspace.is_fullscreen(cx));
+            assert!(workspace.centered_layout);
+            assert!(!workspace.persisted_centered_layout());
+        });
+        cx.run_until_parked();
+        assert_eq!(*events.borrow(), [true]);
+        assert_eq!(
+            DB.stored_fullscreen(workspace_id).unwrap(),
+            Some(Some(true))
+        );
+
+        // Leaving it restores the previous layout.
+        workspace.update(cx, |workspace, cx| {
+            workspace.set_fullscreen(false, cx);
+            assert!(!workspace.is_fullscreen(cx));
+            assert!(!workspace.centered_layout);
+        });
+        cx.run_until_parked();
+        assert_eq!(*events.borrow(), [true, false]);
+
+        // A centered layout chosen while in fullscreen is kept when leaving it.
+        workspace.update(cx, |workspace, cx| {
+            workspace.set_fullscreen(true, cx);
+            workspace.toggle_centered_layout(&ToggleCenteredLayout, cx);
+            workspace.toggle_centered_layout(&ToggleCenteredLayout, cx);
+            workspace.set_fullscreen(false, cx);
+            assert!(workspace.centered_layout);
+        });
+        cx.run_until_parked();
+        assert_eq!(*events.borrow(), [true, false, true, false]);
+
+        // Restoring the workspace enters fullscreen again if it was saved as such.
+        DB.set_fullscreen(workspace_id, true).await.unwrap();
+        workspace.update(cx, |workspace, cx| {
+            workspace.restore_fullscreen(cx);
+            assert!(workspace.is_fullscreen(cx));
+        });
+        cx.run_until_parked();
+        assert_eq!(*events.borrow(), [true, false, true, false, true]);
+    }
+}
@@ -3,8 +3,8 @@ use crate::{
     persistence::model::ItemId,
     searchable::SearchableItemHandle,
     workspace_settings::{AutosaveSetting, WorkspaceSettings},
-    DelayedDebouncedEditAction, FollowableViewRegistry, ItemNavHistory, SerializableItemRegistry,
-    ToolbarItemLocation, ViewId, Workspace, WorkspaceId,
+    DelayedDebouncedEditAction, FollowableViewRegistry, ItemNavHistory, ReservationKind,
+    SerializableItemRegistry, StableItemId, ToolbarItemLocation, ViewId, Workspace, WorkspaceId,
 };
 use anyhow::Result;
 use client::{
@@ -19,13 +19,14 @@ use gpui::{
 };
 use project::{Project, ProjectEntryId, ProjectPath};
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use settings::{Settings, SettingsLocation, SettingsSources};
 use smallvec::SmallVec;
 use std::{
     any::{Any, TypeId},
     cell::RefCell,
     ops::Range,
+    path::Path,
     rc::Rc,
     sync::Arc,
     time::Duration,
@@ -150,6 +151,19 @@ pub enum ItemEvent {
     UpdateTab,
     UpdateBreadcrumbs,
     Edit,
+    /// The [`Item::activity_indicator`] of the item changed.
+    UpdateActivity,
+}
+
+/// Something that happened to an item in the background, shown on its tab.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum ActivityKind {
+    /// The item's contents were changed outside of it, e.g. on disk.
+    ExternalEdit,
+    /// The item's contents were changed both in it and outside of it.
+    Conflict,
+    /// A task is running on the item's contents.
+    TaskRunning,
 }
 
 // TODO: Combine this with existing HighlightedText struct?
@@ -177,6 +191,33 @@ impl TabContentParams {
     }
 }
 
+/// The data an item passes to [`Item::navigate`] to restore its position, stored with the
+/// workspace so that it survives restarts. It is tagged with the kind of the item, so that
+/// data saved by another kind of item, or by an older version of it, is ignored.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerializableNavigationData {
+    kind: Arc<str>,
+    data: serde_json::Value,
+}
+
+impl SerializableNavigationData {
+    pub fn new<T: Serialize>(kind: &str, data: &T) -> Option<Self> {
+        Some(Self {
+            kind: kind.into(),
+            data: serde_json::to_value(data).log_err()?,
+        })
+    }
+
+    /// Decodes the data, or returns `None` if it was saved for another kind of item or
+    /// doesn't match `T` anymore.
+    pub fn decode<T: DeserializeOwned>(&self, kind: &str) -> Option<T> {
+        if &*self.kind != kind {
+            return None;
+        }
+        serde_json::from_value(self.data.clone()).ok()
+    }
+}
+
 pub trait Item: FocusableView + EventEmitter<Self::Event> {
     type Event;
 
@@ -210,9 +251,28 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
     fn deactivated(&mut self, _: &mut ViewContext<Self>) {}
     fn discarded(&self, _project: Model<Project>, _cx: &mut ViewContext<Self>) {}
     fn workspace_deactivated(&mut self, _: &mut ViewContext<Self>) {}
+    /// Called when a tool outside of Zed starts or stops rewriting the item's file, see
+    /// [`Workspace::lock_item_for_external_update`]. Meanwhile, the item should hold off
+    /// reloading the file and reporting conflicts with it, as it's reloaded once the tool is
+    /// done.
+    fn set_external_update_in_progress(&mut self, _: bool, _: &mut ViewContext<Self>) {}
     fn navigate(&mut self, _: Box<dyn Any>, _: &mut ViewContext<Self>) -> bool {
         false
     }
+    /// Captures the item's position, like its scroll position and selections, to restore it
+    /// with [`Item::navigate`] when the workspace is loaded again.
+    fn serialize_navigation_data(&self, _: &AppContext) -> Option<SerializableNavigationData> {
+        None
+    }
+    /// Turns the data from [`Item::serialize_navigation_data`] back into the data
+    /// [`Item::navigate`] accepts, or returns `None` if it is stale.
+    fn deserialize_navigation_data(
+        &self,
+        _: &SerializableNavigationData,
+        _: &AppContext,
+    ) -> Option<Box<dyn Any>> {
+        None
+    }
     fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
         None
     }
@@ -254,6 +314,19 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
     fn has_conflict(&self, _: &AppContext) -> bool {
         false
     }
+    /// Whether the files the item shows changed on disk since it last loaded or saved them,
+    /// see [`crate::ReloadAllItems`].
+    fn has_external_changes(&self, _: &AppContext) -> bool {
+        false
+    }
+    /// Returns the background activity to show on the tab, if any. Items emit
+    /// [`ItemEvent::UpdateActivity`] when it changes.
+    fn activity_indicator(&self, _: &AppContext) -> Option<ActivityKind> {
+        None
+    }
+    /// Called when the user has seen the `activity`, e.g. when [`ActivityKind::ExternalEdit`]
+    /// items are activated.
+    fn clear_activity(&mut self, _activity: ActivityKind, _: &mut ViewContext<Self>) {}
     fn can_save(&self, _cx: &AppContext) -> bool {
         false
     }
@@ -281,6 +354,21 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
         unimplemented!("reload() must be implemented if can_save() returns true")
     }
 
+    /// Writes a snapshot of the item's unsaved contents into `dir`, so that they can be
+    /// brought back with [`Item::restore_recovery`] after a crash.
+    fn write_recovery(&self, _dir: &Path, _cx: &mut ViewContext<Self>) -> Option<Task<Result<()>>> {
+        None
+    }
+
+    /// Replaces the item's contents with a snapshot previously written by [`Item::write_recovery`].
+    fn restore_recovery(
+        &mut self,
+        _dir: &Path,
+        _cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        None
+    }
+
     fn act_as_type<'a>(
         &'a self,
         type_id: TypeId,
@@ -319,6 +407,39 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
     fn preserve_preview(&self, _cx: &AppContext) -> bool {
         false
     }
+
+    /// Whether the item may be closed to make room for a new preview item while it is
+    /// the pane's preview item. Items returning `false` are promoted to permanent tabs instead.
+    fn preview_replaceable(&self, _cx: &AppContext) -> bool {
+        true
+    }
+
+    /// Orders the item relative to the others when saving all items. Items with lower hints
+    /// are saved first, and items with equal hints are saved in the order of their panes.
+    /// Items are saved one after the other rather than in parallel.
+    fn save_ordering_hint(&self, _cx: &AppContext) -> i32 {
+        0
+    }
+
+    /// Whether the item is a transient one, such as a generated report, see
+    /// [`Workspace::open_scratch`]. Ephemeral items are never serialized, and their unsaved
+    /// changes are discarded without prompting.
+    fn is_ephemeral(&self, _cx: &AppContext) -> bool {
+        false
+    }
+
+    /// The contents of an untitled item, one that isn't backed by a file, from which it can
+    /// be recreated in another workspace, see [`crate::register_untitled_item_builder`].
+    fn unsaved_text(&self, _cx: &AppContext) -> Option<String> {
+        None
+    }
+
+    /// The kind of item this is for the purpose of pane reservations, see
+    /// [`Workspace::set_pane_reserved_for`]. Items are only opened automatically
+    /// in panes reserved for their kind, or in panes that aren't reserved.
+    fn reservation_kind(&self, _cx: &AppContext) -> ReservationKind {
+        ReservationKind::Editor
+    }
 }
 
 pub trait SerializableItem: Item {
@@ -347,6 +468,10 @@ pub trait SerializableItem: Item {
     ) -> Option<Task<Result<()>>>;
 
     fn should_serialize(&self, event: &Self::Event) -> bool;
+
+    /// Called once the item is restored with the [`StableItemId`] it had in the previous
+    /// session, for items keeping state of their own under it.
+    fn restore_stable_id(&mut self, _stable_id: StableItemId, _cx: &mut ViewContext<Self>) {}
 }
 
 pub trait SerializableItemHandle: ItemHandle {
@@ -358,6 +483,7 @@ pub trait SerializableItemHandle: ItemHandle {
         cx: &mut WindowContext,
     ) -> Option<Task<Result<()>>>;
     fn should_serialize(&self, event: &dyn Any, cx: &AppContext) -> bool;
+    fn restore_stable_id(&self, stable_id: StableItemId, cx: &mut WindowContext);
 }
 
 impl<T> SerializableItemHandle for View<T>
@@ -384,6 +510,10 @@ where
             .downcast_ref::<T::Event>()
             .map_or(false, |event| self.read(cx).should_serialize(event))
     }
+
+    fn restore_stable_id(&self, stable_id: StableItemId, cx: &mut WindowContext) {
+        self.update(cx, |this, cx| this.restore_stable_id(stable_id, cx))
+    }
 }
 
 pub trait ItemHandle: 'static + Send {
@@ -394,6 +524,7 @@ pub trait ItemHandle: 'static + Send {
     ) -> gpui::Subscription;
     fn focus_handle(&self, cx: &WindowContext) -> FocusHandle;
     fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString>;
+    fn tab_content_text(&self, cx: &WindowContext) -> Option<SharedString>;
     fn tab_description(&self, detail: usize, cx: &AppContext) -> Option<SharedString>;
     fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement;
     fn tab_icon(&self, cx: &WindowContext) -> Option<Icon>;
@@ -424,12 +555,22 @@ pub trait ItemHandle: 'static + Send {
     fn deactivated(&self, cx: &mut WindowContext);
     fn discarded(&self, project: Model<Project>, cx: &mut WindowContext);
     fn workspace_deactivated(&self, cx: &mut WindowContext);
+    fn set_external_update_in_progress(&self, in_progress: bool, cx: &mut WindowContext);
     fn navigate(&self, data: Box<dyn Any>, cx: &mut WindowContext) -> bool;
+    fn navigation_data(&self, cx: &AppContext) -> Option<SerializableNavigationData>;
+    fn restore_navigation_data(
+        &self,
+        data: &SerializableNavigationData,
+        cx: &mut WindowContext,
+    ) -> bool;
     fn item_id(&self) -> EntityId;
     fn to_any(&self) -> AnyView;
     fn is_dirty(&self, cx: &AppContext) -> bool;
     fn has_deleted_file(&self, cx: &AppContext) -> bool;
     fn has_conflict(&self, cx: &AppContext) -> bool;
+    fn has_external_changes(&self, cx: &AppContext) -> bool;
+    fn activity_indicator(&self, cx: &AppContext) -> Option<ActivityKind>;
+    fn clear_activity(&self, activity: ActivityKind, cx: &mut WindowContext);
     fn can_save(&self, cx: &AppContext) -> bool;
     fn save(
         &self,
@@ -444,6 +585,8 @@ pub trait ItemHandle: 'static + Send {
         cx: &mut WindowContext,
     ) -> Task<Result<()>>;
     fn reload(&self, project: Model<Project>, cx: &mut WindowContext) -> Task<Result<()>>;
+    fn write_recovery(&self, dir: &Path, cx: &mut WindowContext) -> Option<Task<Result<()>>>;
+    fn restore_recovery(&self, dir: &Path, cx: &mut WindowContext) -> Option<Task<Result<()>>>;
     fn act_as_type(&self, type_id: TypeId, cx: &AppContext) -> Option<AnyView>;
     fn to_followable_item_handle(&self, cx: &AppContext) -> Option<Box<dyn FollowableItemHandle>>;
     fn to_serializable_item_handle(
@@ -463,6 +606,11 @@ pub trait ItemHandle: 'static + Send {
     fn downgrade_item(&self) -> Box<dyn WeakItemHandle>;
     fn workspace_settings<'a>(&self, cx: &'a AppContext) -> &'a WorkspaceSettings;
     fn preserve_preview(&self, cx: &AppContext) -> bool;
+    fn preview_replaceable(&self, cx: &AppContext) -> bool;
+    fn save_ordering_hint(&self, cx: &AppContext) -> i32;
+    fn is_ephemeral(&self, cx: &AppContext) -> bool;
+    fn unsaved_text(&self, cx: &AppContext) -> Option<String>;
+    fn reservation_kind(&self, cx: &AppContext) -> ReservationKind;
 }
 
 pub trait WeakItemHandle: Send + Sync {
@@ -501,6 +649,10 @@ impl<T: Item> ItemHandle for View<T> {
         self.read(cx).tab_tooltip_text(cx)
     }
 
+    fn tab_content_text(&self, cx: &WindowContext) -> Option<SharedString> {
+        self.read(cx).tab_content_text(cx)
+    }
+
     fn telemetry_event_text(&self, cx: &WindowContext) -> Option<&'static str> {
         self.read(cx).telemetry_event_text()
     }
@@ -617,6 +769,9 @@ impl<T: Item> ItemHandle for View<T> {
             this.set_nav_history(history, cx);
             this.added_to_workspace(workspace, cx);
         });
+        if workspace.is_locked_for_external_update(self, cx) {
+            self.set_external_update_in_progress(true, cx);
+        }
 
         if let Some(serializable_item) = self.to_serializable_item_handle(cx) {
             workspace
@@ -717,18 +872,29 @@ impl<T: Item> ItemHandle for View<T> {
 
                         ItemEvent::UpdateTab => {
                             pane.update(cx, |_, cx| {
-                                cx.emit(pane::Event::ChangeItemTitle);
+                                cx.emit(pane::Event::ChangeItemTitle {
+                                    item_id: item.item_id(),
+                                });
                                 cx.notify();
                             });
                         }
 
+                        ItemEvent::UpdateActivity => {
+                            // Only the tab changes, so the workspace doesn't need to be notified.
+                            pane.update(cx, |_, cx| cx.notify());
+                        }
+
                         ItemEvent::Edit => {
+                            workspace.mark_item_edited_for_recovery(item.item_id());
                             let autosave = item.workspace_settings(cx).autosave;
 
                             if let AutosaveSetting::AfterDelay { milliseconds } = autosave {
                                 let delay = Duration::from_millis(milliseconds);
                                 let item = item.clone();
                                 pending_autosave.fire_new(delay, cx, move |workspace, cx| {
+                                    if workspace.skip_autosave_for_external_update(&item, cx) {
+                                        return Task::ready(Ok(()));
+                                    }
                                     Pane::autosave_item(&item, workspace.project().clone(), cx)
                                 });
                             }
@@ -742,7 +908,9 @@ impl<T: Item> ItemHandle for View<T> {
 
             cx.on_blur(&self.focus_handle(cx), move |workspace, cx| {
                 if let Some(item) = weak_item.upgrade() {
-                    if item.workspace_settings(cx).autosave == AutosaveSetting::OnFocusChange {
+                    if item.workspace_settings(cx).autosave == AutosaveSetting::OnFocusChange
+                        && !workspace.skip_autosave_for_external_update(&item, cx)
+                    {
                         Pane::autosave_item(&item, workspace.project.clone(), cx)
                             .detach_and_log_err(cx);
                     }
@@ -776,10 +944,33 @@ impl<T: Item> ItemHandle for View<T> {
         self.update(cx, |this, cx| this.workspace_deactivated(cx));
     }
 
+    fn set_external_update_in_progress(&self, in_progress: bool, cx: &mut WindowContext) {
+        self.update(cx, |this, cx| {
+            this.set_external_update_in_progress(in_progress, cx)
+        });
+    }
+
     fn navigate(&self, data: Box<dyn Any>, cx: &mut WindowContext) -> bool {
         self.update(cx, |this, cx| this.navigate(data, cx))
     }
 
+    fn navigation_data(&self, cx: &AppContext) -> Option<SerializableNavigationData> {
+        self.read(cx).serialize_navigation_data(cx)
+    }
+
+    fn restore_navigation_data(
+        &self,
+        data: &SerializableNavigationData,
+        cx: &mut WindowContext,
+    ) -> bool {
+        self.update(cx, |this, cx| {
+            match this.deserialize_navigation_data(data, cx) {
+                Some(data) => this.navigate(data, cx),
+                None => false,
+            }
+        })
+    }
+
     fn item_id(&self) -> EntityId {
         self.entity_id()
     }
@@ -800,6 +991,18 @@ impl<T: Item> ItemHandle for View<T> {
         self.read(cx).has_conflict(cx)
     }
 
+    fn has_external_changes(&self, cx: &AppContext) -> bool {
+        self.read(cx).has_external_changes(cx)
+    }
+
+    fn activity_indicator(&self, cx: &AppContext) -> Option<ActivityKind> {
+        self.read(cx).activity_indicator(cx)
+    }
+
+    fn clear_activity(&self, activity: ActivityKind, cx: &mut WindowContext) {
+        self.update(cx, |this, cx| this.clear_activity(activity, cx));
+    }
+
     fn can_save(&self, cx: &AppContext) -> bool {
         self.read(cx).can_save(cx)
     }
@@ -826,6 +1029,14 @@ impl<T: Item> ItemHandle for View<T> {
         self.update(cx, |item, cx| item.reload(project, cx))
     }
 
+    fn write_recovery(&self, dir: &Path, cx: &mut WindowContext) -> Option<Task<Result<()>>> {
+        self.update(cx, |item, cx| item.write_recovery(dir, cx))
+    }
+
+    fn restore_recovery(&self, dir: &Path, cx: &mut WindowContext) -> Option<Task<Result<()>>> {
+        self.update(cx, |item, cx| item.restore_recovery(dir, cx))
+    }
+
     fn act_as_type<'a>(&'a self, type_id: TypeId, cx: &'a AppContext) -> Option<AnyView> {
         self.read(cx).act_as_type(type_id, self, cx)
     }
@@ -870,12 +1081,35 @@ impl<T: Item> ItemHandle for View<T> {
         &self,
         cx: &AppContext,
     ) -> Option<Box<dyn SerializableItemHandle>> {
+        if self.is_ephemeral(cx) {
+            return None;
+        }
         SerializableItemRegistry::view_to_serializable_item_handle(self.to_any(), cx)
     }
 
     fn preserve_preview(&self, cx: &AppContext) -> bool {
         self.read(cx).preserve_preview(cx)
     }
+
+    fn preview_replaceable(&self, cx: &AppContext) -> bool {
+        self.read(cx).preview_replaceable(cx)
+    }
+
+    fn save_ordering_hint(&self, cx: &AppContext) -> i32 {
+        self.read(cx).save_ordering_hint(cx)
+    }
+
+    fn is_ephemeral(&self, cx: &AppContext) -> bool {
+        self.read(cx).is_ephemeral(cx)
+    }
+
+    fn unsaved_text(&self, cx: &AppContext) -> Option<String> {
+        self.read(cx).unsaved_text(cx)
+    }
+
+    fn reservation_kind(&self, cx: &AppContext) -> ReservationKind {
+        self.read(cx).reservation_kind(cx)
+    }
 }
 
 impl From<Box<dyn ItemHandle>> for AnyView {
@@ -1051,17 +1285,33 @@ impl<T: FollowableItem> WeakFollowableItemHandle for WeakView<T> {
 
 #[cfg(any(test, feature = "test-support"))]
 pub mod test {
-    use super::{Item, ItemEvent, SerializableItem, TabContentParams};
-    use crate::{ItemId, ItemNavHistory, Workspace, WorkspaceId};
+    use super::{
+        ActivityKind, Item, ItemEvent, SerializableItem, SerializableNavigationData,
+        TabContentParams,
+    };
+    use crate::{ItemId, ItemNavHistory, StableItemId, Workspace, WorkspaceId};
     use gpui::{
-        AnyElement, AppContext, Context as _, EntityId, EventEmitter, FocusableView,
+        AnyElement, AppContext, Context as _, EntityId, EventEmitter, FocusableView, Global,
         InteractiveElement, IntoElement, Model, Render, SharedString, Task, View, ViewContext,
         VisualContext, WeakView,
     };
     use project::{Project, ProjectEntryId, ProjectPath, WorktreeId};
-    use std::{any::Any, cell::Cell, path::Path};
+    use std::{
+        any::Any,
+        cell::Cell,
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+        path::Path,
+        time::Duration,
+    };
     use ui::WindowContext;
 
+    /// Makes [`TestItem`]s take this long to deserialize.
+    #[derive(Clone, Copy)]
+    pub struct TestItemDeserializationDelay(pub Duration);
+
+    impl Global for TestItemDeserializationDelay {}
+
     pub struct TestProjectItem {
         pub entry_id: Option<ProjectEntryId>,
         pub project_path: Option<ProjectPath>,
@@ -1076,8 +1326,15 @@ pub mod test {
         pub save_as_count: usize,
         pub reload_count: usize,
         pub is_dirty: bool,
+        pub is_ephemeral: bool,
         pub is_singleton: bool,
         pub has_conflict: bool,
+        pub has_external_changes: bool,
+        pub external_update_in_progress: bool,
+        pub activity: Option<ActivityKind>,
+        pub restored_stable_id: Option<StableItemId>,
+        pub preview_replaceable: bool,
+        pub save_ordering_hint: i32,
         pub project_items: Vec<Model<TestProjectItem>>,
         pub nav_history: Option<ItemNavHistory>,
         pub tab_descriptions: Option<Vec<&'static str>>,
@@ -1087,13 +1344,40 @@ pub mod test {
     }
 
     impl project::ProjectItem for TestProjectItem {
+        /// Opens any path, except for images (`.png`), which stand for the files no item can
+        /// open. Paths outside of the project's worktrees get an entry id of their own, so that
+        /// they're only opened once, like the others.
         fn try_open(
-            _project: &Model<Project>,
-            _path: &ProjectPath,
-            _cx: &mut AppContext,
+            project: &Model<Project>,
+            path: &ProjectPath,
+            cx: &mut AppContext,
         ) -> Option<Task<gpui::Result<Model<Self>>>> {
-            None
+            if path
+                .path
+                .extension()
+                .map_or(false, |extension| extension == "png")
+            {
+                return None;
+            }
+            let entry_id = project
+                .read(cx)
+                .entry_for_path(path, cx)
+                .map(|entry| entry.id)
+                .unwrap_or_else(|| {
+                    let mut hasher = DefaultHasher::new();
+                    path.hash(&mut hasher);
+                    ProjectEntryId::from_proto(hasher.finish())
+                });
+            let project_path = path.clone();
+            Some(cx.spawn(|mut cx| async move {
+                cx.new_model(|_| Self {
+                    entry_id: Some(entry_id),
+                    project_path: Some(project_path),
+                    is_dirty: false,
+                })
+            }))
         }
+
         fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
             self.entry_id
         }
@@ -1107,6 +1391,18 @@ pub mod test {
         }
     }
 
+    impl super::ProjectItem for TestItem {
+        type Item = TestProjectItem;
+
+        fn for_project_item(
+            _: Model<Project>,
+            item: Model<TestProjectItem>,
+            cx: &mut ViewContext<Self>,
+        ) -> Self {
+            Self::new(cx).with_project_items(&[item])
+        }
+    }
+
     pub enum TestItemEvent {
         Edit,
     }
@@ -1143,7 +1439,14 @@ pub mod test {
                 save_as_count: 0,
                 reload_count: 0,
                 is_dirty: false,
+                is_ephemeral: false,
                 has_conflict: false,
+                has_external_changes: false,
+                external_update_in_progress: false,
+                activity: None,
+                restored_stable_id: None,
+                preview_replaceable: true,
+                save_ordering_hint: 0,
                 project_items: Vec::new(),
                 is_singleton: true,
                 nav_history: None,
@@ -1176,11 +1479,36 @@ pub mod test {
             self
         }
 
+        pub fn with_ephemeral(mut self, ephemeral: bool) -> Self {
+            self.is_ephemeral = ephemeral;
+            self
+        }
+
         pub fn with_conflict(mut self, has_conflict: bool) -> Self {
             self.has_conflict = has_conflict;
             self
         }
 
+        pub fn with_external_changes(mut self, has_external_changes: bool) -> Self {
+            self.has_external_changes = has_external_changes;
+            self
+        }
+
+        pub fn with_activity(mut self, activity: Option<ActivityKind>) -> Self {
+            self.activity = activity;
+            self
+        }
+
+        pub fn with_preview_replaceable(mut self, preview_replaceable: bool) -> Self {
+            self.preview_replaceable = preview_replaceable;
+            self
+        }
+
+        pub fn with_save_ordering_hint(mut self, save_ordering_hint: i32) -> Self {
+            self.save_ordering_hint = save_ordering_hint;
+            self
+        }
+
         pub fn with_project_items(mut self, items: &[Model<TestProjectItem>]) -> Self {
             self.project_items.clear();
             self.project_items.extend(items.iter().cloned());
@@ -1239,6 +1567,10 @@ pub mod test {
             None
         }
 
+        fn tab_content_text(&self, _: &WindowContext) -> Option<SharedString> {
+            Some(self.label.clone().into())
+        }
+
         fn tab_content(
             &self,
             params: TabContentParams,
@@ -1266,6 +1598,22 @@ pub mod test {
             self.nav_history = Some(history);
         }
 
+        fn set_external_update_in_progress(
+            &mut self,
+            in_progress: bool,
+            _: &mut ViewContext<Self>,
+        ) {
+            self.external_update_in_progress = in_progress;
+        }
+
+        fn write_recovery(
+            &self,
+            _: &Path,
+            _: &mut ViewContext<Self>,
+        ) -> Option<Task<anyhow::Result<()>>> {
+            Some(Task::ready(Ok(())))
+        }
+
         fn navigate(&mut self, state: Box<dyn Any>, _: &mut ViewContext<Self>) -> bool {
             let state = *state.downcast::<String>().unwrap_or_default();
             if state != self.state {
@@ -1276,6 +1624,18 @@ pub mod test {
             }
         }
 
+        fn serialize_navigation_data(&self, _: &AppContext) -> Option<SerializableNavigationData> {
+            SerializableNavigationData::new("TestItem", &self.state)
+        }
+
+        fn deserialize_navigation_data(
+            &self,
+            data: &SerializableNavigationData,
+            _: &AppContext,
+        ) -> Option<Box<dyn Any>> {
+            Some(Box::new(data.decode::<String>("TestItem")?))
+        }
+
         fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
             self.push_to_nav_history(cx);
         }
@@ -1295,8 +1655,15 @@ pub mod test {
                 save_as_count: self.save_as_count,
                 reload_count: self.reload_count,
                 is_dirty: self.is_dirty,
+                is_ephemeral: self.is_ephemeral,
                 is_singleton: self.is_singleton,
                 has_conflict: self.has_conflict,
+                has_external_changes: self.has_external_changes,
+                external_update_in_progress: self.external_update_in_progress,
+                activity: self.activity,
+                restored_stable_id: None,
+                preview_replaceable: self.preview_replaceable,
+                save_ordering_hint: self.save_ordering_hint,
                 project_items: self.project_items.clone(),
                 nav_history: None,
                 tab_descriptions: None,
@@ -1311,10 +1678,44 @@ pub mod test {
             self.is_dirty
         }
 
+        fn preview_replaceable(&self, _: &AppContext) -> bool {
+            self.preview_replaceable
+        }
+
+        fn save_ordering_hint(&self, _: &AppContext) -> i32 {
+            self.save_ordering_hint
+        }
+
+        fn is_ephemeral(&self, _: &AppContext) -> bool {
+            self.is_ephemeral
+        }
+
+        fn unsaved_text(&self, cx: &AppContext) -> Option<String> {
+            self.project_items
+                .iter()
+                .all(|item| item.read(cx).project_path.is_none())
+                .then(|| self.state.clone())
+        }
+
         fn has_conflict(&self, _: &AppContext) -> bool {
             self.has_conflict
         }
 
+        fn has_external_changes(&self, _: &AppContext) -> bool {
+            self.has_external_changes
+        }
+
+        fn activity_indicator(&self, _: &AppContext) -> Option<ActivityKind> {
+            self.activity
+        }
+
+        fn clear_activity(&mut self, activity: ActivityKind, cx: &mut ViewContext<Self>) {
+            if self.activity == Some(activity) {
+                self.activity = None;
+                cx.emit(ItemEvent::UpdateActivity);
+            }
+        }
+
         fn can_save(&self, cx: &AppContext) -> bool {
             !self.project_items.is_empty()
                 && self
@@ -1352,6 +1753,7 @@ pub mod test {
         ) -> Task<anyhow::Result<()>> {
             self.reload_count += 1;
             self.is_dirty = false;
+            self.has_external_changes = false;
             Task::ready(Ok(()))
         }
     }
@@ -1368,6 +1770,12 @@ pub mod test {
             _item_id: ItemId,
             cx: &mut WindowContext,
         ) -> Task<anyhow::Result<View<Self>>> {
+            if let Some(TestItemDeserializationDelay(delay)) = cx.try_global().copied() {
+                return cx.spawn(|mut cx| async move {
+                    cx.background_executor().timer(delay).await;
+                    cx.new_view(|cx| Self::new_deserialized(workspace_id, cx))
+                });
+            }
             let view = cx.new_view(|cx| Self::new_deserialized(workspace_id, cx));
             Task::ready(Ok(view))
         }
@@ -1399,5 +1807,9 @@ pub mod test {
         fn should_serialize(&self, _event: &Self::Event) -> bool {
             false
         }
+
+        fn restore_stable_id(&mut self, stable_id: StableItemId, _: &mut ViewContext<Self>) {
+            self.restored_stable_id = Some(stable_id);
+        }
     }
 }
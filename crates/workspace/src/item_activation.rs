@@ -0,0 +1,331 @@
+use gpui::{View, ViewContext};
+
+use crate::{item::ActivityKind, Pane, Workspace};
+
+/// What made an item active, see [`Workspace::finalize_item_activation`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ItemActivationOrigin {
+    /// The pane activated the item, because it was opened, navigated to, or placed by
+    /// following a collaborator. `local` is set for the activations made by the user.
+    Pane { local: bool },
+    /// The pane of the item got focused.
+    Focus,
+    /// The item was restored along with the rest of the workspace.
+    Restore,
+}
+
+impl Workspace {
+    /// Updates the workspace for the active item of `pane` having changed, or for `pane`
+    /// having become active. The items opened, previewed, reopened from the navigation
+    /// history, restored, or placed by following a collaborator all go through here, so that
+    /// the workspace events are emitted in the same order whichever way the item got there:
+    ///
+    /// 1. [`crate::Event::ItemAdded`], when the item was just added. Panes report added items
+    ///    before activating them.
+    /// 2. [`crate::Event::ActiveItemChanged`], once, when the active item of the workspace
+    ///    isn't the one reported last.
+    /// 3. [`crate::Event::ZoomChanged`], when focusing the pane changed what is zoomed.
+    pub(crate) fn finalize_item_activation(
+        &mut self,
+        pane: &View<Pane>,
+        origin: ItemActivationOrigin,
+        cx: &mut ViewContext<Self>,
+    ) {
+        pane.model.update(cx, |pane, _| {
+            pane.track_alternate_file_items();
+        });
+        self.record_item_activation(pane, cx);
+        self.track_alternate_item(pane, cx);
+        if origin == (ItemActivationOrigin::Pane { local: true }) {
+            self.unfollow_in_pane(pane, cx);
+        }
+        if origin != ItemActivationOrigin::Focus {
+            if let Some(item) = pane.read(cx).active_item() {
+                self.mirror_item(pane, item.as_ref(), cx);
+                // The external changes are seen once the item is shown.
+                if item.activity_indicator(cx) == Some(ActivityKind::ExternalEdit) {
+                    item.clear_activity(ActivityKind::ExternalEdit, cx);
+                }
+            }
+        }
+
+        // What the mirror pane shows isn't sent to followers, to avoid flickering.
+        if pane == self.active_pane() && !self.is_mirror_pane(pane) {
+            self.report_active_item_changed(cx);
+            self.update_active_view_for_followers(cx);
+        } else if origin == ItemActivationOrigin::Focus {
+            // Panes outside of the center, e.g. in the terminal panel, are followed too.
+            self.update_active_view_for_followers(cx);
+        }
+    }
+
+    /// Emits [`crate::Event::ActiveItemChanged`] when the active item of the workspace isn't
+    /// the one reported last.
+    pub(crate) fn report_active_item_changed(&mut self, cx: &mut ViewContext<Self>) {
+        let active_item_id = self.active_item(cx).map(|item| item.item_id());
+        if active_item_id != self.reported_active_item_id {
+            self.reported_active_item_id = active_item_id;
+            self.active_item_path_changed(cx);
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use gpui::{EntityId, Subscription, View, VisualTestContext};
+
+    use crate::{Event, Workspace};
+
+    /// A workspace event, as recorded by [`EventRecorder`].
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub(crate) enum RecordedEvent {
+        ItemAdded(EntityId),
+        /// The id of the active item, when the event was received.
+        ActiveItemChanged(Option<EntityId>),
+        ZoomChanged,
+    }
+
+    /// Records the events related to item activation that a workspace emits, with the batches
+    /// unpacked.
+    pub(crate) struct EventRecorder {
+        events: Rc<RefCell<Vec<RecordedEvent>>>,
+        _subscription: Subscription,
+    }
+
+    impl EventRecorder {
+        pub(crate) fn new(workspace: &View<Workspace>, cx: &mut VisualTestContext) -> Self {
+            let events = Rc::new(RefCell::new(Vec::new()));
+            let subscription = cx.update(|cx| {
+                let events = events.clone();
+                cx.subscribe(workspace, move |workspace, event, cx| {
+                    for event in event.unbatched() {
+                        let event = match event {
+                            Event::ItemAdded { item } => RecordedEvent::ItemAdded(item.item_id()),
+                            Event::ActiveItemChanged => RecordedEvent::ActiveItemChanged(
+                                workspace
+                                    .read(cx)
+                                    .active_item(cx)
+                                    .map(|item| item.item_id()),
+                            ),
+                            Event::ZoomChanged(_) => RecordedEvent::ZoomChanged,
+                            _ => continue,
+                        };
+                        events.borrow_mut().push(event);
+                    }
+                })
+            });
+            Self {
+                events,
+                _subscription: subscription,
+            }
+        }
+
+        /// Returns the events recorded since the last call.
+        pub(crate) fn take(&self) -> Vec<RecordedEvent> {
+            self.events.take()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test::{EventRecorder, RecordedEvent};
+    use crate::{
+        item::{test::TestItem, ItemHandle},
+        persistence::model::{
+            SerializedItem, SerializedPane, SerializedPaneGroup, SerializedWorkspace,
+            SerializedWorkspaceLocation,
+        },
+        register_project_item, register_serializable_item,
+        tests::init_test,
+        SplitDirection, Workspace,
+    };
+    use fs::FakeFs;
+    use gpui::{EntityId, TestAppContext, View, VisualContext, VisualTestContext};
+    use project::{Project, ProjectPath};
+    use serde_json::json;
+    use std::path::Path;
+
+    async fn init_workspace(
+        cx: &mut TestAppContext,
+    ) -> (View<Workspace>, ProjectPath, &mut VisualTestContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            register_project_item::<TestItem>(cx);
+            register_serializable_item::<TestItem>(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "a.txt": "", "b.txt": "" }))
+            .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        cx.run_until_parked();
+        let path = |name: &str| ProjectPath {
+            worktree_id,
+            path: Path::new(name).into(),
+        };
+
+        // Every scenario starts with a file open in the focused, active pane.
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path(path("a.txt"), None, true, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        (workspace, path("b.txt"), cx)
+    }
+
+    fn active_item_id(workspace: &View<Workspace>, cx: &mut VisualTestContext) -> EntityId {
+        workspace.update(cx, |workspace, cx| {
+            workspace.active_item(cx).unwrap().item_id()
+        })
+    }
+
+    /// The events emitted for an item getting added to the workspace and activated.
+    fn expected_events(item_id: EntityId) -> Vec<RecordedEvent> {
+        vec![
+            RecordedEvent::ItemAdded(item_id),
+            RecordedEvent::ActiveItemChanged(Some(item_id)),
+        ]
+    }
+
+    #[gpui::test]
+    async fn test_activation_events_on_open(cx: &mut TestAppContext) {
+        let (workspace, path, cx) = init_workspace(cx).await;
+        let recorder = EventRecorder::new(&workspace, cx);
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path(path, None, true, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            recorder.take(),
+            expected_events(active_item_id(&workspace, cx))
+        );
+    }
+
+    #[gpui::test]
+    async fn test_activation_events_on_preview_open(cx: &mut TestAppContext) {
+        let (workspace, path, cx) = init_workspace(cx).await;
+        let recorder = EventRecorder::new(&workspace, cx);
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path_preview(path, None, true, true, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            recorder.take(),
+            expected_events(active_item_id(&workspace, cx))
+        );
+    }
+
+    #[gpui::test]
+    async fn test_activation_events_on_reopen(cx: &mut TestAppContext) {
+        let (workspace, path, cx) = init_workspace(cx).await;
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path(path, None, true, cx)
+            })
+            .await
+            .unwrap();
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+        pane.update(cx, |pane, cx| {
+            pane.close_active_item(&Default::default(), cx)
+        })
+        .unwrap()
+        .await
+        .unwrap();
+        cx.run_until_parked();
+
+        let recorder = EventRecorder::new(&workspace, cx);
+        workspace
+            .update(cx, |workspace, cx| workspace.reopen_closed_item(cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            recorder.take(),
+            expected_events(active_item_id(&workspace, cx))
+        );
+    }
+
+    #[gpui::test]
+    async fn test_activation_events_on_restore(cx: &mut TestAppContext) {
+        let (workspace, _, cx) = init_workspace(cx).await;
+        let recorder = EventRecorder::new(&workspace, cx);
+        workspace
+            .update(cx, |workspace, cx| {
+                let serialized_workspace = SerializedWorkspace {
+                    id: workspace.database_id().unwrap(),
+                    location: SerializedWorkspaceLocation::from_local_paths(["/root"]),
+                    center_group: SerializedPaneGroup::Pane(SerializedPane::new(
+                        vec![SerializedItem::new("TestItem", 1, true, false)],
+                        true,
+                        0,
+                    )),
+                    window_bounds: None,
+                    centered_layout: false,
+                    session_notes: None,
+                    display: None,
+                    docks: Default::default(),
+                    session_id: None,
+                    window_id: None,
+                };
+                Workspace::load_workspace(serialized_workspace, Vec::new(), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            recorder.take(),
+            expected_events(active_item_id(&workspace, cx))
+        );
+    }
+
+    #[gpui::test]
+    async fn test_activation_events_on_follow(cx: &mut TestAppContext) {
+        let (workspace, _, cx) = init_workspace(cx).await;
+        let recorder = EventRecorder::new(&workspace, cx);
+        let item = cx.new_view(TestItem::new);
+        workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            workspace.place_leader_item(&pane, Box::new(item.clone()), false, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(recorder.take(), expected_events(item.item_id()));
+    }
+
+    #[gpui::test]
+    async fn test_activation_events_on_focus(cx: &mut TestAppContext) {
+        let (workspace, _, cx) = init_workspace(cx).await;
+        let item_id = active_item_id(&workspace, cx);
+        let recorder = EventRecorder::new(&workspace, cx);
+
+        // Focusing another pane reports its active item, and focusing back reports the first
+        // item again, without reporting an unchanged zoom.
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+        workspace.update(cx, |workspace, cx| {
+            workspace.split_pane(pane.clone(), SplitDirection::Right, cx);
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |_, cx| cx.focus_view(&pane));
+        cx.run_until_parked();
+        assert_eq!(
+            recorder.take(),
+            [
+                RecordedEvent::ActiveItemChanged(None),
+                RecordedEvent::ActiveItemChanged(Some(item_id)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use collections::HashSet;
+use futures::{channel::mpsc::UnboundedReceiver, FutureExt as _, StreamExt as _};
+use gpui::{AsyncWindowContext, EntityId, WeakView};
+use util::ResultExt;
+
+use crate::{item::SerializableItemHandle, Workspace};
+
+/// The most items serialized in one turn, so that a burst of changed items, e.g. from a
+/// project-wide replace, doesn't hold up the frames being rendered.
+#[cfg(not(any(test, feature = "test-support")))]
+pub const ITEM_SERIALIZATION_BATCH_SIZE: usize = 25;
+#[cfg(any(test, feature = "test-support"))]
+pub const ITEM_SERIALIZATION_BATCH_SIZE: usize = 10;
+
+/// How long to yield between two batches of items.
+const ITEM_SERIALIZATION_BATCH_INTERVAL: Duration = Duration::from_millis(1);
+
+/// How long to wait once all the items are serialized, before serializing the ones changed since.
+const ITEM_SERIALIZATION_THROTTLE_TIME: Duration = Duration::from_millis(200);
+
+/// The items waiting to be serialized, each of them once, in the order they were enqueued.
+#[derive(Default)]
+struct PendingItems {
+    items: Vec<Box<dyn SerializableItemHandle>>,
+    item_ids: HashSet<EntityId>,
+}
+
+impl PendingItems {
+    fn push(&mut self, item: Box<dyn SerializableItemHandle>) {
+        if self.item_ids.insert(item.item_id()) {
+            self.items.push(item);
+        }
+    }
+
+    /// Takes the next items to serialize, the ones of the active pane first.
+    fn next_batch(
+        &mut self,
+        active_pane_item_ids: &HashSet<EntityId>,
+    ) -> Vec<Box<dyn SerializableItemHandle>> {
+        self.items
+            .sort_by_key(|item| !active_pane_item_ids.contains(&item.item_id()));
+        let batch_len = self.items.len().min(ITEM_SERIALIZATION_BATCH_SIZE);
+        let batch = self.items.drain(..batch_len).collect::<Vec<_>>();
+        for item in &batch {
+            self.item_ids.remove(&item.item_id());
+        }
+        batch
+    }
+}
+
+impl Workspace {
+    /// Serializes the items sent over `items_rx`, [`ITEM_SERIALIZATION_BATCH_SIZE`] at a time.
+    /// An item enqueued again before it was serialized is only serialized once.
+    pub(crate) async fn serialize_items(
+        this: &WeakView<Self>,
+        mut items_rx: UnboundedReceiver<Box<dyn SerializableItemHandle>>,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<()> {
+        let mut pending = PendingItems::default();
+
+        while let Some(item) = items_rx.next().await {
+            pending.push(item);
+            while !pending.items.is_empty() {
+                while let Some(Some(item)) = items_rx.next().now_or_never() {
+                    pending.push(item);
+                }
+
+                this.update(cx, |workspace, cx| {
+                    let active_pane_item_ids = workspace
+                        .active_pane
+                        .read(cx)
+                        .items()
+                        .map(|item| item.item_id())
+                        .collect();
+                    // The references to the items are moved into the tasks, so that they aren't
+                    // kept alive while we're sleeping.
+                    for item in pending.next_batch(&active_pane_item_ids) {
+                        if let Some(task) = item.serialize(workspace, false, cx) {
+                            cx.background_executor()
+                                .spawn(async move { task.await.log_err() })
+                                .detach();
+                        }
+                    }
+                })?;
+
+                if !pending.items.is_empty() {
+                    cx.background_executor()
+                        .timer(ITEM_SERIALIZATION_BATCH_INTERVAL)
+                        .await;
+                }
+            }
+
+            cx.background_executor()
+                .timer(ITEM_SERIALIZATION_THROTTLE_TIME)
+                .await;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{item::test::TestItem, tests::init_test};
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[gpui::test]
+    async fn test_serialize_items_in_batches(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let serialized = Rc::new(RefCell::new(Vec::new()));
+        let items = workspace.update(cx, |workspace, cx| {
+            let items = (0..500)
+                .map(|_| {
+                    cx.new_view(|cx| {
+                        let item_id = cx.entity_id();
+                        let serialized = serialized.clone();
+                        TestItem::new(cx).with_serialize(move || {
+                            serialized.borrow_mut().push(item_id);
+                            None
+                        })
+                    })
+                })
+                .collect::<Vec<_>>();
+            // The last items are open in the active pane.
+            for item in &items[500 - ITEM_SERIALIZATION_BATCH_SIZE..] {
+                workspace.add_item_to_active_pane(Box::new(item.clone()), None, false, cx);
+            }
+            for item in items.iter().chain(&items) {
+                workspace
+                    .enqueue_item_serialization(Box::new(item.clone()))
+                    .unwrap();
+            }
+            items
+        });
+
+        // The items of the active pane are serialized first, then one batch per turn.
+        cx.run_until_parked();
+        let active_pane_item_ids = items[500 - ITEM_SERIALIZATION_BATCH_SIZE..]
+            .iter()
+            .map(|item| item.entity_id())
+            .collect::<Vec<_>>();
+        assert_eq!(*serialized.borrow(), active_pane_item_ids);
+        for batch in 2..=5 {
+            cx.executor()
+                .advance_clock(ITEM_SERIALIZATION_BATCH_INTERVAL);
+            cx.run_until_parked();
+            assert_eq!(
+                serialized.borrow().len(),
+                batch * ITEM_SERIALIZATION_BATCH_SIZE
+            );
+        }
+
+        // Items enqueued again while pending are serialized once.
+        workspace.update(cx, |workspace, _| {
+            for item in &items[100..200] {
+                workspace
+                    .enqueue_item_serialization(Box::new(item.clone()))
+                    .unwrap();
+            }
+        });
+        while serialized.borrow().len() < 500 {
+            cx.executor()
+                .advance_clock(ITEM_SERIALIZATION_BATCH_INTERVAL);
+            cx.run_until_parked();
+        }
+        cx.executor()
+            .advance_clock(ITEM_SERIALIZATION_THROTTLE_TIME);
+        cx.run_until_parked();
+        let serialized = serialized.borrow();
+        assert_eq!(serialized.len(), 500);
+        assert_eq!(serialized.iter().collect::<HashSet<_>>().len(), 500);
+        assert_eq!(
+            serialized[ITEM_SERIALIZATION_BATCH_SIZE..2 * ITEM_SERIALIZATION_BATCH_SIZE],
+            items[..ITEM_SERIALIZATION_BATCH_SIZE]
+                .iter()
+                .map(|item| item.entity_id())
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::{Arc, Weak};
+
+use gpui::{AppContext, Global};
+use settings::{Settings, SettingsStore};
+
+use crate::{AppState, WorkspaceSettings};
+
+/// Holds on to the app state while the `keep_running_without_windows` setting is on, so that the
+/// global actions and the open requests can still open windows once the last one is closed.
+#[derive(Default)]
+struct KeepRunning {
+    app_state: Option<Arc<AppState>>,
+}
+
+impl Global for KeepRunning {}
+
+pub(crate) fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
+    let app_state = Arc::downgrade(app_state);
+    update_keep_running(&app_state, cx);
+    cx.observe_global::<SettingsStore>(move |cx| update_keep_running(&app_state, cx))
+        .detach();
+}
+
+/// Whether the app keeps running once its last window is closed, rather than quitting.
+pub(crate) fn keeps_running_without_windows(cx: &AppContext) -> bool {
+    cx.try_global::<KeepRunning>()
+        .map_or(false, |keep_running| keep_running.app_state.is_some())
+}
+
+fn update_keep_running(app_state: &Weak<AppState>, cx: &mut AppContext) {
+    let keep_running = WorkspaceSettings::get_global(cx).keep_running_without_windows;
+    if keep_running == keeps_running_without_windows(cx) {
+        return;
+    }
+    cx.set_quit_when_last_window_closes(!keep_running);
+    cx.set_global(KeepRunning {
+        app_state: keep_running.then(|| app_state.upgrade()).flatten(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{open_paths, queue_open_request, tests::init_test, CloseWindow, OpenOptions};
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    #[gpui::test]
+    async fn test_keep_running_without_windows(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.keep_running_without_windows = Some(true);
+                });
+            });
+        });
+
+        let app_state = cx.update(AppState::test);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree("/root", json!({ "a": {}, "b": {} }))
+            .await;
+        cx.update(|cx| crate::init(app_state.clone(), cx));
+        let (window, _) = cx
+            .update(|cx| {
+                open_paths(
+                    &[PathBuf::from("/root/a")],
+                    app_state.clone(),
+                    OpenOptions::default(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert!(cx.update(keeps_running_without_windows));
+
+        // Closing the last window keeps the app state alive.
+        let weak_app_state = Arc::downgrade(&app_state);
+        drop(app_state);
+        window
+            .update(cx, |workspace, cx| workspace.close_window(&CloseWindow, cx))
+            .unwrap();
+        cx.run_until_parked();
+        assert!(cx.windows().is_empty());
+        assert!(weak_app_state.upgrade().is_some());
+
+        // A new window can be opened from it.
+        cx.update(|cx| {
+            queue_open_request(vec![PathBuf::from("/root/b")], OpenOptions::default(), cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(cx.windows().len(), 1);
+
+        // Turning the setting off lets the app quit with its last window again.
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.keep_running_without_windows = Some(false);
+                });
+            });
+        });
+        assert!(!cx.update(keeps_running_without_windows));
+    }
+}
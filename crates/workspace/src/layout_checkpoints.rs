@@ -0,0 +1,208 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use gpui::{Task, ViewContext};
+use settings::Settings;
+use util::ResultExt;
+
+use crate::{
+    persistence::{model::SerializedWorkspace, LayoutCheckpoint, DB},
+    Workspace, WorkspaceSettings,
+};
+
+/// How often to check whether layout checkpoints got enabled while they are turned off.
+const DISABLED_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+impl Workspace {
+    /// Periodically saves a checkpoint of the layout, as configured by
+    /// `layout_checkpoint_interval_minutes`.
+    pub(crate) fn write_layout_checkpoints_periodically(cx: &mut ViewContext<Self>) -> Task<()> {
+        cx.spawn(|this, mut cx| async move {
+            loop {
+                let Ok(interval_minutes) = this.update(&mut cx, |_, cx| {
+                    WorkspaceSettings::get_global(cx).layout_checkpoint_interval_minutes
+                }) else {
+                    break;
+                };
+                if interval_minutes == 0 {
+                    cx.background_executor().timer(DISABLED_POLL_INTERVAL).await;
+                    continue;
+                }
+                cx.background_executor()
+                    .timer(Duration::from_secs(interval_minutes * 60))
+                    .await;
+
+                let Ok(task) = this.update(&mut cx, |this, cx| this.write_layout_checkpoint(cx))
+                else {
+                    break;
+                };
+                task.await.log_err();
+            }
+        })
+    }
+
+    /// Saves a checkpoint of the current layout, unless it is the same as the last one.
+    pub fn write_layout_checkpoint(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(workspace_id) = self.database_id else {
+            return Task::ready(Ok(()));
+        };
+        let center_group = self.build_serialized_center_group(cx);
+        let mut docks = self.build_serialized_docks(cx);
+        // Restoring a checkpoint shouldn't move the focus into a dock.
+        for dock in [&mut docks.left, &mut docks.right, &mut docks.bottom] {
+            dock.focused = false;
+        }
+        let layout = (center_group, docks);
+        if self.last_layout_checkpoint.as_ref() == Some(&layout) {
+            return Task::ready(Ok(()));
+        }
+        self.last_layout_checkpoint = Some(layout.clone());
+
+        let (center_group, docks) = layout;
+        let max_checkpoints = WorkspaceSettings::get_global(cx).max_layout_checkpoints;
+        cx.background_executor().spawn(async move {
+            DB.save_layout_checkpoint(workspace_id, center_group, docks, max_checkpoints)
+                .await
+        })
+    }
+
+    /// Returns the layout checkpoints of this workspace, newest first.
+    pub fn layout_checkpoints(
+        &self,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<Vec<LayoutCheckpoint>>> {
+        let Some(workspace_id) = self.database_id else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        cx.background_executor()
+            .spawn(async move { DB.layout_checkpoints(workspace_id) })
+    }
+
+    /// Replaces the center panes and docks with the layout saved in a checkpoint. Items with
+    /// unsaved changes are kept, in the restored active pane if the checkpoint doesn't
+    /// contain them.
+    pub fn restore_layout_checkpoint(
+        &mut self,
+        checkpoint: &LayoutCheckpoint,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let (Some(workspace_id), Some(location)) = (self.database_id, self.serialized_location(cx))
+        else {
+            return Task::ready(Err(anyhow!("workspace has not been serialized")));
+        };
+        let preserved_items = self
+            .panes
+            .iter()
+            .flat_map(|pane| pane.read(cx).items())
+            .filter(|item| item.is_dirty(cx))
+            .map(|item| item.boxed_clone())
+            .collect::<Vec<_>>();
+        let checkpoint_id = checkpoint.id;
+        let centered_layout = self.centered_layout;
+        let session_id = self.session_id.clone();
+        cx.spawn(|this, mut cx| async move {
+            let (center_group, docks) = cx
+                .background_executor()
+                .spawn(async move { DB.layout_checkpoint(checkpoint_id) })
+                .await?;
+            this.update(&mut cx, |this, cx| {
+                // Checkpoints only cover the layout, so the session notes are kept as they are.
+                let serialized_workspace = SerializedWorkspace {
+                    id: workspace_id,
+                    location,
+                    center_group,
+                    window_bounds: None,
+                    display: None,
+                    docks,
+                    centered_layout,
+                    session_notes: this.session_notes.clone(),
+                    session_id,
+                    window_id: None,
+                };
+                Workspace::restore_serialized_workspace(
+                    serialized_workspace,
+                    Vec::new(),
+                    preserved_items,
+                    true,
+                    cx,
+                )
+            })?
+            .await?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::TestItem, register_serializable_item, tests::init_test, SplitDirection,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_restore_layout_checkpoint(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(register_serializable_item::<TestItem>);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({})).await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let workspace_id = DB.next_id().await.unwrap();
+
+        let checkpointed_item = cx.new_view(TestItem::new);
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.database_id = Some(workspace_id);
+                workspace.add_item_to_active_pane(
+                    Box::new(checkpointed_item.clone()),
+                    None,
+                    true,
+                    cx,
+                );
+                workspace.serialize_workspace_internal(cx)
+            })
+            .await;
+        workspace
+            .update(cx, |workspace, cx| workspace.write_layout_checkpoint(cx))
+            .await
+            .unwrap();
+
+        // Split off a pane holding an unsaved item that isn't part of the checkpoint.
+        let dirty_item = cx.new_view(|cx| TestItem::new(cx).with_dirty(true));
+        workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            let new_pane = workspace.split_pane(pane, SplitDirection::Right, cx);
+            new_pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(dirty_item.clone()), true, true, None, cx)
+            });
+        });
+
+        let checkpoints = workspace
+            .update(cx, |workspace, cx| workspace.layout_checkpoints(cx))
+            .await
+            .unwrap();
+        assert_eq!(checkpoints.len(), 1);
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.restore_layout_checkpoint(&checkpoints[0], cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        // The checkpointed item is deserialized anew, and the unsaved one joins it.
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes().len(), 1);
+            let pane = workspace.active_pane().read(cx);
+            assert_eq!(pane.items_len(), 2);
+            assert!(pane.index_for_item(&checkpointed_item).is_none());
+            assert!(pane.index_for_item(&dirty_item).is_some());
+        });
+    }
+}
@@ -0,0 +1,189 @@
+use gpui::{EntityId, View, ViewContext, WeakView};
+use util::ResultExt;
+
+use crate::{item::ItemHandle, pane::Pane, SplitDirection, ToggleMirrorPane, Workspace};
+
+/// A pane showing the item activated last in the other panes, see
+/// [`Workspace::set_mirror_pane`].
+pub(crate) struct MirrorPane {
+    pane: WeakView<Pane>,
+    /// The item of another pane that is mirrored, and its copy in the mirror pane.
+    mirrored: Option<(EntityId, EntityId)>,
+}
+
+impl Workspace {
+    pub fn mirror_pane(&self) -> Option<View<Pane>> {
+        self.mirror_pane.as_ref()?.pane.upgrade()
+    }
+
+    /// Makes `pane` show a copy of whatever item is activated in the other panes, without
+    /// moving the focus to it, as if following yourself.
+    pub fn set_mirror_pane(&mut self, pane: Option<View<Pane>>, cx: &mut ViewContext<Self>) {
+        self.mirror_pane = pane.map(|pane| MirrorPane {
+            pane: pane.downgrade(),
+            mirrored: None,
+        });
+        if let Some(item) = self.active_pane.read(cx).active_item() {
+            let active_pane = self.active_pane.clone();
+            self.mirror_item(&active_pane, item.as_ref(), cx);
+        }
+        cx.notify();
+    }
+
+    pub(crate) fn is_mirror_pane(&self, pane: &View<Pane>) -> bool {
+        self.mirror_pane
+            .as_ref()
+            .map_or(false, |mirror| mirror.pane == pane.downgrade())
+    }
+
+    /// Shows a copy of an item activated in `pane` in the mirror pane, in place of the one
+    /// shown before. Items that can neither be cloned nor reopened are skipped.
+    pub(crate) fn mirror_item(
+        &mut self,
+        pane: &View<Pane>,
+        item: &dyn ItemHandle,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.is_mirror_pane(pane) {
+            return;
+        }
+        let Some(mirror_pane) = self.mirror_pane() else {
+            self.mirror_pane = None;
+            return;
+        };
+        let item_id = item.item_id();
+        let previous = self.mirror_pane.as_ref().and_then(|mirror| mirror.mirrored);
+        if previous.map_or(false, |(mirrored_id, _)| mirrored_id == item_id) {
+            return;
+        }
+
+        if let Some(clone) = item.clone_on_split(self.database_id(), cx) {
+            self.replace_mirrored_item(&mirror_pane, item_id, clone, cx);
+        } else if let Some(project_path) = item.project_path(cx) {
+            let open = self.open_path(project_path, Some(mirror_pane.downgrade()), false, cx);
+            cx.spawn(|this, mut cx| async move {
+                let reopened = open.await?;
+                this.update(&mut cx, |this, cx| {
+                    this.replace_mirrored_item(&mirror_pane, item_id, reopened, cx)
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
+    fn replace_mirrored_item(
+        &mut self,
+        mirror_pane: &View<Pane>,
+        item_id: EntityId,
+        copy: Box<dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(mirror) = self
+            .mirror_pane
+            .as_mut()
+            .filter(|mirror| mirror.pane == mirror_pane.downgrade())
+        else {
+            return;
+        };
+        let previous_copy_id = mirror.mirrored.map(|(_, copy_id)| copy_id);
+        let copy_id = copy.item_id();
+        mirror.mirrored = Some((item_id, copy_id));
+        mirror_pane.update(cx, |pane, cx| {
+            if let Some(index) = pane.index_for_item(copy.as_ref()) {
+                pane.activate_item(index, false, false, cx);
+            } else {
+                pane.add_item(copy, false, false, None, cx);
+            }
+            if let Some(previous_copy_id) = previous_copy_id.filter(|id| *id != copy_id) {
+                pane.remove_item(previous_copy_id, false, false, cx);
+            }
+        });
+    }
+
+    pub(crate) fn toggle_mirror_pane(&mut self, _: &ToggleMirrorPane, cx: &mut ViewContext<Self>) {
+        if let Some(mirror_pane) = self.mirror_pane() {
+            self.set_mirror_pane(None, cx);
+            if mirror_pane.read(cx).items_len() == 0 && self.center.panes().len() > 1 {
+                self.remove_pane(mirror_pane, None, cx);
+            }
+            return;
+        }
+
+        let active_pane = self.active_pane.clone();
+        let mirror_pane = self.split_pane(active_pane.clone(), SplitDirection::Right, cx);
+        cx.focus_view(&active_pane);
+        self.set_mirror_pane(Some(mirror_pane), cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::{TestItem, TestProjectItem},
+        tests::init_test,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_mirror_pane(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let items = (0..2)
+            .map(|i| {
+                cx.new_view(|cx| {
+                    TestItem::new(cx)
+                        .with_label(&format!("item {i}"))
+                        .with_project_items(&[TestProjectItem::new(i, &format!("{i}.txt"), cx)])
+                })
+            })
+            .collect::<Vec<_>>();
+        let source_pane = workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(items[0].clone()), None, true, cx);
+            workspace.active_pane().clone()
+        });
+
+        cx.dispatch_action(ToggleMirrorPane);
+        let mirror_pane = workspace.update(cx, |workspace, _| workspace.mirror_pane().unwrap());
+        let mirrored_label = |cx: &mut gpui::VisualTestContext| {
+            mirror_pane.update(cx, |pane, cx| {
+                let item = pane.active_item()?.downcast::<TestItem>()?;
+                Some((pane.items_len(), item.read(cx).label.clone()))
+            })
+        };
+        // The active item is mirrored right away, and the focus stays where it was.
+        assert_eq!(mirrored_label(cx), Some((1, "item 0".to_string())));
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.active_pane(), &source_pane);
+            assert!(source_pane.focus_handle(cx).contains_focused(cx));
+        });
+
+        // Activating another item replaces the mirrored copy.
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(items[1].clone()), None, true, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(mirrored_label(cx), Some((1, "item 1".to_string())));
+
+        // Closing the mirror pane stops mirroring.
+        mirror_pane
+            .update(cx, |pane, cx| {
+                pane.close_all_items(&Default::default(), cx).unwrap()
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.mirror_pane().is_none());
+            assert_eq!(workspace.panes().len(), 1);
+            workspace.activate_item(&items[0], true, true, cx);
+            assert_eq!(source_pane.read(cx).items_len(), 2);
+        });
+    }
+}
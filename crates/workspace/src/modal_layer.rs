@@ -1,6 +1,10 @@
-use gpui::{AnyView, DismissEvent, FocusHandle, ManagedView, Subscription, View};
+use gpui::{AnyView, DismissEvent, EntityId, FocusHandle, ManagedView, Subscription, View};
 use ui::prelude::*;
 
+/// How many modals can be stacked on top of each other, see [`ModalLayer::push_modal`]. Pushing
+/// more discards the bottom one.
+pub const MAX_STACKED_MODALS: usize = 4;
+
 pub enum DismissDecision {
     Dismiss(bool),
     Pending,
@@ -20,6 +24,7 @@ trait ModalViewHandle {
     fn on_before_dismiss(&mut self, cx: &mut WindowContext) -> DismissDecision;
     fn view(&self) -> AnyView;
     fn fade_out_background(&self, cx: &WindowContext) -> bool;
+    fn focus(&self, cx: &mut WindowContext);
 }
 
 impl<V: ModalView> ModalViewHandle for View<V> {
@@ -34,6 +39,10 @@ impl<V: ModalView> ModalViewHandle for View<V> {
     fn fade_out_background(&self, cx: &WindowContext) -> bool {
         self.read(cx).fade_out_background()
     }
+
+    fn focus(&self, cx: &mut WindowContext) {
+        cx.focus_view(self);
+    }
 }
 
 pub struct ActiveModal {
@@ -45,6 +54,8 @@ pub struct ActiveModal {
 
 pub struct ModalLayer {
     active_modal: Option<ActiveModal>,
+    /// Modals hidden beneath the active one, the most recently pushed last.
+    stacked_modals: Vec<ActiveModal>,
     dismiss_on_focus_lost: bool,
 }
 
@@ -58,10 +69,13 @@ impl ModalLayer {
     pub fn new() -> Self {
         Self {
             active_modal: None,
+            stacked_modals: Vec::new(),
             dismiss_on_focus_lost: false,
         }
     }
 
+    /// Dismisses the active modal if it is a `V`, and shows a new `V` in its place otherwise.
+    /// Modals stacked beneath the active one stay where they are.
     pub fn toggle_modal<V, B>(&mut self, cx: &mut ViewContext<Self>, build_view: B)
     where
         V: ModalView,
@@ -69,15 +83,55 @@ impl ModalLayer {
     {
         if let Some(active_modal) = &self.active_modal {
             let is_close = active_modal.modal.view().downcast::<V>().is_ok();
-            let did_close = self.hide_modal(cx);
-            if is_close || !did_close {
+            if is_close {
+                self.hide_modal(cx);
                 return;
             }
+            let Some(replaced_modal) = self.dismiss_active_modal(cx) else {
+                return;
+            };
+            Self::restore_focus(&replaced_modal, cx);
         }
         let new_modal = cx.new_view(build_view);
         self.show_modal(new_modal, cx);
     }
 
+    /// Dismisses the active modal if it is a `V`, and shows a new `V` on top of it otherwise.
+    pub fn toggle_modal_stacked<V, B>(&mut self, cx: &mut ViewContext<Self>, build_view: B)
+    where
+        V: ModalView,
+        B: FnOnce(&mut ViewContext<V>) -> V,
+    {
+        if self.active_modal::<V>().is_some() {
+            self.hide_modal(cx);
+        } else {
+            self.push_modal(cx, build_view);
+        }
+    }
+
+    /// Shows a new modal on top of the active one, which is revealed again with its state intact
+    /// once the new one is dismissed.
+    pub fn push_modal<V, B>(&mut self, cx: &mut ViewContext<Self>, build_view: B)
+    where
+        V: ModalView,
+        B: FnOnce(&mut ViewContext<V>) -> V,
+    {
+        if let Some(active_modal) = self.active_modal.take() {
+            if self.stacked_modals.len() + 1 >= MAX_STACKED_MODALS {
+                self.stacked_modals.remove(0);
+            }
+            self.stacked_modals.push(active_modal);
+        }
+        let new_modal = cx.new_view(build_view);
+        self.show_modal(new_modal, cx);
+    }
+
+    /// Dismisses the active modal, revealing the one beneath it if there is any. Returns whether
+    /// the modal allowed being dismissed.
+    pub fn pop_modal(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        self.hide_modal(cx)
+    }
+
     fn show_modal<V>(&mut self, new_modal: View<V>, cx: &mut ViewContext<Self>)
     where
         V: ModalView,
@@ -86,12 +140,22 @@ impl ModalLayer {
         self.active_modal = Some(ActiveModal {
             modal: Box::new(new_modal.clone()),
             _subscriptions: [
-                cx.subscribe(&new_modal, |this, _, _: &DismissEvent, cx| {
-                    this.hide_modal(cx);
-                }),
-                cx.on_focus_out(&focus_handle, |this, _event, cx| {
-                    if this.dismiss_on_focus_lost {
+                cx.subscribe(&new_modal, |this, modal, _: &DismissEvent, cx| {
+                    if this.is_active_modal(modal.entity_id()) {
                         this.hide_modal(cx);
+                    } else {
+                        this.stacked_modals.retain(|stacked_modal| {
+                            stacked_modal.modal.view().entity_id() != modal.entity_id()
+                        });
+                    }
+                }),
+                cx.on_focus_out(&focus_handle, {
+                    let modal_id = new_modal.entity_id();
+                    move |this, _event, cx| {
+                        // Stacked modals lose the focus to the modal shown on top of them.
+                        if this.dismiss_on_focus_lost && this.is_active_modal(modal_id) {
+                            this.hide_modal(cx);
+                        }
                     }
                 }),
             ],
@@ -104,36 +168,58 @@ impl ModalLayer {
         cx.notify();
     }
 
+    fn is_active_modal(&self, modal_id: EntityId) -> bool {
+        self.active_modal.as_ref().map_or(false, |active_modal| {
+            active_modal.modal.view().entity_id() == modal_id
+        })
+    }
+
     fn hide_modal(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        let Some(dismissed_modal) = self.dismiss_active_modal(cx) else {
+            return false;
+        };
+        if let Some(revealed_modal) = self.stacked_modals.pop() {
+            revealed_modal.modal.focus(cx);
+            self.active_modal = Some(revealed_modal);
+        } else {
+            Self::restore_focus(&dismissed_modal, cx);
+        }
+        true
+    }
+
+    fn restore_focus(dismissed_modal: &ActiveModal, cx: &mut ViewContext<Self>) {
+        if let Some(previous_focus) = &dismissed_modal.previous_focus_handle {
+            if dismissed_modal.focus_handle.contains_focused(cx) {
+                previous_focus.focus(cx);
+            }
+        }
+    }
+
+    /// Takes the active modal, if it allows being dismissed.
+    fn dismiss_active_modal(&mut self, cx: &mut ViewContext<Self>) -> Option<ActiveModal> {
         let Some(active_modal) = self.active_modal.as_mut() else {
             self.dismiss_on_focus_lost = false;
-            return false;
+            return None;
         };
 
         match active_modal.modal.on_before_dismiss(cx) {
             DismissDecision::Dismiss(dismiss) => {
                 self.dismiss_on_focus_lost = !dismiss;
                 if !dismiss {
-                    return false;
+                    return None;
                 }
             }
             DismissDecision::Pending => {
                 self.dismiss_on_focus_lost = false;
-                return false;
+                return None;
             }
         }
 
-        if let Some(active_modal) = self.active_modal.take() {
-            if let Some(previous_focus) = active_modal.previous_focus_handle {
-                if active_modal.focus_handle.contains_focused(cx) {
-                    previous_focus.focus(cx);
-                }
-            }
-            cx.notify();
-        }
-        true
+        cx.notify();
+        self.active_modal.take()
     }
 
+    /// Returns the modal on top of the stack, if it is a `V`.
     pub fn active_modal<V>(&self) -> Option<View<V>>
     where
         V: 'static,
@@ -179,3 +265,99 @@ impl Render for ModalLayer {
             )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::init_test, Workspace};
+    use fs::FakeFs;
+    use gpui::{EventEmitter, FocusableView, TestAppContext, VisualTestContext};
+    use project::Project;
+
+    struct TestModal {
+        focus_handle: FocusHandle,
+        query: String,
+    }
+
+    impl TestModal {
+        fn new(query: &str, cx: &mut ViewContext<Self>) -> Self {
+            Self {
+                focus_handle: cx.focus_handle(),
+                query: query.to_string(),
+            }
+        }
+    }
+
+    impl EventEmitter<DismissEvent> for TestModal {}
+
+    impl FocusableView for TestModal {
+        fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+            self.focus_handle.clone()
+        }
+    }
+
+    impl ModalView for TestModal {}
+
+    impl Render for TestModal {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            div().track_focus(&self.focus_handle)
+        }
+    }
+
+    #[gpui::test]
+    async fn test_stacked_modals(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let active_modal = |cx: &mut VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                let modal = workspace.active_modal::<TestModal>(cx)?;
+                assert!(modal.read(cx).focus_handle.is_focused(cx));
+                Some(modal.read(cx).query.clone())
+            })
+        };
+
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(cx, |cx| TestModal::new("go to line", cx));
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            workspace.push_modal(cx, |cx| TestModal::new("file finder", cx));
+        });
+        cx.run_until_parked();
+        assert_eq!(active_modal(cx).as_deref(), Some("file finder"));
+
+        // Dismissing the top modal reveals and focuses the one beneath it.
+        let top_modal = workspace.update(cx, |workspace, cx| {
+            workspace.active_modal::<TestModal>(cx).unwrap()
+        });
+        top_modal.update(cx, |_, cx| cx.emit(DismissEvent));
+        cx.run_until_parked();
+        assert_eq!(active_modal(cx).as_deref(), Some("go to line"));
+
+        // Toggling the active modal's type dismisses it rather than stacking another one.
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal_stacked(cx, |cx| TestModal::new("other", cx));
+        });
+        cx.run_until_parked();
+        assert_eq!(active_modal(cx), None);
+        workspace.update(cx, |workspace, cx| assert!(!workspace.has_active_modal(cx)));
+
+        // Only a limited number of modals are kept.
+        workspace.update(cx, |workspace, cx| {
+            for ix in 0..MAX_STACKED_MODALS + 2 {
+                workspace.push_modal(cx, |cx| TestModal::new(&ix.to_string(), cx));
+            }
+        });
+        cx.run_until_parked();
+        let mut dismissed = Vec::new();
+        while let Some(query) = active_modal(cx) {
+            dismissed.push(query);
+            workspace.update(cx, |workspace, cx| assert!(workspace.pop_modal(cx)));
+            cx.run_until_parked();
+        }
+        assert_eq!(dismissed, ["5", "4", "3", "2"]);
+    }
+}
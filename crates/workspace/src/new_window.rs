@@ -0,0 +1,150 @@
+use anyhow::Result;
+use gpui::{PromptLevel, Task, ViewContext, VisualContext, WindowHandle};
+use settings::Settings;
+
+use crate::{open_new, persistence::DB, NewWindowBehavior, Workspace, WorkspaceSettings};
+
+impl Workspace {
+    /// Opens a new window, either empty or on this workspace's project depending on the
+    /// `new_window_behavior` setting. `init` is run on the workspace of an empty window.
+    pub fn open_new_window(
+        &mut self,
+        init: impl FnOnce(&mut Workspace, &mut ViewContext<Workspace>) + 'static + Send,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let has_project = self.visible_worktrees(cx).next().is_some();
+        match WorkspaceSettings::get_global(cx).new_window_behavior {
+            NewWindowBehavior::SameProject if has_project => {
+                let window = self.open_window_on_project(cx);
+                cx.spawn(|_, _| async move { window.await.map(|_| ()) })
+            }
+            NewWindowBehavior::Ask if has_project => {
+                let answer = cx.prompt(
+                    PromptLevel::Info,
+                    "Open the new window on the current project?",
+                    None,
+                    &["Current Project", "Empty Window"],
+                );
+                cx.spawn(|this, mut cx| async move {
+                    if answer.await? == 0 {
+                        this.update(&mut cx, |this, cx| this.open_window_on_project(cx))?
+                            .await?;
+                        Ok(())
+                    } else {
+                        let app_state = this.update(&mut cx, |this, _| this.app_state.clone())?;
+                        cx.update(|cx| open_new(Default::default(), app_state, cx, init))?
+                            .await
+                    }
+                })
+            }
+            _ => open_new(Default::default(), self.app_state.clone(), cx, init),
+        }
+    }
+
+    /// Opens another window on this workspace's project, with a workspace of its own.
+    pub fn open_window_on_project(
+        &mut self,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<WindowHandle<Workspace>>> {
+        let project = self.project.clone();
+        let app_state = self.app_state.clone();
+        cx.spawn(|_, mut cx| async move {
+            let workspace_id = DB.next_id().await.unwrap_or_else(|_| Default::default());
+            let options = cx.update(|cx| (app_state.build_window_options)(None, cx))?;
+            let window = cx.open_window(options, |cx| {
+                cx.new_view(|cx| Workspace::new(Some(workspace_id), project, app_state, cx))
+            })?;
+            window.update(&mut cx, |_, cx| cx.activate_window())?;
+            Ok(window)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test;
+    use fs::FakeFs;
+    use gpui::{Model, TestAppContext};
+    use project::Project;
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    fn set_new_window_behavior(behavior: NewWindowBehavior, cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.new_window_behavior = Some(behavior);
+                });
+            });
+        });
+    }
+
+    /// The database ids of the open workspaces that are on `project`.
+    fn workspaces_on_project(
+        project: &Model<Project>,
+        cx: &mut TestAppContext,
+    ) -> Vec<Option<crate::WorkspaceId>> {
+        cx.windows()
+            .into_iter()
+            .filter_map(|window| {
+                let workspace = window.downcast::<Workspace>()?.root(cx).ok()?;
+                workspace.read_with(cx, |workspace, _| {
+                    (workspace.project() == project).then(|| workspace.database_id())
+                })
+            })
+            .collect()
+    }
+
+    #[gpui::test]
+    async fn test_new_window_behavior(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "a.txt": "" })).await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let new_window = |cx: &mut TestAppContext| {
+            window
+                .update(cx, |workspace, cx| {
+                    workspace.open_new_window(|_, _| {}, cx).detach();
+                })
+                .unwrap();
+            cx.executor().run_until_parked();
+        };
+        let window_counts = |cx: &mut TestAppContext| {
+            (
+                cx.windows().len(),
+                workspaces_on_project(&project, cx).len(),
+            )
+        };
+
+        new_window(cx);
+        assert_eq!(window_counts(cx), (2, 1));
+
+        set_new_window_behavior(NewWindowBehavior::SameProject, cx);
+        new_window(cx);
+        assert_eq!(window_counts(cx), (3, 2));
+
+        // Asking opens either of them.
+        set_new_window_behavior(NewWindowBehavior::Ask, cx);
+        new_window(cx);
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(0);
+        cx.executor().run_until_parked();
+        assert_eq!(window_counts(cx), (4, 3));
+
+        new_window(cx);
+        cx.simulate_prompt_answer(1);
+        cx.executor().run_until_parked();
+        assert_eq!(window_counts(cx), (5, 3));
+
+        // The windows opened on the project have workspaces of their own.
+        let mut workspace_ids = workspaces_on_project(&project, cx)
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        workspace_ids.dedup();
+        assert_eq!(workspace_ids.len(), 2);
+    }
+}
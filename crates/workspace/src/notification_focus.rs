@@ -0,0 +1,252 @@
+use gpui::{FocusHandle, Subscription, ViewContext, WindowContext};
+
+use crate::{
+    notifications::{
+        simple_message_notification::MessageNotification, NotificationGroup, NotificationId,
+    },
+    ActivateNotification, DismissFocusedNotification, FocusNotifications, Workspace,
+};
+
+/// The keyboard focus on the notifications of a workspace, see [`FocusNotifications`].
+pub(crate) struct NotificationFocus {
+    pub(crate) focus_handle: FocusHandle,
+    focused_id: Option<NotificationId>,
+    /// What had the focus before the notifications, to give it back to.
+    previous_focus: Option<FocusHandle>,
+    _blur_subscription: Subscription,
+}
+
+impl NotificationFocus {
+    pub(crate) fn new(cx: &mut ViewContext<Workspace>) -> Self {
+        let focus_handle = cx.focus_handle();
+        // Moving the focus elsewhere, e.g. with the mouse, leaves the notifications for good,
+        // unlike losing the focus altogether.
+        let blur_subscription = cx.on_blur(&focus_handle, |this, cx| {
+            if cx.focused().is_some() {
+                this.notification_focus.focused_id = None;
+                this.notification_focus.previous_focus = None;
+                cx.notify();
+            }
+        });
+        Self {
+            focus_handle,
+            focused_id: None,
+            previous_focus: None,
+            _blur_subscription: blur_subscription,
+        }
+    }
+}
+
+impl Workspace {
+    /// Moves the keyboard focus to the latest notification, or to the next one if the
+    /// notifications are focused already.
+    pub fn focus_notifications(&mut self, _: &FocusNotifications, cx: &mut ViewContext<Self>) {
+        if self.notifications.is_empty() {
+            return;
+        }
+        if self.notification_focus.focus_handle.is_focused(cx) {
+            self.select_notification(1, cx);
+            return;
+        }
+        self.notification_focus.previous_focus = cx.focused();
+        self.notification_focus.focused_id = self.notifications.last().map(|(id, _)| id.clone());
+        cx.focus(&self.notification_focus.focus_handle);
+        cx.notify();
+    }
+
+    /// The notification with the keyboard focus, if any.
+    pub fn focused_notification(&self) -> Option<&NotificationId> {
+        self.notification_focus.focused_id.as_ref()
+    }
+
+    pub(crate) fn is_notification_focused(&self, id: &NotificationId, cx: &WindowContext) -> bool {
+        self.notification_focus.focused_id.as_ref() == Some(id)
+            && self.notification_focus.focus_handle.is_focused(cx)
+    }
+
+    /// Moves the keyboard focus `delta` notifications further, wrapping around.
+    pub(crate) fn select_notification(&mut self, delta: isize, cx: &mut ViewContext<Self>) {
+        let Some(ix) = self.focused_notification_ix() else {
+            return;
+        };
+        let len = self.notifications.len() as isize;
+        let ix = (ix as isize + delta).rem_euclid(len) as usize;
+        self.notification_focus.focused_id = Some(self.notifications[ix].0.clone());
+        cx.notify();
+    }
+
+    /// Does what clicking the focused notification does, e.g. runs the action of a toast.
+    pub fn activate_notification(&mut self, _: &ActivateNotification, cx: &mut ViewContext<Self>) {
+        let Some(ix) = self.focused_notification_ix() else {
+            return;
+        };
+        let notification = self.notifications[ix].1.to_any();
+        let notification = match notification.downcast::<NotificationGroup>() {
+            Ok(group) => group.read(cx).visible_notification(),
+            Err(notification) => Some(notification),
+        };
+        if let Some(message) = notification
+            .and_then(|notification| notification.downcast::<MessageNotification>().ok())
+        {
+            message.update(cx, |message, cx| message.click(cx));
+        }
+    }
+
+    pub fn dismiss_focused_notification(
+        &mut self,
+        _: &DismissFocusedNotification,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(id) = self.notification_focus.focused_id.clone() {
+            self.dismiss_notification(&id, cx);
+        }
+    }
+
+    /// Gives the focus back to what had it before the notifications.
+    pub(crate) fn return_focus_from_notifications(&mut self, cx: &mut ViewContext<Self>) {
+        self.notification_focus.focused_id = None;
+        match self.notification_focus.previous_focus.take() {
+            Some(previous_focus) => cx.focus(&previous_focus),
+            None => cx.focus_self(),
+        }
+        cx.notify();
+    }
+
+    /// Keeps the keyboard focus on the notifications once the focused one went away, on the
+    /// one that took its place. `removed_ix` is where the focused notification was.
+    pub(crate) fn notifications_removed(
+        &mut self,
+        removed_ix: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.notification_focus.focused_id.is_none() || self.focused_notification_ix().is_some()
+        {
+            return;
+        }
+        if self.notifications.is_empty() {
+            if self.notification_focus.focus_handle.is_focused(cx) {
+                self.return_focus_from_notifications(cx);
+            } else {
+                self.notification_focus.focused_id = None;
+            }
+            return;
+        }
+        let ix = removed_ix.unwrap_or(0).min(self.notifications.len() - 1);
+        self.notification_focus.focused_id = Some(self.notifications[ix].0.clone());
+        cx.notify();
+    }
+
+    /// Focuses the notifications again if they lost the focus while one of them was focused,
+    /// e.g. as the focused element was removed. Returns whether they were focused.
+    pub(crate) fn refocus_notifications(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        if self.notification_focus.focused_id.is_none() || self.notifications.is_empty() {
+            return false;
+        }
+        cx.focus(&self.notification_focus.focus_handle);
+        true
+    }
+
+    pub(crate) fn focused_notification_ix(&self) -> Option<usize> {
+        let focused_id = self.notification_focus.focused_id.as_ref()?;
+        self.notifications
+            .iter()
+            .position(|(id, _)| id == focused_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::init_test, Toast};
+    use fs::FakeFs;
+    use gpui::{FocusableView, TestAppContext, VisualTestContext};
+    use project::Project;
+    use std::{cell::Cell, rc::Rc};
+
+    struct First;
+    struct Second;
+
+    #[gpui::test]
+    async fn test_notification_keyboard_focus(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let clicked = Rc::new(Cell::new(0));
+        let pane = workspace.update(cx, |workspace, cx| {
+            for id in [
+                NotificationId::unique::<First>(),
+                NotificationId::unique::<Second>(),
+            ] {
+                let clicked = clicked.clone();
+                workspace.show_toast(
+                    Toast::new(id, "Something happened")
+                        .on_click("Do it", move |_| clicked.set(clicked.get() + 1)),
+                    cx,
+                );
+            }
+            workspace.active_pane().clone()
+        });
+        cx.run_until_parked();
+        let pane_focused = |cx: &mut VisualTestContext| {
+            pane.update(cx, |pane, cx| pane.focus_handle(cx).contains_focused(cx))
+        };
+        assert!(pane_focused(cx));
+
+        // The latest notification is focused first, then they are cycled through.
+        cx.dispatch_action(FocusNotifications);
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(
+                workspace.focused_notification(),
+                Some(&NotificationId::unique::<Second>())
+            );
+        });
+        cx.dispatch_action(FocusNotifications);
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(
+                workspace.focused_notification(),
+                Some(&NotificationId::unique::<First>())
+            );
+        });
+
+        // Activating it runs its action and dismisses it, keeping the focus on the next one.
+        cx.dispatch_action(ActivateNotification);
+        cx.run_until_parked();
+        assert_eq!(clicked.get(), 1);
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(
+                workspace.notification_ids(),
+                [NotificationId::unique::<Second>()]
+            );
+            assert_eq!(
+                workspace.focused_notification(),
+                Some(&NotificationId::unique::<Second>())
+            );
+            assert!(workspace.notification_focus.focus_handle.is_focused(cx));
+        });
+
+        // Dismissing the last one gives the focus back.
+        cx.dispatch_action(DismissFocusedNotification);
+        cx.run_until_parked();
+        assert_eq!(clicked.get(), 1);
+        workspace.update(cx, |workspace, _| {
+            assert!(workspace.notification_ids().is_empty());
+            assert_eq!(workspace.focused_notification(), None);
+        });
+        assert!(pane_focused(cx));
+
+        // Escape gives the focus back too.
+        workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(Toast::new(NotificationId::unique::<First>(), "Again"), cx);
+        });
+        cx.dispatch_action(FocusNotifications);
+        assert!(!pane_focused(cx));
+        cx.dispatch_action(menu::Cancel);
+        assert!(pane_focused(cx));
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.notification_ids().len(), 1);
+            assert_eq!(workspace.focused_notification(), None);
+        });
+    }
+}
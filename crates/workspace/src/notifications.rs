@@ -1,4 +1,5 @@
-use crate::{Toast, Workspace};
+use crate::{OpenError, Toast, Workspace};
+use client::{proto::ErrorCode, ErrorExt};
 use collections::HashMap;
 use gpui::{
     svg, AnyView, AppContext, AsyncWindowContext, ClipboardItem, DismissEvent, Entity, EntityId,
@@ -7,7 +8,12 @@ use gpui::{
 };
 use language::DiagnosticSeverity;
 
-use std::{any::TypeId, ops::DerefMut, time::Duration};
+use std::{
+    any::{Any, TypeId},
+    collections::VecDeque,
+    ops::DerefMut,
+    time::Duration,
+};
 use ui::{prelude::*, Tooltip};
 use util::ResultExt;
 
@@ -148,16 +154,58 @@ impl Workspace {
         cx.notify();
     }
 
+    pub fn dismiss_notification_group(&mut self, group: &str, cx: &mut ViewContext<Self>) {
+        self.dismiss_notification_internal(&notification_group_id(group), cx)
+    }
+
+    /// Shows a notification as part of a group, e.g. all notifications from one language
+    /// server. Only the latest notification of a group is visible, the earlier ones can be
+    /// expanded from it.
+    pub fn show_notification_in_group<V: Notification>(
+        &mut self,
+        group: &str,
+        id: NotificationId,
+        cx: &mut ViewContext<Self>,
+        build_notification: impl FnOnce(&mut ViewContext<Self>) -> View<V>,
+    ) {
+        let notification = build_notification(cx);
+        cx.subscribe(&notification, {
+            let id = id.clone();
+            move |this, _, _: &DismissEvent, cx| {
+                this.dismiss_notification_internal(&id, cx);
+            }
+        })
+        .detach();
+
+        let group_id = notification_group_id(group);
+        let existing_group = self
+            .notifications
+            .iter()
+            .find(|(existing_id, _)| *existing_id == group_id)
+            .and_then(|(_, group)| group.to_any().downcast::<NotificationGroup>().ok());
+        if let Some(existing_group) = existing_group {
+            existing_group.update(cx, |group, cx| group.push(id, notification.into(), cx));
+        } else {
+            self.show_notification(group_id, cx, |cx| {
+                cx.new_view(|cx| {
+                    let mut group = NotificationGroup::new();
+                    group.push(id, notification.into(), cx);
+                    group
+                })
+            });
+        }
+    }
+
     pub fn show_error<E>(&mut self, err: &E, cx: &mut ViewContext<Self>)
     where
-        E: std::fmt::Debug + std::fmt::Display,
+        E: std::fmt::Debug + std::fmt::Display + 'static,
     {
         struct WorkspaceErrorNotification;
 
         self.show_notification(
             NotificationId::unique::<WorkspaceErrorNotification>(),
             cx,
-            |cx| cx.new_view(|_cx| ErrorMessagePrompt::new(format!("Error: {err:#}"))),
+            |cx| cx.new_view(|_cx| ErrorNotification::from_error(err)),
         );
     }
 
@@ -166,7 +214,7 @@ impl Workspace {
 
         self.show_notification(NotificationId::unique::<PortalError>(), cx, |cx| {
             cx.new_view(|_cx| {
-                ErrorMessagePrompt::new(err.to_string()).with_link_button(
+                ErrorNotification::new(err).with_link_button(
                     "See docs",
                     "https://zed.dev/docs/linux#i-cant-open-any-files",
                 )
@@ -212,18 +260,31 @@ impl Workspace {
 
     pub fn clear_all_notifications(&mut self, cx: &mut ViewContext<Self>) {
         self.notifications.clear();
+        self.notifications_removed(None, cx);
         cx.notify();
     }
 
     fn dismiss_notification_internal(&mut self, id: &NotificationId, cx: &mut ViewContext<Self>) {
-        self.notifications.retain(|(existing_id, _)| {
+        let focused_ix = self.focused_notification_ix();
+        self.notifications.retain(|(existing_id, notification)| {
             if existing_id == id {
                 cx.notify();
-                false
-            } else {
-                true
+                return false;
+            }
+            let Ok(group) = notification.to_any().downcast::<NotificationGroup>() else {
+                return true;
+            };
+            // Groups go away together with their last notification.
+            let is_empty = group.update(cx, |group, cx| {
+                group.remove(id, cx);
+                group.notifications.is_empty()
+            });
+            if is_empty {
+                cx.notify();
             }
+            !is_empty
         });
+        self.notifications_removed(focused_ix, cx);
     }
 }
 
@@ -364,22 +425,72 @@ impl Render for LanguageServerPrompt {
 
 impl EventEmitter<DismissEvent> for LanguageServerPrompt {}
 
-pub struct ErrorMessagePrompt {
-    message: SharedString,
+/// Returns a user-facing explanation for errors that carry a well-known [`ErrorCode`].
+pub fn error_code_message(err: &anyhow::Error) -> Option<SharedString> {
+    let message = match err.error_code() {
+        ErrorCode::SignedOut => "Please sign in to continue.",
+        ErrorCode::UpgradeRequired => {
+            "Your are running an unsupported version of Zed. Please update to continue."
+        }
+        ErrorCode::NoSuchChannel => {
+            "No matching channel was found. Please check the link and try again."
+        }
+        ErrorCode::Forbidden => {
+            "This channel is private, and you do not have access. Please ask someone to add you and try again."
+        }
+        ErrorCode::Disconnected => "Please check your internet connection and try again.",
+        _ => return None,
+    };
+    Some(message.into())
+}
+
+/// A notification for an error, showing its headline along with the full error
+/// chain, which can be expanded and copied.
+pub struct ErrorNotification {
+    headline: SharedString,
+    detail: Option<SharedString>,
+    detail_expanded: bool,
     label_and_url_button: Option<(SharedString, SharedString)>,
 }
 
-impl ErrorMessagePrompt {
-    pub fn new<S>(message: S) -> Self
+impl ErrorNotification {
+    pub fn new<S>(headline: S) -> Self
     where
         S: Into<SharedString>,
     {
         Self {
-            message: message.into(),
+            headline: headline.into(),
+            detail: None,
+            detail_expanded: false,
             label_and_url_button: None,
         }
     }
 
+    pub fn from_error<E>(err: &E) -> Self
+    where
+        E: std::fmt::Debug + std::fmt::Display + 'static,
+    {
+        let friendly_message = (err as &dyn Any)
+            .downcast_ref::<anyhow::Error>()
+            .and_then(error_code_message);
+        let headline = friendly_message.unwrap_or_else(|| format!("Error: {err}").into());
+        let detail = format!("{err:#}");
+        let this = Self::new(headline);
+        if this.headline.ends_with(detail.as_str()) {
+            this
+        } else {
+            this.with_detail(detail)
+        }
+    }
+
+    pub fn with_detail<S>(mut self, detail: S) -> Self
+    where
+        S: Into<SharedString>,
+    {
+        self.detail = Some(detail.into());
+        self
+    }
+
     pub fn with_link_button<S>(mut self, label: S, url: S) -> Self
     where
         S: Into<SharedString>,
@@ -387,12 +498,27 @@ impl ErrorMessagePrompt {
         self.label_and_url_button = Some((label.into(), url.into()));
         self
     }
+
+    pub fn headline(&self) -> &SharedString {
+        &self.headline
+    }
+
+    pub fn detail(&self) -> Option<&SharedString> {
+        self.detail.as_ref()
+    }
+
+    fn clipboard_text(&self) -> String {
+        match &self.detail {
+            Some(detail) => format!("{}\n\n{}", self.headline, detail),
+            None => self.headline.to_string(),
+        }
+    }
 }
 
-impl Render for ErrorMessagePrompt {
+impl Render for ErrorNotification {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         h_flex()
-            .id("error_message_prompt_notification")
+            .id("error_notification")
             .occlude()
             .elevation_3(cx)
             .items_start()
@@ -419,19 +545,64 @@ impl Render for ErrorMessagePrompt {
                                     }),
                             )
                             .child(
-                                ui::IconButton::new("close", ui::IconName::Close)
-                                    .on_click(cx.listener(|_, _, cx| cx.emit(gpui::DismissEvent))),
+                                h_flex()
+                                    .child(
+                                        ui::IconButton::new("copy", ui::IconName::Copy)
+                                            .on_click(cx.listener(|this, _, cx| {
+                                                cx.write_to_clipboard(ClipboardItem::new_string(
+                                                    this.clipboard_text(),
+                                                ))
+                                            }))
+                                            .tooltip(|cx| Tooltip::text("Copy", cx)),
+                                    )
+                                    .child(
+                                        ui::IconButton::new("close", ui::IconName::Close).on_click(
+                                            cx.listener(|_, _, cx| cx.emit(gpui::DismissEvent)),
+                                        ),
+                                    ),
                             ),
                     )
                     .child(
                         div()
                             .max_w_80()
-                            .child(Label::new(self.message.clone()).size(LabelSize::Small)),
+                            .child(Label::new(self.headline.clone()).size(LabelSize::Small)),
                     )
+                    .when_some(self.detail.clone(), |elm, detail| {
+                        let expanded = self.detail_expanded;
+                        elm.child(
+                            ui::Button::new(
+                                "toggle_detail",
+                                if expanded {
+                                    "Hide details"
+                                } else {
+                                    "Show details"
+                                },
+                            )
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, cx| {
+                                this.detail_expanded = !this.detail_expanded;
+                                cx.notify();
+                            })),
+                        )
+                        .when(expanded, |elm| {
+                            elm.child(
+                                div()
+                                    .id("error_detail")
+                                    .max_w_80()
+                                    .max_h(vh(0.4, cx))
+                                    .overflow_y_scroll()
+                                    .child(
+                                        Label::new(detail)
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    ),
+                            )
+                        })
+                    })
                     .when_some(self.label_and_url_button.clone(), |elm, (label, url)| {
                         elm.child(
                             div().mt_2().child(
-                                ui::Button::new("error_message_prompt_notification_button", label)
+                                ui::Button::new("error_notification_link_button", label)
                                     .on_click(move |_, cx| cx.open_url(&url)),
                             ),
                         )
@@ -440,7 +611,111 @@ impl Render for ErrorMessagePrompt {
     }
 }
 
-impl EventEmitter<DismissEvent> for ErrorMessagePrompt {}
+impl EventEmitter<DismissEvent> for ErrorNotification {}
+
+fn notification_group_id(group: &str) -> NotificationId {
+    NotificationId::composite::<NotificationGroup>(SharedString::from(group.to_string()))
+}
+
+/// How many notifications a [`NotificationGroup`] keeps, including the visible one.
+const NOTIFICATION_GROUP_HISTORY_LEN: usize = 5;
+
+/// Shows the latest notification of a group, with a button to expand the earlier ones.
+/// See [`Workspace::show_notification_in_group`].
+pub struct NotificationGroup {
+    /// Oldest first, the last one is the visible notification.
+    notifications: VecDeque<(NotificationId, AnyView)>,
+    show_earlier: bool,
+}
+
+impl NotificationGroup {
+    fn new() -> Self {
+        Self {
+            notifications: VecDeque::new(),
+            show_earlier: false,
+        }
+    }
+
+    fn push(&mut self, id: NotificationId, notification: AnyView, cx: &mut ViewContext<Self>) {
+        self.notifications
+            .retain(|(existing_id, _)| *existing_id != id);
+        if self.notifications.len() >= NOTIFICATION_GROUP_HISTORY_LEN {
+            // Language server prompts are kept until answered, as the server waits for them.
+            let evicted_ix = self
+                .notifications
+                .iter()
+                .position(|(_, notification)| !is_pending_prompt(notification, cx));
+            if let Some(ix) = evicted_ix {
+                self.notifications.remove(ix);
+            }
+        }
+        self.notifications.push_back((id, notification));
+        cx.notify();
+    }
+
+    fn remove(&mut self, id: &NotificationId, cx: &mut ViewContext<Self>) {
+        self.notifications
+            .retain(|(existing_id, _)| existing_id != id);
+        cx.notify();
+    }
+
+    /// The latest notification of the group, the one shown when the group is collapsed.
+    pub(crate) fn visible_notification(&self) -> Option<AnyView> {
+        self.notifications
+            .back()
+            .map(|(_, notification)| notification.clone())
+    }
+}
+
+/// Whether the notification is a language server prompt that wasn't answered yet.
+fn is_pending_prompt(notification: &AnyView, cx: &AppContext) -> bool {
+    notification
+        .clone()
+        .downcast::<LanguageServerPrompt>()
+        .map_or(false, |prompt| prompt.read(cx).request.is_some())
+}
+
+impl Render for NotificationGroup {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let earlier_count = self.notifications.len().saturating_sub(1);
+        v_flex()
+            .gap_2()
+            .when(self.show_earlier, |this| {
+                this.children(
+                    self.notifications
+                        .iter()
+                        .take(earlier_count)
+                        .map(|(_, notification)| notification.clone()),
+                )
+            })
+            .children(
+                self.notifications
+                    .back()
+                    .map(|(_, notification)| notification.clone()),
+            )
+            .when(earlier_count > 0, |this| {
+                let label = if self.show_earlier {
+                    "Hide earlier notifications".to_string()
+                } else if earlier_count == 1 {
+                    "Show 1 earlier notification".to_string()
+                } else {
+                    format!("Show {earlier_count} earlier notifications")
+                };
+                this.child(
+                    h_flex().justify_end().child(
+                        ui::Button::new("toggle_earlier_notifications", label)
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, cx| {
+                                this.show_earlier = !this.show_earlier;
+                                cx.notify();
+                            })),
+                    ),
+                )
+            })
+    }
+}
+
+impl EventEmitter<DismissEvent> for NotificationGroup {}
 
 pub mod simple_message_notification {
     use gpui::{
@@ -507,9 +782,45 @@ pub mod simple_message_notification {
             self
         }
 
+        pub fn set_message<S>(&mut self, message: S, cx: &mut ViewContext<Self>)
+        where
+            S: Into<SharedString>,
+        {
+            self.message = message.into();
+            cx.notify();
+        }
+
         pub fn dismiss(&mut self, cx: &mut ViewContext<Self>) {
             cx.emit(DismissEvent);
         }
+
+        /// Does what clicking the primary button does, then dismisses the notification.
+        pub fn click(&mut self, cx: &mut ViewContext<Self>) {
+            if let Some(on_click) = self.on_click.clone() {
+                on_click(cx);
+            }
+            self.dismiss(cx)
+        }
+
+        #[cfg(any(test, feature = "test-support"))]
+        pub fn message(&self) -> &SharedString {
+            &self.message
+        }
+
+        /// Simulates clicking the primary button.
+        #[cfg(any(test, feature = "test-support"))]
+        pub fn simulate_click(&mut self, cx: &mut ViewContext<Self>) {
+            self.click(cx)
+        }
+
+        /// Simulates clicking the secondary button.
+        #[cfg(any(test, feature = "test-support"))]
+        pub fn simulate_secondary_click(&mut self, cx: &mut ViewContext<Self>) {
+            if let Some(on_click) = self.secondary_on_click.clone() {
+                on_click(cx);
+            }
+            self.dismiss(cx)
+        }
     }
 
     impl Render for MessageNotification {
@@ -533,14 +844,8 @@ pub mod simple_message_notification {
                     h_flex()
                         .gap_3()
                         .children(self.click_message.iter().map(|message| {
-                            Button::new(message.clone(), message.clone()).on_click(cx.listener(
-                                |this, _, cx| {
-                                    if let Some(on_click) = this.on_click.as_ref() {
-                                        (on_click)(cx)
-                                    };
-                                    this.dismiss(cx)
-                                },
-                            ))
+                            Button::new(message.clone(), message.clone())
+                                .on_click(cx.listener(|this, _, cx| this.click(cx)))
                         }))
                         .children(self.secondary_click_message.iter().map(|message| {
                             Button::new(message.clone(), message.clone())
@@ -571,13 +876,17 @@ pub trait NotifyResultExt {
 
 impl<T, E> NotifyResultExt for Result<T, E>
 where
-    E: std::fmt::Debug + std::fmt::Display,
+    E: std::fmt::Debug + std::fmt::Display + 'static,
 {
     type Ok = T;
 
     fn notify_err(self, workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> Option<T> {
         match self {
             Ok(value) => Some(value),
+            Err(err) if OpenError::is_opened_as_worktree(&err) => {
+                log::debug!("{err:?}");
+                None
+            }
             Err(err) => {
                 log::error!("TODO {err:?}");
                 workspace.show_error(&err, cx);
@@ -589,6 +898,10 @@ where
     fn notify_async_err(self, cx: &mut AsyncWindowContext) -> Option<T> {
         match self {
             Ok(value) => Some(value),
+            Err(err) if OpenError::is_opened_as_worktree(&err) => {
+                log::debug!("{err:?}");
+                None
+            }
             Err(err) => {
                 log::error!("{err:?}");
                 cx.update_root(|view, cx| {
@@ -648,6 +961,10 @@ where
         cx.spawn(|mut cx| async move {
             let result = self.await;
             if let Err(err) = result.as_ref() {
+                if OpenError::is_opened_as_worktree(err) {
+                    log::debug!("{err:?}");
+                    return None;
+                }
                 log::error!("{err:?}");
                 if let Ok(prompt) = cx.update(|cx| {
                     let detail = f(err, cx).unwrap_or_else(|| format!("{err}. Please try again."));
@@ -670,3 +987,153 @@ where
         self.prompt_err(msg, cx, f).detach();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test;
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_show_error_notification(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let error = anyhow::anyhow!("connection refused").context("failed to open project");
+        workspace.update(cx, |workspace, cx| workspace.show_error(&error, cx));
+        workspace.update(cx, |workspace, cx| {
+            let notification = workspace.notifications[0]
+                .1
+                .to_any()
+                .downcast::<ErrorNotification>()
+                .unwrap();
+            let notification = notification.read(cx);
+            assert_eq!(notification.headline(), "Error: failed to open project");
+            assert_eq!(
+                notification.detail().map(|detail| detail.as_ref()),
+                Some("failed to open project: connection refused")
+            );
+        });
+
+        let error = anyhow::Error::from(ErrorCode::Disconnected).context("failed to join channel");
+        workspace.update(cx, |workspace, cx| workspace.show_error(&error, cx));
+        workspace.update(cx, |workspace, cx| {
+            let notification = workspace.notifications[0]
+                .1
+                .to_any()
+                .downcast::<ErrorNotification>()
+                .unwrap();
+            assert_eq!(
+                notification.read(cx).headline(),
+                "Please check your internet connection and try again."
+            );
+
+            workspace.clear_all_notifications(cx);
+            assert!(workspace.notification_ids().is_empty());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_notification_groups(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+        let show_toast = |message: &str, cx: &mut VisualTestContext| {
+            project.update(cx, |_, cx| {
+                cx.emit(project::Event::Toast {
+                    notification_id: "lsp".into(),
+                    message: message.to_string(),
+                })
+            });
+            cx.run_until_parked();
+        };
+        let group_len = |cx: &mut VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                assert_eq!(workspace.notification_ids().len(), 1);
+                let group = workspace.notifications[0]
+                    .1
+                    .to_any()
+                    .downcast::<NotificationGroup>()
+                    .unwrap();
+                group.read(cx).notifications.len()
+            })
+        };
+
+        show_toast("Indexing…", cx);
+        show_toast("Still indexing…", cx);
+        show_toast("Indexing failed", cx);
+        assert_eq!(group_len(cx), 3);
+
+        // Showing the same message again replaces its earlier entry.
+        show_toast("Indexing…", cx);
+        assert_eq!(group_len(cx), 3);
+
+        // Dismissing the group clears its history, so the next toast starts afresh.
+        project.update(cx, |_, cx| {
+            cx.emit(project::Event::HideToast {
+                notification_id: "lsp".into(),
+            })
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, _| {
+            assert!(workspace.notification_ids().is_empty())
+        });
+        show_toast("Indexing…", cx);
+        assert_eq!(group_len(cx), 1);
+    }
+
+    #[gpui::test]
+    async fn test_notification_groups_keep_pending_prompts(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+        project.update(cx, |_, cx| {
+            cx.emit(project::Event::LanguageServerPrompt(
+                project::LanguageServerPromptRequest::test(
+                    "lsp",
+                    "Reload the project?",
+                    Vec::new(),
+                ),
+            ))
+        });
+        for ix in 0..NOTIFICATION_GROUP_HISTORY_LEN {
+            project.update(cx, |_, cx| {
+                cx.emit(project::Event::Toast {
+                    notification_id: "lsp".into(),
+                    message: format!("Indexing {ix}…"),
+                })
+            });
+        }
+        cx.run_until_parked();
+
+        // The oldest toast makes room for the latest one, rather than the unanswered prompt.
+        workspace.update(cx, |workspace, cx| {
+            let group = workspace.notifications[0]
+                .1
+                .to_any()
+                .downcast::<NotificationGroup>()
+                .unwrap();
+            let group = group.read(cx);
+            assert_eq!(group.notifications.len(), NOTIFICATION_GROUP_HISTORY_LEN);
+            assert!(group
+                .notifications
+                .front()
+                .unwrap()
+                .1
+                .clone()
+                .downcast::<LanguageServerPrompt>()
+                .is_ok());
+        });
+    }
+}
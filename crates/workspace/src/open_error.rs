@@ -0,0 +1,379 @@
+use std::{
+    any::Any,
+    fmt,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use fs::Fs;
+use gpui::{
+    AppContext, AsyncWindowContext, Global, SharedString, Task, ViewContext, VisualContext,
+    WeakView, WindowContext,
+};
+use project::{ProjectEntryId, ProjectPath};
+
+use crate::{
+    item::ItemHandle,
+    notifications::{simple_message_notification::MessageNotification, NotificationId},
+    project_item_opener, ProjectItem, ProjectItemOpener, Workspace, WorkspaceItemBuilder,
+};
+
+/// How many bytes of a file are read to tell whether it's binary.
+const PROBE_LEN: u64 = 8192;
+
+/// Extensions of files that are binary, whatever their content looks like.
+const BINARY_EXTENSIONS: &[&str] = &[
+    "a", "bin", "class", "dll", "dylib", "exe", "gif", "gz", "ico", "jar", "jpeg", "jpg", "o",
+    "pdf", "png", "so", "tar", "wasm", "webp", "zip",
+];
+
+/// The kind of a file that no registered item could open, guessed from its extension and its
+/// first bytes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DetectedFileKind {
+    Binary,
+    Text,
+    /// The file couldn't be read, e.g. because it's in a remote project.
+    Unknown,
+}
+
+/// An error opening a file in the workspace, found by downcasting the errors of
+/// [`Workspace::open_path`] and the like.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OpenError {
+    /// None of the registered project items accepts the file.
+    NoHandler {
+        path: ProjectPath,
+        detected_kind: DetectedFileKind,
+    },
+    /// The path is a directory of the project, which was revealed instead of opening an item.
+    /// Callers can ignore it as nothing went wrong, see [`OpenError::is_opened_as_worktree`].
+    OpenedAsWorktree { abs_path: PathBuf },
+}
+
+impl fmt::Display for OpenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpenError::NoHandler {
+                path,
+                detected_kind: DetectedFileKind::Binary,
+            } => write!(f, "cannot open binary file {:?}", path.path),
+            OpenError::NoHandler { path, .. } => write!(f, "cannot open file {:?}", path.path),
+            OpenError::OpenedAsWorktree { abs_path } => {
+                write!(
+                    f,
+                    "{abs_path:?} is a directory, opened as part of a worktree"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for OpenError {}
+
+impl OpenError {
+    /// Whether `err`, an [`OpenError`] or an [`anyhow::Error`] wrapping one, only tells that a
+    /// directory was opened as part of a worktree. The helpers showing errors to the user skip
+    /// it, as nothing went wrong.
+    pub fn is_opened_as_worktree(err: &dyn Any) -> bool {
+        let err = err
+            .downcast_ref::<anyhow::Error>()
+            .and_then(|err| err.downcast_ref::<OpenError>())
+            .or_else(|| err.downcast_ref::<OpenError>());
+        matches!(err, Some(OpenError::OpenedAsWorktree { .. }))
+    }
+}
+
+/// Suggests how to open the files with the given extension that no item can open, e.g. by
+/// installing an extension.
+pub type OpenHandlerHintResolver = fn(&str, &AppContext) -> Option<SharedString>;
+
+#[derive(Default)]
+struct OpenHandlerHintResolvers(Vec<OpenHandlerHintResolver>);
+
+impl Global for OpenHandlerHintResolvers {}
+
+/// Registers a resolver of the hints shown when no item can open a file. The first hint
+/// found is shown.
+pub fn register_open_handler_hint_resolver(resolver: OpenHandlerHintResolver, cx: &mut AppContext) {
+    cx.default_global::<OpenHandlerHintResolvers>()
+        .0
+        .push(resolver);
+}
+
+pub(crate) fn open_handler_hint(extension: &str, cx: &AppContext) -> Option<SharedString> {
+    cx.try_global::<OpenHandlerHintResolvers>()?
+        .0
+        .iter()
+        .find_map(|resolve| resolve(extension, cx))
+}
+
+struct PlainTextOpener(ProjectItemOpener);
+
+impl Global for PlainTextOpener {}
+
+/// Registers the project item opening any file as plain text, which is offered for the files
+/// no other item can open.
+pub fn register_plain_text_project_item<I: ProjectItem>(cx: &mut AppContext) {
+    cx.set_global(PlainTextOpener(project_item_opener::<I>()));
+}
+
+/// Shows a notification for the [`OpenError`]s of a load, passing its result through.
+pub(crate) fn notify_open_error<T>(
+    workspace: &WeakView<Workspace>,
+    result: Result<T>,
+    cx: &mut AsyncWindowContext,
+) -> Result<T> {
+    if let Err(error) = &result {
+        workspace
+            .update(cx, |workspace, cx| workspace.notify_open_error(error, cx))
+            .ok();
+    }
+    result
+}
+
+impl Workspace {
+    pub(crate) fn no_handler_error(
+        &self,
+        path: ProjectPath,
+        cx: &mut WindowContext,
+    ) -> Task<Result<(Option<ProjectEntryId>, WorkspaceItemBuilder)>> {
+        let project = self.project.read(cx);
+        let fs = project.fs().clone();
+        let abs_path = project
+            .is_local()
+            .then(|| project.absolute_path(&path, cx))
+            .flatten();
+        let detect = cx
+            .background_executor()
+            .spawn(async move { detect_file_kind(fs.as_ref(), abs_path.as_deref()).await });
+        cx.spawn(|_| async move {
+            Err(OpenError::NoHandler {
+                path,
+                detected_kind: detect.await,
+            }
+            .into())
+        })
+    }
+
+    /// Opens a file in the active pane with the registered plain text item, even when other
+    /// items don't accept it.
+    pub fn open_path_as_plain_text(
+        &mut self,
+        path: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<Box<dyn ItemHandle>>> {
+        let Some(open) = cx.try_global::<PlainTextOpener>().map(|opener| opener.0) else {
+            return Task::ready(Err(anyhow!("no plain text item is registered")));
+        };
+        let Some(load) = open(&self.project, &path, cx) else {
+            return Task::ready(Err(anyhow!("cannot open {:?} as plain text", path.path)));
+        };
+        let pane = self.active_pane.downgrade();
+        cx.spawn(|_, mut cx| async move {
+            let (project_entry_id, build_item) = load.await?;
+            pane.update(&mut cx, |pane, cx| {
+                pane.open_item(project_entry_id, true, false, None, cx, build_item)
+            })
+        })
+    }
+
+    fn notify_open_error(&mut self, error: &anyhow::Error, cx: &mut ViewContext<Self>) {
+        let Some(OpenError::NoHandler {
+            path,
+            detected_kind,
+        }) = error.downcast_ref::<OpenError>()
+        else {
+            return;
+        };
+        let file_name = path.path.file_name().map_or_else(
+            || path.path.to_string_lossy(),
+            |name| name.to_string_lossy(),
+        );
+        let mut message = match detected_kind {
+            DetectedFileKind::Binary => {
+                format!(
+                    "\"{file_name}\" looks like a binary file, which no installed item can open."
+                )
+            }
+            DetectedFileKind::Text | DetectedFileKind::Unknown => {
+                format!("No installed item can open \"{file_name}\".")
+            }
+        };
+        if let Some(hint) = path
+            .path
+            .extension()
+            .and_then(|extension| open_handler_hint(&extension.to_string_lossy(), cx))
+        {
+            message.push(' ');
+            message.push_str(&hint);
+        }
+
+        let project = self.project.read(cx);
+        let abs_path = project
+            .is_local()
+            .then(|| project.absolute_path(path, cx))
+            .flatten();
+        let can_open_as_plain_text = cx.has_global::<PlainTextOpener>();
+        let workspace = cx.view().downgrade();
+        let path = path.clone();
+        self.show_notification(NotificationId::unique::<OpenError>(), cx, |cx| {
+            cx.new_view(|_| {
+                let mut notification = MessageNotification::new(message);
+                if can_open_as_plain_text {
+                    notification = notification
+                        .with_click_message("Open as Plain Text")
+                        .on_click(move |cx| {
+                            workspace
+                                .update(cx, |workspace, cx| {
+                                    workspace
+                                        .open_path_as_plain_text(path.clone(), cx)
+                                        .detach_and_log_err(cx)
+                                })
+                                .ok();
+                        });
+                }
+                if let Some(abs_path) = abs_path {
+                    notification = notification
+                        .with_secondary_click_message("Show in File Manager")
+                        .on_secondary_click(move |cx| cx.reveal_path(&abs_path));
+                }
+                notification
+            })
+        });
+    }
+}
+
+async fn detect_file_kind(fs: &dyn Fs, abs_path: Option<&Path>) -> DetectedFileKind {
+    let Some(abs_path) = abs_path else {
+        return DetectedFileKind::Unknown;
+    };
+    let has_binary_extension = abs_path.extension().map_or(false, |extension| {
+        BINARY_EXTENSIONS.contains(&extension.to_string_lossy().to_lowercase().as_str())
+    });
+    if has_binary_extension {
+        return DetectedFileKind::Binary;
+    }
+
+    let Ok(file) = fs.open_sync(abs_path).await else {
+        return DetectedFileKind::Unknown;
+    };
+    let mut probe = Vec::new();
+    if file.take(PROBE_LEN).read_to_end(&mut probe).is_err() {
+        return DetectedFileKind::Unknown;
+    }
+    if is_binary(&probe) {
+        DetectedFileKind::Binary
+    } else {
+        DetectedFileKind::Text
+    }
+}
+
+fn is_binary(probe: &[u8]) -> bool {
+    if probe.contains(&0) {
+        return true;
+    }
+    // The probe may end in the middle of a character.
+    std::str::from_utf8(probe).map_or_else(|error| error.error_len().is_some(), |_| false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{item::test::TestItem, register_project_item, tests::init_test};
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use project::Project;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_open_file_without_handler(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({ "image.png": "not really", "notes.xyz": "some notes" }),
+        )
+        .await;
+        fs.insert_file("/root/data.dat", vec![0xca, 0xfe, 0, 0x01])
+            .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let open = |path: &str, cx: &mut gpui::VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, path), None, true, cx)
+            })
+        };
+        let detected_kind = |error: anyhow::Error| {
+            let OpenError::NoHandler { detected_kind, .. } = error.downcast::<OpenError>().unwrap()
+            else {
+                panic!("expected a missing handler error");
+            };
+            detected_kind
+        };
+
+        // Binary files are told apart by their extension or their content.
+        let error = open("image.png", cx).await.unwrap_err();
+        assert_eq!(detected_kind(error), DetectedFileKind::Binary);
+        let error = open("data.dat", cx).await.unwrap_err();
+        assert_eq!(detected_kind(error), DetectedFileKind::Binary);
+        let error = open("notes.xyz", cx).await.unwrap_err();
+        assert_eq!(error.to_string(), "cannot open file \"notes.xyz\"");
+        assert_eq!(detected_kind(error), DetectedFileKind::Text);
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(
+                workspace.notification_ids(),
+                [NotificationId::unique::<OpenError>()]
+            );
+        });
+
+        // Files can be opened as plain text regardless.
+        cx.update(|cx| register_plain_text_project_item::<TestItem>(cx));
+        let item = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path_as_plain_text((worktree_id, "notes.xyz").into(), cx)
+            })
+            .await
+            .unwrap();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.active_item(cx).unwrap().item_id(), item.item_id());
+            assert!(item.downcast::<TestItem>().is_some());
+        });
+        // Other items are still preferred when they accept the file.
+        cx.update(|cx| register_project_item::<TestItem>(cx));
+        open("notes.xyz", cx).await.unwrap();
+    }
+
+    #[gpui::test]
+    fn test_open_handler_hint(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            assert_eq!(open_handler_hint("xyz", cx), None);
+            register_open_handler_hint_resolver(|_, _| None, cx);
+            register_open_handler_hint_resolver(
+                |extension, _| {
+                    (extension == "xyz").then(|| "The XYZ extension can open .xyz files.".into())
+                },
+                cx,
+            );
+            assert_eq!(
+                open_handler_hint("xyz", cx).as_deref(),
+                Some("The XYZ extension can open .xyz files.")
+            );
+            assert_eq!(open_handler_hint("abc", cx), None);
+        });
+    }
+
+    #[test]
+    fn test_is_binary() {
+        assert!(!is_binary(b"plain text"));
+        assert!(is_binary(b"nul\0byte"));
+        assert!(is_binary(&[0xff, 0xfe, 0x41]));
+        // A character cut at the end of the probe.
+        assert!(!is_binary(&"é".as_bytes()[..1]));
+    }
+}
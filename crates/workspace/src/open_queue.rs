@@ -0,0 +1,134 @@
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    sync::{Arc, Weak},
+};
+
+use gpui::{AppContext, Global};
+use util::ResultExt;
+
+use crate::{open_paths, AppState, OpenOptions};
+
+/// The requests to open paths that are waiting for the workspace to be initialized, or for the
+/// requests received before them to be opened.
+#[derive(Default)]
+struct OpenRequestQueue {
+    /// Set once [`crate::init`] has completed.
+    app_state: Option<Weak<AppState>>,
+    requests: VecDeque<(Vec<PathBuf>, OpenOptions)>,
+    /// The paths being opened, while the queue is being drained.
+    opening: Option<Vec<PathBuf>>,
+    draining: bool,
+}
+
+impl Global for OpenRequestQueue {}
+
+/// Opens `paths` with [`open_paths`] as soon as the workspace is initialized, e.g. for the files
+/// opened from the OS while the app is still launching. Requests are opened in the order they
+/// were received, and the requests for the same paths as a pending one are dropped.
+pub fn queue_open_request(paths: Vec<PathBuf>, options: OpenOptions, cx: &mut AppContext) {
+    let queue = cx.default_global::<OpenRequestQueue>();
+    let is_duplicate = queue.opening.as_ref() == Some(&paths)
+        || queue.requests.iter().any(|(queued, _)| *queued == paths);
+    if is_duplicate {
+        return;
+    }
+    queue.requests.push_back((paths, options));
+    drain_open_requests(cx);
+}
+
+pub(crate) fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
+    cx.default_global::<OpenRequestQueue>().app_state = Some(Arc::downgrade(app_state));
+    drain_open_requests(cx);
+}
+
+fn drain_open_requests(cx: &mut AppContext) {
+    let queue = cx.default_global::<OpenRequestQueue>();
+    if queue.draining || queue.requests.is_empty() {
+        return;
+    }
+    let Some(app_state) = queue.app_state.as_ref().and_then(Weak::upgrade) else {
+        return;
+    };
+    queue.draining = true;
+
+    // Requests are opened one at a time, so that they don't race each other for the windows.
+    cx.spawn(|mut cx| async move {
+        while let Some(open) = cx
+            .update(|cx| {
+                let queue = cx.global_mut::<OpenRequestQueue>();
+                let Some((paths, options)) = queue.requests.pop_front() else {
+                    queue.opening = None;
+                    queue.draining = false;
+                    return None;
+                };
+                queue.opening = Some(paths.clone());
+                Some(open_paths(&paths, app_state.clone(), options, cx))
+            })
+            .ok()
+            .flatten()
+        {
+            open.await.log_err();
+        }
+    })
+    .detach();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::init_test, Workspace};
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    fn window_roots(cx: &mut TestAppContext) -> Vec<Vec<String>> {
+        cx.windows()
+            .into_iter()
+            .filter_map(|window| {
+                let workspace = window.downcast::<Workspace>()?;
+                workspace
+                    .update(cx, |workspace, cx| {
+                        workspace
+                            .visible_worktrees(cx)
+                            .map(|worktree| worktree.read(cx).root_name().to_string())
+                            .collect()
+                    })
+                    .ok()
+            })
+            .collect()
+    }
+
+    #[gpui::test]
+    async fn test_open_requests_before_init(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let app_state = cx.update(AppState::test);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree("/root", json!({ "a": {}, "b": {}, "c": {} }))
+            .await;
+        let queue = |path: &str, cx: &mut TestAppContext| {
+            cx.update(|cx| {
+                queue_open_request(vec![PathBuf::from(path)], OpenOptions::default(), cx)
+            })
+        };
+
+        // Nothing is opened before the workspace is initialized.
+        queue("/root/a", cx);
+        queue("/root/b", cx);
+        queue("/root/a", cx);
+        cx.run_until_parked();
+        assert!(cx.windows().is_empty());
+
+        // The requests are opened once, in order.
+        cx.update(|cx| crate::init(app_state.clone(), cx));
+        cx.run_until_parked();
+        assert_eq!(window_roots(cx), [vec!["a"], vec!["b"]]);
+
+        // Later requests are opened right away.
+        queue("/root/c", cx);
+        cx.run_until_parked();
+        assert_eq!(window_roots(cx), [vec!["a"], vec!["b"], vec!["c"]]);
+    }
+}
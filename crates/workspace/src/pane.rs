@@ -1,25 +1,26 @@
 use crate::{
     item::{
-        ActivateOnClose, ClosePosition, Item, ItemHandle, ItemSettings, PreviewTabsSettings,
-        ShowDiagnostics, TabContentParams, WeakItemHandle,
+        ActivateOnClose, ActivityKind, ClosePosition, Item, ItemHandle, ItemSettings,
+        PreviewTabsSettings, ShowDiagnostics, TabContentParams, WeakItemHandle,
     },
     move_item,
-    notifications::NotifyResultExt,
+    notifications::{NotifyResultExt, NotifyTaskExt},
     toolbar::Toolbar,
-    workspace_settings::{AutosaveSetting, TabBarSettings, WorkspaceSettings},
-    CloseWindow, CopyPath, CopyRelativePath, NewFile, NewTerminal, OpenInTerminal, OpenTerminal,
-    OpenVisible, SplitDirection, ToggleFileFinder, ToggleProjectSymbols, ToggleZoom, Workspace,
+    workspace_settings::{AutosaveSetting, DropTargetPlacement, TabBarSettings, WorkspaceSettings},
+    ClosePaneSavingItems, CloseWindow, CopyPath, CopyRelativePath, NavigationDataRegistry, NewFile,
+    NewTerminal, OpenInTerminal, OpenTerminal, OpenVisible, PanePlacement, SaveItemsInPane,
+    SplitDirection, ToggleFileFinder, ToggleProjectSymbols, ToggleZoom, Workspace,
 };
 use anyhow::Result;
 use collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use futures::{stream::FuturesUnordered, StreamExt};
 use gpui::{
-    actions, anchored, deferred, impl_actions, prelude::*, Action, AnchorCorner, AnyElement,
-    AppContext, AsyncWindowContext, ClickEvent, ClipboardItem, Div, DragMoveEvent, EntityId,
-    EventEmitter, ExternalPaths, FocusHandle, FocusOutEvent, FocusableView, KeyContext, Model,
-    MouseButton, MouseDownEvent, NavigationDirection, Pixels, Point, PromptLevel, Render,
-    ScrollHandle, Subscription, Task, View, ViewContext, VisualContext, WeakFocusHandle, WeakView,
-    WindowContext,
+    actions, anchored, canvas, deferred, impl_actions, prelude::*, Action, AnchorCorner,
+    AnyElement, AnyWindowHandle, AppContext, AsyncWindowContext, Bounds, ClickEvent, ClipboardItem,
+    Div, DragMoveEvent, EntityId, EventEmitter, ExternalPaths, FocusHandle, FocusOutEvent,
+    FocusableView, KeyContext, Model, MouseButton, MouseDownEvent, NavigationDirection, Pixels,
+    Point, PromptLevel, Render, ScrollHandle, Subscription, Task, View, ViewContext, VisualContext,
+    WeakFocusHandle, WeakView, WindowContext,
 };
 use itertools::Itertools;
 use language::DiagnosticSeverity;
@@ -172,6 +173,7 @@ actions!(
         ActivateNextItem,
         ActivateLastItem,
         AlternateFile,
+        CompareWithActiveItem,
         GoBack,
         GoForward,
         JoinIntoNext,
@@ -219,13 +221,17 @@ pub enum Event {
     Split(SplitDirection),
     JoinAll,
     JoinIntoNext,
-    ChangeItemTitle,
+    ChangeItemTitle {
+        item_id: EntityId,
+    },
     Focus,
     ZoomIn,
     ZoomOut,
     UserSavedItem {
         item: Box<dyn WeakItemHandle>,
         save_intent: SaveIntent,
+        /// Where the item was saved to.
+        project_path: Option<ProjectPath>,
     },
 }
 
@@ -252,14 +258,22 @@ impl fmt::Debug for Event {
                 .finish(),
             Event::JoinAll => f.write_str("JoinAll"),
             Event::JoinIntoNext => f.write_str("JoinIntoNext"),
-            Event::ChangeItemTitle => f.write_str("ChangeItemTitle"),
+            Event::ChangeItemTitle { item_id } => f
+                .debug_struct("ChangeItemTitle")
+                .field("item_id", item_id)
+                .finish(),
             Event::Focus => f.write_str("Focus"),
             Event::ZoomIn => f.write_str("ZoomIn"),
             Event::ZoomOut => f.write_str("ZoomOut"),
-            Event::UserSavedItem { item, save_intent } => f
+            Event::UserSavedItem {
+                item,
+                save_intent,
+                project_path,
+            } => f
                 .debug_struct("UserSavedItem")
                 .field("item", &item.id())
                 .field("save_intent", save_intent)
+                .field("project_path", project_path)
                 .finish(),
         }
     }
@@ -293,6 +307,10 @@ pub struct Pane {
         Option<Arc<dyn Fn(&mut Pane, &dyn Any, &mut ViewContext<Pane>) -> ControlFlow<(), ()>>>,
     can_split_predicate: Option<Arc<dyn Fn(&mut Self, &dyn Any, &mut ViewContext<Self>) -> bool>>,
     should_display_tab_bar: Rc<dyn Fn(&ViewContext<Pane>) -> bool>,
+    /// Overrides `should_display_tab_bar` for this pane when set.
+    tab_bar_visibility: Option<bool>,
+    /// Keeps the items opened automatically out of this pane, see [`Workspace::toggle_pane_lock`].
+    locked: bool,
     render_tab_bar_buttons:
         Rc<dyn Fn(&mut Pane, &mut ViewContext<Pane>) -> (Option<AnyElement>, Option<AnyElement>)>,
     _subscriptions: Vec<Subscription>,
@@ -306,6 +324,8 @@ pub struct Pane {
     pub split_item_context_menu_handle: PopoverMenuHandle<ContextMenu>,
     pinned_tab_count: usize,
     diagnostics: HashMap<ProjectPath, DiagnosticSeverity>,
+    /// Where the pane was last drawn in its window.
+    pub(crate) bounds: Option<Bounds<Pixels>>,
 }
 
 pub struct ActivationHistoryEntry {
@@ -358,6 +378,8 @@ pub struct NavigationEntry {
 #[derive(Clone)]
 pub struct DraggedTab {
     pub pane: View<Pane>,
+    /// The window the tab is dragged out of. Tabs dropped into another window are moved there.
+    pub source_window: AnyWindowHandle,
     pub item: Box<dyn ItemHandle>,
     pub ix: usize,
     pub detail: usize,
@@ -415,6 +437,8 @@ impl Pane {
             custom_drop_handle: None,
             can_split_predicate: None,
             should_display_tab_bar: Rc::new(|cx| TabBarSettings::get_global(cx).show),
+            tab_bar_visibility: None,
+            locked: false,
             render_tab_bar_buttons: Rc::new(move |pane, cx| {
                 if !pane.has_focus(cx) && !pane.context_menu_focused(cx) {
                     return (None, None);
@@ -507,10 +531,16 @@ impl Pane {
             new_item_context_menu_handle: Default::default(),
             pinned_tab_count: 0,
             diagnostics: Default::default(),
+            bounds: None,
         }
     }
 
-    fn alternate_file(&mut self, cx: &mut ViewContext<Pane>) {
+    /// The item that was active in this pane before the active one, if it's still open.
+    pub fn alternate_file_item(&self) -> Option<Box<dyn ItemHandle>> {
+        self.alternate_file_items.1.as_ref()?.upgrade()
+    }
+
+    pub(crate) fn activate_alternate_file(&mut self, cx: &mut ViewContext<Pane>) {
         let (_, alternative) = &self.alternate_file_items;
         if let Some(alternative) = alternative {
             let existing = self
@@ -668,6 +698,71 @@ impl Pane {
         self.should_display_tab_bar = Rc::new(should_display_tab_bar);
     }
 
+    /// Shows or hides the tab bar of this pane regardless of the settings, or makes it follow
+    /// them again when `None`.
+    /// Compares one of the items with the active item of the pane that was used last before
+    /// this one.
+    fn compare_with_active_item(&mut self, item_id: EntityId, cx: &mut ViewContext<Self>) {
+        let Some(item) = self
+            .items
+            .iter()
+            .find(|item| item.item_id() == item_id)
+            .cloned()
+        else {
+            return;
+        };
+        let pane = cx.view().clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .compare_with_active_item(&pane, item.as_ref(), cx)
+                    .detach_and_notify_err(cx);
+            })
+            .log_err();
+    }
+
+    pub fn set_tab_bar_visibility(&mut self, visibility: Option<bool>, cx: &mut ViewContext<Self>) {
+        self.tab_bar_visibility = visibility;
+        cx.notify();
+    }
+
+    pub fn tab_bar_visibility(&self) -> Option<bool> {
+        self.tab_bar_visibility
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub(crate) fn set_locked(&mut self, locked: bool, cx: &mut ViewContext<Self>) {
+        self.locked = locked;
+        cx.notify();
+    }
+
+    /// The tab bar of a locked pane only accepts its own tabs, to reorder them.
+    fn locked_tab_bar_can_drop(
+        pane: WeakView<Pane>,
+    ) -> impl Fn(&dyn Any, &mut WindowContext) -> bool + 'static {
+        move |dragged, _| {
+            dragged
+                .downcast_ref::<DraggedTab>()
+                .map_or(false, |tab| tab.pane.downgrade() == pane)
+        }
+    }
+
+    pub fn is_tab_bar_visible(&self, cx: &ViewContext<Self>) -> bool {
+        self.tab_bar_visibility
+            .unwrap_or_else(|| (self.should_display_tab_bar)(cx))
+    }
+
+    /// The override that flips whether the tab bar is shown. Flipping it back to what the
+    /// settings say makes the pane follow them again.
+    pub(crate) fn toggled_tab_bar_visibility(&self, cx: &ViewContext<Self>) -> Option<bool> {
+        let default_visible = (self.should_display_tab_bar)(cx);
+        let visible = self.tab_bar_visibility.unwrap_or(default_visible);
+        (visible == default_visible).then_some(!visible)
+    }
+
     pub fn set_can_split(
         &mut self,
         can_split_predicate: Option<
@@ -871,9 +966,16 @@ impl Pane {
         }
     }
 
+    /// Closes the preview item to make room for a new one, returning its index.
+    /// Preview items that must not be replaced are promoted to permanent tabs instead.
     pub fn close_current_preview_item(&mut self, cx: &mut ViewContext<Self>) -> Option<usize> {
         let item_idx = self.preview_item_idx()?;
         let id = self.preview_item_id()?;
+        if !self.items[item_idx].preview_replaceable(cx) {
+            self.set_preview_item_id(None, cx);
+            cx.notify();
+            return None;
+        }
 
         let prev_active_item_index = self.active_item_index;
         self.remove_item(id, false, false, cx);
@@ -970,8 +1072,6 @@ impl Pane {
 
                 cx.notify();
             }
-
-            self.activate_item(insertion_index, activate_pane, focus_item, cx);
         } else {
             self.items.insert(insertion_index, item.clone());
 
@@ -980,12 +1080,13 @@ impl Pane {
             {
                 self.active_item_index += 1;
             }
-
-            self.activate_item(insertion_index, activate_pane, focus_item, cx);
             cx.notify();
         }
 
+        // Items are reported as added before being activated, so that subscribers always
+        // know about an item by the time it becomes active.
         cx.emit(Event::AddItem { item });
+        self.activate_item(insertion_index, activate_pane, focus_item, cx);
     }
 
     pub fn items_len(&self) -> usize {
@@ -1610,6 +1711,9 @@ impl Pane {
         if save_intent == SaveIntent::Skip {
             return Ok(true);
         }
+        if save_intent == SaveIntent::Close && cx.update(|cx| item.is_ephemeral(cx))? {
+            return Ok(true);
+        }
 
         let (mut has_conflict, mut is_dirty, mut can_save, is_singleton, has_deleted_file) = cx
             .update(|cx| {
@@ -1770,6 +1874,7 @@ impl Pane {
             cx.emit(Event::UserSavedItem {
                 item: item.downgrade_item(),
                 save_intent,
+                project_path: item.project_path(cx),
             });
             true
         })
@@ -1896,7 +2001,7 @@ impl Pane {
         }
     }
 
-    fn pin_tab_at(&mut self, ix: usize, cx: &mut ViewContext<'_, Self>) {
+    pub(crate) fn pin_tab_at(&mut self, ix: usize, cx: &mut ViewContext<'_, Self>) {
         maybe!({
             let pane = cx.view().clone();
             let destination_index = self.pinned_tab_count.min(ix);
@@ -1935,6 +2040,10 @@ impl Pane {
         self.pinned_tab_count > ix
     }
 
+    pub(crate) fn is_active_item_pinned(&self) -> bool {
+        self.is_tab_pinned(self.active_item_index)
+    }
+
     fn has_pinned_tabs(&self) -> bool {
         self.pinned_tab_count != 0
     }
@@ -2061,11 +2170,19 @@ impl Pane {
                 DraggedTab {
                     item: item.boxed_clone(),
                     pane: cx.view().clone(),
+                    source_window: cx.window_handle(),
                     detail,
                     is_active,
                     ix,
                 },
-                |tab, _, cx| cx.new_view(|_| tab.clone()),
+                |tab, _, cx| {
+                    let workspace = tab.pane.read(cx).workspace.clone();
+                    let item_id = tab.item.item_id();
+                    workspace
+                        .update(cx, |workspace, _| workspace.begin_item_drag(item_id))
+                        .ok();
+                    cx.new_view(|_| tab.clone())
+                },
             )
             .drag_over::<DraggedTab>(|tab, _, cx| {
                 tab.bg(cx.theme().colors().drop_target_background)
@@ -2076,6 +2193,9 @@ impl Pane {
             .when_some(self.can_drop_predicate.clone(), |this, p| {
                 this.can_drop(move |a, cx| p(a, cx))
             })
+            .when(self.locked, |this| {
+                this.can_drop(Self::locked_tab_bar_can_drop(cx.view().downgrade()))
+            })
             .on_drop(cx.listener(move |this, dragged_tab: &DraggedTab, cx| {
                 this.drag_split_direction = None;
                 this.handle_tab_drop(dragged_tab, ix, cx)
@@ -2253,6 +2373,36 @@ impl Pane {
                                 }
                             }),
                         );
+                    if let Some(workspace) = pane.read(cx).workspace.upgrade() {
+                        // The workspace reads the pane, so these run outside of a pane update.
+                        menu = menu
+                            .separator()
+                            .entry("Save All in Pane", Some(Box::new(SaveItemsInPane)), {
+                                let workspace = workspace.clone();
+                                let pane = pane.clone();
+                                move |cx| {
+                                    workspace.update(cx, |workspace, cx| {
+                                        workspace
+                                            .save_items_in_pane(pane.clone(), cx)
+                                            .detach_and_log_err(cx)
+                                    })
+                                }
+                            })
+                            .entry(
+                                "Close Pane (Saving All)",
+                                Some(Box::new(ClosePaneSavingItems)),
+                                {
+                                    let pane = pane.clone();
+                                    move |cx| {
+                                        workspace.update(cx, |workspace, cx| {
+                                            workspace
+                                                .close_pane_saving(pane.clone(), cx)
+                                                .detach_and_log_err(cx)
+                                        })
+                                    }
+                                },
+                            );
+                    }
 
                     let pin_tab_entries = |menu: ContextMenu| {
                         menu.separator().map(|this| {
@@ -2311,6 +2461,13 @@ impl Pane {
                                     }),
                                 )
                             })
+                            .entry(
+                                "Compare with Active Item",
+                                Some(Box::new(CompareWithActiveItem)),
+                                cx.handler_for(&pane, move |pane, cx| {
+                                    pane.compare_with_active_item(item_id, cx);
+                                }),
+                            )
                             .map(pin_tab_entries)
                             .separator()
                             .entry(
@@ -2403,6 +2560,25 @@ impl Pane {
                     .start_children(left_children)
                     .end_children(right_children)
             })
+            .when(self.locked, |tab_bar| {
+                // Highlighted while dragging, as the tab bar rejects what comes from elsewhere.
+                let color = if cx.has_active_drag() {
+                    Color::Warning
+                } else {
+                    Color::Muted
+                };
+                tab_bar.start_child(
+                    div()
+                        .id("pane_lock")
+                        .px_1()
+                        .child(
+                            Icon::new(IconName::FileLock)
+                                .size(IconSize::Small)
+                                .color(color),
+                        )
+                        .tooltip(|cx| Tooltip::text("Locked: new items open in other panes", cx)),
+                )
+            })
             .children(pinned_tabs.len().ne(&0).then(|| {
                 h_flex()
                     .children(pinned_tabs)
@@ -2431,6 +2607,9 @@ impl Pane {
                             .drag_over::<DraggedSelection>(|bar, _, cx| {
                                 bar.bg(cx.theme().colors().drop_target_background)
                             })
+                            .when(self.locked, |bar| {
+                                bar.can_drop(Self::locked_tab_bar_can_drop(cx.view().downgrade()))
+                            })
                             .on_drop(cx.listener(move |this, dragged_tab: &DraggedTab, cx| {
                                 this.drag_split_direction = None;
                                 this.handle_tab_drop(dragged_tab, this.items.len(), cx)
@@ -2537,6 +2716,15 @@ impl Pane {
         ix: usize,
         cx: &mut ViewContext<'_, Self>,
     ) {
+        // The tab may come from another window, whose workspace doesn't see this mouse up.
+        let source_workspace = if dragged_tab.pane.entity_id() == cx.entity_id() {
+            self.workspace.clone()
+        } else {
+            dragged_tab.pane.read(cx).workspace.clone()
+        };
+        source_workspace
+            .update(cx, |workspace, _| workspace.end_item_drag())
+            .ok();
         if let Some(custom_drop_handle) = self.custom_drop_handle.clone() {
             if let ControlFlow::Break(()) = custom_drop_handle(self, dragged_tab, cx) {
                 return;
@@ -2544,6 +2732,14 @@ impl Pane {
         }
         let mut to_pane = cx.view().clone();
         let split_direction = self.drag_split_direction;
+        if dragged_tab.source_window != cx.window_handle() {
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    workspace.receive_dragged_tab(dragged_tab, to_pane, ix, split_direction, cx)
+                })
+                .log_err();
+            return;
+        }
         let item_id = dragged_tab.item.item_id();
         if let Some(preview_item_id) = self.preview_item_id {
             if item_id == preview_item_id {
@@ -2697,6 +2893,15 @@ impl Pane {
         }
         let mut to_pane = cx.view().clone();
         let mut split_direction = self.drag_split_direction;
+        let placement = match WorkspaceSettings::get_global(cx).drop_target_placement {
+            DropTargetPlacement::ActivePane => PanePlacement::ActivePane,
+            DropTargetPlacement::SpreadAcrossExistingPanes => {
+                PanePlacement::SpreadAcrossExistingPanes
+            }
+            DropTargetPlacement::SplitPerFile => PanePlacement::SplitPerFile(
+                split_direction.unwrap_or_else(|| SplitDirection::vertical(cx)),
+            ),
+        };
         let paths = paths.paths().to_vec();
         let is_remote = self
             .workspace
@@ -2740,10 +2945,11 @@ impl Pane {
                         if let Some(split_direction) = split_direction {
                             to_pane = workspace.split_pane(to_pane, split_direction, cx);
                         }
-                        workspace.open_paths(
+                        workspace.open_paths_with_pane_placement(
                             paths,
                             OpenVisible::OnlyDirectories,
                             Some(to_pane.downgrade()),
+                            placement,
                             cx,
                         )
                     }) {
@@ -2803,8 +3009,7 @@ impl Render for Pane {
             key_context.add("EmptyPane");
         }
 
-        let should_display_tab_bar = self.should_display_tab_bar.clone();
-        let display_tab_bar = should_display_tab_bar(cx);
+        let display_tab_bar = self.is_tab_bar_visible(cx);
         let is_local = self.project.read(cx).is_local();
 
         v_flex()
@@ -2814,7 +3019,7 @@ impl Render for Pane {
             .flex_none()
             .overflow_hidden()
             .on_action(cx.listener(|pane, _: &AlternateFile, cx| {
-                pane.alternate_file(cx);
+                pane.activate_alternate_file(cx);
             }))
             .on_action(cx.listener(|pane, _: &SplitLeft, cx| pane.split(SplitDirection::Left, cx)))
             .on_action(cx.listener(|pane, _: &SplitUp, cx| pane.split(SplitDirection::Up, cx)))
@@ -2832,7 +3037,25 @@ impl Render for Pane {
             .on_action(cx.listener(|pane, _: &GoForward, cx| pane.navigate_forward(cx)))
             .on_action(cx.listener(|pane, _: &JoinIntoNext, cx| pane.join_into_next(cx)))
             .on_action(cx.listener(|pane, _: &JoinAll, cx| pane.join_all(cx)))
-            .on_action(cx.listener(Pane::toggle_zoom))
+            .on_action(cx.listener(|pane, action: &ToggleZoom, cx| {
+                // The panes restored while the workspace loads replace this one, so zooming
+                // waits for them, like the dock toggles do.
+                let loading_workspace = pane
+                    .workspace
+                    .upgrade()
+                    .filter(|workspace| workspace.read(cx).is_loading());
+                if let Some(workspace) = loading_workspace {
+                    workspace.update(cx, |workspace, _| {
+                        workspace.defer_action(|workspace, cx| {
+                            workspace
+                                .active_pane()
+                                .update(cx, |pane, cx| pane.toggle_zoom(&ToggleZoom, cx));
+                        })
+                    });
+                } else {
+                    pane.toggle_zoom(action, cx);
+                }
+            }))
             .on_action(cx.listener(|pane: &mut Pane, action: &ActivateItem, cx| {
                 pane.activate_item(action.0, true, true, cx);
             }))
@@ -2908,6 +3131,13 @@ impl Render for Pane {
                     }
                 }),
             )
+            .on_action(
+                cx.listener(|pane: &mut Self, _: &CompareWithActiveItem, cx| {
+                    if let Some(item) = pane.active_item() {
+                        pane.compare_with_active_item(item.item_id(), cx);
+                    }
+                }),
+            )
             .on_action(
                 cx.listener(|pane: &mut Self, action: &RevealInProjectPanel, cx| {
                     let entry_id = action
@@ -2999,6 +3229,15 @@ impl Render for Pane {
                             }),
                     )
             })
+            .child({
+                let this = cx.view().clone();
+                canvas(
+                    move |bounds, cx| this.update(cx, |this, _| this.bounds = Some(bounds)),
+                    |_, _, _| {},
+                )
+                .absolute()
+                .size_full()
+            })
             .on_mouse_down(
                 MouseButton::Navigate(NavigationDirection::Back),
                 cx.listener(|pane, _, cx| {
@@ -3109,53 +3348,32 @@ impl NavHistory {
         cx: &mut WindowContext,
     ) {
         let state = &mut *self.0.lock();
-        match state.mode {
-            NavigationMode::Disabled => {}
+        let stack = match state.mode {
+            NavigationMode::Disabled => return,
             NavigationMode::Normal | NavigationMode::ReopeningClosedItem => {
-                if state.backward_stack.len() >= MAX_NAVIGATION_HISTORY_LEN {
-                    state.backward_stack.pop_front();
-                }
-                state.backward_stack.push_back(NavigationEntry {
-                    item,
-                    data: data.map(|data| Box::new(data) as Box<dyn Any + Send>),
-                    timestamp: state.next_timestamp.fetch_add(1, Ordering::SeqCst),
-                    is_preview,
-                });
                 state.forward_stack.clear();
+                &mut state.backward_stack
             }
-            NavigationMode::GoingBack => {
-                if state.forward_stack.len() >= MAX_NAVIGATION_HISTORY_LEN {
-                    state.forward_stack.pop_front();
-                }
-                state.forward_stack.push_back(NavigationEntry {
-                    item,
-                    data: data.map(|data| Box::new(data) as Box<dyn Any + Send>),
-                    timestamp: state.next_timestamp.fetch_add(1, Ordering::SeqCst),
-                    is_preview,
-                });
-            }
-            NavigationMode::GoingForward => {
-                if state.backward_stack.len() >= MAX_NAVIGATION_HISTORY_LEN {
-                    state.backward_stack.pop_front();
-                }
-                state.backward_stack.push_back(NavigationEntry {
-                    item,
-                    data: data.map(|data| Box::new(data) as Box<dyn Any + Send>),
-                    timestamp: state.next_timestamp.fetch_add(1, Ordering::SeqCst),
-                    is_preview,
-                });
-            }
-            NavigationMode::ClosingItem => {
-                if state.closed_stack.len() >= MAX_NAVIGATION_HISTORY_LEN {
-                    state.closed_stack.pop_front();
-                }
-                state.closed_stack.push_back(NavigationEntry {
-                    item,
-                    data: data.map(|data| Box::new(data) as Box<dyn Any + Send>),
-                    timestamp: state.next_timestamp.fetch_add(1, Ordering::SeqCst),
-                    is_preview,
-                });
+            NavigationMode::GoingBack => &mut state.forward_stack,
+            NavigationMode::GoingForward => &mut state.backward_stack,
+            NavigationMode::ClosingItem => &mut state.closed_stack,
+        };
+        // Items may report the same location several times for a single user action, e.g.
+        // when they are activated repeatedly. Recording all of them would require pressing
+        // "go back" several times before anything visibly happens.
+        let is_duplicate = stack.back().map_or(false, |last| {
+            is_same_location(last, item.id(), data.as_ref(), cx)
+        });
+        if !is_duplicate {
+            if stack.len() >= MAX_NAVIGATION_HISTORY_LEN {
+                stack.pop_front();
             }
+            stack.push_back(NavigationEntry {
+                item,
+                data: data.map(|data| Box::new(data) as Box<dyn Any + Send>),
+                timestamp: state.next_timestamp.fetch_add(1, Ordering::SeqCst),
+                is_preview,
+            });
         }
         state.did_update(cx);
     }
@@ -3177,6 +3395,45 @@ impl NavHistory {
     pub fn path_for_item(&self, item_id: EntityId) -> Option<(ProjectPath, Option<PathBuf>)> {
         self.0.lock().paths_by_item.get(&item_id).cloned()
     }
+
+    /// Points the items recorded at `old_path` to `new_path`, e.g. once their file was renamed.
+    pub fn rename_path(
+        &mut self,
+        old_path: &ProjectPath,
+        new_path: ProjectPath,
+        new_abs_path: Option<PathBuf>,
+    ) {
+        for (path, abs_path) in self.0.lock().paths_by_item.values_mut() {
+            if path == old_path {
+                *path = new_path.clone();
+                *abs_path = new_abs_path.clone();
+            }
+        }
+    }
+}
+
+/// Whether a new history entry would point at the same location as an existing one.
+/// Entries without data are the same when they refer to the same item, while entries
+/// with data are only compared if their type was registered via
+/// [`crate::register_navigation_data`].
+fn is_same_location<D: 'static>(
+    entry: &NavigationEntry,
+    item_id: EntityId,
+    data: Option<&D>,
+    cx: &AppContext,
+) -> bool {
+    if entry.item.id() != item_id {
+        return false;
+    }
+    match (entry.data.as_ref(), data) {
+        (None, None) => true,
+        (Some(entry_data), Some(data)) => {
+            entry_data.downcast_ref::<D>().map_or(false, |entry_data| {
+                NavigationDataRegistry::eq(entry_data, data, cx)
+            })
+        }
+        _ => false,
+    }
 }
 
 impl NavHistoryState {
@@ -3243,7 +3500,11 @@ pub fn render_item_indicator(item: Box<dyn ItemHandle>, cx: &WindowContext) -> O
         let indicator_color = match (item.has_conflict(cx), item.is_dirty(cx)) {
             (true, _) => Color::Warning,
             (_, true) => Color::Accent,
-            (false, false) => return None,
+            (false, false) => match item.activity_indicator(cx)? {
+                ActivityKind::Conflict => Color::Warning,
+                ActivityKind::ExternalEdit => Color::Info,
+                ActivityKind::TaskRunning => Color::Muted,
+            },
         };
 
         Some(Indicator::dot().color(indicator_color))
@@ -0,0 +1,128 @@
+use gpui::{View, ViewContext, WindowContext};
+use settings::Settings;
+
+use crate::{
+    pane::Pane, ActivatePane, PaneActivationOrder, SplitDirection, Workspace, WorkspaceSettings,
+};
+
+impl Workspace {
+    /// The index path of a center pane, see [`crate::PaneGroup::index_path_of`]. Unlike the
+    /// pane's position among the panes, the path of a pane stays the same when panes are split
+    /// in other parts of the layout, so features keeping track of panes across layout changes
+    /// should refer to them by path.
+    pub fn pane_index_path(&self, pane: &View<Pane>) -> Option<Vec<usize>> {
+        self.center.index_path_of(pane)
+    }
+
+    /// The center pane at the index path, see [`Workspace::pane_index_path`].
+    pub fn pane_at_index_path(&self, path: &[usize]) -> Option<View<Pane>> {
+        self.center.pane_at_index_path(path).cloned()
+    }
+
+    /// The center panes in the order [`ActivatePane`] counts them, per the
+    /// `pane_activation_order` setting.
+    pub fn panes_in_activation_order(&self, cx: &WindowContext) -> Vec<View<Pane>> {
+        let panes = match WorkspaceSettings::get_global(cx).pane_activation_order {
+            PaneActivationOrder::Visual => self.center.panes_in_reading_order(),
+            PaneActivationOrder::Tree => self.center.panes(),
+        };
+        panes.into_iter().cloned().collect()
+    }
+
+    pub(crate) fn activate_pane_at_index(
+        &mut self,
+        action: &ActivatePane,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let panes = self.panes_in_activation_order(cx);
+        if let Some(pane) = panes.get(action.0) {
+            cx.focus_view(pane);
+        } else {
+            self.split_and_clone(self.active_pane.clone(), SplitDirection::Right, cx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test;
+    use fs::FakeFs;
+    use gpui::{px, size, TestAppContext, VisualTestContext};
+    use project::Project;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_pane_addressing(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        cx.simulate_resize(size(px(1000.), px(800.)));
+
+        // Split into a left column of two panes, next to a right pane.
+        let (top_left, bottom_left, right) = workspace.update(cx, |workspace, cx| {
+            let top_left = workspace.active_pane().clone();
+            let right = workspace.split_pane(top_left.clone(), SplitDirection::Right, cx);
+            let bottom_left = workspace.split_pane(top_left.clone(), SplitDirection::Down, cx);
+            (top_left, bottom_left, right)
+        });
+        cx.run_until_parked();
+        let activate_pane = |index, cx: &mut VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                workspace.activate_pane_at_index(&ActivatePane(index), cx)
+            });
+            cx.run_until_parked();
+            workspace.update(cx, |workspace, _| workspace.active_pane().clone())
+        };
+
+        // Panes are counted in reading order, rather than column by column.
+        assert_eq!(activate_pane(0, cx), top_left);
+        assert_eq!(activate_pane(1, cx), right);
+        assert_eq!(activate_pane(2, cx), bottom_left);
+
+        // The previous order is kept behind a setting.
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.pane_activation_order = Some(PaneActivationOrder::Tree);
+                });
+            });
+        });
+        assert_eq!(activate_pane(1, cx), bottom_left);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.pane_activation_order = None;
+                });
+            });
+        });
+
+        // After a swap, panes are counted where they're shown.
+        workspace.update(cx, |workspace, cx| {
+            workspace.swap_panes(top_left.clone(), right.clone(), cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(activate_pane(0, cx), right);
+        assert_eq!(activate_pane(1, cx), top_left);
+        assert_eq!(activate_pane(2, cx), bottom_left);
+
+        // Index paths aren't affected by splits in other parts of the layout.
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.pane_index_path(&right), Some(vec![0, 0]));
+            assert_eq!(workspace.pane_index_path(&bottom_left), Some(vec![0, 1]));
+            assert_eq!(workspace.pane_index_path(&top_left), Some(vec![1]));
+
+            workspace.split_pane(top_left.clone(), SplitDirection::Down, cx);
+            assert_eq!(workspace.pane_index_path(&top_left), Some(vec![1, 0]));
+            assert_eq!(workspace.pane_index_path(&bottom_left), Some(vec![0, 1]));
+            assert_eq!(
+                workspace.pane_at_index_path(&[0, 1]),
+                Some(bottom_left.clone())
+            );
+            assert_eq!(workspace.pane_at_index_path(&[0]), None);
+            assert_eq!(workspace.pane_at_index_path(&[2]), None);
+        });
+    }
+}
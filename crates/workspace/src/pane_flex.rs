@@ -0,0 +1,121 @@
+use gpui::{View, ViewContext};
+use settings::Settings;
+
+use crate::{pane::Pane, Workspace, WorkspaceSettings};
+
+impl Workspace {
+    const MAX_PANE_RESIZE_STEP: f32 = 0.5;
+
+    /// Sets the flex of a center pane in the split that directly contains it, where `1.0` is
+    /// an equal share of the split, e.g. `1.4` for a 70/30 split of two panes. The other panes
+    /// of the split keep their relative sizes, and no pane gets smaller than the minimum pane
+    /// size. The other splits are left untouched.
+    pub fn set_pane_flex(&mut self, pane: &View<Pane>, flex: f32, cx: &mut ViewContext<Self>) {
+        if self.center.set_flex(pane, flex, &self.bounds) {
+            self.serialize_workspace(cx);
+            cx.notify();
+        }
+    }
+
+    /// Grows the active pane along the split that directly contains it, by the
+    /// `pane_resize_step` fraction of the split. Shrinks it when `grow` is false.
+    pub(crate) fn resize_active_pane_by_step(&mut self, grow: bool, cx: &mut ViewContext<Self>) {
+        let pane = self.active_pane.clone();
+        let Some((flex, count)) = self.center.flex_of(&pane) else {
+            return;
+        };
+        let step = WorkspaceSettings::get_global(cx)
+            .pane_resize_step
+            .clamp(0., Self::MAX_PANE_RESIZE_STEP)
+            * count as f32;
+        let flex = if grow { flex + step } else { flex - step };
+        self.set_pane_flex(&pane, flex, cx);
+    }
+
+    /// Gives all the center panes the same size, like [`Workspace::reset_pane_sizes`], and
+    /// saves the layout.
+    pub(crate) fn equalize_panes(&mut self, cx: &mut ViewContext<Self>) {
+        self.reset_pane_sizes(cx);
+        self.serialize_workspace(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{persistence::model::SerializedPaneGroup, tests::init_test, SplitDirection};
+    use fs::FakeFs;
+    use gpui::{px, size, TestAppContext, VisualTestContext};
+    use project::Project;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_pane_flex(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        cx.simulate_resize(size(px(1000.), px(800.)));
+        let (left, right) = workspace.update(cx, |workspace, cx| {
+            let left = workspace.active_pane().clone();
+            let right = workspace.split_pane(left.clone(), SplitDirection::Right, cx);
+            cx.focus_view(&right);
+            (left, right)
+        });
+        cx.run_until_parked();
+        let serialized_flexes = |cx: &mut VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                match workspace.build_serialized_center_group(cx) {
+                    SerializedPaneGroup::Group { flexes, .. } => flexes.unwrap(),
+                    SerializedPaneGroup::Pane(_) => panic!("expected a split"),
+                }
+            })
+        };
+        let assert_flexes = |expected: [f32; 2], cx: &mut VisualTestContext| {
+            let flexes = serialized_flexes(cx);
+            assert!(
+                flexes
+                    .iter()
+                    .zip(expected)
+                    .all(|(flex, expected)| (flex - expected).abs() < 0.001),
+                "expected {expected:?}, got {flexes:?}"
+            );
+        };
+
+        // The other pane takes the rest of the split, and the sizes are saved.
+        workspace.update(cx, |workspace, cx| {
+            workspace.set_pane_flex(&left, 1.4, cx);
+            assert!(workspace._schedule_serialize.is_some());
+        });
+        assert_flexes([1.4, 0.6], cx);
+
+        // The active pane grows and shrinks by the configured step of the split.
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.pane_resize_step = Some(0.1);
+                });
+            });
+        });
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.active_pane(), &right);
+            workspace.resize_active_pane_by_step(true, cx);
+        });
+        assert_flexes([1.2, 0.8], cx);
+        workspace.update(cx, |workspace, cx| {
+            workspace.resize_active_pane_by_step(false, cx);
+            workspace.resize_active_pane_by_step(false, cx);
+        });
+        assert_flexes([1.6, 0.4], cx);
+
+        // No pane gets smaller than the minimum pane size.
+        workspace.update(cx, |workspace, cx| workspace.set_pane_flex(&left, 2., cx));
+        let flexes = serialized_flexes(cx);
+        assert!(flexes[1] > 0.1 && flexes[1] < 0.2, "{flexes:?}");
+        assert!((flexes.iter().sum::<f32>() - 2.).abs() < 0.001);
+
+        workspace.update(cx, |workspace, cx| workspace.equalize_panes(cx));
+        assert_flexes([1., 1.], cx);
+    }
+}
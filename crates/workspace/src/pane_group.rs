@@ -1,4 +1,5 @@
 use crate::{
+    focus_follows_mouse::FocusFollowsMouseTarget,
     pane_group::element::pane_axis,
     workspace_settings::{PaneSplitDirectionHorizontal, PaneSplitDirectionVertical},
     AppState, FollowerState, Pane, Workspace, WorkspaceSettings,
@@ -114,6 +115,79 @@ impl PaneGroup {
         };
     }
 
+    /// Gives all members of the axis that directly contains `pane` the same size, leaving
+    /// the other axes untouched. Returns whether such an axis was found.
+    pub fn rebalance_axis_containing(&mut self, pane: &View<Pane>) -> bool {
+        match &mut self.root {
+            Member::Pane(_) => false,
+            Member::Axis(axis) => axis.rebalance_axis_containing(pane),
+        }
+    }
+
+    /// Another pane of the axis that directly contains `pane`, which is left in the axis that
+    /// takes up the space of `pane` once it's removed.
+    pub fn sibling_pane(&self, pane: &View<Pane>) -> Option<&View<Pane>> {
+        let Member::Axis(axis) = &self.root else {
+            return None;
+        };
+        let (axis, ix) = axis.axis_containing(pane)?;
+        axis.members
+            .iter()
+            .enumerate()
+            .find_map(|(member_ix, member)| match member {
+                Member::Pane(sibling) if member_ix != ix => Some(sibling),
+                _ => None,
+            })
+    }
+
+    /// The flex of `pane` in the axis that directly contains it, where `1.0` is an equal share
+    /// of the axis, along with the number of members of that axis. `None` when `pane` isn't
+    /// part of a split.
+    pub fn flex_of(&self, pane: &View<Pane>) -> Option<(f32, usize)> {
+        let Member::Axis(axis) = &self.root else {
+            return None;
+        };
+        let (axis, ix) = axis.axis_containing(pane)?;
+        let flexes = axis.flexes.lock();
+        Some((*flexes.get(ix)?, flexes.len()))
+    }
+
+    /// Sets the flex of `pane` in the axis that directly contains it, scaling the other members
+    /// of that axis so that they keep their relative sizes. No member is made smaller than the
+    /// minimum pane size. Returns whether `pane` is part of a split.
+    pub fn set_flex(&mut self, pane: &View<Pane>, flex: f32, bounds: &Bounds<Pixels>) -> bool {
+        let Member::Axis(axis) = &self.root else {
+            return false;
+        };
+        let Some((axis, ix)) = axis.axis_containing(pane) else {
+            return false;
+        };
+        let container_size = axis
+            .bounding_boxes
+            .lock()
+            .iter()
+            .filter_map(|e| *e)
+            .reduce(|acc, e| acc.union(&e))
+            .unwrap_or(*bounds)
+            .size
+            .along(axis.axis);
+        let min_size = match axis.axis {
+            Axis::Horizontal => px(HORIZONTAL_MIN_SIZE),
+            Axis::Vertical => px(VERTICAL_MIN_SIZE),
+        };
+        let mut flexes = axis.flexes.lock();
+        if flexes.len() != axis.members.len() {
+            return false;
+        }
+        let min_flex = if container_size > px(0.) {
+            (flexes.len() as f32 * (min_size / container_size)).min(1.)
+        } else {
+            1.
+        };
+        set_member_flex(flexes.as_mut_slice(), ix, flex, min_flex);
+        true
+    }
+
     pub fn swap(&mut self, from: &View<Pane>, to: &View<Pane>) {
         match &mut self.root {
             Member::Pane(_) => {}
@@ -150,6 +224,44 @@ impl PaneGroup {
         panes
     }
 
+    /// The panes in reading order: by rows from the top, then from left to right. Until the
+    /// panes are laid out, they're in the order of [`PaneGroup::panes`].
+    pub fn panes_in_reading_order(&self) -> Vec<&View<Pane>> {
+        let panes = self.panes();
+        let bounds = panes
+            .iter()
+            .map(|pane| Some((self.bounding_box_for_pane(pane)?, *pane)))
+            .collect::<Option<Vec<_>>>();
+        let Some(mut bounds) = bounds else {
+            return panes;
+        };
+        bounds.sort_by_key(|(bounds, _)| (bounds.top(), bounds.left()));
+        bounds.into_iter().map(|(_, pane)| pane).collect()
+    }
+
+    /// The index path of `pane`: the index of the member leading to it in each axis, from
+    /// the root. A lone root pane has an empty path. Splitting a pane leaves the paths of the
+    /// panes outside of its axis unchanged.
+    pub fn index_path_of(&self, pane: &View<Pane>) -> Option<Vec<usize>> {
+        let mut path = Vec::new();
+        self.root.index_path_of(pane, &mut path).then_some(path)
+    }
+
+    /// The pane at the index path, see [`PaneGroup::index_path_of`].
+    pub fn pane_at_index_path(&self, path: &[usize]) -> Option<&View<Pane>> {
+        let mut member = &self.root;
+        for ix in path {
+            match member {
+                Member::Axis(axis) => member = axis.members.get(*ix)?,
+                Member::Pane(_) => return None,
+            }
+        }
+        match member {
+            Member::Pane(pane) => Some(pane),
+            Member::Axis(_) => None,
+        }
+    }
+
     pub fn first_pane(&self) -> View<Pane> {
         self.root.first_pane()
     }
@@ -314,55 +426,56 @@ impl Member {
                     };
                 }
 
-                div()
-                    .relative()
-                    .flex_1()
-                    .size_full()
-                    .child(
-                        AnyView::from(pane.clone())
-                            .cached(StyleRefinement::default().v_flex().size_full()),
+                Workspace::focus_follows_mouse(
+                    div().relative().flex_1().size_full(),
+                    FocusFollowsMouseTarget::Pane(pane.downgrade()),
+                    cx,
+                )
+                .child(
+                    AnyView::from(pane.clone())
+                        .cached(StyleRefinement::default().v_flex().size_full()),
+                )
+                .when_some(leader_border, |this, color| {
+                    this.child(
+                        div()
+                            .absolute()
+                            .size_full()
+                            .left_0()
+                            .top_0()
+                            .border_2()
+                            .border_color(color),
                     )
-                    .when_some(leader_border, |this, color| {
-                        this.child(
-                            div()
-                                .absolute()
-                                .size_full()
-                                .left_0()
-                                .top_0()
-                                .border_2()
-                                .border_color(color),
-                        )
-                    })
-                    .when_some(leader_status_box, |this, status_box| {
-                        this.child(
-                            div()
-                                .absolute()
-                                .w_96()
-                                .bottom_3()
-                                .right_3()
-                                .elevation_2(cx)
-                                .p_1()
-                                .child(status_box)
-                                .when_some(
-                                    leader_join_data,
-                                    |this, (leader_project_id, leader_user_id)| {
-                                        this.cursor_pointer().on_mouse_down(
-                                            MouseButton::Left,
-                                            cx.listener(move |this, _, cx| {
-                                                crate::join_in_room_project(
-                                                    leader_project_id,
-                                                    leader_user_id,
-                                                    this.app_state().clone(),
-                                                    cx,
-                                                )
-                                                .detach_and_log_err(cx);
-                                            }),
-                                        )
-                                    },
-                                ),
-                        )
-                    })
-                    .into_any()
+                })
+                .when_some(leader_status_box, |this, status_box| {
+                    this.child(
+                        div()
+                            .absolute()
+                            .w_96()
+                            .bottom_3()
+                            .right_3()
+                            .elevation_2(cx)
+                            .p_1()
+                            .child(status_box)
+                            .when_some(
+                                leader_join_data,
+                                |this, (leader_project_id, leader_user_id)| {
+                                    this.cursor_pointer().on_mouse_down(
+                                        MouseButton::Left,
+                                        cx.listener(move |this, _, cx| {
+                                            crate::join_in_room_project(
+                                                leader_project_id,
+                                                leader_user_id,
+                                                this.app_state().clone(),
+                                                cx,
+                                            )
+                                            .detach_and_log_err(cx);
+                                        }),
+                                    )
+                                },
+                            ),
+                    )
+                })
+                .into_any()
             }
             Member::Axis(axis) => axis
                 .render(
@@ -379,6 +492,22 @@ impl Member {
         }
     }
 
+    fn index_path_of(&self, pane: &View<Pane>, path: &mut Vec<usize>) -> bool {
+        match self {
+            Member::Axis(axis) => {
+                for (ix, member) in axis.members.iter().enumerate() {
+                    path.push(ix);
+                    if member.index_path_of(pane, path) {
+                        return true;
+                    }
+                    path.pop();
+                }
+                false
+            }
+            Member::Pane(found) => found == pane,
+        }
+    }
+
     fn collect_panes<'a>(&'a self, panes: &mut Vec<&'a View<Pane>>) {
         match self {
             Member::Axis(axis) => {
@@ -501,6 +630,35 @@ impl PaneAxis {
         }
     }
 
+    fn rebalance_axis_containing(&self, pane: &View<Pane>) -> bool {
+        if self
+            .members
+            .iter()
+            .any(|member| matches!(member, Member::Pane(p) if p == pane))
+        {
+            *self.flexes.lock() = vec![1.; self.members.len()];
+            return true;
+        }
+        self.members.iter().any(|member| match member {
+            Member::Axis(axis) => axis.rebalance_axis_containing(pane),
+            Member::Pane(_) => false,
+        })
+    }
+
+    fn axis_containing(&self, pane: &View<Pane>) -> Option<(&PaneAxis, usize)> {
+        if let Some(ix) = self
+            .members
+            .iter()
+            .position(|member| matches!(member, Member::Pane(p) if p == pane))
+        {
+            return Some((self, ix));
+        }
+        self.members.iter().find_map(|member| match member {
+            Member::Axis(axis) => axis.axis_containing(pane),
+            Member::Pane(_) => None,
+        })
+    }
+
     fn reset_pane_sizes(&self) {
         *self.flexes.lock() = vec![1.; self.members.len()];
         for member in self.members.iter() {
@@ -808,6 +966,37 @@ pub enum ResizeIntent {
     Narrow,
 }
 
+/// Sets `flexes[ix]` to `flex`, scaling the other flexes so that they still add up to their
+/// count, without making any of them smaller than `min_flex`.
+fn set_member_flex(flexes: &mut [f32], ix: usize, flex: f32, min_flex: f32) {
+    if flexes.len() < 2 {
+        return;
+    }
+    let count = flexes.len() as f32;
+    flexes[ix] = flex.clamp(min_flex, count - min_flex * (count - 1.));
+
+    let mut remaining = count - flexes[ix];
+    let mut siblings = (0..flexes.len()).filter(|&i| i != ix).collect::<Vec<_>>();
+    while !siblings.is_empty() {
+        let scale = remaining / siblings.iter().map(|&i| flexes[i]).sum::<f32>();
+        let (too_small, others): (Vec<_>, Vec<_>) = siblings
+            .into_iter()
+            .partition(|&i| flexes[i] * scale < min_flex);
+        if too_small.is_empty() {
+            for i in others {
+                flexes[i] *= scale;
+            }
+            return;
+        }
+        for i in too_small {
+            flexes[i] = min_flex;
+            remaining -= min_flex;
+        }
+        siblings = others;
+    }
+    flexes[ix] += remaining;
+}
+
 mod element {
     use std::mem;
     use std::{cell::RefCell, iter, rc::Rc, sync::Arc};
@@ -0,0 +1,139 @@
+use gpui::{View, ViewContext};
+
+use crate::{pane::Pane, ReservationKind, SplitDirection, TogglePaneLock, Workspace};
+
+impl Workspace {
+    /// Locks or unlocks `pane`. The items opened while a locked pane is active, e.g. by code
+    /// navigation, go to the last active unlocked pane instead, and the tabs of other panes
+    /// can't be dropped onto its tab bar. Followed collaborators still place items there.
+    pub fn toggle_pane_lock(&mut self, pane: &View<Pane>, cx: &mut ViewContext<Self>) {
+        let locked = !pane.read(cx).is_locked();
+        pane.update(cx, |pane, cx| pane.set_locked(locked, cx));
+        if locked && self.last_active_unreserved_pane.as_ref() == Some(&pane.downgrade()) {
+            self.last_active_unreserved_pane = None;
+        }
+        self.serialize_workspace(cx);
+    }
+
+    pub(crate) fn toggle_active_pane_lock(
+        &mut self,
+        _: &TogglePaneLock,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let pane = self.active_pane.clone();
+        self.toggle_pane_lock(&pane, cx);
+    }
+
+    /// Returns the pane in which to open a new item instead of `pane` when `pane` is locked:
+    /// the last active unlocked pane, or a new one split off `pane` if all of them are locked.
+    pub(crate) fn unlocked_pane_for_new_item(
+        &mut self,
+        pane: View<Pane>,
+        cx: &mut ViewContext<Self>,
+    ) -> View<Pane> {
+        if !pane.read(cx).is_locked() {
+            return pane;
+        }
+        match self.pane_for_item_kind(&pane, ReservationKind::Editor, cx) {
+            Some(pane) => pane,
+            None => self.split_pane(pane, SplitDirection::Right, cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::TestItem, persistence::DB, register_serializable_item, tests::init_test,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_locked_pane(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| register_serializable_item::<TestItem>(cx));
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({})).await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let workspace_id = DB.next_id().await.unwrap();
+        let (left_pane, right_pane) = workspace.update(cx, |workspace, cx| {
+            workspace.database_id = Some(workspace_id);
+            let left_pane = workspace.active_pane().clone();
+            let item = Box::new(cx.new_view(TestItem::new));
+            workspace.add_item_to_active_pane(item, None, true, cx);
+            let right_pane = workspace.split_pane(left_pane.clone(), SplitDirection::Right, cx);
+            right_pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(cx.new_view(TestItem::new)), true, true, None, cx)
+            });
+            (left_pane, right_pane)
+        });
+        cx.run_until_parked();
+        cx.dispatch_action(TogglePaneLock);
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.active_pane(), &right_pane);
+            assert!(right_pane.read(cx).is_locked());
+            assert!(!left_pane.read(cx).is_locked());
+        });
+
+        // Items opened while the locked pane is active go to an unlocked pane.
+        let item = cx.new_view(TestItem::new);
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, cx);
+        });
+        right_pane.update(cx, |pane, _| assert_eq!(pane.items_len(), 1));
+        left_pane.update(cx, |pane, _| assert!(pane.index_for_item(&item).is_some()));
+
+        // Followed collaborators still place items in the locked pane.
+        let leader_item = cx.new_view(TestItem::new);
+        workspace.update(cx, |workspace, cx| {
+            workspace.place_leader_item(&right_pane, Box::new(leader_item.clone()), false, cx);
+        });
+        right_pane.update(cx, |pane, _| {
+            assert!(pane.index_for_item(&leader_item).is_some())
+        });
+
+        // The lock is restored with the pane.
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.serialize_workspace_internal(cx)
+            })
+            .await;
+        let serialized_workspace = DB.workspace_for_roots(&["/root"]).unwrap();
+        let restored_workspace = cx.new_view(|cx| Workspace::test_new(project.clone(), cx));
+        restored_workspace
+            .update(cx, |_, cx| {
+                Workspace::load_workspace(serialized_workspace, Vec::new(), cx)
+            })
+            .await
+            .unwrap();
+        restored_workspace.update(cx, |workspace, cx| {
+            let locked = workspace
+                .center
+                .panes()
+                .into_iter()
+                .map(|pane| pane.read(cx).is_locked())
+                .collect::<Vec<_>>();
+            assert_eq!(locked, [false, true]);
+        });
+
+        // When all panes are locked, a new one is split off for the item.
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_pane_lock(&left_pane, cx);
+            let item = cx.new_view(TestItem::new);
+            workspace.add_item_to_active_pane(Box::new(item), None, true, cx);
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes().len(), 3);
+            let new_pane = workspace.panes().last().unwrap();
+            assert!(!new_pane.read(cx).is_locked());
+            assert_eq!(new_pane.read(cx).items_len(), 1);
+        });
+    }
+}
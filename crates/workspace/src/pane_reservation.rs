@@ -0,0 +1,134 @@
+use gpui::{AppContext, View, ViewContext};
+use serde::{Deserialize, Serialize};
+
+use crate::{pane::Pane, Workspace};
+
+/// What a pane is reserved for, or which kind of item is being opened, see
+/// [`Workspace::set_pane_reserved_for`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReservationKind {
+    /// Regular items, such as editors.
+    Editor,
+    /// Terminals.
+    Terminal,
+    /// Keeps out all items opened automatically, leaving the pane to the ones moved there manually.
+    Any,
+}
+
+impl ReservationKind {
+    fn accepts(self, item_kind: ReservationKind) -> bool {
+        self != ReservationKind::Any && self == item_kind
+    }
+}
+
+impl Workspace {
+    /// Reserves a pane for a kind of items, so that items of other kinds opened while it is
+    /// active end up in another pane. Items dragged into the pane are accepted regardless.
+    pub fn set_pane_reserved_for(
+        &mut self,
+        pane: &View<Pane>,
+        kind: Option<ReservationKind>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let pane = pane.downgrade();
+        if let Some(kind) = kind {
+            if self.last_active_unreserved_pane.as_ref() == Some(&pane) {
+                self.last_active_unreserved_pane = None;
+            }
+            self.pane_reservations.insert(pane, kind);
+        } else {
+            self.pane_reservations.remove(&pane);
+        }
+        self.serialize_workspace(cx);
+    }
+
+    pub fn pane_reserved_for(&self, pane: &View<Pane>) -> Option<ReservationKind> {
+        self.pane_reservations.get(&pane.downgrade()).copied()
+    }
+
+    /// Returns the pane in which to automatically open an item of the given kind, instead of
+    /// `pane` when that one is reserved for other items or locked. Returns `None` if all center
+    /// panes are.
+    pub(crate) fn pane_for_item_kind(
+        &self,
+        pane: &View<Pane>,
+        kind: ReservationKind,
+        cx: &AppContext,
+    ) -> Option<View<Pane>> {
+        let accepts = |pane: &View<Pane>| {
+            !pane.read(cx).is_locked()
+                && self
+                    .pane_reserved_for(pane)
+                    .map_or(true, |reserved_for| reserved_for.accepts(kind))
+        };
+        if accepts(pane) {
+            return Some(pane.clone());
+        }
+        self.last_active_unreserved_pane
+            .as_ref()
+            .and_then(|pane| pane.upgrade())
+            .filter(|pane| self.panes.contains(pane) && accepts(pane))
+            .or_else(|| self.panes.iter().find(|pane| accepts(pane)).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{item::test::TestItem, move_item, tests::init_test, SplitDirection};
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_add_item_to_reserved_pane(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let (editor_pane, terminal_pane) = workspace.update(cx, |workspace, cx| {
+            let editor_pane = workspace.active_pane().clone();
+            let terminal_pane =
+                workspace.split_pane(editor_pane.clone(), SplitDirection::Right, cx);
+            workspace.set_pane_reserved_for(&terminal_pane, Some(ReservationKind::Terminal), cx);
+            terminal_pane.update(cx, |pane, cx| pane.focus(cx));
+            (editor_pane, terminal_pane)
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.active_pane(), &terminal_pane);
+        });
+
+        // Items opened while the reserved pane is active go to the last unreserved pane.
+        let item = cx.new_view(TestItem::new);
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, cx);
+        });
+        terminal_pane.update(cx, |pane, _| assert_eq!(pane.items_len(), 0));
+        editor_pane.update(cx, |pane, _| assert!(pane.index_for_item(&item).is_some()));
+
+        // Items dragged into the reserved pane are accepted.
+        workspace.update(cx, |_, cx| {
+            move_item(&editor_pane, &terminal_pane, item.item_id(), 0, cx);
+        });
+        terminal_pane.update(cx, |pane, _| assert!(pane.index_for_item(&item).is_some()));
+
+        // When all panes are reserved, a new one is split off for the item.
+        workspace.update(cx, |workspace, cx| {
+            workspace.set_pane_reserved_for(&editor_pane, Some(ReservationKind::Any), cx);
+            let item = cx.new_view(TestItem::new);
+            workspace.add_item_to_active_pane(Box::new(item), None, true, cx);
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes().len(), 3);
+            let new_pane = workspace.panes().last().unwrap();
+            assert_eq!(workspace.pane_reserved_for(new_pane), None);
+            assert_eq!(new_pane.read(cx).items_len(), 1);
+            assert_eq!(editor_pane.read(cx).items_len(), 0);
+            assert_eq!(terminal_pane.read(cx).items_len(), 1);
+        });
+    }
+}
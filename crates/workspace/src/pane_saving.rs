@@ -0,0 +1,153 @@
+use anyhow::Result;
+use gpui::{Task, View, ViewContext};
+
+use crate::{
+    pane::{CloseAllItems, Pane},
+    ClosePaneSavingItems, SaveIntent, SaveItemsInPane, Workspace,
+};
+
+impl Workspace {
+    /// Saves the dirty items of `pane`, like saving all items does for the whole workspace.
+    /// Resolves to whether all of them were saved.
+    pub fn save_items_in_pane(
+        &mut self,
+        pane: View<Pane>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<bool>> {
+        self.save_dirty_items_in_panes(&[pane], SaveIntent::SaveAll, false, cx)
+    }
+
+    /// Saves the dirty items of `pane`, asking once what to do with them, then closes the pane
+    /// along with its items instead of moving them to another pane. The last center pane stays
+    /// open, empty. Resolves to `false` if the user cancelled.
+    pub fn close_pane_saving(
+        &mut self,
+        pane: View<Pane>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<bool>> {
+        let save = self.save_dirty_items_in_panes(&[pane.clone()], SaveIntent::Close, false, cx);
+        cx.spawn(|_, mut cx| async move {
+            if !save.await? {
+                return Ok(false);
+            }
+            // The items left dirty are the ones the user chose to discard, so they're closed
+            // without prompting again, and the pane removes itself once it's empty.
+            let close = pane.update(&mut cx, |pane, cx| {
+                pane.close_all_items(
+                    &CloseAllItems {
+                        save_intent: Some(SaveIntent::Skip),
+                        close_pinned: true,
+                    },
+                    cx,
+                )
+            })?;
+            if let Some(close) = close {
+                close.await?;
+            }
+            Ok(true)
+        })
+    }
+
+    pub(crate) fn save_items_in_active_pane(
+        &mut self,
+        _: &SaveItemsInPane,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let pane = self.active_pane.clone();
+        self.save_items_in_pane(pane, cx).detach_and_log_err(cx);
+    }
+
+    pub(crate) fn close_active_pane_saving_items(
+        &mut self,
+        _: &ClosePaneSavingItems,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let pane = self.active_pane.clone();
+        self.close_pane_saving(pane, cx).detach_and_log_err(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::{TestItem, TestProjectItem},
+        tests::init_test,
+        SplitDirection,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_save_and_close_pane(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let clean = cx.new_view(TestItem::new);
+        let dirty_items = [1, 2].map(|id| {
+            cx.new_view(|cx| {
+                TestItem::new(cx)
+                    .with_dirty(true)
+                    .with_project_items(&[TestProjectItem::new(id, &format!("{id}.txt"), cx)])
+            })
+        });
+        let pane = workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(clean.clone()), None, true, cx);
+            let pane =
+                workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Right, cx);
+            for item in &dirty_items {
+                pane.update(cx, |pane, cx| {
+                    pane.add_item(Box::new(item.clone()), true, true, None, cx)
+                });
+            }
+            pane
+        });
+        cx.run_until_parked();
+        let save_counts = |cx: &mut gpui::VisualTestContext| {
+            dirty_items
+                .each_ref()
+                .map(|item| item.read_with(cx, |item, _| (item.save_count, item.is_dirty)))
+        };
+
+        // Saving the pane's items leaves it open, with clean items.
+        let saved = workspace
+            .update(cx, |workspace, cx| {
+                workspace.save_items_in_pane(pane.clone(), cx)
+            })
+            .await
+            .unwrap();
+        assert!(saved);
+        assert!(!cx.has_pending_prompt());
+        assert_eq!(save_counts(cx), [(1, false), (1, false)]);
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes().len(), 2);
+            assert_eq!(pane.read(cx).items_len(), 2);
+        });
+
+        // Closing the pane asks once for both dirty items, then closes it with its items.
+        for item in &dirty_items {
+            item.update(cx, |item, _| item.is_dirty = true);
+        }
+        let close = workspace.update(cx, |workspace, cx| {
+            workspace.close_pane_saving(pane.clone(), cx)
+        });
+        cx.run_until_parked();
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(0);
+        cx.run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        assert!(close.await.unwrap());
+        assert_eq!(save_counts(cx), [(2, false), (2, false)]);
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes().len(), 1);
+            assert_eq!(workspace.active_pane().read(cx).items_len(), 1);
+            assert!(workspace
+                .panes_by_item
+                .get(&dirty_items[0].entity_id())
+                .is_none());
+        });
+    }
+}
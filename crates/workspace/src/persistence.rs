@@ -1,6 +1,13 @@
 pub mod model;
 
-use std::{path::Path, str::FromStr};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        LazyLock,
+    },
+};
 
 use anyhow::{anyhow, bail, Context, Result};
 use client::DevServerProjectId;
@@ -10,8 +17,10 @@ use gpui::{point, size, Axis, Bounds, WindowBounds, WindowId};
 use language::{LanguageName, Toolchain};
 use project::WorktreeId;
 use remote::ssh_session::SshProjectId;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use sqlez::{
     bindable::{Bind, Column, StaticColumnCount},
+    domain::Domain,
     statement::Statement,
 };
 
@@ -19,15 +28,36 @@ use ui::px;
 use util::{maybe, ResultExt};
 use uuid::Uuid;
 
-use crate::WorkspaceId;
+use crate::{ReservationKind, WorkspaceId};
 
 use model::{
-    GroupId, LocalPaths, PaneId, SerializedItem, SerializedPane, SerializedPaneGroup,
+    GroupId, ItemId, LocalPaths, PaneId, SerializedItem, SerializedPane, SerializedPaneGroup,
     SerializedSshProject, SerializedWorkspace,
 };
 
 use self::model::{DockStructure, LocalPathsOrder, SerializedWorkspaceLocation};
 
+/// A recently opened workspace, see [`WorkspaceDb::recent_workspaces_with_metadata`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecentWorkspace {
+    pub location: SerializedWorkspaceLocation,
+    /// When the workspace was last activated, as a UTC `YYYY-MM-DD HH:MM:SS` timestamp.
+    pub last_opened_at: String,
+    /// The number of items that were open when the workspace was last serialized.
+    pub item_count: usize,
+    pub is_ssh: bool,
+}
+
+/// A snapshot of a workspace's layout, see [`WorkspaceDb::layout_checkpoints`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayoutCheckpoint {
+    pub(crate) id: i64,
+    /// When the checkpoint was written, as a UTC `YYYY-MM-DD HH:MM:SS` timestamp.
+    pub created_at: String,
+    pub pane_count: usize,
+    pub item_count: usize,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub(crate) struct SerializedAxis(pub(crate) gpui::Axis);
 impl sqlez::bindable::StaticColumnCount for SerializedAxis {}
@@ -45,6 +75,26 @@ impl sqlez::bindable::Bind for SerializedAxis {
     }
 }
 
+impl Serialize for SerializedAxis {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self.0 {
+            gpui::Axis::Horizontal => "Horizontal",
+            gpui::Axis::Vertical => "Vertical",
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SerializedAxis {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "Horizontal" => Ok(Self(Axis::Horizontal)),
+            "Vertical" => Ok(Self(Axis::Vertical)),
+            axis => Err(serde::de::Error::custom(format!("unknown axis {axis:?}"))),
+        }
+    }
+}
+
 impl sqlez::bindable::Column for SerializedAxis {
     fn column(
         statement: &mut sqlez::statement::Statement,
@@ -173,6 +223,8 @@ define_connection! {
     //   centered_layout: Option<bool>, // Is the Centered Layout mode activated?
     //   session_id: Option<String>, // Session id
     //   window_id: Option<u64>, // Window Id
+    //   session_notes: Option<String>, // The user's notes about where they left off
+    //   shared_local_paths: Option<Bincode<Vec<PathBuf>>>, // Set instead of local_paths by windows opened on the roots of another one
     // )
     //
     // pane_groups(
@@ -204,6 +256,21 @@ define_connection! {
     //     position: usize, // Position of the item in the parent pane. This is equivalent to panes' position column
     //     active: bool, // Indicates if this item is the active one in the pane
     //     preview: bool // Indicates if this item is a preview item
+    //     navigation_data: Option<String>, // JSON of the data restoring the item's position
+    //     stable_id: Option<Uuid>, // Identifies the item across sessions, assigned on restore for older rows
+    // )
+    //
+    // item_bookmarks(
+    //     workspace_id: usize, // References workspaces table
+    //     position: usize, // Order in which the items were bookmarked
+    //     path: PathBuf, // Absolute path of the bookmarked item's file
+    // )
+    //
+    // window_bounds_by_display(
+    //     workspace_id: usize, // References workspaces table
+    //     display: Uuid, // The display the window was on
+    //     window_state, window_x, window_y, window_width, window_height, // WindowBounds, as in workspaces
+    //     timestamp: String, // UTC YYYY-MM-DD HH:MM:SS, when the window was last on the display
     // )
     pub static ref DB: WorkspaceDb<()> =
     &[
@@ -313,8 +380,8 @@ define_connection! {
         ALTER TABLE pane_groups ADD COLUMN flexes TEXT;
     ),
     // Add fullscreen field to workspace
-    // Deprecated, `WindowBounds` holds the fullscreen state now.
-    // Preserving so users can downgrade Zed.
+    // `WindowBounds` holds the fullscreen state now, but not on every platform, so the field
+    // is written again by `Workspace::set_fullscreen`.
     sql!(
         ALTER TABLE workspaces ADD COLUMN fullscreen INTEGER; //bool
     ),
@@ -383,16 +450,150 @@ define_connection! {
     sql!(
         ALTER TABLE toolchains ADD COLUMN raw_json TEXT DEFAULT "{}";
     ),
+    sql!(
+        ALTER TABLE workspaces ADD COLUMN left_dock_focused INTEGER; //bool
+        ALTER TABLE workspaces ADD COLUMN right_dock_focused INTEGER; //bool
+        ALTER TABLE workspaces ADD COLUMN bottom_dock_focused INTEGER; //bool
+    ),
+    sql!(
+        ALTER TABLE panes ADD COLUMN reserved_for TEXT;
+    ),
+    // The center group of a checkpoint is stored as JSON, as its panes and items aren't
+    // part of the workspace.
+    sql!(
+        CREATE TABLE layout_checkpoints (
+            checkpoint_id INTEGER PRIMARY KEY,
+            workspace_id INTEGER NOT NULL,
+            timestamp TEXT DEFAULT CURRENT_TIMESTAMP NOT NULL,
+            center_group TEXT NOT NULL,
+            left_dock_visible INTEGER,
+            left_dock_active_panel TEXT,
+            left_dock_zoom INTEGER,
+            left_dock_focused INTEGER,
+            right_dock_visible INTEGER,
+            right_dock_active_panel TEXT,
+            right_dock_zoom INTEGER,
+            right_dock_focused INTEGER,
+            bottom_dock_visible INTEGER,
+            bottom_dock_active_panel TEXT,
+            bottom_dock_zoom INTEGER,
+            bottom_dock_focused INTEGER,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+        ) STRICT;
+    ),
+    sql!(
+        ALTER TABLE workspaces ADD COLUMN session_notes TEXT;
+    ),
+    sql!(
+        ALTER TABLE panes ADD COLUMN tab_bar_visibility INTEGER; //bool
+    ),
+    sql!(
+        CREATE TABLE item_bookmarks (
+            workspace_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            path BLOB NOT NULL,
+            PRIMARY KEY(workspace_id, position),
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+        ) STRICT;
+    ),
+    // `local_paths` is unique, so the other windows open on the same roots store them here.
+    sql!(
+        ALTER TABLE workspaces ADD COLUMN shared_local_paths BLOB;
+    ),
+    sql!(
+        CREATE TABLE suppressed_notifications (
+            workspace_id INTEGER NOT NULL,
+            namespace TEXT NOT NULL,
+            PRIMARY KEY(workspace_id, namespace),
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+        ) STRICT;
+    ),
+    // The bounds stored in `workspaces` become the ones of the display they were saved on.
+    sql!(
+        CREATE TABLE window_bounds_by_display (
+            workspace_id INTEGER NOT NULL,
+            display BLOB NOT NULL,
+            window_state TEXT NOT NULL,
+            window_x REAL,
+            window_y REAL,
+            window_width REAL,
+            window_height REAL,
+            timestamp TEXT DEFAULT CURRENT_TIMESTAMP NOT NULL,
+            PRIMARY KEY(workspace_id, display),
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+        ) STRICT;
+        INSERT INTO window_bounds_by_display(
+            workspace_id, display, window_state, window_x, window_y, window_width, window_height, timestamp
+        )
+        SELECT workspace_id, display, window_state, window_x, window_y, window_width, window_height, timestamp
+        FROM workspaces
+        WHERE display IS NOT NULL AND window_state IS NOT NULL;
+    ),
+    sql!(
+        ALTER TABLE items ADD COLUMN navigation_data TEXT;
+    ),
+    sql!(
+        ALTER TABLE panes ADD COLUMN locked INTEGER; //bool
+    ),
+    // The items saved before are given an id once restored, and keep it from their next save.
+    sql!(
+        ALTER TABLE items ADD COLUMN stable_id BLOB;
+    ),
     ];
 }
 
+/// Whether [`DB`] was migrated by a newer version of Zed, see
+/// [`WorkspaceDb::migrated_by_newer_version`]. Such a database is never written to.
+pub(crate) static NEWER_SCHEMA: LazyLock<bool> =
+    LazyLock::new(|| DB.migrated_by_newer_version().log_err().unwrap_or(false));
+
+/// Set when the user chose to restore workspaces from a database with a [`NEWER_SCHEMA`].
+static RESTORE_FROM_NEWER_SCHEMA: AtomicBool = AtomicBool::new(false);
+
+/// Whether workspaces are restored from [`DB`], which they aren't when it was migrated by a
+/// newer version of Zed, unless [`restore_from_newer_schema`] was called.
+pub(crate) fn restores_workspaces() -> bool {
+    !*NEWER_SCHEMA || RESTORE_FROM_NEWER_SCHEMA.load(Ordering::Acquire)
+}
+
+/// Restores what can be read of the workspaces stored by a newer version of Zed, still
+/// without writing to its database.
+pub(crate) fn restore_from_newer_schema() {
+    RESTORE_FROM_NEWER_SCHEMA.store(true, Ordering::Release);
+}
+
 impl WorkspaceDb {
+    /// Whether the database has more migrations than this version of Zed knows about, which
+    /// happens after downgrading Zed. The queries of this version may not match its schema.
+    pub fn migrated_by_newer_version(&self) -> Result<bool> {
+        let migration_count = self.select_row_bound::<&str, usize>(sql!(
+            SELECT COUNT(*) FROM migrations WHERE domain = ?
+        ))?(<Self as Domain>::name())?
+        .unwrap_or(0);
+        Ok(migration_count > <Self as Domain>::migrations().len())
+    }
+
     /// Returns a serialized workspace for the given worktree_roots. If the passed array
     /// is empty, the most recent workspace is returned instead. If no workspace for the
     /// passed roots is stored, returns none.
     pub(crate) fn workspace_for_roots<P: AsRef<Path>>(
         &self,
         worktree_roots: &[P],
+    ) -> Option<SerializedWorkspace> {
+        self.workspace_for_roots_in_window(worktree_roots, None)
+    }
+
+    /// Like [`Self::workspace_for_roots`], but when several windows were open on these roots,
+    /// returns the workspace of the window with the given id, falling back to the first window
+    /// that was opened on them.
+    pub(crate) fn workspace_for_roots_in_window<P: AsRef<Path>>(
+        &self,
+        worktree_roots: &[P],
+        window_id: Option<u64>,
     ) -> Option<SerializedWorkspace> {
         // paths are sorted before db interactions to ensure that the order of the paths
         // doesn't affect the workspace selection for existing workspaces
@@ -409,6 +610,7 @@ impl WorkspaceDb {
             centered_layout,
             docks,
             window_id,
+            session_notes,
         ): (
             WorkspaceId,
             Option<LocalPaths>,
@@ -418,11 +620,12 @@ impl WorkspaceDb {
             Option<bool>,
             DockStructure,
             Option<u64>,
+            Option<String>,
         ) = self
             .select_row_bound(sql! {
                 SELECT
                     workspace_id,
-                    local_paths,
+                    IFNULL(local_paths, shared_local_paths),
                     local_paths_order,
                     window_state,
                     window_x,
@@ -434,17 +637,23 @@ impl WorkspaceDb {
                     left_dock_visible,
                     left_dock_active_panel,
                     left_dock_zoom,
+                    left_dock_focused,
                     right_dock_visible,
                     right_dock_active_panel,
                     right_dock_zoom,
+                    right_dock_focused,
                     bottom_dock_visible,
                     bottom_dock_active_panel,
                     bottom_dock_zoom,
-                    window_id
+                    bottom_dock_focused,
+                    window_id,
+                    session_notes
                 FROM workspaces
-                WHERE local_paths = ?
+                WHERE local_paths = ?1 OR shared_local_paths = ?1
+                ORDER BY window_id IS ?2 DESC, local_paths IS NULL
+                LIMIT 1
             })
-            .and_then(|mut prepared_statement| (prepared_statement)(&local_paths))
+            .and_then(|mut prepared_statement| (prepared_statement)((&local_paths, window_id)))
             .context("No workspaces found")
             .warn_on_err()
             .flatten()?;
@@ -467,6 +676,7 @@ impl WorkspaceDb {
                 .log_err()?,
             window_bounds,
             centered_layout: centered_layout.unwrap_or(false),
+            session_notes,
             display,
             docks,
             session_id: None,
@@ -478,13 +688,22 @@ impl WorkspaceDb {
         &self,
         ssh_project: &SerializedSshProject,
     ) -> Option<SerializedWorkspace> {
-        let (workspace_id, window_bounds, display, centered_layout, docks, window_id): (
+        let (
+            workspace_id,
+            window_bounds,
+            display,
+            centered_layout,
+            docks,
+            window_id,
+            session_notes,
+        ): (
             WorkspaceId,
             Option<SerializedWindowBounds>,
             Option<Uuid>,
             Option<bool>,
             DockStructure,
             Option<u64>,
+            Option<String>,
         ) = self
             .select_row_bound(sql! {
                 SELECT
@@ -499,13 +718,17 @@ impl WorkspaceDb {
                     left_dock_visible,
                     left_dock_active_panel,
                     left_dock_zoom,
+                    left_dock_focused,
                     right_dock_visible,
                     right_dock_active_panel,
                     right_dock_zoom,
+                    right_dock_focused,
                     bottom_dock_visible,
                     bottom_dock_active_panel,
                     bottom_dock_zoom,
-                    window_id
+                    bottom_dock_focused,
+                    window_id,
+                    session_notes
                 FROM workspaces
                 WHERE ssh_project_id = ?
             })
@@ -523,6 +746,7 @@ impl WorkspaceDb {
                 .log_err()?,
             window_bounds,
             centered_layout: centered_layout.unwrap_or(false),
+            session_notes,
             display,
             docks,
             session_id: None,
@@ -543,11 +767,39 @@ impl WorkspaceDb {
 
                 match workspace.location {
                     SerializedWorkspaceLocation::Local(local_paths, local_paths_order) => {
-                        conn.exec_bound(sql!(
-                            DELETE FROM toolchains WHERE workspace_id = ?1;
-                            DELETE FROM workspaces WHERE local_paths = ? AND workspace_id != ?
-                        ))?((&local_paths, workspace.id))
-                        .context("clearing out old locations")?;
+                        // When another window of this session is open on the same roots, keep
+                        // its workspace and store this one next to it, until this one is gone.
+                        let shares_location = conn.select_row_bound::<_, bool>(sql!(
+                            SELECT EXISTS(
+                                SELECT 1 FROM workspaces
+                                WHERE local_paths = ?1
+                                    AND workspace_id != ?2
+                                    AND session_id = ?3
+                                    AND window_id IS NOT ?4
+                            ) OR EXISTS(
+                                SELECT 1 FROM workspaces
+                                WHERE workspace_id = ?2 AND shared_local_paths = ?1
+                            )
+                        ))?((&local_paths, workspace.id, workspace.session_id.clone(), workspace.window_id))?
+                        .unwrap_or(false);
+
+                        if shares_location {
+                            conn.exec_bound(sql!(
+                                DELETE FROM workspaces WHERE shared_local_paths = ?1 AND window_id IS ?2 AND workspace_id != ?3
+                            ))?((&local_paths, workspace.window_id, workspace.id))
+                            .context("clearing out old locations")?;
+                        } else {
+                            conn.exec_bound(sql!(
+                                DELETE FROM toolchains WHERE workspace_id = ?1;
+                                DELETE FROM workspaces WHERE local_paths = ? AND workspace_id != ?
+                            ))?((&local_paths, workspace.id))
+                            .context("clearing out old locations")?;
+                            // Windows that shared these roots and have been closed since are gone.
+                            conn.exec_bound(sql!(
+                                DELETE FROM workspaces WHERE shared_local_paths = ?1 AND workspace_id != ?2 AND (session_id IS NULL OR session_id != ?3)
+                            ))?((&local_paths, workspace.id, workspace.session_id.clone()))
+                            .context("clearing out closed windows")?;
+                        }
 
                         // Upsert
                         let query = sql!(
@@ -558,17 +810,22 @@ impl WorkspaceDb {
                                 left_dock_visible,
                                 left_dock_active_panel,
                                 left_dock_zoom,
+                                left_dock_focused,
                                 right_dock_visible,
                                 right_dock_active_panel,
                                 right_dock_zoom,
+                                right_dock_focused,
                                 bottom_dock_visible,
                                 bottom_dock_active_panel,
                                 bottom_dock_zoom,
+                                bottom_dock_focused,
                                 session_id,
                                 window_id,
+                                session_notes,
+                                shared_local_paths,
                                 timestamp
                             )
-                            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, CURRENT_TIMESTAMP)
+                            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, CURRENT_TIMESTAMP)
                             ON CONFLICT DO
                             UPDATE SET
                                 local_paths = ?2,
@@ -576,18 +833,30 @@ impl WorkspaceDb {
                                 left_dock_visible = ?4,
                                 left_dock_active_panel = ?5,
                                 left_dock_zoom = ?6,
-                                right_dock_visible = ?7,
-                                right_dock_active_panel = ?8,
-                                right_dock_zoom = ?9,
-                                bottom_dock_visible = ?10,
-                                bottom_dock_active_panel = ?11,
-                                bottom_dock_zoom = ?12,
-                                session_id = ?13,
-                                window_id = ?14,
+                                left_dock_focused = ?7,
+                                right_dock_visible = ?8,
+                                right_dock_active_panel = ?9,
+                                right_dock_zoom = ?10,
+                                right_dock_focused = ?11,
+                                bottom_dock_visible = ?12,
+                                bottom_dock_active_panel = ?13,
+                                bottom_dock_zoom = ?14,
+                                bottom_dock_focused = ?15,
+                                session_id = ?16,
+                                window_id = ?17,
+                                session_notes = ?18,
+                                shared_local_paths = ?19,
                                 timestamp = CURRENT_TIMESTAMP
                         );
                         let mut prepared_query = conn.exec_bound(query)?;
-                        let args = (workspace.id, &local_paths, &local_paths_order, workspace.docks, workspace.session_id, workspace.window_id);
+                        // A workspace sharing its roots stores them apart, so that it isn't
+                        // found by its location.
+                        let (own_local_paths, shared_local_paths) = if shares_location {
+                            (None, Some(&local_paths))
+                        } else {
+                            (Some(&local_paths), None)
+                        };
+                        let args = (workspace.id, own_local_paths, &local_paths_order, workspace.docks, workspace.session_id, workspace.window_id, workspace.session_notes, shared_local_paths);
 
                         prepared_query(args).context("Updating workspace")?;
                     }
@@ -606,38 +875,47 @@ impl WorkspaceDb {
                                 left_dock_visible,
                                 left_dock_active_panel,
                                 left_dock_zoom,
+                                left_dock_focused,
                                 right_dock_visible,
                                 right_dock_active_panel,
                                 right_dock_zoom,
+                                right_dock_focused,
                                 bottom_dock_visible,
                                 bottom_dock_active_panel,
                                 bottom_dock_zoom,
+                                bottom_dock_focused,
                                 session_id,
                                 window_id,
+                                session_notes,
                                 timestamp
                             )
-                            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, CURRENT_TIMESTAMP)
+                            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, CURRENT_TIMESTAMP)
                             ON CONFLICT DO
                             UPDATE SET
                                 ssh_project_id = ?2,
                                 left_dock_visible = ?3,
                                 left_dock_active_panel = ?4,
                                 left_dock_zoom = ?5,
-                                right_dock_visible = ?6,
-                                right_dock_active_panel = ?7,
-                                right_dock_zoom = ?8,
-                                bottom_dock_visible = ?9,
-                                bottom_dock_active_panel = ?10,
-                                bottom_dock_zoom = ?11,
-                                session_id = ?12,
-                                window_id = ?13,
+                                left_dock_focused = ?6,
+                                right_dock_visible = ?7,
+                                right_dock_active_panel = ?8,
+                                right_dock_zoom = ?9,
+                                right_dock_focused = ?10,
+                                bottom_dock_visible = ?11,
+                                bottom_dock_active_panel = ?12,
+                                bottom_dock_zoom = ?13,
+                                bottom_dock_focused = ?14,
+                                session_id = ?15,
+                                window_id = ?16,
+                                session_notes = ?17,
                                 timestamp = CURRENT_TIMESTAMP
                         ))?((
                             workspace.id,
                             ssh_project.id.0,
                             workspace.docks,
                             workspace.session_id,
-                            workspace.window_id
+                            workspace.window_id,
+                            workspace.session_notes
                         ))
                         .context("Updating workspace")?;
                     }
@@ -711,6 +989,22 @@ impl WorkspaceDb {
         }
     }
 
+    query! {
+        fn recent_workspaces_with_item_counts() -> Result<Vec<(WorkspaceId, LocalPaths, LocalPathsOrder, Option<u64>, String, usize)>> {
+            SELECT
+                workspace_id,
+                local_paths,
+                local_paths_order,
+                ssh_project_id,
+                timestamp,
+                (SELECT COUNT(*) FROM items WHERE items.workspace_id = workspaces.workspace_id)
+            FROM workspaces
+            WHERE local_paths IS NOT NULL
+                OR ssh_project_id IS NOT NULL
+            ORDER BY timestamp DESC
+        }
+    }
+
     query! {
         fn session_workspaces(session_id: String) -> Result<Vec<(LocalPaths, LocalPathsOrder, Option<u64>, Option<u64>)>> {
             SELECT local_paths, local_paths_order, window_id, ssh_project_id
@@ -761,6 +1055,29 @@ impl WorkspaceDb {
         }
     }
 
+    pub async fn delete_workspace_by_location(
+        &self,
+        location: SerializedWorkspaceLocation,
+    ) -> Result<()> {
+        self.write(move |conn| match location {
+            SerializedWorkspaceLocation::Local(local_paths, _) => conn.exec_bound(sql!(
+                DELETE FROM toolchains WHERE workspace_id IN (
+                    SELECT workspace_id FROM workspaces WHERE local_paths IS ?1
+                );
+                DELETE FROM workspaces
+                WHERE local_paths IS ?1
+            ))?(&local_paths),
+            SerializedWorkspaceLocation::Ssh(ssh_project) => conn.exec_bound(sql!(
+                DELETE FROM toolchains WHERE workspace_id IN (
+                    SELECT workspace_id FROM workspaces WHERE ssh_project_id IS ?1
+                );
+                DELETE FROM workspaces
+                WHERE ssh_project_id IS ?1
+            ))?(ssh_project.id.0),
+        })
+        .await
+    }
+
     pub async fn delete_workspace_by_dev_server_project_id(
         &self,
         id: DevServerProjectId,
@@ -778,6 +1095,124 @@ impl WorkspaceDb {
         .await
     }
 
+    /// Stores a checkpoint of a workspace's layout, dropping the oldest ones beyond
+    /// `max_checkpoints`.
+    pub(crate) async fn save_layout_checkpoint(
+        &self,
+        workspace_id: WorkspaceId,
+        center_group: SerializedPaneGroup,
+        docks: DockStructure,
+        max_checkpoints: usize,
+    ) -> Result<()> {
+        let center_group = serde_json::to_string(&center_group)?;
+        self.write(move |conn| {
+            conn.with_savepoint("save_layout_checkpoint", || {
+                conn.exec_bound(sql!(
+                    INSERT INTO layout_checkpoints(
+                        workspace_id,
+                        center_group,
+                        left_dock_visible,
+                        left_dock_active_panel,
+                        left_dock_zoom,
+                        left_dock_focused,
+                        right_dock_visible,
+                        right_dock_active_panel,
+                        right_dock_zoom,
+                        right_dock_focused,
+                        bottom_dock_visible,
+                        bottom_dock_active_panel,
+                        bottom_dock_zoom,
+                        bottom_dock_focused
+                    )
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+                ))?((workspace_id, center_group, docks))
+                .context("Saving checkpoint")?;
+                conn.exec_bound(sql!(
+                    DELETE FROM layout_checkpoints WHERE checkpoint_id IN (
+                        SELECT checkpoint_id FROM layout_checkpoints
+                        WHERE workspace_id = ?1
+                        ORDER BY timestamp DESC, checkpoint_id DESC
+                        LIMIT -1 OFFSET ?2
+                    )
+                ))?((workspace_id, max_checkpoints))
+            })
+        })
+        .await
+    }
+
+    query! {
+        fn layout_checkpoint_center_groups(workspace_id: WorkspaceId) -> Result<Vec<(i64, String, String)>> {
+            SELECT checkpoint_id, timestamp, center_group
+            FROM layout_checkpoints
+            WHERE workspace_id = ?
+            ORDER BY timestamp DESC, checkpoint_id DESC
+        }
+    }
+
+    /// Returns the layout checkpoints of a workspace, newest first.
+    pub fn layout_checkpoints(&self, workspace_id: WorkspaceId) -> Result<Vec<LayoutCheckpoint>> {
+        self.layout_checkpoint_center_groups(workspace_id)?
+            .into_iter()
+            .map(|(id, created_at, center_group)| {
+                let center_group = serde_json::from_str::<SerializedPaneGroup>(&center_group)?;
+                let mut panes = Vec::new();
+                center_group.collect_panes(&mut panes);
+                Ok(LayoutCheckpoint {
+                    id,
+                    created_at,
+                    pane_count: panes.len(),
+                    item_count: panes.iter().map(|pane| pane.children.len()).sum(),
+                })
+            })
+            .collect()
+    }
+
+    pub(crate) fn layout_checkpoint(
+        &self,
+        checkpoint_id: i64,
+    ) -> Result<(SerializedPaneGroup, DockStructure)> {
+        let (center_group, docks) = self.select_row_bound::<_, (String, DockStructure)>(sql!(
+            SELECT
+                center_group,
+                left_dock_visible,
+                left_dock_active_panel,
+                left_dock_zoom,
+                left_dock_focused,
+                right_dock_visible,
+                right_dock_active_panel,
+                right_dock_zoom,
+                right_dock_focused,
+                bottom_dock_visible,
+                bottom_dock_active_panel,
+                bottom_dock_zoom,
+                bottom_dock_focused
+            FROM layout_checkpoints
+            WHERE checkpoint_id = ?
+        ))?(checkpoint_id)?
+        .context("No checkpoint found")?;
+        Ok((serde_json::from_str(&center_group)?, docks))
+    }
+
+    /// Returns the kinds and ids of the items in the layout checkpoints of a workspace.
+    pub(crate) fn layout_checkpoint_item_ids(
+        &self,
+        workspace_id: WorkspaceId,
+    ) -> Result<Vec<(String, ItemId)>> {
+        let mut item_ids = Vec::new();
+        for (_, _, center_group) in self.layout_checkpoint_center_groups(workspace_id)? {
+            let center_group = serde_json::from_str::<SerializedPaneGroup>(&center_group)?;
+            let mut panes = Vec::new();
+            center_group.collect_panes(&mut panes);
+            item_ids.extend(
+                panes
+                    .iter()
+                    .flat_map(|pane| &pane.children)
+                    .map(|item| (item.kind.to_string(), item.item_id)),
+            );
+        }
+        Ok(item_ids)
+    }
+
     // Returns the recent locations which are still valid on disk and deletes ones which no longer
     // exist.
     pub async fn recent_workspaces_on_disk(
@@ -810,6 +1245,42 @@ impl WorkspaceDb {
         Ok(result)
     }
 
+    /// Returns up to `limit` recently opened workspaces, most recently activated first,
+    /// without checking whether their local paths still exist.
+    pub fn recent_workspaces_with_metadata(&self, limit: usize) -> Result<Vec<RecentWorkspace>> {
+        let mut result = self.all_recent_workspaces_with_metadata()?;
+        result.truncate(limit);
+        Ok(result)
+    }
+
+    pub(crate) fn all_recent_workspaces_with_metadata(&self) -> Result<Vec<RecentWorkspace>> {
+        let ssh_projects = self.ssh_projects()?;
+        let mut result = Vec::new();
+        for (_, local_paths, order, ssh_project_id, last_opened_at, item_count) in
+            self.recent_workspaces_with_item_counts()?
+        {
+            let location = match ssh_project_id.map(SshProjectId) {
+                Some(ssh_project_id) => {
+                    let Some(ssh_project) = ssh_projects
+                        .iter()
+                        .find(|project| project.id == ssh_project_id)
+                    else {
+                        continue;
+                    };
+                    SerializedWorkspaceLocation::Ssh(ssh_project.clone())
+                }
+                None => SerializedWorkspaceLocation::Local(local_paths, order),
+            };
+            result.push(RecentWorkspace {
+                is_ssh: matches!(location, SerializedWorkspaceLocation::Ssh(_)),
+                location,
+                last_opened_at,
+                item_count,
+            });
+        }
+        Ok(result)
+    }
+
     pub async fn last_workspace(&self) -> Result<Option<SerializedWorkspaceLocation>> {
         Ok(self
             .recent_workspaces_on_disk()
@@ -868,6 +1339,9 @@ impl WorkspaceDb {
                     active: true,
                     children: vec![],
                     pinned_count: 0,
+                    reserved_for: None,
+                    tab_bar_visibility: None,
+                    locked: false,
                 })
             }))
     }
@@ -884,16 +1358,22 @@ impl WorkspaceDb {
             Option<PaneId>,
             Option<bool>,
             Option<usize>,
+            Option<ReservationKind>,
+            Option<bool>,
+            Option<bool>,
             Option<String>,
         );
         self.select_bound::<GroupKey, GroupOrPane>(sql!(
-            SELECT group_id, axis, pane_id, active, pinned_count, flexes
+            SELECT group_id, axis, pane_id, active, pinned_count, reserved_for, tab_bar_visibility, locked, flexes
                 FROM (SELECT
                         group_id,
                         axis,
                         NULL as pane_id,
                         NULL as active,
                         NULL as pinned_count,
+                        NULL as reserved_for,
+                        NULL as tab_bar_visibility,
+                        NULL as locked,
                         position,
                         parent_group_id,
                         workspace_id,
@@ -906,6 +1386,9 @@ impl WorkspaceDb {
                         center_panes.pane_id,
                         panes.active as active,
                         pinned_count,
+                        reserved_for,
+                        tab_bar_visibility,
+                        locked,
                         position,
                         parent_group_id,
                         panes.workspace_id as workspace_id,
@@ -916,28 +1399,41 @@ impl WorkspaceDb {
                 ORDER BY position
         ))?((group_id, workspace_id))?
         .into_iter()
-        .map(|(group_id, axis, pane_id, active, pinned_count, flexes)| {
-            let maybe_pane = maybe!({ Some((pane_id?, active?, pinned_count?)) });
-            if let Some((group_id, axis)) = group_id.zip(axis) {
-                let flexes = flexes
-                    .map(|flexes: String| serde_json::from_str::<Vec<f32>>(&flexes))
-                    .transpose()?;
-
-                Ok(SerializedPaneGroup::Group {
-                    axis,
-                    children: self.get_pane_group(workspace_id, Some(group_id))?,
-                    flexes,
-                })
-            } else if let Some((pane_id, active, pinned_count)) = maybe_pane {
-                Ok(SerializedPaneGroup::Pane(SerializedPane::new(
-                    self.get_items(pane_id)?,
-                    active,
-                    pinned_count,
-                )))
-            } else {
-                bail!("Pane Group Child was neither a pane group or a pane");
-            }
-        })
+        .map(
+            |(
+                group_id,
+                axis,
+                pane_id,
+                active,
+                pinned_count,
+                reserved_for,
+                tab_bar_visibility,
+                locked,
+                flexes,
+            )| {
+                let maybe_pane = maybe!({ Some((pane_id?, active?, pinned_count?)) });
+                if let Some((group_id, axis)) = group_id.zip(axis) {
+                    let flexes = flexes
+                        .map(|flexes: String| serde_json::from_str::<Vec<f32>>(&flexes))
+                        .transpose()?;
+
+                    Ok(SerializedPaneGroup::Group {
+                        axis,
+                        children: self.get_pane_group(workspace_id, Some(group_id))?,
+                        flexes,
+                    })
+                } else if let Some((pane_id, active, pinned_count)) = maybe_pane {
+                    Ok(SerializedPaneGroup::Pane(SerializedPane {
+                        reserved_for,
+                        tab_bar_visibility,
+                        locked: locked.unwrap_or(false),
+                        ..SerializedPane::new(self.get_items(pane_id)?, active, pinned_count)
+                    }))
+                } else {
+                    bail!("Pane Group Child was neither a pane group or a pane");
+                }
+            },
+        )
         // Filter out panes and pane groups which don't have any children or items
         .filter(|pane_group| match pane_group {
             Ok(SerializedPaneGroup::Group { children, .. }) => !children.is_empty(),
@@ -1004,10 +1500,17 @@ impl WorkspaceDb {
         parent: Option<(GroupId, usize)>,
     ) -> Result<PaneId> {
         let pane_id = conn.select_row_bound::<_, i64>(sql!(
-            INSERT INTO panes(workspace_id, active, pinned_count)
-            VALUES (?, ?, ?)
+            INSERT INTO panes(workspace_id, active, pinned_count, reserved_for, tab_bar_visibility, locked)
+            VALUES (?, ?, ?, ?, ?, ?)
             RETURNING pane_id
-        ))?((workspace_id, pane.active, pane.pinned_count))?
+        ))?((
+            workspace_id,
+            pane.active,
+            pane.pinned_count,
+            pane.reserved_for,
+            pane.tab_bar_visibility,
+            pane.locked,
+        ))?
         .ok_or_else(|| anyhow!("Could not retrieve inserted pane_id"))?;
 
         let (parent_id, order) = parent.unzip();
@@ -1023,7 +1526,7 @@ impl WorkspaceDb {
 
     fn get_items(&self, pane_id: PaneId) -> Result<Vec<SerializedItem>> {
         self.select_bound(sql!(
-            SELECT kind, item_id, active, preview FROM items
+            SELECT kind, item_id, active, preview, navigation_data, stable_id FROM items
             WHERE pane_id = ?
                 ORDER BY position
         ))?(pane_id)
@@ -1036,7 +1539,7 @@ impl WorkspaceDb {
         items: &[SerializedItem],
     ) -> Result<()> {
         let mut insert = conn.exec_bound(sql!(
-            INSERT INTO items(workspace_id, pane_id, position, kind, item_id, active, preview) VALUES (?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO items(workspace_id, pane_id, position, kind, item_id, active, preview, navigation_data, stable_id) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
         )).context("Preparing insertion")?;
         for (position, item) in items.iter().enumerate() {
             insert((workspace_id, pane_id, position, item))?;
@@ -1055,6 +1558,32 @@ impl WorkspaceDb {
 
     query! {
         pub(crate) async fn set_window_open_status(workspace_id: WorkspaceId, bounds: SerializedWindowBounds, display: Uuid) -> Result<()> {
+            UPDATE workspaces
+            SET window_state = ?2,
+                window_x = ?3,
+                window_y = ?4,
+                window_width = ?5,
+                window_height = ?6,
+                display = ?7
+            WHERE workspace_id = ?1;
+            INSERT INTO window_bounds_by_display(
+                workspace_id, display, window_state, window_x, window_y, window_width, window_height
+            )
+            VALUES (?1, ?7, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT DO UPDATE SET
+                window_state = ?2,
+                window_x = ?3,
+                window_y = ?4,
+                window_width = ?5,
+                window_height = ?6,
+                timestamp = CURRENT_TIMESTAMP;
+        }
+    }
+
+    // Writes the window's current bounds without recording them as the ones it has on that
+    // display, see `Workspace::save_window_bounds`.
+    query! {
+        pub(crate) async fn set_effective_window_bounds(workspace_id: WorkspaceId, bounds: SerializedWindowBounds, display: Uuid) -> Result<()> {
             UPDATE workspaces
             SET window_state = ?2,
                 window_x = ?3,
@@ -1066,6 +1595,101 @@ impl WorkspaceDb {
         }
     }
 
+    // The bounds the workspace's window had on each display it was on, most recently used first.
+    query! {
+        pub(crate) fn window_bounds_by_display(workspace_id: WorkspaceId) -> Result<Vec<(Uuid, SerializedWindowBounds)>> {
+            SELECT display, window_state, window_x, window_y, window_width, window_height
+            FROM window_bounds_by_display
+            WHERE workspace_id = ?
+            ORDER BY timestamp DESC
+        }
+    }
+
+    query! {
+        async fn stored_session_notes(workspace_id: WorkspaceId) -> Result<Option<Option<String>>> {
+            SELECT session_notes
+            FROM workspaces
+            WHERE workspace_id = ?
+        }
+    }
+
+    /// Returns the session notes stored for a workspace, e.g. to show them next to the
+    /// workspace in the list of recent projects.
+    pub async fn session_notes(&self, workspace_id: WorkspaceId) -> Result<Option<String>> {
+        Ok(self.stored_session_notes(workspace_id).await?.flatten())
+    }
+
+    /// Replaces the bookmarks stored for a workspace with the given paths, in bookmark order.
+    pub(crate) async fn set_item_bookmarks(
+        &self,
+        workspace_id: WorkspaceId,
+        abs_paths: Vec<PathBuf>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("set_item_bookmarks", || {
+                conn.exec_bound(sql!(
+                    DELETE FROM item_bookmarks WHERE workspace_id = ?
+                ))?(workspace_id)?;
+                for (position, abs_path) in abs_paths.into_iter().enumerate() {
+                    conn.exec_bound(sql!(
+                        INSERT INTO item_bookmarks(workspace_id, position, path)
+                        VALUES (?, ?, ?)
+                    ))?((workspace_id, position, abs_path))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+
+    query! {
+        pub(crate) async fn item_bookmarks(workspace_id: WorkspaceId) -> Result<Vec<PathBuf>> {
+            SELECT path
+            FROM item_bookmarks
+            WHERE workspace_id = ?
+            ORDER BY position
+        }
+    }
+
+    query! {
+        pub(crate) async fn suppress_notification(workspace_id: WorkspaceId, namespace: String) -> Result<()> {
+            INSERT INTO suppressed_notifications(workspace_id, namespace)
+            VALUES (?, ?)
+            ON CONFLICT DO NOTHING
+        }
+    }
+
+    query! {
+        pub(crate) fn suppressed_notifications(workspace_id: WorkspaceId) -> Result<Vec<String>> {
+            SELECT namespace
+            FROM suppressed_notifications
+            WHERE workspace_id = ?
+        }
+    }
+
+    query! {
+        pub(crate) async fn reset_suppressed_notifications(workspace_id: WorkspaceId) -> Result<()> {
+            DELETE FROM suppressed_notifications
+            WHERE workspace_id = ?
+        }
+    }
+
+    query! {
+        pub(crate) async fn set_fullscreen(workspace_id: WorkspaceId, fullscreen: bool) -> Result<()> {
+            UPDATE workspaces
+            SET fullscreen = ?2
+            WHERE workspace_id = ?1
+        }
+    }
+
+    query! {
+        pub(crate) fn stored_fullscreen(workspace_id: WorkspaceId) -> Result<Option<Option<bool>>> {
+            SELECT fullscreen
+            FROM workspaces
+            WHERE workspace_id = ?
+        }
+    }
+
     query! {
         pub(crate) async fn set_centered_layout(workspace_id: WorkspaceId, centered_layout: bool) -> Result<()> {
             UPDATE workspaces
@@ -1157,11 +1781,34 @@ impl WorkspaceDb {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::item::SerializableNavigationData;
     use crate::persistence::model::SerializedWorkspace;
     use crate::persistence::model::{SerializedItem, SerializedPane, SerializedPaneGroup};
+    use crate::StableItemId;
     use db::open_test_db;
     use gpui::{self};
 
+    #[gpui::test]
+    async fn test_migrated_by_newer_version() {
+        let db = WorkspaceDb(open_test_db("test_migrated_by_newer_version").await);
+        assert!(!db.migrated_by_newer_version().unwrap());
+
+        // A newer version of Zed ran a migration this one doesn't know about.
+        db.write(|conn| {
+            conn.exec_bound(sql!(
+                INSERT INTO migrations (domain, step, migration) VALUES (?, ?, ?)
+            ))
+            .unwrap()((
+                "WorkspaceDb",
+                WorkspaceDb::migrations().len(),
+                "ALTER TABLE workspaces ADD COLUMN future TEXT",
+            ))
+            .unwrap()
+        })
+        .await;
+        assert!(db.migrated_by_newer_version().unwrap());
+    }
+
     #[gpui::test]
     async fn test_next_id_stability() {
         env_logger::try_init().ok();
@@ -1240,6 +1887,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
+            session_notes: None,
             session_id: None,
             window_id: None,
         };
@@ -1252,6 +1900,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
+            session_notes: None,
             session_id: None,
             window_id: None,
         };
@@ -1359,6 +2008,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
+            session_notes: None,
             session_id: None,
             window_id: Some(999),
         };
@@ -1376,6 +2026,298 @@ mod tests {
         assert_eq!(workspace, round_trip_workspace.unwrap());
     }
 
+    #[gpui::test]
+    async fn test_windows_on_the_same_roots() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_windows_on_the_same_roots").await);
+
+        let pane = |item_id| {
+            SerializedPaneGroup::Pane(SerializedPane::new(
+                vec![SerializedItem::new("Terminal", item_id, true, false)],
+                true,
+                0,
+            ))
+        };
+        let window_workspace = |id, window_id, center_group| SerializedWorkspace {
+            id: WorkspaceId(id),
+            session_id: Some("session".to_owned()),
+            window_id: Some(window_id),
+            ..default_workspace(&["/tmp"], &center_group)
+        };
+        let first = window_workspace(1, 10, pane(1));
+        let second = window_workspace(2, 20, group(Axis::Horizontal, vec![pane(2), pane(3)]));
+
+        let layout_in_window = |window_id| {
+            db.workspace_for_roots_in_window(&["/tmp"], window_id)
+                .map(|workspace| (workspace.id, workspace.center_group))
+        };
+        let first_layout = Some((first.id, first.center_group.clone()));
+        let second_layout = Some((second.id, second.center_group.clone()));
+
+        // Saving either window keeps the layout of the other one.
+        db.save_workspace(first.clone()).await;
+        db.save_workspace(second.clone()).await;
+        db.save_workspace(first.clone()).await;
+        db.save_workspace(second.clone()).await;
+        assert_eq!(layout_in_window(Some(10)), first_layout);
+        assert_eq!(layout_in_window(Some(20)), second_layout);
+
+        // Otherwise, the window that was opened first on the roots is preferred.
+        assert_eq!(layout_in_window(None), first_layout);
+        assert_eq!(layout_in_window(Some(30)), first_layout);
+        let recent_ids = db
+            .recent_workspaces()
+            .unwrap()
+            .into_iter()
+            .map(|(id, ..)| id)
+            .collect::<Vec<_>>();
+        assert_eq!(recent_ids, [first.id]);
+
+        // The layout of a closed window is dropped once the other window is saved.
+        db.save_workspace(SerializedWorkspace {
+            session_id: None,
+            ..second.clone()
+        })
+        .await;
+        db.save_workspace(first.clone()).await;
+        assert_eq!(layout_in_window(Some(20)), first_layout);
+    }
+
+    #[gpui::test]
+    async fn test_item_navigation_data() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_item_navigation_data").await);
+
+        let navigation_data = SerializableNavigationData::new("Terminal", &(3, "line")).unwrap();
+        let center_group = SerializedPaneGroup::Pane(SerializedPane::new(
+            vec![
+                SerializedItem {
+                    navigation_data: Some(navigation_data.clone()),
+                    ..SerializedItem::new("Terminal", 1, true, false)
+                },
+                SerializedItem::new("Terminal", 2, false, false),
+            ],
+            true,
+            0,
+        ));
+        let workspace = default_workspace(&["/tmp"], &center_group);
+        db.save_workspace(workspace.clone()).await;
+        assert_eq!(db.workspace_for_roots(&["/tmp"]).unwrap(), workspace);
+        assert_eq!(
+            navigation_data.decode::<(u32, String)>("Terminal"),
+            Some((3, "line".into()))
+        );
+
+        // Data saved for another kind of item, or that no longer matches its type, is ignored.
+        assert_eq!(navigation_data.decode::<(u32, String)>("Editor"), None);
+        assert_eq!(navigation_data.decode::<String>("Terminal"), None);
+
+        // Data that can't be parsed anymore is dropped when loading the item.
+        db.write(|conn| {
+            conn.exec(sql!(UPDATE items SET navigation_data = 'x'))
+                .unwrap()()
+            .unwrap()
+        })
+        .await;
+        let SerializedPaneGroup::Pane(pane) =
+            db.workspace_for_roots(&["/tmp"]).unwrap().center_group
+        else {
+            panic!("expected a single pane");
+        };
+        assert!(pane
+            .children
+            .iter()
+            .all(|item| item.navigation_data.is_none()));
+    }
+
+    #[gpui::test]
+    async fn test_window_bounds_by_display() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_window_bounds_by_display").await);
+        let workspace = default_workspace(&["/tmp"], &Default::default());
+        db.save_workspace(workspace.clone()).await;
+
+        let bounds = |x: f32| {
+            WindowBounds::Windowed(Bounds::new(point(px(x), px(0.)), size(px(800.), px(600.))))
+        };
+        let laptop = Uuid::from_u128(1);
+        let monitor = Uuid::from_u128(2);
+        let projector = Uuid::from_u128(3);
+        for (display, x) in [(laptop, 100.), (monitor, 2000.), (laptop, 200.)] {
+            db.set_window_open_status(workspace.id, SerializedWindowBounds(bounds(x)), display)
+                .await
+                .unwrap();
+        }
+        db.write(move |conn| {
+            conn.exec_bound(sql!(
+                UPDATE window_bounds_by_display SET timestamp = ? WHERE display = ?
+            ))
+            .unwrap()(("2024-01-01 00:00:00", monitor))
+            .unwrap()
+        })
+        .await;
+
+        let bounds_by_display = db
+            .window_bounds_by_display(workspace.id)
+            .unwrap()
+            .into_iter()
+            .map(|(display, bounds)| (display, bounds.0))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bounds_by_display,
+            [(laptop, bounds(200.)), (monitor, bounds(2000.))]
+        );
+
+        // Each display restores the bounds the window last had on it.
+        let display_bounds = |x: f32| Bounds::new(point(px(x), px(0.)), size(px(1920.), px(1080.)));
+        let laptop_display = (laptop, display_bounds(0.));
+        let monitor_display = (monitor, display_bounds(1920.));
+        let projector_display = (projector, display_bounds(0.));
+        assert_eq!(
+            crate::select_window_bounds(
+                &bounds_by_display,
+                &[monitor_display],
+                Some(monitor_display)
+            ),
+            Some((monitor, bounds(2000.)))
+        );
+        assert_eq!(
+            crate::select_window_bounds(
+                &bounds_by_display,
+                &[laptop_display, monitor_display],
+                Some(monitor_display)
+            ),
+            Some((laptop, bounds(200.)))
+        );
+
+        // Without any of the displays, the most recent bounds go to the primary display.
+        assert_eq!(
+            crate::select_window_bounds(
+                &[(monitor, bounds(2000.))],
+                &[projector_display],
+                Some(projector_display)
+            ),
+            Some((
+                projector,
+                WindowBounds::Windowed(Bounds::centered_at(
+                    projector_display.1.center(),
+                    size(px(800.), px(600.))
+                ))
+            ))
+        );
+    }
+
+    #[gpui::test]
+    async fn test_window_bounds_migration() {
+        let conn = Connection::open_memory(Some("test_window_bounds_migration"));
+        let migrations = WorkspaceDb::migrations();
+        let display = Uuid::from_u128(1);
+
+        // The bounds of the workspace were stored with the display they were on.
+        let window_bounds_migration = migrations
+            .iter()
+            .position(|migration| migration.contains("CREATE TABLE window_bounds_by_display"))
+            .unwrap();
+        conn.migrate("WorkspaceDb", &migrations[..window_bounds_migration])
+            .unwrap();
+        conn.exec_bound(sql!(
+            INSERT INTO workspaces(
+                workspace_id, local_paths, display, window_state, window_x, window_y, window_width, window_height
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        ))
+        .unwrap()((
+            WorkspaceId(1),
+            &LocalPaths::new(["/tmp"]),
+            display,
+            "Maximized",
+            (10, 20, 800, 600),
+        ))
+        .unwrap();
+
+        conn.migrate("WorkspaceDb", migrations).unwrap();
+        let bounds_by_display = conn
+            .select_bound::<WorkspaceId, (Uuid, SerializedWindowBounds)>(sql!(
+                SELECT display, window_state, window_x, window_y, window_width, window_height
+                FROM window_bounds_by_display
+                WHERE workspace_id = ?
+            ))
+            .unwrap()(WorkspaceId(1))
+        .unwrap();
+        assert_eq!(
+            bounds_by_display,
+            [(
+                display,
+                SerializedWindowBounds(WindowBounds::Maximized(Bounds::new(
+                    point(px(10.), px(20.)),
+                    size(px(800.), px(600.))
+                )))
+            )]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_item_stable_id_migration() {
+        let conn = Connection::open_memory(Some("test_item_stable_id_migration"));
+        let migrations = WorkspaceDb::migrations();
+
+        // Items were saved without a stable id.
+        let stable_id_migration = migrations
+            .iter()
+            .position(|migration| migration.contains("ADD COLUMN stable_id"))
+            .unwrap();
+        conn.migrate("WorkspaceDb", &migrations[..stable_id_migration])
+            .unwrap();
+        conn.exec_bound(sql!(
+            INSERT INTO workspaces(workspace_id, local_paths) VALUES (?, ?)
+        ))
+        .unwrap()((WorkspaceId(1), &LocalPaths::new(["/tmp"])))
+        .unwrap();
+        conn.exec(sql!(
+            INSERT INTO panes(pane_id, workspace_id, active) VALUES (1, 1, 1);
+        ))
+        .unwrap()()
+        .unwrap();
+        conn.exec_bound(sql!(
+            INSERT INTO items(item_id, workspace_id, pane_id, kind, position, active, preview)
+            VALUES (5, 1, 1, ?, 0, 1, 0)
+        ))
+        .unwrap()("Terminal")
+        .unwrap();
+
+        // They have none once migrated, and get one when restored.
+        conn.migrate("WorkspaceDb", migrations).unwrap();
+        let items = conn
+            .select_bound::<i64, SerializedItem>(sql!(
+                SELECT kind, item_id, active, preview, navigation_data, stable_id FROM items
+                WHERE pane_id = ?
+            ))
+            .unwrap()(1)
+        .unwrap();
+        assert_eq!(items, [SerializedItem::new("Terminal", 5, true, false)]);
+
+        // The ids assigned since are saved with the items.
+        let db = WorkspaceDb(open_test_db("test_item_stable_id_migration").await);
+        let stable_id = StableItemId::new();
+        let center_group = SerializedPaneGroup::Pane(SerializedPane::new(
+            vec![
+                SerializedItem {
+                    stable_id: Some(stable_id),
+                    ..SerializedItem::new("Terminal", 1, true, false)
+                },
+                SerializedItem::new("Terminal", 2, false, false),
+            ],
+            true,
+            0,
+        ));
+        let workspace = default_workspace(&["/tmp"], &center_group);
+        db.save_workspace(workspace.clone()).await;
+        assert_eq!(db.workspace_for_roots(&["/tmp"]).unwrap(), workspace);
+    }
+
     #[gpui::test]
     async fn test_workspace_assignment() {
         env_logger::try_init().ok();
@@ -1393,6 +2335,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
+            session_notes: None,
             session_id: None,
             window_id: Some(1),
         };
@@ -1405,6 +2348,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
+            session_notes: None,
             session_id: None,
             window_id: Some(2),
         };
@@ -1447,6 +2391,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
+            session_notes: None,
             session_id: None,
             window_id: Some(3),
         };
@@ -1483,6 +2428,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
+            session_notes: None,
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(10),
         };
@@ -1495,6 +2441,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
+            session_notes: None,
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(20),
         };
@@ -1507,6 +2454,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
+            session_notes: None,
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(30),
         };
@@ -1519,6 +2467,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
+            session_notes: None,
             session_id: None,
             window_id: None,
         };
@@ -1536,6 +2485,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
+            session_notes: None,
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(50),
         };
@@ -1551,6 +2501,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
+            session_notes: None,
             session_id: Some("session-id-3".to_owned()),
             window_id: Some(60),
         };
@@ -1592,6 +2543,163 @@ mod tests {
         assert_eq!(locations[0].2, Some(60));
     }
 
+    #[gpui::test]
+    async fn test_recent_workspaces_with_metadata() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_recent_workspaces_with_metadata").await);
+
+        let ssh_project = db
+            .get_or_create_ssh_project("host".to_string(), None, vec![], None)
+            .await
+            .unwrap();
+        let center_group = SerializedPaneGroup::Pane(SerializedPane::new(
+            vec![
+                SerializedItem::new("Terminal", 1, true, false),
+                SerializedItem::new("Terminal", 2, false, false),
+            ],
+            true,
+            0,
+        ));
+        let workspaces = [
+            (
+                1,
+                SerializedWorkspaceLocation::from_local_paths(["/tmp1"]),
+                center_group,
+            ),
+            (
+                2,
+                SerializedWorkspaceLocation::Ssh(ssh_project.clone()),
+                Default::default(),
+            ),
+            (
+                3,
+                SerializedWorkspaceLocation::from_local_paths(["/tmp3"]),
+                Default::default(),
+            ),
+        ];
+        for (id, location, center_group) in workspaces {
+            db.save_workspace(SerializedWorkspace {
+                id: WorkspaceId(id),
+                location,
+                center_group,
+                window_bounds: Default::default(),
+                display: Default::default(),
+                docks: Default::default(),
+                centered_layout: false,
+                session_notes: None,
+                session_id: None,
+                window_id: None,
+            })
+            .await;
+        }
+        db.write(|conn| {
+            let mut set_timestamp = conn
+                .exec_bound(sql!(UPDATE workspaces SET timestamp = ?2 WHERE workspace_id = ?1))
+                .unwrap();
+            set_timestamp((WorkspaceId(1), "2024-01-01 00:00:02")).unwrap();
+            set_timestamp((WorkspaceId(2), "2024-01-01 00:00:03")).unwrap();
+            set_timestamp((WorkspaceId(3), "2024-01-01 00:00:01")).unwrap();
+        })
+        .await;
+
+        let recent = db.recent_workspaces_with_metadata(10).unwrap();
+        assert_eq!(
+            recent,
+            [
+                RecentWorkspace {
+                    location: SerializedWorkspaceLocation::Ssh(ssh_project),
+                    last_opened_at: "2024-01-01 00:00:03".to_string(),
+                    item_count: 0,
+                    is_ssh: true,
+                },
+                RecentWorkspace {
+                    location: SerializedWorkspaceLocation::from_local_paths(["/tmp1"]),
+                    last_opened_at: "2024-01-01 00:00:02".to_string(),
+                    item_count: 2,
+                    is_ssh: false,
+                },
+                RecentWorkspace {
+                    location: SerializedWorkspaceLocation::from_local_paths(["/tmp3"]),
+                    last_opened_at: "2024-01-01 00:00:01".to_string(),
+                    item_count: 0,
+                    is_ssh: false,
+                },
+            ]
+        );
+        assert_eq!(db.recent_workspaces_with_metadata(1).unwrap(), recent[..1]);
+
+        db.delete_workspace_by_location(recent[1].location.clone())
+            .await
+            .unwrap();
+        db.delete_workspace_by_location(recent[0].location.clone())
+            .await
+            .unwrap();
+        assert_eq!(db.recent_workspaces_with_metadata(10).unwrap(), recent[2..]);
+    }
+
+    #[gpui::test]
+    async fn test_layout_checkpoints() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_layout_checkpoints").await);
+        let workspace = default_workspace(&["/tmp"], &Default::default());
+        db.save_workspace(workspace.clone()).await;
+
+        let pane = |item_ids: &[ItemId]| {
+            SerializedPaneGroup::Pane(SerializedPane::new(
+                item_ids
+                    .iter()
+                    .map(|&item_id| SerializedItem::new("Terminal", item_id, false, false))
+                    .collect(),
+                false,
+                0,
+            ))
+        };
+        let layouts = [
+            pane(&[1]),
+            group(Axis::Horizontal, vec![pane(&[1]), pane(&[2, 3])]),
+            group(Axis::Vertical, vec![pane(&[4]), pane(&[5])]),
+        ];
+        for layout in &layouts {
+            db.save_layout_checkpoint(workspace.id, layout.clone(), Default::default(), 2)
+                .await
+                .unwrap();
+        }
+        // Checkpoints don't show up as workspaces of their own.
+        assert_eq!(
+            db.select_row::<usize>(sql!(SELECT COUNT(*) FROM workspaces))
+                .unwrap()()
+            .unwrap(),
+            Some(1)
+        );
+
+        // Only the newest checkpoints are kept.
+        let checkpoints = db.layout_checkpoints(workspace.id).unwrap();
+        assert_eq!(
+            checkpoints
+                .iter()
+                .map(|checkpoint| (checkpoint.pane_count, checkpoint.item_count))
+                .collect::<Vec<_>>(),
+            [(2, 2), (2, 3)]
+        );
+        let (center_group, docks) = db.layout_checkpoint(checkpoints[0].id).unwrap();
+        assert_eq!(center_group, layouts[2]);
+        assert_eq!(docks, DockStructure::default());
+        let mut item_ids = db
+            .layout_checkpoint_item_ids(workspace.id)
+            .unwrap()
+            .into_iter()
+            .map(|(_, item_id)| item_id)
+            .collect::<Vec<_>>();
+        item_ids.sort();
+        assert_eq!(item_ids, [1, 2, 3, 4, 5]);
+
+        // Checkpoints go away with their workspace.
+        db.delete_workspace_by_id(workspace.id).await.unwrap();
+        assert!(db.layout_checkpoint(checkpoints[0].id).is_err());
+    }
+
     fn default_workspace<P: AsRef<Path>>(
         workspace_id: &[P],
         center_group: &SerializedPaneGroup,
@@ -1604,6 +2712,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
+            session_notes: None,
             session_id: None,
             window_id: None,
         }
@@ -1649,6 +2758,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
+            session_notes: None,
             session_id: Some("one-session".to_owned()),
             window_id: Some(window_id),
         })
@@ -1740,6 +2850,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
+            session_notes: None,
             session_id: Some("one-session".to_owned()),
             window_id: Some(window_id),
         })
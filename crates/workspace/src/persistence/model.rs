@@ -1,13 +1,15 @@
 use super::{SerializedAxis, SerializedWindowBounds};
 use crate::{
-    item::ItemHandle, Member, Pane, PaneAxis, SerializableItemRegistry, Workspace, WorkspaceId,
+    item::{ItemHandle, SerializableNavigationData},
+    Member, Pane, PaneAxis, ReservationKind, SerializableItemRegistry, StableItemId, Workspace,
+    WorkspaceId,
 };
 use anyhow::{Context, Result};
-use async_recursion::async_recursion;
 use db::sqlez::{
     bindable::{Bind, Column, StaticColumnCount},
     statement::Statement,
 };
+use futures::StreamExt;
 use gpui::{AsyncWindowContext, Model, View, WeakView};
 use project::Project;
 use remote::ssh_session::SshProjectId;
@@ -111,6 +113,7 @@ impl LocalPaths {
 }
 
 impl StaticColumnCount for LocalPaths {}
+impl StaticColumnCount for &LocalPaths {}
 impl Bind for &LocalPaths {
     fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
         statement.bind(&bincode::serialize(&self.0)?, start_index)
@@ -237,6 +240,7 @@ pub(crate) struct SerializedWorkspace {
     pub(crate) center_group: SerializedPaneGroup,
     pub(crate) window_bounds: Option<SerializedWindowBounds>,
     pub(crate) centered_layout: bool,
+    pub(crate) session_notes: Option<String>,
     pub(crate) display: Option<Uuid>,
     pub(crate) docks: DockStructure,
     pub(crate) session_id: Option<String>,
@@ -279,6 +283,8 @@ pub struct DockData {
     pub(crate) visible: bool,
     pub(crate) active_panel: Option<String>,
     pub(crate) zoom: bool,
+    /// Whether the dock contained the focus, so that it can get it back on restore.
+    pub(crate) focused: bool,
 }
 
 impl Column for DockData {
@@ -286,11 +292,13 @@ impl Column for DockData {
         let (visible, next_index) = Option::<bool>::column(statement, start_index)?;
         let (active_panel, next_index) = Option::<String>::column(statement, next_index)?;
         let (zoom, next_index) = Option::<bool>::column(statement, next_index)?;
+        let (focused, next_index) = Option::<bool>::column(statement, next_index)?;
         Ok((
             DockData {
                 visible: visible.unwrap_or(false),
                 active_panel,
                 zoom: zoom.unwrap_or(false),
+                focused: focused.unwrap_or(false),
             },
             next_index,
         ))
@@ -301,11 +309,38 @@ impl Bind for DockData {
     fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
         let next_index = statement.bind(&self.visible, start_index)?;
         let next_index = statement.bind(&self.active_panel, next_index)?;
-        statement.bind(&self.zoom, next_index)
+        let next_index = statement.bind(&self.zoom, next_index)?;
+        statement.bind(&self.focused, next_index)
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+impl StaticColumnCount for ReservationKind {}
+
+impl Bind for ReservationKind {
+    fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
+        match self {
+            ReservationKind::Editor => "Editor",
+            ReservationKind::Terminal => "Terminal",
+            ReservationKind::Any => "Any",
+        }
+        .bind(statement, start_index)
+    }
+}
+
+impl Column for ReservationKind {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let (kind, next_index) = String::column(statement, start_index)?;
+        let kind = match kind.as_str() {
+            "Editor" => ReservationKind::Editor,
+            "Terminal" => ReservationKind::Terminal,
+            "Any" => ReservationKind::Any,
+            _ => anyhow::bail!("Stored pane reservation kind is incorrect"),
+        };
+        Ok((kind, next_index))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub(crate) enum SerializedPaneGroup {
     Group {
         axis: SerializedAxis,
@@ -322,17 +357,84 @@ impl Default for SerializedPaneGroup {
             children: vec![SerializedItem::default()],
             active: false,
             pinned_count: 0,
+            reserved_for: None,
+            tab_bar_visibility: None,
+            locked: false,
         })
     }
 }
 
+/// How many items [`SerializedPaneGroup::deserialize`] loads at a time.
+pub(crate) const MAX_CONCURRENT_ITEM_LOADS: usize = 16;
+
 impl SerializedPaneGroup {
-    #[async_recursion(?Send)]
+    /// Restores the panes and their items. The panes are created first, so that the items of
+    /// all of them are loaded together, `max_concurrent_loads` at a time, rather than pane
+    /// after pane. The panes are then assembled in tree order from the loaded items.
     pub(crate) async fn deserialize(
         self,
         project: &Model<Project>,
         workspace_id: WorkspaceId,
         workspace: WeakView<Workspace>,
+        max_concurrent_loads: usize,
+        cx: &mut AsyncWindowContext,
+    ) -> Option<(Member, Option<View<Pane>>, Vec<Option<Box<dyn ItemHandle>>>)> {
+        let mut serialized_panes = Vec::new();
+        self.collect_panes(&mut serialized_panes);
+        let mut panes = Vec::with_capacity(serialized_panes.len());
+        for _ in &serialized_panes {
+            let pane = workspace
+                .update(cx, |workspace, cx| workspace.add_pane(cx).downgrade())
+                .log_err()?;
+            panes.push(pane);
+        }
+
+        let items_to_load =
+            serialized_panes
+                .iter()
+                .zip(&panes)
+                .flat_map(|(serialized_pane, pane)| {
+                    serialized_pane
+                        .children
+                        .iter()
+                        .map(move |item| (pane, item))
+                });
+        let loaded_items = SerializedPane::load_items(
+            items_to_load,
+            project,
+            workspace_id,
+            &workspace,
+            max_concurrent_loads,
+            cx,
+        )
+        .await;
+
+        self.assemble(
+            &mut panes.into_iter(),
+            &mut loaded_items.into_iter(),
+            &workspace,
+            cx,
+        )
+    }
+
+    pub(crate) fn collect_panes<'a>(&'a self, panes: &mut Vec<&'a SerializedPane>) {
+        match self {
+            SerializedPaneGroup::Group { children, .. } => {
+                for child in children {
+                    child.collect_panes(panes);
+                }
+            }
+            SerializedPaneGroup::Pane(pane) => panes.push(pane),
+        }
+    }
+
+    /// Builds the member from the panes and the items loaded for them, which are consumed in
+    /// the order of [`SerializedPaneGroup::collect_panes`].
+    fn assemble(
+        self,
+        panes: &mut impl Iterator<Item = WeakView<Pane>>,
+        loaded_items: &mut impl Iterator<Item = Result<Box<dyn ItemHandle>>>,
+        workspace: &WeakView<Workspace>,
         cx: &mut AsyncWindowContext,
     ) -> Option<(Member, Option<View<Pane>>, Vec<Option<Box<dyn ItemHandle>>>)> {
         match self {
@@ -345,9 +447,8 @@ impl SerializedPaneGroup {
                 let mut members = Vec::new();
                 let mut items = Vec::new();
                 for child in children {
-                    if let Some((new_member, active_pane, new_items)) = child
-                        .deserialize(project, workspace_id, workspace.clone(), cx)
-                        .await
+                    if let Some((new_member, active_pane, new_items)) =
+                        child.assemble(panes, loaded_items, workspace, cx)
                     {
                         members.push(new_member);
                         items.extend(new_items);
@@ -370,16 +471,26 @@ impl SerializedPaneGroup {
                 ))
             }
             SerializedPaneGroup::Pane(serialized_pane) => {
-                let pane = workspace
-                    .update(cx, |workspace, cx| workspace.add_pane(cx).downgrade())
-                    .log_err()?;
+                let pane = panes.next()?;
+                let pane_items = loaded_items
+                    .take(serialized_pane.children.len())
+                    .collect::<Vec<_>>();
                 let active = serialized_pane.active;
+                let reserved_for = serialized_pane.reserved_for;
                 let new_items = serialized_pane
-                    .deserialize_to(project, &pane, workspace_id, workspace.clone(), cx)
-                    .await
+                    .add_loaded_items(pane_items, &pane, cx)
                     .log_err()?;
 
                 if pane.update(cx, |pane, _| pane.items_len() != 0).log_err()? {
+                    if let Some(reserved_for) = reserved_for {
+                        workspace
+                            .update(cx, |workspace, _| {
+                                workspace
+                                    .pane_reservations
+                                    .insert(pane.clone(), reserved_for)
+                            })
+                            .log_err()?;
+                    }
                     let pane = pane.upgrade()?;
                     Some((
                         Member::Pane(pane.clone()),
@@ -400,11 +511,14 @@ impl SerializedPaneGroup {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[derive(Debug, PartialEq, Eq, Default, Clone, Serialize, Deserialize)]
 pub struct SerializedPane {
     pub(crate) active: bool,
     pub(crate) children: Vec<SerializedItem>,
     pub(crate) pinned_count: usize,
+    pub(crate) reserved_for: Option<ReservationKind>,
+    pub(crate) tab_bar_visibility: Option<bool>,
+    pub(crate) locked: bool,
 }
 
 impl SerializedPane {
@@ -413,6 +527,9 @@ impl SerializedPane {
             children,
             active,
             pinned_count,
+            reserved_for: None,
+            tab_bar_visibility: None,
+            locked: false,
         }
     }
 
@@ -424,31 +541,68 @@ impl SerializedPane {
         workspace: WeakView<Workspace>,
         cx: &mut AsyncWindowContext,
     ) -> Result<Vec<Option<Box<dyn ItemHandle>>>> {
-        let mut item_tasks = Vec::new();
-        let mut active_item_index = None;
-        let mut preview_item_index = None;
-        for (index, item) in self.children.iter().enumerate() {
-            let project = project.clone();
-            item_tasks.push(pane.update(cx, |_, cx| {
-                SerializableItemRegistry::deserialize(
-                    &item.kind,
-                    project,
-                    workspace.clone(),
-                    workspace_id,
-                    item.item_id,
-                    cx,
-                )
-            })?);
-            if item.active {
-                active_item_index = Some(index);
-            }
-            if item.preview {
-                preview_item_index = Some(index);
-            }
-        }
+        let loaded_items = Self::load_items(
+            self.children.iter().map(|item| (pane, item)),
+            project,
+            workspace_id,
+            &workspace,
+            self.children.len().max(1),
+            cx,
+        )
+        .await;
+        self.add_loaded_items(loaded_items, pane, cx)
+    }
+
+    /// Deserializes the items into their panes, `max_concurrent_loads` at a time, and returns
+    /// them in the same order. The items that fail to load are logged by
+    /// [`SerializedPane::add_loaded_items`].
+    async fn load_items<'a>(
+        items: impl IntoIterator<Item = (&'a WeakView<Pane>, &'a SerializedItem)>,
+        project: &Model<Project>,
+        workspace_id: WorkspaceId,
+        workspace: &WeakView<Workspace>,
+        max_concurrent_loads: usize,
+        cx: &AsyncWindowContext,
+    ) -> Vec<Result<Box<dyn ItemHandle>>> {
+        let mut cx = cx.clone();
+        futures::stream::iter(items)
+            .map(|(pane, item)| {
+                let load = pane.update(&mut cx, |_, cx| {
+                    SerializableItemRegistry::deserialize(
+                        &item.kind,
+                        project.clone(),
+                        workspace.clone(),
+                        workspace_id,
+                        item.item_id,
+                        cx,
+                    )
+                });
+                async move {
+                    match load {
+                        Ok(load) => load.await,
+                        Err(error) => Err(error),
+                    }
+                }
+            })
+            .buffered(max_concurrent_loads)
+            .collect()
+            .await
+    }
+
+    /// Adds the loaded items to the pane, in the order of the serialized ones, and restores
+    /// which of them are active, previewed and pinned.
+    fn add_loaded_items(
+        &self,
+        loaded_items: Vec<Result<Box<dyn ItemHandle>>>,
+        pane: &WeakView<Pane>,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<Vec<Option<Box<dyn ItemHandle>>>> {
+        let active_item_index = self.children.iter().rposition(|item| item.active);
+        let preview_item_index = self.children.iter().rposition(|item| item.preview);
 
         let mut items = Vec::new();
-        for item_handle in futures::future::join_all(item_tasks).await {
+        for (item_handle, item) in loaded_items.into_iter().zip(&self.children) {
+            let navigation_data = item.navigation_data.as_ref();
             let item_handle = item_handle.log_err();
             items.push(item_handle.clone());
 
@@ -456,6 +610,15 @@ impl SerializedPane {
                 pane.update(cx, |pane, cx| {
                     pane.add_item(item_handle.clone(), true, true, None, cx);
                 })?;
+                if let Some(navigation_data) = navigation_data {
+                    cx.update(|cx| item_handle.restore_navigation_data(navigation_data, cx))?;
+                }
+                if let Some(stable_id) = item.stable_id {
+                    let workspace = pane.update(cx, |pane, _| pane.workspace.clone())?;
+                    workspace.update(cx, |workspace, cx| {
+                        workspace.restore_stable_item_id(item_handle.as_ref(), stable_id, cx)
+                    })?;
+                }
             }
         }
 
@@ -472,8 +635,10 @@ impl SerializedPane {
                 }
             })?;
         }
-        pane.update(cx, |pane, _| {
+        pane.update(cx, |pane, cx| {
             pane.set_pinned_count(self.pinned_count.min(items.len()));
+            pane.set_tab_bar_visibility(self.tab_bar_visibility, cx);
+            pane.set_locked(self.locked, cx);
         })?;
 
         anyhow::Ok(items)
@@ -484,12 +649,17 @@ pub type GroupId = i64;
 pub type PaneId = i64;
 pub type ItemId = u64;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct SerializedItem {
     pub kind: Arc<str>,
     pub item_id: ItemId,
     pub active: bool,
     pub preview: bool,
+    /// Restores the item's position once it is deserialized, see
+    /// [`crate::item::Item::serialize_navigation_data`].
+    pub navigation_data: Option<SerializableNavigationData>,
+    /// `None` for the items saved before stable ids existed, which get one once restored.
+    pub stable_id: Option<StableItemId>,
 }
 
 impl SerializedItem {
@@ -499,6 +669,8 @@ impl SerializedItem {
             item_id,
             active,
             preview,
+            navigation_data: None,
+            stable_id: None,
         }
     }
 }
@@ -511,13 +683,15 @@ impl Default for SerializedItem {
             item_id: 100000,
             active: false,
             preview: false,
+            navigation_data: None,
+            stable_id: None,
         }
     }
 }
 
 impl StaticColumnCount for SerializedItem {
     fn column_count() -> usize {
-        4
+        6
     }
 }
 impl Bind for &SerializedItem {
@@ -525,7 +699,13 @@ impl Bind for &SerializedItem {
         let next_index = statement.bind(&self.kind, start_index)?;
         let next_index = statement.bind(&self.item_id, next_index)?;
         let next_index = statement.bind(&self.active, next_index)?;
-        statement.bind(&self.preview, next_index)
+        let next_index = statement.bind(&self.preview, next_index)?;
+        let navigation_data = self
+            .navigation_data
+            .as_ref()
+            .and_then(|data| serde_json::to_string(data).log_err());
+        let next_index = statement.bind(&navigation_data, next_index)?;
+        statement.bind(&self.stable_id, next_index)
     }
 }
 
@@ -535,12 +715,19 @@ impl Column for SerializedItem {
         let (item_id, next_index) = ItemId::column(statement, next_index)?;
         let (active, next_index) = bool::column(statement, next_index)?;
         let (preview, next_index) = bool::column(statement, next_index)?;
+        let (navigation_data, next_index) = Option::<String>::column(statement, next_index)?;
+        // Data that doesn't parse anymore is dropped, the item is restored without it.
+        let navigation_data =
+            navigation_data.and_then(|data| serde_json::from_str(&data).log_err());
+        let (stable_id, next_index) = Option::<StableItemId>::column(statement, next_index)?;
         Ok((
             SerializedItem {
                 kind,
                 item_id,
                 active,
                 preview,
+                navigation_data,
+                stable_id,
             },
             next_index,
         ))
@@ -550,6 +737,17 @@ impl Column for SerializedItem {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        debug_state::PaneGroupDebugState,
+        item::test::{TestItem, TestItemDeserializationDelay},
+        register_serializable_item,
+        tests::init_test,
+        PaneGroup,
+    };
+    use fs::FakeFs;
+    use futures::FutureExt;
+    use gpui::{Axis, TestAppContext};
+    use std::{ops::Range, time::Duration};
 
     #[test]
     fn test_serialize_local_paths() {
@@ -564,4 +762,84 @@ mod tests {
             )
         );
     }
+
+    #[gpui::test]
+    async fn test_deserialize_items_concurrently(cx: &mut TestAppContext) {
+        const LOAD_TIME: Duration = Duration::from_millis(100);
+        init_test(cx);
+        cx.update(|cx| {
+            register_serializable_item::<TestItem>(cx);
+            cx.set_global(TestItemDeserializationDelay(LOAD_TIME));
+        });
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+
+        let pane = |item_ids: Range<u64>, active_item_id, active| {
+            let items = item_ids
+                .map(|id| SerializedItem::new("TestItem", id, id == active_item_id, false))
+                .collect();
+            SerializedPaneGroup::Pane(SerializedPane::new(items, active, 0))
+        };
+        let center_group = SerializedPaneGroup::Group {
+            axis: SerializedAxis(Axis::Horizontal),
+            flexes: None,
+            children: vec![
+                pane(0..3, 1, true),
+                SerializedPaneGroup::Group {
+                    axis: SerializedAxis(Axis::Vertical),
+                    flexes: None,
+                    children: vec![pane(3..5, 4, false), pane(5..8, 5, false)],
+                },
+            ],
+        };
+
+        let mut restore = |max_concurrent_loads| {
+            let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+            let center_group = center_group.clone();
+            let project = project.clone();
+            let mut deserialize = workspace.update(cx, |_, cx| {
+                cx.spawn(|workspace, mut cx| async move {
+                    center_group
+                        .deserialize(
+                            &project,
+                            WorkspaceId(1),
+                            workspace,
+                            max_concurrent_loads,
+                            &mut cx,
+                        )
+                        .await
+                })
+            });
+            let started_at = cx.executor().now();
+            let (member, _, items) = loop {
+                cx.executor().advance_clock(LOAD_TIME / 10);
+                cx.run_until_parked();
+                if let Some(result) = (&mut deserialize).now_or_never() {
+                    break result.unwrap();
+                }
+            };
+            let elapsed = cx.executor().now() - started_at;
+            assert_eq!(items.iter().flatten().count(), 8);
+            let center = workspace.update(cx, |workspace, cx| {
+                workspace.center = PaneGroup::with_root(member);
+                workspace.debug_state(cx).center
+            });
+            (elapsed, center)
+        };
+
+        // The items of all panes load at once, into the same layout as one at a time.
+        let (sequential_elapsed, sequential_center) = restore(1);
+        let (concurrent_elapsed, concurrent_center) = restore(MAX_CONCURRENT_ITEM_LOADS);
+        assert!(sequential_elapsed >= LOAD_TIME * 8);
+        assert!(concurrent_elapsed < LOAD_TIME * 2);
+        assert_eq!(concurrent_center, sequential_center);
+        let PaneGroupDebugState::Group { members, .. } = concurrent_center else {
+            panic!("expected a split layout");
+        };
+        let PaneGroupDebugState::Pane(first_pane) = &members[0] else {
+            panic!("expected a pane");
+        };
+        assert_eq!(first_pane.items.len(), 3);
+        assert_eq!(first_pane.active_item_index, Some(1));
+    }
 }
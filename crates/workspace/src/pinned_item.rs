@@ -0,0 +1,115 @@
+use gpui::{View, ViewContext};
+use settings::Settings;
+
+use crate::{pane::Pane, OpenBesidePinnedItem, SplitDirection, Workspace, WorkspaceSettings};
+
+impl Workspace {
+    /// Returns the pane in which to open a new item instead of `pane` when `pane` shows a pinned
+    /// item, according to the `open_beside_pinned_item` setting. A pane is split off in the
+    /// configured direction if there is none there yet.
+    pub(crate) fn pane_beside_pinned_item(
+        &mut self,
+        pane: View<Pane>,
+        cx: &mut ViewContext<Self>,
+    ) -> View<Pane> {
+        let direction = match WorkspaceSettings::get_global(cx).open_beside_pinned_item {
+            OpenBesidePinnedItem::Off => return pane,
+            OpenBesidePinnedItem::Left => SplitDirection::Left,
+            OpenBesidePinnedItem::Right => SplitDirection::Right,
+            OpenBesidePinnedItem::Up => SplitDirection::Up,
+            OpenBesidePinnedItem::Down => SplitDirection::Down,
+        };
+        if !pane.read(cx).is_active_item_pinned() {
+            return pane;
+        }
+        match self.center.find_pane_in_direction(&pane, direction, cx) {
+            Some(pane) => pane.clone(),
+            None => self.split_pane(pane, direction, cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::{test::TestItem, ItemHandle},
+        register_project_item,
+        tests::init_test,
+    };
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use project::{Project, ProjectPath, WorktreeId};
+    use settings::SettingsStore;
+
+    fn project_path(path: &str) -> ProjectPath {
+        (WorktreeId::from_usize(0), path).into()
+    }
+
+    #[gpui::test]
+    async fn test_open_beside_pinned_item(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| register_project_item::<TestItem>(cx));
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let open = |path: &'static str, cx: &mut gpui::VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                workspace.open_path(project_path(path), None, true, cx)
+            })
+        };
+
+        let pinned = open("pinned.txt", cx).await.unwrap();
+        let pinned_pane = workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            pane.update(cx, |pane, cx| pane.pin_tab_at(0, cx));
+            pane
+        });
+        cx.run_until_parked();
+
+        // Without the setting, files open in the pane of the pinned item.
+        open("a.txt", cx).await.unwrap();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes().len(), 1);
+            assert_eq!(pinned_pane.read(cx).items_len(), 2);
+            workspace.activate_item(pinned.as_ref(), true, true, cx);
+        });
+
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.open_beside_pinned_item = Some(OpenBesidePinnedItem::Right)
+                })
+            })
+        });
+
+        // Files already open in the pane are still activated there.
+        open("a.txt", cx).await.unwrap();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes().len(), 1);
+            assert!(!pinned_pane.read(cx).is_active_item_pinned());
+        });
+
+        // New files open in a pane split off on the right, leaving the pinned item visible.
+        workspace.update(cx, |workspace, cx| {
+            workspace.activate_item(pinned.as_ref(), true, true, cx);
+        });
+        let item = open("b.txt", cx).await.unwrap();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes().len(), 2);
+            let pane = workspace.pane_for(item.as_ref()).unwrap();
+            assert_ne!(pane, pinned_pane);
+            assert_eq!(
+                workspace
+                    .center
+                    .find_pane_in_direction(&pinned_pane, SplitDirection::Right, cx),
+                Some(&pane)
+            );
+            assert_eq!(
+                pinned_pane.read(cx).active_item().unwrap().item_id(),
+                pinned.item_id()
+            );
+        });
+    }
+}
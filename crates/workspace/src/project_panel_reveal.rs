@@ -0,0 +1,178 @@
+use gpui::{ViewContext, WindowContext};
+use project::ProjectPath;
+
+use crate::{
+    dock::Panel,
+    notifications::{NotificationId, Toast},
+    RevealActiveItem, Workspace,
+};
+
+/// Reveals a path in the project panel, returning whether it could.
+pub type RevealHandler = Box<dyn Fn(ProjectPath, &mut WindowContext) -> bool>;
+
+/// The project panel installed with [`Workspace::set_reveal_handler`].
+pub(crate) struct ProjectPanelReveal {
+    panel_name: &'static str,
+    handler: RevealHandler,
+}
+
+struct RevealInProjectPanelFailed;
+
+impl Workspace {
+    /// Lets the project panel, a panel of type `T`, reveal the paths passed to
+    /// [`Workspace::reveal_in_project_panel`]. The handler is called while the workspace is
+    /// being updated.
+    pub fn set_reveal_handler<T: Panel>(&mut self, handler: RevealHandler) {
+        self.project_panel_reveal = Some(ProjectPanelReveal {
+            panel_name: T::persistent_name(),
+            handler,
+        });
+    }
+
+    /// Opens the dock of the project panel and reveals `path` in it. Without a project panel,
+    /// the path is made the active entry of the project instead, and a toast tells that it
+    /// couldn't be revealed.
+    pub fn reveal_in_project_panel(&mut self, path: ProjectPath, cx: &mut ViewContext<Self>) {
+        if let Some(reveal) = self.project_panel_reveal.as_ref() {
+            for dock in [&self.left_dock, &self.bottom_dock, &self.right_dock] {
+                let panel_index = dock
+                    .read(cx)
+                    .panel_index_for_persistent_name(reveal.panel_name, cx);
+                if let Some(panel_index) = panel_index {
+                    dock.update(cx, |dock, cx| {
+                        dock.activate_panel(panel_index, cx);
+                        dock.set_open(true, cx);
+                    });
+                    break;
+                }
+            }
+            if (reveal.handler)(path.clone(), cx) {
+                return;
+            }
+        }
+
+        let entry_id = self
+            .project
+            .read(cx)
+            .entry_for_path(&path, cx)
+            .map(|entry| entry.id);
+        if let Some(entry_id) = entry_id {
+            self.project.update(cx, |_, cx| {
+                cx.emit(project::Event::ActiveEntryChanged(Some(entry_id)))
+            });
+        }
+        self.show_toast(
+            Toast::new(
+                NotificationId::unique::<RevealInProjectPanelFailed>(),
+                format!(
+                    "Couldn't reveal {} in the project panel",
+                    path.path.display()
+                ),
+            )
+            .autohide(),
+            cx,
+        );
+    }
+
+    pub(crate) fn reveal_active_item(&mut self, _: &RevealActiveItem, cx: &mut ViewContext<Self>) {
+        if let Some(path) = self.active_item(cx).and_then(|item| item.project_path(cx)) {
+            self.reveal_in_project_panel(path, cx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dock::{test::TestPanel, DockPosition},
+        item::{
+            test::{TestItem, TestProjectItem},
+            ItemHandle,
+        },
+        tests::init_test,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+    use serde_json::json;
+    use std::{cell::RefCell, path::Path, rc::Rc};
+
+    #[gpui::test]
+    async fn test_reveal_in_project_panel(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "a.txt": "" })).await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let path = ProjectPath {
+            worktree_id,
+            path: Path::new("a.txt").into(),
+        };
+
+        let active_entries = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let active_entries = active_entries.clone();
+            cx.subscribe(&project, move |_, event, _| {
+                if let project::Event::ActiveEntryChanged(entry_id) = event {
+                    active_entries.borrow_mut().push(*entry_id);
+                }
+            })
+            .detach();
+        });
+
+        // Without a project panel, the path becomes the active entry.
+        workspace.update(cx, |workspace, cx| {
+            workspace.reveal_in_project_panel(path.clone(), cx)
+        });
+        cx.run_until_parked();
+        let entry_id = project.update(cx, |project, cx| {
+            project.entry_for_path(&path, cx).unwrap().id
+        });
+        assert_eq!(*active_entries.borrow(), [Some(entry_id)]);
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.notification_ids().len(), 1);
+            workspace.clear_all_notifications(cx);
+        });
+
+        // The panel's dock is opened to reveal the path.
+        let revealed_paths = Rc::new(RefCell::new(Vec::new()));
+        workspace.update(cx, |workspace, cx| {
+            let panel = cx.new_view(|cx| TestPanel::new(DockPosition::Right, cx));
+            workspace.add_panel(panel, cx);
+            let revealed_paths = revealed_paths.clone();
+            workspace.set_reveal_handler::<TestPanel>(Box::new(
+                move |path, _: &mut WindowContext| {
+                    revealed_paths.borrow_mut().push(path);
+                    true
+                },
+            ));
+            assert!(!workspace.right_dock().read(cx).is_open());
+            workspace.reveal_in_project_panel(path.clone(), cx);
+            assert!(workspace.right_dock().read(cx).is_open());
+            assert!(!workspace.left_dock().read(cx).is_open());
+        });
+        cx.run_until_parked();
+        assert_eq!(*revealed_paths.borrow(), [path]);
+        assert_eq!(active_entries.borrow().len(), 1);
+        workspace.update(cx, |workspace, _| {
+            assert!(workspace.notification_ids().is_empty())
+        });
+
+        // The active item's path is revealed by the action.
+        let item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(1, "b.txt", cx)])
+        });
+        let item_path = workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, cx);
+            item.project_path(cx).unwrap()
+        });
+        cx.dispatch_action(RevealActiveItem);
+        cx.run_until_parked();
+        assert_eq!(revealed_paths.borrow().last(), Some(&item_path));
+    }
+}
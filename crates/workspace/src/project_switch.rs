@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use gpui::{Task, ViewContext};
+
+use crate::{CloseIntent, SwitchBackToPreviousProject, Workspace};
+
+impl Workspace {
+    /// Opens the project for `paths` in this window, in place of the current one. The current
+    /// workspace is saved and closed as when its window is replaced, prompting for its unsaved
+    /// items, and the workspace stored for `paths` is restored in the window. The project left
+    /// can be switched back to with [`SwitchBackToPreviousProject`].
+    pub fn switch_project(
+        &mut self,
+        paths: Vec<PathBuf>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(window) = cx.window_handle().downcast::<Self>() else {
+            return Task::ready(Err(anyhow!("not a workspace window")));
+        };
+        let previous_project = self
+            .local_paths(cx)
+            .filter(|paths| !paths.is_empty())
+            .map(|paths| paths.iter().map(|path| path.to_path_buf()).collect());
+        let carried_untitled_items = self.carried_untitled_items(cx);
+        let prepare = self.prepare_to_close(CloseIntent::ReplaceWindow, cx);
+        let app_state = self.app_state.clone();
+
+        cx.spawn(|_, mut cx| async move {
+            if !prepare.await? {
+                return Ok(());
+            }
+            let (workspace, _) = cx
+                .update(|cx| Workspace::new_local(paths, app_state, Some(window), None, None, cx))?
+                .await?;
+            // The window is recorded with the new workspace right away, so that it is the one
+            // restored after a crash.
+            let flush = workspace.update(&mut cx, |workspace, cx| {
+                workspace.previous_project = previous_project;
+                if let Some(items) = carried_untitled_items {
+                    workspace.add_carried_untitled_items(items, cx);
+                }
+                workspace.serialize_workspace(cx);
+                workspace.flush_pending_serialization(cx)
+            })?;
+            flush.await;
+            Ok(())
+        })
+    }
+
+    /// The root paths of the project this window was switched from, if any.
+    pub fn previous_project(&self) -> Option<&[PathBuf]> {
+        self.previous_project.as_deref()
+    }
+
+    pub(crate) fn switch_back_to_previous_project(
+        &mut self,
+        _: &SwitchBackToPreviousProject,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(paths) = self.previous_project.clone() else {
+            return;
+        };
+        self.switch_project(paths, cx).detach_and_log_err(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{open_paths, tests::init_test, AppState, OpenOptions};
+    use gpui::{TestAppContext, WindowHandle};
+    use serde_json::json;
+
+    fn root_names(window: WindowHandle<Workspace>, cx: &mut TestAppContext) -> Vec<String> {
+        window
+            .update(cx, |workspace, cx| {
+                workspace
+                    .visible_worktrees(cx)
+                    .map(|worktree| worktree.read(cx).root_name().to_string())
+                    .collect()
+            })
+            .unwrap()
+    }
+
+    #[gpui::test]
+    async fn test_switch_project(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let app_state = cx.update(AppState::test);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree("/root", json!({ "a": { "one.txt": "" }, "b": {} }))
+            .await;
+        let (window, _) = cx
+            .update(|cx| {
+                open_paths(
+                    &[PathBuf::from("/root/a")],
+                    app_state.clone(),
+                    OpenOptions::default(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        let first_id = window
+            .update(cx, |workspace, _| workspace.database_id())
+            .unwrap();
+
+        // The project is replaced in the same window, remembering the previous one.
+        window
+            .update(cx, |workspace, cx| {
+                workspace.switch_project(vec![PathBuf::from("/root/b")], cx)
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(cx.windows().len(), 1);
+        assert_eq!(root_names(window, cx), ["b"]);
+        let second_id = window
+            .update(cx, |workspace, _| {
+                assert_eq!(
+                    workspace.previous_project(),
+                    Some([PathBuf::from("/root/a")].as_slice())
+                );
+                workspace.database_id()
+            })
+            .unwrap();
+        assert!(second_id.is_some());
+        assert_ne!(second_id, first_id);
+
+        // Switching back restores the workspace stored for the previous project.
+        window
+            .update(cx, |workspace, cx| {
+                workspace.switch_back_to_previous_project(&SwitchBackToPreviousProject, cx)
+            })
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(cx.windows().len(), 1);
+        assert_eq!(root_names(window, cx), ["a"]);
+        window
+            .update(cx, |workspace, _| {
+                assert_eq!(workspace.database_id(), first_id);
+                assert_eq!(
+                    workspace.previous_project(),
+                    Some([PathBuf::from("/root/b")].as_slice())
+                );
+            })
+            .unwrap();
+    }
+}
@@ -0,0 +1,445 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::Result;
+use collections::HashSet;
+use fs::RemoveOptions;
+use futures::StreamExt;
+use gpui::{EntityId, Task, ViewContext, VisualContext};
+use settings::Settings;
+use util::ResultExt;
+
+use crate::{
+    notifications::{simple_message_notification::MessageNotification, NotificationId},
+    Workspace, WorkspaceId, WorkspaceSettings,
+};
+
+/// Name of the file, stored next to an item's recovery snapshot, that holds the
+/// absolute path of the file the snapshot belongs to.
+const RECOVERED_PATH_FILE_NAME: &str = "path";
+
+/// How often to check whether crash recovery got enabled while it is turned off.
+const DISABLED_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+struct RecoveredItemsNotification;
+
+/// The recovery snapshots written by this session.
+#[derive(Default)]
+pub(crate) struct RecoverySnapshots {
+    /// The directories of the snapshots, named after the [`crate::StableItemId`] of their item,
+    /// so that the item restored in the next session finds its snapshot.
+    written_dirs: HashSet<PathBuf>,
+    /// The items whose snapshot holds their current contents, and which aren't written again
+    /// until they're edited.
+    up_to_date_items: HashSet<EntityId>,
+}
+
+fn workspace_recovery_dir(workspace_id: WorkspaceId) -> PathBuf {
+    db::database_dir()
+        .join("recovery")
+        .join(workspace_id.0.to_string())
+}
+
+async fn remove_recovery_dir(fs: &dyn fs::Fs, dir: &Path) {
+    fs.remove_dir(
+        dir,
+        RemoveOptions {
+            recursive: true,
+            ignore_if_not_exists: true,
+        },
+    )
+    .await
+    .log_err();
+}
+
+impl Workspace {
+    /// Periodically snapshots the unsaved contents of dirty items, so that they can be
+    /// offered back to the user after a crash.
+    pub(crate) fn write_recovery_snapshots_periodically(cx: &mut ViewContext<Self>) -> Task<()> {
+        cx.spawn(|this, mut cx| async move {
+            loop {
+                let Ok(interval_seconds) = this.update(&mut cx, |_, cx| {
+                    WorkspaceSettings::get_global(cx).crash_recovery_interval_seconds
+                }) else {
+                    break;
+                };
+                if interval_seconds == 0 {
+                    cx.background_executor().timer(DISABLED_POLL_INTERVAL).await;
+                    continue;
+                }
+                cx.background_executor()
+                    .timer(Duration::from_secs(interval_seconds))
+                    .await;
+
+                let Ok(task) = this.update(&mut cx, |this, cx| this.write_recovery_snapshots(cx))
+                else {
+                    break;
+                };
+                task.await.log_err();
+            }
+        })
+    }
+
+    /// Asks the dirty items edited since their last snapshot for a new one, and removes the
+    /// snapshots of the items that got saved or closed since. The snapshots left by a previous
+    /// session are never overwritten, as long as they weren't restored nor discarded.
+    fn write_recovery_snapshots(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(workspace_id) = self.database_id else {
+            return Task::ready(Ok(()));
+        };
+        let workspace_dir = workspace_recovery_dir(workspace_id);
+        let project = self.project.read(cx);
+        let mut current_dirs = HashSet::default();
+        let mut snapshots = Vec::new();
+        for item in self.items(cx) {
+            if !item.is_dirty(cx) {
+                continue;
+            }
+            let Some(stable_id) = self.stable_id_for_item(item.as_ref()) else {
+                continue;
+            };
+            let Some(abs_path) = item
+                .project_path(cx)
+                .and_then(|project_path| project.absolute_path(&project_path, cx))
+            else {
+                continue;
+            };
+            let item_dir = workspace_dir.join(stable_id.to_string());
+            let is_up_to_date = self
+                .recovery_snapshots
+                .up_to_date_items
+                .contains(&item.item_id());
+            if is_up_to_date && self.recovery_snapshots.written_dirs.contains(&item_dir) {
+                current_dirs.insert(item_dir);
+            } else {
+                snapshots.push((item.boxed_clone(), item_dir, abs_path));
+            }
+        }
+
+        let stale_dirs = self
+            .recovery_snapshots
+            .written_dirs
+            .iter()
+            .filter(|dir| {
+                !current_dirs.contains(*dir)
+                    && !snapshots.iter().any(|(_, item_dir, _)| item_dir == *dir)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let fs = self.app_state.fs.clone();
+        cx.spawn(|this, mut cx| async move {
+            // Snapshots of items that got saved or closed since the last run are stale.
+            for stale_dir in &stale_dirs {
+                remove_recovery_dir(fs.as_ref(), stale_dir).await;
+            }
+            let written_dirs = this.update(&mut cx, |this, _| {
+                for stale_dir in &stale_dirs {
+                    this.recovery_snapshots.written_dirs.remove(stale_dir);
+                }
+                this.recovery_snapshots.written_dirs.clone()
+            })?;
+
+            for (item, item_dir, abs_path) in snapshots {
+                if !written_dirs.contains(&item_dir) && fs.is_dir(&item_dir).await {
+                    continue;
+                }
+                fs.create_dir(&item_dir).await?;
+                let Some(write_task) = cx.update(|cx| item.write_recovery(&item_dir, cx))? else {
+                    remove_recovery_dir(fs.as_ref(), &item_dir).await;
+                    continue;
+                };
+                write_task.await?;
+                fs.atomic_write(
+                    item_dir.join(RECOVERED_PATH_FILE_NAME),
+                    abs_path.to_string_lossy().into_owned(),
+                )
+                .await?;
+                this.update(&mut cx, |this, _| {
+                    this.recovery_snapshots.written_dirs.insert(item_dir);
+                    this.recovery_snapshots
+                        .up_to_date_items
+                        .insert(item.item_id());
+                })?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Makes the next snapshot of an item include its latest edits.
+    pub(crate) fn mark_item_edited_for_recovery(&mut self, item_id: EntityId) {
+        self.recovery_snapshots.up_to_date_items.remove(&item_id);
+    }
+
+    /// Looks for recovery snapshots left behind by a previous session and, if any of them
+    /// are newer than the files they belong to, offers to restore them.
+    pub(crate) fn offer_recovered_items(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(workspace_id) = self.database_id else {
+            return Task::ready(Ok(()));
+        };
+        let fs = self.app_state.fs.clone();
+        cx.spawn(|this, mut cx| async move {
+            let workspace_dir = workspace_recovery_dir(workspace_id);
+            let Ok(mut item_dirs) = fs.read_dir(&workspace_dir).await else {
+                return Ok(());
+            };
+
+            let mut recovered_items = Vec::new();
+            while let Some(item_dir) = item_dirs.next().await {
+                let item_dir = item_dir?;
+                let path_file = item_dir.join(RECOVERED_PATH_FILE_NAME);
+                let (Some(abs_path), Some(recovered_at)) = (
+                    fs.load(&path_file).await.ok(),
+                    fs.metadata(&path_file).await.ok().flatten(),
+                ) else {
+                    remove_recovery_dir(fs.as_ref(), &item_dir).await;
+                    continue;
+                };
+                let abs_path = PathBuf::from(abs_path);
+                let is_newer = match fs.metadata(&abs_path).await.ok().flatten() {
+                    Some(metadata) => recovered_at.mtime.bad_is_greater_than(metadata.mtime),
+                    None => true,
+                };
+                if is_newer {
+                    recovered_items.push((abs_path, item_dir));
+                } else {
+                    remove_recovery_dir(fs.as_ref(), &item_dir).await;
+                }
+            }
+            if recovered_items.is_empty() {
+                return Ok(());
+            }
+
+            this.update(&mut cx, |this, cx| {
+                let message = match recovered_items.as_slice() {
+                    [(abs_path, _)] => format!(
+                        "Zed found unsaved changes to {} from a previous session.",
+                        abs_path.display()
+                    ),
+                    _ => format!(
+                        "Zed found unsaved changes to {} files from a previous session.",
+                        recovered_items.len()
+                    ),
+                };
+                let workspace = cx.view().downgrade();
+                let item_dirs = recovered_items
+                    .iter()
+                    .map(|(_, item_dir)| item_dir.clone())
+                    .collect::<Vec<_>>();
+                this.show_notification(
+                    NotificationId::unique::<RecoveredItemsNotification>(),
+                    cx,
+                    |cx| {
+                        cx.new_view(|_| {
+                            MessageNotification::new(message)
+                                .with_click_message("Restore")
+                                .on_click(move |cx| {
+                                    workspace
+                                        .update(cx, |workspace, cx| {
+                                            workspace
+                                                .restore_recovered_items(
+                                                    recovered_items.clone(),
+                                                    cx,
+                                                )
+                                                .detach_and_log_err(cx);
+                                        })
+                                        .ok();
+                                })
+                                .with_secondary_click_message("Discard")
+                                .on_secondary_click(move |cx| {
+                                    let fs = fs.clone();
+                                    let item_dirs = item_dirs.clone();
+                                    cx.background_executor()
+                                        .spawn(async move {
+                                            for item_dir in item_dirs {
+                                                remove_recovery_dir(fs.as_ref(), &item_dir).await;
+                                            }
+                                        })
+                                        .detach();
+                                })
+                        })
+                    },
+                );
+            })
+        })
+    }
+
+    fn restore_recovered_items(
+        &mut self,
+        recovered_items: Vec<(PathBuf, PathBuf)>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let fs = self.app_state.fs.clone();
+        cx.spawn(|this, mut cx| async move {
+            for (abs_path, item_dir) in recovered_items {
+                let item = this
+                    .update(&mut cx, |this, cx| this.open_abs_path(abs_path, false, cx))?
+                    .await?;
+                if let Some(restore_task) = cx.update(|cx| item.restore_recovery(&item_dir, cx))? {
+                    restore_task.await?;
+                }
+                remove_recovery_dir(fs.as_ref(), &item_dir).await;
+            }
+            Ok(())
+        })
+    }
+
+    /// Removes the recovery snapshot of an item, e.g. once it has been saved.
+    pub(crate) fn discard_item_recovery(&mut self, item_id: EntityId, cx: &mut ViewContext<Self>) {
+        let (Some(workspace_id), Some(stable_id)) =
+            (self.database_id, self.stable_item_ids.get(&item_id))
+        else {
+            return;
+        };
+        let item_dir = workspace_recovery_dir(workspace_id).join(stable_id.to_string());
+        if !self.recovery_snapshots.written_dirs.remove(&item_dir) {
+            return;
+        }
+        self.recovery_snapshots.up_to_date_items.remove(&item_id);
+        let fs = self.app_state.fs.clone();
+        cx.background_executor()
+            .spawn(async move { remove_recovery_dir(fs.as_ref(), &item_dir).await })
+            .detach();
+    }
+
+    /// Removes all recovery snapshots of this workspace, e.g. when its window is closed cleanly.
+    pub(crate) fn discard_all_recovery(&self, cx: &mut ViewContext<Self>) -> Task<()> {
+        let Some(workspace_id) = self.database_id else {
+            return Task::ready(());
+        };
+        let fs = self.app_state.fs.clone();
+        cx.background_executor().spawn(async move {
+            remove_recovery_dir(fs.as_ref(), &workspace_recovery_dir(workspace_id)).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::{
+            test::{TestItem, TestProjectItem},
+            ItemEvent,
+        },
+        tests::init_test,
+    };
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use project::{Project, ProjectPath};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[gpui::test]
+    async fn test_offer_recovered_items(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "stale.txt": "", "fresh.txt": "" }))
+            .await;
+        let project = Project::test(fs.clone(), ["root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let workspace_id = WorkspaceId(1);
+        let workspace_dir = workspace_recovery_dir(workspace_id);
+        let stale_dir = workspace_dir.join("1");
+        let fresh_dir = workspace_dir.join("2");
+        fs.insert_tree(
+            &stale_dir,
+            json!({ RECOVERED_PATH_FILE_NAME: "/root/stale.txt" }),
+        )
+        .await;
+        fs.insert_tree(
+            &fresh_dir,
+            json!({ RECOVERED_PATH_FILE_NAME: "/root/fresh.txt" }),
+        )
+        .await;
+        // Saving the file after the snapshot was taken makes the snapshot outdated.
+        fs.save(
+            "/root/stale.txt".as_ref(),
+            &"saved".into(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.database_id = Some(workspace_id);
+                workspace.offer_recovered_items(cx)
+            })
+            .await
+            .unwrap();
+
+        assert!(!fs.is_dir(&stale_dir).await);
+        assert!(fs.is_dir(&fresh_dir).await);
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(
+                workspace.notification_ids(),
+                vec![NotificationId::unique::<RecoveredItemsNotification>()]
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_write_recovery_snapshots(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "a.txt": "" })).await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let item = cx.new_view(|cx| {
+            let project_item = TestProjectItem::new(1, "a.txt", cx);
+            project_item.update(cx, |project_item, _| {
+                project_item.project_path = Some(ProjectPath {
+                    worktree_id,
+                    path: Arc::from(Path::new("a.txt")),
+                })
+            });
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_project_items(&[project_item])
+        });
+        let item_dir = workspace.update(cx, |workspace, cx| {
+            workspace.database_id = Some(WorkspaceId(1));
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, cx);
+            let stable_id = workspace.stable_id_for_item(&item).unwrap();
+            workspace_recovery_dir(WorkspaceId(1)).join(stable_id.to_string())
+        });
+        let path_file = item_dir.join(RECOVERED_PATH_FILE_NAME);
+        let write = |cx: &mut gpui::VisualTestContext| {
+            workspace.update(cx, |workspace, cx| workspace.write_recovery_snapshots(cx))
+        };
+
+        // The snapshot of a previous session that wasn't restored yet isn't overwritten.
+        fs.insert_tree(&item_dir, json!({ RECOVERED_PATH_FILE_NAME: "previous" }))
+            .await;
+        write(cx).await.unwrap();
+        assert_eq!(fs.load(&path_file).await.unwrap(), "previous");
+
+        // Once it's gone, the item gets a snapshot of its own.
+        remove_recovery_dir(fs.as_ref(), &item_dir).await;
+        write(cx).await.unwrap();
+        assert_eq!(fs.load(&path_file).await.unwrap(), "/root/a.txt");
+
+        // It's only written again once the item is edited.
+        fs.atomic_write(path_file.clone(), "unchanged".into())
+            .await
+            .unwrap();
+        write(cx).await.unwrap();
+        assert_eq!(fs.load(&path_file).await.unwrap(), "unchanged");
+        item.update(cx, |_, cx| cx.emit(ItemEvent::Edit));
+        write(cx).await.unwrap();
+        assert_eq!(fs.load(&path_file).await.unwrap(), "/root/a.txt");
+
+        // The snapshot is removed once the item isn't dirty anymore.
+        item.update(cx, |item, _| item.is_dirty = false);
+        write(cx).await.unwrap();
+        assert!(!fs.is_dir(&item_dir).await);
+    }
+}
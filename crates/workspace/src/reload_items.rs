@@ -0,0 +1,251 @@
+use anyhow::Result;
+use collections::HashSet;
+use futures::{stream, StreamExt as _};
+use gpui::{PromptLevel, Task, ViewContext, VisualContext as _};
+use itertools::Itertools as _;
+use util::ResultExt as _;
+
+use crate::{
+    item::ItemHandle,
+    notifications::{ErrorNotification, NotificationId, Toast},
+    Pane, ReloadAllItems, Workspace,
+};
+
+/// How many items [`Workspace::reload_all_items`] reloads at once.
+const MAX_CONCURRENT_RELOADS: usize = 8;
+
+struct ReloadedItems;
+struct ReloadItemsFailed;
+
+/// What [`Workspace::reload_all_items`] did with the items whose files changed on disk.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReloadSummary {
+    pub reloaded: usize,
+    /// The items left as they were, because they have unsaved changes.
+    pub skipped_dirty: usize,
+    pub failed: usize,
+}
+
+impl ReloadSummary {
+    fn message(&self) -> String {
+        if self.reloaded + self.skipped_dirty + self.failed == 0 {
+            return "No files changed on disk".to_string();
+        }
+        let mut message = match self.reloaded {
+            1 => "Reloaded 1 file".to_string(),
+            reloaded => format!("Reloaded {reloaded} files"),
+        };
+        if self.skipped_dirty > 0 {
+            message.push_str(&format!(", skipped {} dirty", self.skipped_dirty));
+        }
+        message
+    }
+}
+
+impl Workspace {
+    /// Reloads the items of all panes whose files changed on disk, e.g. after switching git
+    /// branches, and tells the user how it went. Items with unsaved changes are skipped,
+    /// unless `ignore_dirty` is set and the user confirms discarding their changes.
+    pub fn reload_all_items(
+        &mut self,
+        ignore_dirty: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<ReloadSummary>> {
+        let mut seen = HashSet::default();
+        let mut items = Vec::new();
+        let mut dirty_items = Vec::new();
+        for pane in &self.panes {
+            for item in pane.read(cx).items() {
+                if !seen.insert(item.item_id()) || !item.has_external_changes(cx) {
+                    continue;
+                }
+                if item.is_dirty(cx) {
+                    dirty_items.push(item.boxed_clone());
+                } else {
+                    items.push(item.boxed_clone());
+                }
+            }
+        }
+
+        let project = self.project.clone();
+        cx.spawn(|this, mut cx| async move {
+            let mut skipped_dirty = dirty_items.len();
+            if ignore_dirty && !dirty_items.is_empty() {
+                let answer = this.update(&mut cx, |_, cx| {
+                    let (_, detail) = Pane::file_names_for_prompt(
+                        &mut dirty_items.iter(),
+                        dirty_items.len(),
+                        cx,
+                    );
+                    let prompt = match dirty_items.len() {
+                        1 => "Discard the unsaved changes of this file and reload it?".to_string(),
+                        count => format!(
+                            "Discard the unsaved changes of the following {count} files and reload them?"
+                        ),
+                    };
+                    cx.prompt(
+                        PromptLevel::Warning,
+                        &prompt,
+                        Some(&detail),
+                        &["Reload all", "Skip them"],
+                    )
+                })?;
+                if answer.await.log_err() == Some(0) {
+                    items.append(&mut dirty_items);
+                    skipped_dirty = 0;
+                }
+            }
+
+            let item_count = items.len();
+            let failures = stream::iter(items)
+                .map(|item| {
+                    let reload = this.update(&mut cx, |_, cx| {
+                        (file_name(item.as_ref(), cx), item.reload(project.clone(), cx))
+                    });
+                    async move {
+                        let (name, reload) = reload.map_err(|error| (String::new(), error))?;
+                        reload.await.map_err(|error| (name, error))
+                    }
+                })
+                .buffer_unordered(MAX_CONCURRENT_RELOADS)
+                .filter_map(|result| async move { result.err() })
+                .collect::<Vec<_>>()
+                .await;
+
+            let summary = ReloadSummary {
+                reloaded: item_count - failures.len(),
+                skipped_dirty,
+                failed: failures.len(),
+            };
+            this.update(&mut cx, |this, cx| {
+                this.show_toast(
+                    Toast::new(NotificationId::unique::<ReloadedItems>(), summary.message())
+                        .autohide(),
+                    cx,
+                );
+                if !failures.is_empty() {
+                    let headline = match failures.len() {
+                        1 => "Failed to reload 1 file".to_string(),
+                        count => format!("Failed to reload {count} files"),
+                    };
+                    let detail = failures
+                        .iter()
+                        .map(|(name, error)| format!("{name}: {error:#}"))
+                        .join("\n");
+                    this.show_notification(
+                        NotificationId::unique::<ReloadItemsFailed>(),
+                        cx,
+                        |cx| cx.new_view(|_| ErrorNotification::new(headline).with_detail(detail)),
+                    );
+                }
+            })?;
+            Ok(summary)
+        })
+    }
+
+    pub(crate) fn reload_all_items_action(
+        &mut self,
+        action: &ReloadAllItems,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.reload_all_items(action.ignore_dirty, cx)
+            .detach_and_log_err(cx);
+    }
+}
+
+fn file_name(item: &dyn ItemHandle, cx: &gpui::AppContext) -> String {
+    item.project_path(cx)
+        .and_then(|path| Some(path.path.file_name()?.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "untitled".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::{TestItem, TestProjectItem},
+        tests::init_test,
+        SplitDirection,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_reload_all_items(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let changed = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_external_changes(true)
+                .with_project_items(&[TestProjectItem::new(1, "changed.txt", cx)])
+        });
+        let dirty = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_external_changes(true)
+                .with_dirty(true)
+                .with_project_items(&[TestProjectItem::new(2, "dirty.txt", cx)])
+        });
+        let unchanged = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(3, "unchanged.txt", cx)])
+        });
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(changed.clone()), None, true, cx);
+            workspace.add_item_to_active_pane(Box::new(unchanged.clone()), None, true, cx);
+            let pane =
+                workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Right, cx);
+            pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(dirty.clone()), true, true, None, cx)
+            });
+        });
+        cx.run_until_parked();
+        let reload_counts = |cx: &mut gpui::VisualTestContext| {
+            [&changed, &dirty, &unchanged]
+                .map(|item| item.read_with(cx, |item, _| item.reload_count))
+        };
+
+        // Only the changed items are reloaded, and dirty ones are skipped.
+        let summary = workspace
+            .update(cx, |workspace, cx| workspace.reload_all_items(false, cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            summary,
+            ReloadSummary {
+                reloaded: 1,
+                skipped_dirty: 1,
+                failed: 0,
+            }
+        );
+        assert_eq!(summary.message(), "Reloaded 1 file, skipped 1 dirty");
+        assert_eq!(reload_counts(cx), [1, 0, 0]);
+
+        // Dirty items are reloaded once the user confirms it.
+        let reload = workspace.update(cx, |workspace, cx| workspace.reload_all_items(true, cx));
+        cx.run_until_parked();
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(0);
+        let summary = reload.await.unwrap();
+        assert_eq!(
+            summary,
+            ReloadSummary {
+                reloaded: 1,
+                skipped_dirty: 0,
+                failed: 0,
+            }
+        );
+        assert_eq!(reload_counts(cx), [1, 1, 0]);
+        dirty.read_with(cx, |item, _| assert!(!item.is_dirty));
+
+        // Nothing is left to reload.
+        let summary = workspace
+            .update(cx, |workspace, cx| workspace.reload_all_items(true, cx))
+            .await
+            .unwrap();
+        assert!(!cx.has_pending_prompt());
+        assert_eq!(summary.message(), "No files changed on disk");
+    }
+}
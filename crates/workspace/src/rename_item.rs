@@ -0,0 +1,235 @@
+use std::path::{Component, Path};
+
+use anyhow::{anyhow, Context as _, Result};
+use gpui::{AppContext, Global, Task, ViewContext};
+use project::ProjectPath;
+
+use crate::{RenameActiveItem, SaveIntent, Workspace};
+
+/// Opens the modal asking for the new name of the file at the given path, see
+/// [`Workspace::interactive_rename_active_item`].
+pub type RenameItemModalOpener = fn(&mut Workspace, ProjectPath, &mut ViewContext<Workspace>);
+
+struct GlobalRenameItemModalOpener(RenameItemModalOpener);
+
+impl Global for GlobalRenameItemModalOpener {}
+
+/// Registers the modal used by [`RenameActiveItem`] to ask for the new name of a file.
+pub fn register_rename_item_modal(opener: RenameItemModalOpener, cx: &mut AppContext) {
+    cx.set_global(GlobalRenameItemModalOpener(opener));
+}
+
+impl Workspace {
+    /// Asks for a new name for the file of the active item and renames it in place. Untitled
+    /// items are saved under a new name instead.
+    pub fn interactive_rename_active_item(
+        &mut self,
+        _: &RenameActiveItem,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(item) = self.active_item(cx) else {
+            return;
+        };
+        if !item.is_singleton(cx) {
+            return;
+        }
+        let Some(project_path) = item.project_path(cx) else {
+            self.save_active_item(SaveIntent::SaveAs, cx)
+                .detach_and_prompt_err("Failed to save", cx, |_, _| None);
+            return;
+        };
+        if let Some(open_modal) = cx
+            .try_global::<GlobalRenameItemModalOpener>()
+            .map(|global| global.0)
+        {
+            open_modal(self, project_path, cx);
+        }
+    }
+
+    /// Resolves the new name given for the file at `project_path`. The name is the new file
+    /// name, or the new path relative to the worktree root when `allow_move` is true. Errors
+    /// describe why the name can't be used.
+    pub fn validate_item_rename(
+        &self,
+        project_path: &ProjectPath,
+        new_name: &str,
+        allow_move: bool,
+        cx: &AppContext,
+    ) -> Result<ProjectPath> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err(anyhow!("The name can't be empty"));
+        }
+        if !allow_move && new_name.chars().any(std::path::is_separator) {
+            return Err(anyhow!("The name can't contain path separators"));
+        }
+        let new_path = if allow_move {
+            let new_path = Path::new(new_name);
+            if !new_path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+            {
+                return Err(anyhow!("The path must stay within the worktree"));
+            }
+            new_path.into()
+        } else {
+            project_path
+                .path
+                .parent()
+                .unwrap_or(Path::new(""))
+                .join(new_name)
+                .into()
+        };
+        let new_project_path = ProjectPath {
+            worktree_id: project_path.worktree_id,
+            path: new_path,
+        };
+        if new_project_path != *project_path
+            && self
+                .project
+                .read(cx)
+                .entry_for_path(&new_project_path, cx)
+                .is_some()
+        {
+            return Err(anyhow!("{new_name:?} already exists"));
+        }
+        Ok(new_project_path)
+    }
+
+    /// Renames the file at `project_path` through the project. The items of the file stay
+    /// open, and their navigation history follows them to the new path.
+    pub fn rename_item(
+        &mut self,
+        project_path: ProjectPath,
+        new_project_path: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        if project_path == new_project_path {
+            return Task::ready(Ok(()));
+        }
+        let rename = self.project.update(cx, |project, cx| {
+            let entry = project
+                .entry_for_path(&project_path, cx)
+                .with_context(|| format!("{:?} no longer exists", project_path.path))?;
+            anyhow::Ok(project.rename_entry(entry.id, new_project_path.path.clone(), cx))
+        });
+
+        cx.spawn(|this, mut cx| async move {
+            rename?.await?;
+            this.update(&mut cx, |this, cx| {
+                let abs_path = this.project.read(cx).absolute_path(&new_project_path, cx);
+                for pane in &this.panes {
+                    pane.update(cx, |pane, _| {
+                        pane.nav_history_mut().rename_path(
+                            &project_path,
+                            new_project_path.clone(),
+                            abs_path.clone(),
+                        )
+                    });
+                }
+                cx.notify();
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::{TestItem, TestProjectItem},
+        tests::init_test,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_rename_item(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({ "one.txt": "", "two.txt": "", "dir": { "three.txt": "" } }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let project_path = |path: &str| ProjectPath {
+            worktree_id,
+            path: Path::new(path).into(),
+        };
+        let item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(1, "one.txt", cx)])
+        });
+        let pane = workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, cx);
+            pane
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            let path = project_path("one.txt");
+            let error = |name: &str, allow_move: bool| {
+                workspace
+                    .validate_item_rename(&path, name, allow_move, cx)
+                    .unwrap_err()
+                    .to_string()
+            };
+            assert_eq!(error("  ", false), "The name can't be empty");
+            assert_eq!(
+                error("dir/one.txt", false),
+                "The name can't contain path separators"
+            );
+            assert_eq!(
+                error("../one.txt", true),
+                "The path must stay within the worktree"
+            );
+            assert_eq!(error("two.txt", false), "\"two.txt\" already exists");
+            assert_eq!(
+                workspace
+                    .validate_item_rename(&path, "dir/one.txt", true, cx)
+                    .unwrap(),
+                project_path("dir/one.txt")
+            );
+        });
+
+        // The file is renamed on disk, and the history of its item follows it.
+        workspace
+            .update(cx, |workspace, cx| {
+                let new_path = workspace
+                    .validate_item_rename(&project_path("one.txt"), "renamed.txt", false, cx)
+                    .unwrap();
+                workspace.rename_item(project_path("one.txt"), new_path, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert!(fs.is_file(Path::new("/root/renamed.txt")).await);
+        assert!(!fs.is_file(Path::new("/root/one.txt")).await);
+        pane.update(cx, |pane, _| {
+            assert_eq!(pane.items_len(), 1);
+            assert_eq!(
+                pane.nav_history().path_for_item(item.entity_id()),
+                Some((
+                    project_path("renamed.txt"),
+                    Some("/root/renamed.txt".into())
+                ))
+            );
+        });
+
+        // Untitled items are saved under a new name instead.
+        let untitled = cx.new_view(|cx| TestItem::new(cx).with_singleton(true));
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(untitled.clone()), None, true, cx);
+            workspace.interactive_rename_active_item(&RenameActiveItem, cx);
+        });
+        cx.run_until_parked();
+        assert!(cx.did_prompt_for_new_path());
+    }
+}
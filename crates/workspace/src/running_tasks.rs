@@ -0,0 +1,187 @@
+use futures::channel::oneshot;
+use gpui::{AnyWeakModel, PromptLevel, ViewContext};
+use task::TaskId;
+
+use crate::{Event, Workspace};
+
+/// A task spawned with [`Event::SpawnTask`], see [`Workspace::running_tasks`].
+#[derive(Clone)]
+pub struct RunningTask {
+    pub id: TaskId,
+    pub label: String,
+    /// What runs the task, usually its terminal.
+    owner: AnyWeakModel,
+}
+
+impl RunningTask {
+    fn is_alive(&self) -> bool {
+        self.owner.upgrade().is_some()
+    }
+}
+
+impl Workspace {
+    /// Records a task spawned with [`Event::SpawnTask`] once it started running in `owner`,
+    /// usually its terminal. Tasks that fail to start are thus never recorded. The task is
+    /// forgotten if `owner` is dropped before the task is reported as completed or cancelled.
+    pub fn add_running_task(&mut self, id: TaskId, label: String, owner: AnyWeakModel) {
+        self.running_tasks.retain(RunningTask::is_alive);
+        self.running_tasks.push(RunningTask { id, label, owner });
+    }
+
+    /// Forgets the task run by `owner`, and emits [`Event::TaskCompleted`] for it.
+    pub fn task_completed(
+        &mut self,
+        owner: &AnyWeakModel,
+        success: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(task) = self.take_running_task(owner) {
+            cx.emit(Event::TaskCompleted {
+                label: task.label,
+                success,
+            });
+        }
+    }
+
+    /// Forgets the task run by `owner`, which stopped without reporting whether it succeeded.
+    pub fn task_cancelled(&mut self, owner: &AnyWeakModel) {
+        self.take_running_task(owner);
+    }
+
+    fn take_running_task(&mut self, owner: &AnyWeakModel) -> Option<RunningTask> {
+        let ix = self
+            .running_tasks
+            .iter()
+            .position(|task| &task.owner == owner)?;
+        Some(self.running_tasks.remove(ix))
+    }
+
+    /// The tasks spawned in this workspace that are still running.
+    pub fn running_tasks(&self) -> impl Iterator<Item = &RunningTask> {
+        self.running_tasks.iter().filter(|task| task.is_alive())
+    }
+
+    /// Asks whether to close the window even though tasks are still running, if any are.
+    /// Answers `0` to close the window, terminating the tasks.
+    pub(crate) fn prompt_to_close_running_tasks(
+        &self,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<oneshot::Receiver<usize>> {
+        let message = match self.running_tasks().count() {
+            0 => return None,
+            1 => "1 task is still running — close anyway?".to_string(),
+            count => format!("{count} tasks are still running — close anyway?"),
+        };
+        Some(cx.prompt(
+            PromptLevel::Warning,
+            &message,
+            Some("Closing the window terminates them."),
+            &["Terminate and Close", "Cancel"],
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::init_test, CloseIntent};
+    use fs::FakeFs;
+    use gpui::{Context as _, TestAppContext};
+    use project::{Project, TaskSourceKind};
+    use std::{cell::RefCell, rc::Rc};
+    use task::{TaskContext, TaskTemplate};
+
+    #[gpui::test]
+    async fn test_running_tasks(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let completed = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let completed = completed.clone();
+            cx.subscribe(&workspace, move |_, event, _| {
+                if let Event::TaskCompleted { label, success } = event {
+                    completed.borrow_mut().push((label.clone(), *success));
+                }
+            })
+            .detach();
+        });
+
+        // Stands for the terminals running the tasks.
+        let build = cx.new_model(|_| ());
+        let test = cx.new_model(|_| ());
+        let lint = cx.new_model(|_| ());
+        workspace.update(cx, |workspace, cx| {
+            for (task, owner) in [("build", &build), ("test", &test), ("lint", &lint)] {
+                workspace.add_running_task(
+                    TaskId(task.to_string()),
+                    task.to_string(),
+                    owner.downgrade().into(),
+                );
+            }
+
+            workspace.task_completed(&build.downgrade().into(), true, cx);
+            workspace.task_cancelled(&test.downgrade().into());
+            assert_eq!(workspace.running_tasks().count(), 1);
+        });
+        assert_eq!(*completed.borrow(), [("build".to_string(), true)]);
+
+        // Closing the window with a running task asks first.
+        let close = workspace.update(cx, |workspace, cx| {
+            workspace.prepare_to_close(CloseIntent::CloseWindow, cx)
+        });
+        cx.run_until_parked();
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(1);
+        assert!(!close.await.unwrap());
+
+        // Tasks whose terminal was dropped aren't running anymore.
+        drop(lint);
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.running_tasks().count(), 0)
+        });
+        let close = workspace.update(cx, |workspace, cx| {
+            workspace.prepare_to_close(CloseIntent::CloseWindow, cx)
+        });
+        cx.run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        assert!(close.await.unwrap());
+    }
+
+    #[gpui::test]
+    async fn test_task_failing_to_start(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        // Without a terminal panel, nothing starts the task, so it isn't running.
+        let task = TaskTemplate {
+            label: "build".to_string(),
+            command: "cargo".to_string(),
+            ..TaskTemplate::default()
+        }
+        .resolve_task("test", &TaskContext::default())
+        .unwrap();
+        workspace.update(cx, |workspace, cx| {
+            crate::tasks::schedule_resolved_task(
+                workspace,
+                TaskSourceKind::UserInput,
+                task,
+                true,
+                cx,
+            );
+            assert_eq!(workspace.running_tasks().count(), 0);
+        });
+        let close = workspace.update(cx, |workspace, cx| {
+            workspace.prepare_to_close(CloseIntent::CloseWindow, cx)
+        });
+        cx.run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        assert!(close.await.unwrap());
+    }
+}
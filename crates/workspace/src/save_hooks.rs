@@ -0,0 +1,176 @@
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use gpui::{SharedString, Subscription, ViewContext, WindowContext};
+use util::post_inc;
+
+use crate::{item::ItemHandle, notifications::NotificationId, SaveIntent, Toast, Workspace};
+
+/// What a hook registered with [`Workspace::on_before_save`] decides about a save.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SaveVeto {
+    Continue,
+    /// Aborts the save, showing the reason to the user.
+    Cancel(SharedString),
+}
+
+type BeforeSaveHook =
+    Rc<RefCell<dyn FnMut(&dyn ItemHandle, SaveIntent, &mut WindowContext) -> SaveVeto>>;
+
+/// The hooks run before the items of a workspace are saved by the user.
+#[derive(Default)]
+pub(crate) struct BeforeSaveHooks {
+    hooks: Rc<RefCell<BTreeMap<usize, BeforeSaveHook>>>,
+    next_id: usize,
+}
+
+struct SaveVetoed;
+
+impl Workspace {
+    /// Registers a hook run before each item is saved with [`Workspace::save_active_item`] or
+    /// when saving all items. Returning [`SaveVeto::Cancel`] aborts the save. The hook is
+    /// removed once the subscription is dropped.
+    pub fn on_before_save(
+        &mut self,
+        hook: impl FnMut(&dyn ItemHandle, SaveIntent, &mut WindowContext) -> SaveVeto + 'static,
+    ) -> Subscription {
+        let id = post_inc(&mut self.before_save_hooks.next_id);
+        self.before_save_hooks
+            .hooks
+            .borrow_mut()
+            .insert(id, Rc::new(RefCell::new(hook)));
+        let hooks = self.before_save_hooks.hooks.clone();
+        Subscription::new(move || {
+            hooks.borrow_mut().remove(&id);
+        })
+    }
+
+    /// Runs the hooks registered with [`Workspace::on_before_save`] for `item`, telling the
+    /// user why when one of them cancels the save. Returns whether the save may go on.
+    pub(crate) fn run_before_save_hooks(
+        &mut self,
+        item: &dyn ItemHandle,
+        save_intent: SaveIntent,
+        cx: &mut ViewContext<Self>,
+    ) -> bool {
+        // The hooks are collected first, so that they can register or drop hooks themselves.
+        let hooks = self
+            .before_save_hooks
+            .hooks
+            .borrow()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        for hook in hooks {
+            if let SaveVeto::Cancel(reason) = (hook.borrow_mut())(item, save_intent, cx) {
+                self.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<SaveVetoed>(),
+                        format!("Save cancelled: {reason}"),
+                    ),
+                    cx,
+                );
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::{TestItem, TestProjectItem},
+        tests::init_test,
+        Event,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::{Project, ProjectPath, WorktreeId};
+    use std::path::Path;
+
+    #[gpui::test]
+    async fn test_before_save_hooks(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let item = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_project_items(&[TestProjectItem::new(1, "one.txt", cx)])
+        });
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, cx);
+        });
+        let saved_paths = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let saved_paths = saved_paths.clone();
+            cx.subscribe(&workspace, move |_, event, _| {
+                if let Event::UserSavedItem { project_path, .. } = event {
+                    saved_paths.borrow_mut().push(project_path.clone());
+                }
+            })
+            .detach();
+        });
+
+        // A hook cancelling the save keeps the item dirty and tells why.
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let veto = workspace.update(cx, |workspace, _| {
+            let seen = seen.clone();
+            workspace.on_before_save(move |item, save_intent, _| {
+                seen.borrow_mut().push((item.item_id(), save_intent));
+                SaveVeto::Cancel("formatting failed".into())
+            })
+        });
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.save_active_item(SaveIntent::Save, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(*seen.borrow(), [(item.item_id(), SaveIntent::Save)]);
+        item.read_with(cx, |item, _| assert_eq!(item.save_count, 0));
+        assert!(saved_paths.borrow().is_empty());
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(
+                workspace.notification_ids(),
+                [NotificationId::unique::<SaveVetoed>()]
+            );
+        });
+
+        // Saving all items is aborted too.
+        let saved_all = workspace
+            .update(cx, |workspace, cx| {
+                workspace.save_all_internal(SaveIntent::SaveAll, cx)
+            })
+            .await
+            .unwrap();
+        assert!(!saved_all);
+        item.read_with(cx, |item, _| assert_eq!(item.save_count, 0));
+
+        // Once the hook is dropped, the item is saved and the event has its path.
+        drop(veto);
+        let _continue = workspace.update(cx, |workspace, _| {
+            workspace.on_before_save(|_, _, _| SaveVeto::Continue)
+        });
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.save_active_item(SaveIntent::Save, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        item.read_with(cx, |item, _| assert_eq!(item.save_count, 1));
+        assert_eq!(
+            *saved_paths.borrow(),
+            [Some(ProjectPath {
+                worktree_id: WorktreeId::from_usize(0),
+                path: Path::new("one.txt").into(),
+            })]
+        );
+        assert_eq!(seen.borrow().len(), 2);
+    }
+}
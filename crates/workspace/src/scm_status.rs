@@ -0,0 +1,245 @@
+use std::{cell::RefCell, sync::Arc, time::Duration};
+
+use collections::{HashMap, HashSet};
+use futures::{channel::mpsc, StreamExt};
+use gpui::{AppContext, Subscription, Task, ViewContext};
+use project::ProjectPath;
+
+use crate::{Event, Workspace};
+
+/// How long to wait for more status changes before reporting them, as version control
+/// changes usually come in bursts, e.g. when checking out a branch.
+const SCM_STATUS_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// How a file differs from the version control repository it belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScmStatus {
+    Modified,
+    Added,
+    Conflicted,
+}
+
+/// Provides the version control statuses shown on tabs and in the window title, so that they
+/// don't need to query version control themselves. See [`Workspace::set_scm_status_provider`].
+pub trait ScmStatusProvider: Send + Sync {
+    fn status_for(&self, path: &ProjectPath, cx: &AppContext) -> Option<ScmStatus>;
+
+    /// Calls `on_change` with the paths whose status changed, until the returned subscription
+    /// is dropped.
+    fn observe_statuses(&self, on_change: Box<dyn Fn(Vec<ProjectPath>) + Send>) -> Subscription;
+}
+
+#[derive(Default)]
+pub(crate) struct ScmStatuses {
+    provider: Option<Arc<dyn ScmStatusProvider>>,
+    cache: RefCell<HashMap<ProjectPath, Option<ScmStatus>>>,
+    _changes: Option<(Subscription, Task<()>)>,
+}
+
+impl Workspace {
+    /// Sets where [`Workspace::scm_status`] gets the statuses from, replacing the previous
+    /// provider.
+    pub fn set_scm_status_provider(
+        &mut self,
+        provider: Arc<dyn ScmStatusProvider>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let (changes_tx, mut changes_rx) = mpsc::unbounded();
+        let subscription = provider.observe_statuses(Box::new(move |paths| {
+            changes_tx.unbounded_send(paths).ok();
+        }));
+        let changes = cx.spawn(|this, mut cx| async move {
+            while let Some(paths) = changes_rx.next().await {
+                let mut changed_paths = paths.into_iter().collect::<HashSet<_>>();
+                cx.background_executor().timer(SCM_STATUS_DEBOUNCE).await;
+                while let Ok(Some(paths)) = changes_rx.try_next() {
+                    changed_paths.extend(paths);
+                }
+                let updated = this.update(&mut cx, |this, cx| {
+                    this.scm_statuses_changed(changed_paths, cx);
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let previous_paths = self
+            .scm_statuses
+            .cache
+            .borrow_mut()
+            .drain()
+            .map(|(path, _)| path)
+            .collect::<HashSet<_>>();
+        self.scm_statuses.provider = Some(provider);
+        self.scm_statuses._changes = Some((subscription, changes));
+        self.scm_statuses_changed(previous_paths, cx);
+    }
+
+    /// The version control status of `path`, cached until the provider reports a change.
+    pub fn scm_status(&self, path: &ProjectPath, cx: &AppContext) -> Option<ScmStatus> {
+        let provider = self.scm_statuses.provider.as_ref()?;
+        *self
+            .scm_statuses
+            .cache
+            .borrow_mut()
+            .entry(path.clone())
+            .or_insert_with(|| provider.status_for(path, cx))
+    }
+
+    fn scm_statuses_changed(
+        &mut self,
+        changed_paths: HashSet<ProjectPath>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if changed_paths.is_empty() {
+            return;
+        }
+        {
+            let mut cache = self.scm_statuses.cache.borrow_mut();
+            for path in &changed_paths {
+                cache.remove(path);
+            }
+        }
+
+        // Only the panes showing the changed paths need to render their tabs again.
+        for pane in &self.panes {
+            let shows_changed_path = pane.read(cx).items().any(|item| {
+                item.project_path(cx)
+                    .map_or(false, |path| changed_paths.contains(&path))
+            });
+            if shows_changed_path {
+                pane.update(cx, |_, cx| cx.notify());
+            }
+        }
+        let active_path = self.active_item(cx).and_then(|item| item.project_path(cx));
+        if active_path.map_or(false, |path| changed_paths.contains(&path)) {
+            self.update_window_title(cx);
+        }
+
+        let mut changed_paths = changed_paths.into_iter().collect::<Vec<_>>();
+        changed_paths.sort();
+        cx.emit(Event::ScmStatusesChanged(changed_paths));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::{TestItem, TestProjectItem},
+        tests::init_test,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use parking_lot::Mutex;
+    use project::{Project, WorktreeId};
+    use std::{
+        rc::Rc,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    #[derive(Default)]
+    struct FakeScmStatusProvider {
+        statuses: Mutex<HashMap<ProjectPath, ScmStatus>>,
+        observers: Mutex<Vec<Box<dyn Fn(Vec<ProjectPath>) + Send>>>,
+        queries: AtomicUsize,
+    }
+
+    impl FakeScmStatusProvider {
+        fn set_status(&self, path: &ProjectPath, status: Option<ScmStatus>) {
+            match status {
+                Some(status) => self.statuses.lock().insert(path.clone(), status),
+                None => self.statuses.lock().remove(path),
+            };
+            for observer in self.observers.lock().iter() {
+                observer(vec![path.clone()]);
+            }
+        }
+    }
+
+    impl ScmStatusProvider for FakeScmStatusProvider {
+        fn status_for(&self, path: &ProjectPath, _: &AppContext) -> Option<ScmStatus> {
+            self.queries.fetch_add(1, Ordering::SeqCst);
+            self.statuses.lock().get(path).copied()
+        }
+
+        fn observe_statuses(
+            &self,
+            on_change: Box<dyn Fn(Vec<ProjectPath>) + Send>,
+        ) -> Subscription {
+            self.observers.lock().push(on_change);
+            Subscription::new(|| {})
+        }
+    }
+
+    #[gpui::test]
+    async fn test_scm_status(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let path = |path: &str| ProjectPath::from((WorktreeId::from_usize(0), path));
+        let provider = Arc::new(FakeScmStatusProvider::default());
+        provider
+            .statuses
+            .lock()
+            .insert(path("a.txt"), ScmStatus::Modified);
+        let item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(1, "a.txt", cx)])
+        });
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item), None, true, cx);
+            workspace.set_scm_status_provider(provider.clone(), cx);
+        });
+        cx.run_until_parked();
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let events = events.clone();
+            cx.subscribe(&workspace, move |_, event, _| {
+                if let Event::ScmStatusesChanged(paths) = event {
+                    events.borrow_mut().push(paths.clone());
+                }
+            })
+            .detach();
+        });
+
+        // Statuses are cached.
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(
+                workspace.scm_status(&path("a.txt"), cx),
+                Some(ScmStatus::Modified)
+            );
+            assert_eq!(
+                workspace.scm_status(&path("a.txt"), cx),
+                Some(ScmStatus::Modified)
+            );
+            assert_eq!(workspace.scm_status(&path("b.txt"), cx), None);
+        });
+        assert_eq!(provider.queries.load(Ordering::SeqCst), 2);
+
+        // Bursts of changes are reported once, for the changed paths only.
+        provider.set_status(&path("a.txt"), Some(ScmStatus::Conflicted));
+        provider.set_status(&path("b.txt"), Some(ScmStatus::Added));
+        provider.set_status(&path("a.txt"), Some(ScmStatus::Conflicted));
+        cx.executor().advance_clock(SCM_STATUS_DEBOUNCE);
+        cx.run_until_parked();
+        assert_eq!(*events.borrow(), [vec![path("a.txt"), path("b.txt")]]);
+
+        // The window title shows that the active file is conflicted.
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(
+                workspace.scm_status(&path("a.txt"), cx),
+                Some(ScmStatus::Conflicted)
+            );
+        });
+        assert_eq!(provider.queries.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            cx.window_title().as_deref(),
+            Some("empty project — a.txt (conflicted)")
+        );
+    }
+}
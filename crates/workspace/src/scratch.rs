@@ -0,0 +1,180 @@
+use std::borrow::Cow;
+
+use anyhow::{anyhow, Result};
+use gpui::{AppContext, Global, Model, Task, ViewContext};
+use language::{Buffer, Capability};
+use project::Project;
+use util::ResultExt;
+
+use crate::{item::ItemHandle, Workspace};
+
+/// Builds the item showing a scratch buffer with the given title and breadcrumb header, see
+/// [`Workspace::open_scratch`].
+pub type ScratchItemBuilder = fn(
+    Model<Buffer>,
+    String,
+    Option<String>,
+    &Model<Project>,
+    &mut ViewContext<Workspace>,
+) -> Box<dyn ItemHandle>;
+
+pub(crate) struct GlobalScratchItemBuilder(ScratchItemBuilder);
+
+impl Global for GlobalScratchItemBuilder {}
+
+/// Registers how to build scratch items. The items it builds must be ephemeral, see
+/// [`crate::item::Item::is_ephemeral`].
+pub fn register_scratch_item_builder(builder: ScratchItemBuilder, cx: &mut AppContext) {
+    cx.set_global(GlobalScratchItemBuilder(builder));
+}
+
+impl Workspace {
+    /// Opens a transient buffer, such as a generated report, in the active pane. The item is
+    /// never serialized, and closing it or its window discards its changes without prompting.
+    /// Remote projects open it in a local window instead.
+    pub fn open_scratch(
+        &mut self,
+        text: impl Into<Cow<'static, str>>,
+        title: &str,
+        language: &str,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<Box<dyn ItemHandle>>> {
+        self.open_scratch_buffer(
+            text.into(),
+            title,
+            None,
+            language,
+            Capability::ReadWrite,
+            cx,
+        )
+    }
+
+    pub(crate) fn open_scratch_buffer(
+        &mut self,
+        text: Cow<'static, str>,
+        title: &str,
+        breadcrumb_header: Option<String>,
+        language: &str,
+        capability: Capability,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<Box<dyn ItemHandle>>> {
+        let Some(build_item) = cx
+            .try_global::<GlobalScratchItemBuilder>()
+            .map(|builder| builder.0)
+        else {
+            return Task::ready(Err(anyhow!("no scratch item builder is registered")));
+        };
+        let title = title.to_string();
+        let language = self.app_state.languages.language_for_name(language);
+        cx.spawn(|workspace, mut cx| async move {
+            let language = language.await.log_err();
+            workspace
+                .update(&mut cx, |workspace, cx| {
+                    workspace.with_local_workspace(cx, move |workspace, cx| {
+                        let project = workspace.project().clone();
+                        let buffer = project.update(cx, |project, cx| {
+                            project.create_local_buffer(text.as_ref(), language, cx)
+                        });
+                        buffer.update(cx, |buffer, cx| buffer.set_capability(capability, cx));
+                        let item = build_item(buffer, title, breadcrumb_header, &project, cx);
+                        debug_assert!(item.is_ephemeral(cx), "scratch items must be ephemeral");
+                        workspace.add_item_to_active_pane(item.boxed_clone(), None, true, cx);
+                        item
+                    })
+                })?
+                .await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::{TestItem, TestProjectItem},
+        register_serializable_item,
+        tests::init_test,
+        CloseIntent,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+
+    #[gpui::test]
+    async fn test_open_scratch(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            register_serializable_item::<TestItem>(cx);
+            register_scratch_item_builder(
+                |_, title, _, _, cx| {
+                    Box::new(
+                        cx.new_view(|cx| TestItem::new(cx).with_label(&title).with_ephemeral(true)),
+                    )
+                },
+                cx,
+            );
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let item = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_scratch("report", "Report", "Plain Text", cx)
+            })
+            .await
+            .unwrap();
+        workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().read(cx);
+            assert_eq!(pane.active_item().unwrap().item_id(), item.item_id());
+            assert_eq!(
+                item.downcast::<TestItem>().unwrap().read(cx).label,
+                "Report"
+            );
+            // Even though the item is serializable, it is left out of the workspace's state.
+            assert!(item.to_serializable_item_handle(cx).is_none());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_close_window_with_ephemeral_items(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        // Dirty ephemeral items don't prevent the window from closing.
+        let scratch = cx.new_view(|cx| TestItem::new(cx).with_dirty(true).with_ephemeral(true));
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(scratch.clone()), None, true, cx)
+        });
+        let task = workspace.update(cx, |workspace, cx| {
+            workspace.prepare_to_close(CloseIntent::CloseWindow, cx)
+        });
+        cx.executor().run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        assert!(task.await.unwrap());
+        assert_eq!(scratch.read_with(cx, |item, _| item.save_count), 0);
+
+        // Other dirty items are still prompted for, but only they are.
+        let item = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_project_items(&[TestProjectItem::new(1, "1.txt", cx)])
+        });
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, cx)
+        });
+        let task = workspace.update(cx, |workspace, cx| {
+            workspace.prepare_to_close(CloseIntent::CloseWindow, cx)
+        });
+        cx.executor().run_until_parked();
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(1); // don't save
+        cx.executor().run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        assert!(task.await.unwrap());
+        assert_eq!(scratch.read_with(cx, |item, _| item.save_count), 0);
+    }
+}
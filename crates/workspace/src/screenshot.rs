@@ -0,0 +1,163 @@
+use std::{path::PathBuf, pin::Pin};
+
+use anyhow::{anyhow, Context as _, Result};
+use gpui::{ClipboardItem, Task, View, ViewContext, VisualContext};
+use uuid::Uuid;
+
+use crate::{
+    dock::DockPosition,
+    notifications::{
+        simple_message_notification::MessageNotification, NotificationId, NotifyTaskExt,
+    },
+    pane::Pane,
+    CaptureActivePaneScreenshot, Workspace,
+};
+
+struct PaneScreenshotCaptured;
+
+/// A part of the window to capture, see [`Workspace::capture_window_screenshot_region`].
+pub enum ScreenshotRegion {
+    Pane(View<Pane>),
+    Dock(DockPosition),
+}
+
+impl Workspace {
+    /// Captures a pane or an open dock as last drawn, and writes it to a PNG file in the
+    /// temp directory, e.g. to attach it to a bug report. Returns the path of the file.
+    pub fn capture_window_screenshot_region(
+        &mut self,
+        region: ScreenshotRegion,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<PathBuf>> {
+        let bounds = match &region {
+            ScreenshotRegion::Pane(pane) => pane
+                .read(cx)
+                .bounds
+                .ok_or_else(|| anyhow!("the pane hasn't been drawn yet")),
+            ScreenshotRegion::Dock(position) => {
+                let dock = match position {
+                    DockPosition::Left => &self.left_dock,
+                    DockPosition::Bottom => &self.bottom_dock,
+                    DockPosition::Right => &self.right_dock,
+                };
+                if self.is_dock_open(*position, cx) {
+                    dock.read(cx)
+                        .bounds
+                        .ok_or_else(|| anyhow!("the dock hasn't been drawn yet"))
+                } else {
+                    Err(anyhow!("the dock is closed"))
+                }
+            }
+        };
+        let png = match bounds.and_then(|bounds| cx.capture_region_png(bounds)) {
+            Ok(png) => png,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        let fs = self.app_state.fs.clone();
+        let dir = std::env::temp_dir().join("zed-screenshots");
+        let path = dir.join(format!("{}.png", Uuid::new_v4()));
+        cx.background_executor().spawn(async move {
+            fs.create_dir(&dir).await?;
+            fs.create_file_with(&path, Pin::new(&mut futures::io::Cursor::new(png)))
+                .await
+                .with_context(|| format!("failed to write screenshot to {path:?}"))?;
+            Ok(path)
+        })
+    }
+
+    pub(crate) fn capture_active_pane_screenshot(
+        &mut self,
+        _: &CaptureActivePaneScreenshot,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let pane = self.active_pane().clone();
+        let capture = self.capture_window_screenshot_region(ScreenshotRegion::Pane(pane), cx);
+        cx.spawn(|this, mut cx| async move {
+            let path = capture.await?;
+            this.update(&mut cx, |this, cx| {
+                let message = format!("Saved a screenshot of the pane to {path:?}");
+                let reveal_path = path.clone();
+                this.show_notification(
+                    NotificationId::unique::<PaneScreenshotCaptured>(),
+                    cx,
+                    |cx| {
+                        cx.new_view(|_| {
+                            MessageNotification::new(message)
+                                .with_click_message("Copy path")
+                                .on_click(move |cx| {
+                                    cx.write_to_clipboard(ClipboardItem::new_string(
+                                        path.to_string_lossy().into_owned(),
+                                    ))
+                                })
+                                .with_secondary_click_message("Reveal")
+                                .on_secondary_click(move |cx| cx.reveal_path(&reveal_path))
+                        })
+                    },
+                )
+            })
+        })
+        .detach_and_notify_err(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test;
+    use fs::{FakeFs, Fs as _};
+    use gpui::TestAppContext;
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_capture_window_screenshot_region(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs.clone(), [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        cx.run_until_parked();
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+        let capture = |region, cx: &mut gpui::VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                workspace.capture_window_screenshot_region(region, cx)
+            })
+        };
+
+        // Platforms that can't capture windows report an error.
+        let error = capture(ScreenshotRegion::Pane(pane.clone()), cx)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("not supported"));
+
+        let window = cx.update(|cx| cx.window_handle());
+        cx.simulate_window_capture_support(window, true);
+        let error = capture(ScreenshotRegion::Dock(DockPosition::Left), cx)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "the dock is closed");
+
+        // The active pane's screenshot is written to a file, which the notification can
+        // copy the path of and reveal.
+        cx.dispatch_action(CaptureActivePaneScreenshot);
+        cx.run_until_parked();
+        let notification = workspace.update(cx, |workspace, _| {
+            let (id, notification) = workspace.notifications.last().unwrap();
+            assert_eq!(id, &NotificationId::unique::<PaneScreenshotCaptured>());
+            notification
+                .to_any()
+                .downcast::<MessageNotification>()
+                .unwrap()
+        });
+        notification.update(cx, |notification, cx| notification.simulate_click(cx));
+        let path = PathBuf::from(cx.read_from_clipboard().unwrap().text().unwrap());
+        assert_eq!(path.extension(), Some("png".as_ref()));
+        let png = fs.load_bytes(&path).await.unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+
+        notification.update(cx, |notification, cx| {
+            notification.simulate_secondary_click(cx)
+        });
+        assert_eq!(cx.revealed_path(), Some(path));
+    }
+}
@@ -0,0 +1,352 @@
+use std::{collections::VecDeque, time::Duration};
+
+use anyhow::anyhow;
+use collections::{HashMap, HashSet};
+use gpui::{AppContext, Global, Keystroke, ViewContext, WindowId};
+use util::ResultExt;
+
+use crate::{
+    notifications::{NotificationId, Toast},
+    SendKeystrokes, Workspace,
+};
+
+/// How many keystrokes a run can send, to avoid infinite recursion.
+const MAX_SENT_KEYSTROKES: usize = 100;
+
+/// How many keystrokes a run can send before its progress is shown. Longer runs can be
+/// cancelled, and let the window handle other events between their keystrokes.
+const SHOW_PROGRESS_AFTER_KEYSTROKES: usize = 20;
+
+struct KeystrokesRunProgress;
+
+/// The keystrokes sent by a [`SendKeystrokes`] action, along with the ones sent by the
+/// actions those keystrokes trigger.
+struct KeystrokesRun {
+    /// The keystrokes of the action that started the run, which name it.
+    name: String,
+    /// The actions whose keystrokes are sent in this run, which can't trigger themselves.
+    actions: HashSet<String>,
+    /// The keystrokes left to dispatch, last one first, along with the action sending them.
+    keystrokes: Vec<(Keystroke, String)>,
+    dispatched: usize,
+}
+
+impl KeystrokesRun {
+    fn total(&self) -> usize {
+        self.dispatched + self.keystrokes.len()
+    }
+}
+
+/// The runs of keystrokes sent to a window, which are dispatched one after the other.
+#[derive(Default)]
+struct SentKeystrokes {
+    /// The run being dispatched first, followed by the ones waiting for it.
+    runs: VecDeque<KeystrokesRun>,
+    /// Set while a keystroke of the first run is dispatched, so that the keystrokes sent by
+    /// the actions it triggers are added to the run instead of waiting for it.
+    dispatching: bool,
+}
+
+#[derive(Default)]
+struct GlobalSentKeystrokes(HashMap<WindowId, SentKeystrokes>);
+
+impl Global for GlobalSentKeystrokes {}
+
+enum NextKeystroke {
+    Dispatch {
+        keystroke: Keystroke,
+        /// The name of the run, how many keystrokes it dispatched and how many it sends in
+        /// total, when the progress is shown.
+        progress: Option<(String, usize, usize)>,
+    },
+    /// All the keystrokes of the first run were dispatched, or it was cancelled.
+    RunEnded,
+    /// The named run sent too many keystrokes, the last one by the given action.
+    LimitReached {
+        run: String,
+        action: String,
+    },
+    Done,
+}
+
+fn next_keystroke(window_id: WindowId, cx: &mut AppContext) -> NextKeystroke {
+    let windows = &mut cx.default_global::<GlobalSentKeystrokes>().0;
+    let Some(sent) = windows.get_mut(&window_id) else {
+        return NextKeystroke::Done;
+    };
+    let Some(run) = sent.runs.front_mut() else {
+        windows.remove(&window_id);
+        return NextKeystroke::Done;
+    };
+    let Some((keystroke, action)) = run.keystrokes.pop() else {
+        sent.runs.pop_front();
+        return NextKeystroke::RunEnded;
+    };
+    if run.dispatched == MAX_SENT_KEYSTROKES {
+        let run = sent.runs.pop_front().unwrap();
+        return NextKeystroke::LimitReached {
+            run: run.name,
+            action,
+        };
+    }
+    run.dispatched += 1;
+    let progress = (run.total() > SHOW_PROGRESS_AFTER_KEYSTROKES)
+        .then(|| (run.name.clone(), run.dispatched, run.total()));
+    NextKeystroke::Dispatch {
+        keystroke,
+        progress,
+    }
+}
+
+fn set_dispatching(window_id: WindowId, dispatching: bool, cx: &mut AppContext) {
+    if let Some(sent) = cx
+        .default_global::<GlobalSentKeystrokes>()
+        .0
+        .get_mut(&window_id)
+    {
+        sent.dispatching = dispatching;
+    }
+}
+
+/// Drops the keystrokes left in the run being dispatched to the window, the runs waiting
+/// for it are dispatched next.
+fn cancel_keystrokes_run(window_id: WindowId, cx: &mut AppContext) {
+    if let Some(run) = cx
+        .default_global::<GlobalSentKeystrokes>()
+        .0
+        .get_mut(&window_id)
+        .and_then(|sent| sent.runs.front_mut())
+    {
+        run.keystrokes.clear();
+    }
+}
+
+impl Workspace {
+    pub(crate) fn send_keystrokes(&mut self, action: &SendKeystrokes, cx: &mut ViewContext<Self>) {
+        let window_id = cx.window_handle().window_id();
+        let keystrokes = action
+            .0
+            .split(' ')
+            .flat_map(|k| Keystroke::parse(k).log_err())
+            .map(|keystroke| (keystroke, action.0.clone()))
+            .rev()
+            .collect::<Vec<_>>();
+        let windows = &mut cx.default_global::<GlobalSentKeystrokes>().0;
+        let sent = windows.entry(window_id).or_default();
+        if sent.dispatching {
+            // The keystrokes sent by a keystroke of the run are part of it.
+            let run = sent
+                .runs
+                .front_mut()
+                .expect("keystrokes are dispatched for a run");
+            if !run.actions.insert(action.0.clone()) {
+                cx.propagate();
+                return;
+            }
+            run.keystrokes.extend(keystrokes);
+            return;
+        }
+        sent.runs.push_back(KeystrokesRun {
+            name: action.0.clone(),
+            actions: HashSet::from_iter([action.0.clone()]),
+            keystrokes,
+            dispatched: 0,
+        });
+        // The runs sent while dispatching others are picked up by the running task.
+        if sent.runs.len() > 1 {
+            return;
+        }
+
+        cx.spawn(|workspace, mut cx| async move {
+            let progress_id = NotificationId::unique::<KeystrokesRunProgress>();
+            loop {
+                match cx.update(|cx| next_keystroke(window_id, cx))? {
+                    NextKeystroke::Dispatch {
+                        keystroke,
+                        progress,
+                    } => {
+                        cx.update(|cx| {
+                            let focused = cx.focused();
+                            set_dispatching(window_id, true, cx);
+                            cx.dispatch_keystroke(keystroke);
+                            set_dispatching(window_id, false, cx);
+                            if cx.focused() != focused {
+                                // dispatch_keystroke may cause the focus to change.
+                                // draw's side effect is to schedule the FocusChanged events in the current flush effect cycle
+                                // And we need that to happen before the next keystroke to keep vim mode happy...
+                                // (Note that the tests always do this implicitly, so you must manually test with something like:
+                                //   "bindings": { "g z": ["workspace::SendKeystrokes", ": j <enter> u"]}
+                                // )
+                                cx.draw();
+                            }
+                        })?;
+                        if let Some((run, dispatched, total)) = progress {
+                            let toast = Toast::new(
+                                progress_id.clone(),
+                                format!(
+                                    "Running \"{}\" ({dispatched}/{total})",
+                                    util::truncate_and_trailoff(&run, 40)
+                                ),
+                            )
+                            .on_click("Cancel", move |cx| {
+                                cancel_keystrokes_run(window_id, cx);
+                            });
+                            workspace
+                                .update(&mut cx, |workspace, cx| workspace.show_toast(toast, cx))?;
+                            // Lets the toast be rendered and clicked.
+                            cx.background_executor().timer(Duration::ZERO).await;
+                        }
+                    }
+                    NextKeystroke::RunEnded => {
+                        workspace.update(&mut cx, |workspace, cx| {
+                            workspace.dismiss_toast(&progress_id, cx)
+                        })?;
+                    }
+                    NextKeystroke::LimitReached { run, action } => {
+                        let error = anyhow!(
+                            "Stopped \"{}\" after sending {MAX_SENT_KEYSTROKES} keystrokes, \
+                            the binding sending \"{}\" likely triggers itself",
+                            util::truncate_and_trailoff(&run, 40),
+                            util::truncate_and_trailoff(&action, 40)
+                        );
+                        workspace.update(&mut cx, |workspace, cx| {
+                            workspace.dismiss_toast(&progress_id, cx);
+                            workspace.show_error(&error, cx);
+                        })?;
+                    }
+                    NextKeystroke::Done => return anyhow::Ok(()),
+                }
+            }
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{notifications::ErrorNotification, tests::init_test, ToggleCenteredLayout};
+    use fs::FakeFs;
+    use gpui::{actions, KeyBinding, TestAppContext};
+    use project::Project;
+    use std::{cell::RefCell, rc::Rc};
+
+    actions!(send_keystrokes_test, [RecordX, RecordY, CancelRun]);
+
+    #[gpui::test]
+    async fn test_send_keystrokes_per_window(cx: &mut TestAppContext) {
+        init_test(cx);
+        let recursing_keystrokes = vec!["b"; MAX_SENT_KEYSTROKES + 1].join(" ");
+        cx.update(|cx| {
+            cx.bind_keys([
+                KeyBinding::new("a", SendKeystrokes(recursing_keystrokes.clone()), None),
+                KeyBinding::new("c", SendKeystrokes("d".into()), None),
+                KeyBinding::new("d", ToggleCenteredLayout, None),
+            ])
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let window_1 = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let window_2 = cx.add_window(|cx| Workspace::test_new(project, cx));
+
+        // Both windows send keystrokes at the same time.
+        cx.dispatch_keystroke(window_1.into(), Keystroke::parse("a").unwrap());
+        cx.dispatch_keystroke(window_2.into(), Keystroke::parse("c").unwrap());
+        cx.run_until_parked();
+
+        // The binding that sends too many keystrokes is reported in its window only, and the
+        // other window's keystrokes are dispatched.
+        window_1
+            .update(cx, |workspace, _| {
+                assert_eq!(workspace.notification_ids().len(), 1);
+                assert!(!workspace.centered_layout);
+            })
+            .unwrap();
+        window_2
+            .update(cx, |workspace, _| {
+                assert!(workspace.notification_ids().is_empty());
+                assert!(workspace.centered_layout);
+            })
+            .unwrap();
+
+        // Keystrokes can be sent again once the limit was reached.
+        cx.dispatch_keystroke(window_1.into(), Keystroke::parse("c").unwrap());
+        cx.run_until_parked();
+        window_1
+            .update(cx, |workspace, _| assert!(workspace.centered_layout))
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_send_keystrokes_runs(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project, cx));
+        let window_id = window.window_id();
+
+        let log = Rc::new(RefCell::new(String::new()));
+        let long_run = vec!["x"; SHOW_PROGRESS_AFTER_KEYSTROKES].join(" ");
+        cx.update(|cx| {
+            cx.bind_keys([
+                KeyBinding::new("x", RecordX, None),
+                KeyBinding::new("y", RecordY, None),
+                KeyBinding::new("z", CancelRun, None),
+                KeyBinding::new("1", SendKeystrokes("x x x".into()), None),
+                KeyBinding::new("2", SendKeystrokes("y y y".into()), None),
+                KeyBinding::new("3", SendKeystrokes("x 2 x".into()), None),
+                KeyBinding::new(
+                    "4",
+                    SendKeystrokes(vec!["y"; MAX_SENT_KEYSTROKES + 1].join(" ")),
+                    None,
+                ),
+                KeyBinding::new("5", SendKeystrokes(format!("x x x z {long_run}")), None),
+            ]);
+            let x_log = log.clone();
+            cx.on_action(move |_: &RecordX, _| x_log.borrow_mut().push('x'));
+            let y_log = log.clone();
+            cx.on_action(move |_: &RecordY, _| y_log.borrow_mut().push('y'));
+            // Stands for clicking the cancel button of the progress toast.
+            cx.on_action(move |_: &CancelRun, cx| cancel_keystrokes_run(window_id, cx));
+        });
+        let send = |keys: &[&str], cx: &mut TestAppContext| {
+            for key in keys {
+                cx.dispatch_keystroke(window.into(), Keystroke::parse(key).unwrap());
+            }
+            cx.run_until_parked();
+            log.take()
+        };
+
+        // Overlapping runs are dispatched one after the other, and the keystrokes sent by a run
+        // are part of it.
+        assert_eq!(send(&["1", "2"], cx), "xxxyyy");
+        assert_eq!(send(&["3", "1"], cx), "xyyyxxxx");
+
+        // Runs are limited on their own, and the error names the one that was stopped.
+        assert_eq!(send(&["4", "2"], cx), "y".repeat(MAX_SENT_KEYSTROKES + 3));
+        window
+            .update(cx, |workspace, cx| {
+                assert_eq!(workspace.notifications.len(), 1);
+                let notification = workspace.notifications[0]
+                    .1
+                    .to_any()
+                    .downcast::<ErrorNotification>()
+                    .unwrap();
+                assert!(notification
+                    .read(cx)
+                    .headline()
+                    .starts_with("Error: Stopped \"y y y"));
+                workspace.clear_all_notifications(cx);
+            })
+            .unwrap();
+
+        // Cancelling a run drops its remaining keystrokes only, and hides its progress.
+        assert_eq!(send(&["5", "2"], cx), "xxxyyy");
+        window
+            .update(cx, |workspace, _| {
+                assert!(workspace.notifications.is_empty())
+            })
+            .unwrap();
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::Result;
+use gpui::{Task, WindowContext};
+use util::ResultExt;
+
+use crate::{
+    persistence::{SerializedWindowBounds, DB},
+    Workspace,
+};
+
+impl Workspace {
+    /// Writes the window's bounds and the display it's on. While the display the window was
+    /// restored for is disconnected, only the bounds it has on the fallback display are
+    /// written, so that the ones it had on the missing display are restored once it's back.
+    pub(crate) fn save_window_bounds(&mut self, cx: &WindowContext) -> Option<Task<Result<()>>> {
+        debug_assert!(
+            !self.serialization_released,
+            "window bounds saved after the workspace was released"
+        );
+        let database_id = self.database_id?;
+        let display_uuid = cx.display()?.uuid().ok()?;
+        let window_bounds = SerializedWindowBounds(cx.window_bounds());
+        if let Some(preferred_display) = self.missing_preferred_display {
+            let preferred_display_connected = cx
+                .displays()
+                .iter()
+                .any(|display| display.uuid().ok() == Some(preferred_display));
+            if preferred_display_connected || display_uuid == preferred_display {
+                self.missing_preferred_display = None;
+            } else {
+                return Some(
+                    cx.background_executor()
+                        .spawn(DB.set_effective_window_bounds(
+                            database_id,
+                            window_bounds,
+                            display_uuid,
+                        )),
+                );
+            }
+        }
+        Some(cx.background_executor().spawn(DB.set_window_open_status(
+            database_id,
+            window_bounds,
+            display_uuid,
+        )))
+    }
+
+    /// Performs the debounced serialization and bounds save right away, cancelling their
+    /// tasks. Called once closing the window was confirmed, so that the last changes are
+    /// written from the workspace as it was rather than from a half torn-down one.
+    pub(crate) fn flush_pending_serialization(&mut self, cx: &mut WindowContext) -> Task<()> {
+        let serialize = self
+            ._schedule_serialize
+            .take()
+            .map(|_| self.serialize_workspace_internal(cx));
+        let save_bounds = self
+            .bounds_save_task_queued
+            .take()
+            .and_then(|_| self.save_window_bounds(cx));
+        cx.spawn(|_| async move {
+            if let Some(serialize) = serialize {
+                serialize.await;
+            }
+            if let Some(save_bounds) = save_bounds {
+                save_bounds.await.log_err();
+            }
+        })
+    }
+
+    /// Cancels the serialization still pending when the workspace is released, and stops
+    /// scheduling new ones. There's no window left to serialize from at this point, so the
+    /// pending changes are dropped: they were written by
+    /// [`Workspace::flush_pending_serialization`] if the window was closed through
+    /// [`Workspace::prepare_to_close`].
+    pub(crate) fn release_serialization(&mut self) {
+        let serialize = self._schedule_serialize.take();
+        let save_bounds = self.bounds_save_task_queued.take();
+        if serialize.is_some() || save_bounds.is_some() {
+            log::debug!("dropping the pending serialization of a released workspace");
+        }
+        self.serialization_released = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::TestItem, persistence::model::SerializedPaneGroup, tests::init_test,
+        CloseIntent, SplitDirection,
+    };
+    use fs::FakeFs;
+    use gpui::{point, px, size, Bounds, TestAppContext, VisualContext, WindowBounds};
+    use project::Project;
+    use serde_json::json;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    #[gpui::test]
+    async fn test_flush_pending_serialization_on_close(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "a.txt": "" })).await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let workspace_id = DB.next_id().await.unwrap();
+        workspace.update(cx, |workspace, cx| {
+            workspace.database_id = Some(workspace_id);
+            let item = Box::new(cx.new_view(TestItem::new));
+            workspace.add_item_to_active_pane(item, None, true, cx);
+        });
+        cx.executor().advance_clock(Duration::from_millis(200));
+        cx.run_until_parked();
+        let pane_count = |group: &SerializedPaneGroup| match group {
+            SerializedPaneGroup::Group { children, .. } => children.len(),
+            SerializedPaneGroup::Pane(_) => 1,
+        };
+        let serialized_pane_count =
+            || pane_count(&DB.workspace_for_roots(&["/root"]).unwrap().center_group);
+        assert_eq!(serialized_pane_count(), 1);
+
+        // The split is written when closing, without waiting for the debounce.
+        let close = workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            let new_pane = workspace.split_pane(pane, SplitDirection::Right, cx);
+            let item = Box::new(cx.new_view(TestItem::new));
+            workspace.add_item(new_pane, item, None, true, true, cx);
+            workspace.serialize_workspace(cx);
+            assert!(workspace._schedule_serialize.is_some());
+            workspace.prepare_to_close(CloseIntent::CloseWindow, cx)
+        });
+        cx.run_until_parked();
+        assert!(close.await.unwrap());
+        assert_eq!(serialized_pane_count(), 2);
+        workspace.update(cx, |workspace, _| {
+            assert!(workspace._schedule_serialize.is_none());
+            assert!(workspace.bounds_save_task_queued.is_none());
+        });
+
+        // Tearing the workspace down doesn't write its panes again.
+        workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            workspace.force_remove_pane(&pane, &None, cx);
+            cx.remove_window();
+        });
+        let workspace = workspace.downgrade();
+        cx.run_until_parked();
+        assert!(workspace.upgrade().is_none());
+        cx.executor().advance_clock(Duration::from_millis(200));
+        cx.run_until_parked();
+        assert_eq!(serialized_pane_count(), 2);
+    }
+
+    #[gpui::test]
+    async fn test_window_bounds_for_missing_display(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/docked", json!({ "a.txt": "" })).await;
+        let project = Project::test(fs, ["/docked".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let workspace_id = DB.next_id().await.unwrap();
+        workspace.update(cx, |workspace, cx| {
+            workspace.database_id = Some(workspace_id);
+            workspace.serialize_workspace(cx);
+        });
+        cx.executor().advance_clock(Duration::from_millis(200));
+        cx.run_until_parked();
+
+        // The window was last on a display that is now disconnected.
+        let monitor = Uuid::from_u128(u128::MAX);
+        let monitor_bounds = WindowBounds::Windowed(Bounds::new(
+            point(px(5000.), px(0.)),
+            size(px(800.), px(600.)),
+        ));
+        DB.set_window_open_status(
+            workspace_id,
+            SerializedWindowBounds(monitor_bounds),
+            monitor,
+        )
+        .await
+        .unwrap();
+        let primary_display = cx.update(|cx| cx.primary_display().unwrap());
+        let restored = cx.update(|cx| {
+            let serialized = DB.workspace_for_roots(&["/docked"]).unwrap();
+            crate::restorable_window_bounds(Some(&serialized), cx).unwrap()
+        });
+        assert_eq!(restored.display, primary_display.uuid().unwrap());
+        assert_eq!(restored.missing_display, Some(monitor));
+        assert!(primary_display
+            .bounds()
+            .contains(&restored.bounds.get_bounds().center()));
+
+        // Saving the bounds on the fallback display keeps the ones of the missing display.
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.missing_preferred_display = restored.missing_display;
+                workspace.save_window_bounds(cx).unwrap()
+            })
+            .await
+            .unwrap();
+        let bounds_by_display = DB
+            .window_bounds_by_display(workspace_id)
+            .unwrap()
+            .into_iter()
+            .map(|(display, bounds)| (display, bounds.0))
+            .collect::<Vec<_>>();
+        assert_eq!(bounds_by_display, [(monitor, monitor_bounds)]);
+        let serialized = DB.workspace_for_roots(&["/docked"]).unwrap();
+        assert_eq!(serialized.display, Some(primary_display.uuid().unwrap()));
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.missing_preferred_display, Some(monitor));
+        });
+    }
+}
@@ -0,0 +1,106 @@
+use gpui::ViewContext;
+
+use crate::Workspace;
+
+/// Session notes are truncated to this many characters when they are stored.
+pub const MAX_SESSION_NOTES_CHARS: usize = 10_000;
+
+impl Workspace {
+    /// The notes the user keeps about where they left off in this workspace, which are stored
+    /// along with its layout.
+    pub fn session_notes(&self) -> Option<&str> {
+        self.session_notes.as_deref()
+    }
+
+    /// Replaces the session notes, truncating them to [`MAX_SESSION_NOTES_CHARS`], and
+    /// schedules them to be serialized with the workspace.
+    pub fn set_session_notes(&mut self, notes: &str, cx: &mut ViewContext<Self>) {
+        let notes = util::truncate(notes, MAX_SESSION_NOTES_CHARS);
+        let notes = (!notes.trim().is_empty()).then(|| notes.to_string());
+        if notes == self.session_notes {
+            return;
+        }
+        self.session_notes = notes;
+        self.serialize_workspace(cx);
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{persistence::DB, tests::init_test};
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[gpui::test]
+    async fn test_session_notes(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({})).await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let workspace_id = DB.next_id().await.unwrap();
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.database_id = Some(workspace_id);
+                workspace.serialize_workspace_internal(cx)
+            })
+            .await;
+
+        workspace.update(cx, |workspace, cx| {
+            workspace.set_session_notes("Fix the", cx);
+            workspace.set_session_notes("Fix the flaky test", cx);
+            assert_eq!(workspace.session_notes(), Some("Fix the flaky test"));
+
+            workspace.set_session_notes(&"a".repeat(MAX_SESSION_NOTES_CHARS + 1), cx);
+            assert_eq!(
+                workspace.session_notes().unwrap().len(),
+                MAX_SESSION_NOTES_CHARS
+            );
+            workspace.set_session_notes("Where I left off", cx);
+        });
+
+        // The notes are stored once the serialization debounce elapses, and only their latest
+        // version is.
+        cx.run_until_parked();
+        assert_eq!(DB.session_notes(workspace_id).await.unwrap(), None);
+        cx.executor().advance_clock(Duration::from_millis(100));
+        cx.run_until_parked();
+        assert_eq!(
+            DB.session_notes(workspace_id).await.unwrap().as_deref(),
+            Some("Where I left off")
+        );
+
+        // Reopening the workspace restores them.
+        let serialized_workspace = DB.workspace_for_roots(&["/root"]).unwrap();
+        assert_eq!(
+            serialized_workspace.session_notes.as_deref(),
+            Some("Where I left off")
+        );
+        let reopened_workspace = cx.new_view(|cx| Workspace::test_new(project.clone(), cx));
+        reopened_workspace
+            .update(cx, |_, cx| {
+                Workspace::load_workspace(serialized_workspace, Vec::new(), cx)
+            })
+            .await
+            .unwrap();
+        reopened_workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.session_notes(), Some("Where I left off"));
+        });
+
+        // Clearing the notes removes them from the database.
+        reopened_workspace.update(cx, |workspace, cx| {
+            workspace.database_id = Some(workspace_id);
+            workspace.set_session_notes("  \n", cx);
+            assert_eq!(workspace.session_notes(), None);
+        });
+        cx.executor().advance_clock(Duration::from_millis(100));
+        cx.run_until_parked();
+        assert_eq!(DB.session_notes(workspace_id).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,145 @@
+use collections::HashMap;
+use gpui::{AppContext, Global, View, ViewContext};
+
+use crate::{item::ItemHandle, NewSearchInSplit, Pane, SplitDirection, Workspace};
+
+/// The kind the project search registers its view under, opened by [`NewSearchInSplit`].
+pub const PROJECT_SEARCH_VIEW_KIND: &str = "project_search";
+
+/// Builds a new item to open in a split, see [`Workspace::open_registered_view_in_split`].
+/// Called before the split is created, while the pane it's opened from is still active.
+pub type SplitViewFactory =
+    fn(&mut Workspace, &mut ViewContext<Workspace>) -> Option<Box<dyn ItemHandle>>;
+
+#[derive(Default)]
+struct SplitViewFactories(HashMap<&'static str, SplitViewFactory>);
+
+impl Global for SplitViewFactories {}
+
+/// Registers how to build the views of the given kind opened in a split.
+pub fn register_split_view(kind: &'static str, factory: SplitViewFactory, cx: &mut AppContext) {
+    cx.default_global::<SplitViewFactories>()
+        .0
+        .insert(kind, factory);
+}
+
+impl Workspace {
+    /// The pane next to the active one in `direction`, or a new one split from it.
+    pub fn pane_in_direction_or_split(
+        &mut self,
+        direction: SplitDirection,
+        cx: &mut ViewContext<Self>,
+    ) -> View<Pane> {
+        match self.find_pane_in_direction(direction, cx) {
+            Some(pane) => pane,
+            None => self.split_pane(self.active_pane.clone(), direction, cx),
+        }
+    }
+
+    /// Opens a new view of a registered `kind` in the pane next to the active one in
+    /// `direction`, splitting it if there's none. Returns the view opened, if the kind is
+    /// registered and its factory built one.
+    pub fn open_registered_view_in_split(
+        &mut self,
+        kind: &str,
+        direction: SplitDirection,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Box<dyn ItemHandle>> {
+        let Some(factory) = cx
+            .try_global::<SplitViewFactories>()
+            .and_then(|factories| factories.0.get(kind).copied())
+        else {
+            log::error!("no view registered to open in a split for {kind:?}");
+            return None;
+        };
+        let item = factory(self, cx)?;
+        let pane = self.pane_in_direction_or_split(direction, cx);
+        self.add_item(pane, item.boxed_clone(), None, true, true, cx);
+        Some(item)
+    }
+
+    pub(crate) fn new_search_in_split(
+        &mut self,
+        action: &NewSearchInSplit,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.open_registered_view_in_split(PROJECT_SEARCH_VIEW_KIND, action.0, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{item::test::TestItem, tests::init_test};
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+
+    const TEST_VIEW_KIND: &str = "test";
+
+    fn build_test_item(
+        _: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Option<Box<dyn ItemHandle>> {
+        Some(Box::new(cx.new_view(TestItem::new)))
+    }
+
+    #[gpui::test]
+    async fn test_open_registered_view_in_split(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| register_split_view(TEST_VIEW_KIND, build_test_item, cx));
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let left_pane = workspace.update(cx, |workspace, cx| {
+            let item = cx.new_view(TestItem::new);
+            workspace.add_item_to_active_pane(Box::new(item), None, true, cx);
+            workspace.active_pane().clone()
+        });
+
+        // The view is opened in a new split, which becomes active.
+        let first = workspace
+            .update(cx, |workspace, cx| {
+                assert!(workspace
+                    .open_registered_view_in_split("unknown", SplitDirection::Right, cx)
+                    .is_none());
+                workspace.open_registered_view_in_split(TEST_VIEW_KIND, SplitDirection::Right, cx)
+            })
+            .unwrap();
+        cx.run_until_parked();
+        let right_pane = workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes().len(), 2);
+            let right_pane = workspace.active_pane().clone();
+            assert_ne!(right_pane, left_pane);
+            assert_eq!(
+                right_pane.read(cx).active_item().unwrap().item_id(),
+                first.item_id()
+            );
+            assert_eq!(left_pane.read(cx).items_len(), 1);
+            right_pane
+        });
+
+        // From the left pane, the pane already on its right is reused.
+        workspace.update(cx, |workspace, cx| {
+            workspace.activate_pane_in_direction(SplitDirection::Left, cx)
+        });
+        cx.run_until_parked();
+        let second = workspace
+            .update(cx, |workspace, cx| {
+                assert_eq!(workspace.active_pane(), &left_pane);
+                workspace.open_registered_view_in_split(TEST_VIEW_KIND, SplitDirection::Right, cx)
+            })
+            .unwrap();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes().len(), 2);
+            assert_eq!(workspace.active_pane(), &right_pane);
+            let right_pane = right_pane.read(cx);
+            assert_eq!(right_pane.items_len(), 2);
+            assert_eq!(
+                right_pane.active_item().unwrap().item_id(),
+                second.item_id()
+            );
+        });
+    }
+}
@@ -0,0 +1,314 @@
+use anyhow::{anyhow, Result};
+use gpui::{AppContext, Global, Model, PromptLevel, Task, ViewContext, VisualContext};
+use remote::{ssh_session::ConnectionIdentifier, SshConnectionOptions, SshRemoteClient};
+use util::ResultExt;
+
+use crate::{
+    notifications::{simple_message_notification::MessageNotification, NotificationId},
+    open_items,
+    persistence::DB,
+    Workspace,
+};
+
+/// Connects to an SSH host on behalf of a workspace, prompting for passwords as needed.
+/// Resolves to `None` if the user cancels the connection.
+pub type SshConnector = fn(
+    &mut Workspace,
+    ConnectionIdentifier,
+    SshConnectionOptions,
+    &mut ViewContext<Workspace>,
+) -> Task<Result<Option<Model<SshRemoteClient>>>>;
+
+struct GlobalSshConnector(SshConnector);
+
+impl Global for GlobalSshConnector {}
+
+/// Registers how workspaces connect to their SSH host again, see
+/// [`Workspace::reconnect_ssh_project`].
+pub fn register_ssh_connector(connector: SshConnector, cx: &mut AppContext) {
+    cx.set_global(GlobalSshConnector(connector));
+}
+
+struct DisconnectedFromSshRemote;
+
+impl Workspace {
+    /// Connects to the SSH host of this workspace again and replaces its project with the new
+    /// session, restoring the layout, toolchains and the unsaved changes of the items that can
+    /// be serialized. The user is asked first whether to discard the unsaved changes that can't
+    /// be restored. If the connection fails, the workspace is left as it was.
+    pub fn reconnect_ssh_project(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let (Some(workspace_id), Some(ssh_project)) =
+            (self.database_id, self.serialized_ssh_project.clone())
+        else {
+            return Task::ready(Err(anyhow!("this is not an SSH project")));
+        };
+        let Some(connect) = cx.try_global::<GlobalSshConnector>().map(|global| global.0) else {
+            return Task::ready(Err(anyhow!("no SSH connector is registered")));
+        };
+        let connection_options = self
+            .project
+            .read(cx)
+            .ssh_connection_options(cx)
+            .unwrap_or_else(|| SshConnectionOptions {
+                host: ssh_project.host.clone(),
+                port: ssh_project.port,
+                username: ssh_project.user.clone(),
+                ..Default::default()
+            });
+
+        // Save the layout and unsaved changes first, so that they can be restored once the
+        // project has been replaced. Saving the items isn't possible without the connection.
+        let serialize_workspace = self.serialize_workspace_internal(cx);
+        let dirty_items = self
+            .panes
+            .iter()
+            .flat_map(|pane| pane.read(cx).items())
+            .filter(|item| item.is_dirty(cx))
+            .map(|item| item.boxed_clone())
+            .collect::<Vec<_>>();
+        let mut serialize_items = Vec::new();
+        let mut unrestorable_item_count = 0;
+        for item in dirty_items {
+            match item
+                .to_serializable_item_handle(cx)
+                .and_then(|item| item.serialize(self, true, cx))
+            {
+                Some(serialize_item) => serialize_items.push(serialize_item),
+                None => unrestorable_item_count += 1,
+            }
+        }
+        let discard_prompt = (unrestorable_item_count > 0).then(|| {
+            let message = if unrestorable_item_count == 1 {
+                "1 item has unsaved changes that can't be restored after reconnecting".to_string()
+            } else {
+                format!(
+                    "{unrestorable_item_count} items have unsaved changes that can't be restored after reconnecting"
+                )
+            };
+            cx.prompt(
+                PromptLevel::Warning,
+                &message,
+                Some("Reconnecting discards these changes."),
+                &["Discard and Reconnect", "Cancel"],
+            )
+        });
+        let app_state = self.app_state.clone();
+        cx.spawn(|this, mut cx| async move {
+            if let Some(discard_prompt) = discard_prompt {
+                if discard_prompt.await? != 0 {
+                    return Ok(());
+                }
+            }
+            serialize_workspace.await;
+            futures::future::try_join_all(serialize_items).await?;
+            let connect = this.update(&mut cx, |this, cx| {
+                connect(
+                    this,
+                    ConnectionIdentifier::Workspace(workspace_id.0),
+                    connection_options,
+                    cx,
+                )
+            })?;
+            let Some(session) = connect.await? else {
+                return Ok(());
+            };
+
+            let project = cx.update(|cx| {
+                project::Project::ssh(
+                    session,
+                    app_state.client.clone(),
+                    app_state.node_runtime.clone(),
+                    app_state.user_store.clone(),
+                    app_state.languages.clone(),
+                    app_state.fs.clone(),
+                    cx,
+                )
+            })?;
+
+            let toolchains = DB.toolchains(workspace_id).await?;
+            for (toolchain, worktree_id) in toolchains {
+                project
+                    .update(&mut cx, |this, cx| {
+                        this.activate_toolchain(worktree_id, toolchain, cx)
+                    })?
+                    .await;
+            }
+
+            let serialized_workspace = cx
+                .background_executor()
+                .spawn({
+                    let ssh_project = ssh_project.clone();
+                    async move { DB.workspace_for_ssh_project(&ssh_project) }
+                })
+                .await;
+            let workspace = cx.update(|cx| {
+                cx.replace_root_view(|cx| {
+                    let mut workspace =
+                        Workspace::new(Some(workspace_id), project, app_state.clone(), cx);
+                    workspace.set_serialized_ssh_project(ssh_project);
+                    workspace
+                })
+            })?;
+            workspace
+                .update(&mut cx, |workspace, cx| {
+                    open_items(workspace, serialized_workspace, Vec::new(), cx)
+                })?
+                .await?;
+            Ok(())
+        })
+    }
+
+    pub(crate) fn show_ssh_disconnected_notification(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(ssh_project) = self.serialized_ssh_project.as_ref() else {
+            return;
+        };
+        let message = format!("Lost the connection to {}", ssh_project.host);
+        let workspace = cx.view().downgrade();
+        self.show_notification(
+            NotificationId::unique::<DisconnectedFromSshRemote>(),
+            cx,
+            |cx| {
+                cx.new_view(|_| {
+                    MessageNotification::new(message)
+                        .with_click_message("Reconnect")
+                        .on_click(move |cx| {
+                            workspace
+                                .update(cx, |workspace, cx| {
+                                    workspace.reconnect_ssh_project_or_show_error(cx)
+                                })
+                                .log_err();
+                        })
+                })
+            },
+        );
+    }
+
+    fn reconnect_ssh_project_or_show_error(&mut self, cx: &mut ViewContext<Self>) {
+        let reconnect = self.reconnect_ssh_project(cx);
+        cx.spawn(|this, mut cx| async move {
+            if let Err(error) = reconnect.await {
+                this.update(&mut cx, |this, cx| this.show_error(&error, cx))
+                    .log_err();
+            }
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+    use crate::{item::test::TestItem, notifications::ErrorNotification, tests::init_test};
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_failed_ssh_reconnect(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            register_ssh_connector(
+                |_, _, connection_options, _| {
+                    assert_eq!(connection_options.host, "example.com");
+                    Task::ready(Err(anyhow!("connection refused")))
+                },
+                cx,
+            )
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let ssh_project = DB
+            .get_or_create_ssh_project("example.com".into(), None, vec!["/root".into()], None)
+            .await
+            .unwrap();
+        let workspace_id = DB.next_id().await.unwrap();
+        workspace.update(cx, |workspace, _| {
+            workspace.database_id = Some(workspace_id);
+            workspace.set_serialized_ssh_project(ssh_project);
+        });
+
+        project.update(cx, |_, cx| {
+            cx.emit(project::Event::DisconnectedFromSshRemote)
+        });
+        cx.run_until_parked();
+        let notification = workspace.update(cx, |workspace, _| {
+            let (id, notification) = workspace.notifications.last().unwrap();
+            assert_eq!(id, &NotificationId::unique::<DisconnectedFromSshRemote>());
+            notification
+                .to_any()
+                .downcast::<MessageNotification>()
+                .unwrap()
+        });
+
+        // The failure is reported, and the workspace keeps its project.
+        notification.update(cx, |notification, cx| notification.simulate_click(cx));
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.notifications.len(), 1);
+            let (_, notification) = workspace.notifications.last().unwrap();
+            assert!(notification
+                .to_any()
+                .downcast::<ErrorNotification>()
+                .is_ok());
+            assert_eq!(workspace.project(), &project);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_ssh_reconnect_with_unrestorable_changes(cx: &mut TestAppContext) {
+        static CONNECTED: AtomicBool = AtomicBool::new(false);
+        init_test(cx);
+        cx.update(|cx| {
+            register_ssh_connector(
+                |_, _, _, _| {
+                    CONNECTED.store(true, Ordering::SeqCst);
+                    Task::ready(Ok(None))
+                },
+                cx,
+            )
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let ssh_project = DB
+            .get_or_create_ssh_project("example.com".into(), None, vec!["/root".into()], None)
+            .await
+            .unwrap();
+        let workspace_id = DB.next_id().await.unwrap();
+        // Test items aren't registered as serializable, so their changes can't be restored.
+        let dirty_item = cx.new_view(|cx| TestItem::new(cx).with_dirty(true));
+        workspace.update(cx, |workspace, cx| {
+            workspace.database_id = Some(workspace_id);
+            workspace.set_serialized_ssh_project(ssh_project);
+            workspace.add_item_to_active_pane(Box::new(dirty_item.clone()), None, true, cx);
+        });
+
+        // Cancelling the prompt leaves the workspace as it was, without connecting.
+        let reconnect = workspace.update(cx, |workspace, cx| workspace.reconnect_ssh_project(cx));
+        cx.run_until_parked();
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(1);
+        reconnect.await.unwrap();
+        assert!(!CONNECTED.load(Ordering::SeqCst));
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.project(), &project);
+            assert!(workspace
+                .active_pane()
+                .read(cx)
+                .index_for_item(&dirty_item)
+                .is_some());
+        });
+
+        // Discarding the changes goes on with reconnecting.
+        let reconnect = workspace.update(cx, |workspace, cx| workspace.reconnect_ssh_project(cx));
+        cx.run_until_parked();
+        cx.simulate_prompt_answer(0);
+        reconnect.await.unwrap();
+        assert!(CONNECTED.load(Ordering::SeqCst));
+    }
+}
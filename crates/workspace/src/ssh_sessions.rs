@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use collections::HashMap;
+use futures::{future::Shared, FutureExt as _};
+use gpui::{AppContext, AsyncAppContext, Global, Model, Task, WeakModel};
+use project::Project;
+
+use crate::WorkspaceId;
+
+type SharedSshProject = Shared<Task<Result<Option<Model<Project>>, Arc<anyhow::Error>>>>;
+
+enum SshSession {
+    Connecting(SharedSshProject),
+    Connected(WeakModel<Project>),
+}
+
+/// The projects of the SSH workspaces opened in this process, keyed by workspace. The remote
+/// server of a workspace is reached through a socket named after it, so two connections for
+/// the same workspace would take over each other's: windows restoring the same workspace
+/// share its project instead.
+#[derive(Default)]
+struct SshSessions(HashMap<WorkspaceId, SshSession>);
+
+impl Global for SshSessions {}
+
+/// Returns the project of the SSH workspace `workspace_id`, connecting with `connect` unless
+/// another window already connected to it or is connecting. The second value is whether this
+/// call connected, rather than sharing the project of another window. Resolves to `None` if
+/// the user cancelled the connection.
+pub(crate) fn shared_ssh_project(
+    workspace_id: WorkspaceId,
+    cx: &mut AsyncAppContext,
+    connect: impl 'static + FnOnce(&mut AsyncAppContext) -> Task<Result<Option<Model<Project>>>>,
+) -> Result<(Task<Result<Option<Model<Project>>>>, bool)> {
+    cx.update(|cx| {
+        let sessions = cx.default_global::<SshSessions>();
+        match sessions.0.get(&workspace_id) {
+            Some(SshSession::Connecting(task)) => {
+                return (share_ssh_project(task.clone(), cx), false);
+            }
+            Some(SshSession::Connected(project)) => {
+                if let Some(project) = project.upgrade() {
+                    return (Task::ready(Ok(Some(project))), false);
+                }
+            }
+            None => {}
+        }
+
+        let task = cx
+            .spawn(|mut cx| async move {
+                let project = connect(&mut cx).await;
+                cx.update_global(|sessions: &mut SshSessions, _| match &project {
+                    Ok(Some(project)) => {
+                        sessions
+                            .0
+                            .insert(workspace_id, SshSession::Connected(project.downgrade()));
+                    }
+                    Ok(None) | Err(_) => {
+                        sessions.0.remove(&workspace_id);
+                    }
+                })
+                .ok();
+                project.map_err(Arc::new)
+            })
+            .shared();
+        cx.default_global::<SshSessions>()
+            .0
+            .insert(workspace_id, SshSession::Connecting(task.clone()));
+        (
+            cx.background_executor()
+                .spawn(async move { task.await.map_err(|error| anyhow!("{error:#}")) }),
+            true,
+        )
+    })
+}
+
+fn share_ssh_project(
+    task: SharedSshProject,
+    cx: &AppContext,
+) -> Task<Result<Option<Model<Project>>>> {
+    cx.background_executor().spawn(async move {
+        task.await.map_err(|error| {
+            anyhow!("the connection opened by another window for this project failed: {error:#}")
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use std::{cell::Cell, rc::Rc, time::Duration};
+
+    #[gpui::test]
+    async fn test_shared_ssh_project(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let attempts = Rc::new(Cell::new(0));
+        let connect = |result: Result<Option<Model<Project>>>| {
+            let attempts = attempts.clone();
+            move |cx: &mut AsyncAppContext| {
+                attempts.set(attempts.get() + 1);
+                let timer = cx.background_executor().timer(Duration::from_secs(1));
+                cx.background_executor().spawn(async move {
+                    timer.await;
+                    result
+                })
+            }
+        };
+        let mut async_cx = cx.to_async();
+
+        // Two windows restoring the same workspace at once connect once and share the project.
+        let workspace_id = WorkspaceId(1);
+        let (first, first_connected) = shared_ssh_project(
+            workspace_id,
+            &mut async_cx,
+            connect(Ok(Some(project.clone()))),
+        )
+        .unwrap();
+        let (second, second_connected) =
+            shared_ssh_project(workspace_id, &mut async_cx, connect(Ok(None))).unwrap();
+        assert!(first_connected);
+        assert!(!second_connected);
+        cx.executor().advance_clock(Duration::from_secs(1));
+        assert_eq!(first.await.unwrap(), Some(project.clone()));
+        assert_eq!(second.await.unwrap(), Some(project.clone()));
+        assert_eq!(attempts.get(), 1);
+
+        // Once connected, the project keeps being shared while it's alive.
+        let (third, third_connected) =
+            shared_ssh_project(workspace_id, &mut async_cx, connect(Ok(None))).unwrap();
+        assert!(!third_connected);
+        assert_eq!(third.await.unwrap(), Some(project.clone()));
+        assert_eq!(attempts.get(), 1);
+
+        // A failed connection is reported to all the windows waiting for it.
+        let workspace_id = WorkspaceId(2);
+        let (first, _) = shared_ssh_project(
+            workspace_id,
+            &mut async_cx,
+            connect(Err(anyhow!("connection refused"))),
+        )
+        .unwrap();
+        let (second, _) =
+            shared_ssh_project(workspace_id, &mut async_cx, connect(Ok(None))).unwrap();
+        cx.executor().advance_clock(Duration::from_secs(1));
+        assert_eq!(first.await.unwrap_err().to_string(), "connection refused");
+        assert_eq!(
+            second.await.unwrap_err().to_string(),
+            "the connection opened by another window for this project failed: connection refused"
+        );
+        assert_eq!(attempts.get(), 2);
+
+        // The next attempt connects again.
+        let (_, connected) =
+            shared_ssh_project(workspace_id, &mut async_cx, connect(Ok(None))).unwrap();
+        assert!(connected);
+    }
+}
@@ -0,0 +1,181 @@
+use std::fmt;
+
+use anyhow::Result;
+use gpui::{AppContext, EntityId, ViewContext};
+use serde::{Deserialize, Serialize};
+use sqlez::{
+    bindable::{Bind, Column, StaticColumnCount},
+    statement::Statement,
+};
+use uuid::Uuid;
+
+use crate::{item::ItemHandle, Workspace};
+
+/// Identifies an item across sessions, unlike its [`crate::ItemId`] which is an entity id. It's
+/// assigned when the item is first added to a pane and serialized along with the item, so
+/// that features can keep state of their own for an item from one session to the next.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StableItemId(Uuid);
+
+impl StableItemId {
+    pub(crate) fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl fmt::Display for StableItemId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl StaticColumnCount for StableItemId {}
+
+impl Bind for StableItemId {
+    fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
+        self.0.bind(statement, start_index)
+    }
+}
+
+impl Column for StableItemId {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let (uuid, next_index) = Uuid::column(statement, start_index)?;
+        Ok((Self(uuid), next_index))
+    }
+}
+
+impl Workspace {
+    /// The [`StableItemId`] of an item in the center panes.
+    pub fn stable_id_for_item(&self, item: &dyn ItemHandle) -> Option<StableItemId> {
+        self.stable_item_ids.get(&item.item_id()).copied()
+    }
+
+    /// The item of the center panes with the given [`StableItemId`].
+    pub fn item_for_stable_id(
+        &self,
+        stable_id: StableItemId,
+        cx: &AppContext,
+    ) -> Option<Box<dyn ItemHandle>> {
+        self.items(cx)
+            .find(|item| self.stable_id_for_item(item.as_ref()) == Some(stable_id))
+            .map(|item| item.boxed_clone())
+    }
+
+    pub(crate) fn assign_stable_item_id(&mut self, item: &dyn ItemHandle) {
+        self.stable_item_ids
+            .entry(item.item_id())
+            .or_insert_with(StableItemId::new);
+    }
+
+    /// Forgets the id of an item removed from a pane, unless it was moved to another one.
+    pub(crate) fn forget_stable_item_id(&mut self, item_id: EntityId, cx: &AppContext) {
+        let still_open = self
+            .panes
+            .iter()
+            .any(|pane| pane.read(cx).items().any(|item| item.item_id() == item_id));
+        if !still_open {
+            self.stable_item_ids.remove(&item_id);
+        }
+    }
+
+    /// Gives a deserialized item back the id it had in the previous session.
+    pub(crate) fn restore_stable_item_id(
+        &mut self,
+        item: &dyn ItemHandle,
+        stable_id: StableItemId,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.stable_item_ids.insert(item.item_id(), stable_id);
+        if let Some(item) = item.to_serializable_item_handle(cx) {
+            item.restore_stable_id(stable_id, cx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::TestItem, move_item, persistence::DB, register_serializable_item,
+        tests::init_test, SaveIntent, SplitDirection,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_stable_item_ids(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| register_serializable_item::<TestItem>(cx));
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({})).await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let workspace_id = DB.next_id().await.unwrap();
+        let item = cx.new_view(TestItem::new);
+        workspace.update(cx, |workspace, cx| {
+            workspace.database_id = Some(workspace_id);
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, cx);
+        });
+        cx.run_until_parked();
+        let stable_id = workspace.update(cx, |workspace, cx| {
+            let stable_id = workspace.stable_id_for_item(&item).unwrap();
+            let found = workspace.item_for_stable_id(stable_id, cx).unwrap();
+            assert_eq!(found.item_id(), item.item_id());
+            stable_id
+        });
+
+        // The id is kept when the item moves to another pane.
+        workspace.update(cx, |workspace, cx| {
+            let left_pane = workspace.active_pane().clone();
+            let right_pane = workspace.split_pane(left_pane.clone(), SplitDirection::Right, cx);
+            move_item(&left_pane, &right_pane, item.item_id(), 0, cx);
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.stable_id_for_item(&item), Some(stable_id));
+        });
+
+        // The restored item gets its id back.
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.serialize_workspace_internal(cx)
+            })
+            .await;
+        let serialized_workspace = DB.workspace_for_roots(&["/root"]).unwrap();
+        let restored_workspace = cx.new_view(|cx| Workspace::test_new(project.clone(), cx));
+        restored_workspace
+            .update(cx, |_, cx| {
+                Workspace::load_workspace(serialized_workspace, Vec::new(), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        let restored_item = restored_workspace.update(cx, |workspace, cx| {
+            let restored_item = workspace.items_of_type::<TestItem>(cx).next().unwrap();
+            assert_eq!(
+                workspace.stable_id_for_item(&restored_item),
+                Some(stable_id)
+            );
+            assert_eq!(restored_item.read(cx).restored_stable_id, Some(stable_id));
+            restored_item
+        });
+
+        // Once the item is closed, its id is forgotten.
+        let pane = restored_workspace.update(cx, |workspace, _| {
+            workspace.pane_for(&restored_item).unwrap()
+        });
+        pane.update(cx, |pane, cx| {
+            pane.close_item_by_id(restored_item.item_id(), SaveIntent::Skip, cx)
+        })
+        .await
+        .unwrap();
+        cx.run_until_parked();
+        restored_workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.stable_id_for_item(&restored_item), None);
+            assert!(workspace.item_for_stable_id(stable_id, cx).is_none());
+        });
+    }
+}
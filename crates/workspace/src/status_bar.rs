@@ -1,7 +1,7 @@
 use crate::{ItemHandle, Pane};
 use gpui::{
-    AnyView, Decorations, IntoElement, ParentElement, Render, Styled, Subscription, View,
-    ViewContext, WindowContext,
+    AnyView, Decorations, EntityId, IntoElement, ParentElement, Render, Styled, Subscription, View,
+    ViewContext, WeakView, WindowContext,
 };
 use std::any::TypeId;
 use theme::CLIENT_SIDE_DECORATION_ROUNDING;
@@ -24,15 +24,77 @@ trait StatusItemViewHandle: Send {
         cx: &mut WindowContext,
     );
     fn item_type(&self) -> TypeId;
+    fn item_id(&self) -> EntityId;
+}
+
+/// The side of the status bar an item is shown on.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StatusItemPosition {
+    Left,
+    Right,
+}
+
+struct StatusItem {
+    view: Box<dyn StatusItemViewHandle>,
+    /// Items with a lower priority are closer to the edge of their side.
+    priority: i32,
 }
 
 pub struct StatusBar {
-    left_items: Vec<Box<dyn StatusItemViewHandle>>,
-    right_items: Vec<Box<dyn StatusItemViewHandle>>,
+    left_items: Vec<StatusItem>,
+    right_items: Vec<StatusItem>,
     active_pane: View<Pane>,
     _observe_active_pane: Subscription,
 }
 
+/// Keeps an item added with [`crate::Workspace::add_status_item`] in the status bar, until
+/// it is dropped or [`StatusItemHandle::remove`] is called.
+#[must_use]
+pub struct StatusItemHandle {
+    status_bar: WeakView<StatusBar>,
+    item_id: EntityId,
+    _remove_on_drop: Subscription,
+}
+
+impl StatusItemHandle {
+    pub(crate) fn new(status_bar: &View<StatusBar>, item_id: EntityId, cx: &WindowContext) -> Self {
+        let status_bar = status_bar.downgrade();
+        let remove_on_drop = Subscription::new({
+            let status_bar = status_bar.clone();
+            let cx = cx.to_async();
+            move || {
+                // Handles can be dropped while the status bar is being updated.
+                cx.spawn(|mut cx| async move {
+                    status_bar
+                        .update(&mut cx, |status_bar, cx| {
+                            status_bar.remove_item(item_id, cx)
+                        })
+                        .ok();
+                })
+                .detach();
+            }
+        });
+        Self {
+            status_bar,
+            item_id,
+            _remove_on_drop: remove_on_drop,
+        }
+    }
+
+    pub fn item_id(&self) -> EntityId {
+        self.item_id
+    }
+
+    /// Removes the item from the status bar right away.
+    pub fn remove(self, cx: &mut WindowContext) {
+        self.status_bar
+            .update(cx, |status_bar, cx| {
+                status_bar.remove_item(self.item_id, cx)
+            })
+            .ok();
+    }
+}
+
 impl Render for StatusBar {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         h_flex()
@@ -66,13 +128,13 @@ impl StatusBar {
         h_flex()
             .gap(DynamicSpacing::Base04.rems(cx))
             .overflow_x_hidden()
-            .children(self.left_items.iter().map(|item| item.to_any()))
+            .children(self.left_items.iter().map(|item| item.view.to_any()))
     }
 
     fn render_right_tools(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         h_flex()
             .gap(DynamicSpacing::Base04.rems(cx))
-            .children(self.right_items.iter().rev().map(|item| item.to_any()))
+            .children(self.right_items.iter().rev().map(|item| item.view.to_any()))
     }
 }
 
@@ -92,19 +154,64 @@ impl StatusBar {
     pub fn add_left_item<T>(&mut self, item: View<T>, cx: &mut ViewContext<Self>)
     where
         T: 'static + StatusItemView,
+    {
+        self.add_item(StatusItemPosition::Left, 0, item, cx);
+    }
+
+    /// Adds an item after the ones of its side with the same or a lower priority.
+    pub fn add_item<T>(
+        &mut self,
+        position: StatusItemPosition,
+        priority: i32,
+        item: View<T>,
+        cx: &mut ViewContext<Self>,
+    ) where
+        T: 'static + StatusItemView,
     {
         let active_pane_item = self.active_pane.read(cx).active_item();
         item.set_active_pane_item(active_pane_item.as_deref(), cx);
 
-        self.left_items.push(Box::new(item));
+        let items = match position {
+            StatusItemPosition::Left => &mut self.left_items,
+            StatusItemPosition::Right => &mut self.right_items,
+        };
+        let ix = items.partition_point(|item| item.priority <= priority);
+        items.insert(
+            ix,
+            StatusItem {
+                view: Box::new(item),
+                priority,
+            },
+        );
         cx.notify();
     }
 
+    /// Removes the item with the given id from either side.
+    pub fn remove_item(&mut self, item_id: EntityId, cx: &mut ViewContext<Self>) {
+        let count = self.left_items.len() + self.right_items.len();
+        self.left_items
+            .retain(|item| item.view.item_id() != item_id);
+        self.right_items
+            .retain(|item| item.view.item_id() != item_id);
+        if self.left_items.len() + self.right_items.len() != count {
+            cx.notify();
+        }
+    }
+
+    /// The ids of the items of a side, from its edge.
+    pub fn item_ids(&self, position: StatusItemPosition) -> Vec<EntityId> {
+        let items = match position {
+            StatusItemPosition::Left => &self.left_items,
+            StatusItemPosition::Right => &self.right_items,
+        };
+        items.iter().map(|item| item.view.item_id()).collect()
+    }
+
     pub fn item_of_type<T: StatusItemView>(&self) -> Option<View<T>> {
         self.left_items
             .iter()
             .chain(self.right_items.iter())
-            .find_map(|item| item.to_any().clone().downcast().log_err())
+            .find_map(|item| item.view.to_any().clone().downcast().log_err())
     }
 
     pub fn position_of_item<T>(&self) -> Option<usize>
@@ -112,12 +219,12 @@ impl StatusBar {
         T: StatusItemView,
     {
         for (index, item) in self.left_items.iter().enumerate() {
-            if item.item_type() == TypeId::of::<T>() {
+            if item.view.item_type() == TypeId::of::<T>() {
                 return Some(index);
             }
         }
         for (index, item) in self.right_items.iter().enumerate() {
-            if item.item_type() == TypeId::of::<T>() {
+            if item.view.item_type() == TypeId::of::<T>() {
                 return Some(index + self.left_items.len());
             }
         }
@@ -136,10 +243,24 @@ impl StatusBar {
         item.set_active_pane_item(active_pane_item.as_deref(), cx);
 
         if position < self.left_items.len() {
-            self.left_items.insert(position + 1, Box::new(item))
+            let priority = self.left_items[position].priority;
+            self.left_items.insert(
+                position + 1,
+                StatusItem {
+                    view: Box::new(item),
+                    priority,
+                },
+            )
         } else {
-            self.right_items
-                .insert(position + 1 - self.left_items.len(), Box::new(item))
+            let position = position - self.left_items.len();
+            let priority = self.right_items[position].priority;
+            self.right_items.insert(
+                position + 1,
+                StatusItem {
+                    view: Box::new(item),
+                    priority,
+                },
+            )
         }
         cx.notify()
     }
@@ -157,11 +278,7 @@ impl StatusBar {
     where
         T: 'static + StatusItemView,
     {
-        let active_pane_item = self.active_pane.read(cx).active_item();
-        item.set_active_pane_item(active_pane_item.as_deref(), cx);
-
-        self.right_items.push(Box::new(item));
-        cx.notify();
+        self.add_item(StatusItemPosition::Right, 0, item, cx);
     }
 
     pub fn set_active_pane(&mut self, active_pane: &View<Pane>, cx: &mut ViewContext<Self>) {
@@ -174,7 +291,8 @@ impl StatusBar {
     fn update_active_pane_item(&mut self, cx: &mut ViewContext<Self>) {
         let active_pane_item = self.active_pane.read(cx).active_item();
         for item in self.left_items.iter().chain(&self.right_items) {
-            item.set_active_pane_item(active_pane_item.as_deref(), cx);
+            item.view
+                .set_active_pane_item(active_pane_item.as_deref(), cx);
         }
     }
 }
@@ -197,6 +315,10 @@ impl<T: StatusItemView> StatusItemViewHandle for View<T> {
     fn item_type(&self) -> TypeId {
         TypeId::of::<T>()
     }
+
+    fn item_id(&self) -> EntityId {
+        self.entity_id()
+    }
 }
 
 impl From<&dyn StatusItemViewHandle> for AnyView {
@@ -204,3 +326,85 @@ impl From<&dyn StatusItemViewHandle> for AnyView {
         val.to_any().clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::init_test, Workspace};
+    use fs::FakeFs;
+    use gpui::{Empty, TestAppContext, VisualContext};
+    use project::Project;
+
+    struct TestStatusItem;
+
+    impl Render for TestStatusItem {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            Empty
+        }
+    }
+
+    impl StatusItemView for TestStatusItem {
+        fn set_active_pane_item(
+            &mut self,
+            _: Option<&dyn crate::ItemHandle>,
+            _: &mut ViewContext<Self>,
+        ) {
+        }
+    }
+
+    #[gpui::test]
+    async fn test_add_and_remove_status_items(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let right_item_ids = |cx: &mut gpui::VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                workspace
+                    .status_bar()
+                    .read(cx)
+                    .item_ids(StatusItemPosition::Right)
+            })
+        };
+        let initial_ids = right_item_ids(cx);
+
+        let items = (0..3)
+            .map(|_| cx.new_view(|_| TestStatusItem))
+            .collect::<Vec<_>>();
+        let mut handles = workspace.update(cx, |workspace, cx| {
+            [None, Some(10), Some(-10)]
+                .into_iter()
+                .zip(&items)
+                .map(|(priority, item)| {
+                    workspace.add_status_item(StatusItemPosition::Right, priority, item.clone(), cx)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        // Items are ordered by priority, the dock buttons staying at the edge.
+        let ids = right_item_ids(cx);
+        assert_eq!(ids[..initial_ids.len()], initial_ids);
+        assert_eq!(
+            ids[initial_ids.len()..],
+            [
+                items[2].entity_id(),
+                items[0].entity_id(),
+                items[1].entity_id()
+            ]
+        );
+
+        // Items go away when their handle is removed or dropped.
+        let handle = handles.remove(0);
+        cx.update(|cx| handle.remove(cx));
+        let ids = right_item_ids(cx);
+        assert_eq!(
+            ids[initial_ids.len()..],
+            [items[2].entity_id(), items[1].entity_id()]
+        );
+
+        drop(handles);
+        cx.run_until_parked();
+        assert_eq!(right_item_ids(cx), initial_ids);
+    }
+}
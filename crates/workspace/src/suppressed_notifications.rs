@@ -0,0 +1,160 @@
+use gpui::ViewContext;
+use util::ResultExt;
+
+use crate::{
+    notifications::{simple_message_notification::MessageNotification, NotificationId},
+    persistence::DB,
+    ResetSuppressedNotifications, Workspace, WorkspaceId,
+};
+
+impl Workspace {
+    /// Reads the notifications suppressed in this workspace from the database, dismissing the
+    /// ones shown in the meantime.
+    pub(crate) fn load_suppressed_notifications(
+        workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let namespaces = cx
+            .background_executor()
+            .spawn(async move { DB.suppressed_notifications(workspace_id) });
+        cx.spawn(|this, mut cx| async move {
+            let Some(namespaces) = namespaces.await.log_err() else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                for namespace in namespaces {
+                    this.dismiss_notification(&NotificationId::named(namespace.clone().into()), cx);
+                    this.suppressed_notifications.insert(namespace);
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Stops showing the notifications of a namespace in this workspace, including after a
+    /// restart, until [`ResetSuppressedNotifications`] is dispatched.
+    pub fn suppress_notification(&mut self, namespace: &str, cx: &mut ViewContext<Self>) {
+        if !self.suppressed_notifications.insert(namespace.to_string()) {
+            return;
+        }
+        self.dismiss_notification(&NotificationId::named(namespace.to_string().into()), cx);
+        if let Some(workspace_id) = self.database_id() {
+            cx.background_executor()
+                .spawn(DB.suppress_notification(workspace_id, namespace.to_string()))
+                .detach_and_log_err(cx);
+        }
+    }
+
+    pub fn is_notification_suppressed(&self, namespace: &str) -> bool {
+        self.suppressed_notifications.contains(namespace)
+    }
+
+    /// Shows a notification unless its namespace is suppressed, with a "Don't show again"
+    /// button suppressing it in this workspace. The button replaces the secondary one of the
+    /// built notification, if any.
+    pub fn show_suppressible_notification(
+        &mut self,
+        namespace: &str,
+        cx: &mut ViewContext<Self>,
+        build_notification: impl FnOnce(&mut ViewContext<Self>) -> MessageNotification,
+    ) {
+        if self.is_notification_suppressed(namespace) {
+            return;
+        }
+        let workspace = cx.view().downgrade();
+        let notification = build_notification(cx);
+        let id = NotificationId::named(namespace.to_string().into());
+        let namespace = namespace.to_string();
+        self.show_notification(id, cx, |cx| {
+            cx.new_view(|_| {
+                notification
+                    .with_secondary_click_message("Don't Show Again")
+                    .on_secondary_click(move |cx| {
+                        workspace
+                            .update(cx, |workspace, cx| {
+                                workspace.suppress_notification(&namespace, cx)
+                            })
+                            .ok();
+                    })
+            })
+        });
+    }
+
+    pub(crate) fn reset_suppressed_notifications(
+        &mut self,
+        _: &ResetSuppressedNotifications,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.suppressed_notifications.clear();
+        if let Some(workspace_id) = self.database_id() {
+            cx.background_executor()
+                .spawn(DB.reset_suppressed_notifications(workspace_id))
+                .detach_and_log_err(cx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::init_test, AppState};
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_suppressed_notifications(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let app_state = cx.update(AppState::test);
+        let workspace_id = DB.next_id().await.unwrap();
+        let (workspace, cx) = cx.add_window_view(|cx| {
+            Workspace::new(Some(workspace_id), project.clone(), app_state.clone(), cx)
+        });
+        let show = |cx: &mut gpui::VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                workspace.show_suppressible_notification("test", cx, |_| {
+                    MessageNotification::new("Something happened")
+                });
+                workspace.notification_ids().len()
+            })
+        };
+
+        // Clicking "Don't Show Again" dismisses the notification and suppresses it.
+        assert_eq!(show(cx), 1);
+        let notification = workspace.update(cx, |workspace, _| {
+            workspace.notifications[0]
+                .1
+                .to_any()
+                .downcast::<MessageNotification>()
+                .unwrap()
+        });
+        notification.update(cx, |notification, cx| {
+            notification.simulate_secondary_click(cx)
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, _| {
+            assert!(workspace.is_notification_suppressed("test"));
+            assert!(!workspace.is_notification_suppressed("other"));
+        });
+        assert_eq!(show(cx), 0);
+
+        // The suppression is kept after a restart, until it's reset.
+        let restored_workspace =
+            cx.new_view(|cx| Workspace::new(Some(workspace_id), project, app_state, cx));
+        cx.run_until_parked();
+        restored_workspace.update(cx, |workspace, cx| {
+            assert!(workspace.is_notification_suppressed("test"));
+            workspace.reset_suppressed_notifications(&ResetSuppressedNotifications, cx);
+            assert!(!workspace.is_notification_suppressed("test"));
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            DB.suppressed_notifications(workspace_id).unwrap(),
+            Vec::<String>::new()
+        );
+    }
+}
@@ -0,0 +1,292 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context as _, Result};
+use gpui::{Task, View, ViewContext};
+
+use crate::{
+    item::ItemHandle, notifications::NotificationId, pane::Pane, DraggedTab, ItemId,
+    SerializableItemRegistry, SplitDirection, Toast, Workspace, WorkspaceId,
+};
+
+struct TabHandoffFailed;
+
+/// How a tab dragged into another window gets recreated there.
+enum TabHandoffPayload {
+    /// Reopened from its path. Unsaved changes carry over through the project's buffer.
+    Path(PathBuf),
+    /// Deserialized from the state the source window serialized for it.
+    Serialized {
+        kind: &'static str,
+        workspace_id: WorkspaceId,
+        item_id: ItemId,
+    },
+}
+
+/// What the window a tab is dragged out of hands over to the window it is dropped into.
+struct TabHandoff {
+    payload: TabHandoffPayload,
+    serialize_task: Option<Task<Result<()>>>,
+    is_pinned: bool,
+    is_preview: bool,
+}
+
+impl Workspace {
+    /// Moves a tab dragged from another window into `pane`, closing it in its original
+    /// window once it has been recreated here. Tabs that can't be recreated without losing
+    /// changes stay where they are, and a toast explains why.
+    pub(crate) fn receive_dragged_tab(
+        &mut self,
+        dragged_tab: &DraggedTab,
+        mut pane: View<Pane>,
+        ix: usize,
+        split_direction: Option<SplitDirection>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let project = self.project.clone();
+        let source_pane = dragged_tab.pane.clone();
+        let item_id = dragged_tab.item.item_id();
+        let Some(source_window) = dragged_tab.source_window.downcast::<Workspace>() else {
+            return;
+        };
+        let handoff = source_window
+            .update(cx, |source, cx| {
+                let same_project = source.project == project;
+                source.hand_off_tab(&source_pane, dragged_tab.item.as_ref(), same_project, cx)
+            })
+            .and_then(|handoff| handoff);
+        let handoff = match handoff {
+            Ok(handoff) => handoff,
+            Err(error) => {
+                self.show_tab_handoff_error(error, cx);
+                return;
+            }
+        };
+        if let Some(split_direction) = split_direction {
+            pane = self.split_pane(pane, split_direction, cx);
+        }
+
+        cx.spawn(|this, mut cx| async move {
+            let result = async {
+                if let Some(serialize_task) = handoff.serialize_task {
+                    serialize_task.await?;
+                }
+                let item = match handoff.payload {
+                    TabHandoffPayload::Path(abs_path) => {
+                        let (_, project_path) = this
+                            .update(&mut cx, |_, cx| {
+                                Workspace::project_path_for_path(project, &abs_path, false, cx)
+                            })?
+                            .await?;
+                        let (project_entry_id, build_item) = this
+                            .update(&mut cx, |this, cx| this.load_path(project_path, cx))?
+                            .await?;
+                        pane.update(&mut cx, |pane, cx| {
+                            pane.open_item(
+                                project_entry_id,
+                                true,
+                                handoff.is_preview,
+                                Some(ix),
+                                cx,
+                                build_item,
+                            )
+                        })?
+                    }
+                    TabHandoffPayload::Serialized {
+                        kind,
+                        workspace_id,
+                        item_id,
+                    } => {
+                        let item = pane
+                            .update(&mut cx, |_, cx| {
+                                SerializableItemRegistry::deserialize(
+                                    kind,
+                                    project,
+                                    this.clone(),
+                                    workspace_id,
+                                    item_id,
+                                    cx,
+                                )
+                            })?
+                            .await?;
+                        pane.update(&mut cx, |pane, cx| {
+                            pane.add_item(item.boxed_clone(), true, true, Some(ix), cx);
+                            if handoff.is_preview {
+                                pane.set_preview_item_id(Some(item.item_id()), cx);
+                            }
+                        })?;
+                        item
+                    }
+                };
+                if handoff.is_pinned {
+                    pane.update(&mut cx, |pane, cx| {
+                        if let Some(ix) = pane.index_for_item(item.as_ref()) {
+                            pane.pin_tab_at(ix, cx);
+                        }
+                    })?;
+                }
+                // The content lives on in the new item, so there is nothing to prompt about.
+                source_window.update(&mut cx, |_, cx| {
+                    source_pane.update(cx, |pane, cx| pane.remove_item(item_id, false, true, cx))
+                })
+            }
+            .await;
+            if let Err(error) = result {
+                this.update(&mut cx, |this, cx| this.show_tab_handoff_error(error, cx))
+                    .ok();
+            }
+        })
+        .detach();
+    }
+
+    /// Describes how to recreate `item` in another window, or fails if that would lose changes.
+    fn hand_off_tab(
+        &mut self,
+        pane: &View<Pane>,
+        item: &dyn ItemHandle,
+        same_project: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Result<TabHandoff> {
+        let (is_pinned, is_preview) = {
+            let pane = pane.read(cx);
+            let ix = pane
+                .index_for_item(item)
+                .context("the tab was closed while being dragged")?;
+            (
+                ix < pane.pinned_count(),
+                pane.preview_item_id() == Some(item.item_id()),
+            )
+        };
+        let is_dirty = item.is_dirty(cx);
+        let abs_path = item
+            .project_path(cx)
+            .and_then(|project_path| self.project.read(cx).absolute_path(&project_path, cx));
+        if is_dirty && abs_path.is_none() {
+            bail!("Save untitled tabs before moving them to another window");
+        }
+
+        let serializable = item.to_serializable_item_handle(cx).zip(self.database_id());
+        let (payload, serialize_task) = match (abs_path, serializable) {
+            (Some(abs_path), _) if same_project || !is_dirty => {
+                (TabHandoffPayload::Path(abs_path), None)
+            }
+            (_, Some((serializable, workspace_id))) => {
+                let serialize_task = serializable.serialize(self, true, cx);
+                let payload = TabHandoffPayload::Serialized {
+                    kind: serializable.serialized_item_kind(),
+                    workspace_id,
+                    item_id: serializable.item_id().as_u64(),
+                };
+                (payload, serialize_task)
+            }
+            (Some(_), None) => {
+                bail!("Save this tab before moving it to a window of another project")
+            }
+            (None, None) => bail!("This tab can't be moved to another window"),
+        };
+        Ok(TabHandoff {
+            payload,
+            serialize_task,
+            is_pinned,
+            is_preview,
+        })
+    }
+
+    fn show_tab_handoff_error(&mut self, error: anyhow::Error, cx: &mut ViewContext<Self>) {
+        self.show_toast(
+            Toast::new(
+                NotificationId::unique::<TabHandoffFailed>(),
+                error.to_string(),
+            )
+            .autohide(),
+            cx,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{item::test::TestItem, register_serializable_item, tests::init_test};
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+
+    #[gpui::test]
+    async fn test_receive_dragged_tab(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(register_serializable_item::<TestItem>);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let source = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let target = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+
+        let (untitled, serializable) = source
+            .update(cx, |workspace, cx| {
+                workspace.database_id = Some(WorkspaceId(1));
+                let untitled = cx.new_view(|cx| TestItem::new(cx).with_dirty(true));
+                let serializable = cx
+                    .new_view(|cx| TestItem::new(cx).with_serialize(|| Some(Task::ready(Ok(())))));
+                workspace.add_item_to_active_pane(Box::new(untitled.clone()), None, true, cx);
+                workspace.add_item_to_active_pane(Box::new(serializable.clone()), None, true, cx);
+                workspace
+                    .active_pane()
+                    .update(cx, |pane, cx| pane.pin_tab_at(1, cx));
+                (untitled, serializable)
+            })
+            .unwrap();
+        cx.run_until_parked();
+        let drag = |item: &View<TestItem>, cx: &mut TestAppContext| {
+            source
+                .update(cx, |workspace, cx| DraggedTab {
+                    pane: workspace.active_pane().clone(),
+                    source_window: cx.window_handle(),
+                    item: Box::new(item.clone()),
+                    ix: 0,
+                    detail: 0,
+                    is_active: true,
+                })
+                .unwrap()
+        };
+
+        // Dirty untitled tabs stay in their window.
+        let dragged_tab = drag(&untitled, cx);
+        target
+            .update(cx, |workspace, cx| {
+                let pane = workspace.active_pane().clone();
+                workspace.receive_dragged_tab(&dragged_tab, pane, 0, None, cx);
+                assert_eq!(
+                    workspace.notification_ids(),
+                    vec![NotificationId::unique::<TabHandoffFailed>()]
+                );
+            })
+            .unwrap();
+
+        // Other tabs are recreated in the target window and closed in the source one.
+        let dragged_tab = drag(&serializable, cx);
+        target
+            .update(cx, |workspace, cx| {
+                let pane = workspace.active_pane().clone();
+                workspace.receive_dragged_tab(&dragged_tab, pane, 0, None, cx);
+            })
+            .unwrap();
+        cx.run_until_parked();
+
+        source
+            .update(cx, |workspace, cx| {
+                let pane = workspace.active_pane().read(cx);
+                assert_eq!(pane.items_len(), 1);
+                assert!(pane.index_for_item(&untitled).is_some());
+            })
+            .unwrap();
+        target
+            .update(cx, |workspace, cx| {
+                let pane = workspace.active_pane().read(cx);
+                assert_eq!(pane.items_len(), 1);
+                assert_eq!(pane.pinned_count(), 1);
+                let item = pane.items().next().unwrap().downcast::<TestItem>().unwrap();
+                assert_eq!(item.read(cx).workspace_id, Some(WorkspaceId(1)));
+            })
+            .unwrap();
+    }
+}
@@ -1,30 +1,109 @@
+mod activation_history;
+mod adopt_items;
+mod alternate_item;
+mod bookmarks;
+mod bundled_file;
+mod carry_untitled;
+mod close_item;
+mod debug_state;
+mod deferred_actions;
+mod diff_item;
 pub mod dock;
+mod dock_auto_hide;
+mod duplicate_window;
+mod external_update;
+mod focus_follows_mouse;
+mod folder_open;
+mod follow_all;
+mod follow_diagnostics;
+mod follow_window;
+mod follower_updates;
+mod followers;
+mod fullscreen;
 pub mod item;
+mod item_activation;
+mod item_serialization;
+mod keep_running;
+mod layout_checkpoints;
+mod mirror_pane;
 mod modal_layer;
+mod new_window;
+mod notification_focus;
 pub mod notifications;
+mod open_error;
+mod open_queue;
 pub mod pane;
+mod pane_addressing;
+mod pane_flex;
 pub mod pane_group;
+mod pane_lock;
+mod pane_reservation;
+mod pane_saving;
 mod persistence;
+mod pinned_item;
+mod project_panel_reveal;
+mod project_switch;
+mod recovery;
+mod reload_items;
+mod rename_item;
+mod running_tasks;
+mod save_hooks;
+mod scm_status;
+mod scratch;
+mod screenshot;
 pub mod searchable;
+mod send_keystrokes;
+mod serialization_flush;
+mod session_notes;
 pub mod shared_screen;
+mod split_view;
+mod ssh_reconnect;
+mod ssh_sessions;
+mod stable_item_id;
 mod status_bar;
+mod suppressed_notifications;
+mod tab_handoff;
 pub mod tasks;
 mod theme_preview;
 mod toolbar;
+mod workspace_counts;
+mod workspace_events;
 mod workspace_settings;
+mod worktree_creation;
+mod worktree_order;
+mod worktree_scan;
+mod zoom_coverage;
 
 use anyhow::{anyhow, Context as _, Result};
+pub use bookmarks::{ItemBookmark, MAX_ITEM_BOOKMARKS};
 use call::{call_settings::CallSettings, ActiveCall};
+pub use carry_untitled::{register_untitled_item_builder, UntitledItemBuilder};
 use client::{
     proto::{self, ErrorCode, PanelId, PeerId},
     ChannelId, Client, ErrorExt, Status, TypedEnvelope, UserStore,
 };
+pub use close_item::request_item_close;
 use collections::{hash_map, HashMap, HashSet};
+pub use debug_state::{
+    AxisDebugState, BoundsDebugState, DockDebugState, FollowerDebugState, ItemDebugState,
+    PaneDebugState, PaneGroupDebugState, ViewIdDebugState, WorkspaceDebugState, ZoomDebugState,
+};
+pub use deferred_actions::defer_until_loaded;
+use deferred_actions::WorkspaceLoading;
 use derive_more::{Deref, DerefMut};
+pub use diff_item::{register_diff_item, DiffItem};
 use dock::{Dock, DockPosition, Panel, PanelButtons, PanelHandle, RESIZE_HANDLE_SIZE};
+use dock_auto_hide::DockAutoHide;
+use external_update::ExternalUpdateLocks;
+pub use external_update::{lock_path_for_external_update, ExternalUpdateGuard};
+use focus_follows_mouse::FocusFollowsMouseTarget;
+use follow_all::FollowAll;
+pub use follow_diagnostics::{FollowDiag, STALLED_FOLLOWING_THRESHOLD};
+use follower_updates::FollowerUpdateQueue;
+use fullscreen::FullscreenState;
 use futures::{
     channel::{
-        mpsc::{self, UnboundedReceiver, UnboundedSender},
+        mpsc::{self, UnboundedSender},
         oneshot,
     },
     future::try_join_all,
@@ -32,29 +111,39 @@ use futures::{
 };
 use gpui::{
     action_as, actions, canvas, impl_action_as, impl_actions, point, relative, size,
-    transparent_black, Action, AnyView, AnyWeakView, AppContext, AsyncAppContext,
+    transparent_black, Action, AnyView, AnyWeakView, AnyWindowHandle, AppContext, AsyncAppContext,
     AsyncWindowContext, Bounds, CursorStyle, Decorations, DragMoveEvent, Entity as _, EntityId,
-    EventEmitter, Flatten, FocusHandle, FocusableView, Global, Hsla, KeyContext, Keystroke,
-    ManagedView, Model, ModelContext, MouseButton, PathPromptOptions, Point, PromptLevel, Render,
-    ResizeEdge, Size, Stateful, Subscription, Task, Tiling, View, WeakView, WindowBounds,
-    WindowHandle, WindowId, WindowOptions,
+    EventEmitter, Flatten, FocusHandle, FocusableView, Global, Hsla, KeyContext, ManagedView,
+    Model, ModelContext, MouseButton, PathPromptOptions, Point, PromptLevel, Render, ResizeEdge,
+    Size, Stateful, Subscription, Task, Tiling, View, WeakView, WindowBounds, WindowHandle,
+    WindowId, WindowOptions,
 };
 pub use item::{
-    FollowableItem, FollowableItemHandle, Item, ItemHandle, ItemSettings, PreviewTabsSettings,
-    ProjectItem, SerializableItem, SerializableItemHandle, WeakItemHandle,
+    ActivityKind, FollowableItem, FollowableItemHandle, Item, ItemHandle, ItemSettings,
+    PreviewTabsSettings, ProjectItem, SerializableItem, SerializableItemHandle, WeakItemHandle,
 };
+use item_activation::ItemActivationOrigin;
+pub use item_serialization::ITEM_SERIALIZATION_BATCH_SIZE;
 use itertools::Itertools;
 use language::{LanguageRegistry, Rope};
 pub use modal_layer::*;
 use node_runtime::NodeRuntime;
+use notification_focus::NotificationFocus;
 use notifications::{
-    simple_message_notification::MessageNotification, DetachAndPromptErr, NotificationHandle,
+    simple_message_notification::MessageNotification, DetachAndPromptErr, ErrorNotification,
+    NotificationHandle,
+};
+pub use open_error::{
+    register_open_handler_hint_resolver, register_plain_text_project_item, DetectedFileKind,
+    OpenError, OpenHandlerHintResolver,
 };
+pub use open_queue::queue_open_request;
 pub use pane::*;
 pub use pane_group::*;
+pub use pane_reservation::ReservationKind;
 pub use persistence::{
     model::{ItemId, LocalPaths, SerializedWorkspaceLocation},
-    WorkspaceDb, DB as WORKSPACE_DB,
+    LayoutCheckpoint, RecentWorkspace, WorkspaceDb, DB as WORKSPACE_DB,
 };
 use persistence::{
     model::{SerializedSshProject, SerializedWorkspace},
@@ -64,29 +153,44 @@ use postage::stream::Stream;
 use project::{
     DirectoryLister, Project, ProjectEntryId, ProjectPath, ResolvedPath, Worktree, WorktreeId,
 };
+use project_panel_reveal::ProjectPanelReveal;
+pub use project_panel_reveal::RevealHandler;
+use recovery::RecoverySnapshots;
+pub use reload_items::ReloadSummary;
 use remote::{ssh_session::ConnectionIdentifier, SshClientDelegate, SshConnectionOptions};
+pub use rename_item::{register_rename_item_modal, RenameItemModalOpener};
+pub use running_tasks::RunningTask;
+use save_hooks::BeforeSaveHooks;
+pub use save_hooks::SaveVeto;
+use scm_status::ScmStatuses;
+pub use scm_status::{ScmStatus, ScmStatusProvider};
+pub use scratch::{register_scratch_item_builder, ScratchItemBuilder};
+pub use screenshot::ScreenshotRegion;
 use serde::Deserialize;
 use session::AppSession;
+pub use session_notes::MAX_SESSION_NOTES_CHARS;
 use settings::Settings;
 use shared_screen::SharedScreen;
+pub use split_view::{register_split_view, SplitViewFactory, PROJECT_SEARCH_VIEW_KIND};
 use sqlez::{
     bindable::{Bind, Column, StaticColumnCount},
     statement::Statement,
 };
+pub use ssh_reconnect::{register_ssh_connector, SshConnector};
+pub use stable_item_id::StableItemId;
 use status_bar::StatusBar;
-pub use status_bar::StatusItemView;
+pub use status_bar::{StatusItemHandle, StatusItemPosition, StatusItemView};
 use std::{
-    any::TypeId,
+    any::{Any, TypeId},
     borrow::Cow,
-    cell::RefCell,
+    cell::Cell,
     cmp,
     collections::hash_map::DefaultHasher,
     env,
     hash::{Hash, Hasher},
     path::{Path, PathBuf},
-    rc::Rc,
     sync::{atomic::AtomicUsize, Arc, LazyLock, Weak},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use task::SpawnInTerminal;
 use theme::{ActiveTheme, SystemAppearance, ThemeSettings};
@@ -99,13 +203,22 @@ use ui::{
 };
 use util::{paths::SanitizedPath, ResultExt, TryFutureExt};
 use uuid::Uuid;
+pub use workspace_counts::{FileCountsIndicator, WorkspaceCounts};
+use workspace_events::EventBatch;
+pub use workspace_events::WorkspaceEventSubscriber;
 pub use workspace_settings::{
-    AutosaveSetting, RestoreOnStartupBehavior, TabBarSettings, WorkspaceSettings,
+    AlternateItemScope, AutosaveSetting, DropTargetPlacement, FolderOpenBehavior,
+    NewWindowBehavior, OpenBesidePinnedItem, PaneActivationOrder, RestoreOnStartupBehavior,
+    TabBarSettings, WorkspaceSettings, ZoomMode,
 };
+pub use worktree_scan::{ScanProgress, WorktreeScanProgress};
 
 use crate::notifications::NotificationId;
 use crate::persistence::{
-    model::{DockData, DockStructure, SerializedItem, SerializedPane, SerializedPaneGroup},
+    model::{
+        DockData, DockStructure, SerializedItem, SerializedPane, SerializedPaneGroup,
+        MAX_CONCURRENT_ITEM_LOADS,
+    },
     SerializedAxis,
 };
 
@@ -123,6 +236,14 @@ static ZED_WINDOW_POSITION: LazyLock<Option<Point<Pixels>>> = LazyLock::new(|| {
         .and_then(parse_pixel_position_env_var)
 });
 
+/// How long to keep waiting for the entry of a file opened with [`Workspace::open_path_when_ready`]
+/// after its worktree finished scanning, e.g. for file system events that are still in flight.
+const ENTRY_RESOLUTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The minimum delay between two window title updates caused by the active item changing its
+/// title.
+const WINDOW_TITLE_UPDATE_INTERVAL: Duration = Duration::from_millis(250);
+
 #[derive(Clone, PartialEq)]
 pub struct RemoveWorktreeFromProject(pub WorktreeId);
 
@@ -131,16 +252,35 @@ actions!(assistant, [ShowConfiguration]);
 actions!(
     workspace,
     [
+        ActivateAlternateItem,
+        ActivateNextItemGlobal,
         ActivateNextPane,
+        ActivatePreviousItemGlobal,
+        ActivateNotification,
         ActivatePreviousPane,
         AddFolderToProject,
+        CaptureActivePaneScreenshot,
         ClearAllNotifications,
         CloseAllDocks,
+        ClosePaneSavingItems,
         CloseWindow,
         CopyPath,
         CopyRelativePath,
+        CycleBookmarkedItems,
+        DismissFocusedNotification,
+        DumpWorkspaceState,
+        DuplicateWindow,
+        EqualizePanes,
         Feedback,
+        FocusNotifications,
+        FollowAllCollaborators,
+        FollowInNewWindow,
         FollowNextCollaborator,
+        FollowParticipant,
+        GrowPane,
+        MoveWindowOnScreen,
+        MoveWorktreeDown,
+        MoveWorktreeUp,
         NewCenterTerminal,
         NewFile,
         NewFileSplitVertical,
@@ -150,15 +290,32 @@ actions!(
         NewWindow,
         Open,
         OpenInTerminal,
+        PromoteActivePreviewTab,
         ReloadActiveItem,
+        RenameActiveItem,
+        ResetSuppressedNotifications,
+        RestartFollowing,
+        RestoreLayoutCheckpoint,
+        RevealActiveItem,
+        SaveAllWindows,
         SaveAs,
+        SaveItemsInPane,
         SaveWithoutFormat,
+        ShrinkPane,
+        SwitchBackToPreviousProject,
+        SwitchWindow,
         ToggleBottomDock,
         ToggleCenteredLayout,
+        ToggleItemBookmark,
         ToggleLeftDock,
+        ToggleMirrorPane,
         ToggleRightDock,
+        ToggleSessionNotes,
+        TogglePaneLock,
+        ToggleTabBarForPane,
         ToggleZoom,
         Unfollow,
+        UnfollowAll,
         Welcome,
     ]
 );
@@ -177,6 +334,13 @@ pub struct ActivatePaneInDirection(pub SplitDirection);
 #[derive(Clone, Deserialize, PartialEq)]
 pub struct SwapPaneInDirection(pub SplitDirection);
 
+/// Opens a new project search in the pane next to the active one, splitting it if needed.
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct NewSearchInSplit(pub SplitDirection);
+
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct SwapPaneWithIndex(pub usize);
+
 #[derive(Clone, PartialEq, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SaveAll {
@@ -201,9 +365,35 @@ pub struct CloseInactiveTabsAndPanes {
     pub save_intent: Option<SaveIntent>,
 }
 
+/// Closes the items of all panes whose path matches `glob`, e.g. `src/legacy/**`.
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloseItemsMatching {
+    pub glob: String,
+    pub save_intent: Option<SaveIntent>,
+}
+
+/// Reloads the items of all panes whose files changed on disk.
+#[derive(Clone, PartialEq, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadAllItems {
+    /// Whether to reload the items with unsaved changes too, once the user confirms it.
+    #[serde(default)]
+    pub ignore_dirty: bool,
+}
+
 #[derive(Clone, Deserialize, PartialEq)]
 pub struct SendKeystrokes(pub String);
 
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDockOpen {
+    pub position: DockPosition,
+    /// Whether to open or close the dock. Toggles it when omitted.
+    #[serde(default)]
+    pub open: Option<bool>,
+}
+
 #[derive(Clone, Deserialize, PartialEq, Default)]
 pub struct Reload {
     pub binary_path: Option<PathBuf>,
@@ -226,12 +416,17 @@ impl_actions!(
         ActivatePaneInDirection,
         CloseAllItemsAndPanes,
         CloseInactiveTabsAndPanes,
+        CloseItemsMatching,
+        NewSearchInSplit,
         OpenTerminal,
         Reload,
+        ReloadAllItems,
         Save,
         SaveAll,
         SwapPaneInDirection,
+        SwapPaneWithIndex,
         SendKeystrokes,
+        SetDockOpen,
     ]
 );
 
@@ -328,47 +523,43 @@ pub fn init(app_state: Arc<AppState>, cx: &mut AppContext) {
     cx.on_action(Workspace::close_global);
     cx.on_action(reload);
 
-    cx.on_action({
-        let app_state = Arc::downgrade(&app_state);
-        move |_: &Open, cx: &mut AppContext| {
-            let paths = cx.prompt_for_paths(PathPromptOptions {
-                files: true,
-                directories: true,
-                multiple: true,
-            });
+    cx.on_action(|_: &Open, cx: &mut AppContext| {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: true,
+            multiple: true,
+            ..Default::default()
+        });
 
-            if let Some(app_state) = app_state.upgrade() {
-                cx.spawn(move |cx| async move {
-                    match Flatten::flatten(paths.await.map_err(|e| e.into())) {
-                        Ok(Some(paths)) => {
-                            cx.update(|cx| {
-                                open_paths(&paths, app_state, OpenOptions::default(), cx)
-                                    .detach_and_log_err(cx)
-                            })
-                            .ok();
-                        }
-                        Ok(None) => {}
-                        Err(err) => {
-                            cx.update(|cx| {
-                                if let Some(workspace_window) = cx
-                                    .active_window()
-                                    .and_then(|window| window.downcast::<Workspace>())
-                                {
-                                    workspace_window
-                                        .update(cx, |workspace, cx| {
-                                            workspace.show_portal_error(err.to_string(), cx);
-                                        })
-                                        .ok();
-                                }
-                            })
-                            .ok();
+        cx.spawn(move |cx| async move {
+            match Flatten::flatten(paths.await.map_err(|e| e.into())) {
+                Ok(Some(paths)) => {
+                    cx.update(|cx| queue_open_request(paths, OpenOptions::default(), cx))
+                        .ok();
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    cx.update(|cx| {
+                        if let Some(workspace_window) = cx
+                            .active_window()
+                            .and_then(|window| window.downcast::<Workspace>())
+                        {
+                            workspace_window
+                                .update(cx, |workspace, cx| {
+                                    workspace.show_portal_error(err.to_string(), cx);
+                                })
+                                .ok();
                         }
-                    };
-                })
-                .detach();
-            }
-        }
+                    })
+                    .ok();
+                }
+            };
+        })
+        .detach();
     });
+
+    keep_running::init(&app_state, cx);
+    open_queue::init(&app_state, cx);
 }
 
 #[derive(Clone, Default, Deref, DerefMut)]
@@ -390,7 +581,11 @@ impl Global for ProjectItemOpeners {}
 /// was added last.
 pub fn register_project_item<I: ProjectItem>(cx: &mut AppContext) {
     let builders = cx.default_global::<ProjectItemOpeners>();
-    builders.push(|project, project_path, cx| {
+    builders.push(project_item_opener::<I>());
+}
+
+fn project_item_opener<I: ProjectItem>() -> ProjectItemOpener {
+    |project, project_path, cx| {
         let project_item = <I::Item as project::ProjectItem>::try_open(project, project_path, cx)?;
         let project = project.clone();
         Some(cx.spawn(|cx| async move {
@@ -403,7 +598,7 @@ pub fn register_project_item<I: ProjectItem>(cx: &mut AppContext) {
             }) as Box<_>;
             Ok((project_entry_id, build_workspace_item))
         }))
-    });
+    }
 }
 
 #[derive(Default)]
@@ -461,6 +656,35 @@ impl FollowableViewRegistry {
     }
 }
 
+/// Compares two navigation entries' data of the same type.
+type NavigationDataEq = fn(&dyn Any, &dyn Any) -> bool;
+
+#[derive(Default)]
+pub(crate) struct NavigationDataRegistry(HashMap<TypeId, NavigationDataEq>);
+
+impl Global for NavigationDataRegistry {}
+
+impl NavigationDataRegistry {
+    pub(crate) fn eq<D: 'static>(a: &D, b: &D, cx: &AppContext) -> bool {
+        cx.try_global::<Self>()
+            .and_then(|this| this.0.get(&TypeId::of::<D>()))
+            .map_or(false, |eq| eq(a, b))
+    }
+}
+
+/// Registers the type of data an item pushes onto its navigation history, so that
+/// consecutive entries pointing at the same location are only recorded once.
+pub fn register_navigation_data<D: PartialEq + 'static>(cx: &mut AppContext) {
+    cx.default_global::<NavigationDataRegistry>()
+        .0
+        .insert(TypeId::of::<D>(), |a, b| {
+            match (a.downcast_ref::<D>(), b.downcast_ref::<D>()) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            }
+        });
+}
+
 #[derive(Copy, Clone)]
 struct SerializableItemDescriptor {
     deserialize: fn(
@@ -571,10 +795,13 @@ impl Global for GlobalAppState {}
 pub struct WorkspaceStore {
     workspaces: HashSet<WindowHandle<Workspace>>,
     client: Arc<Client>,
+    follower_updates: FollowerUpdateQueue,
+    /// The collaborators following the local user, sorted.
+    followers: Vec<Follower>,
     _subscriptions: Vec<client::Subscription>,
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 struct Follower {
     project_id: Option<u64>,
     peer_id: PeerId,
@@ -688,16 +915,49 @@ pub enum Event {
         pane: WeakView<Pane>,
         item: Box<dyn WeakItemHandle>,
         save_intent: SaveIntent,
+        /// Where the item was saved to.
+        project_path: Option<ProjectPath>,
     },
     ContactRequestedJoin(u64),
     WorkspaceCreated(WeakView<Workspace>),
     SpawnTask(Box<SpawnInTerminal>),
+    /// A task spawned with [`Event::SpawnTask`] finished, see [`Workspace::task_completed`].
+    TaskCompleted {
+        label: String,
+        success: bool,
+    },
+    /// Emitted when [`Workspace::open_bundled_file`] opens a file bundled with the app.
     OpenBundledFile {
         text: Cow<'static, str>,
         title: &'static str,
         language: &'static str,
     },
-    ZoomChanged,
+    ZoomChanged(Option<ZoomState>),
+    /// The window entered or left fullscreen, see [`Workspace::set_fullscreen`].
+    FullscreenChanged(bool),
+    CountsChanged(WorkspaceCounts),
+    /// The worktree entry of an item opened with [`Workspace::open_path_when_ready`] was found.
+    ItemEntryResolved {
+        item: Box<dyn ItemHandle>,
+        entry_id: ProjectEntryId,
+    },
+    /// The [`Workspace::scm_status`] of these paths changed.
+    ScmStatusesChanged(Vec<ProjectPath>),
+    /// Someone started or stopped following the local user, see [`Workspace::followers`].
+    FollowersChanged,
+    /// Events emitted together, e.g. the items added while loading a workspace. See
+    /// [`Event::unbatched`] and [`WorkspaceEventSubscriber`].
+    Batched(Vec<Event>),
+}
+
+/// What is zoomed in a workspace, see [`Workspace::zoom_state`].
+#[derive(Clone)]
+pub struct ZoomState {
+    pub view: AnyWeakView,
+    /// The dock the zoomed panel belongs to, or `None` for a center pane.
+    pub position: Option<DockPosition>,
+    /// Where the zoomed view was last rendered, if it has been rendered yet.
+    pub bounds: Option<Bounds<Pixels>>,
 }
 
 #[derive(Debug)]
@@ -708,6 +968,18 @@ pub enum OpenVisible {
     OnlyDirectories,
 }
 
+/// Determines which pane each file opened by [`Workspace::open_paths_with_pane_placement`] goes to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PanePlacement {
+    /// Open every file in the given pane, or in the active one.
+    ActivePane,
+    /// Distribute the files round-robin across the existing center panes,
+    /// starting from the given pane.
+    SpreadAcrossExistingPanes,
+    /// Open the first file in the given pane and split a new pane for each subsequent file.
+    SplitPerFile(SplitDirection),
+}
+
 type PromptForNewPath = Box<
     dyn Fn(&mut Workspace, &mut ViewContext<Workspace>) -> oneshot::Receiver<Option<ProjectPath>>,
 >;
@@ -716,6 +988,7 @@ type PromptForOpenPath = Box<
     dyn Fn(
         &mut Workspace,
         DirectoryLister,
+        PathPromptOptions,
         &mut ViewContext<Workspace>,
     ) -> oneshot::Receiver<Option<Vec<PathBuf>>>,
 >;
@@ -731,6 +1004,8 @@ pub struct Workspace {
     workspace_actions: Vec<Box<dyn Fn(Div, &mut ViewContext<Self>) -> Div>>,
     zoomed: Option<AnyWeakView>,
     zoomed_position: Option<DockPosition>,
+    /// The bounds of the zoomed view, along with the zoom position they were measured for.
+    zoomed_bounds: Option<(Option<DockPosition>, Bounds<Pixels>)>,
     center: PaneGroup,
     left_dock: View<Dock>,
     bottom_dock: View<Dock>,
@@ -739,34 +1014,79 @@ pub struct Workspace {
     panes_by_item: HashMap<EntityId, WeakView<Pane>>,
     active_pane: View<Pane>,
     last_active_center_pane: Option<WeakView<Pane>>,
+    last_active_unreserved_pane: Option<WeakView<Pane>>,
+    pane_reservations: HashMap<WeakView<Pane>, ReservationKind>,
+    mirror_pane: Option<mirror_pane::MirrorPane>,
     last_active_view_id: Option<proto::ViewId>,
     status_bar: View<StatusBar>,
     modal_layer: View<ModalLayer>,
     titlebar_item: Option<AnyView>,
     notifications: Vec<(NotificationId, Box<dyn NotificationHandle>)>,
+    notification_focus: NotificationFocus,
     project: Model<Project>,
     follower_states: HashMap<PeerId, FollowerState>,
     last_leaders_by_pane: HashMap<WeakView<Pane>, PeerId>,
+    follow_window_leader: Option<PeerId>,
+    last_new_follower_notification: Option<Instant>,
     window_edited: bool,
     active_call: Option<(Model<ActiveCall>, Vec<Subscription>)>,
     leader_updates_tx: mpsc::UnboundedSender<(PeerId, proto::UpdateFollowers)>,
     database_id: Option<WorkspaceId>,
     app_state: Arc<AppState>,
-    dispatching_keystrokes: Rc<RefCell<(HashSet<String>, Vec<Keystroke>)>>,
+    dragged_item_id: Option<EntityId>,
     _subscriptions: Vec<Subscription>,
     _apply_leader_updates: Task<Result<()>>,
     _observe_current_user: Task<Result<()>>,
     _schedule_serialize: Option<Task<()>>,
+    last_window_title_update: Option<Instant>,
+    pending_window_title_update: Option<Task<()>>,
+    pending_mouse_focus: Option<(FocusFollowsMouseTarget, Task<()>)>,
+    dock_auto_hide: DockAutoHide,
+    follow_all: FollowAll,
+    project_panel_reveal: Option<ProjectPanelReveal>,
+    event_batch: EventBatch,
+    reported_active_item_id: Option<EntityId>,
+    scm_statuses: ScmStatuses,
+    external_update_locks: ExternalUpdateLocks,
+    fullscreen_state: FullscreenState,
+    /// The root paths of the project this window was switched from.
+    previous_project: Option<Vec<PathBuf>>,
+    running_tasks: Vec<RunningTask>,
     pane_history_timestamp: Arc<AtomicUsize>,
+    item_activations: Vec<activation_history::ItemActivation>,
+    item_activation_cursor: Option<EntityId>,
+    alternate_items: alternate_item::AlternateItems,
+    /// The [`StableItemId`]s of the items in the center panes.
+    stable_item_ids: HashMap<EntityId, StableItemId>,
+    item_bookmarks: Vec<ItemBookmark>,
+    suppressed_notifications: HashSet<String>,
     bounds: Bounds<Pixels>,
     centered_layout: bool,
+    session_notes: Option<String>,
     bounds_save_task_queued: Option<Task<()>>,
+    /// The display the window was last on before it was opened, while that display is
+    /// disconnected and the window is on another one.
+    missing_preferred_display: Option<Uuid>,
+    before_save_hooks: BeforeSaveHooks,
+    /// Set once the workspace is released, after which it's never serialized again.
+    serialization_released: bool,
+    /// How long the last [`Workspace::load_workspace`] took to restore the panes and docks.
+    last_restore_duration: Option<Duration>,
+    loading: WorkspaceLoading,
     on_prompt_for_new_path: Option<PromptForNewPath>,
     on_prompt_for_open_path: Option<PromptForOpenPath>,
+    /// Overrides the `use_system_path_prompts` setting for this workspace.
+    use_system_path_prompts: Option<bool>,
     serializable_items_tx: UnboundedSender<Box<dyn SerializableItemHandle>>,
     serialized_ssh_project: Option<SerializedSshProject>,
     _items_serializer: Task<Result<()>>,
+    recovery_snapshots: RecoverySnapshots,
+    _recovery_writer: Task<()>,
+    last_layout_checkpoint: Option<(SerializedPaneGroup, DockStructure)>,
+    _layout_checkpoint_writer: Task<()>,
     session_id: Option<String>,
+    counts: WorkspaceCounts,
+    counts_update_scheduled: bool,
 }
 
 impl EventEmitter<Event> for Workspace {}
@@ -782,6 +1102,8 @@ pub struct FollowerState {
     dock_pane: Option<View<Pane>>,
     active_view_id: Option<ViewId>,
     items_by_leader_view_id: HashMap<ViewId, FollowerView>,
+    queued_updates: usize,
+    last_update_at: Instant,
 }
 
 struct FollowerView {
@@ -806,11 +1128,17 @@ impl Workspace {
                     this.update_window_title(cx);
                 }
 
+                project::Event::CollaboratorJoined(peer_id) => {
+                    this.offer_to_follow_joined_collaborator(*peer_id, cx);
+                }
+
                 project::Event::CollaboratorLeft(peer_id) => {
                     this.collaborator_left(*peer_id, cx);
                 }
 
-                project::Event::WorktreeRemoved(_) | project::Event::WorktreeAdded(_) => {
+                project::Event::WorktreeRemoved(_)
+                | project::Event::WorktreeAdded(_)
+                | project::Event::WorktreeOrderChanged => {
                     this.update_window_title(cx);
                     this.serialize_workspace(cx);
                 }
@@ -826,6 +1154,7 @@ impl Workspace {
 
                 project::Event::DisconnectedFromSshRemote => {
                     this.update_window_edited(cx);
+                    this.show_ssh_disconnected_notification(cx);
                 }
 
                 project::Event::Closed => {
@@ -843,14 +1172,24 @@ impl Workspace {
                 project::Event::Toast {
                     notification_id,
                     message,
-                } => this.show_notification(
-                    NotificationId::named(notification_id.clone()),
-                    cx,
-                    |cx| cx.new_view(|_| MessageNotification::new(message.clone())),
-                ),
+                } => {
+                    struct ProjectToast;
+
+                    let mut hasher = DefaultHasher::new();
+                    notification_id.hash(&mut hasher);
+                    message.hash(&mut hasher);
+                    let id = hasher.finish();
+
+                    this.show_notification_in_group(
+                        notification_id.as_ref(),
+                        NotificationId::composite::<ProjectToast>(id as usize),
+                        cx,
+                        |cx| cx.new_view(|_| MessageNotification::new(message.clone())),
+                    )
+                }
 
                 project::Event::HideToast { notification_id } => {
-                    this.dismiss_notification(&NotificationId::named(notification_id.clone()), cx)
+                    this.dismiss_notification_group(notification_id, cx)
                 }
 
                 project::Event::LanguageServerPrompt(request) => {
@@ -858,9 +1197,11 @@ impl Workspace {
 
                     let mut hasher = DefaultHasher::new();
                     request.lsp_name.as_str().hash(&mut hasher);
+                    request.message.hash(&mut hasher);
                     let id = hasher.finish();
 
-                    this.show_notification(
+                    this.show_notification_in_group(
+                        &request.lsp_name,
                         NotificationId::composite::<LanguageServerPrompt>(id as usize),
                         cx,
                         |cx| {
@@ -878,6 +1219,9 @@ impl Workspace {
         .detach();
 
         cx.on_focus_lost(|this, cx| {
+            if this.refocus_notifications(cx) {
+                return;
+            }
             let focus_handle = this.focus_handle(cx);
             cx.focus(&focus_handle);
         })
@@ -922,21 +1266,11 @@ impl Workspace {
             anyhow::Ok(())
         });
 
-        // All leader updates are enqueued and then processed in a single task, so
-        // that each asynchronous operation can be run in order.
-        let (leader_updates_tx, mut leader_updates_rx) =
-            mpsc::unbounded::<(PeerId, proto::UpdateFollowers)>();
-        let _apply_leader_updates = cx.spawn(|this, mut cx| async move {
-            while let Some((leader_id, update)) = leader_updates_rx.next().await {
-                Self::process_leader_update(&this, leader_id, update, &mut cx)
-                    .await
-                    .log_err();
-            }
-
-            Ok(())
-        });
+        let (leader_updates_tx, _apply_leader_updates) = Self::apply_leader_updates(cx);
+        let external_update_locks = ExternalUpdateLocks::new(cx);
 
         cx.emit(Event::WorkspaceCreated(weak_handle.clone()));
+        cx.defer(Self::schedule_counts_update);
 
         let left_dock = Dock::new(DockPosition::Left, cx);
         let bottom_dock = Dock::new(DockPosition::Bottom, cx);
@@ -944,11 +1278,19 @@ impl Workspace {
         let left_dock_buttons = cx.new_view(|cx| PanelButtons::new(left_dock.clone(), cx));
         let bottom_dock_buttons = cx.new_view(|cx| PanelButtons::new(bottom_dock.clone(), cx));
         let right_dock_buttons = cx.new_view(|cx| PanelButtons::new(right_dock.clone(), cx));
+        let file_counts = WorkspaceSettings::get_global(cx).show_file_counts.then(|| {
+            let workspace = cx.view().clone();
+            cx.new_view(|cx| FileCountsIndicator::new(&workspace, cx))
+        });
         let status_bar = cx.new_view(|cx| {
+            // Dock buttons stay at the edges, before the items added by default.
             let mut status_bar = StatusBar::new(&center_pane.clone(), cx);
-            status_bar.add_left_item(left_dock_buttons, cx);
-            status_bar.add_right_item(right_dock_buttons, cx);
-            status_bar.add_right_item(bottom_dock_buttons, cx);
+            status_bar.add_item(StatusItemPosition::Left, -1, left_dock_buttons, cx);
+            if let Some(file_counts) = file_counts {
+                status_bar.add_left_item(file_counts, cx);
+            }
+            status_bar.add_item(StatusItemPosition::Right, -1, right_dock_buttons, cx);
+            status_bar.add_item(StatusItemPosition::Right, -1, bottom_dock_buttons, cx);
             status_bar
         });
 
@@ -968,11 +1310,18 @@ impl Workspace {
         let _items_serializer = cx.spawn(|this, mut cx| async move {
             Self::serialize_items(&this, serializable_items_rx, &mut cx).await
         });
+        let _recovery_writer = Self::write_recovery_snapshots_periodically(cx);
+        let notification_focus = NotificationFocus::new(cx);
+        let _layout_checkpoint_writer = Self::write_layout_checkpoints_periodically(cx);
+        if let Some(workspace_id) = workspace_id {
+            Self::load_suppressed_notifications(workspace_id, cx);
+        }
 
         let subscriptions = vec![
             cx.observe_window_activation(Self::on_window_activation_changed),
             cx.observe_window_bounds(move |this, cx| {
-                if this.bounds_save_task_queued.is_some() {
+                this.check_fullscreen_changed(cx);
+                if this.bounds_save_task_queued.is_some() || this.serialization_released {
                     return;
                 }
                 this.bounds_save_task_queued = Some(cx.spawn(|this, mut cx| async move {
@@ -980,19 +1329,8 @@ impl Workspace {
                         .timer(Duration::from_millis(100))
                         .await;
                     this.update(&mut cx, |this, cx| {
-                        if let Some(display) = cx.display() {
-                            if let Ok(display_uuid) = display.uuid() {
-                                let window_bounds = cx.window_bounds();
-                                if let Some(database_id) = workspace_id {
-                                    cx.background_executor()
-                                        .spawn(DB.set_window_open_status(
-                                            database_id,
-                                            SerializedWindowBounds(window_bounds),
-                                            display_uuid,
-                                        ))
-                                        .detach_and_log_err(cx);
-                                }
-                            }
+                        if let Some(save_bounds) = this.save_window_bounds(cx) {
+                            save_bounds.detach_and_log_err(cx);
                         }
                         this.bounds_save_task_queued.take();
                     })
@@ -1009,17 +1347,21 @@ impl Workspace {
             }),
             cx.observe(&left_dock, |this, _, cx| {
                 this.serialize_workspace(cx);
+                this.schedule_counts_update(cx);
                 cx.notify();
             }),
             cx.observe(&bottom_dock, |this, _, cx| {
                 this.serialize_workspace(cx);
+                this.schedule_counts_update(cx);
                 cx.notify();
             }),
             cx.observe(&right_dock, |this, _, cx| {
                 this.serialize_workspace(cx);
+                this.schedule_counts_update(cx);
                 cx.notify();
             }),
             cx.on_release(|this, window, cx| {
+                this.release_serialization();
                 this.app_state.workspace_store.update(cx, |store, _| {
                     let window = window.downcast::<Self>().unwrap();
                     store.workspaces.remove(&window);
@@ -1034,23 +1376,30 @@ impl Workspace {
             weak_self: weak_handle.clone(),
             zoomed: None,
             zoomed_position: None,
+            zoomed_bounds: None,
             center: PaneGroup::new(center_pane.clone()),
             panes: vec![center_pane.clone()],
             panes_by_item: Default::default(),
             active_pane: center_pane.clone(),
             last_active_center_pane: Some(center_pane.downgrade()),
+            last_active_unreserved_pane: Some(center_pane.downgrade()),
+            pane_reservations: Default::default(),
+            mirror_pane: None,
             last_active_view_id: None,
             status_bar,
             modal_layer,
             titlebar_item: None,
             notifications: Default::default(),
+            notification_focus,
             left_dock,
             bottom_dock,
             right_dock,
             project: project.clone(),
             follower_states: Default::default(),
             last_leaders_by_pane: Default::default(),
-            dispatching_keystrokes: Default::default(),
+            follow_window_leader: None,
+            last_new_follower_notification: None,
+            dragged_item_id: None,
             window_edited: false,
             active_call,
             database_id: workspace_id,
@@ -1058,20 +1407,52 @@ impl Workspace {
             _observe_current_user,
             _apply_leader_updates,
             _schedule_serialize: None,
+            last_window_title_update: None,
+            pending_window_title_update: None,
+            pending_mouse_focus: None,
+            dock_auto_hide: DockAutoHide::default(),
+            follow_all: FollowAll::default(),
+            project_panel_reveal: None,
+            event_batch: EventBatch::default(),
+            reported_active_item_id: None,
+            scm_statuses: ScmStatuses::default(),
+            external_update_locks,
+            fullscreen_state: FullscreenState::default(),
+            previous_project: None,
+            running_tasks: Vec::new(),
             leader_updates_tx,
             _subscriptions: subscriptions,
             pane_history_timestamp,
+            item_activations: Vec::new(),
+            item_activation_cursor: None,
+            alternate_items: Default::default(),
+            stable_item_ids: HashMap::default(),
+            item_bookmarks: Vec::new(),
+            suppressed_notifications: HashSet::default(),
             workspace_actions: Default::default(),
             // This data will be incorrect, but it will be overwritten by the time it needs to be used.
             bounds: Default::default(),
             centered_layout: false,
+            session_notes: None,
             bounds_save_task_queued: None,
+            missing_preferred_display: None,
+            before_save_hooks: BeforeSaveHooks::default(),
+            serialization_released: false,
+            last_restore_duration: None,
+            loading: WorkspaceLoading::default(),
             on_prompt_for_new_path: None,
             on_prompt_for_open_path: None,
+            use_system_path_prompts: None,
             serializable_items_tx,
             _items_serializer,
+            recovery_snapshots: RecoverySnapshots::default(),
+            _recovery_writer,
+            last_layout_checkpoint: None,
+            _layout_checkpoint_writer,
             session_id: Some(session_id),
             serialized_ssh_project: None,
+            counts: WorkspaceCounts::default(),
+            counts_update_scheduled: false,
         }
     }
 
@@ -1080,6 +1461,34 @@ impl Workspace {
         app_state: Arc<AppState>,
         requesting_window: Option<WindowHandle<Workspace>>,
         env: Option<HashMap<String, String>>,
+        window_options_override: Option<WindowOptionsOverride>,
+        cx: &mut AppContext,
+    ) -> Task<
+        anyhow::Result<(
+            WindowHandle<Workspace>,
+            Vec<Option<Result<Box<dyn ItemHandle>, anyhow::Error>>>,
+        )>,
+    > {
+        Self::open_local(
+            abs_paths,
+            app_state,
+            requesting_window,
+            env,
+            window_options_override,
+            true,
+            cx,
+        )
+    }
+
+    /// Like [`Workspace::new_local`], but the workspace stored for the paths is only restored
+    /// when `restore_serialized` is true. A new workspace id is used otherwise.
+    pub(crate) fn open_local(
+        abs_paths: Vec<PathBuf>,
+        app_state: Arc<AppState>,
+        requesting_window: Option<WindowHandle<Workspace>>,
+        env: Option<HashMap<String, String>>,
+        window_options_override: Option<WindowOptionsOverride>,
+        restore_serialized: bool,
         cx: &mut AppContext,
     ) -> Task<
         anyhow::Result<(
@@ -1107,8 +1516,16 @@ impl Workspace {
                 }
             }
 
+            // The workspaces stored by a newer version of Zed are only restored on request.
             let serialized_workspace: Option<SerializedWorkspace> =
-                persistence::DB.workspace_for_roots(paths_to_open.as_slice());
+                if restore_serialized && persistence::restores_workspaces() {
+                    persistence::DB.workspace_for_roots_in_window(
+                        paths_to_open.as_slice(),
+                        requesting_window.map(|window| window.window_id().as_u64()),
+                    )
+                } else {
+                    None
+                };
 
             let workspace_location = serialized_workspace
                 .as_ref()
@@ -1158,12 +1575,25 @@ impl Workspace {
             }
 
             let workspace_id = if let Some(serialized_workspace) = serialized_workspace.as_ref() {
-                serialized_workspace.id
+                Some(serialized_workspace.id)
+            } else if *persistence::NEWER_SCHEMA {
+                None
             } else {
-                DB.next_id().await.unwrap_or_else(|_| Default::default())
+                Some(DB.next_id().await.unwrap_or_else(|_| Default::default()))
             };
+            // A database migrated by a newer version of Zed is read as far as possible, and
+            // never written to.
+            let database_id = workspace_id.filter(|_| !*persistence::NEWER_SCHEMA);
 
-            let toolchains = DB.toolchains(workspace_id).await?;
+            let toolchains = match workspace_id {
+                Some(workspace_id) if *persistence::NEWER_SCHEMA => DB
+                    .toolchains(workspace_id)
+                    .await
+                    .log_err()
+                    .unwrap_or_default(),
+                Some(workspace_id) => DB.toolchains(workspace_id).await?,
+                None => Vec::new(),
+            };
             for (toolchain, worktree_id) in toolchains {
                 project_handle
                     .update(&mut cx, |this, cx| {
@@ -1171,42 +1601,41 @@ impl Workspace {
                     })?
                     .await;
             }
+            let mut missing_preferred_display = None;
+            let mut restored_window_bounds = false;
             let window = if let Some(window) = requesting_window {
                 cx.update_window(window.into(), |_, cx| {
                     cx.replace_root_view(|cx| {
-                        Workspace::new(
-                            Some(workspace_id),
-                            project_handle.clone(),
-                            app_state.clone(),
-                            cx,
-                        )
+                        Workspace::new(database_id, project_handle.clone(), app_state.clone(), cx)
                     });
                 })?;
                 window
             } else {
                 let window_bounds_override = window_bounds_env_override();
 
-                let (window_bounds, display) = if let Some(bounds) = window_bounds_override {
-                    (Some(WindowBounds::Windowed(bounds)), None)
+                let (window_bounds, display) = if window_bounds_override.is_some() {
+                    (None, None)
                 } else {
-                    let restorable_bounds = serialized_workspace
-                        .as_ref()
-                        .and_then(|workspace| Some((workspace.display?, workspace.window_bounds?)))
-                        .or_else(|| {
-                            let (display, window_bounds) = DB.last_window().log_err()?;
-                            Some((display?, window_bounds?))
-                        });
+                    let restorable_bounds = cx
+                        .update(|cx| restorable_window_bounds(serialized_workspace.as_ref(), cx))?;
 
-                    if let Some((serialized_display, serialized_status)) = restorable_bounds {
-                        (Some(serialized_status.0), Some(serialized_display))
+                    if let Some(restored) = restorable_bounds {
+                        missing_preferred_display = restored.missing_display;
+                        restored_window_bounds = true;
+                        (Some(restored.bounds), Some(restored.display))
                     } else {
                         (None, None)
                     }
                 };
 
                 // Use the serialized workspace to construct the new window
-                let mut options = cx.update(|cx| (app_state.build_window_options)(display, cx))?;
-                options.window_bounds = window_bounds;
+                let options = cx.update(|cx| (app_state.build_window_options)(display, cx))?;
+                let options = resolve_window_options(
+                    options,
+                    window_bounds,
+                    window_options_override,
+                    window_bounds_override,
+                );
                 let centered_layout = serialized_workspace
                     .as_ref()
                     .map(|w| w.centered_layout)
@@ -1217,7 +1646,7 @@ impl Workspace {
                     move |cx| {
                         cx.new_view(|cx| {
                             let mut workspace =
-                                Workspace::new(Some(workspace_id), project_handle, app_state, cx);
+                                Workspace::new(database_id, project_handle, app_state, cx);
                             workspace.centered_layout = centered_layout;
                             workspace
                         })
@@ -1226,9 +1655,22 @@ impl Workspace {
             };
 
             notify_if_database_failed(window, &mut cx);
+            notify_if_database_newer(window, &mut cx);
+            window
+                .update(&mut cx, |workspace, cx| {
+                    workspace.missing_preferred_display = missing_preferred_display;
+                    // The displays may have changed since the bounds were serialized.
+                    if restored_window_bounds {
+                        ensure_window_on_screen(cx)
+                    }
+                })
+                .log_err();
+            window
+                .update(&mut cx, |workspace, cx| workspace.restore_fullscreen(cx))
+                .log_err();
             let opened_items = window
-                .update(&mut cx, |_workspace, cx| {
-                    open_items(serialized_workspace, project_paths, cx)
+                .update(&mut cx, |workspace, cx| {
+                    open_items(workspace, serialized_workspace, project_paths, cx)
                 })?
                 .await
                 .unwrap_or_default();
@@ -1256,6 +1698,14 @@ impl Workspace {
         &self.right_dock
     }
 
+    pub fn dock_at_position(&self, position: DockPosition) -> &View<Dock> {
+        match position {
+            DockPosition::Left => &self.left_dock,
+            DockPosition::Bottom => &self.bottom_dock,
+            DockPosition::Right => &self.right_dock,
+        }
+    }
+
     pub fn is_edited(&self) -> bool {
         self.window_edited
     }
@@ -1280,6 +1730,22 @@ impl Workspace {
         &self.status_bar
     }
 
+    /// Adds an item to the status bar until the returned handle is dropped. Items with a lower
+    /// `priority`, 0 by default, are closer to the edge of their side.
+    pub fn add_status_item<T: StatusItemView>(
+        &mut self,
+        position: StatusItemPosition,
+        priority: Option<i32>,
+        item: View<T>,
+        cx: &mut ViewContext<Self>,
+    ) -> StatusItemHandle {
+        let item_id = item.entity_id();
+        self.status_bar.update(cx, |status_bar, cx| {
+            status_bar.add_item(position, priority.unwrap_or(0), item, cx)
+        });
+        StatusItemHandle::new(&self.status_bar, item_id, cx)
+    }
+
     pub fn app_state(&self) -> &Arc<AppState> {
         &self.app_state
     }
@@ -1450,6 +1916,16 @@ impl Workspace {
                                         }
                                     })?;
                                 }
+                                Err(open_by_abs_path_e)
+                                    if matches!(
+                                        open_by_abs_path_e.downcast_ref::<OpenError>(),
+                                        Some(OpenError::OpenedAsWorktree { .. })
+                                    ) =>
+                                {
+                                    // The entry's path is a directory now, so there's nothing to
+                                    // navigate to, and the next entry is tried.
+                                    log::debug!("Skipping history entry: {open_by_abs_path_e:#}");
+                                }
                                 Err(open_by_abs_path_e) => {
                                     log::error!("Failed to navigate history: {open_by_project_path_e:#} and {open_by_abs_path_e:#}");
                                 }
@@ -1514,6 +1990,45 @@ impl Workspace {
         self.on_prompt_for_open_path = Some(prompt)
     }
 
+    /// Whether the in-app path prompts are registered, without which the system ones are
+    /// used even when the in-app ones are preferred.
+    pub fn has_path_prompt_handlers(&self) -> bool {
+        self.on_prompt_for_new_path.is_some() && self.on_prompt_for_open_path.is_some()
+    }
+
+    /// Overrides the `use_system_path_prompts` setting for this workspace, or follows it again
+    /// with `None`.
+    pub fn set_use_system_path_prompts(&mut self, use_system_path_prompts: Option<bool>) {
+        self.use_system_path_prompts = use_system_path_prompts;
+    }
+
+    fn use_system_path_prompts(&self, cx: &AppContext) -> bool {
+        self.use_system_path_prompts
+            .unwrap_or_else(|| WorkspaceSettings::get_global(cx).use_system_path_prompts)
+    }
+
+    fn prompt_in_app_for_open_path(
+        &mut self,
+        lister: DirectoryLister,
+        path_prompt_options: PathPromptOptions,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<oneshot::Receiver<Option<Vec<PathBuf>>>> {
+        let prompt = self.on_prompt_for_open_path.take()?;
+        let rx = prompt(self, lister, path_prompt_options, cx);
+        self.on_prompt_for_open_path = Some(prompt);
+        Some(rx)
+    }
+
+    fn prompt_in_app_for_new_path(
+        &mut self,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<oneshot::Receiver<Option<ProjectPath>>> {
+        let prompt = self.on_prompt_for_new_path.take()?;
+        let rx = prompt(self, cx);
+        self.on_prompt_for_new_path = Some(prompt);
+        Some(rx)
+    }
+
     pub fn serialized_ssh_project(&self) -> Option<SerializedSshProject> {
         self.serialized_ssh_project.clone()
     }
@@ -1528,112 +2043,127 @@ impl Workspace {
         lister: DirectoryLister,
         cx: &mut ViewContext<Self>,
     ) -> oneshot::Receiver<Option<Vec<PathBuf>>> {
-        if !lister.is_local(cx) || !WorkspaceSettings::get_global(cx).use_system_path_prompts {
-            let prompt = self.on_prompt_for_open_path.take().unwrap();
-            let rx = prompt(self, lister, cx);
-            self.on_prompt_for_open_path = Some(prompt);
-            rx
-        } else {
-            let (tx, rx) = oneshot::channel();
-            let abs_path = cx.prompt_for_paths(path_prompt_options);
+        let is_local = lister.is_local(cx);
+        if !is_local || !self.use_system_path_prompts(cx) {
+            if let Some(rx) =
+                self.prompt_in_app_for_open_path(lister.clone(), path_prompt_options.clone(), cx)
+            {
+                return rx;
+            }
+            if !is_local {
+                log::error!("no in-app path prompt registered to pick remote paths");
+                let (tx, rx) = oneshot::channel();
+                tx.send(None).ok();
+                return rx;
+            }
+            log::warn!("no in-app path prompt registered, using the system one");
+        }
 
-            cx.spawn(|this, mut cx| async move {
-                let Ok(result) = abs_path.await else {
-                    return Ok(());
-                };
+        let (tx, rx) = oneshot::channel();
+        let abs_path = cx.prompt_for_paths(path_prompt_options.clone());
 
-                match result {
-                    Ok(result) => {
-                        tx.send(result).log_err();
-                    }
-                    Err(err) => {
-                        let rx = this.update(&mut cx, |this, cx| {
-                            this.show_portal_error(err.to_string(), cx);
-                            let prompt = this.on_prompt_for_open_path.take().unwrap();
-                            let rx = prompt(this, lister, cx);
-                            this.on_prompt_for_open_path = Some(prompt);
-                            rx
-                        })?;
-                        if let Ok(path) = rx.await {
-                            tx.send(path).log_err();
-                        }
+        cx.spawn(|this, mut cx| async move {
+            let Ok(result) = abs_path.await else {
+                return Ok(());
+            };
+
+            match result {
+                Ok(result) => {
+                    tx.send(result).log_err();
+                }
+                Err(err) => {
+                    let rx = this.update(&mut cx, |this, cx| {
+                        this.show_portal_error(err.to_string(), cx);
+                        this.prompt_in_app_for_open_path(lister, path_prompt_options, cx)
+                    })?;
+                    let Some(rx) = rx else {
+                        tx.send(None).log_err();
+                        return Ok(());
+                    };
+                    if let Ok(path) = rx.await {
+                        tx.send(path).log_err();
                     }
-                };
-                anyhow::Ok(())
-            })
-            .detach();
+                }
+            };
+            anyhow::Ok(())
+        })
+        .detach();
 
-            rx
-        }
+        rx
     }
 
     pub fn prompt_for_new_path(
         &mut self,
         cx: &mut ViewContext<Self>,
     ) -> oneshot::Receiver<Option<ProjectPath>> {
-        if (self.project.read(cx).is_via_collab() || self.project.read(cx).is_via_ssh())
-            || !WorkspaceSettings::get_global(cx).use_system_path_prompts
-        {
-            let prompt = self.on_prompt_for_new_path.take().unwrap();
-            let rx = prompt(self, cx);
-            self.on_prompt_for_new_path = Some(prompt);
-            rx
-        } else {
-            let start_abs_path = self
-                .project
-                .update(cx, |project, cx| {
-                    let worktree = project.visible_worktrees(cx).next()?;
-                    Some(worktree.read(cx).as_local()?.abs_path().to_path_buf())
-                })
-                .unwrap_or_else(|| Path::new("").into());
-
-            let (tx, rx) = oneshot::channel();
-            let abs_path = cx.prompt_for_new_path(&start_abs_path);
-            cx.spawn(|this, mut cx| async move {
-                let abs_path = match abs_path.await? {
-                    Ok(path) => path,
-                    Err(err) => {
-                        let rx = this.update(&mut cx, |this, cx| {
-                            this.show_portal_error(err.to_string(), cx);
-
-                            let prompt = this.on_prompt_for_new_path.take().unwrap();
-                            let rx = prompt(this, cx);
-                            this.on_prompt_for_new_path = Some(prompt);
-                            rx
-                        })?;
-                        if let Ok(path) = rx.await {
-                            tx.send(path).log_err();
-                        }
+        let is_remote = self.project.read(cx).is_via_collab() || self.project.read(cx).is_via_ssh();
+        if is_remote || !self.use_system_path_prompts(cx) {
+            if let Some(rx) = self.prompt_in_app_for_new_path(cx) {
+                return rx;
+            }
+            if is_remote {
+                log::error!("no in-app path prompt registered to pick a remote path");
+                let (tx, rx) = oneshot::channel();
+                tx.send(None).ok();
+                return rx;
+            }
+            log::warn!("no in-app path prompt registered, using the system one");
+        }
+
+        let start_abs_path = self
+            .project
+            .update(cx, |project, cx| {
+                let worktree = project.visible_worktrees(cx).next()?;
+                Some(worktree.read(cx).as_local()?.abs_path().to_path_buf())
+            })
+            .unwrap_or_else(|| Path::new("").into());
+
+        let (tx, rx) = oneshot::channel();
+        let abs_path = cx.prompt_for_new_path(&start_abs_path);
+        cx.spawn(|this, mut cx| async move {
+            let abs_path = match abs_path.await? {
+                Ok(path) => path,
+                Err(err) => {
+                    let rx = this.update(&mut cx, |this, cx| {
+                        this.show_portal_error(err.to_string(), cx);
+                        this.prompt_in_app_for_new_path(cx)
+                    })?;
+                    let Some(rx) = rx else {
+                        tx.send(None).log_err();
                         return anyhow::Ok(());
+                    };
+                    if let Ok(path) = rx.await {
+                        tx.send(path).log_err();
                     }
-                };
+                    return anyhow::Ok(());
+                }
+            };
 
-                let project_path = abs_path.and_then(|abs_path| {
-                    this.update(&mut cx, |this, cx| {
-                        this.project.update(cx, |project, cx| {
-                            project.find_or_create_worktree(abs_path, true, cx)
-                        })
+            let project_path = abs_path.and_then(|abs_path| {
+                this.update(&mut cx, |this, cx| {
+                    this.project.update(cx, |project, cx| {
+                        project.find_or_create_worktree(abs_path, true, cx)
                     })
-                    .ok()
-                });
+                })
+                .ok()
+            });
 
-                if let Some(project_path) = project_path {
-                    let (worktree, path) = project_path.await?;
-                    let worktree_id = worktree.read_with(&cx, |worktree, _| worktree.id())?;
-                    tx.send(Some(ProjectPath {
-                        worktree_id,
-                        path: path.into(),
-                    }))
-                    .ok();
-                } else {
-                    tx.send(None).ok();
-                }
-                anyhow::Ok(())
-            })
-            .detach_and_log_err(cx);
+            if let Some(project_path) = project_path {
+                let (worktree, path) = project_path.await?;
+                let worktree_id = worktree.read_with(&cx, |worktree, _| worktree.id())?;
+                tx.send(Some(ProjectPath {
+                    worktree_id,
+                    path: path.into(),
+                }))
+                .ok();
+            } else {
+                tx.send(None).ok();
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
 
-            rx
-        }
+        rx
     }
 
     pub fn titlebar_item(&self) -> Option<AnyView> {
@@ -1657,7 +2187,7 @@ impl Workspace {
             Task::Ready(Some(Ok(callback(self, cx))))
         } else {
             let env = self.project.read(cx).cli_environment(cx);
-            let task = Self::new_local(Vec::new(), self.app_state.clone(), None, env, cx);
+            let task = Self::new_local(Vec::new(), self.app_state.clone(), None, env, None, cx);
             cx.spawn(|_vh, mut cx| async move {
                 let (workspace, _) = task.await?;
                 workspace.update(&mut cx, callback)
@@ -1730,10 +2260,15 @@ impl Workspace {
         let active_call = self.active_call().cloned();
         let window = cx.window_handle();
 
-        // On Linux and Windows, closing the last window should restore the last workspace.
+        // On Linux and Windows, closing the last window should restore the last workspace,
+        // unless the app keeps running without windows, as on macOS.
         let save_last_workspace = cfg!(not(target_os = "macos"))
+            && !keep_running::keeps_running_without_windows(cx)
             && close_intent != CloseIntent::ReplaceWindow
             && cx.windows().len() == 1;
+        // Untitled items are carried over to the workspace replacing this one instead.
+        let skip_untitled = close_intent == CloseIntent::ReplaceWindow
+            && carry_untitled::carries_untitled_items(cx);
 
         cx.spawn(|this, mut cx| async move {
             let workspace_count = (*cx).update(|cx| {
@@ -1768,9 +2303,17 @@ impl Workspace {
                 }
             }
 
+            if let Some(answer) =
+                this.update(&mut cx, |this, cx| this.prompt_to_close_running_tasks(cx))?
+            {
+                if answer.await.log_err() != Some(0) {
+                    return anyhow::Ok(false);
+                }
+            }
+
             let save_result = this
                 .update(&mut cx, |this, cx| {
-                    this.save_all_internal(SaveIntent::Close, cx)
+                    this.save_dirty_items(SaveIntent::Close, skip_untitled, cx)
                 })?
                 .await;
 
@@ -1784,6 +2327,13 @@ impl Workspace {
                     .await;
             }
 
+            if save_result.as_ref().map_or(false, |&res| res) {
+                this.update(&mut cx, |this, cx| this.discard_all_recovery(cx))?
+                    .await;
+                this.update(&mut cx, |this, cx| this.flush_pending_serialization(cx))?
+                    .await;
+            }
+
             save_result
         })
     }
@@ -1793,66 +2343,129 @@ impl Workspace {
             .detach_and_log_err(cx);
     }
 
-    fn send_keystrokes(&mut self, action: &SendKeystrokes, cx: &mut ViewContext<Self>) {
-        let mut state = self.dispatching_keystrokes.borrow_mut();
-        if !state.0.insert(action.0.clone()) {
-            cx.propagate();
-            return;
+    /// Saves all items in every window showing this project. A window that fails to save
+    /// doesn't prevent the others from saving, its error is reported once all of them are done.
+    fn save_all_windows(&mut self, _: &SaveAllWindows, cx: &mut ViewContext<Self>) {
+        let mut saves = vec![(
+            self.project_window_label(cx),
+            self.save_all_internal(SaveIntent::SaveAll, cx),
+        )];
+        let results = self.broadcast_to_project_windows(cx, |workspace, cx| {
+            (
+                workspace.project_window_label(cx),
+                workspace.save_all_internal(SaveIntent::SaveAll, cx),
+            )
+        });
+        for (_, result) in results {
+            match result {
+                Ok(save) => saves.push(save),
+                Err(err) => saves.push((SharedString::default(), Task::ready(Err(err)))),
+            }
         }
-        let mut keystrokes: Vec<Keystroke> = action
-            .0
-            .split(' ')
-            .flat_map(|k| Keystroke::parse(k).log_err())
-            .collect();
-        keystrokes.reverse();
-
-        state.1.append(&mut keystrokes);
-        drop(state);
-
-        let keystrokes = self.dispatching_keystrokes.clone();
-        cx.window_context()
-            .spawn(|mut cx| async move {
-                // limit to 100 keystrokes to avoid infinite recursion.
-                for _ in 0..100 {
-                    let Some(keystroke) = keystrokes.borrow_mut().1.pop() else {
-                        keystrokes.borrow_mut().0.clear();
-                        return Ok(());
-                    };
-                    cx.update(|cx| {
-                        let focused = cx.focused();
-                        cx.dispatch_keystroke(keystroke.clone());
-                        if cx.focused() != focused {
-                            // dispatch_keystroke may cause the focus to change.
-                            // draw's side effect is to schedule the FocusChanged events in the current flush effect cycle
-                            // And we need that to happen before the next keystroke to keep vim mode happy...
-                            // (Note that the tests always do this implicitly, so you must manually test with something like:
-                            //   "bindings": { "g z": ["workspace::SendKeystrokes", ": j <enter> u"]}
-                            // )
-                            cx.draw();
-                        }
-                    })?;
-                }
 
-                *keystrokes.borrow_mut() = Default::default();
-                Err(anyhow!("over 100 keystrokes passed to send_keystrokes"))
+        cx.spawn(|this, mut cx| async move {
+            let window_count = saves.len();
+            let (labels, tasks): (Vec<_>, Vec<_>) = saves.into_iter().unzip();
+            let failures = labels
+                .into_iter()
+                .zip(futures::future::join_all(tasks).await)
+                .filter_map(|(label, result)| Some((label, result.err()?)))
+                .collect::<Vec<_>>();
+            if failures.is_empty() {
+                return Ok(());
+            }
+
+            this.update(&mut cx, |this, cx| {
+                struct SaveAllWindowsError;
+
+                let headline = format!(
+                    "Failed to save {} of {window_count} windows",
+                    failures.len()
+                );
+                let detail = failures
+                    .iter()
+                    .map(|(label, err)| format!("{label}: {err:#}"))
+                    .join("\n");
+                this.show_notification(NotificationId::unique::<SaveAllWindowsError>(), cx, |cx| {
+                    cx.new_view(|_| ErrorNotification::new(headline).with_detail(detail))
+                });
             })
-            .detach_and_log_err(cx);
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Runs `f` in every other window showing the same project as this one, that is, the same
+    /// remote project or the same set of worktrees.
+    pub fn broadcast_to_project_windows<R>(
+        &self,
+        cx: &mut ViewContext<Self>,
+        mut f: impl FnMut(&mut Workspace, &mut ViewContext<Workspace>) -> R,
+    ) -> Vec<(WindowHandle<Workspace>, Result<R>)> {
+        let this_window = cx.window_handle();
+        let project = self.project.read(cx);
+        let remote_id = project.remote_id();
+        let worktree_paths = visible_worktree_paths(project, cx);
+        windows_for_project(
+            |other, cx| {
+                if remote_id.is_some() {
+                    other.remote_id() == remote_id
+                } else {
+                    !worktree_paths.is_empty()
+                        && visible_worktree_paths(other, cx) == worktree_paths
+                }
+            },
+            cx,
+        )
+        .into_iter()
+        .filter(|window| AnyWindowHandle::from(*window) != this_window)
+        .map(|window| (window, window.update(cx, |workspace, cx| f(workspace, cx))))
+        .collect()
+    }
+
+    fn project_window_label(&self, cx: &AppContext) -> SharedString {
+        self.project
+            .read(cx)
+            .worktree_root_names(cx)
+            .join(", ")
+            .into()
     }
 
     fn save_all_internal(
         &mut self,
+        save_intent: SaveIntent,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<bool>> {
+        self.save_dirty_items(save_intent, false, cx)
+    }
+
+    fn save_dirty_items(
+        &mut self,
+        save_intent: SaveIntent,
+        skip_untitled: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<bool>> {
+        let panes = self.panes.clone();
+        self.save_dirty_items_in_panes(&panes, save_intent, skip_untitled, cx)
+    }
+
+    fn save_dirty_items_in_panes(
+        &mut self,
+        panes: &[View<Pane>],
         mut save_intent: SaveIntent,
+        skip_untitled: bool,
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<bool>> {
         if self.project.read(cx).is_disconnected(cx) {
             return Task::ready(Ok(true));
         }
-        let dirty_items = self
-            .panes
+        let dirty_items = panes
             .iter()
             .flat_map(|pane| {
                 pane.read(cx).items().filter_map(|item| {
-                    if item.is_dirty(cx) {
+                    if item.is_dirty(cx)
+                        && !item.is_ephemeral(cx)
+                        && !(skip_untitled && item.unsaved_text(cx).is_some())
+                    {
                         item.tab_description(0, cx);
                         Some((pane.downgrade(), item.boxed_clone()))
                     } else {
@@ -1864,7 +2477,7 @@ impl Workspace {
 
         let project = self.project.clone();
         cx.spawn(|workspace, mut cx| async move {
-            let dirty_items = if save_intent == SaveIntent::Close && !dirty_items.is_empty() {
+            let mut dirty_items = if save_intent == SaveIntent::Close && !dirty_items.is_empty() {
                 let (serialize_tasks, remaining_dirty_items) =
                     workspace.update(&mut cx, |workspace, cx| {
                         let mut remaining_dirty_items = Vec::new();
@@ -1910,10 +2523,20 @@ impl Workspace {
                 dirty_items
             };
 
+            // Items that others depend on need to be saved first, so save them sequentially.
+            cx.update(|cx| {
+                dirty_items.sort_by_cached_key(|(_, item)| item.save_ordering_hint(cx));
+            })?;
+
             for (pane, item) in dirty_items {
                 let (singleton, project_entry_ids) =
                     cx.update(|cx| (item.is_singleton(cx), item.project_entry_ids(cx)))?;
                 if singleton || !project_entry_ids.is_empty() {
+                    if !workspace.update(&mut cx, |workspace, cx| {
+                        workspace.run_before_save_hooks(item.as_ref(), save_intent, cx)
+                    })? {
+                        return Ok(false);
+                    }
                     if let Some(ix) =
                         pane.update(&mut cx, |pane, _| pane.index_for_item(item.as_ref()))?
                     {
@@ -1954,31 +2577,60 @@ impl Workspace {
         } else {
             window
         };
+        let carried_untitled_items = window_to_replace
+            .is_some()
+            .then(|| self.carried_untitled_items(cx))
+            .flatten();
         let app_state = self.app_state.clone();
 
         cx.spawn(|_, mut cx| async move {
-            cx.update(|cx| {
-                open_paths(
-                    &paths,
-                    app_state,
-                    OpenOptions {
-                        replace_window: window_to_replace,
-                        ..Default::default()
-                    },
-                    cx,
-                )
-            })?
-            .await?;
+            let (workspace, _) = cx
+                .update(|cx| {
+                    open_paths(
+                        &paths,
+                        app_state,
+                        OpenOptions {
+                            replace_window: window_to_replace,
+                            ..Default::default()
+                        },
+                        cx,
+                    )
+                })?
+                .await?;
+            // The paths may have been opened in another window instead, which leaves the
+            // untitled items where they are.
+            let carried_untitled_items =
+                carried_untitled_items.filter(|_| Some(workspace) == window_to_replace);
+            if let Some(items) = carried_untitled_items {
+                workspace.update(&mut cx, |workspace, cx| {
+                    workspace.add_carried_untitled_items(items, cx)
+                })?;
+            }
             Ok(())
         })
     }
 
     #[allow(clippy::type_complexity)]
     pub fn open_paths(
+        &mut self,
+        abs_paths: Vec<PathBuf>,
+        visible: OpenVisible,
+        pane: Option<WeakView<Pane>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Vec<Option<Result<Box<dyn ItemHandle>, anyhow::Error>>>> {
+        self.open_paths_with_pane_placement(abs_paths, visible, pane, PanePlacement::ActivePane, cx)
+    }
+
+    /// Opens the given paths, deciding which pane each opened file goes to
+    /// according to `placement`. Directories only add worktrees and never
+    /// consume a pane.
+    #[allow(clippy::type_complexity)]
+    pub fn open_paths_with_pane_placement(
         &mut self,
         mut abs_paths: Vec<PathBuf>,
         visible: OpenVisible,
         pane: Option<WeakView<Pane>>,
+        placement: PanePlacement,
         cx: &mut ViewContext<Self>,
     ) -> Task<Vec<Option<Result<Box<dyn ItemHandle>, anyhow::Error>>>> {
         log::info!("open paths {abs_paths:?}");
@@ -1989,6 +2641,8 @@ impl Workspace {
         abs_paths.sort_unstable();
         cx.spawn(move |this, mut cx| async move {
             let mut tasks = Vec::with_capacity(abs_paths.len());
+            let mut opened_file_count = 0;
+            let mut last_split_pane: Option<View<Pane>> = None;
 
             for abs_path in &abs_paths {
                 let visible = match visible {
@@ -2023,13 +2677,30 @@ impl Workspace {
                     None => None,
                 };
 
+                let is_dir = fs.is_dir(abs_path).await;
+                let pane = if project_path.is_none() || is_dir {
+                    pane.clone()
+                } else {
+                    let file_ix = opened_file_count;
+                    opened_file_count += 1;
+                    this.update(&mut cx, |this, cx| {
+                        this.pane_for_placement(
+                            placement,
+                            pane.as_ref(),
+                            file_ix,
+                            &mut last_split_pane,
+                            cx,
+                        )
+                    })
+                    .log_err()
+                    .flatten()
+                };
+
                 let this = this.clone();
                 let abs_path: Arc<Path> = SanitizedPath::from(abs_path.clone()).into();
-                let fs = fs.clone();
-                let pane = pane.clone();
                 let task = cx.spawn(move |mut cx| async move {
                     let (worktree, project_path) = project_path?;
-                    if fs.is_dir(&abs_path).await {
+                    if is_dir {
                         this.update(&mut cx, |workspace, cx| {
                             let worktree = worktree.read(cx);
                             let worktree_abs_path = worktree.abs_path();
@@ -2069,6 +2740,40 @@ impl Workspace {
         })
     }
 
+    fn pane_for_placement(
+        &mut self,
+        placement: PanePlacement,
+        origin: Option<&WeakView<Pane>>,
+        file_ix: usize,
+        last_split_pane: &mut Option<View<Pane>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<WeakView<Pane>> {
+        let origin_pane = origin
+            .and_then(|pane| pane.upgrade())
+            .unwrap_or_else(|| self.active_pane.clone());
+        match placement {
+            PanePlacement::ActivePane => origin.cloned(),
+            PanePlacement::SpreadAcrossExistingPanes => {
+                let panes = self.center.panes();
+                let start_ix = panes
+                    .iter()
+                    .position(|pane| **pane == origin_pane)
+                    .unwrap_or(0);
+                panes
+                    .get((start_ix + file_ix) % panes.len())
+                    .map(|pane| pane.downgrade())
+            }
+            PanePlacement::SplitPerFile(direction) => {
+                let pane = match last_split_pane.take() {
+                    Some(previous_pane) => self.split_pane(previous_pane, direction, cx),
+                    None => origin_pane,
+                };
+                *last_split_pane = Some(pane.clone());
+                Some(pane.downgrade())
+            }
+        }
+    }
+
     pub fn open_resolved_path(
         &mut self,
         path: ResolvedPath,
@@ -2091,40 +2796,300 @@ impl Workspace {
             );
             return;
         }
+        let starting_directory = project
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf());
         let paths = self.prompt_for_open_path(
             PathPromptOptions {
                 files: false,
                 directories: true,
                 multiple: true,
+                starting_directory,
+                ..Default::default()
             },
             DirectoryLister::Project(self.project.clone()),
             cx,
         );
         cx.spawn(|this, mut cx| async move {
             if let Some(paths) = paths.await.log_err().flatten() {
+                this.update(&mut cx, |this, cx| this.add_folders_to_project(paths, cx))?
+                    .await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Adds the given folders to the project as new roots. When a folder is an ancestor or a
+    /// descendant of an existing root, the user is asked whether to replace that root instead.
+    pub fn add_folders_to_project(
+        &mut self,
+        paths: Vec<PathBuf>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        cx.spawn(|this, mut cx| async move {
+            let mut paths_to_open = Vec::new();
+            for path in paths {
+                let Some((overlapping_worktree_id, overlapping_root)) =
+                    this.update(&mut cx, |this, cx| this.overlapping_root(&path, cx))?
+                else {
+                    paths_to_open.push(path);
+                    continue;
+                };
+
+                let answer = this.update(&mut cx, |_, cx| {
+                    let replace_label = format!(
+                        "Replace existing root {} with {}",
+                        overlapping_root.display(),
+                        path.display()
+                    );
+                    cx.prompt(
+                        PromptLevel::Warning,
+                        &format!(
+                            "{} overlaps with the existing project folder {}",
+                            path.display(),
+                            overlapping_root.display()
+                        ),
+                        None,
+                        &[replace_label.as_str(), "Add anyway", "Cancel"],
+                    )
+                })?;
+                match answer.await {
+                    Ok(0) => {
+                        this.update(&mut cx, |this, cx| {
+                            this.replace_worktree_root(overlapping_worktree_id, path, cx)
+                        })?
+                        .await?;
+                    }
+                    Ok(1) => {
+                        // A descendant of an existing root would resolve to that root's worktree
+                        // when opened as a path, so create the worktree explicitly.
+                        this.update(&mut cx, |this, cx| {
+                            this.project
+                                .update(cx, |project, cx| project.create_worktree(path, true, cx))
+                        })?
+                        .await?;
+                    }
+                    _ => {}
+                }
+            }
+
+            if !paths_to_open.is_empty() {
                 let results = this
                     .update(&mut cx, |this, cx| {
-                        this.open_paths(paths, OpenVisible::All, None, cx)
+                        this.open_paths(paths_to_open, OpenVisible::All, None, cx)
                     })?
                     .await;
                 for result in results.into_iter().flatten() {
                     result.log_err();
                 }
             }
-            anyhow::Ok(())
+            Ok(())
+        })
+    }
+
+    /// Returns the visible worktree whose root is an ancestor or a descendant of `abs_path`.
+    fn overlapping_root(
+        &self,
+        abs_path: &Path,
+        cx: &AppContext,
+    ) -> Option<(WorktreeId, Arc<Path>)> {
+        self.project
+            .read(cx)
+            .visible_worktrees(cx)
+            .find_map(|worktree| {
+                let worktree = worktree.read(cx);
+                let root = worktree.abs_path();
+                let overlaps = root.as_ref() != abs_path
+                    && (root.starts_with(abs_path) || abs_path.starts_with(root.as_ref()));
+                overlaps.then(|| (worktree.id(), root))
+            })
+    }
+
+    /// Replaces the worktree with the given id with a new one rooted at `new_root`, reopening
+    /// the items of the old worktree from the new one in place. The dirty items are offered to
+    /// be saved first, and the worktree is kept if the user cancels.
+    fn replace_worktree_root(
+        &mut self,
+        old_worktree_id: WorktreeId,
+        new_root: PathBuf,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let save_items = self.save_worktree_items(old_worktree_id, cx);
+        cx.spawn(|this, mut cx| async move {
+            if !save_items.await? {
+                return Ok(());
+            }
+            let new_worktree = this
+                .update(&mut cx, |this, cx| {
+                    this.project.update(cx, |project, cx| {
+                        project.create_worktree(new_root, true, cx)
+                    })
+                })?
+                .await?;
+            let scan_complete = new_worktree.update(&mut cx, |worktree, _| {
+                worktree.as_local().map(|worktree| worktree.scan_complete())
+            })?;
+            if let Some(scan_complete) = scan_complete {
+                scan_complete.await;
+            }
+
+            this.update(&mut cx, |this, cx| {
+                this.remap_items_to_worktree(old_worktree_id, &new_worktree, cx)
+            })?
+            .await
+        })
+    }
+
+    /// Prompts to save the dirty items of the given worktree, returning whether the user went
+    /// through all of them rather than cancelling.
+    fn save_worktree_items(
+        &mut self,
+        worktree_id: WorktreeId,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<bool>> {
+        let dirty_items = self
+            .panes
+            .iter()
+            .flat_map(|pane| {
+                pane.read(cx)
+                    .items()
+                    .filter(|item| {
+                        item.is_dirty(cx)
+                            && item.project_path(cx).map_or(false, |project_path| {
+                                project_path.worktree_id == worktree_id
+                            })
+                    })
+                    .map(|item| (pane.downgrade(), item.boxed_clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let project = self.project.clone();
+        cx.spawn(|_, mut cx| async move {
+            for (pane, item) in dirty_items {
+                let Some(ix) =
+                    pane.update(&mut cx, |pane, _| pane.index_for_item(item.as_ref()))?
+                else {
+                    continue;
+                };
+                let saved = Pane::save_item(
+                    project.clone(),
+                    &pane,
+                    ix,
+                    item.as_ref(),
+                    SaveIntent::Close,
+                    &mut cx,
+                )
+                .await?;
+                if !saved {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        })
+    }
+
+    /// Removes a worktree and reopens its items from another worktree that contains the same
+    /// files, keeping their tabs at the same position. The items out of the other worktree are
+    /// reopened from a hidden worktree of their own, as they would be when opened by path.
+    fn remap_items_to_worktree(
+        &mut self,
+        old_worktree_id: WorktreeId,
+        new_worktree: &Model<Worktree>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let (new_worktree_id, new_root) = {
+            let new_worktree = new_worktree.read(cx);
+            (new_worktree.id(), new_worktree.abs_path())
+        };
+        let Some(old_root) = self
+            .project
+            .read(cx)
+            .worktree_for_id(old_worktree_id, cx)
+            .map(|worktree| worktree.read(cx).abs_path())
+        else {
+            return Task::ready(Ok(()));
+        };
+
+        let mut remaps = Vec::new();
+        for pane in &self.panes {
+            for item in pane.read(cx).items() {
+                let Some(project_path) = item.project_path(cx) else {
+                    continue;
+                };
+                if project_path.worktree_id == old_worktree_id {
+                    let abs_path = old_root.join(&project_path.path);
+                    remaps.push((pane.downgrade(), item.item_id(), abs_path));
+                }
+            }
+        }
+        // Removed first, so that the items out of the new root don't resolve to it again.
+        self.project.update(cx, |project, cx| {
+            project.remove_worktree(old_worktree_id, cx)
+        });
+
+        let project = self.project.clone();
+        cx.spawn(|this, mut cx| async move {
+            for (pane, old_item_id, abs_path) in remaps {
+                let project_path = match abs_path.strip_prefix(new_root.as_ref()) {
+                    Ok(path) => ProjectPath {
+                        worktree_id: new_worktree_id,
+                        path: path.into(),
+                    },
+                    Err(_) => {
+                        cx.update(|cx| {
+                            Workspace::project_path_for_path(project.clone(), &abs_path, false, cx)
+                        })?
+                        .await?
+                        .1
+                    }
+                };
+                let (_, build_item) = this
+                    .update(&mut cx, |this, cx| this.load_path(project_path, cx))?
+                    .await?;
+                pane.update(&mut cx, |pane, cx| {
+                    let Some(ix) = pane.items().position(|item| item.item_id() == old_item_id)
+                    else {
+                        return;
+                    };
+                    let active_item_id = pane.active_item().map(|item| item.item_id());
+                    let new_item = build_item(cx);
+                    let new_item_id = new_item.item_id();
+                    pane.add_item(new_item, false, false, Some(ix), cx);
+                    pane.remove_item(old_item_id, false, false, cx);
+
+                    let item_to_activate = if active_item_id == Some(old_item_id) {
+                        Some(new_item_id)
+                    } else {
+                        active_item_id
+                    };
+                    if let Some(ix) = item_to_activate
+                        .and_then(|item_id| pane.items().position(|item| item.item_id() == item_id))
+                    {
+                        pane.activate_item(ix, false, false, cx);
+                    }
+                })?;
+            }
+            Ok(())
         })
-        .detach_and_log_err(cx);
     }
 
+    /// Resolves `abs_path` to a path of `project`, creating a worktree for it if needed. The
+    /// worktrees are created one at a time, so that paths resolved concurrently land in the
+    /// worktrees of their parents rather than in worktrees of their own.
     pub fn project_path_for_path(
         project: Model<Project>,
         abs_path: &Path,
         visible: bool,
         cx: &mut AppContext,
     ) -> Task<Result<(Model<Worktree>, ProjectPath)>> {
-        let entry = project.update(cx, |project, cx| {
-            project.find_or_create_worktree(abs_path, visible, cx)
-        });
+        let entry = worktree_creation::find_or_create_worktree(
+            project,
+            abs_path.to_path_buf(),
+            visible,
+            cx,
+        );
         cx.spawn(|mut cx| async move {
             let (worktree, path) = entry.await?;
             let worktree_id = worktree.update(&mut cx, |t, _| t.id())?;
@@ -2181,9 +3146,15 @@ impl Workspace {
         let item_ix = pane.read(cx).active_item_index();
         let item = pane.read(cx).active_item();
         let pane = pane.downgrade();
+        let workspace = self.weak_self.clone();
 
         cx.spawn(|mut cx| async move {
             if let Some(item) = item {
+                if !workspace.update(&mut cx, |workspace, cx| {
+                    workspace.run_before_save_hooks(item.as_ref(), save_intent, cx)
+                })? {
+                    return Ok(());
+                }
                 Pane::save_item(project, &pane, item_ix, item.as_ref(), save_intent, &mut cx)
                     .await
                     .map(|_| ())
@@ -2272,6 +3243,36 @@ impl Workspace {
     }
 
     pub fn toggle_dock(&mut self, dock_side: DockPosition, cx: &mut ViewContext<Self>) {
+        self.set_dock_open(dock_side, None, cx);
+    }
+
+    /// Whether the dock at the given position is open and not hidden behind a zoomed item.
+    pub fn is_dock_open(&self, dock_side: DockPosition, cx: &AppContext) -> bool {
+        let dock = match dock_side {
+            DockPosition::Left => &self.left_dock,
+            DockPosition::Bottom => &self.bottom_dock,
+            DockPosition::Right => &self.right_dock,
+        };
+        let other_is_zoomed = self.zoomed.is_some()
+            && self.zoomed_position != Some(dock_side)
+            && WorkspaceSettings::get_global(cx).zoom_mode == ZoomMode::Overlay;
+        dock.read(cx).is_open() && !other_is_zoomed
+    }
+
+    /// Opens or closes the dock at the given position, or toggles it when `open` is `None`.
+    pub fn set_dock_open(
+        &mut self,
+        dock_side: DockPosition,
+        open: Option<bool>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let was_visible = self.is_dock_open(dock_side, cx);
+        let open = open.unwrap_or(!was_visible);
+        if open == was_visible {
+            return;
+        }
+        self.side_dock_toggled_by_user(dock_side);
+
         let dock = match dock_side {
             DockPosition::Left => &self.left_dock,
             DockPosition::Bottom => &self.bottom_dock,
@@ -2280,9 +3281,7 @@ impl Workspace {
         let mut focus_center = false;
         let mut reveal_dock = false;
         dock.update(cx, |dock, cx| {
-            let other_is_zoomed = self.zoomed.is_some() && self.zoomed_position != Some(dock_side);
-            let was_visible = dock.is_open() && !other_is_zoomed;
-            dock.set_open(!was_visible, cx);
+            dock.set_open(open, cx);
 
             if let Some(active_panel) = dock.active_panel() {
                 if was_visible {
@@ -2310,6 +3309,7 @@ impl Workspace {
     }
 
     pub fn close_all_docks(&mut self, cx: &mut ViewContext<Self>) {
+        self.side_dock_toggled_by_user(DockPosition::Left);
         let docks = [&self.left_dock, &self.bottom_dock, &self.right_dock];
 
         for dock in docks {
@@ -2458,7 +3458,7 @@ impl Workspace {
         if self.zoomed_position != dock_to_reveal {
             self.zoomed = None;
             self.zoomed_position = None;
-            cx.emit(Event::ZoomChanged);
+            self.emit_zoom_changed(cx);
         }
 
         cx.notify();
@@ -2481,6 +3481,7 @@ impl Workspace {
         self.panes.push(pane.clone());
         cx.focus_view(&pane);
         cx.emit(Event::PaneAdded(pane.clone()));
+        self.schedule_counts_update(cx);
         pane
     }
 
@@ -2508,14 +3509,25 @@ impl Workspace {
         focus_item: bool,
         cx: &mut WindowContext,
     ) {
-        self.add_item(
-            self.active_pane.clone(),
-            item,
-            destination_index,
-            false,
-            focus_item,
-            cx,
-        )
+        let active_pane = self.active_pane.clone();
+        match self.pane_for_item_kind(&active_pane, item.reservation_kind(cx), cx) {
+            Some(pane) if pane == active_pane => {
+                self.add_item(pane, item, destination_index, false, focus_item, cx)
+            }
+            Some(pane) => self.add_item(pane, item, None, false, focus_item, cx),
+            None => {
+                // All panes are reserved for other kinds of items, so make room for this one.
+                let workspace = self.weak_self.clone();
+                cx.defer(move |cx| {
+                    workspace
+                        .update(cx, |workspace, cx| {
+                            let pane = workspace.split_pane(active_pane, SplitDirection::Right, cx);
+                            workspace.add_item(pane, item, None, false, focus_item, cx);
+                        })
+                        .log_err();
+                });
+            }
+        }
     }
 
     pub fn add_item(
@@ -2554,6 +3566,7 @@ impl Workspace {
         visible: bool,
         cx: &mut ViewContext<Self>,
     ) -> Task<anyhow::Result<Box<dyn ItemHandle>>> {
+        let fs = self.app_state.fs.clone();
         cx.spawn(|workspace, mut cx| async move {
             let open_paths_task_result = workspace
                 .update(&mut cx, |workspace, cx| {
@@ -2570,31 +3583,122 @@ impl Workspace {
                 })
                 .with_context(|| format!("open abs path {abs_path:?} task spawn"))?
                 .await;
-            anyhow::ensure!(
-                open_paths_task_result.len() == 1,
-                "open abs path {abs_path:?} task returned incorrect number of results"
-            );
-            match open_paths_task_result
-                .into_iter()
-                .next()
-                .expect("ensured single task result")
-            {
-                Some(open_result) => {
-                    open_result.with_context(|| format!("open abs path {abs_path:?} task join"))
-                }
-                None => anyhow::bail!("open abs path {abs_path:?} task returned None"),
+            let result_count = open_paths_task_result.len();
+            if let Some(item) = item_from_open_results(&abs_path, open_paths_task_result)? {
+                return Ok(item);
+            }
+            if !fs.is_dir(&abs_path).await {
+                anyhow::bail!("open abs path {abs_path:?} task returned None");
+            }
+            // A directory resolving to an existing worktree, e.g. through a symlink, yields no
+            // result at all, so it's revealed here like the directories `open_paths` adds.
+            if result_count == 0 {
+                workspace.update(&mut cx, |workspace, cx| {
+                    workspace.reveal_worktree_entry(&abs_path, cx)
+                })?;
             }
+            Err(OpenError::OpenedAsWorktree { abs_path }.into())
         })
     }
 
-    pub fn split_abs_path(
-        &mut self,
-        abs_path: PathBuf,
-        visible: bool,
-        cx: &mut ViewContext<Self>,
-    ) -> Task<anyhow::Result<Box<dyn ItemHandle>>> {
-        let project_path_task =
-            Workspace::project_path_for_path(self.project.clone(), &abs_path, visible, cx);
+    fn reveal_worktree_entry(&mut self, abs_path: &Path, cx: &mut ViewContext<Self>) {
+        let project = self.project.read(cx);
+        let Some((worktree, relative_path)) = project.find_worktree(abs_path, cx) else {
+            return;
+        };
+        let Some(entry_id) = worktree
+            .read(cx)
+            .entry_for_path(relative_path)
+            .map(|entry| entry.id)
+        else {
+            return;
+        };
+        self.project.update(cx, |_, cx| {
+            cx.emit(project::Event::ActiveEntryChanged(Some(entry_id)));
+        });
+    }
+
+    /// Opens the file at `abs_path` right away, like [`Self::open_abs_path`], and emits
+    /// [`Event::ItemEntryResolved`] once the scan of its worktree has reached it, so that
+    /// features relying on the worktree entry can catch up.
+    pub fn open_path_when_ready(
+        &mut self,
+        abs_path: PathBuf,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<Box<dyn ItemHandle>>> {
+        let open_task = self.open_abs_path(abs_path.clone(), true, cx);
+        cx.spawn(|this, mut cx| async move {
+            let item = open_task.await?;
+            this.update(&mut cx, |this, cx| {
+                this.resolve_item_entry(item.boxed_clone(), &abs_path, cx)
+            })?;
+            Ok(item)
+        })
+    }
+
+    /// Waits for the worktree containing `abs_path` to have an entry for it, giving up
+    /// [`ENTRY_RESOLUTION_TIMEOUT`] after the worktree finished scanning.
+    fn resolve_item_entry(
+        &mut self,
+        item: Box<dyn ItemHandle>,
+        abs_path: &Path,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some((worktree, path)) = self.project.read(cx).find_worktree(abs_path, cx) else {
+            return;
+        };
+        let worktree_id = worktree.read(cx).id();
+        let (updates_tx, mut updates_rx) = mpsc::unbounded();
+        let subscription = cx.subscribe(&self.project, move |_, _, event, _| {
+            if let project::Event::WorktreeUpdatedEntries(id, _) = event {
+                if *id == worktree_id {
+                    updates_tx.unbounded_send(()).ok();
+                }
+            }
+        });
+        let scan_complete = worktree
+            .read(cx)
+            .as_local()
+            .map(|worktree| worktree.scan_complete());
+        let executor = cx.background_executor().clone();
+
+        cx.spawn(|this, mut cx| async move {
+            let _subscription = subscription;
+            let timeout = async move {
+                if let Some(scan_complete) = scan_complete {
+                    scan_complete.await;
+                }
+                executor.timer(ENTRY_RESOLUTION_TIMEOUT).await;
+            }
+            .fuse();
+            futures::pin_mut!(timeout);
+
+            loop {
+                let entry_id = worktree.update(&mut cx, |worktree, _| {
+                    worktree.entry_for_path(&path).map(|entry| entry.id)
+                })?;
+                if let Some(entry_id) = entry_id {
+                    return this.update(&mut cx, |_, cx| {
+                        cx.emit(Event::ItemEntryResolved { item, entry_id })
+                    });
+                }
+                futures::select_biased! {
+                    _ = updates_rx.next() => {}
+                    _ = timeout => return Ok(()),
+                }
+            }
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn split_abs_path(
+        &mut self,
+        abs_path: PathBuf,
+        visible: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<Box<dyn ItemHandle>>> {
+        let project_path_task =
+            Workspace::project_path_for_path(self.project.clone(), &abs_path, visible, cx);
         cx.spawn(|this, mut cx| async move {
             let (_, path) = project_path_task.await?;
             this.update(&mut cx, |this, cx| this.split_path(path, cx))?
@@ -2620,18 +3724,61 @@ impl Workspace {
         allow_preview: bool,
         cx: &mut WindowContext,
     ) -> Task<Result<Box<dyn ItemHandle>, anyhow::Error>> {
+        let mut split_reserved_pane = false;
+        // Locked panes are passed over like the panes reserved for other items.
+        let pane = pane.filter(|pane| {
+            pane.upgrade()
+                .map_or(true, |pane| !pane.read(cx).is_locked())
+        });
+        let open_beside_pinned_item = pane.is_none();
         let pane = pane.unwrap_or_else(|| {
-            self.last_active_center_pane.clone().unwrap_or_else(|| {
+            let pane = self.last_active_center_pane.clone().unwrap_or_else(|| {
                 self.panes
                     .first()
                     .expect("There must be an active pane")
                     .downgrade()
-            })
+            });
+            // Files open in editors, which is what panes reserved for other items are kept clear of.
+            let Some(reserved_pane) = pane.upgrade() else {
+                return pane;
+            };
+            match self.pane_for_item_kind(&reserved_pane, ReservationKind::Editor, cx) {
+                Some(pane) => pane.downgrade(),
+                None => {
+                    split_reserved_pane = true;
+                    pane
+                }
+            }
         });
 
         let task = self.load_path(path.into(), cx);
+        let workspace = self.weak_self.clone();
         cx.spawn(move |mut cx| async move {
-            let (project_entry_id, build_item) = task.await?;
+            let (project_entry_id, build_item) =
+                open_error::notify_open_error(&workspace, task.await, &mut cx)?;
+            let pane = if split_reserved_pane {
+                workspace.update(&mut cx, |workspace, cx| {
+                    let pane = pane.upgrade().context("pane was dropped")?;
+                    anyhow::Ok(
+                        workspace
+                            .split_pane(pane, SplitDirection::Right, cx)
+                            .downgrade(),
+                    )
+                })??
+            } else if open_beside_pinned_item {
+                workspace.update(&mut cx, |workspace, cx| {
+                    let pane = pane.upgrade().context("pane was dropped")?;
+                    // Files already open in the pane are activated there.
+                    if project_entry_id.map_or(false, |entry_id| {
+                        pane.read(cx).item_for_entry(entry_id, cx).is_some()
+                    }) {
+                        return anyhow::Ok(pane.downgrade());
+                    }
+                    anyhow::Ok(workspace.pane_beside_pinned_item(pane, cx).downgrade())
+                })??
+            } else {
+                pane
+            };
             pane.update(&mut cx, |pane, cx| {
                 pane.open_item(
                     project_entry_id,
@@ -2675,7 +3822,8 @@ impl Workspace {
 
         let task = self.load_path(path.into(), cx);
         cx.spawn(|this, mut cx| async move {
-            let (project_entry_id, build_item) = task.await?;
+            let (project_entry_id, build_item) =
+                open_error::notify_open_error(&this, task.await, &mut cx)?;
             this.update(&mut cx, move |this, cx| -> Option<_> {
                 let pane = pane.upgrade()?;
                 let new_pane =
@@ -2695,6 +3843,8 @@ impl Workspace {
         })
     }
 
+    /// Loads the file at `path` with the last registered project item accepting it. Fails with
+    /// an [`OpenError`] when there is none.
     fn load_path(
         &mut self,
         path: ProjectPath,
@@ -2707,7 +3857,7 @@ impl Workspace {
             .rev()
             .find_map(|open_project_item| open_project_item(&project, &path, cx))
         else {
-            return Task::ready(Err(anyhow!("cannot open file {:?}", path.path)));
+            return self.no_handler_error(path, cx);
         };
         open_project_item
     }
@@ -2752,6 +3902,24 @@ impl Workspace {
             .is_some()
     }
 
+    /// Turns the active item into a permanent tab, if it is the active pane's preview item.
+    pub fn promote_active_preview_tab(&mut self, cx: &mut ViewContext<Self>) {
+        let promoted = self.active_pane.update(cx, |pane, cx| {
+            let Some(active_item_id) = pane.active_item().map(|item| item.item_id()) else {
+                return false;
+            };
+            if !pane.is_active_preview_item(active_item_id) {
+                return false;
+            }
+            pane.set_preview_item_id(None, cx);
+            cx.notify();
+            true
+        });
+        if promoted {
+            self.serialize_workspace(cx);
+        }
+    }
+
     pub fn open_project_item<T>(
         &mut self,
         pane: View<Pane>,
@@ -2768,6 +3936,8 @@ impl Workspace {
             return item;
         }
 
+        let pane = self.unlocked_pane_for_new_item(pane, cx);
+        let pane = self.pane_beside_pinned_item(pane, cx);
         let item = cx.new_view(|cx| T::for_project_item(self.project().clone(), project_item, cx));
         let item_id = item.item_id();
         let mut destination_index = None;
@@ -2823,15 +3993,6 @@ impl Workspace {
         }
     }
 
-    fn activate_pane_at_index(&mut self, action: &ActivatePane, cx: &mut ViewContext<Self>) {
-        let panes = self.center.panes();
-        if let Some(pane) = panes.get(action.0).map(|p| (*p).clone()) {
-            cx.focus_view(&pane);
-        } else {
-            self.split_and_clone(self.active_pane.clone(), SplitDirection::Right, cx);
-        }
-    }
-
     pub fn activate_next_pane(&mut self, cx: &mut WindowContext) {
         let panes = self.center.panes();
         if let Some(ix) = panes.iter().position(|pane| **pane == self.active_pane) {
@@ -2975,6 +4136,34 @@ impl Workspace {
         }
     }
 
+    /// Swaps the positions of two center panes, keeping their items and focus.
+    pub fn swap_panes(&mut self, a: View<Pane>, b: View<Pane>, cx: &mut ViewContext<Self>) {
+        if a == b {
+            return;
+        }
+        self.center.swap(&a, &b);
+        self.serialize_workspace(cx);
+        cx.notify();
+    }
+
+    /// Swaps the active pane with the center pane at the given index, counted as by
+    /// [`ActivatePane`].
+    pub fn swap_pane_with_index(&mut self, index: usize, cx: &mut ViewContext<Self>) {
+        let Some(pane) = self.panes_in_activation_order(cx).get(index).cloned() else {
+            struct SwapPaneWithIndexToast;
+            self.show_toast(
+                Toast::new(
+                    NotificationId::unique::<SwapPaneWithIndexToast>(),
+                    format!("There is no pane at index {index}"),
+                )
+                .autohide(),
+                cx,
+            );
+            return;
+        };
+        self.swap_panes(self.active_pane.clone(), pane, cx);
+    }
+
     pub fn resize_pane(&mut self, axis: gpui::Axis, amount: Pixels, cx: &mut ViewContext<Self>) {
         self.center
             .resize(&self.active_pane.clone(), axis, amount, &self.bounds);
@@ -3001,25 +4190,28 @@ impl Workspace {
         }
 
         self.dismiss_zoomed_items_to_reveal(None, cx);
+        let zoomed_before = (self.zoomed.clone(), self.zoomed_position);
         if pane.read(cx).is_zoomed() {
             self.zoomed = Some(pane.downgrade().into());
         } else {
             self.zoomed = None;
         }
         self.zoomed_position = None;
-        cx.emit(Event::ZoomChanged);
-        self.update_active_view_for_followers(cx);
-        pane.model.update(cx, |pane, _| {
-            pane.track_alternate_file_items();
-        });
+        self.finalize_item_activation(&pane, ItemActivationOrigin::Focus, cx);
+        if (self.zoomed.clone(), self.zoomed_position) != zoomed_before {
+            self.emit_zoom_changed(cx);
+        }
 
         cx.notify();
     }
 
     fn set_active_pane(&mut self, pane: &View<Pane>, cx: &mut ViewContext<Self>) {
         self.active_pane = pane.clone();
-        self.active_item_path_changed(cx);
+        self.report_active_item_changed(cx);
         self.last_active_center_pane = Some(pane.downgrade());
+        if self.pane_reserved_for(pane).is_none() && !pane.read(cx).is_locked() {
+            self.last_active_unreserved_pane = Some(pane.downgrade());
+        }
     }
 
     fn handle_panel_focused(&mut self, cx: &mut ViewContext<Self>) {
@@ -3032,12 +4224,17 @@ impl Workspace {
         event: &pane::Event,
         cx: &mut ViewContext<Self>,
     ) {
+        self.schedule_counts_update(cx);
         match event {
             pane::Event::AddItem { item } => {
                 item.added_to_pane(self, pane, cx);
-                cx.emit(Event::ItemAdded {
-                    item: item.boxed_clone(),
-                });
+                self.assign_stable_item_id(item.as_ref());
+                self.emit_batchable_event(
+                    Event::ItemAdded {
+                        item: item.boxed_clone(),
+                    },
+                    cx,
+                );
             }
             pane::Event::Split(direction) => {
                 self.split_and_clone(pane, *direction, cx);
@@ -3045,44 +4242,55 @@ impl Workspace {
             pane::Event::JoinIntoNext => self.join_pane_into_next(pane, cx),
             pane::Event::JoinAll => self.join_all_panes(cx),
             pane::Event::Remove { focus_on_pane } => {
-                self.remove_pane(pane, focus_on_pane.clone(), cx)
+                self.remove_pane_adopting_items(pane, focus_on_pane.clone(), cx)
             }
             pane::Event::ActivateItem { local } => {
                 cx.on_next_frame(|_, cx| {
                     cx.invalidate_character_coordinates();
                 });
-
-                pane.model.update(cx, |pane, _| {
-                    pane.track_alternate_file_items();
+                self.finalize_item_activation(
+                    &pane,
+                    ItemActivationOrigin::Pane { local: *local },
+                    cx,
+                );
+            }
+            pane::Event::UserSavedItem {
+                item,
+                save_intent,
+                project_path,
+            } => {
+                self.discard_item_recovery(item.id(), cx);
+                cx.emit(Event::UserSavedItem {
+                    pane: pane.downgrade(),
+                    item: item.boxed_clone(),
+                    save_intent: *save_intent,
+                    project_path: project_path.clone(),
                 });
-                if *local {
-                    self.unfollow_in_pane(&pane, cx);
-                }
-                if &pane == self.active_pane() {
-                    self.active_item_path_changed(cx);
-                    self.update_active_view_for_followers(cx);
-                }
             }
-            pane::Event::UserSavedItem { item, save_intent } => cx.emit(Event::UserSavedItem {
-                pane: pane.downgrade(),
-                item: item.boxed_clone(),
-                save_intent: *save_intent,
-            }),
-            pane::Event::ChangeItemTitle => {
-                if pane == self.active_pane {
-                    self.active_item_path_changed(cx);
+            pane::Event::ChangeItemTitle { item_id } => {
+                // The tabs of the pane are re-rendered by the pane itself, so only the title
+                // of the active item affects the workspace.
+                let is_active_item = pane == self.active_pane
+                    && pane
+                        .read(cx)
+                        .active_item()
+                        .map_or(false, |item| item.item_id() == *item_id);
+                if is_active_item {
+                    self.update_active_path(cx);
+                    self.schedule_window_title_update(cx);
                 }
                 self.update_window_edited(cx);
             }
             pane::Event::RemoveItem { .. } => {}
             pane::Event::RemovedItem { item_id } => {
-                cx.emit(Event::ActiveItemChanged);
+                self.report_active_item_changed(cx);
                 self.update_window_edited(cx);
                 if let hash_map::Entry::Occupied(entry) = self.panes_by_item.entry(*item_id) {
                     if entry.get().entity_id() == pane.entity_id() {
                         entry.remove();
                     }
                 }
+                self.forget_stable_item_id(*item_id, cx);
             }
             pane::Event::Focus => {
                 cx.on_next_frame(|_, cx| {
@@ -3096,7 +4304,7 @@ impl Workspace {
                     if pane.read(cx).has_focus(cx) {
                         self.zoomed = Some(pane.downgrade().into());
                         self.zoomed_position = None;
-                        cx.emit(Event::ZoomChanged);
+                        self.emit_zoom_changed(cx);
                     }
                     cx.notify();
                 }
@@ -3105,7 +4313,7 @@ impl Workspace {
                 pane.update(cx, |pane, cx| pane.set_zoomed(false, cx));
                 if self.zoomed_position.is_none() {
                     self.zoomed = None;
-                    cx.emit(Event::ZoomChanged);
+                    self.emit_zoom_changed(cx);
                 }
                 cx.notify();
             }
@@ -3225,12 +4433,27 @@ impl Workspace {
         cx.notify();
     }
 
+    /// Gives all panes and splits of the axis that directly contains `pane` the same size.
+    pub fn rebalance_axis_containing(&mut self, pane: &View<Pane>, cx: &mut ViewContext<Self>) {
+        if self.center.rebalance_axis_containing(pane) {
+            self.serialize_workspace(cx);
+            cx.notify();
+        }
+    }
+
+    /// Removes a pane along with the items still in it, which are dropped without going
+    /// through the close flow. See [`Workspace::remove_pane_adopting_items`] to keep them.
     fn remove_pane(
         &mut self,
         pane: View<Pane>,
         focus_on: Option<View<Pane>>,
         cx: &mut ViewContext<Self>,
     ) {
+        let rebalanced_sibling = if WorkspaceSettings::get_global(cx).rebalance_on_pane_close {
+            self.center.sibling_pane(&pane).cloned()
+        } else {
+            None
+        };
         if self.center.remove(&pane).unwrap() {
             self.force_remove_pane(&pane, &focus_on, cx);
             self.unfollow_in_pane(&pane, cx);
@@ -3238,6 +4461,9 @@ impl Workspace {
             for removed_item in pane.read(cx).items() {
                 self.panes_by_item.remove(&removed_item.item_id());
             }
+            if let Some(sibling) = rebalanced_sibling {
+                self.rebalance_axis_containing(&sibling, cx);
+            }
 
             cx.notify();
         } else {
@@ -3282,6 +4508,14 @@ impl Workspace {
     }
 
     fn collaborator_left(&mut self, peer_id: PeerId, cx: &mut ViewContext<Self>) {
+        if self.follow_window_leader == Some(peer_id) {
+            self.notify_follow_window_ended(cx);
+        }
+        self.remove_followers_of_project(peer_id, cx);
+        let leader_pane = self
+            .follower_states
+            .get(&peer_id)
+            .map(|state| state.center_pane.clone());
         self.follower_states.retain(|leader_id, state| {
             if *leader_id == peer_id {
                 for item in state.items_by_leader_view_id.values() {
@@ -3292,6 +4526,9 @@ impl Workspace {
                 true
             }
         });
+        if let Some(pane) = leader_pane {
+            self.remove_follow_all_pane(pane, cx);
+        }
         cx.notify();
     }
 
@@ -3301,11 +4538,23 @@ impl Workspace {
         cx: &mut ViewContext<Self>,
     ) -> Option<Task<Result<()>>> {
         let pane = self.active_pane().clone();
+        self.start_following_in_pane(leader_id, pane, cx)
+    }
 
+    fn start_following_in_pane(
+        &mut self,
+        leader_id: PeerId,
+        pane: View<Pane>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
         self.last_leaders_by_pane
             .insert(pane.downgrade(), leader_id);
-        self.unfollow(leader_id, cx);
-        self.unfollow_in_pane(&pane, cx);
+        // Following again isn't unfollowing, so the leader of a follow window is kept.
+        cx.notify();
+        self.stop_following(leader_id, cx);
+        if let Some(pane_leader_id) = self.leader_for_pane(&pane) {
+            self.stop_following(pane_leader_id, cx);
+        }
         self.follower_states.insert(
             leader_id,
             FollowerState {
@@ -3313,6 +4562,8 @@ impl Workspace {
                 dock_pane: None,
                 active_view_id: None,
                 items_by_leader_view_id: Default::default(),
+                queued_updates: 0,
+                last_update_at: cx.background_executor().now(),
             },
         );
         cx.notify();
@@ -3351,30 +4602,27 @@ impl Workspace {
         _: &FollowNextCollaborator,
         cx: &mut ViewContext<Self>,
     ) {
-        let collaborators = self.project.read(cx).collaborators();
-        let next_leader_id = if let Some(leader_id) = self.leader_for_pane(&self.active_pane) {
-            let mut collaborators = collaborators.keys().copied();
-            for peer_id in collaborators.by_ref() {
-                if peer_id == leader_id {
-                    break;
-                }
-            }
-            collaborators.next()
-        } else if let Some(last_leader_id) =
-            self.last_leaders_by_pane.get(&self.active_pane.downgrade())
-        {
-            if collaborators.contains_key(last_leader_id) {
-                Some(*last_leader_id)
-            } else {
-                None
-            }
+        let collaborators = self.followable_collaborators(cx);
+        let pane = self.active_pane.clone();
+        let current_position = self.leader_for_pane(&pane).and_then(|leader_id| {
+            collaborators
+                .iter()
+                .position(|(peer_id, _)| *peer_id == leader_id)
+        });
+        let next_leader = if let Some(position) = current_position {
+            collaborators.get((position + 1) % collaborators.len())
         } else {
-            None
+            self.last_leaders_by_pane
+                .get(&pane.downgrade())
+                .and_then(|last_leader_id| {
+                    collaborators
+                        .iter()
+                        .find(|(peer_id, _)| peer_id == last_leader_id)
+                })
+                .or_else(|| collaborators.first())
         };
 
-        let pane = self.active_pane.clone();
-        let Some(leader_id) = next_leader_id.or_else(|| collaborators.keys().copied().next())
-        else {
+        let Some((leader_id, github_login)) = next_leader.cloned() else {
             return;
         };
         if self.unfollow_in_pane(&pane, cx) == Some(leader_id) {
@@ -3383,6 +4631,55 @@ impl Workspace {
         if let Some(task) = self.start_following(leader_id, cx) {
             task.detach_and_log_err(cx)
         }
+
+        struct FollowingToast;
+        self.show_toast(
+            Toast::new(
+                NotificationId::unique::<FollowingToast>(),
+                format!("Following {github_login}"),
+            )
+            .autohide(),
+            cx,
+        );
+    }
+
+    /// Returns the project collaborators that are worth following, ordered by their
+    /// participant index, so that cycling through them is deterministic. Collaborators
+    /// that are not in this workspace's shared project have no views to follow here, and are
+    /// skipped.
+    fn followable_collaborators(&self, cx: &AppContext) -> Vec<(PeerId, SharedString)> {
+        let Some(room) = self.active_call().and_then(|call| call.read(cx).room()) else {
+            return Vec::new();
+        };
+        let room = room.read(cx);
+        let project = self.project.read(cx);
+        let remote_id = project.remote_id();
+        let mut collaborators = project
+            .collaborators()
+            .keys()
+            .filter_map(|peer_id| {
+                let participant = room.remote_participant_for_peer_id(*peer_id)?;
+                match participant.location {
+                    call::ParticipantLocation::SharedProject { project_id }
+                        if Some(project_id) == remote_id =>
+                    {
+                        Some((
+                            participant.participant_index.0,
+                            *peer_id,
+                            SharedString::from(participant.user.github_login.clone()),
+                        ))
+                    }
+                    call::ParticipantLocation::SharedProject { .. }
+                    | call::ParticipantLocation::UnsharedProject
+                    | call::ParticipantLocation::External => None,
+                }
+            })
+            .collect::<Vec<_>>();
+        collaborators.sort_unstable_by_key(|(participant_index, _, _)| *participant_index);
+        collaborators
+            .into_iter()
+            .map(|(_, peer_id, github_login)| (peer_id, github_login))
+            .collect()
     }
 
     pub fn follow(&mut self, leader_id: PeerId, cx: &mut ViewContext<Self>) {
@@ -3429,6 +4726,25 @@ impl Workspace {
 
     pub fn unfollow(&mut self, leader_id: PeerId, cx: &mut ViewContext<Self>) -> Option<()> {
         cx.notify();
+        // A follow window stops being one once its leader is unfollowed.
+        if self.follow_window_leader == Some(leader_id) {
+            self.follow_window_leader = None;
+        }
+        self.stop_following(leader_id, cx)
+    }
+
+    /// Stops following every collaborator this workspace is following, and removes the panes
+    /// split off by [`FollowAllCollaborators`].
+    pub fn unfollow_all(&mut self, cx: &mut ViewContext<Self>) {
+        let leader_ids = self.follower_states.keys().copied().collect::<Vec<_>>();
+        for leader_id in leader_ids {
+            self.stop_following(leader_id, cx);
+        }
+        self.collapse_follow_all_panes(cx);
+        cx.notify();
+    }
+
+    fn stop_following(&mut self, leader_id: PeerId, cx: &mut ViewContext<Self>) -> Option<()> {
         let state = self.follower_states.remove(&leader_id)?;
         for (_, item) in state.items_by_leader_view_id {
             item.view.set_leader_peer_id(None, cx);
@@ -3453,15 +4769,52 @@ impl Workspace {
     }
 
     fn active_item_path_changed(&mut self, cx: &mut ViewContext<Self>) {
-        cx.emit(Event::ActiveItemChanged);
+        self.update_active_path(cx);
+        self.update_window_title(cx);
+    }
+
+    fn update_active_path(&mut self, cx: &mut ViewContext<Self>) {
+        self.emit_batchable_event(Event::ActiveItemChanged, cx);
         let active_entry = self.active_project_path(cx);
         self.project
             .update(cx, |project, cx| project.set_active_path(active_entry, cx));
-
-        self.update_window_title(cx);
     }
 
     fn update_window_title(&mut self, cx: &mut WindowContext) {
+        let title = self.window_title(cx);
+        cx.set_window_title(&title);
+    }
+
+    /// Updates the window title for a change of the active item's title, at most once per
+    /// [`WINDOW_TITLE_UPDATE_INTERVAL`], as some items, e.g. terminals, change their title
+    /// continuously. The last change is always reflected.
+    fn schedule_window_title_update(&mut self, cx: &mut ViewContext<Self>) {
+        if self.pending_window_title_update.is_some() {
+            return;
+        }
+        let now = cx.background_executor().now();
+        let next_update_at = self
+            .last_window_title_update
+            .map(|last_update| last_update + WINDOW_TITLE_UPDATE_INTERVAL)
+            .filter(|next_update_at| *next_update_at > now);
+        let Some(next_update_at) = next_update_at else {
+            self.last_window_title_update = Some(now);
+            self.update_window_title(cx);
+            return;
+        };
+        self.pending_window_title_update = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(next_update_at - now).await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_window_title_update = None;
+                this.last_window_title_update = Some(cx.background_executor().now());
+                this.update_window_title(cx);
+            })
+            .ok();
+        }));
+    }
+
+    /// The title of the workspace's window, naming the project and the active item.
+    pub fn window_title(&self, cx: &AppContext) -> String {
         let project = self.project().read(cx);
         let mut title = String::new();
 
@@ -3493,6 +4846,9 @@ impl Workspace {
             if let Some(filename) = filename {
                 title.push_str(" — ");
                 title.push_str(filename.as_ref());
+                if self.scm_status(&path, cx) == Some(ScmStatus::Conflicted) {
+                    title.push_str(" (conflicted)");
+                }
             }
         }
 
@@ -3501,14 +4857,14 @@ impl Workspace {
         } else if project.is_shared() {
             title.push_str(" ↗");
         }
-
-        cx.set_window_title(&title);
+        title
     }
 
     fn update_window_edited(&mut self, cx: &mut WindowContext) {
         let is_edited = !self.project.read(cx).is_disconnected(cx)
             && self
                 .items(cx)
+                .filter(|item| !item.is_ephemeral(cx))
                 .any(|item| item.has_conflict(cx) || item.is_dirty(cx));
         if is_edited != self.window_edited {
             self.window_edited = is_edited;
@@ -3516,12 +4872,25 @@ impl Workspace {
         }
     }
 
-    fn render_notifications(&self, _cx: &ViewContext<Self>) -> Option<Div> {
+    fn render_notifications(&self, cx: &ViewContext<Self>) -> Option<impl IntoElement> {
         if self.notifications.is_empty() {
             None
         } else {
             Some(
                 div()
+                    .key_context("Notifications")
+                    .track_focus(&self.notification_focus.focus_handle)
+                    .on_action(
+                        cx.listener(|this, _: &menu::SelectNext, cx| {
+                            this.select_notification(1, cx)
+                        }),
+                    )
+                    .on_action(cx.listener(|this, _: &menu::SelectPrev, cx| {
+                        this.select_notification(-1, cx)
+                    }))
+                    .on_action(cx.listener(|this, _: &menu::Cancel, cx| {
+                        this.return_focus_from_notifications(cx)
+                    }))
                     .absolute()
                     .right_3()
                     .bottom_3()
@@ -3531,11 +4900,15 @@ impl Workspace {
                     .flex_col()
                     .justify_end()
                     .gap_2()
-                    .children(
-                        self.notifications
-                            .iter()
-                            .map(|(_, notification)| notification.to_any()),
-                    ),
+                    .children(self.notifications.iter().map(|(id, notification)| {
+                        div()
+                            .rounded_md()
+                            .when(self.is_notification_focused(id, cx), |this| {
+                                this.border_2()
+                                    .border_color(cx.theme().colors().border_focused)
+                            })
+                            .child(notification.to_any())
+                    })),
             )
         }
     }
@@ -3592,13 +4965,45 @@ impl Workspace {
         &mut self,
         leader_id: PeerId,
         message: proto::UpdateFollowers,
-        _cx: &mut ViewContext<Self>,
+        cx: &mut ViewContext<Self>,
     ) {
+        if self.leader_updates_tx.is_closed() {
+            log::error!("leader updates stopped being applied, restarting them");
+            self.restart_leader_updates(cx);
+        }
+        if let Some(state) = self.follower_states.get_mut(&leader_id) {
+            state.queued_updates += 1;
+        }
         self.leader_updates_tx
             .unbounded_send((leader_id, message))
             .ok();
     }
 
+    /// All leader updates are enqueued and then processed in a single task, so
+    /// that each asynchronous operation can be run in order.
+    fn apply_leader_updates(
+        cx: &mut ViewContext<Self>,
+    ) -> (
+        mpsc::UnboundedSender<(PeerId, proto::UpdateFollowers)>,
+        Task<Result<()>>,
+    ) {
+        let (leader_updates_tx, mut leader_updates_rx) =
+            mpsc::unbounded::<(PeerId, proto::UpdateFollowers)>();
+        let apply_leader_updates = cx.spawn(|this, mut cx| async move {
+            while let Some((leader_id, update)) = leader_updates_rx.next().await {
+                Self::process_leader_update(&this, leader_id, update, &mut cx)
+                    .await
+                    .log_err();
+                this.update(&mut cx, |this, cx| {
+                    this.leader_update_processed(leader_id, cx)
+                })?;
+            }
+
+            Ok(())
+        });
+        (leader_updates_tx, apply_leader_updates)
+    }
+
     async fn process_leader_update(
         this: &WeakView<Self>,
         leader_id: PeerId,
@@ -3685,13 +5090,9 @@ impl Workspace {
         let id = ViewId::from_proto(id)?;
         let panel_id = view.panel_id.and_then(proto::PanelId::from_i32);
 
-        let pane = this.update(cx, |this, _cx| {
-            let state = this
-                .follower_states
-                .get(&leader_id)
-                .context("stopped following")?;
-            anyhow::Ok(state.pane().clone())
-        })??;
+        let pane = this
+            .update(cx, |this, _| this.pane_for_leader(leader_id))?
+            .context("stopped following")?;
         let existing_item = pane.update(cx, |pane, cx| {
             let client = this.read(cx).client().clone();
             pane.items().find_map(|item| {
@@ -3722,6 +5123,12 @@ impl Workspace {
             };
 
             let mut new_item = task.await?;
+
+            // Panes may have been removed or rearranged while the view was being constructed.
+            let Some(pane) = this.update(cx, |this, _| this.pane_for_leader(leader_id))? else {
+                cx.update(|cx| new_item.set_leader_peer_id(None, cx))?;
+                return Ok(());
+            };
             pane.update(cx, |pane, cx| {
                 let mut item_to_remove = None;
                 for (ix, item) in pane.items().enumerate() {
@@ -3874,6 +5281,17 @@ impl Workspace {
         })
     }
 
+    /// The pane showing the views of the given leader, unless it is no longer part of the layout.
+    fn pane_for_leader(&self, leader_id: PeerId) -> Option<View<Pane>> {
+        let state = self.follower_states.get(&leader_id)?;
+        if let Some(dock_pane) = &state.dock_pane {
+            return Some(dock_pane.clone());
+        }
+        self.panes
+            .contains(&state.center_pane)
+            .then(|| state.center_pane.clone())
+    }
+
     fn leader_updated(&mut self, leader_id: PeerId, cx: &mut ViewContext<Self>) -> Option<()> {
         cx.notify();
 
@@ -3898,6 +5316,10 @@ impl Workspace {
             }
         };
 
+        if self.pane_for_leader(leader_id).is_none() {
+            self.unfollow(leader_id, cx);
+            return None;
+        }
         let state = self.follower_states.get(&leader_id)?;
         let mut item_to_activate = None;
         if let (Some(active_view_id), true) = (state.active_view_id, leader_in_this_app) {
@@ -3928,20 +5350,31 @@ impl Workspace {
             }
         }
 
+        self.place_leader_item(&pane, item, transfer_focus, cx);
+        None
+    }
+
+    /// Shows the active item of a followed collaborator in `pane`, adding it to the pane when
+    /// it isn't there yet.
+    fn place_leader_item(
+        &mut self,
+        pane: &View<Pane>,
+        item: Box<dyn ItemHandle>,
+        transfer_focus: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
         pane.update(cx, |pane, cx| {
             let focus_active_item = pane.has_focus(cx) || transfer_focus;
             if let Some(index) = pane.index_for_item(item.as_ref()) {
                 pane.activate_item(index, false, false, cx);
             } else {
-                pane.add_item(item.boxed_clone(), false, false, None, cx)
+                pane.add_item(item, false, false, None, cx)
             }
 
             if focus_active_item {
                 pane.focus_active_item(cx)
             }
         });
-
-        None
     }
 
     #[cfg(target_os = "windows")]
@@ -3995,7 +5428,8 @@ impl Workspace {
                         if matches!(
                             item.workspace_settings(cx).autosave,
                             AutosaveSetting::OnWindowChange | AutosaveSetting::OnFocusChange
-                        ) {
+                        ) && !self.external_update_locks.skip_autosave(item.as_ref(), cx)
+                        {
                             Pane::autosave_item(item.as_ref(), self.project.clone(), cx)
                                 .detach_and_log_err(cx);
                         }
@@ -4020,6 +5454,12 @@ impl Workspace {
             | call::room::Event::RemoteVideoTracksChanged { participant_id } => {
                 self.leader_updated(*participant_id, cx);
             }
+            call::room::Event::RoomLeft { .. } => {
+                if self.follow_window_leader.is_some() {
+                    self.notify_follow_window_ended(cx);
+                }
+                self.clear_followers(cx);
+            }
             _ => {}
         }
     }
@@ -4043,6 +5483,27 @@ impl Workspace {
         }
     }
 
+    /// Reopens the workspace in its window, restoring what was stored for it. Workspaces with
+    /// unsaved items are kept as they are.
+    fn reopen_restored(&mut self, cx: &mut ViewContext<Self>) {
+        if self.items(cx).any(|item| item.is_dirty(cx)) {
+            return;
+        }
+        let Some(paths) = self.local_paths(cx) else {
+            return;
+        };
+        let window = cx.window_handle().downcast::<Self>();
+        Workspace::new_local(
+            paths.iter().map(|path| path.to_path_buf()).collect(),
+            self.app_state.clone(),
+            window,
+            None,
+            None,
+            cx,
+        )
+        .detach_and_log_err(cx);
+    }
+
     fn remove_panes(&mut self, member: Member, cx: &mut ViewContext<Workspace>) {
         match member {
             Member::Axis(PaneAxis { members, .. }) => {
@@ -4058,9 +5519,14 @@ impl Workspace {
 
     fn remove_from_session(&mut self, cx: &mut WindowContext) -> Task<()> {
         self.session_id.take();
+        // This writes the whole workspace, including the changes still being debounced.
+        self._schedule_serialize.take();
         self.serialize_workspace_internal(cx)
     }
 
+    /// Forgets a pane that's no longer part of the center, without looking at its items. This is
+    /// what restoring a workspace uses, as it captured the items already; other callers should
+    /// go through [`Workspace::remove_pane`].
     fn force_remove_pane(
         &mut self,
         pane: &View<Pane>,
@@ -4079,11 +5545,18 @@ impl Workspace {
         if self.last_active_center_pane == Some(pane.downgrade()) {
             self.last_active_center_pane = None;
         }
+        if self.last_active_unreserved_pane == Some(pane.downgrade()) {
+            self.last_active_unreserved_pane = None;
+        }
+        self.pane_reservations.remove(&pane.downgrade());
+        if self.is_mirror_pane(pane) {
+            self.mirror_pane = None;
+        }
         cx.notify();
     }
 
     fn serialize_workspace(&mut self, cx: &mut ViewContext<Self>) {
-        if self._schedule_serialize.is_none() {
+        if self._schedule_serialize.is_none() && !self.serialization_released {
             self._schedule_serialize = Some(cx.spawn(|this, mut cx| async move {
                 cx.background_executor()
                     .timer(Duration::from_millis(100))
@@ -4097,12 +5570,71 @@ impl Workspace {
         }
     }
 
-    fn serialize_workspace_internal(&self, cx: &mut WindowContext) -> Task<()> {
-        let Some(database_id) = self.database_id() else {
-            return Task::ready(());
-        };
+    fn build_serialized_docks(&self, cx: &mut WindowContext) -> DockStructure {
+        let left_dock = self.left_dock.read(cx);
+        // The docks hidden while the window is narrow are restored open.
+        let left_visible = left_dock.is_open() || self.dock_auto_hide.is_hidden(DockPosition::Left);
+        let left_active_panel = left_dock
+            .active_panel()
+            .map(|panel| panel.persistent_name().to_string());
+        let left_dock_zoom = left_dock
+            .active_panel()
+            .map(|panel| panel.is_zoomed(cx))
+            .unwrap_or(false);
+        let left_dock_focused = left_dock.focus_handle(cx).contains_focused(cx);
+
+        let right_dock = self.right_dock.read(cx);
+        let right_visible =
+            right_dock.is_open() || self.dock_auto_hide.is_hidden(DockPosition::Right);
+        let right_active_panel = right_dock
+            .active_panel()
+            .map(|panel| panel.persistent_name().to_string());
+        let right_dock_zoom = right_dock
+            .active_panel()
+            .map(|panel| panel.is_zoomed(cx))
+            .unwrap_or(false);
+        let right_dock_focused = right_dock.focus_handle(cx).contains_focused(cx);
+
+        let bottom_dock = self.bottom_dock.read(cx);
+        let bottom_visible = bottom_dock.is_open();
+        let bottom_active_panel = bottom_dock
+            .active_panel()
+            .map(|panel| panel.persistent_name().to_string());
+        let bottom_dock_zoom = bottom_dock
+            .active_panel()
+            .map(|panel| panel.is_zoomed(cx))
+            .unwrap_or(false);
+        let bottom_dock_focused = bottom_dock.focus_handle(cx).contains_focused(cx);
+
+        DockStructure {
+            left: DockData {
+                visible: left_visible,
+                active_panel: left_active_panel,
+                zoom: left_dock_zoom,
+                focused: left_dock_focused,
+            },
+            right: DockData {
+                visible: right_visible,
+                active_panel: right_active_panel,
+                zoom: right_dock_zoom,
+                focused: right_dock_focused,
+            },
+            bottom: DockData {
+                visible: bottom_visible,
+                active_panel: bottom_active_panel,
+                zoom: bottom_dock_zoom,
+                focused: bottom_dock_focused,
+            },
+        }
+    }
 
-        fn serialize_pane_handle(pane_handle: &View<Pane>, cx: &WindowContext) -> SerializedPane {
+    fn build_serialized_center_group(&self, cx: &WindowContext) -> SerializedPaneGroup {
+        fn serialize_pane_handle(
+            pane_handle: &View<Pane>,
+            reservations: &HashMap<WeakView<Pane>, ReservationKind>,
+            stable_item_ids: &HashMap<EntityId, StableItemId>,
+            cx: &WindowContext,
+        ) -> SerializedPane {
             let (items, active, pinned_count) = {
                 let pane = pane_handle.read(cx);
                 let active_item_id = pane.active_item().map(|item| item.item_id());
@@ -4116,6 +5648,8 @@ impl Workspace {
                                 item_id: handle.item_id().as_u64(),
                                 active: Some(handle.item_id()) == active_item_id,
                                 preview: pane.is_active_preview_item(handle.item_id()),
+                                navigation_data: handle.navigation_data(cx),
+                                stable_id: stable_item_ids.get(&handle.item_id()).copied(),
                             })
                         })
                         .collect::<Vec<_>>(),
@@ -4124,11 +5658,18 @@ impl Workspace {
                 )
             };
 
-            SerializedPane::new(items, active, pinned_count)
+            SerializedPane {
+                reserved_for: reservations.get(&pane_handle.downgrade()).copied(),
+                tab_bar_visibility: pane_handle.read(cx).tab_bar_visibility(),
+                locked: pane_handle.read(cx).is_locked(),
+                ..SerializedPane::new(items, active, pinned_count)
+            }
         }
 
         fn build_serialized_pane_group(
             pane_group: &Member,
+            reservations: &HashMap<WeakView<Pane>, ReservationKind>,
+            stable_item_ids: &HashMap<EntityId, StableItemId>,
             cx: &WindowContext,
         ) -> SerializedPaneGroup {
             match pane_group {
@@ -4141,67 +5682,31 @@ impl Workspace {
                     axis: SerializedAxis(*axis),
                     children: members
                         .iter()
-                        .map(|member| build_serialized_pane_group(member, cx))
+                        .map(|member| {
+                            build_serialized_pane_group(member, reservations, stable_item_ids, cx)
+                        })
                         .collect::<Vec<_>>(),
                     flexes: Some(flexes.lock().clone()),
                 },
-                Member::Pane(pane_handle) => {
-                    SerializedPaneGroup::Pane(serialize_pane_handle(pane_handle, cx))
-                }
+                Member::Pane(pane_handle) => SerializedPaneGroup::Pane(serialize_pane_handle(
+                    pane_handle,
+                    reservations,
+                    stable_item_ids,
+                    cx,
+                )),
             }
         }
 
-        fn build_serialized_docks(this: &Workspace, cx: &mut WindowContext) -> DockStructure {
-            let left_dock = this.left_dock.read(cx);
-            let left_visible = left_dock.is_open();
-            let left_active_panel = left_dock
-                .active_panel()
-                .map(|panel| panel.persistent_name().to_string());
-            let left_dock_zoom = left_dock
-                .active_panel()
-                .map(|panel| panel.is_zoomed(cx))
-                .unwrap_or(false);
-
-            let right_dock = this.right_dock.read(cx);
-            let right_visible = right_dock.is_open();
-            let right_active_panel = right_dock
-                .active_panel()
-                .map(|panel| panel.persistent_name().to_string());
-            let right_dock_zoom = right_dock
-                .active_panel()
-                .map(|panel| panel.is_zoomed(cx))
-                .unwrap_or(false);
-
-            let bottom_dock = this.bottom_dock.read(cx);
-            let bottom_visible = bottom_dock.is_open();
-            let bottom_active_panel = bottom_dock
-                .active_panel()
-                .map(|panel| panel.persistent_name().to_string());
-            let bottom_dock_zoom = bottom_dock
-                .active_panel()
-                .map(|panel| panel.is_zoomed(cx))
-                .unwrap_or(false);
-
-            DockStructure {
-                left: DockData {
-                    visible: left_visible,
-                    active_panel: left_active_panel,
-                    zoom: left_dock_zoom,
-                },
-                right: DockData {
-                    visible: right_visible,
-                    active_panel: right_active_panel,
-                    zoom: right_dock_zoom,
-                },
-                bottom: DockData {
-                    visible: bottom_visible,
-                    active_panel: bottom_active_panel,
-                    zoom: bottom_dock_zoom,
-                },
-            }
-        }
+        build_serialized_pane_group(
+            &self.center.root,
+            &self.pane_reservations,
+            &self.stable_item_ids,
+            cx,
+        )
+    }
 
-        let location = if let Some(ssh_project) = &self.serialized_ssh_project {
+    fn serialized_location(&self, cx: &AppContext) -> Option<SerializedWorkspaceLocation> {
+        if let Some(ssh_project) = &self.serialized_ssh_project {
             Some(SerializedWorkspaceLocation::Ssh(ssh_project.clone()))
         } else if let Some(local_paths) = self.local_paths(cx) {
             if !local_paths.is_empty() {
@@ -4211,11 +5716,25 @@ impl Workspace {
             }
         } else {
             None
+        }
+    }
+
+    fn serialize_workspace_internal(&self, cx: &mut WindowContext) -> Task<()> {
+        debug_assert!(
+            !self.serialization_released,
+            "workspace serialized after it was released"
+        );
+        let Some(database_id) = self.database_id() else {
+            return Task::ready(());
         };
+        // Writing to a database migrated by a newer version of Zed could corrupt its data.
+        if *persistence::NEWER_SCHEMA {
+            return Task::ready(());
+        }
 
-        if let Some(location) = location {
-            let center_group = build_serialized_pane_group(&self.center.root, cx);
-            let docks = build_serialized_docks(self, cx);
+        if let Some(location) = self.serialized_location(cx) {
+            let center_group = self.build_serialized_center_group(cx);
+            let docks = self.build_serialized_docks(cx);
             let window_bounds = Some(SerializedWindowBounds(cx.window_bounds()));
             let serialized_workspace = SerializedWorkspace {
                 id: database_id,
@@ -4224,7 +5743,8 @@ impl Workspace {
                 window_bounds,
                 display: Default::default(),
                 docks,
-                centered_layout: self.centered_layout,
+                centered_layout: self.persisted_centered_layout(),
+                session_notes: self.session_notes.clone(),
                 session_id: self.session_id.clone(),
                 window_id: Some(cx.window_handle().window_id().as_u64()),
             };
@@ -4233,43 +5753,6 @@ impl Workspace {
         Task::ready(())
     }
 
-    async fn serialize_items(
-        this: &WeakView<Self>,
-        items_rx: UnboundedReceiver<Box<dyn SerializableItemHandle>>,
-        cx: &mut AsyncWindowContext,
-    ) -> Result<()> {
-        const CHUNK_SIZE: usize = 200;
-        const THROTTLE_TIME: Duration = Duration::from_millis(200);
-
-        let mut serializable_items = items_rx.ready_chunks(CHUNK_SIZE);
-
-        while let Some(items_received) = serializable_items.next().await {
-            let unique_items =
-                items_received
-                    .into_iter()
-                    .fold(HashMap::default(), |mut acc, item| {
-                        acc.entry(item.item_id()).or_insert(item);
-                        acc
-                    });
-
-            // We use into_iter() here so that the references to the items are moved into
-            // the tasks and not kept alive while we're sleeping.
-            for (_, item) in unique_items.into_iter() {
-                if let Ok(Some(task)) =
-                    this.update(cx, |workspace, cx| item.serialize(workspace, false, cx))
-                {
-                    cx.background_executor()
-                        .spawn(async move { task.await.log_err() })
-                        .detach();
-                }
-            }
-
-            cx.background_executor().timer(THROTTLE_TIME).await;
-        }
-
-        Ok(())
-    }
-
     pub(crate) fn enqueue_item_serialization(
         &mut self,
         item: Box<dyn SerializableItemHandle>,
@@ -4283,6 +5766,50 @@ impl Workspace {
         serialized_workspace: SerializedWorkspace,
         paths_to_open: Vec<Option<ProjectPath>>,
         cx: &mut ViewContext<Workspace>,
+    ) -> Task<Result<Vec<Option<Box<dyn ItemHandle>>>>> {
+        // Every restored item would otherwise be reported on its own.
+        cx.spawn(|workspace, mut cx| async move {
+            let started_at = cx.background_executor().now();
+            let restore = workspace.update(&mut cx, |workspace, cx| {
+                workspace.begin_event_batch();
+                workspace.begin_loading();
+                Self::restore_serialized_workspace(
+                    serialized_workspace,
+                    paths_to_open,
+                    Vec::new(),
+                    true,
+                    cx,
+                )
+            })?;
+            let result = restore.await;
+            let restore_duration = cx.background_executor().now() - started_at;
+            log::info!("restored workspace in {restore_duration:?}");
+            workspace.update(&mut cx, |workspace, cx| {
+                workspace.last_restore_duration = Some(restore_duration);
+                workspace.end_event_batch(cx);
+                workspace.finish_loading(cx);
+            })?;
+            result
+        })
+    }
+
+    /// How long restoring the panes, items and docks of the workspace took, when it was
+    /// restored from the database.
+    pub fn last_restore_duration(&self) -> Option<Duration> {
+        self.last_restore_duration
+    }
+
+    /// Replaces the center panes and docks with the serialized ones. Of the `preserved_items`,
+    /// those that the serialized panes don't contain are added to the restored active pane.
+    ///
+    /// Unless `owns_items` is false, e.g. when copying the layout of another workspace, the
+    /// serialized items that weren't loaded are deleted, and the item bookmarks are restored.
+    fn restore_serialized_workspace(
+        serialized_workspace: SerializedWorkspace,
+        paths_to_open: Vec<Option<ProjectPath>>,
+        preserved_items: Vec<Box<dyn ItemHandle>>,
+        owns_items: bool,
+        cx: &mut ViewContext<Workspace>,
     ) -> Task<Result<Vec<Option<Box<dyn ItemHandle>>>>> {
         cx.spawn(|workspace, mut cx| async move {
             let project = workspace.update(&mut cx, |workspace, _| workspace.project().clone())?;
@@ -4297,6 +5824,7 @@ impl Workspace {
                     &project,
                     serialized_workspace.id,
                     workspace.clone(),
+                    MAX_CONCURRENT_ITEM_LOADS,
                     &mut cx,
                 )
                 .await
@@ -4323,6 +5851,15 @@ impl Workspace {
                     all_deserialized_items.push(item);
                 }
             })?;
+            let preserved_items = cx.update(|cx| {
+                preserved_items
+                    .into_iter()
+                    .filter(|item| {
+                        item.project_path(cx)
+                            .map_or(true, |path| !items_by_project_path.contains_key(&path))
+                    })
+                    .collect::<Vec<_>>()
+            })?;
 
             let opened_items = paths_to_open
                 .into_iter()
@@ -4334,19 +5871,47 @@ impl Workspace {
 
             // Remove old panes from workspace panes list
             workspace.update(&mut cx, |workspace, cx| {
+                let mut focus_center = false;
                 if let Some((center_group, active_pane)) = center_group {
+                    for item in &preserved_items {
+                        for pane in &workspace.panes {
+                            pane.update(cx, |pane, cx| {
+                                pane.remove_item(item.item_id(), false, false, cx)
+                            });
+                        }
+                    }
                     workspace.remove_panes(workspace.center.root.clone(), cx);
 
                     // Swap workspace center group
                     workspace.center = PaneGroup::with_root(center_group);
                     if let Some(active_pane) = active_pane {
                         workspace.set_active_pane(&active_pane, cx);
-                        cx.focus_self();
+                        focus_center = true;
                     } else {
                         workspace.set_active_pane(&workspace.center.first_pane(), cx);
                     }
+
+                    let active_pane = workspace.active_pane.clone();
+                    for item in preserved_items {
+                        if let Some(serializable_item_handle) = item.to_serializable_item_handle(cx)
+                        {
+                            item_ids_by_kind
+                                .entry(serializable_item_handle.serialized_item_kind())
+                                .or_insert(Vec::new())
+                                .push(item.item_id().as_u64() as ItemId);
+                        }
+                        active_pane
+                            .update(cx, |pane, cx| pane.add_item(item, false, false, None, cx));
+                    }
+                    workspace.finalize_item_activation(
+                        &active_pane,
+                        ItemActivationOrigin::Restore,
+                        cx,
+                    );
                 }
 
+                workspace.session_notes = serialized_workspace.session_notes;
+
                 let docks = serialized_workspace.docks;
 
                 for (dock, serialized_dock) in [
@@ -4362,14 +5927,51 @@ impl Workspace {
                     });
                 }
 
+                // Docks take the focus back themselves if they had it when the workspace was
+                // serialized and their panel has been restored.
+                let dock_focused = [
+                    &workspace.left_dock,
+                    &workspace.right_dock,
+                    &workspace.bottom_dock,
+                ]
+                .into_iter()
+                .any(|dock| {
+                    dock.read(cx)
+                        .active_panel()
+                        .map_or(false, |panel| panel.focus_handle(cx).is_focused(cx))
+                });
+                if focus_center && !dock_focused {
+                    cx.focus_self();
+                }
+
                 cx.notify();
             })?;
 
+            if !owns_items {
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        workspace.serialize_workspace_internal(cx).detach();
+                        workspace.update_window_edited(cx);
+                    })
+                    .ok();
+                return Ok(opened_items);
+            }
+
             // Clean up all the items that have _not_ been loaded. Our ItemIds aren't stable. That means
             // after loading the items, we might have different items and in order to avoid
             // the database filling up, we delete items that haven't been loaded now.
             //
             // The items that have been loaded, have been saved after they've been added to the workspace.
+            // Items referenced by layout checkpoints are kept around for restoring them.
+            let checkpoint_item_ids = persistence::DB
+                .layout_checkpoint_item_ids(serialized_workspace.id)
+                .log_err()
+                .unwrap_or_default();
+            for (item_kind, item_id) in checkpoint_item_ids {
+                if let Some(loaded_items) = item_ids_by_kind.get_mut(item_kind.as_str()) {
+                    loaded_items.push(item_id);
+                }
+            }
             let clean_up_tasks = workspace.update(&mut cx, |_, cx| {
                 item_ids_by_kind
                     .into_iter()
@@ -4387,13 +5989,23 @@ impl Workspace {
 
             futures::future::join_all(clean_up_tasks).await;
 
+            let item_bookmarks = persistence::DB
+                .item_bookmarks(serialized_workspace.id)
+                .await
+                .log_err()
+                .unwrap_or_default();
+
             workspace
                 .update(&mut cx, |workspace, cx| {
+                    workspace.restore_item_bookmarks(item_bookmarks, cx);
+
                     // Serialize ourself to make sure our timestamps and any pane / item changes are replicated
                     workspace.serialize_workspace_internal(cx).detach();
 
                     // Ensure that we mark the window as edited if we did load dirty items
                     workspace.update_window_edited(cx);
+
+                    workspace.offer_recovered_items(cx).detach_and_log_err(cx);
                 })
                 .ok();
 
@@ -4405,16 +6017,41 @@ impl Workspace {
         self.add_workspace_actions_listeners(div, cx)
             .on_action(cx.listener(Self::close_inactive_items_and_panes))
             .on_action(cx.listener(Self::close_all_items_and_panes))
+            .on_action(cx.listener(Self::close_items_matching_action))
+            .on_action(cx.listener(Self::reveal_active_item))
+            .on_action(cx.listener(Self::dump_workspace_state))
             .on_action(cx.listener(Self::save_all))
+            .on_action(cx.listener(Self::save_all_windows))
+            .on_action(cx.listener(Self::save_items_in_active_pane))
+            .on_action(cx.listener(Self::close_active_pane_saving_items))
             .on_action(cx.listener(Self::send_keystrokes))
             .on_action(cx.listener(Self::add_folder_to_project))
             .on_action(cx.listener(Self::follow_next_collaborator))
+            .on_action(cx.listener(Self::capture_active_pane_screenshot))
+            .on_action(cx.listener(Self::activate_previous_item_global))
+            .on_action(cx.listener(Self::activate_alternate_item))
+            .on_action(cx.listener(Self::reload_all_items_action))
+            .on_action(cx.listener(Self::toggle_item_bookmark))
+            .on_action(cx.listener(Self::cycle_bookmarked_items))
+            .on_action(cx.listener(Self::restart_following))
+            .on_action(cx.listener(Self::reset_suppressed_notifications))
+            .on_action(cx.listener(Self::move_worktree_up))
+            .on_action(cx.listener(Self::move_worktree_down))
+            .on_action(cx.listener(Self::toggle_mirror_pane))
+            .on_action(cx.listener(Self::duplicate_window))
+            .on_action(cx.listener(Self::activate_next_item_global))
             .on_action(cx.listener(Self::close_window))
             .on_action(cx.listener(Self::activate_pane_at_index))
             .on_action(cx.listener(|workspace, _: &Unfollow, cx| {
                 let pane = workspace.active_pane().clone();
                 workspace.unfollow_in_pane(&pane, cx);
             }))
+            .on_action(cx.listener(|workspace, _: &FollowAllCollaborators, cx| {
+                workspace.follow_all_collaborators(cx);
+            }))
+            .on_action(cx.listener(|workspace, _: &UnfollowAll, cx| {
+                workspace.unfollow_all(cx);
+            }))
             .on_action(cx.listener(|workspace, action: &Save, cx| {
                 workspace
                     .save_active_item(action.save_intent.unwrap_or(SaveIntent::Save), cx)
@@ -4425,6 +6062,10 @@ impl Workspace {
                     .save_active_item(SaveIntent::SaveWithoutFormat, cx)
                     .detach_and_prompt_err("Failed to save", cx, |_, _| None);
             }))
+            .on_action(cx.listener(Self::interactive_rename_active_item))
+            .on_action(cx.listener(Self::focus_notifications))
+            .on_action(cx.listener(Self::activate_notification))
+            .on_action(cx.listener(Self::dismiss_focused_notification))
             .on_action(cx.listener(|workspace, _: &SaveAs, cx| {
                 workspace
                     .save_active_item(SaveIntent::SaveAs, cx)
@@ -4441,27 +6082,53 @@ impl Workspace {
                     workspace.activate_pane_in_direction(action.0, cx)
                 }),
             )
+            .on_action(cx.listener(Self::new_search_in_split))
             .on_action(cx.listener(|workspace, action: &SwapPaneInDirection, cx| {
                 workspace.swap_pane_in_direction(action.0, cx)
             }))
-            .on_action(cx.listener(|this, _: &ToggleLeftDock, cx| {
-                this.toggle_dock(DockPosition::Left, cx);
+            .on_action(cx.listener(|workspace, action: &SwapPaneWithIndex, cx| {
+                workspace.swap_pane_with_index(action.0, cx)
+            }))
+            .on_action(cx.listener(|workspace, _: &EqualizePanes, cx| workspace.equalize_panes(cx)))
+            .on_action(cx.listener(|workspace, _: &GrowPane, cx| {
+                workspace.resize_active_pane_by_step(true, cx)
             }))
+            .on_action(cx.listener(|workspace, _: &ShrinkPane, cx| {
+                workspace.resize_active_pane_by_step(false, cx)
+            }))
+            .on_action(cx.listener(Self::switch_back_to_previous_project))
+            // The restored docks would overwrite the changes made while the workspace loads.
             .on_action(
-                cx.listener(|workspace: &mut Workspace, _: &ToggleRightDock, cx| {
-                    workspace.toggle_dock(DockPosition::Right, cx);
-                }),
+                cx.listener(defer_until_loaded(|this, _: &ToggleLeftDock, cx| {
+                    this.toggle_dock(DockPosition::Left, cx);
+                })),
             )
-            .on_action(
-                cx.listener(|workspace: &mut Workspace, _: &ToggleBottomDock, cx| {
+            .on_action(cx.listener(defer_until_loaded(
+                |workspace: &mut Workspace, _: &ToggleRightDock, cx| {
+                    workspace.toggle_dock(DockPosition::Right, cx);
+                },
+            )))
+            .on_action(cx.listener(defer_until_loaded(
+                |workspace: &mut Workspace, _: &ToggleBottomDock, cx| {
                     workspace.toggle_dock(DockPosition::Bottom, cx);
-                }),
-            )
+                },
+            )))
             .on_action(
-                cx.listener(|workspace: &mut Workspace, _: &CloseAllDocks, cx| {
-                    workspace.close_all_docks(cx);
-                }),
+                cx.listener(defer_until_loaded(|workspace, action: &SetDockOpen, cx| {
+                    workspace.set_dock_open(action.position, action.open, cx);
+                })),
             )
+            .on_action(cx.listener(defer_until_loaded(
+                |workspace: &mut Workspace, _: &CloseAllDocks, cx| {
+                    workspace.close_all_docks(cx);
+                },
+            )))
+            .on_action(cx.listener(|workspace, _: &PromoteActivePreviewTab, cx| {
+                workspace.promote_active_preview_tab(cx);
+            }))
+            .on_action(cx.listener(|_, _: &MoveWindowOnScreen, cx| {
+                move_window_on_screen(cx);
+            }))
             .on_action(
                 cx.listener(|workspace: &mut Workspace, _: &ClearAllNotifications, cx| {
                     workspace.clear_all_notifications(cx);
@@ -4472,7 +6139,9 @@ impl Workspace {
                     workspace.reopen_closed_item(cx).detach();
                 }),
             )
-            .on_action(cx.listener(Workspace::toggle_centered_layout))
+            .on_action(cx.listener(defer_until_loaded(Workspace::toggle_centered_layout)))
+            .on_action(cx.listener(Workspace::toggle_tab_bar_for_pane))
+            .on_action(cx.listener(Workspace::toggle_active_pane_lock))
     }
 
     #[cfg(any(test, feature = "test-support"))]
@@ -4539,8 +6208,33 @@ impl Workspace {
             .update(cx, |modal_layer, cx| modal_layer.toggle_modal(cx, build))
     }
 
+    /// Like [`Self::toggle_modal`], but keeps the active modal beneath the new one, to reveal
+    /// it again once the new one is dismissed.
+    pub fn toggle_modal_stacked<V: ModalView, B>(&mut self, cx: &mut WindowContext, build: B)
+    where
+        B: FnOnce(&mut ViewContext<V>) -> V,
+    {
+        self.modal_layer.update(cx, |modal_layer, cx| {
+            modal_layer.toggle_modal_stacked(cx, build)
+        })
+    }
+
+    pub fn push_modal<V: ModalView, B>(&mut self, cx: &mut WindowContext, build: B)
+    where
+        B: FnOnce(&mut ViewContext<V>) -> V,
+    {
+        self.modal_layer
+            .update(cx, |modal_layer, cx| modal_layer.push_modal(cx, build))
+    }
+
+    pub fn pop_modal(&mut self, cx: &mut WindowContext) -> bool {
+        self.modal_layer
+            .update(cx, |modal_layer, cx| modal_layer.pop_modal(cx))
+    }
+
     pub fn toggle_centered_layout(&mut self, _: &ToggleCenteredLayout, cx: &mut ViewContext<Self>) {
         self.centered_layout = !self.centered_layout;
+        self.forget_centered_layout_before_fullscreen();
         if let Some(database_id) = self.database_id() {
             cx.background_executor()
                 .spawn(DB.set_centered_layout(database_id, self.centered_layout))
@@ -4549,6 +6243,25 @@ impl Workspace {
         cx.notify();
     }
 
+    /// Shows or hides the tab bar of a pane regardless of the settings, or makes it follow them
+    /// again when `None`.
+    pub fn set_pane_tab_bar_visibility(
+        &mut self,
+        pane: &View<Pane>,
+        visibility: Option<bool>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        pane.update(cx, |pane, cx| pane.set_tab_bar_visibility(visibility, cx));
+        self.serialize_workspace(cx);
+        cx.notify();
+    }
+
+    fn toggle_tab_bar_for_pane(&mut self, _: &ToggleTabBarForPane, cx: &mut ViewContext<Self>) {
+        let pane = self.active_pane.clone();
+        let visibility = pane.update(cx, |pane, cx| pane.toggled_tab_bar_visibility(cx));
+        self.set_pane_tab_bar_visibility(&pane, visibility, cx);
+    }
+
     fn adjust_padding(padding: Option<f32>) -> f32 {
         padding
             .unwrap_or(Self::DEFAULT_PADDING)
@@ -4559,7 +6272,7 @@ impl Workspace {
         &self,
         position: DockPosition,
         dock: &View<Dock>,
-        cx: &WindowContext,
+        cx: &mut ViewContext<Self>,
     ) -> Option<Div> {
         if self.zoomed_position == Some(position) {
             return None;
@@ -4571,13 +6284,37 @@ impl Workspace {
             leader_border_for_pane(follower_states, &pane, cx)
         });
 
+        Some(
+            Self::focus_follows_mouse(
+                div().flex().flex_none().overflow_hidden(),
+                FocusFollowsMouseTarget::Dock(dock.downgrade()),
+                cx,
+            )
+            .child(dock.clone())
+            .children(leader_border),
+        )
+    }
+
+    /// Renders the zoomed item, if any, reporting the bounds it ends up with.
+    fn render_zoomed(&self, cx: &mut ViewContext<Self>) -> Option<Div> {
+        let zoomed_view = self.zoomed.as_ref()?.upgrade()?;
+        let this = cx.view().clone();
         Some(
             div()
-                .flex()
-                .flex_none()
+                .relative()
                 .overflow_hidden()
-                .child(dock.clone())
-                .children(leader_border),
+                .bg(cx.theme().colors().background)
+                .child(zoomed_view)
+                .child(
+                    canvas(
+                        move |bounds, cx| {
+                            this.update(cx, |this, cx| this.set_zoomed_bounds(bounds, cx))
+                        },
+                        |_, _, _| {},
+                    )
+                    .absolute()
+                    .size_full(),
+                ),
         )
     }
 
@@ -4589,6 +6326,46 @@ impl Workspace {
     pub fn zoomed_item(&self) -> Option<&AnyWeakView> {
         self.zoomed.as_ref()
     }
+
+    pub fn zoom_state(&self, _cx: &AppContext) -> Option<ZoomState> {
+        let view = self.zoomed.clone()?;
+        let bounds = self
+            .zoomed_bounds
+            .and_then(|(position, bounds)| (position == self.zoomed_position).then_some(bounds));
+        Some(ZoomState {
+            view,
+            position: self.zoomed_position,
+            bounds,
+        })
+    }
+
+    pub(crate) fn emit_zoom_changed(&self, cx: &mut ViewContext<Self>) {
+        cx.emit(Event::ZoomChanged(self.zoom_state(cx)));
+    }
+
+    fn set_zoomed_bounds(&mut self, bounds: Bounds<Pixels>, cx: &mut ViewContext<Self>) {
+        let zoomed_bounds = Some((self.zoomed_position, bounds));
+        if self.zoomed_bounds != zoomed_bounds {
+            self.zoomed_bounds = zoomed_bounds;
+            self.emit_zoom_changed(cx);
+        }
+    }
+
+    /// Records that the tab of the given item started being dragged, so that
+    /// window-level mouse handling doesn't start resizing the window mid-drag.
+    pub fn begin_item_drag(&mut self, item_id: EntityId) {
+        self.dragged_item_id = Some(item_id);
+    }
+
+    /// Records that the tab drag ended, whether the tab was dropped or the drag cancelled.
+    pub fn end_item_drag(&mut self) {
+        self.dragged_item_id = None;
+    }
+
+    /// The item whose tab is currently being dragged, if any.
+    pub fn dragged_item_id(&self) -> Option<EntityId> {
+        self.dragged_item_id
+    }
 }
 
 fn leader_border_for_pane(
@@ -4624,6 +6401,168 @@ fn leader_border_for_pane(
     )
 }
 
+/// Determines the options of a new workspace window. In order of precedence, its bounds come
+/// from the environment variables, the explicit override, the serialized bounds, and finally
+/// the defaults provided by [`AppState::build_window_options`].
+fn resolve_window_options(
+    mut options: WindowOptions,
+    serialized_bounds: Option<WindowBounds>,
+    window_options_override: Option<WindowOptionsOverride>,
+    env_bounds: Option<Bounds<Pixels>>,
+) -> WindowOptions {
+    options.window_bounds = serialized_bounds;
+    if let Some(window_options_override) = window_options_override {
+        options = window_options_override(options);
+    }
+    if let Some(bounds) = env_bounds {
+        options.window_bounds = Some(WindowBounds::Windowed(bounds));
+        options.display_id = None;
+    }
+    options
+}
+
+/// The display and bounds to open a workspace's window with.
+#[derive(Debug, PartialEq)]
+struct RestoredWindowBounds {
+    display: Uuid,
+    bounds: WindowBounds,
+    /// The display the window was last on, when it's disconnected. The bounds the window had
+    /// there are kept, to be restored once it's connected again.
+    missing_display: Option<Uuid>,
+}
+
+/// Returns the display and bounds to open a workspace's window with. The window gets the bounds
+/// it last had on one of the available displays, or the ones of the last window when the
+/// workspace wasn't serialized.
+fn restorable_window_bounds(
+    serialized_workspace: Option<&SerializedWorkspace>,
+    cx: &AppContext,
+) -> Option<RestoredWindowBounds> {
+    let mut bounds_by_display = serialized_workspace
+        .and_then(|workspace| DB.window_bounds_by_display(workspace.id).log_err())
+        .unwrap_or_default();
+    if bounds_by_display.is_empty() {
+        let last_bounds = serialized_workspace
+            .and_then(|workspace| Some((workspace.display?, workspace.window_bounds?)))
+            .or_else(|| {
+                let (display, window_bounds) = DB.last_window().log_err()?;
+                Some((display?, window_bounds?))
+            });
+        bounds_by_display.extend(last_bounds);
+    }
+    let bounds_by_display = bounds_by_display
+        .into_iter()
+        .map(|(display, bounds)| (display, bounds.0))
+        .collect::<Vec<_>>();
+
+    let displays = cx
+        .displays()
+        .into_iter()
+        .filter_map(|display| Some((display.uuid().ok()?, display.bounds())))
+        .collect::<Vec<_>>();
+    let primary_display = cx
+        .primary_display()
+        .and_then(|display| Some((display.uuid().ok()?, display.bounds())));
+    let missing_display = bounds_by_display
+        .first()
+        .map(|(display, _)| *display)
+        .filter(|display| !displays.iter().any(|(uuid, _)| uuid == display));
+    let (display, bounds) = select_window_bounds(&bounds_by_display, &displays, primary_display)?;
+    Some(RestoredWindowBounds {
+        display,
+        bounds,
+        missing_display,
+    })
+}
+
+/// Picks the bounds to restore a window with from the ones it had on each display, most
+/// recently used first. The bounds of an available display are preferred; otherwise the
+/// most recent ones are moved onto the primary display if they'd be off-screen.
+fn select_window_bounds(
+    bounds_by_display: &[(Uuid, WindowBounds)],
+    displays: &[(Uuid, Bounds<Pixels>)],
+    primary_display: Option<(Uuid, Bounds<Pixels>)>,
+) -> Option<(Uuid, WindowBounds)> {
+    let available = bounds_by_display
+        .iter()
+        .find(|(display, _)| displays.iter().any(|(uuid, _)| uuid == display));
+    if let Some(entry) = available {
+        return Some(*entry);
+    }
+
+    let (display, window_bounds) = *bounds_by_display.first()?;
+    let Some((primary_uuid, primary_bounds)) = primary_display else {
+        return Some((display, window_bounds));
+    };
+    let display_bounds = displays
+        .iter()
+        .map(|(_, bounds)| *bounds)
+        .collect::<Vec<_>>();
+    let clamp =
+        |bounds| on_screen_window_bounds(bounds, &display_bounds, primary_bounds).unwrap_or(bounds);
+    let window_bounds = match window_bounds {
+        WindowBounds::Windowed(bounds) => WindowBounds::Windowed(clamp(bounds)),
+        WindowBounds::Maximized(bounds) => WindowBounds::Maximized(clamp(bounds)),
+        WindowBounds::Fullscreen(bounds) => WindowBounds::Fullscreen(clamp(bounds)),
+    };
+    Some((primary_uuid, window_bounds))
+}
+
+/// The fraction of a window's area that has to be visible on the displays for the window
+/// to count as being on screen.
+const MIN_VISIBLE_WINDOW_AREA: f32 = 0.2;
+
+/// Returns where to move a window with the given bounds so that it can be seen, or `None`
+/// if enough of it is already visible on the displays. Windows that are mostly off-screen
+/// get centered on the target display, shrinking them to fit if needed. All the bounds are
+/// expected in the same coordinate space.
+fn on_screen_window_bounds(
+    bounds: Bounds<Pixels>,
+    display_bounds: &[Bounds<Pixels>],
+    target_display_bounds: Bounds<Pixels>,
+) -> Option<Bounds<Pixels>> {
+    let area = |bounds: Bounds<Pixels>| bounds.size.width.0 * bounds.size.height.0;
+    let window_area = area(bounds);
+    let visible_area = display_bounds
+        .iter()
+        .filter(|display_bounds| display_bounds.intersects(&bounds))
+        .map(|display_bounds| area(display_bounds.intersect(&bounds)))
+        .sum::<f32>();
+    if window_area > 0. && visible_area / window_area >= MIN_VISIBLE_WINDOW_AREA {
+        return None;
+    }
+    let size = bounds.size.min(&target_display_bounds.size);
+    Some(Bounds::centered_at(target_display_bounds.center(), size))
+}
+
+/// Applies [`on_screen_window_bounds`] to the window's current bounds. Only the window's
+/// display is considered, as some platforms report the bounds of a window relative to its
+/// display rather than in the coordinate space shared by all displays.
+fn on_screen_bounds_for_window(cx: &WindowContext) -> Option<Bounds<Pixels>> {
+    let display_bounds = cx.display().or_else(|| cx.primary_display())?.bounds();
+    on_screen_window_bounds(cx.bounds(), &[display_bounds], display_bounds)
+}
+
+/// Moves the window back onto its display if it ended up off-screen, e.g. because the
+/// restored bounds refer to a display that is gone or was rearranged.
+fn ensure_window_on_screen(cx: &mut WindowContext) {
+    if let Some(new_bounds) = on_screen_bounds_for_window(cx) {
+        log::warn!(
+            "window at {:?} is off-screen, moving it to {new_bounds:?}",
+            cx.bounds()
+        );
+        cx.set_window_bounds(new_bounds);
+    }
+}
+
+fn move_window_on_screen(cx: &mut WindowContext) {
+    let new_bounds = on_screen_bounds_for_window(cx).unwrap_or_else(|| {
+        let display_id = cx.display().map(|display| display.id());
+        Bounds::centered(display_id, cx.bounds().size, cx)
+    });
+    cx.set_window_bounds(new_bounds);
+}
+
 fn window_bounds_env_override() -> Option<Bounds<Pixels>> {
     ZED_WINDOW_POSITION
         .zip(*ZED_WINDOW_SIZE)
@@ -4633,7 +6572,26 @@ fn window_bounds_env_override() -> Option<Bounds<Pixels>> {
         })
 }
 
+/// Restores the serialized workspace, if any, and opens the given paths in it. The workspace
+/// is loading until both are done, see [`Workspace::is_loading`].
 fn open_items(
+    workspace: &mut Workspace,
+    serialized_workspace: Option<SerializedWorkspace>,
+    project_paths_to_open: Vec<(PathBuf, Option<ProjectPath>)>,
+    cx: &mut ViewContext<Workspace>,
+) -> impl 'static + Future<Output = Result<Vec<Option<Result<Box<dyn ItemHandle>>>>>> {
+    workspace.begin_loading();
+    let open = restore_and_open_items(serialized_workspace, project_paths_to_open, cx);
+    cx.spawn(|workspace, mut cx| async move {
+        let result = open.await;
+        workspace
+            .update(&mut cx, |workspace, cx| workspace.finish_loading(cx))
+            .ok();
+        result
+    })
+}
+
+fn restore_and_open_items(
     serialized_workspace: Option<SerializedWorkspace>,
     mut project_paths_to_open: Vec<(PathBuf, Option<ProjectPath>)>,
     cx: &mut ViewContext<Workspace>,
@@ -4730,6 +6688,37 @@ fn open_items(
     })
 }
 
+/// Picks the item opened for `abs_path` out of the results of [`Workspace::open_paths`],
+/// which may not hold exactly one, e.g. once symlinks are resolved. The first opened item
+/// wins, and `None` means nothing was opened nor failed to.
+#[allow(clippy::type_complexity)]
+fn item_from_open_results(
+    abs_path: &Path,
+    results: Vec<Option<Result<Box<dyn ItemHandle>, anyhow::Error>>>,
+) -> Result<Option<Box<dyn ItemHandle>>> {
+    let result_count = results.len();
+    let mut first_error = None;
+    for result in results.into_iter().flatten() {
+        match result {
+            Ok(item) => {
+                if result_count > 1 {
+                    log::warn!(
+                        "open abs path {abs_path:?} returned {result_count} results, using the first item"
+                    );
+                }
+                return Ok(Some(item));
+            }
+            Err(error) => {
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+    match first_error {
+        Some(error) => Err(error).with_context(|| format!("open abs path {abs_path:?} task join")),
+        None => Ok(None),
+    }
+}
+
 enum ActivateInDirectionTarget {
     Pane(View<Pane>),
     Dock(View<Dock>),
@@ -4759,6 +6748,38 @@ fn notify_if_database_failed(workspace: WindowHandle<Workspace>, cx: &mut AsyncA
         .log_err();
 }
 
+fn notify_if_database_newer(workspace: WindowHandle<Workspace>, cx: &mut AsyncAppContext) {
+    if persistence::restores_workspaces() {
+        return;
+    }
+    workspace
+        .update(cx, |workspace, cx| {
+            struct DatabaseNewerNotification;
+
+            let workspace_handle = cx.view().downgrade();
+            workspace.show_notification_once(
+                NotificationId::unique::<DatabaseNewerNotification>(),
+                cx,
+                |cx| {
+                    cx.new_view(|_| {
+                        MessageNotification::new(
+                            "The saved layout comes from a newer version of Zed and was not restored. \
+                            Changes to it aren't saved, to keep it intact.",
+                        )
+                        .with_click_message("Use Anyway (Read-Only)")
+                        .on_click(move |cx| {
+                            persistence::restore_from_newer_schema();
+                            workspace_handle
+                                .update(cx, |workspace, cx| workspace.reopen_restored(cx))
+                                .log_err();
+                        })
+                    })
+                },
+            );
+        })
+        .log_err();
+}
+
 impl FocusableView for Workspace {
     fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
         self.active_pane.focus_handle(cx)
@@ -4799,9 +6820,23 @@ impl Render for Workspace {
         let theme = cx.theme().clone();
         let colors = theme.colors();
 
+        let zoomed = self.render_zoomed(cx);
+        let (zoomed_in_center, zoomed_overlay) = match WorkspaceSettings::get_global(cx).zoom_mode {
+            ZoomMode::Overlay => (None, zoomed),
+            ZoomMode::ReplaceCenter => (zoomed, None),
+        };
+        let zoomed_overlay =
+            zoomed_overlay.map(|overlay| self.position_zoomed_overlay(overlay, cx));
+        let zoom_backdrop = zoomed_overlay
+            .is_some()
+            .then(|| self.render_zoom_backdrop(cx))
+            .flatten();
+
         client_side_decorations(
             self.actions(div(), cx)
                 .key_context(context)
+                // Dropping a tab or cancelling its drag both end with the mouse going up.
+                .capture_any_mouse_up(cx.listener(|workspace, _, _| workspace.end_item_drag()))
                 .relative()
                 .size_full()
                 .flex()
@@ -4856,6 +6891,7 @@ impl Render for Workspace {
                                                             cx,
                                                         )
                                                     });
+                                                    this.update_dock_auto_hide(cx);
                                                 }
                                             })
                                         },
@@ -4920,15 +6956,20 @@ impl Render for Workspace {
                                                         .when_some(paddings.0, |this, p| {
                                                             this.child(p.border_r_1())
                                                         })
-                                                        .child(self.center.render(
-                                                            &self.project,
-                                                            &self.follower_states,
-                                                            self.active_call(),
-                                                            &self.active_pane,
-                                                            self.zoomed.as_ref(),
-                                                            &self.app_state,
-                                                            cx,
-                                                        ))
+                                                        .map(|this| match zoomed_in_center {
+                                                            Some(zoomed) => {
+                                                                this.child(zoomed.flex_1().h_full())
+                                                            }
+                                                            None => this.child(self.center.render(
+                                                                &self.project,
+                                                                &self.follower_states,
+                                                                self.active_call(),
+                                                                &self.active_pane,
+                                                                self.zoomed.as_ref(),
+                                                                &self.app_state,
+                                                                cx,
+                                                            )),
+                                                        })
                                                         .when_some(paddings.1, |this, p| {
                                                             this.child(p.border_l_1())
                                                         }),
@@ -4946,27 +6987,8 @@ impl Render for Workspace {
                                             cx,
                                         )),
                                 )
-                                .children(self.zoomed.as_ref().and_then(|view| {
-                                    let zoomed_view = view.upgrade()?;
-                                    let div = div()
-                                        .occlude()
-                                        .absolute()
-                                        .overflow_hidden()
-                                        .border_color(colors.border)
-                                        .bg(colors.background)
-                                        .child(zoomed_view)
-                                        .inset_0()
-                                        .shadow_lg();
-
-                                    Some(match self.zoomed_position {
-                                        Some(DockPosition::Left) => div.right_2().border_r_1(),
-                                        Some(DockPosition::Right) => div.left_2().border_l_1(),
-                                        Some(DockPosition::Bottom) => div.top_2().border_t_1(),
-                                        None => {
-                                            div.top_2().bottom_2().left_2().right_2().border_1()
-                                        }
-                                    })
-                                }))
+                                .children(zoom_backdrop)
+                                .children(zoomed_overlay)
                                 .children(self.render_notifications(cx)),
                         )
                         .child(self.status_bar.clone())
@@ -5018,26 +7040,39 @@ impl WorkspaceStore {
             _subscriptions: vec![
                 client.add_request_handler(cx.weak_model(), Self::handle_follow),
                 client.add_message_handler(cx.weak_model(), Self::handle_update_followers),
+                client.add_message_handler(cx.weak_model(), Self::handle_unfollow),
             ],
             client,
+            follower_updates: FollowerUpdateQueue::new(),
+            followers: Vec::new(),
         }
     }
 
+    /// The windows of every workspace, some of which may have been closed already.
+    pub fn workspaces(&self) -> &HashSet<WindowHandle<Workspace>> {
+        &self.workspaces
+    }
+
+    /// Sends `update` to the followers in the active call. The updates are throttled, so that
+    /// only the latest active view and scroll position are sent, and those failing while the
+    /// client reconnects are sent once it's connected again.
     pub fn update_followers(
-        &self,
+        &mut self,
         project_id: Option<u64>,
         update: proto::update_followers::Variant,
-        cx: &AppContext,
+        cx: &mut ModelContext<Self>,
     ) -> Option<()> {
         let active_call = ActiveCall::try_global(cx)?;
         let room_id = active_call.read(cx).room()?.read(cx).id();
-        self.client
-            .send(proto::UpdateFollowers {
+        self.send_follower_update(
+            proto::UpdateFollowers {
                 room_id,
                 project_id,
                 variant: Some(update),
-            })
-            .log_err()
+            },
+            cx,
+        );
+        Some(())
     }
 
     pub async fn handle_follow(
@@ -5052,6 +7087,7 @@ impl WorkspaceStore {
             };
 
             let mut response = proto::FollowResponse::default();
+            this.add_follower(follower, cx);
             this.workspaces.retain(|workspace| {
                 workspace
                     .update(cx, |workspace, cx| {
@@ -5175,13 +7211,69 @@ pub fn activate_workspace_for_project(
     None
 }
 
-pub async fn last_opened_workspace_location() -> Option<SerializedWorkspaceLocation> {
-    DB.last_workspace().await.log_err().flatten()
-}
-
-pub fn last_session_workspace_locations(
-    last_session_id: &str,
-    last_session_window_stack: Option<Vec<WindowId>>,
+/// Returns the workspace windows whose project matches `predicate`.
+pub fn windows_for_project(
+    predicate: impl Fn(&Project, &AppContext) -> bool,
+    cx: &AppContext,
+) -> Vec<WindowHandle<Workspace>> {
+    cx.windows()
+        .into_iter()
+        .filter_map(|window| window.downcast::<Workspace>())
+        .filter(|workspace| {
+            workspace
+                .read(cx)
+                .is_ok_and(|workspace| predicate(workspace.project.read(cx), cx))
+        })
+        .collect()
+}
+
+fn visible_worktree_paths(project: &Project, cx: &AppContext) -> Vec<Arc<Path>> {
+    project
+        .visible_worktrees(cx)
+        .map(|worktree| worktree.read(cx).abs_path())
+        .sorted()
+        .collect()
+}
+
+pub async fn last_opened_workspace_location() -> Option<SerializedWorkspaceLocation> {
+    DB.last_workspace().await.log_err().flatten()
+}
+
+/// Returns up to `limit` recently opened workspaces, most recently activated first, skipping
+/// local ones whose paths no longer exist. SSH projects are always kept.
+pub async fn recent_workspaces(limit: usize, fs: &dyn fs::Fs) -> Vec<RecentWorkspace> {
+    let Some(workspaces) = DB.all_recent_workspaces_with_metadata().log_err() else {
+        return Vec::new();
+    };
+    let mut result = Vec::new();
+    for workspace in workspaces {
+        if result.len() >= limit {
+            break;
+        }
+        if let SerializedWorkspaceLocation::Local(local_paths, _) = &workspace.location {
+            let mut any_dir = false;
+            let mut all_exist = true;
+            for path in local_paths.paths().iter() {
+                match fs.metadata(path).await.ok().flatten() {
+                    Some(metadata) => any_dir |= metadata.is_dir,
+                    None => {
+                        all_exist = false;
+                        break;
+                    }
+                }
+            }
+            if !all_exist || !any_dir {
+                continue;
+            }
+        }
+        result.push(workspace);
+    }
+    result
+}
+
+pub fn last_session_workspace_locations(
+    last_session_id: &str,
+    last_session_window_stack: Option<Vec<WindowId>>,
 ) -> Option<Vec<SerializedWorkspaceLocation>> {
     DB.last_session_workspace_locations(last_session_id, last_session_window_stack)
         .log_err()
@@ -5249,8 +7341,52 @@ async fn join_channel_internal(
         }
     }
 
-    let client = cx.update(|cx| active_call.read(cx).client())?;
+    let (progress, cancelled) = JoinChannelProgress::show(requesting_window, cx);
+    let cancelled = cancelled.fuse();
+    futures::pin_mut!(cancelled);
+    let result = {
+        let join = connect_and_join_channel(
+            channel_id,
+            app_state,
+            requesting_window,
+            active_call,
+            &progress,
+            cx,
+        );
+        futures::pin_mut!(join);
+        futures::select_biased! {
+            _ = cancelled => None,
+            result = join.fuse() => Some(result),
+        }
+    };
+    if let Some(result) = result {
+        return result;
+    }
 
+    // Dropping the join stops it, but the channel may have been joined already, e.g. while
+    // the project was being shared.
+    active_call
+        .update(cx, |active_call, cx| {
+            if active_call.channel_id(cx) == Some(channel_id) {
+                active_call.hang_up(cx)
+            } else {
+                Task::ready(Ok(()))
+            }
+        })?
+        .await?;
+    Ok(false)
+}
+
+/// Waits for the client to connect, then joins the channel, reporting the steps to `progress`.
+async fn connect_and_join_channel(
+    channel_id: ChannelId,
+    app_state: &Arc<AppState>,
+    requesting_window: Option<WindowHandle<Workspace>>,
+    active_call: &Model<ActiveCall>,
+    progress: &JoinChannelProgress,
+    cx: &mut AsyncAppContext,
+) -> Result<bool> {
+    let client = cx.update(|cx| active_call.read(cx).client())?;
     let mut client_status = client.status();
 
     // this loop will terminate within client::CONNECTION_TIMEOUT seconds.
@@ -5260,10 +7396,12 @@ async fn join_channel_internal(
         };
 
         match status {
-            Status::Connecting
-            | Status::Authenticating
-            | Status::Reconnecting
-            | Status::Reauthenticating => continue,
+            Status::Connecting | Status::Reconnecting => {
+                progress.set_message("Connecting to channel…", cx);
+            }
+            Status::Authenticating | Status::Reauthenticating => {
+                progress.set_message("Authenticating…", cx);
+            }
             Status::Connected { .. } => break 'outer,
             Status::SignedOut => return Err(ErrorCode::SignedOut.into()),
             Status::UpgradeRequired => return Err(ErrorCode::UpgradeRequired.into()),
@@ -5273,6 +7411,8 @@ async fn join_channel_internal(
         }
     }
 
+    progress.set_message("Joining channel…", cx);
+
     let room = active_call
         .update(cx, |active_call, cx| {
             active_call.join_channel(channel_id, cx)
@@ -5332,6 +7472,83 @@ async fn join_channel_internal(
     anyhow::Ok(false)
 }
 
+/// A notification showing the progress of connecting to a channel, which lets the user
+/// cancel the connection attempt.
+struct JoinChannelProgress {
+    notification: Option<(WindowHandle<Workspace>, WeakView<MessageNotification>)>,
+}
+
+impl JoinChannelProgress {
+    /// Shows the notification in the requesting window, returning a future that resolves
+    /// once the user cancelled joining the channel.
+    fn show(
+        requesting_window: Option<WindowHandle<Workspace>>,
+        cx: &mut AsyncAppContext,
+    ) -> (Self, impl Future<Output = ()>) {
+        let (cancel_tx, cancel_rx) = oneshot::channel::<()>();
+        let cancel_tx = Cell::new(Some(cancel_tx));
+        let notification = requesting_window.and_then(|window| {
+            window
+                .update(cx, |workspace, cx| {
+                    let notification = cx.new_view(|_| {
+                        MessageNotification::new("Connecting to channel…")
+                            .with_click_message("Cancel")
+                            .on_click(move |_| {
+                                if let Some(cancel_tx) = cancel_tx.take() {
+                                    cancel_tx.send(()).ok();
+                                }
+                            })
+                    });
+                    workspace.show_notification(
+                        NotificationId::unique::<JoinChannelProgress>(),
+                        cx,
+                        |_| notification.clone(),
+                    );
+                    (window, notification.downgrade())
+                })
+                .ok()
+        });
+        let cancelled = async move {
+            // Dismissing the notification drops the sender without cancelling.
+            if cancel_rx.await.is_err() {
+                futures::future::pending::<()>().await;
+            }
+        };
+        (Self { notification }, cancelled)
+    }
+
+    fn set_message(&self, message: &'static str, cx: &mut AsyncAppContext) {
+        if let Some((window, notification)) = &self.notification {
+            window
+                .update(cx, |_, cx| {
+                    notification
+                        .update(cx, |notification, cx| notification.set_message(message, cx))
+                })
+                .ok();
+        }
+    }
+
+    fn dismiss(window: WindowHandle<Workspace>, cx: &mut AsyncAppContext) {
+        window
+            .update(cx, |workspace, cx| {
+                workspace.dismiss_notification(&NotificationId::unique::<JoinChannelProgress>(), cx)
+            })
+            .ok();
+    }
+}
+
+impl Workspace {
+    /// The notification showing the progress of joining a channel, if any.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn join_channel_progress(&self) -> Option<View<MessageNotification>> {
+        let id = NotificationId::unique::<JoinChannelProgress>();
+        self.notifications
+            .iter()
+            .find(|(notification_id, _)| *notification_id == id)
+            .and_then(|(_, notification)| notification.to_any().downcast().ok())
+    }
+}
+
 pub fn join_channel(
     channel_id: ChannelId,
     app_state: Arc<AppState>,
@@ -5348,6 +7565,9 @@ pub fn join_channel(
             &mut cx,
         )
             .await;
+        if let Some(requesting_window) = requesting_window {
+            JoinChannelProgress::dismiss(requesting_window, &mut cx);
+        }
 
         // join channel succeeded, and opened a window
         if matches!(result, Ok(true)) {
@@ -5361,7 +7581,14 @@ pub fn join_channel(
             // no open workspaces, make one to show the error in (blergh)
             let (window_handle, _) = cx
                 .update(|cx| {
-                    Workspace::new_local(vec![], app_state.clone(), requesting_window, None, cx)
+                    Workspace::new_local(
+                        vec![],
+                        app_state.clone(),
+                        requesting_window,
+                        None,
+                        None,
+                        cx,
+                    )
                 })?
                 .await?;
 
@@ -5379,22 +7606,8 @@ pub fn join_channel(
             if let Some(active_window) = active_window {
                 active_window
                     .update(&mut cx, |_, cx| {
-                        let detail: SharedString = match err.error_code() {
-                            ErrorCode::SignedOut => {
-                                "Please sign in to continue.".into()
-                            }
-                            ErrorCode::UpgradeRequired => {
-                                "Your are running an unsupported version of Zed. Please update to continue.".into()
-                            }
-                            ErrorCode::NoSuchChannel => {
-                                "No matching channel was found. Please check the link and try again.".into()
-                            }
-                            ErrorCode::Forbidden => {
-                                "This channel is private, and you do not have access. Please ask someone to add you and try again.".into()
-                            }
-                            ErrorCode::Disconnected => "Please check your internet connection and try again.".into(),
-                            _ => format!("{}\n\nPlease try again.", err).into(),
-                        };
+                        let detail = notifications::error_code_message(&err)
+                            .unwrap_or_else(|| format!("{}\n\nPlease try again.", err).into());
                         cx.prompt(
                             PromptLevel::Critical,
                             "Failed to join channel",
@@ -5419,7 +7632,7 @@ pub async fn get_any_active_workspace(
     // find an existing workspace to focus and show call controls
     let active_window = activate_any_workspace_window(&mut cx);
     if active_window.is_none() {
-        cx.update(|cx| Workspace::new_local(vec![], app_state.clone(), None, None, cx))?
+        cx.update(|cx| Workspace::new_local(vec![], app_state.clone(), None, None, None, cx))?
             .await?;
     }
     activate_any_workspace_window(&mut cx).context("could not open zed")
@@ -5460,11 +7673,18 @@ pub fn local_workspace_windows(cx: &AppContext) -> Vec<WindowHandle<Workspace>>
         .collect()
 }
 
+/// Adjusts the options of a single window about to be opened, see [`OpenOptions::window_options_override`].
+pub type WindowOptionsOverride = Box<dyn FnOnce(WindowOptions) -> WindowOptions + Send>;
+
 #[derive(Default)]
 pub struct OpenOptions {
     pub open_new_workspace: Option<bool>,
     pub replace_window: Option<WindowHandle<Workspace>>,
     pub env: Option<HashMap<String, String>>,
+    /// Customizes the options of the window opened for the workspace, if a new one is opened.
+    /// It is applied on top of the window bounds restored for the workspace, but the
+    /// `ZED_WINDOW_POSITION` and `ZED_WINDOW_SIZE` environment variables still take precedence.
+    pub window_options_override: Option<WindowOptionsOverride>,
 }
 
 #[allow(clippy::type_complexity)]
@@ -5527,15 +7747,62 @@ pub fn open_paths(
         }
 
         if let Some(existing) = existing {
-            Ok((
-                existing,
-                existing
+            // Folders may go to windows of their own, unless told where to open them.
+            let new_window_ixs = if open_options.open_new_workspace.is_none() {
+                folder_open::folders_for_new_windows(existing, &abs_paths, &app_state.fs, &mut cx)
+                    .await?
+            } else {
+                Default::default()
+            };
+
+            // Sort the paths opened in the existing window like it does, to match them with the
+            // results, which are returned in the order of `abs_paths`.
+            let mut existing_ixs = (0..abs_paths.len())
+                .filter(|ix| !new_window_ixs.contains(ix))
+                .collect::<Vec<_>>();
+            existing_ixs.sort_by(|a, b| abs_paths[*a].cmp(&abs_paths[*b]));
+            let mut results = abs_paths.iter().map(|_| None).collect::<Vec<_>>();
+            // The existing window is returned if anything was opened there, the last new one
+            // otherwise.
+            let mut window = existing;
+            let opens_in_existing = !existing_ixs.is_empty();
+            if opens_in_existing {
+                let existing_paths = existing_ixs
+                    .iter()
+                    .map(|ix| abs_paths[*ix].clone())
+                    .collect();
+                let existing_results = existing
                     .update(&mut cx, |workspace, cx| {
                         cx.activate_window();
-                        workspace.open_paths(abs_paths, open_visible, None, cx)
+                        workspace.open_paths(existing_paths, open_visible, None, cx)
                     })?
-                    .await,
-            ))
+                    .await;
+                for (ix, result) in existing_ixs.into_iter().zip(existing_results) {
+                    results[ix] = result;
+                }
+            }
+            for ix in new_window_ixs.into_iter().sorted() {
+                let new_window = cx.update(|cx| {
+                    Workspace::new_local(
+                        vec![abs_paths[ix].clone()],
+                        app_state.clone(),
+                        None,
+                        open_options.env.clone(),
+                        None,
+                        cx,
+                    )
+                })?;
+                match new_window.await {
+                    Ok((new_window, mut new_window_results)) => {
+                        if !opens_in_existing {
+                            window = new_window;
+                        }
+                        results[ix] = new_window_results.pop().flatten();
+                    }
+                    Err(error) => results[ix] = Some(Err(error)),
+                }
+            }
+            Ok((window, results))
         } else {
             cx.update(move |cx| {
                 Workspace::new_local(
@@ -5543,6 +7810,7 @@ pub fn open_paths(
                     app_state.clone(),
                     open_options.replace_window,
                     open_options.env,
+                    open_options.window_options_override,
                     cx,
                 )
             })?
@@ -5557,7 +7825,14 @@ pub fn open_new(
     cx: &mut AppContext,
     init: impl FnOnce(&mut Workspace, &mut ViewContext<Workspace>) + 'static + Send,
 ) -> Task<anyhow::Result<()>> {
-    let task = Workspace::new_local(Vec::new(), app_state, None, open_options.env, cx);
+    let task = Workspace::new_local(
+        Vec::new(),
+        app_state,
+        None,
+        open_options.env,
+        open_options.window_options_override,
+        cx,
+    );
     cx.spawn(|mut cx| async move {
         let (workspace, opened_paths) = task.await?;
         workspace.update(&mut cx, |workspace, cx| {
@@ -5609,42 +7884,54 @@ pub fn open_ssh_project(
         let (serialized_ssh_project, workspace_id, serialized_workspace) =
             serialize_ssh_project(connection_options.clone(), paths.clone(), &cx).await?;
 
-        let session = match cx
-            .update(|cx| {
-                remote::SshRemoteClient::new(
-                    ConnectionIdentifier::Workspace(workspace_id.0),
-                    connection_options,
-                    cancel_rx,
-                    delegate,
-                    cx,
-                )
-            })?
-            .await?
-        {
-            Some(result) => result,
-            None => return Ok(()),
-        };
+        // Another window restoring this workspace at the same time shares its connection.
+        let (project, connected) = ssh_sessions::shared_ssh_project(workspace_id, &mut cx, {
+            let app_state = app_state.clone();
+            move |cx| {
+                cx.spawn(|mut cx| async move {
+                    let session = match cx
+                        .update(|cx| {
+                            remote::SshRemoteClient::new(
+                                ConnectionIdentifier::Workspace(workspace_id.0),
+                                connection_options,
+                                cancel_rx,
+                                delegate,
+                                cx,
+                            )
+                        })?
+                        .await?
+                    {
+                        Some(result) => result,
+                        None => return Ok(None),
+                    };
 
-        let project = cx.update(|cx| {
-            project::Project::ssh(
-                session,
-                app_state.client.clone(),
-                app_state.node_runtime.clone(),
-                app_state.user_store.clone(),
-                app_state.languages.clone(),
-                app_state.fs.clone(),
-                cx,
-            )
-        })?;
+                    let project = cx.update(|cx| {
+                        project::Project::ssh(
+                            session,
+                            app_state.client.clone(),
+                            app_state.node_runtime.clone(),
+                            app_state.user_store.clone(),
+                            app_state.languages.clone(),
+                            app_state.fs.clone(),
+                            cx,
+                        )
+                    })?;
 
-        let toolchains = DB.toolchains(workspace_id).await?;
-        for (toolchain, worktree_id) in toolchains {
-            project
-                .update(&mut cx, |this, cx| {
-                    this.activate_toolchain(worktree_id, toolchain, cx)
-                })?
-                .await;
-        }
+                    let toolchains = DB.toolchains(workspace_id).await?;
+                    for (toolchain, worktree_id) in toolchains {
+                        project
+                            .update(&mut cx, |this, cx| {
+                                this.activate_toolchain(worktree_id, toolchain, cx)
+                            })?
+                            .await;
+                    }
+                    Ok(Some(project))
+                })
+            }
+        })?;
+        let Some(project) = project.await? else {
+            return Ok(());
+        };
         let mut project_paths_to_open = vec![];
         let mut project_path_errors = vec![];
 
@@ -5670,24 +7957,32 @@ pub fn open_ssh_project(
 
         cx.update_window(window.into(), |_, cx| {
             cx.replace_root_view(|cx| {
-                let mut workspace =
-                    Workspace::new(Some(workspace_id), project, app_state.clone(), cx);
+                // Only the window that connected is serialized, so that the workspace isn't
+                // written by two windows.
+                let mut workspace = Workspace::new(
+                    connected.then_some(workspace_id),
+                    project,
+                    app_state.clone(),
+                    cx,
+                );
 
                 workspace
                     .client()
                     .telemetry()
                     .report_app_event("open ssh project".to_string());
 
-                workspace.set_serialized_ssh_project(serialized_ssh_project);
+                if connected {
+                    workspace.set_serialized_ssh_project(serialized_ssh_project);
+                }
                 workspace
             });
         })?;
 
         window
-            .update(&mut cx, |_, cx| {
+            .update(&mut cx, |workspace, cx| {
                 cx.activate_window();
 
-                open_items(serialized_workspace, project_paths_to_open, cx)
+                open_items(workspace, serialized_workspace, project_paths_to_open, cx)
             })?
             .await?;
 
@@ -5952,12 +8247,7 @@ pub fn client_side_decorations(element: impl IntoElement, cx: &mut WindowContext
                     let size = cx.window_bounds().get_bounds().size;
                     let pos = e.position;
 
-                    let edge = match resize_edge(
-                        pos,
-                        theme::CLIENT_SIDE_DECORATION_SHADOW,
-                        size,
-                        tiling,
-                    ) {
+                    let edge = match window_resize_edge(pos, size, tiling, cx) {
                         Some(value) => value,
                         None => return,
                     };
@@ -5965,6 +8255,11 @@ pub fn client_side_decorations(element: impl IntoElement, cx: &mut WindowContext
                     cx.start_window_resize(edge);
                 }),
         })
+        .capture_any_mouse_up(|_, cx| {
+            if let Some(workspace) = Workspace::for_window(cx) {
+                workspace.update(cx, |workspace, _| workspace.end_item_drag());
+            }
+        })
         .size_full()
         .child(
             div()
@@ -6025,9 +8320,7 @@ pub fn client_side_decorations(element: impl IntoElement, cx: &mut WindowContext
                     move |_bounds, hitbox, cx| {
                         let mouse = cx.mouse_position();
                         let size = cx.window_bounds().get_bounds().size;
-                        let Some(edge) =
-                            resize_edge(mouse, theme::CLIENT_SIDE_DECORATION_SHADOW, size, tiling)
-                        else {
+                        let Some(edge) = window_resize_edge(mouse, size, tiling, cx) else {
                             return;
                         };
                         cx.set_global(GlobalResizeEdge(edge));
@@ -6054,6 +8347,28 @@ pub fn client_side_decorations(element: impl IntoElement, cx: &mut WindowContext
         })
 }
 
+/// Returns the edge to resize the window from at the given position, unless
+/// a tab is being dragged across the client-side decorations.
+fn window_resize_edge(
+    pos: Point<Pixels>,
+    window_size: Size<Pixels>,
+    tiling: Tiling,
+    cx: &mut WindowContext,
+) -> Option<ResizeEdge> {
+    let item_drag_in_progress = Workspace::for_window(cx).map_or(false, |workspace| {
+        workspace.read(cx).dragged_item_id().is_some()
+    });
+    if item_drag_in_progress {
+        return None;
+    }
+    resize_edge(
+        pos,
+        theme::CLIENT_SIDE_DECORATION_SHADOW,
+        window_size,
+        tiling,
+    )
+}
+
 fn resize_edge(
     pos: Point<Pixels>,
     shadow_size: Pixels,
@@ -6186,15 +8501,19 @@ pub fn move_item(
 
 #[cfg(test)]
 mod tests {
-    use std::{cell::RefCell, rc::Rc};
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
 
     use super::*;
     use crate::{
         dock::{test::TestPanel, PanelEvent},
         item::{
             test::{TestItem, TestProjectItem},
-            ItemEvent,
+            ItemEvent, SerializableNavigationData,
         },
+        notifications::NotifyTaskExt,
     };
     use fs::FakeFs;
     use gpui::{
@@ -6346,6 +8665,135 @@ mod tests {
         assert_eq!(cx.window_title().as_deref(), Some("root2 — one.txt"));
     }
 
+    #[gpui::test]
+    async fn test_item_title_changes(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({})).await;
+        let project = Project::test(fs, ["root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let new_item = |path: &str, cx: &mut VisualTestContext| {
+            cx.new_view(|cx| {
+                TestItem::new(cx).with_project_items(&[TestProjectItem::new(1, path, cx)])
+            })
+        };
+        let active_item = new_item("active.txt", cx);
+        let background_item = new_item("background.txt", cx);
+        let other_pane_item = new_item("other.txt", cx);
+        workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            let other_pane = workspace.split_pane(pane.clone(), SplitDirection::Right, cx);
+            other_pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(other_pane_item.clone()), true, true, None, cx)
+            });
+            workspace.activate_pane_in_direction(SplitDirection::Left, cx);
+            pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(background_item.clone()), true, true, None, cx);
+                pane.add_item(Box::new(active_item.clone()), true, true, None, cx);
+            });
+        });
+        cx.run_until_parked();
+        assert_eq!(cx.window_title().as_deref(), Some("root — active.txt"));
+
+        let notify_count = Rc::new(Cell::new(0));
+        let _subscription = cx.update(|cx| {
+            let notify_count = notify_count.clone();
+            cx.observe(&workspace, move |_, _| {
+                notify_count.set(notify_count.get() + 1)
+            })
+        });
+        let rename = |item: &View<TestItem>, path: &str, cx: &mut VisualTestContext| {
+            item.update(cx, |item, cx| {
+                let project_item = item.project_items[0].clone();
+                project_item.update(cx, |project_item, _| {
+                    project_item.project_path = Some((WorktreeId::from_usize(0), path).into())
+                });
+                cx.emit(ItemEvent::UpdateTab);
+            });
+        };
+
+        // Title changes of the other items leave the workspace and the window title alone.
+        for i in 0..10 {
+            rename(&background_item, &format!("background-{i}.txt"), cx);
+            rename(&other_pane_item, &format!("other-{i}.txt"), cx);
+        }
+        cx.run_until_parked();
+        assert_eq!(notify_count.get(), 0);
+        assert_eq!(cx.window_title().as_deref(), Some("root — active.txt"));
+
+        // The active item updates the window title right away, then at most every interval.
+        rename(&active_item, "renamed-1.txt", cx);
+        assert_eq!(cx.window_title().as_deref(), Some("root — renamed-1.txt"));
+        rename(&active_item, "renamed-2.txt", cx);
+        rename(&active_item, "renamed-3.txt", cx);
+        assert_eq!(cx.window_title().as_deref(), Some("root — renamed-1.txt"));
+        cx.executor().advance_clock(WINDOW_TITLE_UPDATE_INTERVAL);
+        cx.run_until_parked();
+        assert_eq!(cx.window_title().as_deref(), Some("root — renamed-3.txt"));
+    }
+
+    #[gpui::test]
+    async fn test_item_activity(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+
+        let edited_item = cx.new_view(TestItem::new);
+        let running_item = cx.new_view(TestItem::new);
+        let active_item = cx.new_view(TestItem::new);
+        pane.update(cx, |pane, cx| {
+            for item in [&edited_item, &running_item, &active_item] {
+                pane.add_item(Box::new(item.clone()), true, true, None, cx);
+            }
+        });
+        cx.run_until_parked();
+
+        let pane_notify_count = Rc::new(Cell::new(0));
+        let workspace_notify_count = Rc::new(Cell::new(0));
+        let _subscriptions = cx.update(|cx| {
+            let count = pane_notify_count.clone();
+            let pane_subscription = cx.observe(&pane, move |_, _| count.set(count.get() + 1));
+            let count = workspace_notify_count.clone();
+            let workspace_subscription =
+                cx.observe(&workspace, move |_, _| count.set(count.get() + 1));
+            [pane_subscription, workspace_subscription]
+        });
+        let set_activity =
+            |item: &View<TestItem>, activity: ActivityKind, cx: &mut VisualTestContext| {
+                item.update(cx, |item, cx| {
+                    item.activity = Some(activity);
+                    cx.emit(ItemEvent::UpdateActivity);
+                });
+            };
+
+        // Activity on background items re-renders their pane only.
+        set_activity(&edited_item, ActivityKind::ExternalEdit, cx);
+        set_activity(&running_item, ActivityKind::TaskRunning, cx);
+        cx.run_until_parked();
+        assert_eq!(pane_notify_count.get(), 2);
+        assert_eq!(workspace_notify_count.get(), 0);
+        cx.update(|cx| {
+            assert!(render_item_indicator(Box::new(edited_item.clone()), cx).is_some());
+            assert!(render_item_indicator(Box::new(active_item.clone()), cx).is_none());
+        });
+
+        // Activating the items clears their external edits, but not their running tasks.
+        pane.update(cx, |pane, cx| {
+            pane.activate_item(0, true, true, cx);
+            pane.activate_item(1, true, true, cx);
+        });
+        cx.run_until_parked();
+        edited_item.update(cx, |item, _| assert_eq!(item.activity, None));
+        running_item.update(cx, |item, _| {
+            assert_eq!(item.activity, Some(ActivityKind::TaskRunning))
+        });
+    }
+
     #[gpui::test]
     async fn test_close_window(cx: &mut TestAppContext) {
         init_test(cx);
@@ -6422,51 +8870,166 @@ mod tests {
     }
 
     #[gpui::test]
-    async fn test_close_pane_items(cx: &mut TestAppContext) {
+    async fn test_save_all_ordering_hints(cx: &mut TestAppContext) {
         init_test(cx);
 
         let fs = FakeFs::new(cx.executor());
-
         let project = Project::test(fs, None, cx).await;
         let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
 
-        let item1 = cx.new_view(|cx| {
+        let generated = cx.new_view(|cx| {
             TestItem::new(cx)
                 .with_dirty(true)
+                .with_save_ordering_hint(1)
                 .with_project_items(&[dirty_project_item(1, "1.txt", cx)])
         });
-        let item2 = cx.new_view(|cx| {
+        let source = cx.new_view(|cx| {
             TestItem::new(cx)
                 .with_dirty(true)
-                .with_conflict(true)
+                .with_save_ordering_hint(-1)
                 .with_project_items(&[dirty_project_item(2, "2.txt", cx)])
         });
-        let item3 = cx.new_view(|cx| {
+        let other = cx.new_view(|cx| {
             TestItem::new(cx)
                 .with_dirty(true)
-                .with_conflict(true)
                 .with_project_items(&[dirty_project_item(3, "3.txt", cx)])
         });
-        let item4 = cx.new_view(|cx| {
-            TestItem::new(cx).with_dirty(true).with_project_items(&[{
-                let project_item = TestProjectItem::new_untitled(cx);
-                project_item.update(cx, |project_item, _| project_item.is_dirty = true);
-                project_item
-            }])
+        workspace.update(cx, |w, cx| {
+            w.add_item_to_active_pane(Box::new(generated.clone()), None, true, cx);
+            w.add_item_to_active_pane(Box::new(source.clone()), None, true, cx);
+            w.add_item_to_active_pane(Box::new(other.clone()), None, true, cx);
         });
-        let pane = workspace.update(cx, |workspace, cx| {
-            workspace.add_item_to_active_pane(Box::new(item1.clone()), None, true, cx);
-            workspace.add_item_to_active_pane(Box::new(item2.clone()), None, true, cx);
-            workspace.add_item_to_active_pane(Box::new(item3.clone()), None, true, cx);
-            workspace.add_item_to_active_pane(Box::new(item4.clone()), None, true, cx);
-            workspace.active_pane().clone()
+
+        let saved_items = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let saved_items = saved_items.clone();
+            cx.subscribe(&workspace, move |_, event, _| {
+                if let Event::UserSavedItem { item, .. } = event {
+                    saved_items.borrow_mut().push(item.id());
+                }
+            })
+            .detach();
         });
 
-        let close_items = pane.update(cx, |pane, cx| {
-            pane.activate_item(1, true, true, cx);
-            assert_eq!(pane.active_item().unwrap().item_id(), item2.item_id());
-            let item1_id = item1.item_id();
-            let item3_id = item3.item_id();
+        // Saving before closing still prompts once for all the dirty items.
+        let task = workspace.update(cx, |w, cx| w.save_all_internal(SaveIntent::Close, cx));
+        cx.executor().run_until_parked();
+        cx.simulate_prompt_answer(0);
+        cx.executor().run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        assert!(task.await.unwrap());
+
+        assert_eq!(
+            *saved_items.borrow(),
+            [source.item_id(), other.item_id(), generated.item_id()]
+        );
+        for item in [&generated, &source, &other] {
+            item.read_with(cx, |item, _| assert_eq!(item.save_count, 1));
+        }
+    }
+
+    #[gpui::test]
+    async fn test_save_all_windows(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "a": {}, "b": {} })).await;
+        let project_a1 = Project::test(fs.clone(), ["/root/a".as_ref()], cx).await;
+        let project_a2 = Project::test(fs.clone(), ["/root/a".as_ref()], cx).await;
+        let project_b = Project::test(fs.clone(), ["/root/b".as_ref()], cx).await;
+        let window_a1 = cx.add_window(|cx| Workspace::test_new(project_a1, cx));
+        let window_a2 = cx.add_window(|cx| Workspace::test_new(project_a2, cx));
+        let window_b = cx.add_window(|cx| Workspace::test_new(project_b, cx));
+
+        let mut items = Vec::new();
+        for (ix, window) in [window_a1, window_a2, window_b].into_iter().enumerate() {
+            let item = window
+                .update(cx, |workspace, cx| {
+                    let item = cx.new_view(|cx| {
+                        TestItem::new(cx).with_dirty(true).with_project_items(&[
+                            dirty_project_item(ix as u64, &format!("{ix}.txt"), cx),
+                        ])
+                    });
+                    workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, cx);
+                    item
+                })
+                .unwrap();
+            items.push(item);
+        }
+
+        window_a1
+            .update(cx, |workspace, cx| {
+                let windows = workspace.broadcast_to_project_windows(cx, |_, _| ());
+                assert_eq!(
+                    windows
+                        .into_iter()
+                        .map(|(window, _)| window)
+                        .collect::<Vec<_>>(),
+                    [window_a2]
+                );
+                workspace.save_all_windows(&SaveAllWindows, cx);
+            })
+            .unwrap();
+        cx.run_until_parked();
+
+        let save_counts = items
+            .iter()
+            .map(|item| item.read_with(cx, |item, _| item.save_count))
+            .collect::<Vec<_>>();
+        assert_eq!(save_counts, [1, 1, 0]);
+        window_a1
+            .update(cx, |workspace, _| {
+                assert!(workspace.notification_ids().is_empty())
+            })
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_close_pane_items(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let item1 = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_project_items(&[dirty_project_item(1, "1.txt", cx)])
+        });
+        let item2 = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_conflict(true)
+                .with_project_items(&[dirty_project_item(2, "2.txt", cx)])
+        });
+        let item3 = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_conflict(true)
+                .with_project_items(&[dirty_project_item(3, "3.txt", cx)])
+        });
+        let item4 = cx.new_view(|cx| {
+            TestItem::new(cx).with_dirty(true).with_project_items(&[{
+                let project_item = TestProjectItem::new_untitled(cx);
+                project_item.update(cx, |project_item, _| project_item.is_dirty = true);
+                project_item
+            }])
+        });
+        let pane = workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item1.clone()), None, true, cx);
+            workspace.add_item_to_active_pane(Box::new(item2.clone()), None, true, cx);
+            workspace.add_item_to_active_pane(Box::new(item3.clone()), None, true, cx);
+            workspace.add_item_to_active_pane(Box::new(item4.clone()), None, true, cx);
+            workspace.active_pane().clone()
+        });
+
+        let close_items = pane.update(cx, |pane, cx| {
+            pane.activate_item(1, true, true, cx);
+            assert_eq!(pane.active_item().unwrap().item_id(), item2.item_id());
+            let item1_id = item1.item_id();
+            let item3_id = item3.item_id();
             let item4_id = item4.item_id();
             pane.close_items(cx, SaveIntent::Close, move |id| {
                 [item1_id, item3_id, item4_id].contains(&id)
@@ -6817,635 +9380,1287 @@ mod tests {
     }
 
     #[gpui::test]
-    async fn test_toggle_docks_and_panels(cx: &mut gpui::TestAppContext) {
+    async fn test_go_back_skips_duplicate_locations(cx: &mut gpui::TestAppContext) {
         init_test(cx);
-        let fs = FakeFs::new(cx.executor());
+        cx.update(register_navigation_data::<String>);
 
+        let fs = FakeFs::new(cx.executor());
         let project = Project::test(fs, [], cx).await;
         let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
 
-        let panel = workspace.update(cx, |workspace, cx| {
-            let panel = cx.new_view(|cx| TestPanel::new(DockPosition::Right, cx));
-            workspace.add_panel(panel.clone(), cx);
-
-            workspace
-                .right_dock()
-                .update(cx, |right_dock, cx| right_dock.set_open(true, cx));
-
-            panel
+        let item_1 = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(1, "1.txt", cx)])
         });
-
-        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
-        pane.update(cx, |pane, cx| {
-            let item = cx.new_view(TestItem::new);
-            pane.add_item(Box::new(item), true, true, None, cx);
+        let item_2 = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(2, "2.txt", cx)])
         });
-
-        // Transfer focus from center to panel
         workspace.update(cx, |workspace, cx| {
-            workspace.toggle_panel_focus::<TestPanel>(cx);
+            workspace.add_item_to_active_pane(Box::new(item_1.clone()), None, true, cx);
+            workspace.add_item_to_active_pane(Box::new(item_2.clone()), None, true, cx);
         });
 
-        workspace.update(cx, |workspace, cx| {
-            assert!(workspace.right_dock().read(cx).is_open());
-            assert!(!panel.is_zoomed(cx));
-            assert!(panel.read(cx).focus_handle(cx).contains_focused(cx));
+        // Activating the already active item again reports its location multiple times.
+        item_2.update(cx, |item, cx| {
+            item.state = "two".to_string();
+            item.deactivated(cx);
+            item.deactivated(cx);
         });
-
-        // Transfer focus from panel to center
-        workspace.update(cx, |workspace, cx| {
-            workspace.toggle_panel_focus::<TestPanel>(cx);
+        pane.update(cx, |pane, cx| {
+            pane.activate_item(1, true, true, cx);
+            pane.activate_item(1, true, true, cx);
         });
 
-        workspace.update(cx, |workspace, cx| {
-            assert!(workspace.right_dock().read(cx).is_open());
-            assert!(!panel.is_zoomed(cx));
-            assert!(!panel.read(cx).focus_handle(cx).contains_focused(cx));
+        // A single go back lands on the previous distinct location.
+        workspace
+            .update(cx, |workspace, cx| workspace.go_back(pane.downgrade(), cx))
+            .await
+            .unwrap();
+        pane.update(cx, |pane, _| {
+            assert_eq!(
+                pane.active_item().map(|item| item.item_id()),
+                Some(item_1.item_id())
+            );
+            assert!(!pane.can_navigate_backward());
         });
 
-        // Close the dock
-        workspace.update(cx, |workspace, cx| {
-            workspace.toggle_dock(DockPosition::Right, cx);
+        // Going forward returns to the second item in one step as well.
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.go_forward(pane.downgrade(), cx)
+            })
+            .await
+            .unwrap();
+        pane.update(cx, |pane, _| {
+            assert_eq!(
+                pane.active_item().map(|item| item.item_id()),
+                Some(item_2.item_id())
+            );
+            assert!(!pane.can_navigate_forward());
         });
+    }
 
-        workspace.update(cx, |workspace, cx| {
-            assert!(!workspace.right_dock().read(cx).is_open());
-            assert!(!panel.is_zoomed(cx));
-            assert!(!panel.read(cx).focus_handle(cx).contains_focused(cx));
-        });
+    #[gpui::test]
+    fn test_resolve_window_options(cx: &mut TestAppContext) {
+        let display_id = cx.update(|cx| cx.primary_display().unwrap().id());
+        let serialized_bounds = WindowBounds::Windowed(Bounds::new(
+            point(px(10.), px(10.)),
+            size(px(800.), px(600.)),
+        ));
+        let pinned_bounds = Bounds::new(point(px(0.), px(0.)), size(px(320.), px(240.)));
+        let env_bounds = Bounds::new(point(px(50.), px(50.)), size(px(1024.), px(768.)));
+
+        let options = resolve_window_options(
+            WindowOptions::default(),
+            Some(serialized_bounds),
+            None,
+            None,
+        );
+        assert_eq!(options.window_bounds, Some(serialized_bounds));
+
+        // The override can pin the window to a display and give it a fixed size.
+        let pin_window = || -> WindowOptionsOverride {
+            Box::new(move |options| WindowOptions {
+                display_id: Some(display_id),
+                window_bounds: Some(WindowBounds::Windowed(pinned_bounds)),
+                ..options
+            })
+        };
+        let options = resolve_window_options(
+            WindowOptions::default(),
+            Some(serialized_bounds),
+            Some(pin_window()),
+            None,
+        );
+        assert_eq!(options.display_id, Some(display_id));
+        assert_eq!(
+            options.window_bounds,
+            Some(WindowBounds::Windowed(pinned_bounds))
+        );
 
-        // Open the dock
-        workspace.update(cx, |workspace, cx| {
-            workspace.toggle_dock(DockPosition::Right, cx);
-        });
+        // The environment variables still win over the override.
+        let options = resolve_window_options(
+            WindowOptions::default(),
+            Some(serialized_bounds),
+            Some(pin_window()),
+            Some(env_bounds),
+        );
+        assert_eq!(options.display_id, None);
+        assert_eq!(
+            options.window_bounds,
+            Some(WindowBounds::Windowed(env_bounds))
+        );
+    }
 
-        workspace.update(cx, |workspace, cx| {
-            assert!(workspace.right_dock().read(cx).is_open());
-            assert!(!panel.is_zoomed(cx));
-            assert!(panel.read(cx).focus_handle(cx).contains_focused(cx));
-        });
+    #[test]
+    fn test_on_screen_window_bounds() {
+        let primary = Bounds::new(point(px(0.), px(0.)), size(px(1920.), px(1080.)));
+        let centered = |width: f32, height: f32| {
+            Bounds::centered_at(primary.center(), size(px(width), px(height)))
+        };
 
-        // Focus and zoom panel
-        panel.update(cx, |panel, cx| {
-            cx.focus_self();
-            panel.set_zoomed(true, cx)
-        });
+        // Fully off-screen windows are centered on the primary display.
+        let bounds = Bounds::new(point(px(5000.), px(5000.)), size(px(800.), px(600.)));
+        assert_eq!(
+            on_screen_window_bounds(bounds, &[primary], primary),
+            Some(centered(800., 600.))
+        );
 
-        workspace.update(cx, |workspace, cx| {
-            assert!(workspace.right_dock().read(cx).is_open());
-            assert!(panel.is_zoomed(cx));
-            assert!(panel.read(cx).focus_handle(cx).contains_focused(cx));
-        });
+        // Windows that are too large for the primary display are shrunk to fit.
+        let bounds = Bounds::new(point(px(-5000.), px(0.)), size(px(2560.), px(600.)));
+        assert_eq!(
+            on_screen_window_bounds(bounds, &[primary], primary),
+            Some(centered(1920., 600.))
+        );
 
-        // Transfer focus to the center closes the dock
-        workspace.update(cx, |workspace, cx| {
-            workspace.toggle_panel_focus::<TestPanel>(cx);
-        });
+        // Partially off-screen windows stay put as long as enough of them is visible.
+        let bounds = Bounds::new(point(px(1520.), px(100.)), size(px(800.), px(600.)));
+        assert_eq!(on_screen_window_bounds(bounds, &[primary], primary), None);
+        let bounds = Bounds::new(point(px(1820.), px(100.)), size(px(800.), px(600.)));
+        assert_eq!(
+            on_screen_window_bounds(bounds, &[primary], primary),
+            Some(centered(800., 600.))
+        );
 
-        workspace.update(cx, |workspace, cx| {
-            assert!(!workspace.right_dock().read(cx).is_open());
-            assert!(panel.is_zoomed(cx));
-            assert!(!panel.read(cx).focus_handle(cx).contains_focused(cx));
-        });
+        // Displays to the left of and above the primary one have negative coordinates.
+        let left = Bounds::new(point(px(-2560.), px(0.)), size(px(2560.), px(1440.)));
+        let top = Bounds::new(point(px(0.), px(-1080.)), size(px(1920.), px(1080.)));
+        let displays = [primary, left, top];
+        let bounds = Bounds::new(point(px(-2000.), px(200.)), size(px(800.), px(600.)));
+        assert_eq!(on_screen_window_bounds(bounds, &displays, primary), None);
+        let bounds = Bounds::new(point(px(100.), px(-900.)), size(px(800.), px(600.)));
+        assert_eq!(on_screen_window_bounds(bounds, &displays, primary), None);
+        // Straddling two displays counts the area visible on both.
+        let bounds = Bounds::new(point(px(-400.), px(-500.)), size(px(800.), px(600.)));
+        assert_eq!(on_screen_window_bounds(bounds, &displays, primary), None);
+        // The gap to the top left of the primary display is not covered by any display.
+        let bounds = Bounds::new(point(px(-900.), px(-700.)), size(px(800.), px(600.)));
+        assert_eq!(
+            on_screen_window_bounds(bounds, &displays, primary),
+            Some(centered(800., 600.))
+        );
+    }
 
-        // Transferring focus back to the panel keeps it zoomed
-        workspace.update(cx, |workspace, cx| {
-            workspace.toggle_panel_focus::<TestPanel>(cx);
-        });
+    #[gpui::test]
+    async fn test_preview_items_that_are_not_replaceable(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
 
-        workspace.update(cx, |workspace, cx| {
-            assert!(workspace.right_dock().read(cx).is_open());
-            assert!(panel.is_zoomed(cx));
-            assert!(panel.read(cx).focus_handle(cx).contains_focused(cx));
-        });
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
 
-        // Close the dock while it is zoomed
-        workspace.update(cx, |workspace, cx| {
-            workspace.toggle_dock(DockPosition::Right, cx)
+        let kept_item = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_preview_replaceable(false)
+                .with_project_items(&[TestProjectItem::new(1, "1.txt", cx)])
         });
-
-        workspace.update(cx, |workspace, cx| {
-            assert!(!workspace.right_dock().read(cx).is_open());
-            assert!(panel.is_zoomed(cx));
-            assert!(workspace.zoomed.is_none());
-            assert!(!panel.read(cx).focus_handle(cx).contains_focused(cx));
+        let replaced_item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(2, "2.txt", cx)])
         });
-
-        // Opening the dock, when it's zoomed, retains focus
-        workspace.update(cx, |workspace, cx| {
-            workspace.toggle_dock(DockPosition::Right, cx)
+        let new_item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(3, "3.txt", cx)])
         });
 
-        workspace.update(cx, |workspace, cx| {
-            assert!(workspace.right_dock().read(cx).is_open());
-            assert!(panel.is_zoomed(cx));
-            assert!(workspace.zoomed.is_some());
-            assert!(panel.read(cx).focus_handle(cx).contains_focused(cx));
-        });
+        pane.update(cx, |pane, cx| {
+            pane.open_item(None, true, true, None, cx, |_| Box::new(kept_item.clone()));
+            assert_eq!(pane.preview_item_id(), Some(kept_item.item_id()));
 
-        // Unzoom and close the panel, zoom the active pane.
-        panel.update(cx, |panel, cx| panel.set_zoomed(false, cx));
-        workspace.update(cx, |workspace, cx| {
-            workspace.toggle_dock(DockPosition::Right, cx)
-        });
-        pane.update(cx, |pane, cx| pane.toggle_zoom(&Default::default(), cx));
+            // Opening another preview item promotes the kept item instead of closing it.
+            pane.open_item(None, true, true, None, cx, |_| {
+                Box::new(replaced_item.clone())
+            });
+            assert_eq!(pane.items_len(), 2);
+            assert_eq!(pane.preview_item_id(), Some(replaced_item.item_id()));
 
-        // Opening a dock unzooms the pane.
-        workspace.update(cx, |workspace, cx| {
-            workspace.toggle_dock(DockPosition::Right, cx)
+            pane.open_item(None, true, true, None, cx, |_| Box::new(new_item.clone()));
+            assert_eq!(pane.items_len(), 2);
+            assert!(pane.index_for_item(&replaced_item).is_none());
+            assert_eq!(pane.preview_item_id(), Some(new_item.item_id()));
         });
-        workspace.update(cx, |workspace, cx| {
-            let pane = pane.read(cx);
-            assert!(!pane.is_zoomed());
-            assert!(!pane.focus_handle(cx).is_focused(cx));
-            assert!(workspace.right_dock().read(cx).is_open());
-            assert!(workspace.zoomed.is_none());
+
+        workspace.update(cx, |workspace, cx| workspace.promote_active_preview_tab(cx));
+        pane.update(cx, |pane, _| {
+            assert_eq!(pane.preview_item_id(), None);
+            assert_eq!(pane.items_len(), 2);
         });
     }
 
     #[gpui::test]
-    async fn test_join_pane_into_next(cx: &mut gpui::TestAppContext) {
+    async fn test_set_dock_open(cx: &mut gpui::TestAppContext) {
         init_test(cx);
-
         let fs = FakeFs::new(cx.executor());
 
-        let project = Project::test(fs, None, cx).await;
+        let project = Project::test(fs, [], cx).await;
         let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
 
-        // Let's arrange the panes like this:
-        //
-        // +-----------------------+
-        // |         top           |
-        // +------+--------+-------+
-        // | left | center | right |
-        // +------+--------+-------+
-        // |        bottom         |
-        // +-----------------------+
-
-        let top_item = cx.new_view(|cx| {
-            TestItem::new(cx).with_project_items(&[TestProjectItem::new(1, "top.txt", cx)])
-        });
-        let bottom_item = cx.new_view(|cx| {
-            TestItem::new(cx).with_project_items(&[TestProjectItem::new(2, "bottom.txt", cx)])
-        });
-        let left_item = cx.new_view(|cx| {
-            TestItem::new(cx).with_project_items(&[TestProjectItem::new(3, "left.txt", cx)])
-        });
-        let right_item = cx.new_view(|cx| {
-            TestItem::new(cx).with_project_items(&[TestProjectItem::new(4, "right.txt", cx)])
-        });
-        let center_item = cx.new_view(|cx| {
-            TestItem::new(cx).with_project_items(&[TestProjectItem::new(5, "center.txt", cx)])
-        });
-
-        let top_pane_id = workspace.update(cx, |workspace, cx| {
-            let top_pane_id = workspace.active_pane().entity_id();
-            workspace.add_item_to_active_pane(Box::new(top_item.clone()), None, false, cx);
-            workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Down, cx);
-            top_pane_id
-        });
-        let bottom_pane_id = workspace.update(cx, |workspace, cx| {
-            let bottom_pane_id = workspace.active_pane().entity_id();
-            workspace.add_item_to_active_pane(Box::new(bottom_item.clone()), None, false, cx);
-            workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Up, cx);
-            bottom_pane_id
-        });
-        let left_pane_id = workspace.update(cx, |workspace, cx| {
-            let left_pane_id = workspace.active_pane().entity_id();
-            workspace.add_item_to_active_pane(Box::new(left_item.clone()), None, false, cx);
-            workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Right, cx);
-            left_pane_id
-        });
-        let right_pane_id = workspace.update(cx, |workspace, cx| {
-            let right_pane_id = workspace.active_pane().entity_id();
-            workspace.add_item_to_active_pane(Box::new(right_item.clone()), None, false, cx);
-            workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Left, cx);
-            right_pane_id
-        });
-        let center_pane_id = workspace.update(cx, |workspace, cx| {
-            let center_pane_id = workspace.active_pane().entity_id();
-            workspace.add_item_to_active_pane(Box::new(center_item.clone()), None, false, cx);
-            center_pane_id
+        let panel = workspace.update(cx, |workspace, cx| {
+            let panel = cx.new_view(|cx| TestPanel::new(DockPosition::Bottom, cx));
+            workspace.add_panel(panel.clone(), cx);
+            panel
         });
-        cx.executor().run_until_parked();
-
         workspace.update(cx, |workspace, cx| {
-            assert_eq!(center_pane_id, workspace.active_pane().entity_id());
-
-            // Join into next from center pane into right
-            workspace.join_pane_into_next(workspace.active_pane().clone(), cx);
+            assert!(!workspace.is_dock_open(DockPosition::Bottom, cx));
         });
 
-        workspace.update(cx, |workspace, cx| {
-            let active_pane = workspace.active_pane();
-            assert_eq!(right_pane_id, active_pane.entity_id());
-            assert_eq!(2, active_pane.read(cx).items_len());
-            let item_ids_in_pane =
-                HashSet::from_iter(active_pane.read(cx).items().map(|item| item.item_id()));
-            assert!(item_ids_in_pane.contains(&center_item.item_id()));
-            assert!(item_ids_in_pane.contains(&right_item.item_id()));
-
-            // Join into next from right pane into bottom
-            workspace.join_pane_into_next(workspace.active_pane().clone(), cx);
-        });
+        // Forcing the dock open twice leaves it open and focused.
+        for _ in 0..2 {
+            cx.dispatch_action(SetDockOpen {
+                position: DockPosition::Bottom,
+                open: Some(true),
+            });
+            workspace.update(cx, |workspace, cx| {
+                assert!(workspace.is_dock_open(DockPosition::Bottom, cx));
+                assert!(panel.read(cx).focus_handle(cx).contains_focused(cx));
+            });
+        }
 
-        workspace.update(cx, |workspace, cx| {
-            let active_pane = workspace.active_pane();
-            assert_eq!(bottom_pane_id, active_pane.entity_id());
-            assert_eq!(3, active_pane.read(cx).items_len());
-            let item_ids_in_pane =
-                HashSet::from_iter(active_pane.read(cx).items().map(|item| item.item_id()));
-            assert!(item_ids_in_pane.contains(&center_item.item_id()));
-            assert!(item_ids_in_pane.contains(&right_item.item_id()));
-            assert!(item_ids_in_pane.contains(&bottom_item.item_id()));
+        // Forcing it closed twice leaves it closed and returns focus to the center.
+        for _ in 0..2 {
+            cx.dispatch_action(SetDockOpen {
+                position: DockPosition::Bottom,
+                open: Some(false),
+            });
+            workspace.update(cx, |workspace, cx| {
+                assert!(!workspace.is_dock_open(DockPosition::Bottom, cx));
+                assert!(workspace
+                    .active_pane()
+                    .focus_handle(cx)
+                    .contains_focused(cx));
+            });
+        }
 
-            // Join into next from bottom pane into left
-            workspace.join_pane_into_next(workspace.active_pane().clone(), cx);
+        // Without an explicit state, the action toggles, just like `ToggleBottomDock`.
+        cx.dispatch_action(SetDockOpen {
+            position: DockPosition::Bottom,
+            open: None,
         });
-
         workspace.update(cx, |workspace, cx| {
-            let active_pane = workspace.active_pane();
-            assert_eq!(left_pane_id, active_pane.entity_id());
-            assert_eq!(4, active_pane.read(cx).items_len());
-            let item_ids_in_pane =
-                HashSet::from_iter(active_pane.read(cx).items().map(|item| item.item_id()));
-            assert!(item_ids_in_pane.contains(&center_item.item_id()));
-            assert!(item_ids_in_pane.contains(&right_item.item_id()));
-            assert!(item_ids_in_pane.contains(&bottom_item.item_id()));
-            assert!(item_ids_in_pane.contains(&left_item.item_id()));
-
-            // Join into next from left pane into top
-            workspace.join_pane_into_next(workspace.active_pane().clone(), cx);
+            assert!(workspace.is_dock_open(DockPosition::Bottom, cx));
         });
-
+        cx.dispatch_action(ToggleBottomDock);
         workspace.update(cx, |workspace, cx| {
-            let active_pane = workspace.active_pane();
-            assert_eq!(top_pane_id, active_pane.entity_id());
-            assert_eq!(5, active_pane.read(cx).items_len());
-            let item_ids_in_pane =
-                HashSet::from_iter(active_pane.read(cx).items().map(|item| item.item_id()));
-            assert!(item_ids_in_pane.contains(&center_item.item_id()));
-            assert!(item_ids_in_pane.contains(&right_item.item_id()));
-            assert!(item_ids_in_pane.contains(&bottom_item.item_id()));
-            assert!(item_ids_in_pane.contains(&left_item.item_id()));
-            assert!(item_ids_in_pane.contains(&top_item.item_id()));
-
-            // Single pane left: no-op
-            workspace.join_pane_into_next(workspace.active_pane().clone(), cx)
-        });
-
-        workspace.update(cx, |workspace, _cx| {
-            let active_pane = workspace.active_pane();
-            assert_eq!(top_pane_id, active_pane.entity_id());
+            assert!(!workspace.is_dock_open(DockPosition::Bottom, cx));
         });
     }
 
-    fn add_an_item_to_active_pane(
-        cx: &mut VisualTestContext,
-        workspace: &View<Workspace>,
-        item_id: u64,
-    ) -> View<TestItem> {
-        let item = cx.new_view(|cx| {
-            TestItem::new(cx).with_project_items(&[TestProjectItem::new(
-                item_id,
-                "item{item_id}.txt",
-                cx,
-            )])
+    #[gpui::test]
+    async fn test_restore_focused_dock(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+        let panel = workspace.update(cx, |workspace, cx| {
+            let panel = cx.new_view(|cx| TestPanel::new(DockPosition::Bottom, cx));
+            workspace.add_panel(panel.clone(), cx);
+            workspace.toggle_dock(DockPosition::Bottom, cx);
+            cx.focus_view(&panel);
+            panel
         });
-        workspace.update(cx, |workspace, cx| {
-            workspace.add_item_to_active_pane(Box::new(item.clone()), None, false, cx);
+        cx.run_until_parked();
+        let docks = workspace.update(cx, |workspace, cx| {
+            assert!(panel.focus_handle(cx).is_focused(cx));
+            workspace.build_serialized_docks(cx)
+        });
+        assert!(docks.bottom.focused);
+        assert!(!docks.left.focused && !docks.right.focused);
+
+        let serialized_workspace = SerializedWorkspace {
+            id: WorkspaceId(1),
+            location: SerializedWorkspaceLocation::from_local_paths(["/root"]),
+            center_group: SerializedPaneGroup::Pane(SerializedPane::new(Vec::new(), true, 0)),
+            window_bounds: None,
+            centered_layout: false,
+            session_notes: None,
+            display: None,
+            docks,
+            session_id: None,
+            window_id: None,
+        };
+        let (restored_workspace, cx) =
+            cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        restored_workspace.update(cx, |workspace, cx| {
+            let panel = cx.new_view(|cx| TestPanel::new(DockPosition::Bottom, cx));
+            workspace.add_panel(panel, cx);
         });
-        return item;
-    }
-
-    fn split_pane(cx: &mut VisualTestContext, workspace: &View<Workspace>) -> View<Pane> {
-        return workspace.update(cx, |workspace, cx| {
-            let new_pane =
-                workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Right, cx);
-            new_pane
+        restored_workspace
+            .update(cx, |_, cx| {
+                Workspace::load_workspace(serialized_workspace, Vec::new(), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        restored_workspace.update(cx, |workspace, cx| {
+            let bottom_dock = workspace.bottom_dock().read(cx);
+            assert!(bottom_dock.is_open());
+            assert!(bottom_dock.focus_handle(cx).contains_focused(cx));
         });
     }
 
     #[gpui::test]
-    async fn test_join_all_panes(cx: &mut gpui::TestAppContext) {
+    async fn test_zoom_state(cx: &mut gpui::TestAppContext) {
         init_test(cx);
         let fs = FakeFs::new(cx.executor());
-        let project = Project::test(fs, None, cx).await;
+        let project = Project::test(fs, [], cx).await;
         let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
 
-        add_an_item_to_active_pane(cx, &workspace, 1);
-        split_pane(cx, &workspace);
-        add_an_item_to_active_pane(cx, &workspace, 2);
-        split_pane(cx, &workspace); // empty pane
-        split_pane(cx, &workspace);
-        let last_item = add_an_item_to_active_pane(cx, &workspace, 3);
-
-        cx.executor().run_until_parked();
-
-        workspace.update(cx, |workspace, cx| {
-            let num_panes = workspace.panes().len();
-            let num_items_in_current_pane = workspace.active_pane().read(cx).items().count();
-            let active_item = workspace
-                .active_pane()
-                .read(cx)
-                .active_item()
-                .expect("item is in focus");
+        let events = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let events = events.clone();
+            cx.subscribe(&workspace, move |_, event, _| {
+                if let Event::ZoomChanged(zoom_state) = event {
+                    events.borrow_mut().push(
+                        zoom_state
+                            .as_ref()
+                            .map(|zoom_state| (zoom_state.position, zoom_state.bounds)),
+                    );
+                }
+            })
+            .detach();
+        });
 
-            assert_eq!(num_panes, 4);
-            assert_eq!(num_items_in_current_pane, 1);
-            assert_eq!(active_item.item_id(), last_item.item_id());
+        // Zooming a pane reports the overlay bounds once it has been rendered.
+        add_an_item_to_active_pane(cx, &workspace, 1);
+        let pane = workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            pane.update(cx, |pane, cx| pane.focus(cx));
+            pane
         });
+        events.borrow_mut().clear();
+        cx.dispatch_action(ToggleZoom);
+        cx.run_until_parked();
+        let pane_bounds = workspace.update(cx, |workspace, cx| {
+            let zoom_state = workspace.zoom_state(cx).unwrap();
+            assert_eq!(zoom_state.view, AnyWeakView::from(pane.downgrade()));
+            assert_eq!(zoom_state.position, None);
+            let bounds = zoom_state.bounds.unwrap();
+            assert_eq!(bounds.center(), workspace.bounds.center());
+            assert!(bounds.size.width < workspace.bounds.size.width);
+            bounds
+        });
+        assert_eq!(events.borrow().first(), Some(&Some((None, None))));
+        assert_eq!(
+            events.borrow().last(),
+            Some(&Some((None, Some(pane_bounds))))
+        );
 
+        // Zooming a panel reports the dock it belongs to.
+        let panel = workspace.update(cx, |workspace, cx| {
+            let panel = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            workspace.add_panel(panel.clone(), cx);
+            workspace.toggle_dock(DockPosition::Left, cx);
+            panel
+        });
+        events.borrow_mut().clear();
+        panel.update(cx, |_, cx| cx.emit(PanelEvent::ZoomIn));
+        cx.run_until_parked();
         workspace.update(cx, |workspace, cx| {
-            workspace.join_all_panes(cx);
+            let zoom_state = workspace.zoom_state(cx).unwrap();
+            assert_eq!(zoom_state.view, panel.to_any().downgrade());
+            assert_eq!(zoom_state.position, Some(DockPosition::Left));
+            let bounds = zoom_state.bounds.unwrap();
+            assert_eq!(bounds.origin, workspace.bounds.origin);
+            assert_eq!(bounds.size.height, workspace.bounds.size.height);
+            assert!(bounds.size.width < workspace.bounds.size.width);
+            assert_eq!(
+                events.borrow().last(),
+                Some(&Some((Some(DockPosition::Left), Some(bounds))))
+            );
         });
 
+        panel.update(cx, |_, cx| cx.emit(PanelEvent::ZoomOut));
+        cx.run_until_parked();
         workspace.update(cx, |workspace, cx| {
-            let num_panes = workspace.panes().len();
-            let num_items_in_current_pane = workspace.active_pane().read(cx).items().count();
-            let active_item = workspace
-                .active_pane()
-                .read(cx)
-                .active_item()
-                .expect("item is in focus");
-
-            assert_eq!(num_panes, 1);
-            assert_eq!(num_items_in_current_pane, 3);
-            assert_eq!(active_item.item_id(), last_item.item_id());
+            assert!(workspace.zoom_state(cx).is_none())
         });
+        assert_eq!(events.borrow().last(), Some(&None));
     }
-    struct TestModal(FocusHandle);
 
-    impl TestModal {
-        fn new(cx: &mut ViewContext<Self>) -> Self {
-            Self(cx.focus_handle())
-        }
-    }
+    #[gpui::test]
+    async fn test_zoom_mode(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
 
-    impl EventEmitter<DismissEvent> for TestModal {}
+        let (left_panel, right_panel) = workspace.update(cx, |workspace, cx| {
+            let left_panel = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            let right_panel = cx.new_view(|cx| TestPanel::new(DockPosition::Right, cx));
+            workspace.add_panel(left_panel.clone(), cx);
+            workspace.add_panel(right_panel.clone(), cx);
+            workspace.toggle_dock(DockPosition::Left, cx);
+            workspace.toggle_dock(DockPosition::Right, cx);
+            (left_panel, right_panel)
+        });
+        let right_dock_width = right_panel.update(cx, |panel, _| panel.size);
 
-    impl FocusableView for TestModal {
-        fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
-            self.0.clone()
-        }
-    }
+        for zoom_mode in [ZoomMode::Overlay, ZoomMode::ReplaceCenter] {
+            cx.update(|cx| {
+                SettingsStore::update_global(cx, |settings, cx| {
+                    settings.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                        settings.zoom_mode = Some(zoom_mode);
+                    })
+                })
+            });
 
-    impl ModalView for TestModal {}
+            left_panel.update(cx, |_, cx| cx.emit(PanelEvent::ZoomIn));
+            cx.run_until_parked();
+            workspace.update(cx, |workspace, cx| {
+                let bounds = workspace.zoom_state(cx).unwrap().bounds.unwrap();
+                let center_right = workspace.bounds.right() - right_dock_width;
+                assert_eq!(bounds.origin, workspace.bounds.origin);
+                match zoom_mode {
+                    ZoomMode::Overlay => {
+                        // The zoomed panel covers the center panes along with the right dock.
+                        assert!(bounds.right() > center_right);
+                        assert!(!workspace.is_dock_open(DockPosition::Right, cx));
+                    }
+                    ZoomMode::ReplaceCenter => {
+                        // The zoomed panel takes the place of the center panes only.
+                        assert!(bounds.right() <= center_right);
+                        assert!(workspace.is_dock_open(DockPosition::Right, cx));
+                    }
+                }
+            });
 
-    impl Render for TestModal {
-        fn render(&mut self, _cx: &mut ViewContext<TestModal>) -> impl IntoElement {
-            div().track_focus(&self.0)
+            // Zooming out restores the previous layout.
+            left_panel.update(cx, |_, cx| cx.emit(PanelEvent::ZoomOut));
+            cx.run_until_parked();
+            workspace.update(cx, |workspace, cx| {
+                assert!(workspace.zoom_state(cx).is_none());
+                assert!(workspace.is_dock_open(DockPosition::Left, cx));
+                assert!(workspace.is_dock_open(DockPosition::Right, cx));
+            });
         }
     }
 
     #[gpui::test]
-    async fn test_panels(cx: &mut gpui::TestAppContext) {
+    async fn test_toggle_docks_and_panels(cx: &mut gpui::TestAppContext) {
         init_test(cx);
         let fs = FakeFs::new(cx.executor());
 
         let project = Project::test(fs, [], cx).await;
         let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
 
-        let (panel_1, panel_2) = workspace.update(cx, |workspace, cx| {
-            let panel_1 = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
-            workspace.add_panel(panel_1.clone(), cx);
-            workspace
-                .left_dock()
-                .update(cx, |left_dock, cx| left_dock.set_open(true, cx));
-            let panel_2 = cx.new_view(|cx| TestPanel::new(DockPosition::Right, cx));
-            workspace.add_panel(panel_2.clone(), cx);
+        let panel = workspace.update(cx, |workspace, cx| {
+            let panel = cx.new_view(|cx| TestPanel::new(DockPosition::Right, cx));
+            workspace.add_panel(panel.clone(), cx);
+
             workspace
                 .right_dock()
                 .update(cx, |right_dock, cx| right_dock.set_open(true, cx));
 
-            let left_dock = workspace.left_dock();
-            assert_eq!(
-                left_dock.read(cx).visible_panel().unwrap().panel_id(),
-                panel_1.panel_id()
-            );
-            assert_eq!(
-                left_dock.read(cx).active_panel_size(cx).unwrap(),
-                panel_1.size(cx)
-            );
-
-            left_dock.update(cx, |left_dock, cx| {
-                left_dock.resize_active_panel(Some(px(1337.)), cx)
-            });
-            assert_eq!(
-                workspace
-                    .right_dock()
-                    .read(cx)
-                    .visible_panel()
-                    .unwrap()
-                    .panel_id(),
-                panel_2.panel_id(),
-            );
+            panel
+        });
 
-            (panel_1, panel_2)
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+        pane.update(cx, |pane, cx| {
+            let item = cx.new_view(TestItem::new);
+            pane.add_item(Box::new(item), true, true, None, cx);
         });
 
-        // Move panel_1 to the right
-        panel_1.update(cx, |panel_1, cx| {
-            panel_1.set_position(DockPosition::Right, cx)
+        // Transfer focus from center to panel
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_panel_focus::<TestPanel>(cx);
         });
 
         workspace.update(cx, |workspace, cx| {
-            // Since panel_1 was visible on the left, it should now be visible now that it's been moved to the right.
-            // Since it was the only panel on the left, the left dock should now be closed.
-            assert!(!workspace.left_dock().read(cx).is_open());
-            assert!(workspace.left_dock().read(cx).visible_panel().is_none());
-            let right_dock = workspace.right_dock();
-            assert_eq!(
-                right_dock.read(cx).visible_panel().unwrap().panel_id(),
-                panel_1.panel_id()
-            );
-            assert_eq!(
-                right_dock.read(cx).active_panel_size(cx).unwrap(),
-                px(1337.)
-            );
+            assert!(workspace.right_dock().read(cx).is_open());
+            assert!(!panel.is_zoomed(cx));
+            assert!(panel.read(cx).focus_handle(cx).contains_focused(cx));
+        });
 
-            // Now we move panel_2 to the left
-            panel_2.set_position(DockPosition::Left, cx);
+        // Transfer focus from panel to center
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_panel_focus::<TestPanel>(cx);
         });
 
         workspace.update(cx, |workspace, cx| {
-            // Since panel_2 was not visible on the right, we don't open the left dock.
-            assert!(!workspace.left_dock().read(cx).is_open());
-            // And the right dock is unaffected in its displaying of panel_1
             assert!(workspace.right_dock().read(cx).is_open());
-            assert_eq!(
-                workspace
-                    .right_dock()
-                    .read(cx)
-                    .visible_panel()
-                    .unwrap()
-                    .panel_id(),
-                panel_1.panel_id(),
-            );
+            assert!(!panel.is_zoomed(cx));
+            assert!(!panel.read(cx).focus_handle(cx).contains_focused(cx));
         });
 
-        // Move panel_1 back to the left
-        panel_1.update(cx, |panel_1, cx| {
-            panel_1.set_position(DockPosition::Left, cx)
+        // Close the dock
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_dock(DockPosition::Right, cx);
         });
 
         workspace.update(cx, |workspace, cx| {
-            // Since panel_1 was visible on the right, we open the left dock and make panel_1 active.
-            let left_dock = workspace.left_dock();
-            assert!(left_dock.read(cx).is_open());
-            assert_eq!(
-                left_dock.read(cx).visible_panel().unwrap().panel_id(),
-                panel_1.panel_id()
-            );
-            assert_eq!(left_dock.read(cx).active_panel_size(cx).unwrap(), px(1337.));
-            // And the right dock should be closed as it no longer has any panels.
             assert!(!workspace.right_dock().read(cx).is_open());
-
-            // Now we move panel_1 to the bottom
-            panel_1.set_position(DockPosition::Bottom, cx);
+            assert!(!panel.is_zoomed(cx));
+            assert!(!panel.read(cx).focus_handle(cx).contains_focused(cx));
         });
 
+        // Open the dock
         workspace.update(cx, |workspace, cx| {
-            // Since panel_1 was visible on the left, we close the left dock.
-            assert!(!workspace.left_dock().read(cx).is_open());
-            // The bottom dock is sized based on the panel's default size,
-            // since the panel orientation changed from vertical to horizontal.
-            let bottom_dock = workspace.bottom_dock();
-            assert_eq!(
-                bottom_dock.read(cx).active_panel_size(cx).unwrap(),
-                panel_1.size(cx),
-            );
-            // Close bottom dock and move panel_1 back to the left.
-            bottom_dock.update(cx, |bottom_dock, cx| bottom_dock.set_open(false, cx));
-            panel_1.set_position(DockPosition::Left, cx);
+            workspace.toggle_dock(DockPosition::Right, cx);
         });
 
-        // Emit activated event on panel 1
-        panel_1.update(cx, |_, cx| cx.emit(PanelEvent::Activate));
-
-        // Now the left dock is open and panel_1 is active and focused.
         workspace.update(cx, |workspace, cx| {
-            let left_dock = workspace.left_dock();
-            assert!(left_dock.read(cx).is_open());
-            assert_eq!(
-                left_dock.read(cx).visible_panel().unwrap().panel_id(),
-                panel_1.panel_id(),
-            );
-            assert!(panel_1.focus_handle(cx).is_focused(cx));
+            assert!(workspace.right_dock().read(cx).is_open());
+            assert!(!panel.is_zoomed(cx));
+            assert!(panel.read(cx).focus_handle(cx).contains_focused(cx));
         });
 
-        // Emit closed event on panel 2, which is not active
-        panel_2.update(cx, |_, cx| cx.emit(PanelEvent::Close));
+        // Focus and zoom panel
+        panel.update(cx, |panel, cx| {
+            cx.focus_self();
+            panel.set_zoomed(true, cx)
+        });
 
-        // Wo don't close the left dock, because panel_2 wasn't the active panel
         workspace.update(cx, |workspace, cx| {
-            let left_dock = workspace.left_dock();
-            assert!(left_dock.read(cx).is_open());
-            assert_eq!(
-                left_dock.read(cx).visible_panel().unwrap().panel_id(),
-                panel_1.panel_id(),
-            );
+            assert!(workspace.right_dock().read(cx).is_open());
+            assert!(panel.is_zoomed(cx));
+            assert!(panel.read(cx).focus_handle(cx).contains_focused(cx));
         });
 
-        // Emitting a ZoomIn event shows the panel as zoomed.
-        panel_1.update(cx, |_, cx| cx.emit(PanelEvent::ZoomIn));
-        workspace.update(cx, |workspace, _| {
-            assert_eq!(workspace.zoomed, Some(panel_1.to_any().downgrade()));
-            assert_eq!(workspace.zoomed_position, Some(DockPosition::Left));
+        // Transfer focus to the center closes the dock
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_panel_focus::<TestPanel>(cx);
         });
 
-        // Move panel to another dock while it is zoomed
-        panel_1.update(cx, |panel, cx| panel.set_position(DockPosition::Right, cx));
-        workspace.update(cx, |workspace, _| {
-            assert_eq!(workspace.zoomed, Some(panel_1.to_any().downgrade()));
-
-            assert_eq!(workspace.zoomed_position, Some(DockPosition::Right));
+        workspace.update(cx, |workspace, cx| {
+            assert!(!workspace.right_dock().read(cx).is_open());
+            assert!(panel.is_zoomed(cx));
+            assert!(!panel.read(cx).focus_handle(cx).contains_focused(cx));
         });
 
-        // This is a helper for getting a:
-        // - valid focus on an element,
-        // - that isn't a part of the panes and panels system of the Workspace,
-        // - and doesn't trigger the 'on_focus_lost' API.
-        let focus_other_view = {
-            let workspace = workspace.clone();
-            move |cx: &mut VisualTestContext| {
-                workspace.update(cx, |workspace, cx| {
-                    if let Some(_) = workspace.active_modal::<TestModal>(cx) {
-                        workspace.toggle_modal(cx, TestModal::new);
-                        workspace.toggle_modal(cx, TestModal::new);
-                    } else {
-                        workspace.toggle_modal(cx, TestModal::new);
-                    }
-                })
-            }
-        };
+        // Transferring focus back to the panel keeps it zoomed
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_panel_focus::<TestPanel>(cx);
+        });
 
-        // If focus is transferred to another view that's not a panel or another pane, we still show
-        // the panel as zoomed.
-        focus_other_view(cx);
-        workspace.update(cx, |workspace, _| {
-            assert_eq!(workspace.zoomed, Some(panel_1.to_any().downgrade()));
-            assert_eq!(workspace.zoomed_position, Some(DockPosition::Right));
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.right_dock().read(cx).is_open());
+            assert!(panel.is_zoomed(cx));
+            assert!(panel.read(cx).focus_handle(cx).contains_focused(cx));
         });
 
-        // If focus is transferred elsewhere in the workspace, the panel is no longer zoomed.
-        workspace.update(cx, |_, cx| cx.focus_self());
-        workspace.update(cx, |workspace, _| {
-            assert_eq!(workspace.zoomed, None);
-            assert_eq!(workspace.zoomed_position, None);
+        // Close the dock while it is zoomed
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_dock(DockPosition::Right, cx)
         });
 
-        // If focus is transferred again to another view that's not a panel or a pane, we won't
-        // show the panel as zoomed because it wasn't zoomed before.
-        focus_other_view(cx);
-        workspace.update(cx, |workspace, _| {
-            assert_eq!(workspace.zoomed, None);
-            assert_eq!(workspace.zoomed_position, None);
+        workspace.update(cx, |workspace, cx| {
+            assert!(!workspace.right_dock().read(cx).is_open());
+            assert!(panel.is_zoomed(cx));
+            assert!(workspace.zoomed.is_none());
+            assert!(!panel.read(cx).focus_handle(cx).contains_focused(cx));
         });
 
-        // When the panel is activated, it is zoomed again.
-        cx.dispatch_action(ToggleRightDock);
-        workspace.update(cx, |workspace, _| {
-            assert_eq!(workspace.zoomed, Some(panel_1.to_any().downgrade()));
-            assert_eq!(workspace.zoomed_position, Some(DockPosition::Right));
+        // Opening the dock, when it's zoomed, retains focus
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_dock(DockPosition::Right, cx)
         });
 
-        // Emitting a ZoomOut event unzooms the panel.
-        panel_1.update(cx, |_, cx| cx.emit(PanelEvent::ZoomOut));
-        workspace.update(cx, |workspace, _| {
-            assert_eq!(workspace.zoomed, None);
-            assert_eq!(workspace.zoomed_position, None);
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.right_dock().read(cx).is_open());
+            assert!(panel.is_zoomed(cx));
+            assert!(workspace.zoomed.is_some());
+            assert!(panel.read(cx).focus_handle(cx).contains_focused(cx));
         });
 
-        // Emit closed event on panel 1, which is active
-        panel_1.update(cx, |_, cx| cx.emit(PanelEvent::Close));
+        // Unzoom and close the panel, zoom the active pane.
+        panel.update(cx, |panel, cx| panel.set_zoomed(false, cx));
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_dock(DockPosition::Right, cx)
+        });
+        pane.update(cx, |pane, cx| pane.toggle_zoom(&Default::default(), cx));
 
-        // Now the left dock is closed, because panel_1 was the active panel
+        // Opening a dock unzooms the pane.
         workspace.update(cx, |workspace, cx| {
-            let right_dock = workspace.right_dock();
-            assert!(!right_dock.read(cx).is_open());
+            workspace.toggle_dock(DockPosition::Right, cx)
+        });
+        workspace.update(cx, |workspace, cx| {
+            let pane = pane.read(cx);
+            assert!(!pane.is_zoomed());
+            assert!(!pane.focus_handle(cx).is_focused(cx));
+            assert!(workspace.right_dock().read(cx).is_open());
+            assert!(workspace.zoomed.is_none());
         });
     }
 
     #[gpui::test]
-    async fn test_no_save_prompt_when_multi_buffer_dirty_items_closed(cx: &mut TestAppContext) {
+    async fn test_join_pane_into_next(cx: &mut gpui::TestAppContext) {
         init_test(cx);
 
-        let fs = FakeFs::new(cx.background_executor.clone());
-        let project = Project::test(fs, [], cx).await;
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
         let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
-        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
 
-        let dirty_regular_buffer = cx.new_view(|cx| {
-            TestItem::new(cx)
-                .with_dirty(true)
-                .with_label("1.txt")
-                .with_project_items(&[dirty_project_item(1, "1.txt", cx)])
-        });
-        let dirty_regular_buffer_2 = cx.new_view(|cx| {
-            TestItem::new(cx)
-                .with_dirty(true)
-                .with_label("2.txt")
+        // Let's arrange the panes like this:
+        //
+        // +-----------------------+
+        // |         top           |
+        // +------+--------+-------+
+        // | left | center | right |
+        // +------+--------+-------+
+        // |        bottom         |
+        // +-----------------------+
+
+        let top_item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(1, "top.txt", cx)])
+        });
+        let bottom_item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(2, "bottom.txt", cx)])
+        });
+        let left_item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(3, "left.txt", cx)])
+        });
+        let right_item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(4, "right.txt", cx)])
+        });
+        let center_item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(5, "center.txt", cx)])
+        });
+
+        let top_pane_id = workspace.update(cx, |workspace, cx| {
+            let top_pane_id = workspace.active_pane().entity_id();
+            workspace.add_item_to_active_pane(Box::new(top_item.clone()), None, false, cx);
+            workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Down, cx);
+            top_pane_id
+        });
+        let bottom_pane_id = workspace.update(cx, |workspace, cx| {
+            let bottom_pane_id = workspace.active_pane().entity_id();
+            workspace.add_item_to_active_pane(Box::new(bottom_item.clone()), None, false, cx);
+            workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Up, cx);
+            bottom_pane_id
+        });
+        let left_pane_id = workspace.update(cx, |workspace, cx| {
+            let left_pane_id = workspace.active_pane().entity_id();
+            workspace.add_item_to_active_pane(Box::new(left_item.clone()), None, false, cx);
+            workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Right, cx);
+            left_pane_id
+        });
+        let right_pane_id = workspace.update(cx, |workspace, cx| {
+            let right_pane_id = workspace.active_pane().entity_id();
+            workspace.add_item_to_active_pane(Box::new(right_item.clone()), None, false, cx);
+            workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Left, cx);
+            right_pane_id
+        });
+        let center_pane_id = workspace.update(cx, |workspace, cx| {
+            let center_pane_id = workspace.active_pane().entity_id();
+            workspace.add_item_to_active_pane(Box::new(center_item.clone()), None, false, cx);
+            center_pane_id
+        });
+        cx.executor().run_until_parked();
+
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(center_pane_id, workspace.active_pane().entity_id());
+
+            // Join into next from center pane into right
+            workspace.join_pane_into_next(workspace.active_pane().clone(), cx);
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            let active_pane = workspace.active_pane();
+            assert_eq!(right_pane_id, active_pane.entity_id());
+            assert_eq!(2, active_pane.read(cx).items_len());
+            let item_ids_in_pane =
+                HashSet::from_iter(active_pane.read(cx).items().map(|item| item.item_id()));
+            assert!(item_ids_in_pane.contains(&center_item.item_id()));
+            assert!(item_ids_in_pane.contains(&right_item.item_id()));
+
+            // Join into next from right pane into bottom
+            workspace.join_pane_into_next(workspace.active_pane().clone(), cx);
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            let active_pane = workspace.active_pane();
+            assert_eq!(bottom_pane_id, active_pane.entity_id());
+            assert_eq!(3, active_pane.read(cx).items_len());
+            let item_ids_in_pane =
+                HashSet::from_iter(active_pane.read(cx).items().map(|item| item.item_id()));
+            assert!(item_ids_in_pane.contains(&center_item.item_id()));
+            assert!(item_ids_in_pane.contains(&right_item.item_id()));
+            assert!(item_ids_in_pane.contains(&bottom_item.item_id()));
+
+            // Join into next from bottom pane into left
+            workspace.join_pane_into_next(workspace.active_pane().clone(), cx);
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            let active_pane = workspace.active_pane();
+            assert_eq!(left_pane_id, active_pane.entity_id());
+            assert_eq!(4, active_pane.read(cx).items_len());
+            let item_ids_in_pane =
+                HashSet::from_iter(active_pane.read(cx).items().map(|item| item.item_id()));
+            assert!(item_ids_in_pane.contains(&center_item.item_id()));
+            assert!(item_ids_in_pane.contains(&right_item.item_id()));
+            assert!(item_ids_in_pane.contains(&bottom_item.item_id()));
+            assert!(item_ids_in_pane.contains(&left_item.item_id()));
+
+            // Join into next from left pane into top
+            workspace.join_pane_into_next(workspace.active_pane().clone(), cx);
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            let active_pane = workspace.active_pane();
+            assert_eq!(top_pane_id, active_pane.entity_id());
+            assert_eq!(5, active_pane.read(cx).items_len());
+            let item_ids_in_pane =
+                HashSet::from_iter(active_pane.read(cx).items().map(|item| item.item_id()));
+            assert!(item_ids_in_pane.contains(&center_item.item_id()));
+            assert!(item_ids_in_pane.contains(&right_item.item_id()));
+            assert!(item_ids_in_pane.contains(&bottom_item.item_id()));
+            assert!(item_ids_in_pane.contains(&left_item.item_id()));
+            assert!(item_ids_in_pane.contains(&top_item.item_id()));
+
+            // Single pane left: no-op
+            workspace.join_pane_into_next(workspace.active_pane().clone(), cx)
+        });
+
+        workspace.update(cx, |workspace, _cx| {
+            let active_pane = workspace.active_pane();
+            assert_eq!(top_pane_id, active_pane.entity_id());
+        });
+    }
+
+    fn add_an_item_to_active_pane(
+        cx: &mut VisualTestContext,
+        workspace: &View<Workspace>,
+        item_id: u64,
+    ) -> View<TestItem> {
+        let item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(
+                item_id,
+                "item{item_id}.txt",
+                cx,
+            )])
+        });
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, false, cx);
+        });
+        return item;
+    }
+
+    fn split_pane(cx: &mut VisualTestContext, workspace: &View<Workspace>) -> View<Pane> {
+        return workspace.update(cx, |workspace, cx| {
+            let new_pane =
+                workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Right, cx);
+            new_pane
+        });
+    }
+
+    #[gpui::test]
+    async fn test_rebalance_on_pane_close(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let root_flexes = |workspace: &Workspace| match &workspace.center.root {
+            Member::Axis(axis) => axis.flexes.lock().clone(),
+            Member::Pane(_) => Vec::new(),
+        };
+        let set_root_flexes = |flexes: Vec<f32>, cx: &mut VisualTestContext| {
+            workspace.update(cx, |workspace, _| {
+                let Member::Axis(axis) = &workspace.center.root else {
+                    panic!("expected a split");
+                };
+                *axis.flexes.lock() = flexes;
+            });
+        };
+        // A pane on the left, next to a column of two panes.
+        let split_into_column = |cx: &mut VisualTestContext| {
+            add_an_item_to_active_pane(cx, &workspace, 1);
+            let bottom_pane = workspace.update(cx, |workspace, cx| {
+                let top_pane = workspace.split_pane(
+                    workspace.active_pane().clone(),
+                    SplitDirection::Right,
+                    cx,
+                );
+                workspace.split_pane(top_pane, SplitDirection::Down, cx)
+            });
+            set_root_flexes(vec![1.5, 0.5], cx);
+            bottom_pane
+        };
+        let remove_all_but_first_pane = |cx: &mut VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                let panes = workspace.panes().to_vec();
+                for pane in panes.into_iter().skip(1) {
+                    workspace.remove_pane(pane, None, cx);
+                }
+            });
+        };
+
+        // By default, removing the bottom pane of the column leaves the sizes of the axis
+        // containing the column as they were.
+        let bottom_pane = split_into_column(cx);
+        workspace.update(cx, |workspace, cx| {
+            workspace.remove_pane(bottom_pane, None, cx);
+            assert_eq!(root_flexes(workspace), [1.5, 0.5]);
+        });
+        remove_all_but_first_pane(cx);
+
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |settings, cx| {
+                settings.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.rebalance_on_pane_close = Some(true);
+                })
+            });
+        });
+
+        // With the setting on, the surviving panes are equalized.
+        let bottom_pane = split_into_column(cx);
+        workspace.update(cx, |workspace, cx| {
+            workspace.remove_pane(bottom_pane, None, cx);
+            assert_eq!(root_flexes(workspace), [1., 1.]);
+        });
+        remove_all_but_first_pane(cx);
+
+        add_an_item_to_active_pane(cx, &workspace, 2);
+        let middle_pane = split_pane(cx, &workspace);
+        split_pane(cx, &workspace);
+        set_root_flexes(vec![1.5, 0.5, 1.], cx);
+        workspace.update(cx, |workspace, cx| {
+            workspace.remove_pane(middle_pane, None, cx);
+            assert_eq!(root_flexes(workspace), [1., 1.]);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_swap_pane_with_index(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let item_1 = add_an_item_to_active_pane(cx, &workspace, 1);
+        split_pane(cx, &workspace);
+        let item_2 = add_an_item_to_active_pane(cx, &workspace, 2);
+        let active_pane = split_pane(cx, &workspace);
+        let item_3 = add_an_item_to_active_pane(cx, &workspace, 3);
+
+        let item_ids_by_pane = |workspace: &Workspace, cx: &AppContext| {
+            workspace
+                .center
+                .panes()
+                .into_iter()
+                .map(|pane| {
+                    pane.read(cx)
+                        .items()
+                        .map(|item| item.item_id())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        cx.dispatch_action(SwapPaneWithIndex(0));
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(
+                item_ids_by_pane(workspace, cx),
+                [
+                    vec![item_3.item_id()],
+                    vec![item_2.item_id()],
+                    vec![item_1.item_id()]
+                ]
+            );
+            assert_eq!(workspace.active_pane(), &active_pane);
+            assert_eq!(workspace.center.panes()[0], &active_pane);
+            assert!(active_pane.focus_handle(cx).contains_focused(cx));
+        });
+
+        // Out of range indices leave the layout as it was.
+        cx.dispatch_action(SwapPaneWithIndex(3));
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(
+                item_ids_by_pane(workspace, cx),
+                [
+                    vec![item_3.item_id()],
+                    vec![item_2.item_id()],
+                    vec![item_1.item_id()]
+                ]
+            );
+            assert_eq!(workspace.notification_ids().len(), 1);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_item_drag_suppresses_window_resize(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let item = add_an_item_to_active_pane(cx, &workspace, 1);
+
+        let window_size = size(px(800.), px(600.));
+        let shadow_corner = point(px(1.), px(1.));
+        cx.update(|cx| {
+            assert_eq!(
+                window_resize_edge(shadow_corner, window_size, Tiling::default(), cx),
+                Some(ResizeEdge::TopLeft)
+            );
+        });
+
+        workspace.update(cx, |workspace, _| {
+            workspace.begin_item_drag(item.entity_id());
+        });
+        cx.update(|cx| {
+            assert_eq!(
+                window_resize_edge(shadow_corner, window_size, Tiling::default(), cx),
+                None,
+                "no window resize should start while a tab is dragged across the shadow"
+            );
+        });
+
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.dragged_item_id(), Some(item.entity_id()));
+            workspace.end_item_drag();
+        });
+        cx.update(|cx| {
+            assert_eq!(
+                window_resize_edge(shadow_corner, window_size, Tiling::default(), cx),
+                Some(ResizeEdge::TopLeft)
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_item_drag_ends_on_mouse_up(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let item = add_an_item_to_active_pane(cx, &workspace, 1);
+
+        workspace.update(cx, |workspace, _| {
+            workspace.begin_item_drag(item.entity_id());
+        });
+        // Releasing the tab away from any drop target cancels the drag.
+        cx.simulate_mouse_up(
+            point(px(100.), px(100.)),
+            MouseButton::Left,
+            gpui::Modifiers::none(),
+        );
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.dragged_item_id(), None);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_join_all_panes(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        add_an_item_to_active_pane(cx, &workspace, 1);
+        split_pane(cx, &workspace);
+        add_an_item_to_active_pane(cx, &workspace, 2);
+        split_pane(cx, &workspace); // empty pane
+        split_pane(cx, &workspace);
+        let last_item = add_an_item_to_active_pane(cx, &workspace, 3);
+
+        cx.executor().run_until_parked();
+
+        workspace.update(cx, |workspace, cx| {
+            let num_panes = workspace.panes().len();
+            let num_items_in_current_pane = workspace.active_pane().read(cx).items().count();
+            let active_item = workspace
+                .active_pane()
+                .read(cx)
+                .active_item()
+                .expect("item is in focus");
+
+            assert_eq!(num_panes, 4);
+            assert_eq!(num_items_in_current_pane, 1);
+            assert_eq!(active_item.item_id(), last_item.item_id());
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            workspace.join_all_panes(cx);
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            let num_panes = workspace.panes().len();
+            let num_items_in_current_pane = workspace.active_pane().read(cx).items().count();
+            let active_item = workspace
+                .active_pane()
+                .read(cx)
+                .active_item()
+                .expect("item is in focus");
+
+            assert_eq!(num_panes, 1);
+            assert_eq!(num_items_in_current_pane, 3);
+            assert_eq!(active_item.item_id(), last_item.item_id());
+        });
+    }
+    pub(crate) struct TestModal(FocusHandle);
+
+    impl TestModal {
+        pub(crate) fn new(cx: &mut ViewContext<Self>) -> Self {
+            Self(cx.focus_handle())
+        }
+    }
+
+    impl EventEmitter<DismissEvent> for TestModal {}
+
+    impl FocusableView for TestModal {
+        fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+            self.0.clone()
+        }
+    }
+
+    impl ModalView for TestModal {}
+
+    impl Render for TestModal {
+        fn render(&mut self, _cx: &mut ViewContext<TestModal>) -> impl IntoElement {
+            div().track_focus(&self.0)
+        }
+    }
+
+    #[gpui::test]
+    async fn test_panels(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let (panel_1, panel_2) = workspace.update(cx, |workspace, cx| {
+            let panel_1 = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            workspace.add_panel(panel_1.clone(), cx);
+            workspace
+                .left_dock()
+                .update(cx, |left_dock, cx| left_dock.set_open(true, cx));
+            let panel_2 = cx.new_view(|cx| TestPanel::new(DockPosition::Right, cx));
+            workspace.add_panel(panel_2.clone(), cx);
+            workspace
+                .right_dock()
+                .update(cx, |right_dock, cx| right_dock.set_open(true, cx));
+
+            let left_dock = workspace.left_dock();
+            assert_eq!(
+                left_dock.read(cx).visible_panel().unwrap().panel_id(),
+                panel_1.panel_id()
+            );
+            assert_eq!(
+                left_dock.read(cx).active_panel_size(cx).unwrap(),
+                panel_1.size(cx)
+            );
+
+            left_dock.update(cx, |left_dock, cx| {
+                left_dock.resize_active_panel(Some(px(1337.)), cx)
+            });
+            assert_eq!(
+                workspace
+                    .right_dock()
+                    .read(cx)
+                    .visible_panel()
+                    .unwrap()
+                    .panel_id(),
+                panel_2.panel_id(),
+            );
+
+            (panel_1, panel_2)
+        });
+
+        // Move panel_1 to the right
+        panel_1.update(cx, |panel_1, cx| {
+            panel_1.set_position(DockPosition::Right, cx)
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            // Since panel_1 was visible on the left, it should now be visible now that it's been moved to the right.
+            // Since it was the only panel on the left, the left dock should now be closed.
+            assert!(!workspace.left_dock().read(cx).is_open());
+            assert!(workspace.left_dock().read(cx).visible_panel().is_none());
+            let right_dock = workspace.right_dock();
+            assert_eq!(
+                right_dock.read(cx).visible_panel().unwrap().panel_id(),
+                panel_1.panel_id()
+            );
+            assert_eq!(
+                right_dock.read(cx).active_panel_size(cx).unwrap(),
+                px(1337.)
+            );
+
+            // Now we move panel_2 to the left
+            panel_2.set_position(DockPosition::Left, cx);
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            // Since panel_2 was not visible on the right, we don't open the left dock.
+            assert!(!workspace.left_dock().read(cx).is_open());
+            // And the right dock is unaffected in its displaying of panel_1
+            assert!(workspace.right_dock().read(cx).is_open());
+            assert_eq!(
+                workspace
+                    .right_dock()
+                    .read(cx)
+                    .visible_panel()
+                    .unwrap()
+                    .panel_id(),
+                panel_1.panel_id(),
+            );
+        });
+
+        // Move panel_1 back to the left
+        panel_1.update(cx, |panel_1, cx| {
+            panel_1.set_position(DockPosition::Left, cx)
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            // Since panel_1 was visible on the right, we open the left dock and make panel_1 active.
+            let left_dock = workspace.left_dock();
+            assert!(left_dock.read(cx).is_open());
+            assert_eq!(
+                left_dock.read(cx).visible_panel().unwrap().panel_id(),
+                panel_1.panel_id()
+            );
+            assert_eq!(left_dock.read(cx).active_panel_size(cx).unwrap(), px(1337.));
+            // And the right dock should be closed as it no longer has any panels.
+            assert!(!workspace.right_dock().read(cx).is_open());
+
+            // Now we move panel_1 to the bottom
+            panel_1.set_position(DockPosition::Bottom, cx);
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            // Since panel_1 was visible on the left, we close the left dock.
+            assert!(!workspace.left_dock().read(cx).is_open());
+            // The bottom dock is sized based on the panel's default size,
+            // since the panel orientation changed from vertical to horizontal.
+            let bottom_dock = workspace.bottom_dock();
+            assert_eq!(
+                bottom_dock.read(cx).active_panel_size(cx).unwrap(),
+                panel_1.size(cx),
+            );
+            // Close bottom dock and move panel_1 back to the left.
+            bottom_dock.update(cx, |bottom_dock, cx| bottom_dock.set_open(false, cx));
+            panel_1.set_position(DockPosition::Left, cx);
+        });
+
+        // Emit activated event on panel 1
+        panel_1.update(cx, |_, cx| cx.emit(PanelEvent::Activate));
+
+        // Now the left dock is open and panel_1 is active and focused.
+        workspace.update(cx, |workspace, cx| {
+            let left_dock = workspace.left_dock();
+            assert!(left_dock.read(cx).is_open());
+            assert_eq!(
+                left_dock.read(cx).visible_panel().unwrap().panel_id(),
+                panel_1.panel_id(),
+            );
+            assert!(panel_1.focus_handle(cx).is_focused(cx));
+        });
+
+        // Emit closed event on panel 2, which is not active
+        panel_2.update(cx, |_, cx| cx.emit(PanelEvent::Close));
+
+        // Wo don't close the left dock, because panel_2 wasn't the active panel
+        workspace.update(cx, |workspace, cx| {
+            let left_dock = workspace.left_dock();
+            assert!(left_dock.read(cx).is_open());
+            assert_eq!(
+                left_dock.read(cx).visible_panel().unwrap().panel_id(),
+                panel_1.panel_id(),
+            );
+        });
+
+        // Emitting a ZoomIn event shows the panel as zoomed.
+        panel_1.update(cx, |_, cx| cx.emit(PanelEvent::ZoomIn));
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.zoomed, Some(panel_1.to_any().downgrade()));
+            assert_eq!(workspace.zoomed_position, Some(DockPosition::Left));
+        });
+
+        // Move panel to another dock while it is zoomed
+        panel_1.update(cx, |panel, cx| panel.set_position(DockPosition::Right, cx));
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.zoomed, Some(panel_1.to_any().downgrade()));
+
+            assert_eq!(workspace.zoomed_position, Some(DockPosition::Right));
+        });
+
+        // This is a helper for getting a:
+        // - valid focus on an element,
+        // - that isn't a part of the panes and panels system of the Workspace,
+        // - and doesn't trigger the 'on_focus_lost' API.
+        let focus_other_view = {
+            let workspace = workspace.clone();
+            move |cx: &mut VisualTestContext| {
+                workspace.update(cx, |workspace, cx| {
+                    if let Some(_) = workspace.active_modal::<TestModal>(cx) {
+                        workspace.toggle_modal(cx, TestModal::new);
+                        workspace.toggle_modal(cx, TestModal::new);
+                    } else {
+                        workspace.toggle_modal(cx, TestModal::new);
+                    }
+                })
+            }
+        };
+
+        // If focus is transferred to another view that's not a panel or another pane, we still show
+        // the panel as zoomed.
+        focus_other_view(cx);
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.zoomed, Some(panel_1.to_any().downgrade()));
+            assert_eq!(workspace.zoomed_position, Some(DockPosition::Right));
+        });
+
+        // If focus is transferred elsewhere in the workspace, the panel is no longer zoomed.
+        workspace.update(cx, |_, cx| cx.focus_self());
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.zoomed, None);
+            assert_eq!(workspace.zoomed_position, None);
+        });
+
+        // If focus is transferred again to another view that's not a panel or a pane, we won't
+        // show the panel as zoomed because it wasn't zoomed before.
+        focus_other_view(cx);
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.zoomed, None);
+            assert_eq!(workspace.zoomed_position, None);
+        });
+
+        // When the panel is activated, it is zoomed again.
+        cx.dispatch_action(ToggleRightDock);
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.zoomed, Some(panel_1.to_any().downgrade()));
+            assert_eq!(workspace.zoomed_position, Some(DockPosition::Right));
+        });
+
+        // Emitting a ZoomOut event unzooms the panel.
+        panel_1.update(cx, |_, cx| cx.emit(PanelEvent::ZoomOut));
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.zoomed, None);
+            assert_eq!(workspace.zoomed_position, None);
+        });
+
+        // Emit closed event on panel 1, which is active
+        panel_1.update(cx, |_, cx| cx.emit(PanelEvent::Close));
+
+        // Now the left dock is closed, because panel_1 was the active panel
+        workspace.update(cx, |workspace, cx| {
+            let right_dock = workspace.right_dock();
+            assert!(!right_dock.read(cx).is_open());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_no_save_prompt_when_multi_buffer_dirty_items_closed(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+
+        let dirty_regular_buffer = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_label("1.txt")
+                .with_project_items(&[dirty_project_item(1, "1.txt", cx)])
+        });
+        let dirty_regular_buffer_2 = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_label("2.txt")
                 .with_project_items(&[dirty_project_item(2, "2.txt", cx)])
         });
         let dirty_multi_buffer_with_both = cx.new_view(|cx| {
@@ -7855,6 +11070,121 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_toggle_tab_bar_for_pane(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| register_serializable_item::<TestItem>(cx));
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({})).await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let workspace_id = DB.next_id().await.unwrap();
+        let (left_pane, right_pane) = workspace.update(cx, |workspace, cx| {
+            workspace.database_id = Some(workspace_id);
+            let left_pane = workspace.active_pane().clone();
+            let item = Box::new(cx.new_view(TestItem::new));
+            workspace.add_item_to_active_pane(item, None, true, cx);
+            let right_pane = workspace.split_pane(left_pane.clone(), SplitDirection::Right, cx);
+            right_pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(cx.new_view(TestItem::new)), true, true, None, cx)
+            });
+            (left_pane, right_pane)
+        });
+        cx.run_until_parked();
+
+        let visibility = |pane: &View<Pane>, cx: &mut VisualTestContext| {
+            pane.update(cx, |pane, cx| {
+                (pane.tab_bar_visibility(), pane.is_tab_bar_visible(cx))
+            })
+        };
+        cx.dispatch_action(ToggleTabBarForPane);
+        assert_eq!(visibility(&right_pane, cx), (Some(false), false));
+        assert_eq!(visibility(&left_pane, cx), (None, true));
+
+        // Toggling back makes the pane follow the settings again.
+        cx.dispatch_action(ToggleTabBarForPane);
+        assert_eq!(visibility(&right_pane, cx), (None, true));
+
+        cx.dispatch_action(ToggleTabBarForPane);
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.serialize_workspace_internal(cx)
+            })
+            .await;
+        let serialized_workspace = DB.workspace_for_roots(&["/root"]).unwrap();
+        let restored_workspace = cx.new_view(|cx| Workspace::test_new(project.clone(), cx));
+        restored_workspace
+            .update(cx, |_, cx| {
+                Workspace::load_workspace(serialized_workspace, Vec::new(), cx)
+            })
+            .await
+            .unwrap();
+        let restored_panes = restored_workspace.update(cx, |workspace, _| {
+            workspace
+                .center
+                .panes()
+                .into_iter()
+                .cloned()
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(restored_panes.len(), 2);
+        assert_eq!(visibility(&restored_panes[0], cx), (None, true));
+        assert_eq!(visibility(&restored_panes[1], cx), (Some(false), false));
+    }
+
+    #[gpui::test]
+    async fn test_restore_item_navigation_data(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| register_serializable_item::<TestItem>(cx));
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        // Only the data saved by the same kind of item is restored.
+        let item = |item_id, navigation_data: Option<SerializableNavigationData>| SerializedItem {
+            navigation_data,
+            ..SerializedItem::new("TestItem", item_id, item_id == 1, false)
+        };
+        let serialized_workspace = SerializedWorkspace {
+            id: WorkspaceId(1),
+            location: SerializedWorkspaceLocation::from_local_paths(["/root"]),
+            center_group: SerializedPaneGroup::Pane(SerializedPane::new(
+                vec![
+                    item(1, SerializableNavigationData::new("TestItem", &"line 42")),
+                    item(2, SerializableNavigationData::new("Terminal", &"line 42")),
+                    item(3, None),
+                ],
+                true,
+                0,
+            )),
+            window_bounds: None,
+            centered_layout: false,
+            session_notes: None,
+            display: None,
+            docks: Default::default(),
+            session_id: None,
+            window_id: None,
+        };
+        workspace
+            .update(cx, |_, cx| {
+                Workspace::load_workspace(serialized_workspace, Vec::new(), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        let states = workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_pane()
+                .read(cx)
+                .items_of_type::<TestItem>()
+                .map(|item| item.read(cx).state.clone())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(states, ["line 42", "", ""]);
+    }
+
     mod register_project_item_tests {
         use ui::Context as _;
 
@@ -7880,36 +11210,245 @@ mod tests {
                 }
             }
 
-            fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
-                None
-            }
-
-            fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
-                None
-            }
-
-            fn is_dirty(&self) -> bool {
-                false
-            }
+            fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
+                None
+            }
+
+            fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+                None
+            }
+
+            fn is_dirty(&self) -> bool {
+                false
+            }
+        }
+
+        impl Item for TestPngItemView {
+            type Event = ();
+        }
+        impl EventEmitter<()> for TestPngItemView {}
+        impl FocusableView for TestPngItemView {
+            fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+                self.focus_handle.clone()
+            }
+        }
+
+        impl Render for TestPngItemView {
+            fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+                Empty
+            }
+        }
+
+        impl ProjectItem for TestPngItemView {
+            type Item = TestPngItem;
+
+            fn for_project_item(
+                _project: Model<Project>,
+                _item: Model<Self::Item>,
+                cx: &mut ViewContext<Self>,
+            ) -> Self
+            where
+                Self: Sized,
+            {
+                Self {
+                    focus_handle: cx.focus_handle(),
+                }
+            }
+        }
+
+        // View
+        struct TestIpynbItemView {
+            focus_handle: FocusHandle,
+        }
+        // Model
+        struct TestIpynbItem {}
+
+        impl project::ProjectItem for TestIpynbItem {
+            fn try_open(
+                _project: &Model<Project>,
+                path: &ProjectPath,
+                cx: &mut AppContext,
+            ) -> Option<Task<gpui::Result<Model<Self>>>> {
+                if path.path.extension().unwrap() == "ipynb" {
+                    Some(cx.spawn(|mut cx| async move { cx.new_model(|_| TestIpynbItem {}) }))
+                } else {
+                    None
+                }
+            }
+
+            fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
+                None
+            }
+
+            fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+                None
+            }
+
+            fn is_dirty(&self) -> bool {
+                false
+            }
+        }
+
+        impl Item for TestIpynbItemView {
+            type Event = ();
+        }
+        impl EventEmitter<()> for TestIpynbItemView {}
+        impl FocusableView for TestIpynbItemView {
+            fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+                self.focus_handle.clone()
+            }
+        }
+
+        impl Render for TestIpynbItemView {
+            fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+                Empty
+            }
+        }
+
+        impl ProjectItem for TestIpynbItemView {
+            type Item = TestIpynbItem;
+
+            fn for_project_item(
+                _project: Model<Project>,
+                _item: Model<Self::Item>,
+                cx: &mut ViewContext<Self>,
+            ) -> Self
+            where
+                Self: Sized,
+            {
+                Self {
+                    focus_handle: cx.focus_handle(),
+                }
+            }
+        }
+
+        // View
+        struct TestTextItemView {
+            focus_handle: FocusHandle,
+            item: Model<TestTextItem>,
+            is_dirty: bool,
+        }
+        // Model
+        struct TestTextItem {
+            project_path: ProjectPath,
+        }
+
+        impl project::ProjectItem for TestTextItem {
+            fn try_open(
+                _project: &Model<Project>,
+                path: &ProjectPath,
+                cx: &mut AppContext,
+            ) -> Option<Task<gpui::Result<Model<Self>>>> {
+                if path.path.extension().unwrap() == "txt" {
+                    let project_path = path.clone();
+                    Some(cx.spawn(|mut cx| async move {
+                        cx.new_model(|_| TestTextItem { project_path })
+                    }))
+                } else {
+                    None
+                }
+            }
+
+            fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
+                None
+            }
+
+            fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+                Some(self.project_path.clone())
+            }
+
+            fn is_dirty(&self) -> bool {
+                false
+            }
+        }
+
+        impl Item for TestTextItemView {
+            type Event = ();
+
+            fn is_singleton(&self, _: &AppContext) -> bool {
+                true
+            }
+
+            fn is_dirty(&self, _: &AppContext) -> bool {
+                self.is_dirty
+            }
+
+            fn can_save(&self, _: &AppContext) -> bool {
+                true
+            }
+
+            fn save(
+                &mut self,
+                _: bool,
+                _: Model<Project>,
+                _: &mut ViewContext<Self>,
+            ) -> Task<anyhow::Result<()>> {
+                self.is_dirty = false;
+                Task::ready(Ok(()))
+            }
+
+            fn for_each_project_item(
+                &self,
+                cx: &AppContext,
+                f: &mut dyn FnMut(EntityId, &dyn project::ProjectItem),
+            ) {
+                f(self.item.entity_id(), self.item.read(cx))
+            }
+        }
+        impl EventEmitter<()> for TestTextItemView {}
+        impl FocusableView for TestTextItemView {
+            fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+                self.focus_handle.clone()
+            }
+        }
+
+        impl Render for TestTextItemView {
+            fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+                Empty
+            }
+        }
+
+        impl ProjectItem for TestTextItemView {
+            type Item = TestTextItem;
+
+            fn for_project_item(
+                _project: Model<Project>,
+                item: Model<Self::Item>,
+                cx: &mut ViewContext<Self>,
+            ) -> Self
+            where
+                Self: Sized,
+            {
+                Self {
+                    focus_handle: cx.focus_handle(),
+                    item,
+                    is_dirty: false,
+                }
+            }
+        }
+
+        struct TestAlternatePngItemView {
+            focus_handle: FocusHandle,
         }
 
-        impl Item for TestPngItemView {
+        impl Item for TestAlternatePngItemView {
             type Event = ();
         }
-        impl EventEmitter<()> for TestPngItemView {}
-        impl FocusableView for TestPngItemView {
+
+        impl EventEmitter<()> for TestAlternatePngItemView {}
+        impl FocusableView for TestAlternatePngItemView {
             fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
                 self.focus_handle.clone()
             }
         }
 
-        impl Render for TestPngItemView {
+        impl Render for TestAlternatePngItemView {
             fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
                 Empty
             }
         }
 
-        impl ProjectItem for TestPngItemView {
+        impl ProjectItem for TestAlternatePngItemView {
             type Item = TestPngItem;
 
             fn for_project_item(
@@ -7926,180 +11465,759 @@ mod tests {
             }
         }
 
-        // View
-        struct TestIpynbItemView {
-            focus_handle: FocusHandle,
+        #[gpui::test]
+        async fn test_register_project_item(cx: &mut TestAppContext) {
+            init_test(cx);
+
+            cx.update(|cx| {
+                register_project_item::<TestPngItemView>(cx);
+                register_project_item::<TestIpynbItemView>(cx);
+            });
+
+            let fs = FakeFs::new(cx.executor());
+            fs.insert_tree(
+                "/root1",
+                json!({
+                    "one.png": "BINARYDATAHERE",
+                    "two.ipynb": "{ totally a notebook }",
+                    "three.txt": "editing text, sure why not?"
+                }),
+            )
+            .await;
+
+            let project = Project::test(fs, ["root1".as_ref()], cx).await;
+            let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+            let worktree_id = project.update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            });
+
+            let handle = workspace
+                .update(cx, |workspace, cx| {
+                    let project_path = (worktree_id, "one.png");
+                    workspace.open_path(project_path, None, true, cx)
+                })
+                .await
+                .unwrap();
+
+            // Now we can check if the handle we got back errored or not
+            assert_eq!(
+                handle.to_any().entity_type(),
+                TypeId::of::<TestPngItemView>()
+            );
+
+            let handle = workspace
+                .update(cx, |workspace, cx| {
+                    let project_path = (worktree_id, "two.ipynb");
+                    workspace.open_path(project_path, None, true, cx)
+                })
+                .await
+                .unwrap();
+
+            assert_eq!(
+                handle.to_any().entity_type(),
+                TypeId::of::<TestIpynbItemView>()
+            );
+
+            let handle = workspace
+                .update(cx, |workspace, cx| {
+                    let project_path = (worktree_id, "three.txt");
+                    workspace.open_path(project_path, None, true, cx)
+                })
+                .await;
+            assert!(handle.is_err());
         }
-        // Model
-        struct TestIpynbItem {}
 
-        impl project::ProjectItem for TestIpynbItem {
-            fn try_open(
-                _project: &Model<Project>,
-                path: &ProjectPath,
-                cx: &mut AppContext,
-            ) -> Option<Task<gpui::Result<Model<Self>>>> {
-                if path.path.extension().unwrap() == "ipynb" {
-                    Some(cx.spawn(|mut cx| async move { cx.new_model(|_| TestIpynbItem {}) }))
-                } else {
-                    None
-                }
-            }
+        #[gpui::test]
+        async fn test_register_project_item_two_enter_one_leaves(cx: &mut TestAppContext) {
+            init_test(cx);
 
-            fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
-                None
-            }
+            cx.update(|cx| {
+                register_project_item::<TestPngItemView>(cx);
+                register_project_item::<TestAlternatePngItemView>(cx);
+            });
 
-            fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
-                None
-            }
+            let fs = FakeFs::new(cx.executor());
+            fs.insert_tree(
+                "/root1",
+                json!({
+                    "one.png": "BINARYDATAHERE",
+                    "two.ipynb": "{ totally a notebook }",
+                    "three.txt": "editing text, sure why not?"
+                }),
+            )
+            .await;
 
-            fn is_dirty(&self) -> bool {
-                false
-            }
+            let project = Project::test(fs, ["root1".as_ref()], cx).await;
+            let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+            let worktree_id = project.update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            });
+
+            let handle = workspace
+                .update(cx, |workspace, cx| {
+                    let project_path = (worktree_id, "one.png");
+                    workspace.open_path(project_path, None, true, cx)
+                })
+                .await
+                .unwrap();
+
+            // This _must_ be the second item registered
+            assert_eq!(
+                handle.to_any().entity_type(),
+                TypeId::of::<TestAlternatePngItemView>()
+            );
+
+            let handle = workspace
+                .update(cx, |workspace, cx| {
+                    let project_path = (worktree_id, "three.txt");
+                    workspace.open_path(project_path, None, true, cx)
+                })
+                .await;
+            assert!(handle.is_err());
         }
 
-        impl Item for TestIpynbItemView {
-            type Event = ();
+        #[gpui::test]
+        async fn test_open_paths_spread_across_existing_panes(cx: &mut TestAppContext) {
+            init_test(cx);
+
+            cx.update(|cx| {
+                register_project_item::<TestPngItemView>(cx);
+            });
+
+            let fs = FakeFs::new(cx.executor());
+            fs.insert_tree(
+                "/root1",
+                json!({
+                    "one.png": "BINARYDATAHERE",
+                    "two.png": "BINARYDATAHERE",
+                    "three.png": "BINARYDATAHERE",
+                    "dir": {}
+                }),
+            )
+            .await;
+
+            let project = Project::test(fs, ["root1".as_ref()], cx).await;
+            let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+            let (left_pane, right_pane) = workspace.update(cx, |workspace, cx| {
+                let left_pane = workspace.active_pane().clone();
+                let right_pane = workspace.split_pane(left_pane.clone(), SplitDirection::Right, cx);
+                (left_pane, right_pane)
+            });
+
+            let opened_items = workspace
+                .update(cx, |workspace, cx| {
+                    workspace.open_paths_with_pane_placement(
+                        vec![
+                            "/root1/one.png".into(),
+                            "/root1/two.png".into(),
+                            "/root1/three.png".into(),
+                            "/root1/dir".into(),
+                        ],
+                        OpenVisible::All,
+                        Some(left_pane.downgrade()),
+                        PanePlacement::SpreadAcrossExistingPanes,
+                        cx,
+                    )
+                })
+                .await;
+            assert_eq!(
+                opened_items
+                    .iter()
+                    .filter(|item| matches!(item, Some(Ok(_))))
+                    .count(),
+                3
+            );
+
+            // The directory doesn't take a slot: "one" and "two" go to the left pane, "three" to the right.
+            workspace.update(cx, |workspace, cx| {
+                assert_eq!(workspace.panes().len(), 2);
+                assert_eq!(left_pane.read(cx).items_len(), 2);
+                assert_eq!(right_pane.read(cx).items_len(), 1);
+            });
         }
-        impl EventEmitter<()> for TestIpynbItemView {}
-        impl FocusableView for TestIpynbItemView {
-            fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
-                self.focus_handle.clone()
-            }
+
+        #[gpui::test]
+        async fn test_open_abs_path_without_single_result(cx: &mut TestAppContext) {
+            init_test(cx);
+            cx.update(|cx| register_project_item::<TestPngItemView>(cx));
+
+            let fs = FakeFs::new(cx.executor());
+            fs.insert_tree("/root", json!({ "one.png": "", "dir": {} }))
+                .await;
+            let project = Project::test(fs, ["/root".as_ref()], cx).await;
+            let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+            // A directory of the project opens no item, which isn't an error for the user.
+            let error = workspace
+                .update(cx, |workspace, cx| {
+                    workspace.open_abs_path("/root/dir".into(), true, cx)
+                })
+                .await
+                .unwrap_err();
+            assert_eq!(
+                error.downcast_ref::<OpenError>(),
+                Some(&OpenError::OpenedAsWorktree {
+                    abs_path: "/root/dir".into()
+                })
+            );
+
+            // So the helpers reporting the errors of the callers to the user skip it.
+            workspace.update(cx, |workspace, cx| {
+                workspace
+                    .open_abs_path("/root/dir".into(), true, cx)
+                    .detach_and_notify_err(cx);
+                workspace
+                    .open_abs_path("/root/dir".into(), true, cx)
+                    .detach_and_prompt_err("Failed to open", cx, |_, _| None);
+            });
+            cx.run_until_parked();
+            assert!(!cx.has_pending_prompt());
+            workspace.update(cx, |workspace, cx| {
+                assert_eq!(workspace.active_pane().read(cx).items_len(), 0);
+                assert!(workspace.notification_ids().is_empty());
+            });
+
+            // With several results, the first opened item is used.
+            let item = workspace
+                .update(cx, |workspace, cx| {
+                    workspace.open_abs_path("/root/one.png".into(), true, cx)
+                })
+                .await
+                .unwrap();
+            let other_item: Box<dyn ItemHandle> = Box::new(cx.new_view(TestItem::new));
+            let picked = item_from_open_results(
+                Path::new("/root/one.png"),
+                vec![
+                    None,
+                    Some(Err(anyhow!("not found"))),
+                    Some(Ok(item.boxed_clone())),
+                    Some(Ok(other_item)),
+                ],
+            )
+            .unwrap();
+            assert_eq!(picked.map(|item| item.item_id()), Some(item.item_id()));
+            assert!(item_from_open_results(Path::new("/root/dir"), vec![None])
+                .unwrap()
+                .is_none());
+            assert!(item_from_open_results(
+                Path::new("/root/one.png"),
+                vec![Some(Err(anyhow!("not found")))]
+            )
+            .is_err());
+        }
+
+        #[gpui::test]
+        async fn test_open_path_when_ready(cx: &mut TestAppContext) {
+            init_test(cx);
+            cx.update(|cx| register_project_item::<TestPngItemView>(cx));
+
+            let fs = FakeFs::new(cx.executor());
+            fs.insert_tree("/root", json!({ "one.png": "" })).await;
+            let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+            let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+            let worktree_id = project.update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            });
+
+            let resolved = Rc::new(RefCell::new(Vec::new()));
+            cx.update(|cx| {
+                let resolved = resolved.clone();
+                cx.subscribe(&workspace, move |_, event, _| {
+                    if let Event::ItemEntryResolved { item, entry_id } = event {
+                        resolved.borrow_mut().push((item.item_id(), *entry_id));
+                    }
+                })
+                .detach();
+            });
+
+            // The worktree hasn't seen the file yet when it gets opened.
+            fs.pause_events();
+            fs.insert_file("/root/two.png", Vec::new()).await;
+            let item = workspace
+                .update(cx, |workspace, cx| {
+                    workspace.open_path_when_ready("/root/two.png".into(), cx)
+                })
+                .await
+                .unwrap();
+            cx.run_until_parked();
+            assert!(resolved.borrow().is_empty());
+
+            fs.flush_events(fs.buffered_event_count());
+            cx.run_until_parked();
+            let entry_id = project.update(cx, |project, cx| {
+                project
+                    .entry_for_path(&(worktree_id, "two.png").into(), cx)
+                    .unwrap()
+                    .id
+            });
+            assert_eq!(*resolved.borrow(), [(item.item_id(), entry_id)]);
+
+            // Files the worktree doesn't pick up in time are given up on.
+            resolved.borrow_mut().clear();
+            fs.insert_file("/root/three.png", Vec::new()).await;
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.open_path_when_ready("/root/three.png".into(), cx)
+                })
+                .await
+                .unwrap();
+            cx.executor().advance_clock(ENTRY_RESOLUTION_TIMEOUT);
+            cx.run_until_parked();
+            fs.flush_events(fs.buffered_event_count());
+            cx.run_until_parked();
+            assert!(resolved.borrow().is_empty());
         }
 
-        impl Render for TestIpynbItemView {
-            fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
-                Empty
-            }
-        }
+        #[gpui::test]
+        async fn test_open_path_avoids_reserved_pane(cx: &mut TestAppContext) {
+            init_test(cx);
+            cx.update(|cx| register_project_item::<TestTextItemView>(cx));
 
-        impl ProjectItem for TestIpynbItemView {
-            type Item = TestIpynbItem;
+            let fs = FakeFs::new(cx.executor());
+            fs.insert_tree("/root", json!({ "one.txt": "one" })).await;
+            let project = Project::test(fs, ["/root".as_ref()], cx).await;
+            let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+            let worktree_id = project.update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            });
 
-            fn for_project_item(
-                _project: Model<Project>,
-                _item: Model<Self::Item>,
-                cx: &mut ViewContext<Self>,
-            ) -> Self
-            where
-                Self: Sized,
-            {
-                Self {
-                    focus_handle: cx.focus_handle(),
-                }
-            }
-        }
+            let (editor_pane, terminal_pane) = workspace.update(cx, |workspace, cx| {
+                let editor_pane = workspace.active_pane().clone();
+                let terminal_pane =
+                    workspace.split_pane(editor_pane.clone(), SplitDirection::Right, cx);
+                workspace.set_pane_reserved_for(
+                    &terminal_pane,
+                    Some(ReservationKind::Terminal),
+                    cx,
+                );
+                terminal_pane.update(cx, |pane, cx| pane.focus(cx));
+                (editor_pane, terminal_pane)
+            });
+            cx.run_until_parked();
 
-        struct TestAlternatePngItemView {
-            focus_handle: FocusHandle,
+            let item = workspace
+                .update(cx, |workspace, cx| {
+                    workspace.open_path((worktree_id, "one.txt"), None, true, cx)
+                })
+                .await
+                .unwrap();
+            terminal_pane.update(cx, |pane, _| assert_eq!(pane.items_len(), 0));
+            editor_pane.update(cx, |pane, _| {
+                assert!(pane.index_for_item(item.as_ref()).is_some())
+            });
         }
 
-        impl Item for TestAlternatePngItemView {
-            type Event = ();
-        }
+        #[gpui::test]
+        async fn test_reopen_and_restore_bookmarked_items(cx: &mut TestAppContext) {
+            init_test(cx);
+            cx.update(|cx| register_project_item::<TestTextItemView>(cx));
 
-        impl EventEmitter<()> for TestAlternatePngItemView {}
-        impl FocusableView for TestAlternatePngItemView {
-            fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
-                self.focus_handle.clone()
-            }
-        }
+            let fs = FakeFs::new(cx.executor());
+            fs.insert_tree("/root", json!({ "one.txt": "one", "two.txt": "two" }))
+                .await;
+            let project = Project::test(fs, ["/root".as_ref()], cx).await;
+            let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+            let worktree_id = project.update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            });
+            let workspace_id = DB.next_id().await.unwrap();
+            workspace.update(cx, |workspace, _| {
+                workspace.database_id = Some(workspace_id)
+            });
 
-        impl Render for TestAlternatePngItemView {
-            fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
-                Empty
+            let mut items = Vec::new();
+            for path in ["one.txt", "two.txt"] {
+                let item = workspace
+                    .update(cx, |workspace, cx| {
+                        workspace.open_path((worktree_id, path), None, true, cx)
+                    })
+                    .await
+                    .unwrap();
+                items.push(item);
             }
-        }
+            cx.dispatch_action(ToggleItemBookmark);
+            cx.run_until_parked();
+            workspace.update(cx, |workspace, cx| {
+                workspace.activate_item(items[0].as_ref(), true, true, cx);
+            });
+            cx.dispatch_action(ToggleItemBookmark);
 
-        impl ProjectItem for TestAlternatePngItemView {
-            type Item = TestPngItem;
+            // Cycling to a closed item reopens it.
+            let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+            pane.update(cx, |pane, cx| {
+                pane.close_item_by_id(items[1].item_id(), SaveIntent::Skip, cx)
+            })
+            .await
+            .unwrap();
+            cx.dispatch_action(CycleBookmarkedItems);
+            cx.run_until_parked();
+            workspace.update(cx, |workspace, cx| {
+                let active_item = workspace.active_item(cx).unwrap();
+                assert_ne!(active_item.item_id(), items[1].item_id());
+                assert_eq!(
+                    active_item.project_path(cx),
+                    Some((worktree_id, "two.txt").into())
+                );
+                assert_eq!(
+                    workspace.bookmarks()[0].item_id(),
+                    Some(active_item.item_id())
+                );
+            });
 
-            fn for_project_item(
-                _project: Model<Project>,
-                _item: Model<Self::Item>,
-                cx: &mut ViewContext<Self>,
-            ) -> Self
-            where
-                Self: Sized,
-            {
-                Self {
-                    focus_handle: cx.focus_handle(),
-                }
-            }
+            // Bookmarks are stored in the order they were made, and restored with the workspace.
+            let abs_paths = vec![
+                PathBuf::from("/root/two.txt"),
+                PathBuf::from("/root/one.txt"),
+            ];
+            assert_eq!(DB.item_bookmarks(workspace_id).await.unwrap(), abs_paths);
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.serialize_workspace_internal(cx)
+                })
+                .await;
+            let serialized_workspace = DB.workspace_for_roots(&["/root"]).unwrap();
+            let restored_workspace = cx.new_view(|cx| Workspace::test_new(project.clone(), cx));
+            restored_workspace
+                .update(cx, |_, cx| {
+                    Workspace::load_workspace(serialized_workspace, Vec::new(), cx)
+                })
+                .await
+                .unwrap();
+            restored_workspace.update(cx, |workspace, _| {
+                let bookmarked_paths = workspace
+                    .bookmarks()
+                    .iter()
+                    .map(|bookmark| bookmark.abs_path().unwrap().to_path_buf())
+                    .collect::<Vec<_>>();
+                assert_eq!(bookmarked_paths, abs_paths);
+            });
         }
 
-        #[gpui::test]
-        async fn test_register_project_item(cx: &mut TestAppContext) {
+        async fn open_nested_root_item(
+            cx: &mut TestAppContext,
+        ) -> (
+            Model<Project>,
+            View<Workspace>,
+            Box<dyn ItemHandle>,
+            &mut VisualTestContext,
+        ) {
             init_test(cx);
 
             cx.update(|cx| {
-                register_project_item::<TestPngItemView>(cx);
-                register_project_item::<TestIpynbItemView>(cx);
+                register_project_item::<TestTextItemView>(cx);
             });
 
             let fs = FakeFs::new(cx.executor());
             fs.insert_tree(
-                "/root1",
+                "/root",
                 json!({
-                    "one.png": "BINARYDATAHERE",
-                    "two.ipynb": "{ totally a notebook }",
-                    "three.txt": "editing text, sure why not?"
+                    "a": { "one.txt": "one" },
+                    "two.txt": "two",
                 }),
             )
             .await;
 
-            let project = Project::test(fs, ["root1".as_ref()], cx).await;
+            let project = Project::test(fs, ["/root/a".as_ref()], cx).await;
             let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
-
             let worktree_id = project.update(cx, |project, cx| {
                 project.worktrees(cx).next().unwrap().read(cx).id()
             });
-
-            let handle = workspace
+            let item = workspace
                 .update(cx, |workspace, cx| {
-                    let project_path = (worktree_id, "one.png");
-                    workspace.open_path(project_path, None, true, cx)
+                    workspace.open_path((worktree_id, "one.txt"), None, true, cx)
                 })
                 .await
                 .unwrap();
+            (project, workspace, item, cx)
+        }
 
-            // Now we can check if the handle we got back errored or not
+        #[gpui::test]
+        async fn test_add_parent_folder_replacing_root(cx: &mut TestAppContext) {
+            let (project, workspace, old_item, cx) = open_nested_root_item(cx).await;
+
+            let task = workspace.update(cx, |workspace, cx| {
+                workspace.add_folders_to_project(vec!["/root".into()], cx)
+            });
+            cx.executor().run_until_parked();
+            cx.simulate_prompt_answer(0);
+            task.await.unwrap();
+
+            let worktree_id = project.update(cx, |project, cx| {
+                let worktrees = project.visible_worktrees(cx).collect::<Vec<_>>();
+                assert_eq!(worktrees.len(), 1);
+                assert_eq!(
+                    worktrees[0].read(cx).abs_path().as_ref(),
+                    Path::new("/root")
+                );
+                worktrees[0].read(cx).id()
+            });
+            workspace.update(cx, |workspace, cx| {
+                let pane = workspace.active_pane().read(cx);
+                assert_eq!(pane.items_len(), 1);
+                let item = pane.active_item().unwrap();
+                assert_ne!(item.item_id(), old_item.item_id());
+                assert!(!item.is_dirty(cx));
+                assert_eq!(
+                    item.project_path(cx),
+                    Some(ProjectPath {
+                        worktree_id,
+                        path: Path::new("a/one.txt").into(),
+                    })
+                );
+            });
+        }
+
+        #[gpui::test]
+        async fn test_replace_root_with_dirty_item(cx: &mut TestAppContext) {
+            let (project, workspace, old_item, cx) = open_nested_root_item(cx).await;
+            let old_view = old_item.downcast::<TestTextItemView>().unwrap();
+            old_view.update(cx, |view, _| view.is_dirty = true);
+
+            // Cancelling the save keeps the root and the unsaved changes.
+            let task = workspace.update(cx, |workspace, cx| {
+                workspace.add_folders_to_project(vec!["/root".into()], cx)
+            });
+            cx.executor().run_until_parked();
+            cx.simulate_prompt_answer(0);
+            cx.executor().run_until_parked();
+            cx.simulate_prompt_answer(2);
+            task.await.unwrap();
+            project.update(cx, |project, cx| {
+                let roots = project
+                    .visible_worktrees(cx)
+                    .map(|worktree| worktree.read(cx).abs_path())
+                    .collect::<Vec<_>>();
+                assert_eq!(roots, vec![Arc::from(Path::new("/root/a"))]);
+            });
+            workspace.update(cx, |workspace, cx| {
+                let item = workspace.active_pane().read(cx).active_item().unwrap();
+                assert_eq!(item.item_id(), old_item.item_id());
+                assert!(item.is_dirty(cx));
+            });
+
+            // Saving the item lets the root be replaced.
+            let task = workspace.update(cx, |workspace, cx| {
+                workspace.add_folders_to_project(vec!["/root".into()], cx)
+            });
+            cx.executor().run_until_parked();
+            cx.simulate_prompt_answer(0);
+            cx.executor().run_until_parked();
+            cx.simulate_prompt_answer(0);
+            task.await.unwrap();
+            assert!(!old_view.read_with(cx, |view, _| view.is_dirty));
+            project.update(cx, |project, cx| {
+                let roots = project
+                    .visible_worktrees(cx)
+                    .map(|worktree| worktree.read(cx).abs_path())
+                    .collect::<Vec<_>>();
+                assert_eq!(roots, vec![Arc::from(Path::new("/root"))]);
+            });
+            workspace.update(cx, |workspace, cx| {
+                let pane = workspace.active_pane().read(cx);
+                assert_eq!(pane.items_len(), 1);
+                assert_ne!(pane.active_item().unwrap().item_id(), old_item.item_id());
+            });
+        }
+
+        #[gpui::test]
+        async fn test_replace_root_with_item_out_of_new_root(cx: &mut TestAppContext) {
+            init_test(cx);
+            cx.update(|cx| {
+                register_project_item::<TestTextItemView>(cx);
+            });
+            let fs = FakeFs::new(cx.executor());
+            fs.insert_tree(
+                "/root",
+                json!({
+                    "a": { "one.txt": "one" },
+                    "two.txt": "two",
+                }),
+            )
+            .await;
+            let project = Project::test(fs, ["/root".as_ref()], cx).await;
+            let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+            let worktree_id = project.update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            });
+            for path in ["a/one.txt", "two.txt"] {
+                workspace
+                    .update(cx, |workspace, cx| {
+                        workspace.open_path((worktree_id, path), None, true, cx)
+                    })
+                    .await
+                    .unwrap();
+            }
+
+            let task = workspace.update(cx, |workspace, cx| {
+                workspace.add_folders_to_project(vec!["/root/a".into()], cx)
+            });
+            cx.executor().run_until_parked();
+            cx.simulate_prompt_answer(0);
+            task.await.unwrap();
+
+            project.update(cx, |project, cx| {
+                let roots = project
+                    .visible_worktrees(cx)
+                    .map(|worktree| worktree.read(cx).abs_path())
+                    .collect::<Vec<_>>();
+                assert_eq!(roots, vec![Arc::from(Path::new("/root/a"))]);
+            });
+            let abs_paths = workspace.update(cx, |workspace, cx| {
+                let project = workspace.project().read(cx);
+                workspace
+                    .active_pane()
+                    .read(cx)
+                    .items()
+                    .map(|item| {
+                        let project_path = item.project_path(cx).unwrap();
+                        assert_ne!(project_path.worktree_id, worktree_id);
+                        project.absolute_path(&project_path, cx).unwrap()
+                    })
+                    .collect::<Vec<_>>()
+            });
             assert_eq!(
-                handle.to_any().entity_type(),
-                TypeId::of::<TestPngItemView>()
+                abs_paths,
+                vec![
+                    PathBuf::from("/root/a/one.txt"),
+                    PathBuf::from("/root/two.txt")
+                ]
             );
+        }
 
-            let handle = workspace
-                .update(cx, |workspace, cx| {
-                    let project_path = (worktree_id, "two.ipynb");
-                    workspace.open_path(project_path, None, true, cx)
-                })
-                .await
-                .unwrap();
+        #[gpui::test]
+        async fn test_add_parent_folder_anyway(cx: &mut TestAppContext) {
+            let (project, workspace, old_item, cx) = open_nested_root_item(cx).await;
+
+            let task = workspace.update(cx, |workspace, cx| {
+                workspace.add_folders_to_project(vec!["/root".into()], cx)
+            });
+            cx.executor().run_until_parked();
+            cx.simulate_prompt_answer(1);
+            task.await.unwrap();
+
+            project.update(cx, |project, cx| {
+                let mut roots = project
+                    .visible_worktrees(cx)
+                    .map(|worktree| worktree.read(cx).abs_path())
+                    .collect::<Vec<_>>();
+                roots.sort();
+                assert_eq!(
+                    roots,
+                    vec![
+                        Arc::from(Path::new("/root")),
+                        Arc::from(Path::new("/root/a"))
+                    ]
+                );
+            });
+            workspace.update(cx, |workspace, cx| {
+                let pane = workspace.active_pane().read(cx);
+                assert_eq!(pane.items_len(), 1);
+                assert_eq!(pane.active_item().unwrap().item_id(), old_item.item_id());
+            });
+        }
+
+        #[gpui::test]
+        async fn test_add_folder_prompt_starts_in_first_worktree(cx: &mut TestAppContext) {
+            let (_, workspace, _, cx) = open_nested_root_item(cx).await;
+            cx.update(|cx| {
+                SettingsStore::update_global(cx, |store, cx| {
+                    store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                        settings.use_system_path_prompts = Some(false);
+                    });
+                });
+            });
+
+            let prompted_options = Rc::new(RefCell::new(Vec::new()));
+            workspace.update(cx, |workspace, _| {
+                let prompted_options = prompted_options.clone();
+                workspace.set_prompt_for_open_path(Box::new(move |_, _, options, _| {
+                    prompted_options.borrow_mut().push(options);
+                    let (tx, rx) = oneshot::channel();
+                    tx.send(None).ok();
+                    rx
+                }));
+            });
+            cx.dispatch_action(AddFolderToProject);
+            cx.run_until_parked();
 
+            let prompted_options = prompted_options.borrow();
+            assert_eq!(prompted_options.len(), 1);
+            assert!(prompted_options[0].directories);
             assert_eq!(
-                handle.to_any().entity_type(),
-                TypeId::of::<TestIpynbItemView>()
+                prompted_options[0].starting_directory.as_deref(),
+                Some(Path::new("/root/a"))
             );
+            assert!(prompted_options[0].extension_filters.is_empty());
+        }
 
-            let handle = workspace
-                .update(cx, |workspace, cx| {
-                    let project_path = (worktree_id, "three.txt");
-                    workspace.open_path(project_path, None, true, cx)
-                })
-                .await;
-            assert!(handle.is_err());
+        #[gpui::test]
+        async fn test_use_system_path_prompts_override(cx: &mut TestAppContext) {
+            let (_, workspace, _, cx) = open_nested_root_item(cx).await;
+
+            // Without in-app prompts, the system prompt is used even when they're preferred.
+            let prompt = workspace.update(cx, |workspace, cx| {
+                assert!(!workspace.has_path_prompt_handlers());
+                workspace.set_use_system_path_prompts(Some(false));
+                workspace.prompt_for_new_path(cx)
+            });
+            cx.run_until_parked();
+            assert!(cx.did_prompt_for_new_path());
+            cx.simulate_new_path_selection(|_| None);
+            assert_eq!(prompt.await.unwrap(), None);
+
+            let in_app_prompts = Rc::new(Cell::new(0));
+            workspace.update(cx, |workspace, _| {
+                let in_app_prompts = in_app_prompts.clone();
+                workspace.set_prompt_for_new_path(Box::new(move |_, _| {
+                    in_app_prompts.set(in_app_prompts.get() + 1);
+                    let (tx, rx) = oneshot::channel();
+                    tx.send(None).ok();
+                    rx
+                }));
+            });
+
+            // The override wins over the setting, in both directions.
+            workspace.update(cx, |workspace, cx| {
+                workspace.prompt_for_new_path(cx);
+            });
+            cx.run_until_parked();
+            assert_eq!(in_app_prompts.get(), 1);
+            assert!(!cx.did_prompt_for_new_path());
+
+            cx.update(|cx| {
+                SettingsStore::update_global(cx, |store, cx| {
+                    store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                        settings.use_system_path_prompts = Some(false);
+                    });
+                });
+            });
+            workspace.update(cx, |workspace, cx| {
+                workspace.set_use_system_path_prompts(Some(true));
+                workspace.prompt_for_new_path(cx);
+            });
+            cx.run_until_parked();
+            assert_eq!(in_app_prompts.get(), 1);
+            assert!(cx.did_prompt_for_new_path());
+            cx.simulate_new_path_selection(|_| None);
+
+            // Without an override, the setting applies again.
+            workspace.update(cx, |workspace, cx| {
+                workspace.set_use_system_path_prompts(None);
+                workspace.prompt_for_new_path(cx);
+            });
+            cx.run_until_parked();
+            assert_eq!(in_app_prompts.get(), 2);
         }
 
         #[gpui::test]
-        async fn test_register_project_item_two_enter_one_leaves(cx: &mut TestAppContext) {
+        async fn test_open_paths_split_per_file(cx: &mut TestAppContext) {
             init_test(cx);
 
             cx.update(|cx| {
                 register_project_item::<TestPngItemView>(cx);
-                register_project_item::<TestAlternatePngItemView>(cx);
             });
 
             let fs = FakeFs::new(cx.executor());
@@ -8107,8 +12225,9 @@ mod tests {
                 "/root1",
                 json!({
                     "one.png": "BINARYDATAHERE",
-                    "two.ipynb": "{ totally a notebook }",
-                    "three.txt": "editing text, sure why not?"
+                    "two.png": "BINARYDATAHERE",
+                    "three.png": "BINARYDATAHERE",
+                    "dir": {}
                 }),
             )
             .await;
@@ -8116,31 +12235,36 @@ mod tests {
             let project = Project::test(fs, ["root1".as_ref()], cx).await;
             let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
 
-            let worktree_id = project.update(cx, |project, cx| {
-                project.worktrees(cx).next().unwrap().read(cx).id()
-            });
-
-            let handle = workspace
+            let opened_items = workspace
                 .update(cx, |workspace, cx| {
-                    let project_path = (worktree_id, "one.png");
-                    workspace.open_path(project_path, None, true, cx)
+                    workspace.open_paths_with_pane_placement(
+                        vec![
+                            "/root1/dir".into(),
+                            "/root1/one.png".into(),
+                            "/root1/two.png".into(),
+                            "/root1/three.png".into(),
+                        ],
+                        OpenVisible::All,
+                        None,
+                        PanePlacement::SplitPerFile(SplitDirection::Right),
+                        cx,
+                    )
                 })
-                .await
-                .unwrap();
-
-            // This _must_ be the second item registered
+                .await;
             assert_eq!(
-                handle.to_any().entity_type(),
-                TypeId::of::<TestAlternatePngItemView>()
+                opened_items
+                    .iter()
+                    .filter(|item| matches!(item, Some(Ok(_))))
+                    .count(),
+                3
             );
 
-            let handle = workspace
-                .update(cx, |workspace, cx| {
-                    let project_path = (worktree_id, "three.txt");
-                    workspace.open_path(project_path, None, true, cx)
-                })
-                .await;
-            assert!(handle.is_err());
+            workspace.update(cx, |workspace, cx| {
+                assert_eq!(workspace.panes().len(), 3);
+                for pane in workspace.panes() {
+                    assert_eq!(pane.read(cx).items_len(), 1);
+                }
+            });
         }
     }
 
@@ -0,0 +1,186 @@
+use gpui::{AppContext, IntoElement, Render, Subscription, View, ViewContext};
+use ui::{prelude::*, LineHeightStyle};
+
+use crate::{item::ItemHandle, Event, StatusItemView, Workspace};
+
+/// A summary of what is open in a workspace, see [`Workspace::counts`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WorkspaceCounts {
+    /// The number of items open in the center panes.
+    pub items: usize,
+    /// The number of those items that have unsaved changes.
+    pub dirty: usize,
+    /// The number of center panes.
+    pub panes: usize,
+    /// The number of open docks.
+    pub docks_open: usize,
+}
+
+impl Workspace {
+    pub fn counts(&self) -> WorkspaceCounts {
+        self.counts
+    }
+
+    /// Recomputes the counts at the end of the current update, so that a burst of changes
+    /// results in a single [`Event::CountsChanged`].
+    pub(crate) fn schedule_counts_update(&mut self, cx: &mut ViewContext<Self>) {
+        if self.counts_update_scheduled {
+            return;
+        }
+        self.counts_update_scheduled = true;
+        cx.defer(|this, cx| {
+            this.counts_update_scheduled = false;
+            let counts = this.compute_counts(cx);
+            if counts != this.counts {
+                this.counts = counts;
+                cx.emit(Event::CountsChanged(counts));
+            }
+        });
+    }
+
+    fn compute_counts(&self, cx: &AppContext) -> WorkspaceCounts {
+        let mut counts = WorkspaceCounts {
+            panes: self.panes.len(),
+            ..Default::default()
+        };
+        for item in self.panes.iter().flat_map(|pane| pane.read(cx).items()) {
+            counts.items += 1;
+            if item.is_dirty(cx) {
+                counts.dirty += 1;
+            }
+        }
+        counts.docks_open = [&self.left_dock, &self.bottom_dock, &self.right_dock]
+            .into_iter()
+            .filter(|dock| dock.read(cx).is_open())
+            .count();
+        counts
+    }
+}
+
+/// Shows the number of open and unsaved files in the status bar.
+pub struct FileCountsIndicator {
+    counts: WorkspaceCounts,
+    _subscription: Subscription,
+}
+
+impl FileCountsIndicator {
+    pub fn new(workspace: &View<Workspace>, cx: &mut ViewContext<Self>) -> Self {
+        Self {
+            counts: WorkspaceCounts::default(),
+            _subscription: cx.subscribe(workspace, |this, _, event, cx| {
+                if let Event::CountsChanged(counts) = event {
+                    this.counts = *counts;
+                    cx.notify();
+                }
+            }),
+        }
+    }
+}
+
+impl Render for FileCountsIndicator {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        let files = match self.counts.items {
+            1 => "1 file".to_string(),
+            count => format!("{count} files"),
+        };
+        let label = if self.counts.dirty > 0 {
+            format!("{files} ({} unsaved)", self.counts.dirty)
+        } else {
+            files
+        };
+        Label::new(label)
+            .size(LabelSize::Small)
+            .line_height_style(LineHeightStyle::UiLabel)
+    }
+}
+
+impl StatusItemView for FileCountsIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        _active_pane_item: Option<&dyn ItemHandle>,
+        _cx: &mut ViewContext<Self>,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dock::{test::TestPanel, DockPosition},
+        item::test::{TestItem, TestProjectItem},
+        tests::init_test,
+        SaveIntent, SplitDirection,
+    };
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualContext};
+    use project::Project;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[gpui::test]
+    async fn test_workspace_counts(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let events = events.clone();
+            cx.subscribe(&workspace, move |_, event, _| {
+                if let Event::CountsChanged(counts) = event {
+                    events.borrow_mut().push(*counts);
+                }
+            })
+            .detach();
+        });
+
+        // Several changes within the same update are reported once.
+        let dirty_item = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_project_items(&[TestProjectItem::new(1, "1.txt", cx)])
+        });
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(dirty_item.clone()), None, true, cx);
+            let clean_item = cx.new_view(TestItem::new);
+            workspace.add_item_to_active_pane(Box::new(clean_item), None, true, cx);
+            let panel = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            workspace.add_panel(panel, cx);
+            workspace.toggle_dock(DockPosition::Left, cx);
+        });
+        cx.run_until_parked();
+        let expected = WorkspaceCounts {
+            items: 2,
+            dirty: 1,
+            panes: 1,
+            docks_open: 1,
+        };
+        assert_eq!(*events.borrow(), [expected]);
+
+        // Splitting adds a pane holding a copy of the active item.
+        workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            workspace.split_and_clone(pane, SplitDirection::Right, cx);
+        });
+        cx.run_until_parked();
+
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.save_all_internal(SaveIntent::SaveAll, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        let expected = WorkspaceCounts {
+            items: 3,
+            dirty: 0,
+            panes: 2,
+            docks_open: 1,
+        };
+        workspace.update(cx, |workspace, _| assert_eq!(workspace.counts(), expected));
+        assert_eq!(events.borrow().last(), Some(&expected));
+    }
+}
@@ -0,0 +1,248 @@
+use gpui::{Subscription, View, ViewContext};
+
+use crate::{item::ItemHandle, Event, Pane, Workspace};
+
+/// The events held back while a batch is open, see [`Workspace::begin_event_batch`].
+#[derive(Default)]
+pub(crate) struct EventBatch {
+    depth: usize,
+    events: Vec<Event>,
+}
+
+impl Event {
+    /// The events this event stands for: the batched ones for [`Event::Batched`], or the event
+    /// itself otherwise.
+    pub fn unbatched(&self) -> &[Event] {
+        match self {
+            Event::Batched(events) => events,
+            event => std::slice::from_ref(event),
+        }
+    }
+}
+
+impl Workspace {
+    /// Emits `event`, or holds it back until the current batch ends. Only the events that come
+    /// in bursts go through here: panes are still reported right away, as they need to be set
+    /// up before they're rendered.
+    pub(crate) fn emit_batchable_event(&mut self, event: Event, cx: &mut ViewContext<Self>) {
+        if self.event_batch.depth > 0 {
+            self.event_batch.events.push(event);
+        } else {
+            cx.emit(event);
+        }
+    }
+
+    /// Holds the batchable events back until the matching [`Workspace::end_event_batch`], so
+    /// that they're emitted together as an [`Event::Batched`].
+    pub(crate) fn begin_event_batch(&mut self) {
+        self.event_batch.depth += 1;
+    }
+
+    pub(crate) fn end_event_batch(&mut self, cx: &mut ViewContext<Self>) {
+        self.event_batch.depth = self.event_batch.depth.saturating_sub(1);
+        if self.event_batch.depth > 0 {
+            return;
+        }
+        let mut events = std::mem::take(&mut self.event_batch.events);
+        match events.len() {
+            0 => {}
+            1 => cx.emit(events.pop().unwrap()),
+            _ => cx.emit(Event::Batched(events)),
+        }
+    }
+}
+
+/// Subscribes views to the events of a workspace, without matching on [`Event`] and unpacking
+/// the batches themselves:
+///
+/// ```ignore
+/// let subscription = WorkspaceEventSubscriber::new(&workspace)
+///     .on_active_item_changed(cx, |panel, workspace, cx| panel.update_active_item(workspace, cx));
+/// ```
+pub struct WorkspaceEventSubscriber {
+    workspace: View<Workspace>,
+}
+
+impl WorkspaceEventSubscriber {
+    pub fn new(workspace: &View<Workspace>) -> Self {
+        Self {
+            workspace: workspace.clone(),
+        }
+    }
+
+    /// Calls `handler` for every event, with the batches unpacked.
+    pub fn on_event<V: 'static>(
+        &self,
+        cx: &mut ViewContext<V>,
+        handler: impl Fn(&mut V, &View<Workspace>, &Event, &mut ViewContext<V>) + 'static,
+    ) -> Subscription {
+        cx.subscribe(&self.workspace, move |this, workspace, event, cx| {
+            for event in event.unbatched() {
+                handler(this, &workspace, event, cx);
+            }
+        })
+    }
+
+    /// Calls `handler` when the active item changes, once for a batch.
+    pub fn on_active_item_changed<V: 'static>(
+        &self,
+        cx: &mut ViewContext<V>,
+        handler: impl Fn(&mut V, &View<Workspace>, &mut ViewContext<V>) + 'static,
+    ) -> Subscription {
+        cx.subscribe(&self.workspace, move |this, workspace, event, cx| {
+            let changed = event
+                .unbatched()
+                .iter()
+                .any(|event| matches!(event, Event::ActiveItemChanged));
+            if changed {
+                handler(this, &workspace, cx);
+            }
+        })
+    }
+
+    /// Calls `handler` for every pane added to the workspace.
+    pub fn on_pane_added<V: 'static>(
+        &self,
+        cx: &mut ViewContext<V>,
+        handler: impl Fn(&mut V, &View<Pane>, &mut ViewContext<V>) + 'static,
+    ) -> Subscription {
+        self.on_event(cx, move |this, _, event, cx| {
+            if let Event::PaneAdded(pane) = event {
+                handler(this, pane, cx);
+            }
+        })
+    }
+
+    /// Calls `handler` with the items added to the workspace, all at once for a batch.
+    pub fn on_item_added<V: 'static>(
+        &self,
+        cx: &mut ViewContext<V>,
+        handler: impl Fn(&mut V, &[Box<dyn ItemHandle>], &mut ViewContext<V>) + 'static,
+    ) -> Subscription {
+        cx.subscribe(&self.workspace, move |this, _, event, cx| {
+            let items = event
+                .unbatched()
+                .iter()
+                .filter_map(|event| match event {
+                    Event::ItemAdded { item } => Some(item.boxed_clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            if !items.is_empty() {
+                handler(this, &items, cx);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::TestItem,
+        persistence::model::{
+            SerializedItem, SerializedPane, SerializedPaneGroup, SerializedWorkspace,
+            SerializedWorkspaceLocation,
+        },
+        register_serializable_item,
+        tests::init_test,
+    };
+    use fs::FakeFs;
+    use gpui::{Render, TestAppContext, VisualContext};
+    use project::Project;
+
+    #[derive(Default)]
+    struct CallCounts {
+        active_item_changed: usize,
+        panes_added: usize,
+        item_added: usize,
+        items_added: usize,
+    }
+
+    struct TestPanel {
+        counts: CallCounts,
+        _subscriptions: Vec<Subscription>,
+    }
+
+    impl Render for TestPanel {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl gpui::IntoElement {
+            gpui::Empty
+        }
+    }
+
+    #[gpui::test]
+    async fn test_event_subscriber_during_restore(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(register_serializable_item::<TestItem>);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let panel = cx.new_view(|cx| {
+            let subscriber = WorkspaceEventSubscriber::new(&workspace);
+            TestPanel {
+                counts: CallCounts::default(),
+                _subscriptions: vec![
+                    subscriber.on_active_item_changed(cx, |panel: &mut TestPanel, _, _| {
+                        panel.counts.active_item_changed += 1;
+                    }),
+                    subscriber.on_pane_added(cx, |panel: &mut TestPanel, _, _| {
+                        panel.counts.panes_added += 1;
+                    }),
+                    subscriber.on_item_added(cx, |panel: &mut TestPanel, items, _| {
+                        panel.counts.item_added += 1;
+                        panel.counts.items_added += items.len();
+                    }),
+                ],
+            }
+        });
+
+        // Items added outside of a restore are reported one by one.
+        workspace.update(cx, |workspace, cx| {
+            for _ in 0..2 {
+                let item = Box::new(cx.new_view(TestItem::new));
+                workspace.add_item_to_active_pane(item, None, true, cx);
+            }
+        });
+        cx.run_until_parked();
+        panel.update(cx, |panel, _| {
+            assert_eq!(panel.counts.item_added, 2);
+            assert_eq!(panel.counts.items_added, 2);
+            assert_eq!(panel.counts.active_item_changed, 2);
+            panel.counts = CallCounts::default();
+        });
+
+        // The items of a restored workspace are reported at once.
+        let serialized_workspace = SerializedWorkspace {
+            id: workspace.update(cx, |workspace, _| workspace.database_id().unwrap()),
+            location: SerializedWorkspaceLocation::from_local_paths(["/root"]),
+            center_group: SerializedPaneGroup::Pane(SerializedPane::new(
+                (1..=5)
+                    .map(|id| SerializedItem::new("TestItem", id, id == 5, false))
+                    .collect(),
+                true,
+                0,
+            )),
+            window_bounds: None,
+            centered_layout: false,
+            session_notes: None,
+            display: None,
+            docks: Default::default(),
+            session_id: None,
+            window_id: None,
+        };
+        workspace
+            .update(cx, |_, cx| {
+                Workspace::load_workspace(serialized_workspace, Vec::new(), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        panel.update(cx, |panel, _| {
+            assert_eq!(panel.counts.item_added, 1);
+            assert_eq!(panel.counts.items_added, 5);
+            assert_eq!(panel.counts.active_item_changed, 1);
+            assert_eq!(panel.counts.panes_added, 1);
+        });
+    }
+}
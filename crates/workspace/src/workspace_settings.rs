@@ -1,6 +1,6 @@
 use anyhow::Result;
 use collections::HashMap;
-use gpui::AppContext;
+use gpui::{AppContext, Pixels};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
@@ -16,8 +16,27 @@ pub struct WorkspaceSettings {
     pub autosave: AutosaveSetting,
     pub restore_on_startup: RestoreOnStartupBehavior,
     pub drop_target_size: f32,
+    pub drop_target_placement: DropTargetPlacement,
     pub when_closing_with_no_tabs: CloseWindowWhenNoItems,
     pub use_system_path_prompts: bool,
+    pub crash_recovery_interval_seconds: u64,
+    pub layout_checkpoint_interval_minutes: u64,
+    pub max_layout_checkpoints: usize,
+    pub show_file_counts: bool,
+    pub rebalance_on_pane_close: bool,
+    pub carry_untitled: bool,
+    pub new_window_behavior: NewWindowBehavior,
+    pub folder_open_behavior: FolderOpenBehavior,
+    pub open_beside_pinned_item: OpenBesidePinnedItem,
+    pub zoom_mode: ZoomMode,
+    pub zoom_coverage: f32,
+    pub pane_activation_order: PaneActivationOrder,
+    pub alternate_item_scope: AlternateItemScope,
+    pub pane_resize_step: f32,
+    pub centered_in_fullscreen: bool,
+    pub keep_running_without_windows: bool,
+    pub focus_follows_mouse: bool,
+    pub auto_hide_docks_below_width: Option<Pixels>,
     pub command_aliases: HashMap<String, String>,
     pub show_user_picture: bool,
 }
@@ -68,6 +87,88 @@ impl CloseWindowWhenNoItems {
     }
 }
 
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DropTargetPlacement {
+    /// Open all dropped files in the pane they were dropped on.
+    #[default]
+    ActivePane,
+    /// Distribute dropped files across the existing panes.
+    SpreadAcrossExistingPanes,
+    /// Split a new pane for every dropped file after the first one.
+    SplitPerFile,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoomMode {
+    /// Show the zoomed pane or panel above the whole workspace, covering the docks.
+    #[default]
+    Overlay,
+    /// Show the zoomed pane or panel in place of the center panes, keeping the docks visible.
+    ReplaceCenter,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PaneActivationOrder {
+    /// Count the panes in reading order: by rows from the top, then from left to right.
+    #[default]
+    Visual,
+    /// Count the panes in the order of the splits that created them, as before.
+    Tree,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AlternateItemScope {
+    /// Alternate between the items of the active pane.
+    #[default]
+    Pane,
+    /// Alternate between the last two active items of the workspace, whichever panes they are in.
+    Workspace,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NewWindowBehavior {
+    /// Open an empty window with a new untitled buffer.
+    #[default]
+    Empty,
+    /// Open another window on the project of the current one.
+    SameProject,
+    /// Ask which of the two to open.
+    Ask,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FolderOpenBehavior {
+    /// Ask whether to add the folder to the project or to open it in a new window.
+    Prompt,
+    /// Add the folder to the project as another worktree.
+    #[default]
+    AddWorktree,
+    /// Open the folder in a new window.
+    NewWindow,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenBesidePinnedItem {
+    /// Open new items in the target pane, even when it shows a pinned item.
+    #[default]
+    Off,
+    /// Open them in the pane on the left of it, splitting one off if there is none.
+    Left,
+    /// Open them in the pane on the right of it, splitting one off if there is none.
+    Right,
+    /// Open them in the pane above it, splitting one off if there is none.
+    Up,
+    /// Open them in the pane below it, splitting one off if there is none.
+    Down,
+}
+
 #[derive(Copy, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RestoreOnStartupBehavior {
@@ -115,6 +216,10 @@ pub struct WorkspaceSettingsContent {
     ///
     /// Default: `0.2` (20% of the smaller dimension of the workspace)
     pub drop_target_size: Option<f32>,
+    /// Where to open files dropped into a pane from outside of Zed.
+    /// Values: active_pane, spread_across_existing_panes, split_per_file
+    /// Default: active_pane
+    pub drop_target_placement: Option<DropTargetPlacement>,
     /// Whether to close the window when using 'close active item' on a workspace with no tabs
     ///
     /// Default: auto ("on" on macOS, "off" otherwise)
@@ -124,6 +229,91 @@ pub struct WorkspaceSettingsContent {
     ///
     /// Default: true
     pub use_system_path_prompts: Option<bool>,
+    /// How often, in seconds, to snapshot unsaved changes so they can be
+    /// restored after a crash. Set to 0 to disable.
+    ///
+    /// Default: 30
+    pub crash_recovery_interval_seconds: Option<u64>,
+    /// How often, in minutes, to save a checkpoint of the layout of the center panes and
+    /// docks, which can be restored with `workspace::RestoreLayoutCheckpoint`. Set to 0 to
+    /// disable.
+    ///
+    /// Default: 0
+    pub layout_checkpoint_interval_minutes: Option<u64>,
+    /// How many layout checkpoints to keep per workspace.
+    ///
+    /// Default: 10
+    pub max_layout_checkpoints: Option<usize>,
+    /// Whether to show the number of open and unsaved files in the status bar.
+    ///
+    /// Default: false
+    pub show_file_counts: Option<bool>,
+    /// Whether to give the remaining panes of a split the same size when one of them is closed,
+    /// including when the split collapses into the one around it.
+    ///
+    /// Default: false
+    pub rebalance_on_pane_close: Option<bool>,
+    /// Whether to bring unsaved untitled buffers along when the window switches to another
+    /// project, instead of asking what to do with them before closing the current one.
+    ///
+    /// Default: false
+    pub carry_untitled: Option<bool>,
+    /// What a new window opens when created from an existing one.
+    /// Values: empty, same_project, ask
+    /// Default: empty
+    pub new_window_behavior: Option<NewWindowBehavior>,
+    /// What to do with a folder opened from the CLI or the OS in a window whose project has
+    /// other folders already. Files are always opened in that window.
+    /// Values: prompt, add_worktree, new_window
+    /// Default: add_worktree
+    pub folder_open_behavior: Option<FolderOpenBehavior>,
+    /// Where to open a new file when the pane it would open in shows a pinned item,
+    /// so that the pinned item stays visible.
+    /// Values: off, left, right, up, down
+    /// Default: off
+    pub open_beside_pinned_item: Option<OpenBesidePinnedItem>,
+    /// How to show a zoomed pane or panel.
+    /// Values: overlay, replace_center
+    /// Default: overlay
+    pub zoom_mode: Option<ZoomMode>,
+    /// The fraction of the workspace covered by a zoomed pane or panel, between 0.5 and 1.0.
+    /// Below 1.0, the rest of the workspace stays visible, dimmed, around the zoomed view.
+    ///
+    /// Default: 1.0
+    pub zoom_coverage: Option<f32>,
+    /// How the panes are counted by `workspace::ActivatePane`.
+    /// Values: visual, tree
+    /// Default: visual
+    pub pane_activation_order: Option<PaneActivationOrder>,
+    /// Which item `workspace::ActivateAlternateItem` switches to.
+    /// Values: pane, workspace
+    /// Default: pane
+    pub alternate_item_scope: Option<AlternateItemScope>,
+    /// The fraction of its split by which `workspace::GrowPane` and `workspace::ShrinkPane`
+    /// resize the active pane.
+    ///
+    /// Default: 0.05
+    pub pane_resize_step: Option<f32>,
+    /// Whether to turn on the centered layout while the window is fullscreen, and to turn it
+    /// back off when leaving fullscreen.
+    ///
+    /// Default: false
+    pub centered_in_fullscreen: Option<bool>,
+    /// Whether to keep the app running when its last window is closed, to open new windows
+    /// from it later, instead of quitting. Has no effect on macOS, where the app always keeps
+    /// running.
+    ///
+    /// Default: false
+    pub keep_running_without_windows: Option<bool>,
+    /// Whether to focus the pane or the dock under the mouse, without clicking it.
+    ///
+    /// Default: false
+    pub focus_follows_mouse: Option<bool>,
+    /// Close the left and right docks while the window is narrower than this many pixels,
+    /// and open them again when it grows back.
+    ///
+    /// Default: null
+    pub auto_hide_docks_below_width: Option<f32>,
     /// Aliases for the command palette. When you type a key in this map,
     /// it will be assumed to equal the value.
     ///
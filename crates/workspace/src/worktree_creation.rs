@@ -0,0 +1,163 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use collections::HashMap;
+use futures::{future::Shared, FutureExt as _};
+use gpui::{AppContext, EntityId, Global, Model, Task};
+use project::{Project, Worktree};
+
+type WorktreeCreation = Shared<Task<Result<(Model<Worktree>, PathBuf), Arc<anyhow::Error>>>>;
+
+/// The worktree creations in flight for each project, by the path they were requested for.
+/// Paths inside of one of those are only resolved once its worktree exists: concurrently
+/// resolving a directory and the paths inside of it would otherwise create a worktree for
+/// each of them.
+#[derive(Default)]
+struct PendingWorktreeCreations {
+    by_project: HashMap<EntityId, HashMap<PathBuf, WorktreeCreation>>,
+}
+
+impl Global for PendingWorktreeCreations {}
+
+/// Finds the worktree containing `abs_path` in `project`, creating one if there's none. If a
+/// worktree is being created for a directory containing `abs_path`, that creation is awaited
+/// first, while the creations for unrelated paths go on independently.
+pub(crate) fn find_or_create_worktree(
+    project: Model<Project>,
+    abs_path: PathBuf,
+    visible: bool,
+    cx: &mut AppContext,
+) -> Task<Result<(Model<Worktree>, PathBuf)>> {
+    if let Some(found) = project.read(cx).find_worktree(&abs_path, cx) {
+        return Task::ready(Ok(found));
+    }
+
+    let project_id = project.entity_id();
+    let containing_creation = cx
+        .default_global::<PendingWorktreeCreations>()
+        .by_project
+        .get(&project_id)
+        .and_then(|creations| {
+            creations
+                .iter()
+                .find(|(root, _)| abs_path.starts_with(root))
+                .map(|(_, creation)| creation.clone())
+        });
+    if let Some(containing_creation) = containing_creation {
+        return cx.spawn(|mut cx| async move {
+            containing_creation.await.ok();
+            cx.update(|cx| find_or_create_worktree(project, abs_path, visible, cx))?
+                .await
+        });
+    }
+
+    let creation = cx
+        .spawn({
+            let abs_path = abs_path.clone();
+            |mut cx| async move {
+                let result = match project.update(&mut cx, |project, cx| {
+                    project.find_or_create_worktree(&abs_path, visible, cx)
+                }) {
+                    Ok(create) => create.await,
+                    Err(error) => Err(error),
+                };
+                cx.update_global(|pending: &mut PendingWorktreeCreations, _| {
+                    if let Some(creations) = pending.by_project.get_mut(&project_id) {
+                        creations.remove(&abs_path);
+                        if creations.is_empty() {
+                            pending.by_project.remove(&project_id);
+                        }
+                    }
+                })
+                .ok();
+                result.map_err(Arc::new)
+            }
+        })
+        .shared();
+    cx.default_global::<PendingWorktreeCreations>()
+        .by_project
+        .entry(project_id)
+        .or_default()
+        .insert(abs_path, creation.clone());
+    cx.spawn(|_| async move { creation.await.map_err(|error| anyhow!("{error:#}")) })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::init_test, Workspace};
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use project::Project;
+    use serde_json::json;
+    use std::path::{Path, PathBuf};
+
+    #[gpui::test]
+    async fn test_concurrent_nested_paths_share_worktree(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let mut tree = serde_json::Value::Object(Default::default());
+        for depth in (0..49).rev() {
+            let mut directory = serde_json::Map::new();
+            directory.insert(format!("d{depth}"), tree);
+            tree = directory.into();
+        }
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", tree).await;
+        let project = Project::test(fs, [], cx).await;
+
+        // The root and the 49 directories nested in it, resolved all at once.
+        let mut abs_paths = vec![PathBuf::from("/root")];
+        for depth in 0..49 {
+            abs_paths.push(abs_paths[depth].join(format!("d{depth}")));
+        }
+        let resolved = cx.update(|cx| {
+            abs_paths
+                .iter()
+                .map(|abs_path| {
+                    Workspace::project_path_for_path(project.clone(), abs_path, true, cx)
+                })
+                .collect::<Vec<_>>()
+        });
+        let resolved = futures::future::try_join_all(resolved).await.unwrap();
+
+        project.read_with(cx, |project, cx| {
+            assert_eq!(project.worktrees(cx).count(), 1);
+        });
+        let (worktree, _) = &resolved[0];
+        for ((resolved_worktree, project_path), abs_path) in resolved.iter().zip(&abs_paths) {
+            assert_eq!(resolved_worktree, worktree);
+            assert_eq!(
+                Path::new("/root").join(&project_path.path),
+                abs_path.as_path()
+            );
+        }
+    }
+
+    #[gpui::test]
+    async fn test_concurrent_unrelated_paths(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/a", json!({ "inner": {} })).await;
+        fs.insert_tree("/b", json!({})).await;
+        let project = Project::test(fs, [], cx).await;
+
+        // Unrelated roots get a worktree each, and the paths inside of them share it.
+        let abs_paths = ["/a", "/b", "/a/inner"];
+        let resolved = cx.update(|cx| {
+            abs_paths
+                .iter()
+                .map(|abs_path| {
+                    Workspace::project_path_for_path(project.clone(), Path::new(abs_path), true, cx)
+                })
+                .collect::<Vec<_>>()
+        });
+        let resolved = futures::future::try_join_all(resolved).await.unwrap();
+
+        project.read_with(cx, |project, cx| {
+            assert_eq!(project.worktrees(cx).count(), 2);
+        });
+        assert_ne!(resolved[0].0, resolved[1].0);
+        assert_eq!(resolved[0].0, resolved[2].0);
+    }
+}
@@ -0,0 +1,150 @@
+use anyhow::Result;
+use gpui::ViewContext;
+use project::WorktreeId;
+use util::ResultExt;
+
+use crate::{MoveWorktreeDown, MoveWorktreeUp, Workspace};
+
+impl Workspace {
+    /// Moves a visible worktree to the given position among the visible worktrees, clamped to
+    /// the last one. The new order is shown in the window title and stored with the workspace.
+    pub fn move_worktree(
+        &mut self,
+        worktree_id: WorktreeId,
+        new_index: usize,
+        cx: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        let visible_worktree_ids = self.visible_worktree_ids(cx);
+        let Some(&destination) =
+            visible_worktree_ids.get(new_index.min(visible_worktree_ids.len().saturating_sub(1)))
+        else {
+            return Ok(());
+        };
+        self.project.update(cx, |project, cx| {
+            project.move_worktree(worktree_id, destination, cx)
+        })
+    }
+
+    pub(crate) fn move_worktree_up(&mut self, _: &MoveWorktreeUp, cx: &mut ViewContext<Self>) {
+        if let Some((worktree_id, index)) = self.active_worktree_index(cx) {
+            self.move_worktree(worktree_id, index.saturating_sub(1), cx)
+                .log_err();
+        }
+    }
+
+    pub(crate) fn move_worktree_down(&mut self, _: &MoveWorktreeDown, cx: &mut ViewContext<Self>) {
+        if let Some((worktree_id, index)) = self.active_worktree_index(cx) {
+            self.move_worktree(worktree_id, index + 1, cx).log_err();
+        }
+    }
+
+    fn visible_worktree_ids(&self, cx: &ViewContext<Self>) -> Vec<WorktreeId> {
+        self.project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).id())
+            .collect()
+    }
+
+    /// The worktree containing the active item, and its position among the visible worktrees.
+    fn active_worktree_index(&self, cx: &ViewContext<Self>) -> Option<(WorktreeId, usize)> {
+        let worktree_id = self.active_item(cx)?.project_path(cx)?.worktree_id;
+        let index = self
+            .visible_worktree_ids(cx)
+            .iter()
+            .position(|id| *id == worktree_id)?;
+        Some((worktree_id, index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::{TestItem, TestProjectItem},
+        persistence::{model::SerializedWorkspaceLocation, DB},
+        tests::init_test,
+        AppState,
+    };
+    use gpui::{TestAppContext, VisualContext, WindowHandle};
+    use serde_json::json;
+    use std::{path::PathBuf, sync::Arc};
+
+    #[gpui::test]
+    async fn test_move_worktree(cx: &mut TestAppContext) {
+        init_test(cx);
+        let app_state = cx.update(AppState::test);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                "/root",
+                json!({ "a": { "a.txt": "" }, "b": { "b.txt": "" } }),
+            )
+            .await;
+        let root_names = |window: WindowHandle<Workspace>, cx: &mut TestAppContext| {
+            window
+                .update(cx, |workspace, cx| {
+                    workspace
+                        .project()
+                        .read(cx)
+                        .worktree_root_names(cx)
+                        .map(str::to_string)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap()
+        };
+
+        let window = open_window(&app_state, cx).await;
+        assert_eq!(root_names(window, cx), ["a", "b"]);
+
+        // The worktree of the active item is moved, and moves past the ends are clamped.
+        window
+            .update(cx, |workspace, cx| {
+                let worktree_id = workspace.visible_worktree_ids(cx)[1];
+                let project_item = TestProjectItem::new(1, "b.txt", cx);
+                project_item.update(cx, |item, _| {
+                    item.project_path = Some((worktree_id, "b.txt").into())
+                });
+                let item = cx.new_view(|cx| TestItem::new(cx).with_project_items(&[project_item]));
+                workspace.add_item_to_active_pane(Box::new(item), None, true, cx);
+                workspace.move_worktree_up(&MoveWorktreeUp, cx);
+                workspace.move_worktree_up(&MoveWorktreeUp, cx);
+            })
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(root_names(window, cx), ["b", "a"]);
+        window
+            .update(cx, |workspace, cx| {
+                assert!(workspace.window_title(cx).starts_with("b, a"));
+                workspace.serialize_workspace_internal(cx)
+            })
+            .unwrap()
+            .await;
+        assert_eq!(
+            DB.workspace_for_roots(&["/root/a", "/root/b"])
+                .unwrap()
+                .location,
+            SerializedWorkspaceLocation::from_local_paths(["/root/b", "/root/a"])
+        );
+
+        // Reopening the workspace restores the new order.
+        window.update(cx, |_, cx| cx.remove_window()).unwrap();
+        cx.run_until_parked();
+        let window = open_window(&app_state, cx).await;
+        assert_eq!(root_names(window, cx), ["b", "a"]);
+    }
+
+    async fn open_window(
+        app_state: &Arc<AppState>,
+        cx: &mut TestAppContext,
+    ) -> WindowHandle<Workspace> {
+        let paths = vec![PathBuf::from("/root/a"), PathBuf::from("/root/b")];
+        let (window, _) = cx
+            .update(|cx| Workspace::new_local(paths, app_state.clone(), None, None, None, cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        window
+    }
+}
@@ -0,0 +1,197 @@
+use std::time::Duration;
+
+use futures::{channel::mpsc, FutureExt as _, Stream, StreamExt as _};
+use gpui::{AppContext, Model, Task, ViewContext};
+use project::{Worktree, WorktreeId};
+
+use crate::Workspace;
+
+/// How far the scans of the visible local worktrees of a workspace went.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScanProgress {
+    pub worktrees: Vec<WorktreeScanProgress>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorktreeScanProgress {
+    pub worktree_id: WorktreeId,
+    /// The entries scanned so far.
+    pub scanned_entries: usize,
+    pub complete: bool,
+}
+
+impl ScanProgress {
+    pub fn is_complete(&self) -> bool {
+        self.worktrees.iter().all(|worktree| worktree.complete)
+    }
+
+    pub fn scanned_entries(&self) -> usize {
+        self.worktrees
+            .iter()
+            .map(|worktree| worktree.scanned_entries)
+            .sum()
+    }
+}
+
+impl WorktreeScanProgress {
+    fn of(worktree: &Model<Worktree>, cx: &AppContext) -> Option<Self> {
+        let worktree = worktree.read(cx).as_local()?;
+        Some(Self {
+            worktree_id: worktree.id(),
+            scanned_entries: worktree.entry_count(),
+            complete: !worktree.is_scanning(),
+        })
+    }
+}
+
+impl Workspace {
+    /// The current progress of the scans of the visible local worktrees.
+    pub fn scan_progress(&self, cx: &AppContext) -> ScanProgress {
+        ScanProgress {
+            worktrees: self
+                .visible_worktrees(cx)
+                .filter_map(|worktree| WorktreeScanProgress::of(&worktree, cx))
+                .collect(),
+        }
+    }
+
+    /// Reports the progress of the scans of the visible local worktrees as they go, see
+    /// [`Workspace::worktree_scans_complete`]. The stream ends with the progress where all of
+    /// them are complete, or once the workspace is dropped. Worktrees added to the project
+    /// afterwards aren't reported, and those removed from it are no longer reported.
+    pub fn worktree_scan_progress(
+        &self,
+        cx: &mut ViewContext<Self>,
+    ) -> impl Stream<Item = ScanProgress> + 'static {
+        let (progress_tx, progress_rx) = mpsc::unbounded();
+        let (changed_tx, mut changed_rx) = mpsc::unbounded();
+        let worktrees = self
+            .visible_worktrees(cx)
+            .filter(|worktree| worktree.read(cx).is_local())
+            .collect::<Vec<_>>();
+        let mut subscriptions = worktrees
+            .iter()
+            .flat_map(|worktree| {
+                let (changed_tx, released_tx) = (changed_tx.clone(), changed_tx.clone());
+                [
+                    cx.observe(worktree, move |_, _, _| {
+                        changed_tx.unbounded_send(()).ok();
+                    }),
+                    cx.observe_release(worktree, move |_, _, _| {
+                        released_tx.unbounded_send(()).ok();
+                    }),
+                ]
+            })
+            .collect::<Vec<_>>();
+        subscriptions.push(cx.on_release(move |_, _, _| {
+            changed_tx.unbounded_send(()).ok();
+        }));
+
+        let worktrees = worktrees
+            .iter()
+            .map(|worktree| worktree.downgrade())
+            .collect::<Vec<_>>();
+        cx.spawn(|this, mut cx| async move {
+            let _subscriptions = subscriptions;
+            loop {
+                let progress = this.update(&mut cx, |_, cx| ScanProgress {
+                    worktrees: worktrees
+                        .iter()
+                        .filter_map(|worktree| WorktreeScanProgress::of(&worktree.upgrade()?, cx))
+                        .collect(),
+                });
+                let Ok(progress) = progress else {
+                    break;
+                };
+                let complete = progress.is_complete();
+                if progress_tx.unbounded_send(progress).is_err() || complete {
+                    break;
+                }
+                if changed_rx.next().await.is_none() {
+                    break;
+                }
+                // Coalesce the updates that arrived meanwhile.
+                while let Some(Some(())) = changed_rx.next().now_or_never() {}
+            }
+        })
+        .detach();
+
+        progress_rx
+    }
+
+    /// Waits for the scans of the local worktrees for at most `timeout`. Resolves to whether
+    /// they completed.
+    pub fn wait_for_scans_with_timeout(&self, timeout: Duration, cx: &AppContext) -> Task<bool> {
+        let scans = self.worktree_scans_complete(cx);
+        let timer = cx.background_executor().timer(timeout);
+        cx.background_executor().spawn(async move {
+            futures::select_biased! {
+                _ = scans.fuse() => true,
+                _ = timer.fuse() => false,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use project::Project;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_worktree_scan_progress(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let dirs = (0..20)
+            .map(|dir| {
+                let files = (0..50)
+                    .map(|file| (format!("{file}.txt"), json!("")))
+                    .collect::<serde_json::Map<_, _>>();
+                (format!("dir{dir}"), json!(files))
+            })
+            .collect::<serde_json::Map<_, _>>();
+        fs.insert_tree("/root", json!(dirs)).await;
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        project
+            .update(cx, |project, cx| {
+                project.find_or_create_worktree("/root", true, cx)
+            })
+            .await
+            .unwrap();
+
+        // The counts only grow, and the stream ends with the complete scan.
+        let progress = workspace
+            .update(cx, |workspace, cx| workspace.worktree_scan_progress(cx))
+            .collect::<Vec<_>>()
+            .await;
+        assert!(progress.len() > 1);
+        let last = progress.last().unwrap();
+        assert!(last.is_complete());
+        assert_eq!(last.worktrees.len(), 1);
+        // The root, the directories and their files.
+        assert_eq!(last.scanned_entries(), 1 + 20 + 20 * 50);
+        assert!(progress
+            .windows(2)
+            .all(|pair| pair[0].scanned_entries() <= pair[1].scanned_entries()));
+        assert!(progress[..progress.len() - 1]
+            .iter()
+            .all(|progress| !progress.is_complete()));
+
+        let complete = workspace
+            .update(cx, |workspace, cx| {
+                workspace.wait_for_scans_with_timeout(Duration::from_secs(1), cx)
+            })
+            .await;
+        assert!(complete);
+
+        // Once the scans are complete, the stream only reports that.
+        let progress = workspace.update(cx, |workspace, cx| workspace.worktree_scan_progress(cx));
+        assert_eq!(progress.collect::<Vec<_>>().await.len(), 1);
+    }
+}
@@ -0,0 +1,190 @@
+use gpui::{div, hsla, relative, Div, InteractiveElement, MouseButton, Styled, ViewContext};
+use settings::Settings;
+use ui::ActiveTheme;
+
+use crate::{dock::DockPosition, Workspace, WorkspaceSettings};
+
+impl Workspace {
+    const MIN_ZOOM_COVERAGE: f32 = 0.5;
+
+    /// The `zoom_coverage` setting, as the fraction of the workspace the zoomed overlay covers.
+    fn zoom_coverage(cx: &ViewContext<Self>) -> f32 {
+        WorkspaceSettings::get_global(cx)
+            .zoom_coverage
+            .clamp(Self::MIN_ZOOM_COVERAGE, 1.0)
+    }
+
+    /// Places the zoomed overlay above the workspace. A zoomed panel stays against the side of
+    /// its dock, and a zoomed pane is centered, each covering the `zoom_coverage` fraction of
+    /// the workspace along the axes they can grow.
+    pub(crate) fn position_zoomed_overlay(&self, overlay: Div, cx: &ViewContext<Self>) -> Div {
+        let overlay = overlay
+            .occlude()
+            .absolute()
+            .border_color(cx.theme().colors().border)
+            .inset_0()
+            .shadow_lg();
+        let coverage = Self::zoom_coverage(cx);
+        if coverage >= 1.0 {
+            return match self.zoomed_position {
+                Some(DockPosition::Left) => overlay.right_2().border_r_1(),
+                Some(DockPosition::Right) => overlay.left_2().border_l_1(),
+                Some(DockPosition::Bottom) => overlay.top_2().border_t_1(),
+                None => overlay.top_2().bottom_2().left_2().right_2().border_1(),
+            };
+        }
+
+        let uncovered = relative(1.0 - coverage);
+        let margin = relative((1.0 - coverage) / 2.0);
+        match self.zoomed_position {
+            Some(DockPosition::Left) => overlay.right(uncovered).border_r_1(),
+            Some(DockPosition::Right) => overlay.left(uncovered).border_l_1(),
+            Some(DockPosition::Bottom) => overlay.top(uncovered).border_t_1(),
+            None => overlay
+                .top(margin)
+                .bottom(margin)
+                .left(margin)
+                .right(margin)
+                .border_1(),
+        }
+    }
+
+    /// Dims the workspace around a zoomed overlay that doesn't cover all of it. Clicking the
+    /// dimmed part exits the zoom.
+    pub(crate) fn render_zoom_backdrop(&self, cx: &mut ViewContext<Self>) -> Option<Div> {
+        if self.zoomed.is_none() || Self::zoom_coverage(cx) >= 1.0 {
+            return None;
+        }
+        Some(
+            div()
+                .occlude()
+                .absolute()
+                .inset_0()
+                .bg(hsla(0., 0., 0., 0.2))
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|workspace, _, cx| workspace.exit_zoom(cx)),
+                ),
+        )
+    }
+
+    /// Zooms out of the zoomed pane or panel.
+    pub(crate) fn exit_zoom(&mut self, cx: &mut ViewContext<Self>) {
+        match self.zoomed_position {
+            Some(position) => {
+                self.dock_at_position(position).update(cx, |dock, cx| {
+                    if let Some(panel) = dock.active_panel() {
+                        let panel = panel.to_any();
+                        dock.set_panel_zoomed(&panel, false, cx);
+                    }
+                });
+            }
+            None => {
+                for pane in &self.panes {
+                    pane.update(cx, |pane, cx| pane.set_zoomed(false, cx));
+                }
+            }
+        }
+        self.zoomed = None;
+        self.zoomed_position = None;
+        self.emit_zoom_changed(cx);
+        self.serialize_workspace(cx);
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dock::{test::TestPanel, PanelEvent},
+        item::test::TestItem,
+        tests::init_test,
+        ToggleZoom,
+    };
+    use fs::FakeFs;
+    use gpui::{
+        point, px, size, Modifiers, Pixels, TestAppContext, VisualContext, VisualTestContext,
+    };
+    use project::Project;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_zoom_coverage(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        cx.simulate_resize(size(px(1000.), px(800.)));
+        let panel = workspace.update(cx, |workspace, cx| {
+            let panel = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            workspace.add_panel(panel.clone(), cx);
+            workspace.toggle_dock(DockPosition::Left, cx);
+            workspace.add_item_to_active_pane(Box::new(cx.new_view(TestItem::new)), None, true, cx);
+            panel
+        });
+        let set_coverage = |coverage: f32, cx: &mut VisualTestContext| {
+            cx.update(|cx| {
+                SettingsStore::update_global(cx, |store, cx| {
+                    store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                        settings.zoom_coverage = Some(coverage);
+                    });
+                });
+            });
+        };
+        let assert_near = |actual: Pixels, expected: Pixels| {
+            assert!(
+                (actual - expected).abs() <= px(2.),
+                "expected {expected:?}, got {actual:?}"
+            );
+        };
+
+        // Without a backdrop by default, the zoomed panel covers nearly all the workspace.
+        panel.update(cx, |_, cx| cx.emit(PanelEvent::ZoomIn));
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            let bounds = workspace.zoom_state(cx).unwrap().bounds.unwrap();
+            assert_eq!(bounds.origin, workspace.bounds.origin);
+            assert!(bounds.size.width > workspace.bounds.size.width * 0.95);
+            assert!(workspace.render_zoom_backdrop(cx).is_none());
+        });
+
+        // A zoomed panel keeps to the side of its dock.
+        set_coverage(0.6, cx);
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            let bounds = workspace.zoom_state(cx).unwrap().bounds.unwrap();
+            assert_eq!(bounds.origin, workspace.bounds.origin);
+            assert_near(bounds.size.width, workspace.bounds.size.width * 0.6);
+        });
+        panel.update(cx, |_, cx| cx.emit(PanelEvent::ZoomOut));
+        cx.run_until_parked();
+
+        // A zoomed pane is centered, and the coverage is clamped.
+        set_coverage(0.2, cx);
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+        pane.update(cx, |pane, cx| {
+            pane.focus(cx);
+            pane.toggle_zoom(&ToggleZoom, cx);
+        });
+        cx.run_until_parked();
+        let workspace_bounds = workspace.update(cx, |workspace, _| {
+            let bounds = workspace.zoom_state(cx).unwrap().bounds.unwrap();
+            assert_near(bounds.size.width, workspace.bounds.size.width * 0.5);
+            assert_near(bounds.center().x, workspace.bounds.center().x);
+            workspace.bounds
+        });
+
+        // Clicking around the zoomed pane exits the zoom.
+        cx.simulate_click(
+            workspace_bounds.origin + point(px(10.), px(10.)),
+            Modifiers::none(),
+        );
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.zoom_state(cx).is_none());
+            assert!(!pane.read(cx).is_zoomed());
+        });
+    }
+}
@@ -1299,6 +1299,10 @@ impl LocalWorktree {
         changes.into()
     }
 
+    pub fn is_scanning(&self) -> bool {
+        *self.is_scanning.1.borrow()
+    }
+
     pub fn scan_complete(&self) -> impl Future<Output = ()> {
         let mut is_scanning_rx = self.is_scanning.1.clone();
         async move {
@@ -138,16 +138,10 @@ pub fn initialize_workspace(
         let center_pane = workspace.active_pane().clone();
         initialize_pane(workspace, &center_pane, cx);
         cx.subscribe(&workspace_handle, {
-            move |workspace, _, event, cx| match event {
-                workspace::Event::PaneAdded(pane) => {
+            move |workspace, _, event, cx| {
+                if let workspace::Event::PaneAdded(pane) = event {
                     initialize_pane(workspace, pane, cx);
                 }
-                workspace::Event::OpenBundledFile {
-                    text,
-                    title,
-                    language,
-                } => open_bundled_file(workspace, text.clone(), title, language, cx),
-                _ => {}
             }
         })
         .detach();
@@ -334,6 +328,7 @@ pub fn initialize_workspace(
                             files: true,
                             directories: true,
                             multiple: true,
+                            ..Default::default()
                         },
                         DirectoryLister::Project(workspace.project().clone()),
                         cx,
@@ -448,13 +443,14 @@ pub fn initialize_workspace(
                 open_log_file(workspace, cx);
             })
             .register_action(|workspace, _: &zed_actions::OpenLicenses, cx| {
-                open_bundled_file(
-                    workspace,
-                    asset_str::<Assets>("licenses.md"),
-                    "Open Source License Attribution",
-                    "Markdown",
-                    cx,
-                );
+                workspace
+                    .open_bundled_file(
+                        asset_str::<Assets>("licenses.md"),
+                        "Open Source License Attribution",
+                        "Markdown",
+                        cx,
+                    )
+                    .detach_and_log_err(cx);
             })
             .register_action(
                 move |workspace: &mut Workspace,
@@ -499,26 +495,28 @@ pub fn initialize_workspace(
                 move |workspace: &mut Workspace,
                       _: &zed_actions::OpenDefaultKeymap,
                       cx: &mut ViewContext<Workspace>| {
-                    open_bundled_file(
-                        workspace,
-                        settings::default_keymap(),
-                        "Default Key Bindings",
-                        "JSON",
-                        cx,
-                    );
+                    workspace
+                        .open_bundled_file(
+                            settings::default_keymap(),
+                            "Default Key Bindings",
+                            "JSON",
+                            cx,
+                        )
+                        .detach_and_log_err(cx);
                 },
             )
             .register_action(
                 move |workspace: &mut Workspace,
                       _: &OpenDefaultSettings,
                       cx: &mut ViewContext<Workspace>| {
-                    open_bundled_file(
-                        workspace,
-                        settings::default_settings(),
-                        "Default Settings",
-                        "JSON",
-                        cx,
-                    );
+                    workspace
+                        .open_bundled_file(
+                            settings::default_settings(),
+                            "Default Settings",
+                            "JSON",
+                            cx,
+                        )
+                        .detach_and_log_err(cx);
                 },
             )
             .register_action(
@@ -564,16 +562,13 @@ pub fn initialize_workspace(
                     workspace.toggle_panel_focus::<TerminalPanel>(cx);
                 },
             )
-            .register_action({
-                let app_state = Arc::downgrade(&app_state);
-                move |_, _: &NewWindow, cx| {
-                    if let Some(app_state) = app_state.upgrade() {
-                        open_new(Default::default(), app_state, cx, |workspace, cx| {
-                            Editor::new_file(workspace, &Default::default(), cx)
-                        })
-                        .detach();
-                    }
-                }
+            .register_action(|workspace, _: &NewWindow, cx| {
+                workspace
+                    .open_new_window(
+                        |workspace, cx| Editor::new_file(workspace, &Default::default(), cx),
+                        cx,
+                    )
+                    .detach_and_log_err(cx);
             })
             .register_action({
                 let app_state = Arc::downgrade(&app_state);
@@ -926,8 +921,7 @@ pub fn open_new_ssh_project_from_project(
             app_state,
             workspace::OpenOptions {
                 open_new_workspace: Some(true),
-                replace_window: None,
-                env: None,
+                ..Default::default()
             },
             &mut cx,
         )
@@ -1079,45 +1073,6 @@ fn open_telemetry_log_file(workspace: &mut Workspace, cx: &mut ViewContext<Works
     }).detach();
 }
 
-fn open_bundled_file(
-    workspace: &Workspace,
-    text: Cow<'static, str>,
-    title: &'static str,
-    language: &'static str,
-    cx: &mut ViewContext<Workspace>,
-) {
-    let language = workspace.app_state().languages.language_for_name(language);
-    cx.spawn(|workspace, mut cx| async move {
-        let language = language.await.log_err();
-        workspace
-            .update(&mut cx, |workspace, cx| {
-                workspace.with_local_workspace(cx, |workspace, cx| {
-                    let project = workspace.project();
-                    let buffer = project.update(cx, move |project, cx| {
-                        project.create_local_buffer(text.as_ref(), language, cx)
-                    });
-                    let buffer = cx.new_model(|cx| {
-                        MultiBuffer::singleton(buffer, cx).with_title(title.into())
-                    });
-                    workspace.add_item_to_active_pane(
-                        Box::new(cx.new_view(|cx| {
-                            let mut editor =
-                                Editor::for_multibuffer(buffer, Some(project.clone()), true, cx);
-                            editor.set_read_only(true);
-                            editor.set_breadcrumb_header(title.into());
-                            editor
-                        })),
-                        None,
-                        true,
-                        cx,
-                    );
-                })
-            })?
-            .await
-    })
-    .detach_and_log_err(cx);
-}
-
 fn open_settings_file(
     abs_path: &'static Path,
     default_content: impl FnOnce() -> Rope + Send + 'static,
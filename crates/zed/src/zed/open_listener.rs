@@ -299,6 +299,23 @@ pub async fn handle_cli_connection(
                 let status = if open_workspace_result.is_err() { 1 } else { 0 };
                 responses.send(CliResponse::Exit { status }).log_err();
             }
+            CliRequest::LockForExternalUpdate { path, duration_ms } => {
+                let duration = Duration::from_millis(duration_ms);
+                let status = match cx.update(|cx| {
+                    workspace::lock_path_for_external_update(Path::new(&path), duration, cx)
+                }) {
+                    Ok(Ok(())) => 0,
+                    Ok(Err(error)) | Err(error) => {
+                        responses
+                            .send(CliResponse::Stderr {
+                                message: format!("{error}"),
+                            })
+                            .log_err();
+                        1
+                    }
+                };
+                responses.send(CliResponse::Exit { status }).log_err();
+            }
         }
     }
 }